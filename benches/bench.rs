@@ -2,7 +2,9 @@
 
 extern crate test;
 
-use bitbuffer::{BigEndian, BitRead, BitReadBuffer, BitReadStream, Endianness, LittleEndian};
+use bitbuffer::{
+    BigEndian, BitRead, BitReadBuffer, BitReadStream, BitWriteStream, Endianness, LittleEndian,
+};
 use test::Bencher;
 
 fn read_perf<E: Endianness>(buffer: &BitReadBuffer<E>) -> u16 {
@@ -42,6 +44,75 @@ fn perf_be(b: &mut Bencher) {
     });
 }
 
+fn read_u32_perf<E: Endianness>(buffer: &BitReadBuffer<E>, start: usize) -> u32 {
+    let mut pos = start;
+    let len = buffer.bit_len();
+    let mut result: u32 = 0;
+    while pos + 32 <= len {
+        result = result.wrapping_add(buffer.read_int::<u32>(pos, 32).unwrap());
+        pos += 32;
+    }
+    result
+}
+
+#[bench]
+fn perf_u32_aligned_le(b: &mut Bencher) {
+    let data = vec![1u8; 1024 * 1024 * 10];
+    let buffer = BitReadBuffer::new(&data, LittleEndian);
+    b.iter(|| test::black_box(read_u32_perf(&buffer, 0)));
+}
+
+#[bench]
+fn perf_u32_aligned_be(b: &mut Bencher) {
+    let data = vec![1u8; 1024 * 1024 * 10];
+    let buffer = BitReadBuffer::new(&data, BigEndian);
+    b.iter(|| test::black_box(read_u32_perf(&buffer, 0)));
+}
+
+#[bench]
+fn perf_u32_unaligned_le(b: &mut Bencher) {
+    let data = vec![1u8; 1024 * 1024 * 10];
+    let buffer = BitReadBuffer::new(&data, LittleEndian);
+    b.iter(|| test::black_box(read_u32_perf(&buffer, 3)));
+}
+
+#[bench]
+fn perf_u32_unaligned_be(b: &mut Bencher) {
+    let data = vec![1u8; 1024 * 1024 * 10];
+    let buffer = BitReadBuffer::new(&data, BigEndian);
+    b.iter(|| test::black_box(read_u32_perf(&buffer, 3)));
+}
+
+fn read_small_fields<E: Endianness>(stream: &mut BitReadStream<E>) -> u32 {
+    let mut result: u32 = 0;
+    while stream.bits_left() >= 13 {
+        let flag = stream.read_bool().unwrap();
+        let a: u8 = stream.read_int(3).unwrap();
+        let b: u8 = stream.read_int(5).unwrap();
+        let c: u8 = stream.read_int(4).unwrap();
+        result = result.wrapping_add(flag as u32 + a as u32 + b as u32 + c as u32);
+    }
+    result
+}
+
+#[bench]
+fn perf_stream_small_fields_le(b: &mut Bencher) {
+    let data = vec![1u8; 1024 * 1024];
+    b.iter(|| {
+        let mut stream = BitReadStream::new(BitReadBuffer::new(&data, LittleEndian));
+        test::black_box(read_small_fields(&mut stream))
+    });
+}
+
+#[bench]
+fn perf_stream_small_fields_be(b: &mut Bencher) {
+    let data = vec![1u8; 1024 * 1024];
+    b.iter(|| {
+        let mut stream = BitReadStream::new(BitReadBuffer::new(&data, BigEndian));
+        test::black_box(read_small_fields(&mut stream))
+    });
+}
+
 #[bench]
 fn perf_f32_be(b: &mut Bencher) {
     let data = vec![1u8; 1024 * 1024 * 10];
@@ -84,6 +155,31 @@ fn perf_f32_le(b: &mut Bencher) {
     });
 }
 
+#[bench]
+fn perf_f32_vec_le(b: &mut Bencher) {
+    let data = vec![1u8; 1024 * 1024 * 10];
+    let buffer = BitReadBuffer::new(&data, LittleEndian);
+    b.iter(|| {
+        let mut stream = BitReadStream::new(buffer.clone());
+        let count = stream.bits_left() / 32;
+        let values = stream.read_float_vec::<f32>(count).unwrap();
+        test::black_box(values);
+    });
+}
+
+#[bench]
+fn perf_u16_vec_unaligned(b: &mut Bencher) {
+    let data = vec![1u8; 1024 * 1024 * 10];
+    let buffer = BitReadBuffer::new(&data, LittleEndian);
+    b.iter(|| {
+        let mut stream = BitReadStream::new(buffer.clone());
+        stream.skip_bits(1).unwrap();
+        let count = stream.bits_left() / 16;
+        let values = stream.read_int_vec::<u16>(count, 16).unwrap();
+        test::black_box(values);
+    });
+}
+
 const F64_RESULT: f64 = 0.0000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000010156250477904244;
 
 #[bench]
@@ -109,7 +205,7 @@ fn perf_f64(b: &mut Bencher) {
 
 #[bench]
 fn perf_bool(b: &mut Bencher) {
-    let data = vec![1u8; 1024 * 1024 * 1];
+    let data = vec![1u8; 1024 * 1024];
     let buffer = BitReadBuffer::new(&data, BigEndian);
     b.iter(|| {
         let mut pos = 0;
@@ -187,6 +283,25 @@ fn perf_string_le(b: &mut Bencher) {
     });
 }
 
+#[bench]
+fn perf_string_le_unaligned(b: &mut Bencher) {
+    let data = get_string_buffer();
+    let buffer = BitReadBuffer::new(&data, LittleEndian);
+
+    b.iter(|| {
+        let mut pos = 3;
+        let len = buffer.bit_len();
+        loop {
+            if pos + (128 * 8) > len {
+                break;
+            }
+            let result = buffer.read_string(pos, None).unwrap();
+            pos += (result.len() + 1) * 8;
+            test::black_box(result);
+        }
+    });
+}
+
 #[bench]
 fn perf_bytes_be(b: &mut Bencher) {
     let data = get_string_buffer();
@@ -263,6 +378,30 @@ fn perf_bytes_le_unaligned(b: &mut Bencher) {
     });
 }
 
+fn write_bytes_perf<E: Endianness>(endianness: E, shift: usize) -> usize {
+    let bytes = vec![0x5au8; 1024 * 1024];
+    let mut data = Vec::with_capacity(bytes.len() + 1);
+    let mut stream = BitWriteStream::new(&mut data, endianness);
+    stream.write_int(0u8, shift).unwrap();
+    stream.write_bytes(&bytes).unwrap();
+    stream.bit_len()
+}
+
+#[bench]
+fn perf_write_bytes_le(b: &mut Bencher) {
+    b.iter(|| test::black_box(write_bytes_perf(LittleEndian, 0)));
+}
+
+#[bench]
+fn perf_write_bytes_le_unaligned(b: &mut Bencher) {
+    b.iter(|| test::black_box(write_bytes_perf(LittleEndian, 3)));
+}
+
+#[bench]
+fn perf_write_bytes_be_unaligned(b: &mut Bencher) {
+    b.iter(|| test::black_box(write_bytes_perf(BigEndian, 3)));
+}
+
 #[allow(dead_code)]
 #[derive(BitRead)]
 struct BasicStruct {
@@ -1,6 +1,34 @@
-use syn::{Expr, Lit, Variant};
+use syn::{Attribute, Expr, Lit, Meta, NestedMeta, Variant};
 use syn_util::get_attribute_value;
 
+/// The number of bits used for the discriminant of an enum
+///
+/// Taken from the `discriminant_bits` attribute, or from the size of the integer `repr` of the enum
+pub fn discriminant_bits(attrs: &[Attribute]) -> Option<u64> {
+    get_attribute_value(attrs, &["discriminant_bits"]).or_else(|| {
+        attrs
+            .iter()
+            .filter(|attr| attr.path.is_ident("repr"))
+            .filter_map(|attr| match attr.parse_meta() {
+                Ok(Meta::List(list)) => Some(list.nested),
+                _ => None,
+            })
+            .flatten()
+            .find_map(|nested| match nested {
+                NestedMeta::Meta(Meta::Path(path)) => {
+                    match path.get_ident()?.to_string().as_str() {
+                        "u8" | "i8" => Some(8),
+                        "u16" | "i16" => Some(16),
+                        "u32" | "i32" => Some(32),
+                        "u64" | "i64" => Some(64),
+                        _ => None,
+                    }
+                }
+                _ => None,
+            })
+    })
+}
+
 pub enum Discriminant {
     Int(usize),
     Default,
@@ -0,0 +1,187 @@
+use crate::discriminant::{discriminant_bits, Discriminant};
+use proc_macro2::{Span, TokenStream};
+use quote::{quote, quote_spanned};
+use syn::spanned::Spanned;
+use syn::{
+    parse_macro_input, parse_str, Attribute, Data, DataStruct, DeriveInput, Fields, Ident, Lit,
+    LitStr, Type,
+};
+use syn_util::get_attribute_value;
+
+pub fn derive_layout_trait(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input: DeriveInput = parse_macro_input!(input as DeriveInput);
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    // bit sizes are determined using the endianness of the struct if set, they don't differ between endianness
+    let endianness: Option<String> = get_attribute_value(&input.attrs, &["endianness"]);
+    let endianness =
+        parse_str::<Type>(endianness.as_deref().unwrap_or("::bitbuffer::LittleEndian"))
+            .expect("endianness");
+
+    let layout = layout(&input.data, name, &input.attrs, &endianness);
+
+    let expanded = quote! {
+        impl #impl_generics ::bitbuffer::LayoutDescribe for #name #ty_generics #where_clause {
+            fn layout() -> ::bitbuffer::Layout {
+                #layout
+            }
+        }
+    };
+
+    proc_macro::TokenStream::from(expanded)
+}
+
+fn layout(data: &Data, struct_name: &Ident, attrs: &[Attribute], endianness: &Type) -> TokenStream {
+    let span = struct_name.span();
+    let name = LitStr::new(&struct_name.to_string(), span);
+
+    match data {
+        Data::Struct(DataStruct { fields, .. }) => {
+            let fields = fields.iter().enumerate().map(|(i, f)| {
+                let field_name = f
+                    .ident
+                    .as_ref()
+                    .map(|ident| ident.to_string())
+                    .unwrap_or_else(|| i.to_string());
+                field_layout(&field_name, &f.ty, &f.attrs, endianness, f.span())
+            });
+
+            quote_spanned! {span=>
+                ::bitbuffer::Layout::Struct {
+                    name: #name,
+                    fields: vec![#(#fields),*],
+                }
+            }
+        }
+        Data::Enum(data) => {
+            let discriminant_bits: u64 = match discriminant_bits(attrs) {
+                Some(attr) => attr,
+                None => {
+                    return quote_spanned! {span=>
+                        compile_error!("'discriminant_bits' attribute or an integer `repr` is required when deriving `LayoutDescribe` for enums");
+                    }
+                }
+            };
+            let discriminant_bits = discriminant_bits as usize;
+
+            let mut last_discriminant = -1;
+            let variants = data.variants.iter().map(|variant| {
+                let span = variant.span();
+                let variant_name = LitStr::new(&variant.ident.to_string(), span);
+
+                let discriminant = match Discriminant::from(variant) {
+                    Discriminant::Int(discriminant) => {
+                        last_discriminant = discriminant as isize;
+                        quote_spanned! { span => Some(#discriminant) }
+                    }
+                    Discriminant::Wildcard => quote_spanned! { span => None },
+                    Discriminant::Default => {
+                        let new_discriminant = (last_discriminant + 1) as usize;
+                        last_discriminant += 1;
+                        quote_spanned! { span => Some(#new_discriminant) }
+                    }
+                };
+
+                let field = match &variant.fields {
+                    Fields::Unit => quote_spanned! { span => None },
+                    Fields::Unnamed(f) => {
+                        let ty = &f.unnamed.first().expect("variant field").ty;
+                        let field = field_layout("0", ty, &variant.attrs, endianness, f.span());
+                        quote_spanned! { span => Some(#field) }
+                    }
+                    Fields::Named(_) => {
+                        return syn::Error::new_spanned(
+                            &variant.fields,
+                            "named fields in enum variants are not supported when deriving `LayoutDescribe`",
+                        )
+                        .to_compile_error();
+                    }
+                };
+
+                quote_spanned! {span=>
+                    ::bitbuffer::VariantLayout {
+                        name: #variant_name,
+                        discriminant: #discriminant,
+                        field: #field,
+                    }
+                }
+            });
+
+            quote_spanned! {span=>
+                ::bitbuffer::Layout::Enum {
+                    name: #name,
+                    discriminant_bits: #discriminant_bits,
+                    variants: vec![#(#variants),*],
+                }
+            }
+        }
+        Data::Union(data) => syn::Error::new_spanned(
+            data.union_token,
+            "unions are not supported when deriving `LayoutDescribe`",
+        )
+        .to_compile_error(),
+    }
+}
+
+fn field_layout(
+    name: &str,
+    ty: &Type,
+    attrs: &[Attribute],
+    endianness: &Type,
+    span: Span,
+) -> TokenStream {
+    let name = LitStr::new(name, span);
+    let type_name = LitStr::new(&type_name(ty), span);
+
+    let (size, bit_size) = match get_attribute_value(attrs, &["size"]) {
+        Some(Lit::Int(size)) => (
+            quote_spanned! { span => ::bitbuffer::SizeSource::Fixed(#size) },
+            quote_spanned! { span =>
+                <#ty as ::bitbuffer::BitReadSized<'_, #endianness>>::bit_size_sized(#size)
+            },
+        ),
+        Some(Lit::Str(size_field)) if size_field.value() == "input_size" => (
+            quote_spanned! { span => ::bitbuffer::SizeSource::InputSize },
+            quote_spanned! { span => None },
+        ),
+        Some(Lit::Str(size_field)) => (
+            quote_spanned! { span => ::bitbuffer::SizeSource::Expression(#size_field) },
+            quote_spanned! { span => None },
+        ),
+        Some(_) => panic!("Unsupported value for size attribute"),
+        None => match get_attribute_value::<Lit>(attrs, &["size_bits"]) {
+            Some(size_bits) => (
+                quote_spanned! { span => ::bitbuffer::SizeSource::Bits(#size_bits) },
+                quote_spanned! { span => None },
+            ),
+            None => (
+                quote_spanned! { span => ::bitbuffer::SizeSource::Unsized },
+                quote_spanned! { span =>
+                    <#ty as ::bitbuffer::BitRead<'_, #endianness>>::bit_size()
+                },
+            ),
+        },
+    };
+
+    quote_spanned! {span=>
+        ::bitbuffer::FieldLayout {
+            name: #name,
+            type_name: #type_name,
+            size: #size,
+            bit_size: #bit_size,
+        }
+    }
+}
+
+/// Render the type as it would be written in the source
+fn type_name(ty: &Type) -> String {
+    quote!(#ty)
+        .to_string()
+        .replace(" <", "<")
+        .replace("< ", "<")
+        .replace(" >", ">")
+        .replace(" ,", ",")
+        .replace("& ", "&")
+}
@@ -74,6 +74,32 @@
 //! }
 //! ```
 //!
+//! If the enum has an integer `repr` and no `discriminant_bits` attribute, the size of the `repr` is used as the number of discriminant bits.
+//!
+//! ```
+//! # use bitbuffer::BitRead;
+//! #
+//! #[derive(BitRead)]
+//! #[repr(u8)]
+//! enum TestReprEnum {
+//!     Foo = 1,
+//!     Bar = 2,
+//!     Asd = 0xFF,
+//! }
+//! ```
+//!
+//! Without either of them the number of discriminant bits is unknown and deriving fails with a compile error.
+//!
+//! ```compile_fail
+//! # use bitbuffer::BitRead;
+//! #
+//! #[derive(BitRead)]
+//! enum TestMissingDiscriminantBits {
+//!     Foo,
+//!     Bar,
+//! }
+//! ```
+//!
 //! ```
 //! # use bitbuffer::BitRead;
 //! #
@@ -132,26 +158,108 @@
 //! }
 //! ```
 //!
+//! # C bitfield layout
+//!
+//! To exchange structs with C code, the `bitfield_layout` attribute can be set to `"gcc"` or `"msvc"` to
+//! pack the fields the same way the respective compiler does.
+//! Fields with a `size` attribute are placed as bitfields of their declared type, other fields are aligned
+//! to their natural alignment, and the struct is padded to a multiple of its alignment.
+//!
+//! Use `LittleEndian` for data from little endian targets and `BigEndian` for data from big endian targets.
+//!
+//! ```
+//! # use bitbuffer::{BitRead, BitWrite};
+//! #
+//! // struct { uint8_t a: 3; uint16_t b: 7; uint32_t c: 30; uint8_t d; }
+//! #[derive(BitRead, BitWrite)]
+//! #[bitfield_layout = "gcc"]
+//! struct CStruct {
+//!     #[size = 3]
+//!     a: u8,
+//!     #[size = 7]
+//!     b: u16,
+//!     #[size = 30]
+//!     c: u32,
+//!     d: u8,
+//! }
+//! ```
+//!
+//! # Layout description
+//!
+//! Deriving `LayoutDescribe` next to `BitRead` or `BitReadSized` generates a description of the
+//! fields, their sizes and the source of those sizes, using the same attributes.
+//!
+//! ```
+//! # use bitbuffer::{BitRead, LayoutDescribe};
+//! #
+//! #[derive(BitRead, LayoutDescribe)]
+//! #[discriminant_bits = 2]
+//! enum TestUnnamedFieldEnum {
+//!     #[size = 5]
+//!     Foo(i8),
+//!     Bar(bool),
+//! }
+//!
+//! assert_eq!(TestUnnamedFieldEnum::layout().name(), "TestUnnamedFieldEnum");
+//! ```
+//!
 mod discriminant;
+mod layout;
 mod write;
 
+/// Enums without `discriminant_bits` or an integer `repr` are rejected by every derive
+///
+/// ```compile_fail
+/// #[derive(bitbuffer::BitReadSized)]
+/// enum MissingDiscriminantBits {
+///     Foo,
+///     Bar,
+/// }
+/// ```
+///
+/// ```compile_fail
+/// #[derive(bitbuffer::BitWrite)]
+/// enum MissingDiscriminantBits {
+///     Foo,
+///     Bar,
+/// }
+/// ```
+///
+/// ```compile_fail
+/// #[derive(bitbuffer::LayoutDescribe)]
+/// enum MissingDiscriminantBits {
+///     Foo,
+///     Bar,
+/// }
+/// ```
+#[cfg(doctest)]
+struct CompileFailTests;
+
 extern crate proc_macro;
 
+use crate::layout::derive_layout_trait;
 use crate::write::derive_bitwrite_trait;
-use discriminant::Discriminant;
+use discriminant::{discriminant_bits, Discriminant};
 use proc_macro2::{Span, TokenStream};
 use quote::{quote, quote_spanned};
 use syn::spanned::Spanned;
 use syn::{
     parse_macro_input, parse_quote, parse_str, Attribute, Data, DataStruct, DeriveInput, Expr,
-    Fields, GenericParam, Ident, Lit, LitStr, Path,
+    Fields, GenericParam, Ident, Lit, LitStr, Path, Type,
 };
 use syn_util::get_attribute_value;
 
 /// See the [crate documentation](index.html) for details
 #[proc_macro_derive(
     BitRead,
-    attributes(size, size_bits, discriminant_bits, discriminant, endianness)
+    attributes(
+        size,
+        size_bits,
+        discriminant_bits,
+        discriminant,
+        endianness,
+        bitfield_layout
+    )
 )]
 pub fn derive_bitread(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     derive_bitread_trait(input, "BitRead".to_owned(), None)
@@ -161,7 +269,14 @@ pub fn derive_bitread(input: proc_macro::TokenStream) -> proc_macro::TokenStream
 /// See the [crate documentation](index.html) for details
 #[proc_macro_derive(
     BitReadSized,
-    attributes(size, size_bits, discriminant_bits, discriminant, endianness)
+    attributes(
+        size,
+        size_bits,
+        discriminant_bits,
+        discriminant,
+        endianness,
+        bitfield_layout
+    )
 )]
 pub fn derive_bitread_sized(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     let extra_param = parse_str::<TokenStream>(", input_size: usize").unwrap();
@@ -171,7 +286,14 @@ pub fn derive_bitread_sized(input: proc_macro::TokenStream) -> proc_macro::Token
 /// See the [crate documentation](index.html) for details
 #[proc_macro_derive(
     BitWrite,
-    attributes(size, size_bits, discriminant_bits, discriminant, endianness)
+    attributes(
+        size,
+        size_bits,
+        discriminant_bits,
+        discriminant,
+        endianness,
+        bitfield_layout
+    )
 )]
 pub fn derive_bitwrite(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     derive_bitwrite_trait(input, "BitWrite".into(), "write".into(), None)
@@ -181,7 +303,14 @@ pub fn derive_bitwrite(input: proc_macro::TokenStream) -> proc_macro::TokenStrea
 /// See the [crate documentation](index.html) for details
 #[proc_macro_derive(
     BitWriteSized,
-    attributes(size, size_bits, discriminant_bits, discriminant, endianness)
+    attributes(
+        size,
+        size_bits,
+        discriminant_bits,
+        discriminant,
+        endianness,
+        bitfield_layout
+    )
 )]
 pub fn derive_bitwrite_sized(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     let extra_param = parse_str::<TokenStream>(", input_size: usize").unwrap();
@@ -193,6 +322,22 @@ pub fn derive_bitwrite_sized(input: proc_macro::TokenStream) -> proc_macro::Toke
     )
 }
 
+/// See the [crate documentation](index.html) for details
+#[proc_macro_derive(
+    LayoutDescribe,
+    attributes(
+        size,
+        size_bits,
+        discriminant_bits,
+        discriminant,
+        endianness,
+        bitfield_layout
+    )
+)]
+pub fn derive_layout_describe(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    derive_layout_trait(input)
+}
+
 fn derive_bitread_trait(
     input: proc_macro::TokenStream,
     trait_name: String,
@@ -227,17 +372,17 @@ fn derive_bitread_trait(
 
     let size = size(
         input.data.clone(),
-        &name,
+        name,
         &input.attrs,
         extra_param.is_some(),
     );
-    let parsed = parse(input.data.clone(), &name, &input.attrs, false);
-    let parsed_unchecked = parse(input.data.clone(), &name, &input.attrs, true);
+    let parsed = parse(input.data.clone(), name, &input.attrs, false);
+    let parsed_unchecked = parse(input.data.clone(), name, &input.attrs, true);
 
     let endianness_placeholder = endianness.unwrap_or_else(|| "_E".to_owned());
     let trait_def_str = format!(
         "::bitbuffer::{}<{}, {}>",
-        trait_name, lifetime, &endianness_placeholder
+        trait_name, lifetime, endianness_placeholder
     );
     let trait_def = parse_str::<Path>(&trait_def_str).unwrap();
 
@@ -267,8 +412,9 @@ fn derive_bitread_trait(
     let expanded = quote! {
         impl #impl_generics #trait_def for #name #ty_generics #where_clause {
             fn read(stream: &mut ::bitbuffer::BitReadStream<#lifetime, #endianness_ident>#extra_param) -> ::bitbuffer::Result<Self> {
+                let start = stream.pos();
                 // if the read has a predicable size, we can do the bounds check in one go
-                match <Self as #trait_def>::#size_method_name(#extra_param_call) {
+                let result = match <Self as #trait_def>::#size_method_name(#extra_param_call) {
                     Some(size) => {
                         let end = stream.check_read(size)?;
                         unsafe {
@@ -276,9 +422,18 @@ fn derive_bitread_trait(
                         }
                     },
                     None => {
-                        #parsed
+                        // types without a predictable size can be recursive, so track the nesting depth
+                        stream.enter_nested()?;
+                        let result = (|| -> ::bitbuffer::Result<Self> { #parsed })();
+                        stream.leave_nested();
+                        result
                     }
+                };
+                if result.is_err() {
+                    // leave the stream untouched on failure, the position was valid before the read
+                    stream.set_pos(start)?;
                 }
+                result
             }
 
             unsafe fn read_unchecked(stream: &mut ::bitbuffer::BitReadStream<#lifetime, #endianness_ident>#extra_param, end: bool) -> ::bitbuffer::Result<Self> {
@@ -301,11 +456,15 @@ fn parse(data: Data, struct_name: &Ident, attrs: &[Attribute], unchecked: bool)
 
     match data {
         Data::Struct(DataStruct { fields, .. }) => {
+            let abi = get_bitfield_abi(attrs, span);
             let values = fields.iter().map(|f| {
                 // Get attributes `#[..]` on each field
                 let size = get_field_size(&f.attrs, f.span());
                 let field_type = &f.ty;
                 let span = f.span();
+                if abi.is_some() {
+                    return read_bitfield_layout_field(field_type, size, unchecked, span);
+                }
                 if unchecked {
                     match size {
                         Some(size) => {
@@ -332,6 +491,17 @@ fn parse(data: Data, struct_name: &Ident, attrs: &[Attribute], unchecked: bool)
                                 }
                             }
                         }
+                        // the most common field types get read through their dedicated methods
+                        None if type_is(field_type, "bool") => {
+                            quote_spanned! { span =>
+                                stream.read_bit()?
+                            }
+                        }
+                        None if type_is(field_type, "u8") => {
+                            quote_spanned! { span =>
+                                stream.read_byte()?
+                            }
+                        }
                         None => {
                             quote_spanned! { span =>
                                 stream.read::<#field_type>()?
@@ -341,11 +511,24 @@ fn parse(data: Data, struct_name: &Ident, attrs: &[Attribute], unchecked: bool)
                 }
             });
 
+            let layout_start = abi.as_ref().map(|abi| {
+                quote_spanned! { span =>
+                    let mut __layout = ::bitbuffer::BitfieldLayout::new(#abi);
+                }
+            });
+            let layout_end = abi.as_ref().map(|_| {
+                quote_spanned! { span =>
+                    stream.skip_bits(__layout.finish())?;
+                }
+            });
+
             match &fields {
                 Fields::Named(fields) => {
                     let definitions = fields.named.iter().zip(values).map(|(f, value)| {
                         let name = &f.ident;
+                        // fields can be `()`
                         quote_spanned! { f.span() =>
+                            #[allow(clippy::let_unit_value)]
                             let #name = #value;
                         }
                     });
@@ -356,7 +539,9 @@ fn parse(data: Data, struct_name: &Ident, attrs: &[Attribute], unchecked: bool)
                         }
                     });
                     quote_spanned! { span =>
+                        #layout_start
                         #(#definitions)*
+                        #layout_end
 
                         Ok(#struct_name {
                             #(#struct_definition)*
@@ -364,21 +549,26 @@ fn parse(data: Data, struct_name: &Ident, attrs: &[Attribute], unchecked: bool)
                     }
                 }
                 Fields::Unnamed(_) => quote_spanned! { span =>
-                    Ok(#struct_name(
+                    #layout_start
+                    let __result = #struct_name(
                         #(#values ,)*
-                    ))
+                    );
+                    #layout_end
+                    Ok(__result)
                 },
                 Fields::Unit => quote_spanned! {span=>
+                    #layout_start
+                    #layout_end
                     Ok(#struct_name)
                 },
             }
         }
         Data::Enum(data) => {
-            let discriminant_bits: u64 = match get_attribute_value(attrs, &["discriminant_bits"]) {
+            let discriminant_bits: u64 = match discriminant_bits(attrs) {
                 Some(attr) => attr,
                 None => {
-                    return quote! {span=>
-                        compile_error!("'discriminant_bits' attribute is required when deriving `BinRead` for enums");
+                    return quote_spanned! {span=>
+                        compile_error!("'discriminant_bits' attribute or an integer `repr` is required when deriving `BinRead` for enums");
                     }
                 }
             };
@@ -451,6 +641,9 @@ fn size(data: Data, struct_name: &Ident, attrs: &[Attribute], has_input_size: bo
 
     match data {
         Data::Struct(DataStruct { fields, .. }) => {
+            if let Some(abi) = get_bitfield_abi(attrs, span) {
+                return bitfield_layout_size(&fields, abi, has_input_size, span);
+            }
             let sizes = fields.iter().map(|f| {
                 // Get attributes `#[..]` on each field
                 if is_const_size(&f.attrs, has_input_size) {
@@ -489,12 +682,11 @@ fn size(data: Data, struct_name: &Ident, attrs: &[Attribute], has_input_size: bo
             }
         }
         Data::Enum(data) => {
-            let discriminant_bits = match get_attribute_value::<u64>(attrs, &["discriminant_bits"])
-            {
+            let discriminant_bits = match discriminant_bits(attrs) {
                 Some(attr) => attr as usize,
                 None => {
-                    return quote! {span=>
-                        compile_error!("'discriminant_bits' attribute is required when deriving `BinRead` for enums");
+                    return quote_spanned! {span=>
+                        compile_error!("'discriminant_bits' attribute or an integer `repr` is required when deriving `BinRead` for enums");
                     }
                 }
             };
@@ -518,6 +710,101 @@ fn size(data: Data, struct_name: &Ident, attrs: &[Attribute], has_input_size: bo
     }
 }
 
+/// Read a field, including the padding before it, for structs with a `bitfield_layout`
+fn read_bitfield_layout_field(
+    field_type: &Type,
+    size: Option<TokenStream>,
+    unchecked: bool,
+    span: Span,
+) -> TokenStream {
+    let (padding, read) = match size {
+        Some(size) => (
+            quote_spanned! { span =>
+                let _size: usize = #size;
+                let _padding = __layout.bitfield(::std::mem::size_of::<#field_type>(), _size);
+            },
+            if unchecked {
+                quote_spanned! { span => stream.read_sized_unchecked::<#field_type>(_size, end)? }
+            } else {
+                quote_spanned! { span => stream.read_sized::<#field_type>(_size)? }
+            },
+        ),
+        None => (
+            quote_spanned! { span =>
+                let _padding = __layout.field(::std::mem::align_of::<#field_type>());
+            },
+            if unchecked {
+                quote_spanned! { span => stream.read_unchecked::<#field_type>(end)? }
+            } else {
+                quote_spanned! { span => stream.read::<#field_type>()? }
+            },
+        ),
+    };
+    quote_spanned! { span =>
+        {
+            #padding
+            stream.skip_bits(_padding)?;
+            let _start = stream.pos();
+            let _value = #read;
+            __layout.advance(stream.pos() - _start);
+            _value
+        }
+    }
+}
+
+/// The size of a struct with a `bitfield_layout`, including all padding
+fn bitfield_layout_size(
+    fields: &Fields,
+    abi: TokenStream,
+    has_input_size: bool,
+    span: Span,
+) -> TokenStream {
+    let sizes = fields.iter().map(|f| {
+        let field_type = &f.ty;
+        let span = f.span();
+        if !is_const_size(&f.attrs, has_input_size) {
+            return quote_spanned! { span =>
+                __layout.advance(None?);
+            };
+        }
+        match get_field_size(&f.attrs, span) {
+            Some(size) => quote_spanned! { span =>
+                __layout.bitfield(::std::mem::size_of::<#field_type>(), #size);
+                __layout.advance(<#field_type as ::bitbuffer::BitReadSized<'_, ::bitbuffer::LittleEndian>>::bit_size_sized(#size)?);
+            },
+            None => quote_spanned! { span =>
+                __layout.field(::std::mem::align_of::<#field_type>());
+                __layout.advance(<#field_type as ::bitbuffer::BitRead<'_, ::bitbuffer::LittleEndian>>::bit_size()?);
+            },
+        }
+    });
+    quote_spanned! { span =>
+        let mut __layout = ::bitbuffer::BitfieldLayout::new(#abi);
+        #(#sizes)*
+        __layout.finish();
+        Some(__layout.offset())
+    }
+}
+
+/// The `BitfieldAbi` set with the `bitfield_layout` attribute, if any
+fn get_bitfield_abi(attrs: &[Attribute], span: Span) -> Option<TokenStream> {
+    get_attribute_value::<String>(attrs, &["bitfield_layout"]).map(|abi| match abi.as_str() {
+        "gcc" => quote_spanned! { span => ::bitbuffer::BitfieldAbi::Gcc },
+        "msvc" => quote_spanned! { span => ::bitbuffer::BitfieldAbi::Msvc },
+        _ => quote_spanned! { span =>
+            compile_error!("Unsupported value for bitfield_layout attribute, expected \"gcc\" or \"msvc\"")
+        },
+    })
+}
+
+/// Whether the type is written as the plain type `name`
+fn type_is(ty: &Type, name: &str) -> bool {
+    match ty {
+        Type::Path(path) if path.qself.is_none() => path.path.is_ident(name),
+        _ => false,
+    }
+}
+
 fn is_const_size(attrs: &[Attribute], has_input_size: bool) -> bool {
     if get_attribute_value::<Lit>(attrs, &["size_bits"]).is_some() {
         return false;
@@ -1,5 +1,5 @@
-use crate::discriminant::Discriminant;
-use crate::size;
+use crate::discriminant::{discriminant_bits, Discriminant};
+use crate::{get_bitfield_abi, size, type_is};
 use proc_macro2::{Span, TokenStream};
 use quote::{quote, quote_spanned};
 use syn::spanned::Spanned;
@@ -44,15 +44,15 @@ pub fn derive_bitwrite_trait(
 
     let _size = size(
         input.data.clone(),
-        &name,
+        name,
         &input.attrs,
         extra_param.is_some(),
     );
-    let parsed = write(input.data.clone(), &name, &input.attrs);
-    let _parsed_unchecked = write(input.data.clone(), &name, &input.attrs);
+    let parsed = write(input.data.clone(), name, &input.attrs);
+    let _parsed_unchecked = write(input.data.clone(), name, &input.attrs);
 
     let endianness_placeholder = endianness.unwrap_or_else(|| "_E".to_owned());
-    let trait_def_str = format!("::bitbuffer::{}<{}>", trait_name, &endianness_placeholder);
+    let trait_def_str = format!("::bitbuffer::{}<{}>", trait_name, endianness_placeholder);
     let trait_def = parse_str::<Path>(&trait_def_str).expect("trait");
 
     let endianness_ident = Ident::new(&endianness_placeholder, span);
@@ -111,6 +111,7 @@ fn write(data: Data, struct_name: &Ident, attrs: &[Attribute]) -> TokenStream {
                 }
             });
 
+            let abi = get_bitfield_abi(attrs, span);
             let writes = fields.iter().enumerate().map(|(i, f)| {
                 // Get attributes `#[..]` on each field
                 let size = get_field_size(&f.attrs, f.span());
@@ -121,6 +122,9 @@ fn write(data: Data, struct_name: &Ident, attrs: &[Attribute]) -> TokenStream {
                         span,
                     })
                 });
+                if abi.is_some() {
+                    return write_bitfield_layout_field(&f.ty, &member, size, span);
+                }
                 match size {
                     Some(size) => {
                         quote_spanned! { span =>
@@ -130,6 +134,11 @@ fn write(data: Data, struct_name: &Ident, attrs: &[Attribute]) -> TokenStream {
                             }
                         }
                     }
+                    None if type_is(&f.ty, "bool") => {
+                        quote_spanned! { span => {
+                            __target__stream.write_bit(self.#member)?;
+                        }}
+                    }
                     None => {
                         quote_spanned! { span => {
                             __target__stream.write(&self.#member)?;
@@ -138,18 +147,31 @@ fn write(data: Data, struct_name: &Ident, attrs: &[Attribute]) -> TokenStream {
                 }
             });
 
+            let layout_start = abi.as_ref().map(|abi| {
+                quote_spanned! { span =>
+                    let mut __layout = ::bitbuffer::BitfieldLayout::new(#abi);
+                }
+            });
+            let layout_end = abi.as_ref().map(|_| {
+                quote_spanned! { span =>
+                    ::bitbuffer::BitfieldLayout::write_padding(__target__stream, __layout.finish())?;
+                }
+            });
+
             quote_spanned! {span=>
                 #(#expand)*
+                #layout_start
                 #(#writes)*
+                #layout_end
                 Ok(())
             }
         }
         Data::Enum(data) => {
-            let discriminant_bits: u64 = match get_attribute_value(attrs, &["discriminant_bits"]) {
+            let discriminant_bits: u64 = match discriminant_bits(attrs) {
                 Some(attr) => attr,
                 None => {
-                    return quote! {span=>
-                        compile_error!("'discriminant_bits' attribute is required when deriving `BinWrite` for enums");
+                    return quote_spanned! {span=>
+                        compile_error!("'discriminant_bits' attribute or an integer `repr` is required when deriving `BinWrite` for enums");
                     }
                 }
             };
@@ -245,7 +267,7 @@ fn write(data: Data, struct_name: &Ident, attrs: &[Attribute]) -> TokenStream {
                 let discriminant = match &self {
                     #(#discriminant_value),*
                 };
-                __target__stream.write_int(discriminant as usize, #discriminant_bits as usize)?;
+                __target__stream.write_int(discriminant, #discriminant_bits as usize)?;
                 match &self {
                     #(#write_inner)*
                 }
@@ -256,6 +278,39 @@ fn write(data: Data, struct_name: &Ident, attrs: &[Attribute]) -> TokenStream {
     }
 }
 
+/// Write a field, including the padding before it, for structs with a `bitfield_layout`
+fn write_bitfield_layout_field(
+    field_type: &Type,
+    member: &Member,
+    size: Option<TokenStream>,
+    span: Span,
+) -> TokenStream {
+    let (padding, write) = match size {
+        Some(size) => (
+            quote_spanned! { span =>
+                let _size: usize = #size;
+                let _padding = __layout.bitfield(::std::mem::size_of::<#field_type>(), _size);
+            },
+            quote_spanned! { span => __target__stream.write_sized(&self.#member, _size)?; },
+        ),
+        None => (
+            quote_spanned! { span =>
+                let _padding = __layout.field(::std::mem::align_of::<#field_type>());
+            },
+            quote_spanned! { span => __target__stream.write(&self.#member)?; },
+        ),
+    };
+    quote_spanned! { span =>
+        {
+            #padding
+            ::bitbuffer::BitfieldLayout::write_padding(__target__stream, _padding)?;
+            let _start = __target__stream.bit_len();
+            #write
+            __layout.advance(__target__stream.bit_len() - _start);
+        }
+    }
+}
+
 fn get_field_size(attrs: &[Attribute], span: Span) -> Option<TokenStream> {
     get_attribute_value(attrs, &["size"])
         .map(|size_lit| match size_lit {
@@ -0,0 +1,61 @@
+use bitbuffer::{BitRead, BitReadBuffer, BitReadStream, BitWrite, BitWriteStream, LittleEndian};
+
+// struct { uint8_t a: 3; uint16_t b: 7; uint32_t c: 30; uint8_t d; }
+#[derive(BitRead, BitWrite, PartialEq, Debug)]
+#[bitfield_layout = "gcc"]
+struct GccStruct {
+    #[size = 3]
+    a: u8,
+    #[size = 7]
+    b: u16,
+    #[size = 30]
+    c: u32,
+    d: u8,
+}
+
+// struct { uint32_t a: 3; uint8_t b: 4; uint32_t c: 5; }
+#[derive(BitRead, BitWrite, PartialEq, Debug)]
+#[bitfield_layout = "msvc"]
+struct MsvcStruct(#[size = 3] u32, #[size = 4] u8, #[size = 5] u32);
+
+#[derive(BitRead, BitWrite, PartialEq, Debug)]
+#[bitfield_layout = "gcc"]
+struct GccPacked(#[size = 3] u32, #[size = 4] u8, #[size = 5] u32);
+
+fn roundtrip<T>(value: T, expected: &[u8])
+where
+    T: for<'a> BitRead<'a, LittleEndian> + BitWrite<LittleEndian> + PartialEq + std::fmt::Debug,
+{
+    assert_eq!(T::bit_size(), Some(expected.len() * 8));
+
+    let mut data = Vec::new();
+    let mut stream = BitWriteStream::new(&mut data, LittleEndian);
+    stream.write(&value).unwrap();
+    assert_eq!(data, expected);
+
+    let mut stream = BitReadStream::new(BitReadBuffer::new(&data, LittleEndian));
+    assert_eq!(stream.read::<T>().unwrap(), value);
+    assert_eq!(stream.bits_left(), 0);
+}
+
+#[test]
+fn test_gcc_layout() {
+    roundtrip(
+        GccStruct {
+            a: 5,
+            b: 0x55,
+            c: 1,
+            d: 0xAB,
+        },
+        &[0xAD, 0x02, 0, 0, 1, 0, 0, 0, 0xAB, 0, 0, 0],
+    );
+    roundtrip(GccPacked(5, 0xF, 0x1F), &[0b1111_1101, 0b0000_1111, 0, 0]);
+}
+
+#[test]
+fn test_msvc_layout() {
+    roundtrip(
+        MsvcStruct(5, 0xF, 0x1F),
+        &[5, 0, 0, 0, 0xF, 0, 0, 0, 0x1F, 0, 0, 0],
+    );
+}
@@ -0,0 +1,174 @@
+#![allow(clippy::unnecessary_cast, clippy::disallowed_names)]
+#![allow(dead_code)]
+#![allow(unreachable_patterns)]
+
+use bitbuffer::{
+    BitReadStream, Endianness, FieldLayout, Layout, LayoutDescribe, SizeSource, VariantLayout,
+};
+use bitbuffer_derive::{BitRead, BitReadSized};
+
+#[derive(BitRead, LayoutDescribe)]
+struct TestStruct {
+    foo: u8,
+    str: String,
+    #[size = 2]
+    truncated: String,
+    #[size = 3]
+    asd: u8,
+    #[size_bits = 2]
+    dynamic: u8,
+    #[size = "asd"]
+    previous_field: u8,
+}
+
+#[test]
+fn test_struct_layout() {
+    let layout = TestStruct::layout();
+    assert_eq!(layout.name(), "TestStruct");
+    assert_eq!(
+        layout.fields(),
+        &[
+            FieldLayout {
+                name: "foo",
+                type_name: "u8",
+                size: SizeSource::Unsized,
+                bit_size: Some(8),
+            },
+            FieldLayout {
+                name: "str",
+                type_name: "String",
+                size: SizeSource::Unsized,
+                bit_size: None,
+            },
+            FieldLayout {
+                name: "truncated",
+                type_name: "String",
+                size: SizeSource::Fixed(2),
+                bit_size: Some(16),
+            },
+            FieldLayout {
+                name: "asd",
+                type_name: "u8",
+                size: SizeSource::Fixed(3),
+                bit_size: Some(3),
+            },
+            FieldLayout {
+                name: "dynamic",
+                type_name: "u8",
+                size: SizeSource::Bits(2),
+                bit_size: None,
+            },
+            FieldLayout {
+                name: "previous_field",
+                type_name: "u8",
+                size: SizeSource::Expression("asd"),
+                bit_size: None,
+            },
+        ]
+    );
+    assert_eq!(layout.bit_size(), None);
+}
+
+#[derive(BitRead, LayoutDescribe)]
+struct TestUnnamedStruct(u8, #[size = 4] u16, bool);
+
+#[test]
+fn test_unnamed_struct_layout() {
+    let layout = TestUnnamedStruct::layout();
+    let names: Vec<_> = layout.fields().iter().map(|field| field.name).collect();
+    assert_eq!(names, vec!["0", "1", "2"]);
+    assert_eq!(layout.bit_size(), Some(13));
+}
+
+#[derive(BitReadSized, LayoutDescribe)]
+struct TestStructSized {
+    foo: u8,
+    #[size = "input_size"]
+    string: String,
+}
+
+#[test]
+fn test_sized_struct_layout() {
+    let layout = TestStructSized::layout();
+    assert_eq!(layout.fields()[1].size, SizeSource::InputSize);
+    assert_eq!(layout.fields()[1].bit_size, None);
+}
+
+#[derive(BitRead, LayoutDescribe)]
+#[discriminant_bits = 2]
+enum TestBareEnum {
+    Foo,
+    Bar,
+    Asd = 3,
+}
+
+#[derive(BitRead, LayoutDescribe)]
+#[discriminant_bits = 2]
+enum TestUnnamedFieldEnum {
+    #[size = 5]
+    Foo(i8),
+    Bar(bool),
+    #[discriminant = "_"]
+    Asd(u8),
+}
+
+#[test]
+fn test_enum_layout() {
+    assert_eq!(TestBareEnum::layout().bit_size(), Some(2));
+
+    let layout = TestUnnamedFieldEnum::layout();
+    assert_eq!(layout.bit_size(), None);
+    assert_eq!(
+        layout,
+        Layout::Enum {
+            name: "TestUnnamedFieldEnum",
+            discriminant_bits: 2,
+            variants: vec![
+                VariantLayout {
+                    name: "Foo",
+                    discriminant: Some(0),
+                    field: Some(FieldLayout {
+                        name: "0",
+                        type_name: "i8",
+                        size: SizeSource::Fixed(5),
+                        bit_size: Some(5),
+                    }),
+                },
+                VariantLayout {
+                    name: "Bar",
+                    discriminant: Some(1),
+                    field: Some(FieldLayout {
+                        name: "0",
+                        type_name: "bool",
+                        size: SizeSource::Unsized,
+                        bit_size: Some(1),
+                    }),
+                },
+                VariantLayout {
+                    name: "Asd",
+                    discriminant: None,
+                    field: Some(FieldLayout {
+                        name: "0",
+                        type_name: "u8",
+                        size: SizeSource::Unsized,
+                        bit_size: Some(8),
+                    }),
+                },
+            ],
+        }
+    );
+}
+
+#[derive(BitRead, LayoutDescribe)]
+#[endianness = "E"]
+struct TestEndiannessStruct<'a, E: Endianness> {
+    size: u8,
+    #[size = "size"]
+    stream: BitReadStream<'a, E>,
+}
+
+#[test]
+fn test_generic_layout() {
+    let layout = <TestEndiannessStruct<bitbuffer::BigEndian>>::layout();
+    assert_eq!(layout.fields()[1].type_name, "BitReadStream<'a, E>");
+}
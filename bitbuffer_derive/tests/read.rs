@@ -92,6 +92,24 @@ fn test_read_bare_enum() {
     assert_eq!(Some(2), bit_size_of::<TestBareEnum>());
 }
 
+#[derive(BitRead, PartialEq, Debug)]
+#[repr(u16)]
+enum TestReprEnum {
+    Foo = 1,
+    Bar = 0x1234,
+}
+
+#[test]
+fn test_read_repr_enum() {
+    let bytes = vec![0x12, 0x34, 0x00, 0x01, 0x00, 0x02];
+    let buffer = BitReadBuffer::new(&bytes, BigEndian);
+    let mut stream = BitReadStream::from(buffer);
+    assert_eq!(TestReprEnum::Bar, stream.read().unwrap());
+    assert_eq!(TestReprEnum::Foo, stream.read().unwrap());
+    assert_eq!(true, stream.read::<TestReprEnum>().is_err());
+    assert_eq!(Some(16), bit_size_of::<TestReprEnum>());
+}
+
 #[derive(BitRead, PartialEq, Debug)]
 #[discriminant_bits = 2]
 enum TestUnnamedFieldEnum {
@@ -335,3 +353,71 @@ fn test_bit_size_sized() {
         Some(8 + 8 * 16 + 1)
     );
 }
+
+#[derive(BitRead, PartialEq, Debug)]
+struct LinkedNode {
+    value: u8,
+    next: Option<Box<LinkedNode>>,
+}
+
+#[test]
+fn test_read_depth_limit() {
+    use bitbuffer::{BitError, ReadLimit, ReadLimits};
+
+    // 3 nodes with a value of 0, the `next` flags are at bit 8, 17 and 26
+    let bytes = vec![0, 1, 2, 0, 0, 0];
+    let buffer = BitReadBuffer::new(&bytes, LittleEndian);
+    let mut stream = BitReadStream::new(buffer);
+    stream.set_limits(ReadLimits {
+        max_depth: 3,
+        ..ReadLimits::default()
+    });
+    let node: LinkedNode = stream.read().unwrap();
+    assert!(node.next.unwrap().next.is_some());
+
+    stream.set_pos(0).unwrap();
+    stream.set_limits(ReadLimits {
+        max_depth: 2,
+        ..ReadLimits::default()
+    });
+    assert!(matches!(
+        stream.read::<LinkedNode>(),
+        Err(BitError::LimitExceeded {
+            limit: ReadLimit::Depth,
+            requested: 3,
+            max: 2
+        })
+    ));
+
+    // the depth is restored after a failed read
+    stream.set_pos(0).unwrap();
+    stream.set_limits(ReadLimits::UNLIMITED);
+    assert!(stream.read::<LinkedNode>().is_ok());
+}
+
+#[test]
+fn test_failed_read_doesnt_advance() {
+    use bitbuffer::BitRead;
+
+    // runs out of data after reading the strings
+    let bytes = vec![12, b'a', b'b', 0, b'c', b'd', 0, 0];
+    let buffer = BitReadBuffer::new(&bytes, LittleEndian);
+    let mut stream = BitReadStream::new(buffer);
+    stream.skip_bits(8).unwrap();
+    assert!(<TestStruct as BitRead<LittleEndian>>::read(&mut stream).is_err());
+    assert_eq!(stream.pos(), 8);
+
+    // unmatched discriminant at bit 4
+    let bytes = vec![0b1111_1000];
+    let buffer = BitReadBuffer::new(&bytes, BigEndian);
+    let mut stream = BitReadStream::new(buffer);
+    stream.skip_bits(4).unwrap();
+    assert!(<TestBareEnum as BitRead<BigEndian>>::read(&mut stream).is_err());
+    assert_eq!(stream.pos(), 4);
+
+    // unmatched discriminant after reading a valid one
+    stream.set_pos(2).unwrap();
+    assert!(stream.read::<[TestBareEnum; 2]>().is_err());
+    assert_eq!(stream.pos(), 2);
+    assert_eq!(stream.read::<TestBareEnum>().unwrap(), TestBareEnum::Asd);
+}
@@ -76,6 +76,23 @@ fn test_read_bare_enum() {
     assert_eq!(bytes, data);
 }
 
+#[derive(BitWrite, PartialEq, Debug)]
+#[repr(i8)]
+enum TestReprEnum {
+    Foo = 1,
+    Bar = 0x7F,
+}
+
+#[test]
+fn test_write_repr_enum() {
+    let mut data = Vec::new();
+    let mut stream = BitWriteStream::new(&mut data, BigEndian);
+    stream.write(&TestReprEnum::Bar).unwrap();
+    stream.write(&TestReprEnum::Foo).unwrap();
+
+    assert_eq!(vec![0x7F, 0x01], data);
+}
+
 #[derive(BitWrite, BitRead, PartialEq, Debug)]
 #[discriminant_bits = 2]
 enum TestUnnamedFieldEnum {
@@ -0,0 +1,199 @@
+//! Python bindings for [`bitbuffer`]
+//!
+//! Exposes [`BitReadStream`](bitbuffer::BitReadStream) and [`BitWriteStream`](bitbuffer::BitWriteStream) as python
+//! classes, which allows prototyping a format in python before porting it to rust.
+//!
+//! The crate builds a `bitbuffer` python extension module, the classes can also be added to a different python
+//! module created with pyo3 using [`register`]:
+//!
+//! ```ignore
+//! use pyo3::prelude::*;
+//!
+//! #[pymodule]
+//! fn my_module(m: &Bound<'_, PyModule>) -> PyResult<()> {
+//!     bitbuffer_python::register(m)
+//! }
+//! ```
+//!
+//! After which they can be used from python:
+//!
+//! ```python
+//! from bitbuffer import BitReadStream, BitWriteStream
+//!
+//! stream = BitReadStream(b"\x2a\xff", little_endian=False)
+//! version = stream.read_uint(4)
+//! flags = stream.read_uint(4)
+//! ```
+//!
+//! Errors are raised as `EOFError` when the stream runs out of data, `OSError` for io errors
+//! and `ValueError` for all other errors.
+
+use bitbuffer::owned::{with_owned_stream, with_owned_writer, OwnedStream, OwnedWriter};
+use bitbuffer::{BitError, ErrorKind};
+use pyo3::exceptions::{PyEOFError, PyOSError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::PyBytes;
+
+/// Convert an error into the matching python exception
+pub fn to_py_err(err: BitError) -> PyErr {
+    match err.kind() {
+        ErrorKind::NotEnoughData => PyEOFError::new_err(err.to_string()),
+        ErrorKind::Io => PyOSError::new_err(err.to_string()),
+        _ => PyValueError::new_err(err.to_string()),
+    }
+}
+
+#[pymodule]
+#[pyo3(name = "bitbuffer")]
+fn bitbuffer_module(module: &Bound<'_, PyModule>) -> PyResult<()> {
+    register(module)
+}
+
+/// Add the `BitReadStream` and `BitWriteStream` classes to a python module
+pub fn register(module: &Bound<'_, PyModule>) -> PyResult<()> {
+    module.add_class::<PyBitReadStream>()?;
+    module.add_class::<PyBitWriteStream>()?;
+    Ok(())
+}
+
+/// A python class for reading bits from a copy of a `bytes` object
+#[pyclass(name = "BitReadStream", module = "bitbuffer")]
+pub struct PyBitReadStream {
+    stream: OwnedStream,
+}
+
+#[pymethods]
+impl PyBitReadStream {
+    #[new]
+    #[pyo3(signature = (data, little_endian = true))]
+    fn new(data: &[u8], little_endian: bool) -> Self {
+        PyBitReadStream {
+            stream: OwnedStream::new(data.to_vec(), little_endian),
+        }
+    }
+
+    fn read_bool(&mut self) -> PyResult<bool> {
+        with_owned_stream!(&mut self.stream, stream => stream.read_bool()).map_err(to_py_err)
+    }
+
+    fn read_uint(&mut self, bits: usize) -> PyResult<u128> {
+        if bits == 0 {
+            return Ok(0);
+        }
+        with_owned_stream!(&mut self.stream, stream => stream.read_int(bits)).map_err(to_py_err)
+    }
+
+    fn read_sint(&mut self, bits: usize) -> PyResult<i128> {
+        if bits == 0 {
+            return Ok(0);
+        }
+        with_owned_stream!(&mut self.stream, stream => stream.read_int(bits)).map_err(to_py_err)
+    }
+
+    fn read_f32(&mut self) -> PyResult<f32> {
+        with_owned_stream!(&mut self.stream, stream => stream.read_float()).map_err(to_py_err)
+    }
+
+    fn read_f64(&mut self) -> PyResult<f64> {
+        with_owned_stream!(&mut self.stream, stream => stream.read_float()).map_err(to_py_err)
+    }
+
+    #[pyo3(signature = (length = None))]
+    fn read_string(&mut self, length: Option<usize>) -> PyResult<String> {
+        with_owned_stream!(&mut self.stream, stream => stream.read_string(length))
+            .map(|string| string.into_owned())
+            .map_err(to_py_err)
+    }
+
+    fn read_bytes<'py>(&mut self, py: Python<'py>, count: usize) -> PyResult<Bound<'py, PyBytes>> {
+        let bytes = with_owned_stream!(&mut self.stream, stream => stream.read_bytes(count))
+            .map_err(to_py_err)?;
+        Ok(PyBytes::new(py, &bytes))
+    }
+
+    fn skip_bits(&mut self, bits: usize) -> PyResult<()> {
+        with_owned_stream!(&mut self.stream, stream => stream.skip_bits(bits)).map_err(to_py_err)
+    }
+
+    fn set_pos(&mut self, pos: usize) -> PyResult<()> {
+        with_owned_stream!(&mut self.stream, stream => stream.set_pos(pos)).map_err(to_py_err)
+    }
+
+    #[getter]
+    fn pos(&self) -> usize {
+        with_owned_stream!(&self.stream, stream => stream.pos())
+    }
+
+    #[getter]
+    fn bits_left(&self) -> usize {
+        with_owned_stream!(&self.stream, stream => stream.bits_left())
+    }
+
+    #[getter]
+    fn bit_len(&self) -> usize {
+        with_owned_stream!(&self.stream, stream => stream.bit_len())
+    }
+}
+
+/// A python class for writing bits into a growing buffer
+#[pyclass(name = "BitWriteStream", module = "bitbuffer")]
+pub struct PyBitWriteStream {
+    writer: OwnedWriter,
+}
+
+#[pymethods]
+impl PyBitWriteStream {
+    #[new]
+    #[pyo3(signature = (little_endian = true))]
+    fn new(little_endian: bool) -> Self {
+        PyBitWriteStream {
+            writer: OwnedWriter::new(little_endian),
+        }
+    }
+
+    fn write_bool(&mut self, value: bool) -> PyResult<()> {
+        with_owned_writer!(self.writer, stream => stream.write_bool(value)).map_err(to_py_err)
+    }
+
+    fn write_uint(&mut self, value: u128, bits: usize) -> PyResult<()> {
+        if bits == 0 {
+            return Ok(());
+        }
+        with_owned_writer!(self.writer, stream => stream.write_int(value, bits)).map_err(to_py_err)
+    }
+
+    fn write_sint(&mut self, value: i128, bits: usize) -> PyResult<()> {
+        if bits == 0 {
+            return Ok(());
+        }
+        with_owned_writer!(self.writer, stream => stream.write_int(value, bits)).map_err(to_py_err)
+    }
+
+    fn write_f32(&mut self, value: f32) -> PyResult<()> {
+        with_owned_writer!(self.writer, stream => stream.write_float(value)).map_err(to_py_err)
+    }
+
+    fn write_f64(&mut self, value: f64) -> PyResult<()> {
+        with_owned_writer!(self.writer, stream => stream.write_float(value)).map_err(to_py_err)
+    }
+
+    #[pyo3(signature = (string, length = None))]
+    fn write_string(&mut self, string: &str, length: Option<usize>) -> PyResult<()> {
+        with_owned_writer!(self.writer, stream => stream.write_string(string, length))
+            .map_err(to_py_err)
+    }
+
+    fn write_bytes(&mut self, data: &[u8]) -> PyResult<()> {
+        with_owned_writer!(self.writer, stream => stream.write_bytes(data)).map_err(to_py_err)
+    }
+
+    /// The written data, the last byte is padded with zero bits
+    fn data<'py>(&self, py: Python<'py>) -> Bound<'py, PyBytes> {
+        PyBytes::new(py, self.writer.data())
+    }
+
+    #[getter]
+    fn bit_len(&self) -> usize {
+        self.writer.bit_len()
+    }
+}
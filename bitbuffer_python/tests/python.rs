@@ -0,0 +1,79 @@
+use bitbuffer_python::register;
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use std::ffi::CString;
+
+fn run(code: &str) {
+    let code = CString::new(code).unwrap();
+    Python::initialize();
+    Python::attach(|py| {
+        let module = PyModule::new(py, "bitbuffer").unwrap();
+        register(&module).unwrap();
+        let globals = PyDict::new(py);
+        globals.set_item("bitbuffer", module).unwrap();
+        if let Err(e) = py.run(&code, Some(&globals), None) {
+            panic!("{}", e);
+        }
+    });
+}
+
+#[test]
+fn test_read_stream() {
+    run(r#"
+stream = bitbuffer.BitReadStream(b"\x2a\xff\x68\x69\x00\x01", little_endian=False)
+assert stream.read_uint(4) == 2
+assert stream.read_sint(4) == -6
+assert stream.read_bool()
+assert stream.pos == 9
+stream.skip_bits(7)
+assert stream.read_string() == "hi"
+assert stream.read_bytes(1) == b"\x01"
+assert stream.bits_left == 0
+
+try:
+    stream.read_bool()
+    assert False
+except EOFError:
+    pass
+
+stream.set_pos(0)
+try:
+    stream.read_uint(129)
+    assert False
+except ValueError:
+    pass
+assert stream.pos == 0
+
+stream = bitbuffer.BitReadStream(b"\x00\x00\x80\x3f")
+assert stream.read_f32() == 1.0
+"#);
+}
+
+#[test]
+fn test_write_stream() {
+    run(r#"
+stream = bitbuffer.BitWriteStream(little_endian=False)
+stream.write_uint(2, 4)
+stream.write_sint(-6, 4)
+stream.write_bool(True)
+assert stream.bit_len == 9
+assert stream.data() == b"\x2a\x80"
+stream.write_uint(0, 7)
+stream.write_string("hi")
+stream.write_bytes(b"\x01")
+
+try:
+    stream.write_string("foo", 2)
+    assert False
+except ValueError:
+    pass
+assert stream.data() == b"\x2a\x80\x68\x69\x00\x01"
+
+stream = bitbuffer.BitWriteStream()
+stream.write_f64(1.5)
+stream.write_uint(2**100, 101)
+read = bitbuffer.BitReadStream(stream.data())
+assert read.read_f64() == 1.5
+assert read.read_uint(101) == 2**100
+"#);
+}
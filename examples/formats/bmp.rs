@@ -0,0 +1,164 @@
+//! Windows bitmap headers and uncompressed 24 bit pixel data
+//!
+//! All fields are little endian, the pixel rows are stored bottom up and padded to a multiple of 4 bytes.
+
+use bitbuffer::{BitError, BitRead, BitReadStream, BitWrite, LayoutDescribe, LittleEndian, Result};
+
+/// A 2x2 pixel image with a red, green, blue and white pixel
+#[rustfmt::skip]
+pub const SAMPLE: [u8; 70] = [
+    // file header
+    b'B', b'M', 70, 0, 0, 0, 0, 0, 0, 0, 54, 0, 0, 0,
+    // info header
+    40, 0, 0, 0, 2, 0, 0, 0, 2, 0, 0, 0, 1, 0, 24, 0, 0, 0, 0, 0, 16, 0, 0, 0,
+    0x13, 0x0B, 0, 0, 0x13, 0x0B, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+    // bottom row, blue and white, in bgr order
+    0xFF, 0x00, 0x00, 0xFF, 0xFF, 0xFF, 0, 0,
+    // top row, red and green
+    0x00, 0x00, 0xFF, 0x00, 0xFF, 0x00, 0, 0,
+];
+
+#[derive(BitRead, BitWrite, LayoutDescribe, Debug, Clone, PartialEq)]
+pub struct FileHeader {
+    pub magic: [u8; 2],
+    pub file_size: u32,
+    pub reserved: u32,
+    pub pixel_offset: u32,
+}
+
+#[derive(BitRead, BitWrite, Debug, Clone, Copy, PartialEq)]
+#[repr(u32)]
+pub enum Compression {
+    Rgb = 0,
+    Rle8 = 1,
+    Rle4 = 2,
+    Bitfields = 3,
+}
+
+#[derive(BitRead, BitWrite, LayoutDescribe, Debug, Clone, PartialEq)]
+pub struct InfoHeader {
+    pub header_size: u32,
+    pub width: i32,
+    /// Negative for images stored top down
+    pub height: i32,
+    pub planes: u16,
+    pub bit_count: u16,
+    pub compression: Compression,
+    pub image_size: u32,
+    pub x_pixels_per_meter: i32,
+    pub y_pixels_per_meter: i32,
+    pub colors_used: u32,
+    pub colors_important: u32,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Bitmap {
+    pub header: FileHeader,
+    pub info: InfoHeader,
+    /// The pixels as rgb, row by row starting at the top
+    pub pixels: Vec<[u8; 3]>,
+}
+
+pub fn read_bitmap(stream: &mut BitReadStream<LittleEndian>) -> Result<Bitmap> {
+    let start = stream.pos();
+    let header: FileHeader = stream.read()?;
+    if header.magic != *b"BM" {
+        return Err(BitError::Malformed {
+            pos: start,
+            reason: "not a bitmap",
+        });
+    }
+    let info: InfoHeader = stream.read()?;
+    if info.bit_count != 24 || info.compression != Compression::Rgb {
+        return Err(BitError::Malformed {
+            pos: start,
+            reason: "only uncompressed 24 bit bitmaps are supported",
+        });
+    }
+
+    let width = info.width.unsigned_abs() as usize;
+    let height = info.height.unsigned_abs() as usize;
+    let padding = (4 - width * 3 % 4) % 4;
+    let mut rows = Vec::with_capacity(height);
+    stream.set_pos(start + header.pixel_offset as usize * 8)?;
+    for _ in 0..height {
+        let mut row = Vec::with_capacity(width);
+        for _ in 0..width {
+            let [blue, green, red]: [u8; 3] = stream.read()?;
+            row.push([red, green, blue]);
+        }
+        stream.skip_bits(padding * 8)?;
+        rows.push(row);
+    }
+    if info.height > 0 {
+        rows.reverse();
+    }
+
+    Ok(Bitmap {
+        header,
+        info,
+        pixels: rows.concat(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bitbuffer::{BitReadBuffer, BitWriteStream};
+
+    #[test]
+    fn test_read_bitmap() {
+        let mut stream = BitReadStream::new(BitReadBuffer::new(&SAMPLE, LittleEndian));
+        let bitmap = read_bitmap(&mut stream).unwrap();
+        assert_eq!(bitmap.header.file_size as usize, SAMPLE.len());
+        assert_eq!(bitmap.info.width, 2);
+        assert_eq!(bitmap.info.compression, Compression::Rgb);
+        assert_eq!(
+            bitmap.pixels,
+            [[255, 0, 0], [0, 255, 0], [0, 0, 255], [255, 255, 255]]
+        );
+        assert_eq!(stream.bits_left(), 0);
+    }
+
+    #[test]
+    fn test_write_headers() {
+        let mut stream = BitReadStream::new(BitReadBuffer::new(&SAMPLE, LittleEndian));
+        let bitmap = read_bitmap(&mut stream).unwrap();
+
+        let mut data = Vec::new();
+        let mut stream = BitWriteStream::new(&mut data, LittleEndian);
+        stream.write(&bitmap.header).unwrap();
+        stream.write(&bitmap.info).unwrap();
+        drop(stream);
+        assert_eq!(data, SAMPLE[..54]);
+    }
+
+    #[test]
+    fn test_layout() {
+        let layout = FileHeader::layout();
+        assert_eq!(layout.name(), "FileHeader");
+        assert_eq!(layout.bit_size(), Some(14 * 8));
+        let layout = InfoHeader::layout();
+        assert_eq!(layout.fields().len(), 11);
+        assert_eq!(layout.fields()[5].name, "compression");
+    }
+
+    #[test]
+    fn test_invalid_bitmap() {
+        let mut bytes = SAMPLE;
+        bytes[0] = b'X';
+        let mut stream = BitReadStream::new(BitReadBuffer::new(&bytes, LittleEndian));
+        assert!(matches!(
+            read_bitmap(&mut stream),
+            Err(BitError::Malformed { pos: 0, .. })
+        ));
+
+        let mut bytes = SAMPLE;
+        bytes[30] = 5;
+        let mut stream = BitReadStream::new(BitReadBuffer::new(&bytes, LittleEndian));
+        assert!(matches!(
+            read_bitmap(&mut stream),
+            Err(BitError::UnmatchedDiscriminant { .. })
+        ));
+    }
+}
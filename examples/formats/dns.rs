@@ -0,0 +1,200 @@
+//! DNS message headers and questions
+//!
+//! All fields are big endian, the flags in the header are packed as individual bits.
+
+use bitbuffer::{
+    BigEndian, BitError, BitRead, BitReadStream, BitWrite, BitWriteStream, Endianness,
+    LayoutDescribe, Result,
+};
+
+/// A recursive query for the A record of `example.com`
+#[rustfmt::skip]
+pub const SAMPLE: [u8; 29] = [
+    // header
+    0x12, 0x34, 0x01, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    // question
+    7, b'e', b'x', b'a', b'm', b'p', b'l', b'e', 3, b'c', b'o', b'm', 0,
+    0x00, 0x01, 0x00, 0x01,
+];
+
+#[derive(BitRead, BitWrite, LayoutDescribe, Debug, Clone, Copy, PartialEq)]
+#[discriminant_bits = 4]
+pub enum Opcode {
+    Query,
+    InverseQuery,
+    Status,
+    #[discriminant = 4]
+    Notify,
+    Update,
+}
+
+#[derive(BitRead, BitWrite, LayoutDescribe, Debug, Clone, Copy, PartialEq)]
+#[discriminant_bits = 4]
+pub enum ResponseCode {
+    NoError,
+    FormatError,
+    ServerFailure,
+    NameError,
+    NotImplemented,
+    Refused,
+}
+
+#[derive(BitRead, BitWrite, LayoutDescribe, Debug, Clone, PartialEq)]
+pub struct Header {
+    pub id: u16,
+    pub response: bool,
+    pub opcode: Opcode,
+    pub authoritative: bool,
+    pub truncated: bool,
+    pub recursion_desired: bool,
+    pub recursion_available: bool,
+    #[size = 3]
+    pub reserved: u8,
+    pub response_code: ResponseCode,
+    pub question_count: u16,
+    pub answer_count: u16,
+    pub authority_count: u16,
+    pub additional_count: u16,
+}
+
+/// A domain name, stored as a list of length prefixed labels ending with an empty label
+///
+/// Compressed names, which point to an earlier name in the message, are not supported.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Name(pub Vec<String>);
+
+impl<E: Endianness> BitRead<'_, E> for Name {
+    fn read(stream: &mut BitReadStream<E>) -> Result<Self> {
+        let mut labels = Vec::new();
+        loop {
+            let pos = stream.pos();
+            let length: u8 = stream.clone().read()?;
+            if length == 0 {
+                stream.skip_bits(8)?;
+                return Ok(Name(labels));
+            }
+            if length > 63 {
+                return Err(BitError::Malformed {
+                    pos,
+                    reason: "compressed names are not supported",
+                });
+            }
+            labels.push(stream.read_string_prefixed(8)?.into_owned());
+        }
+    }
+}
+
+impl<E: Endianness> BitWrite<E> for Name {
+    fn write(&self, stream: &mut BitWriteStream<E>) -> Result<()> {
+        for label in &self.0 {
+            stream.write_string_prefixed(label, 8)?;
+        }
+        stream.write_int(0u8, 8)
+    }
+}
+
+#[derive(BitRead, BitWrite, Debug, Clone, PartialEq)]
+pub struct Question {
+    pub name: Name,
+    pub record_type: u16,
+    pub class: u16,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Query {
+    pub header: Header,
+    pub questions: Vec<Question>,
+}
+
+pub fn read_query(stream: &mut BitReadStream<BigEndian>) -> Result<Query> {
+    let header: Header = stream.read()?;
+    let questions = stream.read_sized(header.question_count as usize)?;
+    Ok(Query { header, questions })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bitbuffer::{assert_bit_eq, BitReadBuffer};
+
+    #[test]
+    fn test_read_query() {
+        let mut stream = BitReadStream::new(BitReadBuffer::new(&SAMPLE, BigEndian));
+        let query = read_query(&mut stream).unwrap();
+        assert_eq!(stream.bits_left(), 0);
+        assert_eq!(query.header.id, 0x1234);
+        assert!(!query.header.response);
+        assert_eq!(query.header.opcode, Opcode::Query);
+        assert!(query.header.recursion_desired);
+        assert_eq!(query.header.response_code, ResponseCode::NoError);
+        assert_eq!(
+            query.questions,
+            [Question {
+                name: Name(vec!["example".into(), "com".into()]),
+                record_type: 1,
+                class: 1,
+            }]
+        );
+    }
+
+    #[test]
+    fn test_write_response_header() {
+        let header = Header {
+            id: 0x1234,
+            response: true,
+            opcode: Opcode::Notify,
+            authoritative: true,
+            truncated: false,
+            recursion_desired: true,
+            recursion_available: true,
+            reserved: 0,
+            response_code: ResponseCode::NameError,
+            question_count: 1,
+            answer_count: 0,
+            authority_count: 1,
+            additional_count: 0,
+        };
+        let mut data = Vec::new();
+        let mut stream = BitWriteStream::new(&mut data, BigEndian);
+        stream.write(&header).unwrap();
+        assert_bit_eq!(
+            BitReadBuffer::from_hex("1234 a583 0001 0000 0001 0000", BigEndian).unwrap(),
+            stream
+        );
+        drop(stream);
+
+        let mut stream = BitReadStream::new(BitReadBuffer::new(&data, BigEndian));
+        assert_eq!(stream.read::<Header>().unwrap(), header);
+    }
+
+    #[test]
+    fn test_header_layout() {
+        assert_eq!(Header::layout().bit_size(), Some(12 * 8));
+        assert_eq!(Opcode::layout().bit_size(), Some(4));
+    }
+
+    #[test]
+    fn test_write_question() {
+        let mut data = Vec::new();
+        let mut stream = BitWriteStream::new(&mut data, BigEndian);
+        stream
+            .write(&Question {
+                name: Name(vec!["example".into(), "com".into()]),
+                record_type: 1,
+                class: 1,
+            })
+            .unwrap();
+        drop(stream);
+        assert_eq!(data, SAMPLE[12..]);
+    }
+
+    #[test]
+    fn test_compressed_name() {
+        let bytes = [0xC0, 0x0C, 0x00, 0x01, 0x00, 0x01];
+        let mut stream = BitReadStream::new(BitReadBuffer::new(&bytes, BigEndian));
+        assert!(matches!(
+            stream.read::<Question>(),
+            Err(BitError::Malformed { pos: 0, .. })
+        ));
+    }
+}
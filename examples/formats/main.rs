@@ -0,0 +1,32 @@
+//! A few real world formats implemented with the public api of bitbuffer
+//!
+//! Every module combines derived implementations with hand written ones where the format requires it,
+//! and contains tests that read and write sample data. The tests run as part of `cargo test`,
+//! `cargo run --example formats` prints the parsed samples.
+
+use bitbuffer::{BigEndian, BitReadBuffer, BitReadStream, LittleEndian, Result};
+
+mod bmp;
+mod dns;
+mod midi;
+
+fn main() -> Result<()> {
+    let mut stream = BitReadStream::new(BitReadBuffer::new(&bmp::SAMPLE, LittleEndian));
+    let bitmap = bmp::read_bitmap(&mut stream)?;
+    println!("{:#?}", bitmap.info);
+    println!("{:?}", bitmap.pixels);
+
+    let mut stream = BitReadStream::new(BitReadBuffer::new(&dns::SAMPLE, BigEndian));
+    println!("{:#?}", dns::read_query(&mut stream)?);
+
+    let mut stream = BitReadStream::new(BitReadBuffer::new(&midi::SAMPLE, BigEndian));
+    for event in midi::read_events(&mut stream)? {
+        match event.message {
+            midi::Message::PitchBend(bend) => {
+                println!("+{} pitch bend {:#x}", event.delta_time, bend.value())
+            }
+            message => println!("+{} {:?}", event.delta_time, message),
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,166 @@
+//! MIDI channel events as stored in a standard MIDI file track
+//!
+//! Every event starts with a variable length delta time, followed by a status byte containing the message type
+//! in the upper 4 bits and the channel in the lower 4 bits. Running status, where the status byte is omitted
+//! when it is the same as the previous event, is not supported.
+
+use bitbuffer::{BigEndian, BitRead, BitReadStream, BitWrite, Result, Vlq};
+
+/// Middle C played for a quarter note at 96 ticks per quarter note on channel 1, with a volume change first
+#[rustfmt::skip]
+pub const SAMPLE: [u8; 12] = [
+    0x00, 0xB0, 0x07, 0x64,
+    0x00, 0x90, 0x3C, 0x40,
+    0x60, 0x80, 0x3C, 0x00,
+];
+
+#[derive(BitRead, BitWrite, Debug, Clone, Copy, PartialEq)]
+pub struct Note {
+    #[size = 4]
+    pub channel: u8,
+    pub key: u8,
+    pub velocity: u8,
+}
+
+#[derive(BitRead, BitWrite, Debug, Clone, Copy, PartialEq)]
+pub struct Control {
+    #[size = 4]
+    pub channel: u8,
+    pub controller: u8,
+    pub value: u8,
+}
+
+#[derive(BitRead, BitWrite, Debug, Clone, Copy, PartialEq)]
+pub struct ChannelValue {
+    #[size = 4]
+    pub channel: u8,
+    pub value: u8,
+}
+
+#[derive(BitRead, BitWrite, Debug, Clone, Copy, PartialEq)]
+pub struct PitchBend {
+    #[size = 4]
+    pub channel: u8,
+    pub low: u8,
+    pub high: u8,
+}
+
+impl PitchBend {
+    /// The 14 bit bend amount, 0x2000 is the center
+    pub fn value(&self) -> u16 {
+        (self.high as u16) << 7 | self.low as u16
+    }
+}
+
+#[derive(BitRead, BitWrite, Debug, Clone, Copy, PartialEq)]
+#[discriminant_bits = 4]
+pub enum Message {
+    #[discriminant = 8]
+    NoteOff(Note),
+    NoteOn(Note),
+    KeyPressure(Note),
+    ControlChange(Control),
+    ProgramChange(ChannelValue),
+    ChannelPressure(ChannelValue),
+    PitchBend(PitchBend),
+}
+
+#[derive(BitRead, BitWrite, Debug, Clone, Copy, PartialEq)]
+pub struct Event {
+    pub delta_time: Vlq,
+    pub message: Message,
+}
+
+/// Read events until the end of the stream
+pub fn read_events(stream: &mut BitReadStream<BigEndian>) -> Result<Vec<Event>> {
+    let mut events = Vec::new();
+    while stream.bits_left() > 0 {
+        events.push(stream.read()?);
+    }
+    Ok(events)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bitbuffer::{BitError, BitReadBuffer, BitWriteStream};
+
+    #[test]
+    fn test_read_events() {
+        let mut stream = BitReadStream::new(BitReadBuffer::new(&SAMPLE, BigEndian));
+        let events = read_events(&mut stream).unwrap();
+        assert_eq!(events.len(), 3);
+        assert_eq!(
+            events[0].message,
+            Message::ControlChange(Control {
+                channel: 0,
+                controller: 7,
+                value: 100,
+            })
+        );
+        let note = Note {
+            channel: 0,
+            key: 60,
+            velocity: 64,
+        };
+        assert_eq!(events[1].message, Message::NoteOn(note));
+        assert_eq!(events[2].delta_time, Vlq(96));
+        assert_eq!(
+            events[2].message,
+            Message::NoteOff(Note {
+                velocity: 0,
+                ..note
+            })
+        );
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        let events = [
+            Event {
+                delta_time: Vlq(200),
+                message: Message::PitchBend(PitchBend {
+                    channel: 9,
+                    low: 0x00,
+                    high: 0x40,
+                }),
+            },
+            Event {
+                delta_time: Vlq(0),
+                message: Message::ProgramChange(ChannelValue {
+                    channel: 15,
+                    value: 42,
+                }),
+            },
+        ];
+        let mut data = Vec::new();
+        let mut stream = BitWriteStream::new(&mut data, BigEndian);
+        for event in &events {
+            stream.write(event).unwrap();
+        }
+        drop(stream);
+        assert_eq!(data, [0x81, 0x48, 0xE9, 0x00, 0x40, 0x00, 0xCF, 42]);
+
+        let mut stream = BitReadStream::new(BitReadBuffer::new(&data, BigEndian));
+        let read = read_events(&mut stream).unwrap();
+        assert_eq!(read, events);
+        match read[0].message {
+            Message::PitchBend(bend) => assert_eq!(bend.value(), 0x2000),
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn test_system_messages() {
+        // a tempo meta event
+        let bytes = [0x00, 0xFF, 0x51, 0x03, 0x07, 0xA1, 0x20];
+        let mut stream = BitReadStream::new(BitReadBuffer::new(&bytes, BigEndian));
+        assert!(matches!(
+            read_events(&mut stream),
+            Err(BitError::UnmatchedDiscriminant {
+                discriminant: 0xF,
+                ..
+            })
+        ));
+    }
+}
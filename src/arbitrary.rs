@@ -0,0 +1,101 @@
+use crate::{BitRead, BitReadBuffer, BitReadStream, BitWrite, BitWriteStream, Endianness};
+use arbitrary::{Arbitrary, Result, Unstructured};
+use std::fmt::Debug;
+
+impl<'a, E: Endianness> Arbitrary<'a> for BitReadBuffer<'a, E> {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(BitReadBuffer::new(
+            <&'a [u8]>::arbitrary(u)?,
+            E::endianness(),
+        ))
+    }
+
+    fn arbitrary_take_rest(u: Unstructured<'a>) -> Result<Self> {
+        Ok(BitReadBuffer::new(
+            <&'a [u8]>::arbitrary_take_rest(u)?,
+            E::endianness(),
+        ))
+    }
+
+    fn size_hint(depth: usize) -> (usize, Option<usize>) {
+        <&'a [u8]>::size_hint(depth)
+    }
+}
+
+impl<'a, E: Endianness> Arbitrary<'a> for BitReadStream<'a, E> {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        BitReadBuffer::arbitrary(u).map(BitReadStream::new)
+    }
+
+    fn arbitrary_take_rest(u: Unstructured<'a>) -> Result<Self> {
+        BitReadBuffer::arbitrary_take_rest(u).map(BitReadStream::new)
+    }
+
+    fn size_hint(depth: usize) -> (usize, Option<usize>) {
+        <BitReadBuffer<'a, E>>::size_hint(depth)
+    }
+}
+
+/// Check that any value read from the input survives a write and read round trip
+///
+/// The input is read as `T`, inputs that can't be read are ignored. The read value is then written
+/// and read back, panicking if the written value can't be read, doesn't equal the original value
+/// or takes up a different number of bits.
+///
+/// This makes it possible to set up a fuzz target for a format with a single line.
+///
+/// ```ignore
+/// #![no_main]
+/// use bitbuffer::{fuzz_roundtrip, LittleEndian};
+/// use libfuzzer_sys::fuzz_target;
+///
+/// fuzz_target!(|data: &[u8]| fuzz_roundtrip::<MyFormat, LittleEndian>(data));
+/// ```
+///
+/// Note that types containing floats might fail the round trip for `NaN` values,
+/// since those don't compare equal to themselves.
+///
+/// # Examples
+///
+/// ```
+/// use bitbuffer::{fuzz_roundtrip, BitRead, BitWrite, LittleEndian};
+///
+/// #[derive(BitRead, BitWrite, Debug, PartialEq)]
+/// struct Packet {
+///     #[size = 3]
+///     kind: u8,
+///     name: String,
+/// }
+///
+/// fuzz_roundtrip::<Packet, LittleEndian>(&[0b1010_1001, 0b1100_0011, 0, 0]);
+/// ```
+pub fn fuzz_roundtrip<T, E>(data: &[u8])
+where
+    T: for<'a> BitRead<'a, E> + BitWrite<E> + Debug + PartialEq,
+    E: Endianness,
+{
+    let mut stream = BitReadStream::new(BitReadBuffer::new(data, E::endianness()));
+    let value: T = match stream.read() {
+        Ok(value) => value,
+        Err(_) => return,
+    };
+    let read_len = stream.pos();
+
+    let mut written = Vec::new();
+    let mut write_stream = BitWriteStream::new(&mut written, E::endianness());
+    write_stream
+        .write(&value)
+        .expect("failed to write value that was read");
+    let write_len = write_stream.bit_len();
+
+    let mut stream = BitReadStream::new(BitReadBuffer::new(&written, E::endianness()));
+    let result: T = stream
+        .read()
+        .expect("failed to read back value that was written");
+
+    assert_eq!(value, result, "value changed during round trip");
+    assert_eq!(
+        read_len, write_len,
+        "written value has a different size than the read value"
+    );
+}
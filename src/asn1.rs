@@ -0,0 +1,304 @@
+//! ASN.1 BER and DER tag and length primitives
+//!
+//! Helpers for the identifier and length octets of BER and DER encoded values, as defined in X.690,
+//! which is enough to walk certificates, SNMP messages and other ASN.1 data without a full ASN.1 framework.
+//!
+//! Reading accepts all BER encodings, writing always uses the shortest encoding, which is the encoding required by DER.
+//! All read functions leave the stream unchanged when they return an error.
+//!
+//! # Examples
+//!
+//! ```
+//! # use bitbuffer::Result;
+//! use bitbuffer::asn1::{read_element, write_element, Tag};
+//! use bitbuffer::{BitReadBuffer, BitReadStream, BitWriteStream, BigEndian};
+//!
+//! # fn main() -> Result<()> {
+//! // SEQUENCE { INTEGER 5, OCTET STRING "hi" }
+//! let bytes = [0x30, 0x07, 0x02, 0x01, 0x05, 0x04, 0x02, b'h', b'i'];
+//! let mut stream = BitReadStream::new(BitReadBuffer::new(&bytes, BigEndian));
+//!
+//! let (tag, mut sequence) = read_element(&mut stream)?;
+//! assert_eq!(tag, Tag::SEQUENCE);
+//! let (tag, mut integer) = read_element(&mut sequence)?;
+//! assert_eq!(tag, Tag::INTEGER);
+//! assert_eq!(integer.read_int::<u8>(8)?, 5);
+//! let (tag, string) = read_element(&mut sequence)?;
+//! assert_eq!(tag, Tag::OCTET_STRING);
+//! assert_eq!(string.bit_len(), 16);
+//!
+//! let mut data = Vec::new();
+//! let mut stream = BitWriteStream::new(&mut data, BigEndian);
+//! write_element(&mut stream, Tag::OCTET_STRING, b"hi")?;
+//! assert_eq!(data, [0x04, 0x02, b'h', b'i']);
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::readstream::checked_bits;
+use crate::{BitError, BitReadStream, BitWriteStream, Endianness, Result};
+
+/// The class of a tag
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum TagClass {
+    /// Types defined in X.680
+    Universal = 0,
+    /// Types specific to an application
+    Application = 1,
+    /// Types whose meaning depends on the context, commonly used for optional fields
+    ContextSpecific = 2,
+    /// Types specific to an organization
+    Private = 3,
+}
+
+/// The identifier of a value
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Tag {
+    /// The class of the tag
+    pub class: TagClass,
+    /// Whether the value consists of nested elements
+    pub constructed: bool,
+    /// The tag number
+    pub number: u32,
+}
+
+impl Tag {
+    /// The end-of-contents marker that ends a value with an indefinite length
+    pub const END_OF_CONTENTS: Tag = Tag::universal(0, false);
+    /// A `BOOLEAN`
+    pub const BOOLEAN: Tag = Tag::universal(1, false);
+    /// An `INTEGER`
+    pub const INTEGER: Tag = Tag::universal(2, false);
+    /// A `BIT STRING`
+    pub const BIT_STRING: Tag = Tag::universal(3, false);
+    /// An `OCTET STRING`
+    pub const OCTET_STRING: Tag = Tag::universal(4, false);
+    /// A `NULL`
+    pub const NULL: Tag = Tag::universal(5, false);
+    /// An `OBJECT IDENTIFIER`
+    pub const OBJECT_IDENTIFIER: Tag = Tag::universal(6, false);
+    /// A `UTF8String`
+    pub const UTF8_STRING: Tag = Tag::universal(12, false);
+    /// A `SEQUENCE` or `SEQUENCE OF`
+    pub const SEQUENCE: Tag = Tag::universal(16, true);
+    /// A `SET` or `SET OF`
+    pub const SET: Tag = Tag::universal(17, true);
+
+    const fn universal(number: u32, constructed: bool) -> Tag {
+        Tag {
+            class: TagClass::Universal,
+            constructed,
+            number,
+        }
+    }
+
+    /// A context specific tag, as used for `[n]` tagged fields
+    pub const fn context(number: u32, constructed: bool) -> Tag {
+        Tag {
+            class: TagClass::ContextSpecific,
+            constructed,
+            number,
+        }
+    }
+}
+
+/// The length of a value
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Length {
+    /// The value is the given number of bytes long
+    Definite(usize),
+    /// The value is terminated by an end-of-contents marker, only allowed for constructed values in BER
+    Indefinite,
+}
+
+/// Read the identifier octets of a value
+///
+/// # Errors
+///
+/// - [`ReadError::NotEnoughData`]: not enough bits available in the stream
+/// - [`ReadError::VarIntOverflow`]: the tag number doesn't fit in 32 bits
+///
+/// [`ReadError::NotEnoughData`]: crate::BitError::NotEnoughData
+/// [`ReadError::VarIntOverflow`]: crate::BitError::VarIntOverflow
+pub fn read_tag<E: Endianness>(stream: &mut BitReadStream<E>) -> Result<Tag> {
+    let pos = stream.pos();
+    stream.restore_on_error(|stream| {
+        let first: u8 = stream.read_int(8)?;
+        let class = match first >> 6 {
+            0 => TagClass::Universal,
+            1 => TagClass::Application,
+            2 => TagClass::ContextSpecific,
+            _ => TagClass::Private,
+        };
+        let mut number = (first & 0x1F) as u32;
+        if number == 0x1F {
+            number = 0;
+            loop {
+                let byte: u8 = stream.read_int(8)?;
+                if number >> 25 != 0 {
+                    return Err(BitError::VarIntOverflow { pos, max: 32 });
+                }
+                number = (number << 7) | (byte & 0x7F) as u32;
+                if byte & 0x80 == 0 {
+                    break;
+                }
+            }
+        }
+        Ok(Tag {
+            class,
+            constructed: first & 0x20 != 0,
+            number,
+        })
+    })
+}
+
+/// Read the length octets of a value
+///
+/// # Errors
+///
+/// - [`ReadError::NotEnoughData`]: not enough bits available in the stream
+/// - [`ReadError::VarIntOverflow`]: the length doesn't fit in a `usize`
+/// - [`ReadError::Malformed`]: the length uses the reserved `0xFF` octet
+///
+/// [`ReadError::NotEnoughData`]: crate::BitError::NotEnoughData
+/// [`ReadError::VarIntOverflow`]: crate::BitError::VarIntOverflow
+/// [`ReadError::Malformed`]: crate::BitError::Malformed
+pub fn read_length<E: Endianness>(stream: &mut BitReadStream<E>) -> Result<Length> {
+    let pos = stream.pos();
+    stream.restore_on_error(|stream| {
+        let first: u8 = stream.read_int(8)?;
+        match first {
+            0..=0x7F => Ok(Length::Definite(first as usize)),
+            0x80 => Ok(Length::Indefinite),
+            0xFF => Err(BitError::Malformed {
+                pos,
+                reason: "the length octet 0xFF is reserved",
+            }),
+            _ => {
+                let mut length: usize = 0;
+                for _ in 0..(first & 0x7F) {
+                    let byte: u8 = stream.read_int(8)?;
+                    if length >> (usize::BITS - 8) != 0 {
+                        return Err(BitError::VarIntOverflow {
+                            pos,
+                            max: usize::BITS as usize,
+                        });
+                    }
+                    length = (length << 8) | byte as usize;
+                }
+                Ok(Length::Definite(length))
+            }
+        }
+    })
+}
+
+/// Read a complete element, returning the tag and a stream containing the contents
+///
+/// For values with an indefinite length the nested elements are walked to find the end-of-contents marker,
+/// the returned stream doesn't include the marker.
+///
+/// # Errors
+///
+/// - [`ReadError::NotEnoughData`]: not enough bits available in the stream
+/// - [`ReadError::VarIntOverflow`]: the tag number or length doesn't fit
+/// - [`ReadError::Malformed`]: a primitive value has an indefinite length or a reserved length octet is used
+/// - [`ReadError::LimitExceeded`]: values with an indefinite length are nested deeper than [`ReadLimits::max_depth`](crate::ReadLimits::max_depth)
+///
+/// [`ReadError::NotEnoughData`]: crate::BitError::NotEnoughData
+/// [`ReadError::VarIntOverflow`]: crate::BitError::VarIntOverflow
+/// [`ReadError::Malformed`]: crate::BitError::Malformed
+/// [`ReadError::LimitExceeded`]: crate::BitError::LimitExceeded
+pub fn read_element<'a, E: Endianness>(
+    stream: &mut BitReadStream<'a, E>,
+) -> Result<(Tag, BitReadStream<'a, E>)> {
+    stream.restore_on_error(|stream| {
+        let tag = read_tag(stream)?;
+        let pos = stream.pos();
+        match read_length(stream)? {
+            Length::Definite(length) => Ok((tag, stream.read_bits(checked_bits(length, 8)?)?)),
+            Length::Indefinite if !tag.constructed => Err(BitError::Malformed {
+                pos,
+                reason: "a primitive value can't have an indefinite length",
+            }),
+            Length::Indefinite => {
+                let mut contents = stream.clone();
+                stream.enter_nested()?;
+                let result = skip_to_end_of_contents(stream);
+                stream.leave_nested();
+                let length = result?;
+                Ok((tag, contents.read_bits(length)?))
+            }
+        }
+    })
+}
+
+/// Skip nested elements up to and including the end-of-contents marker, returns the number of skipped bits
+/// excluding the marker
+fn skip_to_end_of_contents<E: Endianness>(stream: &mut BitReadStream<E>) -> Result<usize> {
+    let start = stream.pos();
+    loop {
+        let end = stream.pos();
+        let mut peek = stream.clone();
+        if read_tag(&mut peek)? == Tag::END_OF_CONTENTS
+            && read_length(&mut peek)? == Length::Definite(0)
+        {
+            stream.skip_bits(16)?;
+            return Ok(end - start);
+        }
+        read_element(stream)?;
+    }
+}
+
+/// Write the identifier octets of a value
+pub fn write_tag<E: Endianness>(stream: &mut BitWriteStream<E>, tag: Tag) -> Result<()> {
+    let first = (tag.class as u8) << 6 | (tag.constructed as u8) << 5;
+    if tag.number < 0x1F {
+        return stream.write_int(first | tag.number as u8, 8);
+    }
+    let mut bytes = [0; 6];
+    let mut start = bytes.len() - 1;
+    let mut number = tag.number;
+    bytes[start] = (number & 0x7F) as u8;
+    number >>= 7;
+    while number != 0 {
+        start -= 1;
+        bytes[start] = 0x80 | (number & 0x7F) as u8;
+        number >>= 7;
+    }
+    start -= 1;
+    bytes[start] = first | 0x1F;
+    stream.write_bytes(&bytes[start..])
+}
+
+/// Write the length octets of a value, using the short form when possible
+pub fn write_length<E: Endianness>(stream: &mut BitWriteStream<E>, length: Length) -> Result<()> {
+    match length {
+        Length::Indefinite => stream.write_int(0x80u8, 8),
+        Length::Definite(length) if length < 0x80 => stream.write_int(length as u8, 8),
+        Length::Definite(length) => {
+            let bytes = (length as u64).to_be_bytes();
+            let skip = (length as u64).leading_zeros() as usize / 8;
+            stream.write_int(0x80 | (bytes.len() - skip) as u8, 8)?;
+            stream.write_bytes(&bytes[skip..])
+        }
+    }
+}
+
+/// Write an element with a definite length
+pub fn write_element<E: Endianness>(
+    stream: &mut BitWriteStream<E>,
+    tag: Tag,
+    contents: &[u8],
+) -> Result<()> {
+    write_tag(stream, tag)?;
+    write_length(stream, Length::Definite(contents.len()))?;
+    stream.write_bytes(contents)
+}
+
+/// Write the end-of-contents marker that ends a value written with an indefinite length
+pub fn write_end_of_contents<E: Endianness>(stream: &mut BitWriteStream<E>) -> Result<()> {
+    stream.write_bytes(&[0, 0])
+}
@@ -0,0 +1,190 @@
+//! Base64 and base32 conversion
+//!
+//! Protocols that embed bit packed data in text, like json apis, urls or config files, often transport it as
+//! base64 or base32. The encoded text can be decoded directly into a [`BitReadBuffer`] and a [`BitWriteStream`]
+//! can be encoded into text, including a partially written final byte.
+//!
+//! The text only contains whole bytes, so the exact number of bits has to be transported separately when the
+//! data doesn't end on a byte boundary, see [`Alphabet::decode_bits`].
+//!
+//! # Examples
+//!
+//! ```
+//! # use bitbuffer::Result;
+//! use bitbuffer::basenc::BASE64;
+//! use bitbuffer::{BigEndian, BitReadStream, BitWriteStream};
+//!
+//! # fn main() -> Result<()> {
+//! let mut data = Vec::new();
+//! let mut stream = BitWriteStream::new(&mut data, BigEndian);
+//! stream.write_int(0x1234u16, 16)?;
+//! stream.write_int(0b101u8, 3)?;
+//! let bit_len = stream.bit_len();
+//! let text = BASE64.encode_stream(stream);
+//! assert_eq!(text, "EjSg");
+//!
+//! let mut stream = BitReadStream::new(BASE64.decode_bits(&text, bit_len, BigEndian)?);
+//! assert_eq!(stream.read_int::<u16>(16)?, 0x1234);
+//! assert_eq!(stream.read_int::<u8>(3)?, 0b101);
+//! assert_eq!(stream.bits_left(), 0);
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::{BitError, BitReadBuffer, BitWriteStream, Endianness, Result};
+
+/// The symbols used to encode the data
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Alphabet {
+    symbols: &'static [u8],
+    padding: bool,
+}
+
+/// The standard base64 alphabet from RFC 4648, with padding
+pub const BASE64: Alphabet = Alphabet {
+    symbols: b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/",
+    padding: true,
+};
+
+/// The url and filename safe base64 alphabet from RFC 4648, without padding
+pub const BASE64_URL: Alphabet = Alphabet {
+    symbols: b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_",
+    padding: false,
+};
+
+/// The standard base32 alphabet from RFC 4648, with padding
+pub const BASE32: Alphabet = Alphabet {
+    symbols: b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567",
+    padding: true,
+};
+
+/// The base32 alphabet with extended hex digits from RFC 4648, with padding
+pub const BASE32_HEX: Alphabet = Alphabet {
+    symbols: b"0123456789ABCDEFGHIJKLMNOPQRSTUV",
+    padding: true,
+};
+
+impl Alphabet {
+    /// The number of bits encoded by every symbol
+    fn symbol_bits(&self) -> usize {
+        self.symbols.len().trailing_zeros() as usize
+    }
+
+    /// Encode the bytes as text
+    pub fn encode(&self, bytes: &[u8]) -> String {
+        let bits = self.symbol_bits();
+        let mask = (1u32 << bits) - 1;
+        let mut text = String::with_capacity((bytes.len() * 8).div_ceil(bits));
+        let mut acc = 0u32;
+        let mut acc_bits = 0;
+        for &byte in bytes {
+            acc = acc << 8 | byte as u32;
+            acc_bits += 8;
+            while acc_bits >= bits {
+                acc_bits -= bits;
+                text.push(self.symbols[(acc >> acc_bits & mask) as usize] as char);
+            }
+            acc &= (1 << acc_bits) - 1;
+        }
+        if acc_bits > 0 {
+            text.push(self.symbols[(acc << (bits - acc_bits) & mask) as usize] as char);
+        }
+        if self.padding {
+            // the number of symbols that encode a whole number of bytes
+            let group = 8 >> bits.trailing_zeros().min(3);
+            while !text.len().is_multiple_of(group) {
+                text.push('=');
+            }
+        }
+        text
+    }
+
+    /// Encode all data written to the stream as text
+    ///
+    /// The unused bits of a partially written final byte are encoded as 0.
+    pub fn encode_stream<E: Endianness>(&self, stream: BitWriteStream<E>) -> String {
+        let (bytes, bit_len) = stream.finish();
+        let mut bytes = bytes[..bit_len.div_ceil(8)].to_vec();
+        let used = bit_len % 8;
+        if let (Some(last), true) = (bytes.last_mut(), used > 0) {
+            if E::is_le() {
+                *last &= (1 << used) - 1;
+            } else {
+                *last &= !(0xFF >> used);
+            }
+        }
+        self.encode(&bytes)
+    }
+
+    /// Decode the text into a buffer
+    ///
+    /// Padding is optional, the text is accepted both with and without it.
+    ///
+    /// # Errors
+    ///
+    /// - [`ReadError::Malformed`]: the text contains an invalid symbol, has an invalid length or the unused
+    ///   bits of the final symbol aren't 0
+    ///
+    /// [`ReadError::Malformed`]: crate::BitError::Malformed
+    pub fn decode<E: Endianness>(
+        &self,
+        text: &str,
+        endianness: E,
+    ) -> Result<BitReadBuffer<'static, E>> {
+        let bits = self.symbol_bits();
+        let text = text.trim_end_matches('=');
+        let mut bytes = Vec::with_capacity(text.len() * bits / 8);
+        let mut acc = 0u32;
+        let mut acc_bits = 0;
+        for (index, symbol) in text.bytes().enumerate() {
+            let value = self
+                .symbols
+                .iter()
+                .position(|&valid| valid == symbol)
+                .ok_or(BitError::Malformed {
+                    pos: index * bits,
+                    reason: "invalid symbol in encoded text",
+                })?;
+            acc = acc << bits | value as u32;
+            acc_bits += bits;
+            if acc_bits >= 8 {
+                acc_bits -= 8;
+                bytes.push((acc >> acc_bits) as u8);
+            }
+            acc &= (1 << acc_bits) - 1;
+        }
+        let pos = bytes.len() * 8;
+        if acc_bits >= bits {
+            return Err(BitError::Malformed {
+                pos,
+                reason: "invalid length of encoded text",
+            });
+        }
+        if acc != 0 {
+            return Err(BitError::Malformed {
+                pos,
+                reason: "unused bits of encoded text aren't 0",
+            });
+        }
+        Ok(BitReadBuffer::new_owned(bytes, endianness))
+    }
+
+    /// Decode the text into a buffer with the first `bit_len` bits of the decoded data
+    ///
+    /// # Errors
+    ///
+    /// - [`ReadError::Malformed`]: the text contains an invalid symbol, has an invalid length or the unused
+    ///   bits of the final symbol aren't 0
+    /// - [`ReadError::NotEnoughData`]: the text encodes less than `bit_len` bits
+    ///
+    /// [`ReadError::Malformed`]: crate::BitError::Malformed
+    /// [`ReadError::NotEnoughData`]: crate::BitError::NotEnoughData
+    pub fn decode_bits<E: Endianness>(
+        &self,
+        text: &str,
+        bit_len: usize,
+        endianness: E,
+    ) -> Result<BitReadBuffer<'static, E>> {
+        self.decode(text, endianness)?.get_sub_buffer(bit_len)
+    }
+}
@@ -0,0 +1,162 @@
+use crate::{BitWriteStream, Endianness, Result};
+
+/// The C compiler whose bitfield packing rules are followed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum BitfieldAbi {
+    /// Packing as done by gcc and clang (System V ABI)
+    ///
+    /// Bitfields are packed back to back, regardless of their declared type, unless a bitfield would
+    /// straddle the boundary of an allocation unit of its declared type, in which case it's moved to the next unit.
+    Gcc,
+    /// Packing as done by msvc
+    ///
+    /// Consecutive bitfields share an allocation unit only if their declared types have the same size
+    /// and the bitfield fits in the remaining bits of the unit, otherwise a new unit is started.
+    Msvc,
+}
+
+/// Tracks the position of fields in a struct following the bitfield layout of a C compiler
+///
+/// This is used by the `#[bitfield_layout = "gcc"|"msvc"]` derive option, but can also be used to
+/// manually read or write C compatible structs.
+///
+/// The bits are placed in the order they are allocated by the compiler, which matches the bit order of
+/// [`LittleEndian`] streams for little endian targets and of [`BigEndian`] streams for big endian targets.
+///
+/// For every field, first get the number of padding bits before the field, using [`bitfield`] for bitfields
+/// or [`field`] for other members, skip or write the padding, then [`advance`] past the field.
+/// After the last field the trailing padding is given by [`finish`].
+///
+/// # Examples
+///
+/// ```
+/// use bitbuffer::{BitfieldAbi, BitfieldLayout};
+///
+/// // struct { uint8_t a: 3; uint16_t b: 7; uint32_t c; }
+/// let mut layout = BitfieldLayout::new(BitfieldAbi::Msvc);
+/// assert_eq!(layout.bitfield(1, 3), 0);
+/// layout.advance(3);
+/// // different type size, so a new 16 bit unit is started
+/// assert_eq!(layout.bitfield(2, 7), 13);
+/// layout.advance(7);
+/// assert_eq!(layout.field(4), 9);
+/// layout.advance(32);
+/// assert_eq!(layout.finish(), 0);
+/// assert_eq!(layout.offset(), 64);
+/// ```
+///
+/// [`LittleEndian`]: crate::LittleEndian
+/// [`BigEndian`]: crate::BigEndian
+/// [`bitfield`]: BitfieldLayout::bitfield
+/// [`field`]: BitfieldLayout::field
+/// [`advance`]: BitfieldLayout::advance
+/// [`finish`]: BitfieldLayout::finish
+#[derive(Debug, Clone)]
+pub struct BitfieldLayout {
+    abi: BitfieldAbi,
+    offset: usize,
+    /// The start and size of the allocation unit of the previous bitfield, for msvc
+    unit: Option<(usize, usize)>,
+    max_align: usize,
+}
+
+impl BitfieldLayout {
+    /// Start the layout of a new struct
+    pub fn new(abi: BitfieldAbi) -> Self {
+        BitfieldLayout {
+            abi,
+            offset: 0,
+            unit: None,
+            max_align: 8,
+        }
+    }
+
+    /// The current position in bits from the start of the struct
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// Place a bitfield of `width` bits with a declared type of `type_size` bytes, returning the number of padding bits before it
+    ///
+    /// A zero width bitfield ends the current allocation unit.
+    pub fn bitfield(&mut self, type_size: usize, width: usize) -> usize {
+        let unit_bits = type_size * 8;
+        let start = self.offset;
+        match self.abi {
+            BitfieldAbi::Gcc => {
+                let straddles =
+                    width > 0 && self.offset / unit_bits != (self.offset + width - 1) / unit_bits;
+                if width == 0 || straddles {
+                    self.offset = align_up(self.offset, unit_bits);
+                }
+                if width > 0 {
+                    self.max_align = self.max_align.max(unit_bits);
+                }
+            }
+            BitfieldAbi::Msvc => {
+                let fits = match self.unit {
+                    Some((unit_start, size)) => {
+                        size == unit_bits && self.offset + width <= unit_start + size
+                    }
+                    None => false,
+                };
+                if width == 0 {
+                    self.end_unit();
+                } else if !fits {
+                    self.end_unit();
+                    self.offset = align_up(self.offset, unit_bits);
+                    self.unit = Some((self.offset, unit_bits));
+                }
+                self.max_align = self.max_align.max(unit_bits);
+            }
+        }
+        self.offset - start
+    }
+
+    /// Place a non-bitfield member with an alignment of `align` bytes, returning the number of padding bits before it
+    pub fn field(&mut self, align: usize) -> usize {
+        let start = self.offset;
+        self.end_unit();
+        self.offset = align_up(self.offset, align * 8);
+        self.max_align = self.max_align.max(align * 8);
+        self.offset - start
+    }
+
+    /// Move past a field of `bits` bits
+    pub fn advance(&mut self, bits: usize) {
+        self.offset += bits;
+    }
+
+    /// End the struct, returning the number of padding bits needed to align the size of the struct
+    pub fn finish(&mut self) -> usize {
+        let start = self.offset;
+        self.end_unit();
+        self.offset = align_up(self.offset, self.max_align);
+        self.offset - start
+    }
+
+    /// Write `count` bits of padding
+    pub fn write_padding<E: Endianness>(
+        stream: &mut BitWriteStream<E>,
+        count: usize,
+    ) -> Result<()> {
+        let mut remaining = count;
+        while remaining > 0 {
+            let chunk = remaining.min(32);
+            stream.write_int(0u32, chunk)?;
+            remaining -= chunk;
+        }
+        Ok(())
+    }
+
+    fn end_unit(&mut self) {
+        if let Some((start, size)) = self.unit.take() {
+            self.offset = self.offset.max(start + size);
+        }
+    }
+}
+
+fn align_up(offset: usize, align: usize) -> usize {
+    offset.div_ceil(align) * align
+}
@@ -0,0 +1,104 @@
+//! Integration with the [`bitflags`] crate
+//!
+//! Flag types can't implement the traits from this crate directly,
+//! use [`impl_bitflags!`] to implement [`BitReadSized`] and [`BitWriteSized`] for a flags type,
+//! reading and writing the flags as an integer of the given number of bits.
+//!
+//! Unknown bits are retained when reading.
+//!
+//! # Examples
+//!
+//! ```
+//! # use bitbuffer::Result;
+//! use bitbuffer::{impl_bitflags, BitReadBuffer, BitReadStream, BitWriteStream, LittleEndian};
+//! use bitflags::bitflags;
+//!
+//! bitflags! {
+//!     #[derive(Debug, PartialEq)]
+//!     struct Permissions: u8 {
+//!         const READ = 0b001;
+//!         const WRITE = 0b010;
+//!         const EXECUTE = 0b100;
+//!     }
+//! }
+//!
+//! impl_bitflags!(Permissions);
+//!
+//! # fn main() -> Result<()> {
+//! let mut data = Vec::new();
+//! let mut stream = BitWriteStream::new(&mut data, LittleEndian);
+//! stream.write_sized(&(Permissions::READ | Permissions::EXECUTE), 3)?;
+//! assert_eq!(data, [0b101]);
+//!
+//! let mut stream = BitReadStream::new(BitReadBuffer::new(&data, LittleEndian));
+//! let permissions: Permissions = stream.read_sized(3)?;
+//! assert_eq!(permissions, Permissions::READ | Permissions::EXECUTE);
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! [`bitflags`]: https://docs.rs/bitflags
+//! [`impl_bitflags!`]: crate::impl_bitflags
+//! [`BitReadSized`]: crate::BitReadSized
+//! [`BitWriteSized`]: crate::BitWriteSized
+
+use crate::num_traits::{IntoBytes, IsSigned, UncheckedPrimitiveInt};
+use crate::{BitReadStream, BitWriteStream, Endianness, Result};
+use ::bitflags::Flags;
+use num_traits::PrimInt;
+use std::fmt::Debug;
+use std::ops::{BitOrAssign, BitXor};
+
+/// Read a flags type from `size` bits
+pub fn read_flags<F, E>(stream: &mut BitReadStream<E>, size: usize) -> Result<F>
+where
+    F: Flags,
+    F::Bits: PrimInt + BitOrAssign + IsSigned + UncheckedPrimitiveInt,
+    E: Endianness,
+{
+    Ok(F::from_bits_retain(stream.read_int(size)?))
+}
+
+/// Write a flags type as `size` bits
+pub fn write_flags<F, E>(flags: &F, stream: &mut BitWriteStream<E>, size: usize) -> Result<()>
+where
+    F: Flags,
+    F::Bits: PrimInt + BitOrAssign + IsSigned + UncheckedPrimitiveInt + BitXor + IntoBytes + Debug,
+    E: Endianness,
+{
+    stream.write_int(flags.bits(), size)
+}
+
+/// Implement [`BitReadSized`] and [`BitWriteSized`] for a type generated by the `bitflags!` macro
+///
+/// See the [module documentation](crate::bitflags) for details.
+///
+/// [`BitReadSized`]: crate::BitReadSized
+/// [`BitWriteSized`]: crate::BitWriteSized
+#[macro_export]
+macro_rules! impl_bitflags {
+    ($type:ty) => {
+        impl<E: $crate::Endianness> $crate::BitReadSized<'_, E> for $type {
+            #[inline]
+            fn read(stream: &mut $crate::BitReadStream<E>, size: usize) -> $crate::Result<Self> {
+                $crate::bitflags::read_flags(stream, size)
+            }
+
+            #[inline]
+            fn bit_size_sized(size: usize) -> Option<usize> {
+                Some(size)
+            }
+        }
+
+        impl<E: $crate::Endianness> $crate::BitWriteSized<E> for $type {
+            #[inline]
+            fn write_sized(
+                &self,
+                stream: &mut $crate::BitWriteStream<E>,
+                size: usize,
+            ) -> $crate::Result<()> {
+                $crate::bitflags::write_flags(self, stream, size)
+            }
+        }
+    };
+}
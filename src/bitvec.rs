@@ -0,0 +1,154 @@
+//! Interoperability with the [`bitvec`] crate
+//!
+//! Bit buffers and streams can be converted to and from bit slices and vectors of bytes,
+//! where [`LittleEndian`] data uses the [`Lsb0`] bit order and [`BigEndian`] data uses the [`Msb0`] bit order.
+//! With the matching bit order the bytes are laid out the same way, so no repacking is needed.
+//!
+//! Additionally [`BitVec`] implements [`BitReadSized`] and [`BitWrite`], for any storage type and bit order.
+//!
+//! # Examples
+//!
+//! ```
+//! # use bitbuffer::Result;
+//! use bitbuffer::{BitReadBuffer, BitReadStream, LittleEndian};
+//! use bitvec::prelude::*;
+//!
+//! # fn main() -> Result<()> {
+//! let bytes = [0b1011_0101, 0b0110_1010];
+//! let mut stream = BitReadStream::new(BitReadBuffer::new(&bytes, LittleEndian));
+//! stream.skip_bits(4)?;
+//!
+//! let bits = stream.as_bitslice();
+//! assert_eq!(bits, bits![u8, Lsb0; 1, 1, 0, 1, 0, 1, 0, 1, 0, 1, 1, 0]);
+//!
+//! let mut stream = BitReadStream::from(bits.to_bitvec());
+//! assert_eq!(stream.read_int::<u16>(12)?, 0b0110_1010_1011);
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! [`bitvec`]: https://docs.rs/bitvec
+//! [`Lsb0`]: ::bitvec::order::Lsb0
+//! [`Msb0`]: ::bitvec::order::Msb0
+//! [`BitVec`]: ::bitvec::vec::BitVec
+
+use crate::{
+    BigEndian, BitReadBuffer, BitReadSized, BitReadStream, BitWrite, BitWriteStream, Endianness,
+    LittleEndian, Result,
+};
+use ::bitvec::order::{BitOrder, Lsb0, Msb0};
+use ::bitvec::slice::BitSlice;
+use ::bitvec::store::BitStore;
+use ::bitvec::vec::BitVec;
+use std::cmp::min;
+
+macro_rules! impl_bitvec_conversions {
+    ($endianness:ident, $order:ident) => {
+        impl<'a> BitReadBuffer<'a, $endianness> {
+            /// View the bits of the buffer as bit slice
+            pub fn as_bitslice(&self) -> &BitSlice<u8, $order> {
+                &BitSlice::from_slice(self.bytes.as_slice())[..self.bit_len()]
+            }
+        }
+
+        impl<'a> BitReadStream<'a, $endianness> {
+            /// View the remaining bits of the stream as bit slice
+            pub fn as_bitslice(&self) -> &BitSlice<u8, $order> {
+                &self.buffer.as_bitslice()[self.pos..]
+            }
+        }
+
+        impl From<BitVec<u8, $order>> for BitReadBuffer<'static, $endianness> {
+            fn from(mut bits: BitVec<u8, $order>) -> Self {
+                let bit_len = bits.len();
+                bits.force_align();
+                bits.set_uninitialized(false);
+                BitReadBuffer::new_owned(bits.into_vec(), $endianness)
+                    .get_sub_buffer(bit_len)
+                    .expect("bit length is within the converted bytes")
+            }
+        }
+
+        impl From<&BitSlice<u8, $order>> for BitReadBuffer<'static, $endianness> {
+            fn from(bits: &BitSlice<u8, $order>) -> Self {
+                bits.to_bitvec().into()
+            }
+        }
+
+        impl From<BitVec<u8, $order>> for BitReadStream<'static, $endianness> {
+            fn from(bits: BitVec<u8, $order>) -> Self {
+                BitReadStream::new(bits.into())
+            }
+        }
+
+        impl From<&BitSlice<u8, $order>> for BitReadStream<'static, $endianness> {
+            fn from(bits: &BitSlice<u8, $order>) -> Self {
+                BitReadStream::new(bits.into())
+            }
+        }
+
+        impl From<&BitReadBuffer<'_, $endianness>> for BitVec<u8, $order> {
+            fn from(buffer: &BitReadBuffer<'_, $endianness>) -> Self {
+                buffer.as_bitslice().to_bitvec()
+            }
+        }
+
+        impl From<&BitReadStream<'_, $endianness>> for BitVec<u8, $order> {
+            fn from(stream: &BitReadStream<'_, $endianness>) -> Self {
+                stream.as_bitslice().to_bitvec()
+            }
+        }
+    };
+}
+
+impl_bitvec_conversions!(LittleEndian, Lsb0);
+impl_bitvec_conversions!(BigEndian, Msb0);
+
+/// Read `size` bits into a bit vector
+impl<'a, E: Endianness, T: BitStore, O: BitOrder> BitReadSized<'a, E> for BitVec<T, O> {
+    fn read(stream: &mut BitReadStream<'a, E>, size: usize) -> Result<Self> {
+        stream.check_read(size)?;
+        let mut bits = BitVec::with_capacity(size);
+        let mut remaining = size;
+        while remaining > 0 {
+            let count = min(remaining, 32);
+            let chunk: u32 = stream.read_int(count)?;
+            if E::is_le() {
+                bits.extend((0..count).map(|i| chunk & (1 << i) != 0));
+            } else {
+                bits.extend((0..count).rev().map(|i| chunk & (1 << i) != 0));
+            }
+            remaining -= count;
+        }
+        Ok(bits)
+    }
+
+    #[inline]
+    fn bit_size_sized(size: usize) -> Option<usize> {
+        Some(size)
+    }
+}
+
+impl<E: Endianness, T: BitStore, O: BitOrder> BitWrite<E> for BitSlice<T, O> {
+    fn write(&self, stream: &mut BitWriteStream<E>) -> Result<()> {
+        for chunk in self.chunks(32) {
+            let value = chunk
+                .iter()
+                .by_vals()
+                .enumerate()
+                .fold(0u32, |value, (i, bit)| {
+                    let shift = if E::is_le() { i } else { chunk.len() - i - 1 };
+                    value | (bit as u32) << shift
+                });
+            stream.write_int(value, chunk.len())?;
+        }
+        Ok(())
+    }
+}
+
+impl<E: Endianness, T: BitStore, O: BitOrder> BitWrite<E> for BitVec<T, O> {
+    #[inline]
+    fn write(&self, stream: &mut BitWriteStream<E>) -> Result<()> {
+        self.as_bitslice().write(stream)
+    }
+}
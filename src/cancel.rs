@@ -0,0 +1,52 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+/// A token to cooperatively cancel a long running read from another thread
+///
+/// Once a token is installed with [`BitReadStream::set_cancellation_token`], bulk operations like reading
+/// collections, reading bytes and skipping check the token and fail with [`ReadError::Cancelled`] after
+/// [`cancel`](CancellationToken::cancel) is called. Clones of a token share the same state.
+///
+/// Reads of single values are not interrupted, custom bulk reads can check the token with
+/// [`BitReadStream::check_cancelled`].
+///
+/// # Examples
+///
+/// ```
+/// use bitbuffer::{BitError, BitReadBuffer, BitReadStream, CancellationToken, LittleEndian};
+///
+/// let bytes = vec![0; 1024];
+/// let mut stream = BitReadStream::new(BitReadBuffer::new(&bytes, LittleEndian));
+/// let token = CancellationToken::new();
+/// stream.set_cancellation_token(token.clone());
+///
+/// // cancel from a different thread, like the ui thread of an application
+/// std::thread::spawn(move || token.cancel()).join().unwrap();
+///
+/// assert!(matches!(stream.read_sized::<Vec<u16>>(512), Err(BitError::Cancelled)));
+/// ```
+///
+/// [`BitReadStream::set_cancellation_token`]: crate::BitReadStream::set_cancellation_token
+/// [`BitReadStream::check_cancelled`]: crate::BitReadStream::check_cancelled
+/// [`ReadError::Cancelled`]: crate::BitError::Cancelled
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl CancellationToken {
+    /// Create a token that isn't cancelled
+    pub fn new() -> Self {
+        CancellationToken::default()
+    }
+
+    /// Cancel all reads using this token or one of its clones
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    /// Check if the token has been cancelled
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+}
@@ -0,0 +1,240 @@
+//! Reading integers in a const context
+//!
+//! [`ConstBitReader`] reads integers, booleans and bytes using only `const fn`s, which allows parsing fixed headers
+//! of data embedded in a binary (e.g. using `include_bytes!`) at compile time.
+//!
+//! Since trait methods can't be called in a const context, the reader doesn't use [`BitRead`](crate::BitRead) and
+//! only supports integers of up to 64 bits. The bit order of [`new_le`](ConstBitReader::new_le) and [`new_be`](ConstBitReader::new_be)
+//! matches a [`BitReadStream`](crate::BitReadStream) using [`LittleEndian`](crate::LittleEndian) or [`BigEndian`](crate::BigEndian).
+//!
+//! Errors are returned as a [`ConstReadError`], which is `Copy` so it can be handled in a const context,
+//! and can be converted into a [`BitError`] for use outside of one.
+//!
+//! # Examples
+//!
+//! ```
+//! use bitbuffer::const_read::ConstBitReader;
+//!
+//! const ASSET: &[u8] = &[0b1010_0011, 0x01, 0x40, 0xFF];
+//!
+//! const fn parse_header(data: &[u8]) -> (u8, u16) {
+//!     let mut reader = ConstBitReader::new_be(data);
+//!     let version = match reader.read_uint(4) {
+//!         Ok(version) => version as u8,
+//!         Err(_) => panic!("asset too short"),
+//!     };
+//!     let width = match reader.read_uint(12) {
+//!         Ok(width) => width as u16,
+//!         Err(_) => panic!("asset too short"),
+//!     };
+//!     (version, width)
+//! }
+//!
+//! const HEADER: (u8, u16) = parse_header(ASSET);
+//! assert_eq!(HEADER, (0b1010, 0x301));
+//! ```
+
+use crate::BitError;
+
+/// Error returned by [`ConstBitReader`]
+///
+/// This mirrors the matching [`BitError`] variants, but can be used in a const context.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum ConstReadError {
+    /// Too many bits requested to fit in a 64 bit integer
+    TooManyBits {
+        /// The number of bits requested to read
+        requested: usize,
+        /// The number of bits that fit in the requested data type
+        max: usize,
+    },
+    /// Not enough data in the buffer to read all requested bits
+    NotEnoughData {
+        /// The number of bits requested to read
+        requested: usize,
+        /// the number of bits left in the buffer
+        bits_left: usize,
+    },
+}
+
+impl From<ConstReadError> for BitError {
+    fn from(err: ConstReadError) -> Self {
+        match err {
+            ConstReadError::TooManyBits { requested, max } => {
+                BitError::TooManyBits { requested, max }
+            }
+            ConstReadError::NotEnoughData {
+                requested,
+                bits_left,
+            } => BitError::NotEnoughData {
+                requested,
+                bits_left,
+            },
+        }
+    }
+}
+
+/// Reads bits from a byte slice in a const context
+///
+/// See the [module level documentation](self) for details.
+#[derive(Debug, Clone, Copy)]
+pub struct ConstBitReader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+    little_endian: bool,
+}
+
+impl<'a> ConstBitReader<'a> {
+    /// Create a little endian reader for the bytes
+    pub const fn new_le(bytes: &'a [u8]) -> Self {
+        ConstBitReader {
+            bytes,
+            pos: 0,
+            little_endian: true,
+        }
+    }
+
+    /// Create a big endian reader for the bytes
+    pub const fn new_be(bytes: &'a [u8]) -> Self {
+        ConstBitReader {
+            bytes,
+            pos: 0,
+            little_endian: false,
+        }
+    }
+
+    /// The current position in bits
+    pub const fn pos(&self) -> usize {
+        self.pos
+    }
+
+    /// The number of bits left to read
+    pub const fn bits_left(&self) -> usize {
+        self.bytes.len() * 8 - self.pos
+    }
+
+    /// Read an unsigned integer of `bits` bits
+    ///
+    /// # Errors
+    ///
+    /// - [`ConstReadError::TooManyBits`]: more than 64 bits requested
+    /// - [`ConstReadError::NotEnoughData`]: not enough bits left
+    ///
+    /// The reader is not advanced if an error is returned.
+    pub const fn read_uint(&mut self, bits: usize) -> Result<u64, ConstReadError> {
+        if bits > 64 {
+            return Err(ConstReadError::TooManyBits {
+                requested: bits,
+                max: 64,
+            });
+        }
+        if bits > self.bits_left() {
+            return Err(ConstReadError::NotEnoughData {
+                requested: bits,
+                bits_left: self.bits_left(),
+            });
+        }
+
+        let mut value = 0u64;
+        let mut read = 0;
+        while read < bits {
+            let pos = self.pos + read;
+            let offset = pos % 8;
+            let remaining = bits - read;
+            let take = if 8 - offset < remaining {
+                8 - offset
+            } else {
+                remaining
+            };
+            let mask = ((1u16 << take) - 1) as u8;
+            let byte = self.bytes[pos / 8];
+            if self.little_endian {
+                let chunk = (byte >> offset) & mask;
+                value |= (chunk as u64) << read;
+            } else {
+                let chunk = (byte >> (8 - offset - take)) & mask;
+                value = (value << take) | chunk as u64;
+            }
+            read += take;
+        }
+        self.pos += bits;
+        Ok(value)
+    }
+
+    /// Read a signed integer of `bits` bits
+    ///
+    /// # Errors
+    ///
+    /// - [`ConstReadError::TooManyBits`]: more than 64 bits requested
+    /// - [`ConstReadError::NotEnoughData`]: not enough bits left
+    ///
+    /// The reader is not advanced if an error is returned.
+    pub const fn read_sint(&mut self, bits: usize) -> Result<i64, ConstReadError> {
+        let value = match self.read_uint(bits) {
+            Ok(value) => value,
+            Err(err) => return Err(err),
+        };
+        if bits > 0 && bits < 64 && value & (1 << (bits - 1)) != 0 {
+            Ok((value | (u64::MAX << bits)) as i64)
+        } else {
+            Ok(value as i64)
+        }
+    }
+
+    /// Read a single bit as boolean
+    ///
+    /// # Errors
+    ///
+    /// - [`ConstReadError::NotEnoughData`]: no bits left
+    pub const fn read_bool(&mut self) -> Result<bool, ConstReadError> {
+        match self.read_uint(1) {
+            Ok(value) => Ok(value == 1),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Read `N` bytes
+    ///
+    /// # Errors
+    ///
+    /// - [`ConstReadError::NotEnoughData`]: not enough bits left
+    ///
+    /// The reader is not advanced if an error is returned.
+    pub const fn read_bytes<const N: usize>(&mut self) -> Result<[u8; N], ConstReadError> {
+        if N * 8 > self.bits_left() {
+            return Err(ConstReadError::NotEnoughData {
+                requested: N * 8,
+                bits_left: self.bits_left(),
+            });
+        }
+        let mut bytes = [0; N];
+        let mut i = 0;
+        while i < N {
+            bytes[i] = match self.read_uint(8) {
+                Ok(byte) => byte as u8,
+                Err(err) => return Err(err),
+            };
+            i += 1;
+        }
+        Ok(bytes)
+    }
+
+    /// Skip `bits` bits
+    ///
+    /// # Errors
+    ///
+    /// - [`ConstReadError::NotEnoughData`]: not enough bits left
+    ///
+    /// The reader is not advanced if an error is returned.
+    pub const fn skip_bits(&mut self, bits: usize) -> Result<(), ConstReadError> {
+        if bits > self.bits_left() {
+            return Err(ConstReadError::NotEnoughData {
+                requested: bits,
+                bits_left: self.bits_left(),
+            });
+        }
+        self.pos += bits;
+        Ok(())
+    }
+}
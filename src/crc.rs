@@ -0,0 +1,358 @@
+//! Cyclic redundancy checks over bit ranges
+//!
+//! CRCs are described by a [`CrcAlgorithm`] using the usual parameters (width, polynomial, initial value,
+//! input and output reflection and final xor) with presets for common algorithms.
+//!
+//! Besides whole bytes, a [`Crc`] can be calculated over any range of bits from a [`BitReadStream`] or
+//! [`BitReadBuffer`], the bits are processed 8 at a time in the same way as bytes, with the final
+//! partial byte treated as an integer of less than 8 bits.
+//! For byte aligned ranges this gives the same result as calculating the crc over the bytes.
+//!
+//! # Examples
+//!
+//! ```
+//! # use bitbuffer::Result;
+//! use bitbuffer::crc::{Crc, CRC_16_IBM_3740};
+//! use bitbuffer::{BitReadBuffer, BitReadStream, BigEndian};
+//!
+//! # fn main() -> Result<()> {
+//! assert_eq!(CRC_16_IBM_3740.checksum(b"123456789"), 0x29B1);
+//!
+//! let data = [0b1011_0101, 0b0110_1010, 0b1010_1100];
+//! let mut stream = BitReadStream::new(BitReadBuffer::new(&data, BigEndian));
+//! stream.skip_bits(3)?;
+//! let mut crc = Crc::new(&CRC_16_IBM_3740);
+//! crc.update_stream(&mut stream, 13)?;
+//! let checksum = crc.finalize();
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::{BitReadBuffer, BitReadStream, Endianness, Result};
+
+/// Parameters describing a crc algorithm
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CrcAlgorithm {
+    /// The width of the crc in bits, between 1 and 64
+    pub width: usize,
+    /// The generator polynomial, without the top bit
+    pub poly: u64,
+    /// The initial value of the register
+    pub init: u64,
+    /// Whether the bits of every input byte are processed least significant bit first
+    pub reflect_in: bool,
+    /// Whether the final register value is reflected
+    pub reflect_out: bool,
+    /// Value xor-ed with the final register value
+    pub xor_out: u64,
+}
+
+/// CRC-8/SMBUS
+pub const CRC_8: CrcAlgorithm = CrcAlgorithm {
+    width: 8,
+    poly: 0x07,
+    init: 0x00,
+    reflect_in: false,
+    reflect_out: false,
+    xor_out: 0x00,
+};
+
+/// CRC-16/ARC, also known as CRC-16 or CRC-16/IBM
+pub const CRC_16_ARC: CrcAlgorithm = CrcAlgorithm {
+    width: 16,
+    poly: 0x8005,
+    init: 0x0000,
+    reflect_in: true,
+    reflect_out: true,
+    xor_out: 0x0000,
+};
+
+/// CRC-16/IBM-3740, also known as CRC-16/CCITT-FALSE
+pub const CRC_16_IBM_3740: CrcAlgorithm = CrcAlgorithm {
+    width: 16,
+    poly: 0x1021,
+    init: 0xFFFF,
+    reflect_in: false,
+    reflect_out: false,
+    xor_out: 0x0000,
+};
+
+/// CRC-16/KERMIT, also known as CRC-16/CCITT
+pub const CRC_16_KERMIT: CrcAlgorithm = CrcAlgorithm {
+    width: 16,
+    poly: 0x1021,
+    init: 0x0000,
+    reflect_in: true,
+    reflect_out: true,
+    xor_out: 0x0000,
+};
+
+/// CRC-24/BLE, the crc used by Bluetooth Low Energy advertising packets
+///
+/// Connections use the same algorithm with the `init` value negotiated when setting up the connection.
+pub const CRC_24_BLE: CrcAlgorithm = CrcAlgorithm {
+    width: 24,
+    poly: 0x00_065B,
+    init: 0x55_5555,
+    reflect_in: true,
+    reflect_out: true,
+    xor_out: 0x00_0000,
+};
+
+/// The crc used by Mode S transponder messages, as used by ADS-B
+///
+/// Most downlink formats XOR the crc with the aircraft address before sending it, see the [`mode_s`](crate::mode_s)
+/// module.
+pub const CRC_24_MODE_S: CrcAlgorithm = CrcAlgorithm {
+    width: 24,
+    poly: 0xFF_F409,
+    init: 0x00_0000,
+    reflect_in: false,
+    reflect_out: false,
+    xor_out: 0x00_0000,
+};
+
+/// CRC-32/ISO-HDLC, the crc used by zip, png and ethernet
+pub const CRC_32: CrcAlgorithm = CrcAlgorithm {
+    width: 32,
+    poly: 0x04C1_1DB7,
+    init: 0xFFFF_FFFF,
+    reflect_in: true,
+    reflect_out: true,
+    xor_out: 0xFFFF_FFFF,
+};
+
+/// CRC-32/ISCSI, also known as CRC-32C
+pub const CRC_32C: CrcAlgorithm = CrcAlgorithm {
+    width: 32,
+    poly: 0x1EDC_6F41,
+    init: 0xFFFF_FFFF,
+    reflect_in: true,
+    reflect_out: true,
+    xor_out: 0xFFFF_FFFF,
+};
+
+/// CRC-32/MPEG-2, the crc used by MPEG transport stream sections
+pub const CRC_32_MPEG_2: CrcAlgorithm = CrcAlgorithm {
+    width: 32,
+    poly: 0x04C1_1DB7,
+    init: 0xFFFF_FFFF,
+    reflect_in: false,
+    reflect_out: false,
+    xor_out: 0x0000_0000,
+};
+
+/// CRC-64/ECMA-182
+pub const CRC_64_ECMA_182: CrcAlgorithm = CrcAlgorithm {
+    width: 64,
+    poly: 0x42F0_E1EB_A9EA_3693,
+    init: 0x0000_0000_0000_0000,
+    reflect_in: false,
+    reflect_out: false,
+    xor_out: 0x0000_0000_0000_0000,
+};
+
+/// CRC-64/XZ
+pub const CRC_64_XZ: CrcAlgorithm = CrcAlgorithm {
+    width: 64,
+    poly: 0x42F0_E1EB_A9EA_3693,
+    init: 0xFFFF_FFFF_FFFF_FFFF,
+    reflect_in: true,
+    reflect_out: true,
+    xor_out: 0xFFFF_FFFF_FFFF_FFFF,
+};
+
+impl CrcAlgorithm {
+    /// Calculate the crc of a byte slice
+    pub fn checksum(&self, bytes: &[u8]) -> u64 {
+        let mut crc = Crc::new(self);
+        crc.update(bytes);
+        crc.finalize()
+    }
+
+    /// Calculate the crc of `count` bits of the buffer, starting at `position`
+    ///
+    /// # Errors
+    ///
+    /// - [`ReadError::NotEnoughData`]: not enough bits available in the buffer
+    /// - [`ReadError::IndexOutOfBounds`]: the requested position is outside the buffer
+    ///
+    /// [`ReadError::NotEnoughData`]: enum.ReadError.html#variant.NotEnoughData
+    /// [`ReadError::IndexOutOfBounds`]: enum.ReadError.html#variant.IndexOutOfBounds
+    pub fn checksum_bits<E: Endianness>(
+        &self,
+        buffer: &BitReadBuffer<E>,
+        position: usize,
+        count: usize,
+    ) -> Result<u64> {
+        let mut crc = Crc::new(self);
+        crc.update_buffer(buffer, position, count)?;
+        Ok(crc.finalize())
+    }
+
+    fn mask(&self) -> u64 {
+        u64::MAX >> (64 - self.width)
+    }
+}
+
+/// In progress crc calculation
+///
+/// The register is kept aligned to the top of a `u64` so the same table driven implementation
+/// can be used for every width.
+#[derive(Clone)]
+pub struct Crc {
+    algorithm: CrcAlgorithm,
+    table: Box<[u64; 256]>,
+    poly: u64,
+    register: u64,
+}
+
+impl Crc {
+    /// Start a new crc calculation
+    ///
+    /// # Panics
+    ///
+    /// Panics if the width of the algorithm isn't between 1 and 64
+    pub fn new(algorithm: &CrcAlgorithm) -> Self {
+        assert!(
+            algorithm.width > 0 && algorithm.width <= 64,
+            "crc width must be between 1 and 64"
+        );
+        let shift = 64 - algorithm.width;
+        let poly = (algorithm.poly & algorithm.mask()) << shift;
+
+        let mut table = Box::new([0; 256]);
+        for (byte, entry) in table.iter_mut().enumerate() {
+            let mut register = (byte as u64) << 56;
+            for _ in 0..8 {
+                register = if register & (1 << 63) != 0 {
+                    (register << 1) ^ poly
+                } else {
+                    register << 1
+                };
+            }
+            *entry = register;
+        }
+
+        Crc {
+            algorithm: *algorithm,
+            table,
+            poly,
+            register: (algorithm.init & algorithm.mask()) << shift,
+        }
+    }
+
+    /// The algorithm used for this calculation
+    pub fn algorithm(&self) -> &CrcAlgorithm {
+        &self.algorithm
+    }
+
+    /// Restart the calculation
+    pub fn reset(&mut self) {
+        self.register =
+            (self.algorithm.init & self.algorithm.mask()) << (64 - self.algorithm.width);
+    }
+
+    #[inline]
+    fn update_byte(&mut self, byte: u8) {
+        let byte = if self.algorithm.reflect_in {
+            byte.reverse_bits()
+        } else {
+            byte
+        };
+        let index = ((self.register >> 56) as u8 ^ byte) as usize;
+        self.register = (self.register << 8) ^ self.table[index];
+    }
+
+    #[inline]
+    fn update_bit(&mut self, bit: bool) {
+        let top = self.register & (1 << 63) != 0;
+        self.register <<= 1;
+        if top != bit {
+            self.register ^= self.poly;
+        }
+    }
+
+    /// Add bytes to the calculation
+    pub fn update(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.update_byte(*byte);
+        }
+    }
+
+    /// Add the lowest `count` bits of `value` to the calculation
+    ///
+    /// The bits are processed in the same order as the bits of an input byte would be,
+    /// least significant bit first if the input is reflected and most significant bit first otherwise.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `count` is larger than 64
+    pub fn update_bits(&mut self, value: u64, count: usize) {
+        assert!(count <= 64, "can't add more than 64 bits at once");
+        if self.algorithm.reflect_in {
+            for i in 0..count {
+                self.update_bit(value >> i & 1 == 1);
+            }
+        } else {
+            for i in (0..count).rev() {
+                self.update_bit(value >> i & 1 == 1);
+            }
+        }
+    }
+
+    /// Read `count` bits from the stream and add them to the calculation
+    ///
+    /// # Errors
+    ///
+    /// - [`ReadError::NotEnoughData`]: not enough bits available in the stream
+    ///
+    /// [`ReadError::NotEnoughData`]: enum.ReadError.html#variant.NotEnoughData
+    pub fn update_stream<E: Endianness>(
+        &mut self,
+        stream: &mut BitReadStream<E>,
+        count: usize,
+    ) -> Result<()> {
+        let mut bits = stream.read_bits(count)?;
+        let bytes = bits.read_bytes(count / 8)?;
+        self.update(&bytes);
+        let remaining = count % 8;
+        if remaining > 0 {
+            let last: u8 = bits.read_int(remaining)?;
+            self.update_bits(last as u64, remaining);
+        }
+        Ok(())
+    }
+
+    /// Add `count` bits of the buffer starting at `position` to the calculation
+    ///
+    /// # Errors
+    ///
+    /// - [`ReadError::NotEnoughData`]: not enough bits available in the buffer
+    /// - [`ReadError::IndexOutOfBounds`]: the requested position is outside the buffer
+    ///
+    /// [`ReadError::NotEnoughData`]: enum.ReadError.html#variant.NotEnoughData
+    /// [`ReadError::IndexOutOfBounds`]: enum.ReadError.html#variant.IndexOutOfBounds
+    pub fn update_buffer<E: Endianness>(
+        &mut self,
+        buffer: &BitReadBuffer<E>,
+        position: usize,
+        count: usize,
+    ) -> Result<()> {
+        let mut stream = BitReadStream::new(buffer.clone());
+        stream.set_pos(position)?;
+        self.update_stream(&mut stream, count)
+    }
+
+    /// Get the crc of all data added so far
+    pub fn finalize(&self) -> u64 {
+        let width = self.algorithm.width;
+        let register = self.register >> (64 - width);
+        let register = if self.algorithm.reflect_out {
+            register.reverse_bits() >> (64 - width)
+        } else {
+            register
+        };
+        (register ^ self.algorithm.xor_out) & self.algorithm.mask()
+    }
+}
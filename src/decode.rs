@@ -0,0 +1,217 @@
+//! Incremental decoding of data that arrives in pieces
+//!
+//! A [`BitDecode`] implementation describes how to parse a single item from a [`BitReadStream`], without
+//! caring where the data comes from. A [`Decoder`] buffers the bytes that are fed into it and runs the parser
+//! once enough data is available, reporting that more data is needed otherwise.
+//!
+//! Since the decoder doesn't do any IO itself, the same parser can be used for blocking readers,
+//! async streams or data received from an interrupt handler.
+//!
+//! # Examples
+//!
+//! ```
+//! # use bitbuffer::Result;
+//! use bitbuffer::decode::{Decoded, Decoder, ReadDecoder};
+//! use bitbuffer::{BitRead, LittleEndian};
+//!
+//! #[derive(BitRead, Debug, PartialEq)]
+//! struct Sample {
+//!     #[size = 4]
+//!     channel: u8,
+//!     #[size = 12]
+//!     value: u16,
+//! }
+//!
+//! # fn main() -> Result<()> {
+//! let mut decoder = Decoder::new(ReadDecoder::<Sample>::new(), LittleEndian);
+//! decoder.feed(&[0x21]);
+//! assert_eq!(decoder.decode()?, Decoded::NeedMoreData { bits: 8 });
+//!
+//! decoder.feed(&[0x43, 0x65]);
+//! assert_eq!(decoder.decode()?, Decoded::Item(Sample { channel: 1, value: 0x432 }));
+//! assert_eq!(decoder.decode()?, Decoded::NeedMoreData { bits: 8 });
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::{BitError, BitRead, BitReadBuffer, BitReadStream, Endianness, Result};
+use std::fmt::{self, Debug, Formatter};
+use std::marker::PhantomData;
+
+/// A parser for items from a bit stream
+///
+/// This is implemented for [`ReadDecoder`], which reads any [`BitRead`] type,
+/// and for closures taking a [`BitReadStream`].
+pub trait BitDecode<E: Endianness> {
+    /// The type of the decoded items
+    type Item;
+
+    /// Decode a single item from the stream
+    ///
+    /// If the stream doesn't contain enough data for a full item, this should return a
+    /// [`NotEnoughData`](crate::BitError::NotEnoughData) error, the decode will be retried once more data is available.
+    fn decode(&mut self, stream: &mut BitReadStream<'_, E>) -> Result<Self::Item>;
+}
+
+impl<E, T, F> BitDecode<E> for F
+where
+    E: Endianness,
+    F: FnMut(&mut BitReadStream<'_, E>) -> Result<T>,
+{
+    type Item = T;
+
+    fn decode(&mut self, stream: &mut BitReadStream<'_, E>) -> Result<T> {
+        self(stream)
+    }
+}
+
+/// Decode items using their [`BitRead`] implementation
+pub struct ReadDecoder<T> {
+    item: PhantomData<fn() -> T>,
+}
+
+impl<T> ReadDecoder<T> {
+    /// Create a decoder for `T`
+    pub fn new() -> Self {
+        ReadDecoder { item: PhantomData }
+    }
+}
+
+impl<T> Default for ReadDecoder<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Clone for ReadDecoder<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for ReadDecoder<T> {}
+
+impl<T> Debug for ReadDecoder<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "ReadDecoder<{}>", std::any::type_name::<T>())
+    }
+}
+
+impl<E, T> BitDecode<E> for ReadDecoder<T>
+where
+    E: Endianness,
+    T: for<'a> BitRead<'a, E>,
+{
+    type Item = T;
+
+    fn decode(&mut self, stream: &mut BitReadStream<'_, E>) -> Result<T> {
+        stream.read()
+    }
+}
+
+/// The result of a [`Decoder::decode`] call
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Decoded<T> {
+    /// A full item was decoded
+    Item(T),
+    /// Not enough data is buffered to decode an item
+    NeedMoreData {
+        /// The minimum number of bits needed before the decode can make progress
+        bits: usize,
+    },
+}
+
+impl<T> Decoded<T> {
+    /// Get the decoded item, if any
+    pub fn item(self) -> Option<T> {
+        match self {
+            Decoded::Item(item) => Some(item),
+            Decoded::NeedMoreData { .. } => None,
+        }
+    }
+}
+
+/// Buffers incoming bytes and decodes items from them
+///
+/// See the [module level documentation](self) for details.
+pub struct Decoder<D, E> {
+    decoder: D,
+    buffer: Vec<u8>,
+    /// The bit position of the first unconsumed bit in the buffer
+    pos: usize,
+    endianness: PhantomData<fn() -> E>,
+}
+
+impl<D: BitDecode<E>, E: Endianness> Decoder<D, E> {
+    /// Create a decoder using the provided parser
+    pub fn new(decoder: D, _endianness: E) -> Self {
+        Decoder {
+            decoder,
+            buffer: Vec::new(),
+            pos: 0,
+            endianness: PhantomData,
+        }
+    }
+
+    /// Add data to the end of the buffer
+    pub fn feed(&mut self, bytes: &[u8]) {
+        self.buffer.drain(0..self.pos / 8);
+        self.pos %= 8;
+        self.buffer.extend_from_slice(bytes);
+    }
+
+    /// Try to decode the next item from the buffered data
+    ///
+    /// If the parser fails because it runs out of data, nothing is consumed and [`Decoded::NeedMoreData`] is returned.
+    ///
+    /// # Errors
+    ///
+    /// - any error other than [`NotEnoughData`](crate::BitError::NotEnoughData) returned by the parser,
+    ///   the position is not advanced
+    pub fn decode(&mut self) -> Result<Decoded<D::Item>> {
+        let mut stream = BitReadStream::new(BitReadBuffer::new(&self.buffer, E::endianness()));
+        stream.set_pos(self.pos)?;
+        match self.decoder.decode(&mut stream) {
+            Ok(item) => {
+                self.pos = stream.pos();
+                Ok(Decoded::Item(item))
+            }
+            Err(BitError::NotEnoughData {
+                requested,
+                bits_left,
+            }) => Ok(Decoded::NeedMoreData {
+                bits: requested.saturating_sub(bits_left),
+            }),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// The number of buffered bits that haven't been consumed yet
+    pub fn bits_left(&self) -> usize {
+        self.buffer.len() * 8 - self.pos
+    }
+
+    /// Get a reference to the parser
+    pub fn get_ref(&self) -> &D {
+        &self.decoder
+    }
+
+    /// Get a mutable reference to the parser
+    pub fn get_mut(&mut self) -> &mut D {
+        &mut self.decoder
+    }
+
+    /// Get the parser, discarding any buffered data
+    pub fn into_inner(self) -> D {
+        self.decoder
+    }
+}
+
+impl<D: Debug, E> Debug for Decoder<D, E> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Decoder")
+            .field("decoder", &self.decoder)
+            .field("buffered_bits", &(self.buffer.len() * 8 - self.pos))
+            .finish()
+    }
+}
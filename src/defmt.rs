@@ -0,0 +1,192 @@
+use crate::{BitError, ErrorKind};
+use ::defmt::{write, Format, Formatter};
+
+/// Log errors without going trough `core::fmt`
+impl Format for BitError {
+    fn format(&self, f: Formatter) {
+        match self {
+            BitError::TooManyBits { requested, max } => write!(
+                f,
+                "Too many bits requested to fit in the requested data type, requested to read {} bits while only {} fit in the datatype",
+                requested,
+                max
+            ),
+            BitError::NotEnoughData {
+                requested,
+                bits_left,
+            } => write!(
+                f,
+                "Not enough data in the buffer to read all requested bits, requested to read {} bits while only {} bits are left",
+                requested,
+                bits_left
+            ),
+            BitError::IndexOutOfBounds { pos, size } => write!(
+                f,
+                "The requested position is outside the bounds of the stream, requested position {} while the stream or buffer is only {} bits long",
+                pos,
+                size
+            ),
+            BitError::SeekOutOfBounds {
+                current,
+                requested,
+                size,
+            } => write!(
+                f,
+                "Can't seek to position {} from position {}, the stream is only {} bits long",
+                requested,
+                current,
+                size
+            ),
+            BitError::UnmatchedDiscriminant {
+                discriminant,
+                enum_name,
+            } => write!(
+                f,
+                "Unmatched discriminant '{}' found while trying to read enum '{=str}'",
+                discriminant,
+                enum_name.as_str()
+            ),
+            BitError::Utf8Error(err, len) => write!(
+                f,
+                "The read slice of bytes are not valid utf8: invalid utf8 after {} of {} bytes",
+                err.valid_up_to(),
+                len
+            ),
+            BitError::Utf16Error { unit, pos } => write!(
+                f,
+                "Unpaired utf16 surrogate {=u16:#06x} found at position {}",
+                unit,
+                pos
+            ),
+            BitError::StringToLong {
+                string_length,
+                requested_length,
+            } => write!(
+                f,
+                "The string that was requested to be written does not fit in the specified fixed length, string is {} bytes long, while a size of {} has been specified",
+                string_length,
+                requested_length
+            ),
+            BitError::InvalidBitStuffing { pos } => write!(
+                f,
+                "Invalid bit stuffing found at position {}, expected a stuffed bit after a run of identical bits",
+                pos
+            ),
+            BitError::InvalidLineCode { code, pos } => write!(
+                f,
+                "Invalid line code {=u16:#b} found at position {}",
+                code,
+                pos
+            ),
+            BitError::DisparityError { code, pos } => write!(
+                f,
+                "8b/10b code {=u16:#012b} found at position {} doesn't match the running disparity",
+                code,
+                pos
+            ),
+            BitError::InvalidControlSymbol { value } => write!(
+                f,
+                "{=u8:#04x} is not a valid 8b/10b control symbol",
+                value
+            ),
+            BitError::LimitExceeded {
+                limit,
+                requested,
+                max,
+            } => write!(
+                f,
+                "The {} limit was exceeded, requested {} while the limit is {}",
+                limit,
+                requested,
+                max
+            ),
+            BitError::TrailingData { bits_left } => write!(
+                f,
+                "The stream wasn't fully consumed, {} bits are left",
+                bits_left
+            ),
+            BitError::UnterminatedString { max_length } => write!(
+                f,
+                "No null terminator found within the maximum string length of {} bytes",
+                max_length
+            ),
+            BitError::VarIntOverflow { pos, max } => write!(
+                f,
+                "The variable length integer at position {} doesn't fit in {} bits",
+                pos,
+                max
+            ),
+            BitError::CapacityExceeded {
+                requested,
+                capacity,
+            } => write!(
+                f,
+                "Requested {} elements while the collection can only hold {}",
+                requested,
+                capacity
+            ),
+            BitError::LengthMismatch {
+                length,
+                requested_length,
+            } => write!(
+                f,
+                "The collection contains {} elements while a size of {} has been specified",
+                length,
+                requested_length
+            ),
+            BitError::UnresolvedField { name } => write!(
+                f,
+                "The referenced field '{=str}' hasn't been read or doesn't contain a usable integer",
+                name.as_str()
+            ),
+            BitError::UnsupportedType { type_name } => write!(
+                f,
+                "The type '{=str}' can't be read dynamically",
+                type_name.as_str()
+            ),
+            BitError::SizeOverflow {
+                count,
+                element_bits,
+            } => write!(
+                f,
+                "The size of {} elements of {} bits each doesn't fit in a usize",
+                count,
+                element_bits
+            ),
+            BitError::ValueOutOfRange { required, bits } => write!(
+                f,
+                "The value that was requested to be written needs {} bits, while a size of {} bits has been specified",
+                required,
+                bits
+            ),
+            BitError::Malformed { pos, reason } => {
+                write!(f, "Malformed data at position {}: {}", pos, reason)
+            }
+            BitError::UnresolvedOffset { label, reason } => write!(
+                f,
+                "The offset to label {} can't be resolved: {}",
+                label,
+                reason
+            ),
+            BitError::Cancelled => write!(f, "The read was cancelled"),
+            // io errors are only formatted trough `core::fmt`, log the kind instead
+            BitError::Io(err) => write!(f, "IO error: {}", ::defmt::Debug2Format(&err.kind())),
+        }
+    }
+}
+
+impl Format for ErrorKind {
+    fn format(&self, f: Formatter) {
+        let name = match self {
+            ErrorKind::InvalidInput => "InvalidInput",
+            ErrorKind::NotEnoughData => "NotEnoughData",
+            ErrorKind::Overflow => "Overflow",
+            ErrorKind::InvalidData => "InvalidData",
+            ErrorKind::InvalidString => "InvalidString",
+            ErrorKind::LimitExceeded => "LimitExceeded",
+            ErrorKind::Cancelled => "Cancelled",
+            ErrorKind::Io => "Io",
+        };
+        write!(f, "{=str}", name)
+    }
+}
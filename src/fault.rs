@@ -0,0 +1,198 @@
+//! Injecting read failures for testing error handling
+//!
+//! A [`FaultyReadStream`] wraps a [`BitReadStream`] and makes reads fail at a chosen point, which allows testing
+//! the error handling and rollback paths of a parser deterministically.
+//!
+//! - [`fail_at`](FaultyReadStream::fail_at) makes every read that would read past a bit offset fail with
+//!   [`BitError::NotEnoughData`], as if the data ended at that offset.
+//!   Since the limit applies to the stream that is passed to the read, this also works for parsers that read many
+//!   values from the stream.
+//! - [`fail_after`](FaultyReadStream::fail_after) makes every read after the first `n` reads fail with an
+//!   [`BitError::Io`] error.
+//!
+//! The position is not reset after a failed read, so tests can verify that a parser restores the position itself.
+//! The `read_*` methods of the wrapper restore the position just like the ones on [`BitReadStream`].
+//!
+//! # Examples
+//!
+//! ```
+//! # use bitbuffer::Result;
+//! use bitbuffer::fault::FaultyReadStream;
+//! use bitbuffer::{BitError, BitReadBuffer, BitReadStream, LittleEndian};
+//!
+//! fn parse_header(stream: &mut BitReadStream<LittleEndian>) -> Result<(u8, u16)> {
+//!     let start = stream.pos();
+//!     let result = (|| Ok((stream.read()?, stream.read()?)))();
+//!     if result.is_err() {
+//!         stream.set_pos(start)?;
+//!     }
+//!     result
+//! }
+//!
+//! # fn main() -> Result<()> {
+//! let bytes = [1, 2, 3, 4];
+//! let mut stream = FaultyReadStream::new(BitReadStream::new(BitReadBuffer::new(&bytes, LittleEndian)));
+//! stream.fail_at(20);
+//! let result = stream.with(parse_header);
+//! assert!(matches!(result, Err(BitError::NotEnoughData { .. })));
+//! assert_eq!(stream.pos(), 0);
+//!
+//! stream.clear_faults();
+//! assert_eq!(stream.with(parse_header)?, (1, 0x0302));
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! [`BitError::NotEnoughData`]: crate::BitError::NotEnoughData
+//! [`BitError::Io`]: crate::BitError::Io
+
+use crate::num_traits::{IsSigned, UncheckedPrimitiveInt};
+use crate::{BitError, BitRead, BitReadSized, BitReadStream, Endianness, Result};
+use num_traits::PrimInt;
+use std::borrow::Cow;
+use std::fmt::{self, Debug, Formatter};
+use std::io;
+use std::ops::{BitOrAssign, Deref};
+
+/// A read stream that fails reads at a configured offset or after a configured number of reads
+///
+/// See the [module level documentation](self) for details.
+///
+/// The wrapped stream can be accessed through [`Deref`] for methods that don't move the position.
+pub struct FaultyReadStream<'a, E: Endianness> {
+    stream: BitReadStream<'a, E>,
+    fail_at: Option<usize>,
+    fail_after: Option<usize>,
+    reads: usize,
+}
+
+impl<'a, E: Endianness> FaultyReadStream<'a, E> {
+    /// Wrap a stream without injecting any failures
+    pub fn new(stream: BitReadStream<'a, E>) -> Self {
+        FaultyReadStream {
+            stream,
+            fail_at: None,
+            fail_after: None,
+            reads: 0,
+        }
+    }
+
+    /// Fail all reads that would read past `offset`, as if the data ended there
+    ///
+    /// The offset is relative to the start of the stream, like [`BitReadStream::pos`].
+    pub fn fail_at(&mut self, offset: usize) {
+        self.fail_at = Some(offset);
+    }
+
+    /// Fail all reads after the first `reads` reads
+    ///
+    /// Reads made before calling this are counted, see [`reads`](Self::reads).
+    pub fn fail_after(&mut self, reads: usize) {
+        self.fail_after = Some(reads);
+    }
+
+    /// Remove all configured failures
+    pub fn clear_faults(&mut self) {
+        self.fail_at = None;
+        self.fail_after = None;
+    }
+
+    /// The number of reads attempted, including failed reads
+    pub fn reads(&self) -> usize {
+        self.reads
+    }
+
+    /// Perform an arbitrary operation on the wrapped stream as a single read
+    ///
+    /// If an offset is set with [`fail_at`](Self::fail_at), `f` is called with a stream that ends at that offset.
+    ///
+    /// # Errors
+    ///
+    /// - [`ReadError::Io`]: more reads than allowed by [`fail_after`](Self::fail_after), `f` isn't called
+    /// - any error returned by `f`
+    ///
+    /// The position is left where `f` left it, even if `f` fails.
+    ///
+    /// [`ReadError::Io`]: crate::BitError::Io
+    pub fn with<T, F>(&mut self, f: F) -> Result<T>
+    where
+        F: FnOnce(&mut BitReadStream<'a, E>) -> Result<T>,
+    {
+        self.reads += 1;
+        if self.fail_after.is_some_and(|reads| self.reads > reads) {
+            return Err(BitError::Io(io::Error::other(format!(
+                "injected failure at read {}",
+                self.reads
+            ))));
+        }
+
+        let mut stream = self.stream.truncated(self.fail_at.unwrap_or(usize::MAX));
+        let result = f(&mut stream);
+        self.stream.pos = stream.pos;
+        result
+    }
+
+    /// Read a value based on the provided type, see [`BitReadStream::read`]
+    pub fn read<T: BitRead<'a, E>>(&mut self) -> Result<T> {
+        self.with(|stream| stream.read())
+    }
+
+    /// Read a value based on the provided type and size, see [`BitReadStream::read_sized`]
+    pub fn read_sized<T: BitReadSized<'a, E>>(&mut self, size: usize) -> Result<T> {
+        self.with(|stream| stream.read_sized(size))
+    }
+
+    /// Read a single bit as boolean, see [`BitReadStream::read_bool`]
+    pub fn read_bool(&mut self) -> Result<bool> {
+        self.with(|stream| stream.read_bool())
+    }
+
+    /// Read a sequence of bits as integer, see [`BitReadStream::read_int`]
+    pub fn read_int<T>(&mut self, count: usize) -> Result<T>
+    where
+        T: PrimInt + BitOrAssign + IsSigned + UncheckedPrimitiveInt,
+    {
+        self.with(|stream| stream.read_int(count))
+    }
+
+    /// Read a series of bytes, see [`BitReadStream::read_bytes`]
+    pub fn read_bytes(&mut self, byte_count: usize) -> Result<Cow<'a, [u8]>> {
+        self.with(|stream| stream.read_bytes(byte_count))
+    }
+
+    /// Skip a number of bits, see [`BitReadStream::skip_bits`]
+    pub fn skip_bits(&mut self, count: usize) -> Result<()> {
+        self.with(|stream| stream.skip_bits(count))
+    }
+
+    /// Set the position of the stream, see [`BitReadStream::set_pos`]
+    ///
+    /// This doesn't count as a read.
+    pub fn set_pos(&mut self, pos: usize) -> Result<()> {
+        self.stream.set_pos(pos)
+    }
+
+    /// Get the wrapped stream
+    pub fn into_inner(self) -> BitReadStream<'a, E> {
+        self.stream
+    }
+}
+
+impl<'a, E: Endianness> Deref for FaultyReadStream<'a, E> {
+    type Target = BitReadStream<'a, E>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.stream
+    }
+}
+
+impl<E: Endianness + Debug> Debug for FaultyReadStream<'_, E> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FaultyReadStream")
+            .field("stream", &self.stream)
+            .field("fail_at", &self.fail_at)
+            .field("fail_after", &self.fail_after)
+            .field("reads", &self.reads)
+            .finish()
+    }
+}
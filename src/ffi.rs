@@ -0,0 +1,428 @@
+//! A C api for reading and writing bits
+//!
+//! The api is handle based, [`bitbuffer_reader_new`] and [`bitbuffer_writer_new`] create a handle that has to be
+//! freed with [`bitbuffer_reader_free`] and [`bitbuffer_writer_free`]. All functions that can fail return a
+//! [`BitbufferStatus`] and write their result to an out pointer, a description of a status can be retrieved
+//! with [`bitbuffer_status_message`].
+//!
+//! The functions are meant to be exported from a `cdylib` or `staticlib` with headers generated by `cbindgen`.
+//!
+//! ```c
+//! BitbufferReader *reader = bitbuffer_reader_new(data, len, true);
+//! uint64_t version;
+//! if (bitbuffer_reader_read_uint(reader, 4, &version) != BITBUFFER_STATUS_OK) {
+//!     // handle error
+//! }
+//! bitbuffer_reader_free(reader);
+//! ```
+//!
+//! Like the rust api, reads and writes that fail don't advance the reader or modify the writer.
+
+use crate::owned::{with_owned_stream, with_owned_writer, OwnedStream, OwnedWriter};
+use crate::{BitError, BitReadStream, Endianness, ErrorKind, Result};
+use std::os::raw::c_char;
+use std::ptr;
+use std::slice;
+
+/// The result of a fallible function
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BitbufferStatus {
+    /// The function succeeded
+    Ok = 0,
+    /// A required pointer was null
+    NullPointer = 1,
+    /// The arguments don't make sense for the operation, like reading more than 64 bits
+    InvalidInput = 2,
+    /// Not enough data left to read
+    NotEnoughData = 3,
+    /// A value doesn't fit in the space it's written to
+    Overflow = 4,
+    /// The data doesn't follow the expected format
+    InvalidData = 5,
+    /// A string isn't valid in its encoding
+    InvalidString = 6,
+    /// A limit on the size of the read data was exceeded
+    LimitExceeded = 7,
+    /// An io error
+    Io = 8,
+    /// The read was cancelled
+    Cancelled = 9,
+}
+
+impl From<&BitError> for BitbufferStatus {
+    fn from(err: &BitError) -> Self {
+        match err.kind() {
+            ErrorKind::InvalidInput => BitbufferStatus::InvalidInput,
+            ErrorKind::NotEnoughData => BitbufferStatus::NotEnoughData,
+            ErrorKind::Overflow => BitbufferStatus::Overflow,
+            ErrorKind::InvalidData => BitbufferStatus::InvalidData,
+            ErrorKind::InvalidString => BitbufferStatus::InvalidString,
+            ErrorKind::LimitExceeded => BitbufferStatus::LimitExceeded,
+            ErrorKind::Cancelled => BitbufferStatus::Cancelled,
+            ErrorKind::Io => BitbufferStatus::Io,
+        }
+    }
+}
+
+fn status(result: Result<()>) -> BitbufferStatus {
+    match result {
+        Ok(()) => BitbufferStatus::Ok,
+        Err(e) => BitbufferStatus::from(&e),
+    }
+}
+
+/// Get a description of a status as null terminated string
+///
+/// The returned string is static and must not be freed.
+#[no_mangle]
+pub extern "C" fn bitbuffer_status_message(status: BitbufferStatus) -> *const c_char {
+    let message: &'static [u8] = match status {
+        BitbufferStatus::Ok => b"ok\0",
+        BitbufferStatus::NullPointer => b"a required pointer was null\0",
+        BitbufferStatus::InvalidInput => b"invalid input\0",
+        BitbufferStatus::NotEnoughData => b"not enough data\0",
+        BitbufferStatus::Overflow => b"value overflows the available space\0",
+        BitbufferStatus::InvalidData => b"invalid data\0",
+        BitbufferStatus::InvalidString => b"invalid string\0",
+        BitbufferStatus::LimitExceeded => b"read limit exceeded\0",
+        BitbufferStatus::Io => b"io error\0",
+        BitbufferStatus::Cancelled => b"read cancelled\0",
+    };
+    message.as_ptr() as *const c_char
+}
+
+/// A handle for reading bits from a copy of a byte buffer
+pub struct BitbufferReader {
+    stream: OwnedStream,
+}
+
+/// Create a reader for a copy of `len` bytes from `data`
+///
+/// Returns null if `data` is null while `len` is not zero.
+///
+/// # Safety
+///
+/// `data` must point to at least `len` readable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn bitbuffer_reader_new(
+    data: *const u8,
+    len: usize,
+    little_endian: bool,
+) -> *mut BitbufferReader {
+    let bytes = match (data.is_null(), len) {
+        (_, 0) => Vec::new(),
+        (true, _) => return ptr::null_mut(),
+        (false, len) => slice::from_raw_parts(data, len).to_vec(),
+    };
+    let stream = OwnedStream::new(bytes, little_endian);
+    Box::into_raw(Box::new(BitbufferReader { stream }))
+}
+
+/// Free a reader
+///
+/// # Safety
+///
+/// `reader` must be null or a reader created by [`bitbuffer_reader_new`] that hasn't been freed yet.
+#[no_mangle]
+pub unsafe extern "C" fn bitbuffer_reader_free(reader: *mut BitbufferReader) {
+    if !reader.is_null() {
+        drop(Box::from_raw(reader));
+    }
+}
+
+/// Read an unsigned integer of `bits` bits, up to 64
+///
+/// # Safety
+///
+/// `reader` must be a valid reader and `out` must be valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn bitbuffer_reader_read_uint(
+    reader: *mut BitbufferReader,
+    bits: usize,
+    out: *mut u64,
+) -> BitbufferStatus {
+    let (reader, out) = match (reader.as_mut(), out.as_mut()) {
+        (Some(reader), Some(out)) => (reader, out),
+        _ => return BitbufferStatus::NullPointer,
+    };
+    status(
+        with_owned_stream!(&mut reader.stream, stream => read_int(stream, bits).map(|value| *out = value)),
+    )
+}
+
+/// Read a signed integer of `bits` bits, up to 64
+///
+/// # Safety
+///
+/// `reader` must be a valid reader and `out` must be valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn bitbuffer_reader_read_sint(
+    reader: *mut BitbufferReader,
+    bits: usize,
+    out: *mut i64,
+) -> BitbufferStatus {
+    let (reader, out) = match (reader.as_mut(), out.as_mut()) {
+        (Some(reader), Some(out)) => (reader, out),
+        _ => return BitbufferStatus::NullPointer,
+    };
+    status(
+        with_owned_stream!(&mut reader.stream, stream => read_int(stream, bits).map(|value| *out = value)),
+    )
+}
+
+fn read_int<E: Endianness, T>(stream: &mut BitReadStream<E>, bits: usize) -> Result<T>
+where
+    T: num_traits::PrimInt
+        + std::ops::BitOrAssign
+        + crate::num_traits::IsSigned
+        + crate::num_traits::UncheckedPrimitiveInt,
+{
+    match bits {
+        0 => Ok(T::zero()),
+        bits => stream.read_int(bits),
+    }
+}
+
+/// Read a single bit as boolean
+///
+/// # Safety
+///
+/// `reader` must be a valid reader and `out` must be valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn bitbuffer_reader_read_bool(
+    reader: *mut BitbufferReader,
+    out: *mut bool,
+) -> BitbufferStatus {
+    let (reader, out) = match (reader.as_mut(), out.as_mut()) {
+        (Some(reader), Some(out)) => (reader, out),
+        _ => return BitbufferStatus::NullPointer,
+    };
+    status(
+        with_owned_stream!(&mut reader.stream, stream => stream.read_bool().map(|value| *out = value)),
+    )
+}
+
+/// Read `count` bytes into `out`
+///
+/// # Safety
+///
+/// `reader` must be a valid reader and `out` must be valid for writing `count` bytes.
+#[no_mangle]
+pub unsafe extern "C" fn bitbuffer_reader_read_bytes(
+    reader: *mut BitbufferReader,
+    out: *mut u8,
+    count: usize,
+) -> BitbufferStatus {
+    let reader = match reader.as_mut() {
+        Some(reader) if count == 0 || !out.is_null() => reader,
+        _ => return BitbufferStatus::NullPointer,
+    };
+    status(
+        with_owned_stream!(&mut reader.stream, stream => stream.read_bytes(count).map(|bytes| {
+            if count > 0 {
+                slice::from_raw_parts_mut(out, count).copy_from_slice(&bytes);
+            }
+        })),
+    )
+}
+
+/// Skip `bits` bits
+///
+/// # Safety
+///
+/// `reader` must be a valid reader.
+#[no_mangle]
+pub unsafe extern "C" fn bitbuffer_reader_skip_bits(
+    reader: *mut BitbufferReader,
+    bits: usize,
+) -> BitbufferStatus {
+    match reader.as_mut() {
+        Some(reader) => {
+            status(with_owned_stream!(&mut reader.stream, stream => stream.skip_bits(bits)))
+        }
+        None => BitbufferStatus::NullPointer,
+    }
+}
+
+/// Set the position of the reader in bits
+///
+/// # Safety
+///
+/// `reader` must be a valid reader.
+#[no_mangle]
+pub unsafe extern "C" fn bitbuffer_reader_set_pos(
+    reader: *mut BitbufferReader,
+    pos: usize,
+) -> BitbufferStatus {
+    match reader.as_mut() {
+        Some(reader) => {
+            status(with_owned_stream!(&mut reader.stream, stream => stream.set_pos(pos)))
+        }
+        None => BitbufferStatus::NullPointer,
+    }
+}
+
+/// The position of the reader in bits, 0 if `reader` is null
+///
+/// # Safety
+///
+/// `reader` must be null or a valid reader.
+#[no_mangle]
+pub unsafe extern "C" fn bitbuffer_reader_pos(reader: *mut BitbufferReader) -> usize {
+    match reader.as_mut() {
+        Some(reader) => with_owned_stream!(&mut reader.stream, stream => stream.pos()),
+        None => 0,
+    }
+}
+
+/// The number of bits left in the reader, 0 if `reader` is null
+///
+/// # Safety
+///
+/// `reader` must be null or a valid reader.
+#[no_mangle]
+pub unsafe extern "C" fn bitbuffer_reader_bits_left(reader: *mut BitbufferReader) -> usize {
+    match reader.as_mut() {
+        Some(reader) => with_owned_stream!(&mut reader.stream, stream => stream.bits_left()),
+        None => 0,
+    }
+}
+
+/// A handle for writing bits into a growing byte buffer
+pub struct BitbufferWriter {
+    writer: OwnedWriter,
+}
+
+/// Create an empty writer
+#[no_mangle]
+pub extern "C" fn bitbuffer_writer_new(little_endian: bool) -> *mut BitbufferWriter {
+    Box::into_raw(Box::new(BitbufferWriter {
+        writer: OwnedWriter::new(little_endian),
+    }))
+}
+
+/// Free a writer
+///
+/// # Safety
+///
+/// `writer` must be null or a writer created by [`bitbuffer_writer_new`] that hasn't been freed yet.
+#[no_mangle]
+pub unsafe extern "C" fn bitbuffer_writer_free(writer: *mut BitbufferWriter) {
+    if !writer.is_null() {
+        drop(Box::from_raw(writer));
+    }
+}
+
+/// Write the lower `bits` bits of an unsigned integer, up to 64
+///
+/// # Safety
+///
+/// `writer` must be a valid writer.
+#[no_mangle]
+pub unsafe extern "C" fn bitbuffer_writer_write_uint(
+    writer: *mut BitbufferWriter,
+    value: u64,
+    bits: usize,
+) -> BitbufferStatus {
+    match writer.as_mut() {
+        Some(_) if bits == 0 => BitbufferStatus::Ok,
+        Some(writer) => {
+            status(with_owned_writer!(writer.writer, stream => stream.write_int(value, bits)))
+        }
+        None => BitbufferStatus::NullPointer,
+    }
+}
+
+/// Write the lower `bits` bits of a signed integer, up to 64
+///
+/// # Safety
+///
+/// `writer` must be a valid writer.
+#[no_mangle]
+pub unsafe extern "C" fn bitbuffer_writer_write_sint(
+    writer: *mut BitbufferWriter,
+    value: i64,
+    bits: usize,
+) -> BitbufferStatus {
+    match writer.as_mut() {
+        Some(_) if bits == 0 => BitbufferStatus::Ok,
+        Some(writer) => {
+            status(with_owned_writer!(writer.writer, stream => stream.write_int(value, bits)))
+        }
+        None => BitbufferStatus::NullPointer,
+    }
+}
+
+/// Write a boolean as a single bit
+///
+/// # Safety
+///
+/// `writer` must be a valid writer.
+#[no_mangle]
+pub unsafe extern "C" fn bitbuffer_writer_write_bool(
+    writer: *mut BitbufferWriter,
+    value: bool,
+) -> BitbufferStatus {
+    match writer.as_mut() {
+        Some(writer) => {
+            status(with_owned_writer!(writer.writer, stream => stream.write_bool(value)))
+        }
+        None => BitbufferStatus::NullPointer,
+    }
+}
+
+/// Write `len` bytes from `data`
+///
+/// # Safety
+///
+/// `writer` must be a valid writer and `data` must point to at least `len` readable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn bitbuffer_writer_write_bytes(
+    writer: *mut BitbufferWriter,
+    data: *const u8,
+    len: usize,
+) -> BitbufferStatus {
+    let writer = match writer.as_mut() {
+        Some(writer) if len == 0 || !data.is_null() => writer,
+        _ => return BitbufferStatus::NullPointer,
+    };
+    if len == 0 {
+        return BitbufferStatus::Ok;
+    }
+    let bytes = slice::from_raw_parts(data, len);
+    status(with_owned_writer!(writer.writer, stream => stream.write_bytes(bytes)))
+}
+
+/// The number of bits written, 0 if `writer` is null
+///
+/// # Safety
+///
+/// `writer` must be null or a valid writer.
+#[no_mangle]
+pub unsafe extern "C" fn bitbuffer_writer_bit_len(writer: *const BitbufferWriter) -> usize {
+    writer.as_ref().map_or(0, |writer| writer.writer.bit_len())
+}
+
+/// Get the written data, the number of bytes is written to `len`
+///
+/// The last byte is padded with zero bits if the number of written bits isn't a multiple of 8.
+/// The returned pointer is only valid until the next write to, or the freeing of, the writer.
+/// Returns null if `writer` or `len` is null.
+///
+/// # Safety
+///
+/// `writer` must be null or a valid writer and `len` must be null or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn bitbuffer_writer_data(
+    writer: *const BitbufferWriter,
+    len: *mut usize,
+) -> *const u8 {
+    match (writer.as_ref(), len.as_mut()) {
+        (Some(writer), Some(len)) => {
+            *len = writer.writer.data().len();
+            writer.writer.data().as_ptr()
+        }
+        _ => ptr::null(),
+    }
+}
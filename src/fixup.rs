@@ -0,0 +1,194 @@
+use crate::{BitError, Result};
+use std::mem::take;
+
+/// A position in a write stream that offsets can refer to before it's known
+///
+/// Labels are created with [`BitWriteStream::create_label`], offsets to the label can be written with
+/// [`BitWriteStream::write_offset`] before or after the label is placed with [`BitWriteStream::place_label`].
+/// The offsets are filled in once the stream is finished.
+///
+/// A label can only be used with the stream that created it.
+///
+/// [`BitWriteStream::create_label`]: crate::BitWriteStream::create_label
+/// [`BitWriteStream::write_offset`]: crate::BitWriteStream::write_offset
+/// [`BitWriteStream::place_label`]: crate::BitWriteStream::place_label
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Label(usize);
+
+impl Label {
+    /// The index of the label in the order of creation
+    pub fn index(&self) -> usize {
+        self.0
+    }
+}
+
+/// The positions of labels as placed during the previous pass of [`BitWriteStream::write_with_offsets`]
+///
+/// [`BitWriteStream::write_with_offsets`]: crate::BitWriteStream::write_with_offsets
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LabelPositions {
+    positions: Vec<Option<usize>>,
+}
+
+impl LabelPositions {
+    /// The position of a label in bits from the start of the stream, or `0` if the position isn't known yet
+    pub fn get(&self, label: Label) -> usize {
+        self.position(label).unwrap_or_default()
+    }
+
+    /// The position of a label in bits from the start of the stream, if it was placed in the previous pass
+    pub fn position(&self, label: Label) -> Option<usize> {
+        self.positions.get(label.0).copied().flatten()
+    }
+
+    /// The index of the first label with a different position in `other`
+    pub(crate) fn first_moved(&self, other: &LabelPositions) -> usize {
+        (0..)
+            .find(|&i| self.positions.get(i) != other.positions.get(i))
+            .unwrap_or_default()
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Fixup {
+    pos: usize,
+    bits: usize,
+    label: Label,
+    unit_bits: usize,
+}
+
+/// The labels and unresolved offsets of a write stream
+///
+/// All positions are stored relative to the outermost stream, `origin` is the position of the first bit of
+/// the stream owning the fixups, which is only non-zero for temporary streams created while writing a section.
+#[derive(Debug, Default)]
+pub(crate) struct Fixups {
+    origin: usize,
+    labels: Vec<Option<usize>>,
+    pending: Vec<Fixup>,
+}
+
+impl Fixups {
+    pub fn create_label(&mut self) -> Label {
+        self.labels.push(None);
+        Label(self.labels.len() - 1)
+    }
+
+    pub fn place(&mut self, label: Label, pos: usize) -> Result<()> {
+        let origin = self.origin;
+        match self.labels.get_mut(label.0) {
+            Some(placed) => {
+                *placed = Some(origin + pos);
+                Ok(())
+            }
+            None => Err(unknown_label(label)),
+        }
+    }
+
+    pub fn position(&self, label: Label) -> Option<usize> {
+        self.labels.get(label.0).copied().flatten()
+    }
+
+    pub fn add(&mut self, label: Label, pos: usize, bits: usize, unit_bits: usize) -> Result<()> {
+        if label.0 >= self.labels.len() {
+            return Err(unknown_label(label));
+        }
+        self.pending.push(Fixup {
+            pos: self.origin + pos,
+            bits,
+            label,
+            unit_bits,
+        });
+        Ok(())
+    }
+
+    /// Forget the labels and offsets after `bit_len`
+    pub fn truncate(&mut self, bit_len: usize) {
+        let end = self.origin + bit_len;
+        self.pending.retain(|fixup| fixup.pos + fixup.bits <= end);
+        for placed in self.labels.iter_mut() {
+            if placed.is_some_and(|pos| pos > end) {
+                *placed = None;
+            }
+        }
+    }
+
+    /// Forget all labels created after the first `count` labels
+    pub fn truncate_labels(&mut self, count: usize) {
+        self.labels.truncate(count);
+    }
+
+    pub fn label_count(&self) -> usize {
+        self.labels.len()
+    }
+
+    pub fn positions(&self) -> LabelPositions {
+        LabelPositions {
+            positions: self.labels.clone(),
+        }
+    }
+
+    /// Copy the labels to the fixups of a temporary stream starting at `start`, without any offsets
+    pub fn copy_labels(&self, start: usize) -> Fixups {
+        Fixups {
+            origin: self.origin + start,
+            labels: self.labels.clone(),
+            pending: Vec::new(),
+        }
+    }
+
+    /// Move the labels and offsets to the fixups of a temporary stream starting at `start`
+    pub fn split_off(&mut self, start: usize) -> Fixups {
+        Fixups {
+            origin: self.origin + start,
+            labels: take(&mut self.labels),
+            pending: take(&mut self.pending),
+        }
+    }
+
+    /// Take back the labels and offsets from a temporary stream
+    pub fn join(&mut self, mut other: Fixups) {
+        self.labels = take(&mut other.labels);
+        self.pending = take(&mut other.pending);
+    }
+
+    /// Calculate the values of all pending offsets inside this stream, as `(position, bits, value)`
+    ///
+    /// Nothing is removed if any of the offsets can't be resolved.
+    pub fn resolve(&mut self) -> Result<Vec<(usize, usize, usize)>> {
+        let mut resolved = Vec::new();
+        for fixup in self.pending.iter().filter(|fixup| fixup.pos >= self.origin) {
+            let target = self
+                .position(fixup.label)
+                .ok_or_else(|| unresolved(fixup.label, "the label is never placed"))?;
+            if target % fixup.unit_bits != 0 {
+                return Err(unresolved(
+                    fixup.label,
+                    "the label isn't aligned to the offset unit",
+                ));
+            }
+            let value = target / fixup.unit_bits;
+            if fixup.bits < usize::BITS as usize && value >> fixup.bits != 0 {
+                return Err(unresolved(
+                    fixup.label,
+                    "the offset doesn't fit in the reserved bits",
+                ));
+            }
+            resolved.push((fixup.pos - self.origin, fixup.bits, value));
+        }
+        let origin = self.origin;
+        self.pending.retain(|fixup| fixup.pos < origin);
+        Ok(resolved)
+    }
+}
+
+fn unknown_label(label: Label) -> BitError {
+    unresolved(label, "the label is created by a different stream")
+}
+
+fn unresolved(label: Label, reason: &'static str) -> BitError {
+    BitError::UnresolvedOffset {
+        label: label.0,
+        reason,
+    }
+}
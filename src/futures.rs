@@ -0,0 +1,391 @@
+//! Reading items from an [`AsyncRead`] and writing them to an [`AsyncWrite`]
+//!
+//! [`DecodeStream`] reads data from an [`AsyncRead`] into an internal [`Decoder`] and yields the decoded items
+//! as a [`Stream`]. Items don't have to be byte aligned and can span multiple reads.
+//!
+//! [`AsyncBitWriter`] buffers bit-packed items and writes them to an [`AsyncWrite`],
+//! using watermarks to limit the amount of buffered data.
+//!
+//! # Examples
+//!
+//! ```
+//! # use bitbuffer::Result;
+//! use bitbuffer::futures::read_items;
+//! use bitbuffer::{BitRead, BigEndian};
+//! use futures::executor::block_on;
+//! use futures::io::Cursor;
+//! use futures::TryStreamExt;
+//!
+//! #[derive(BitRead, Debug, PartialEq)]
+//! struct Record {
+//!     #[size = 4]
+//!     kind: u8,
+//!     #[size = 8]
+//!     value: u8,
+//! }
+//!
+//! # fn main() -> Result<()> {
+//! let reader = Cursor::new([0x12, 0x34, 0x56]);
+//! let records: Vec<Record> = block_on(read_items(reader, BigEndian).try_collect())?;
+//! assert_eq!(records, [Record { kind: 1, value: 0x23 }, Record { kind: 4, value: 0x56 }]);
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! [`AsyncRead`]: futures_io::AsyncRead
+//! [`AsyncWrite`]: futures_io::AsyncWrite
+//! [`Stream`]: futures_core::Stream
+
+use crate::decode::{BitDecode, Decoded, Decoder, ReadDecoder};
+use crate::{
+    BitError, BitRead, BitReadBuffer, BitReadStream, BitWrite, BitWriteStream, Endianness, Result,
+};
+use ::futures_core::Stream;
+use ::futures_io::{AsyncRead, AsyncWrite};
+use std::fmt::{self, Debug, Formatter};
+use std::future::poll_fn;
+use std::io;
+use std::marker::PhantomData;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+const READ_SIZE: usize = 4096;
+const DEFAULT_HIGH_WATERMARK: usize = 64 * 1024;
+const DEFAULT_LOW_WATERMARK: usize = 16 * 1024;
+
+/// Create a stream of `T` read from `reader`
+pub fn read_items<T, R, E>(reader: R, endianness: E) -> DecodeStream<R, ReadDecoder<T>, E>
+where
+    T: for<'a> BitRead<'a, E>,
+    R: AsyncRead + Unpin,
+    E: Endianness,
+{
+    DecodeStream::new(reader, ReadDecoder::new(), endianness)
+}
+
+/// A stream of items decoded from an [`AsyncRead`](futures_io::AsyncRead)
+///
+/// Once the reader reaches the end of its data, any remaining bits that can't be decoded are ignored
+/// if they don't make up a full byte, so padding at the end of the data is not an error.
+/// A full byte or more of leftover data results in a [`NotEnoughData`](crate::BitError::NotEnoughData) error.
+///
+/// The stream ends after yielding an error.
+pub struct DecodeStream<R, D, E> {
+    reader: R,
+    decoder: Decoder<D, E>,
+    buffer: Box<[u8]>,
+    done: bool,
+}
+
+impl<R, D, E> DecodeStream<R, D, E>
+where
+    R: AsyncRead + Unpin,
+    D: BitDecode<E>,
+    E: Endianness,
+{
+    /// Create a stream that decodes items from `reader` using `decoder`
+    pub fn new(reader: R, decoder: D, endianness: E) -> Self {
+        DecodeStream {
+            reader,
+            decoder: Decoder::new(decoder, endianness),
+            buffer: vec![0; READ_SIZE].into_boxed_slice(),
+            done: false,
+        }
+    }
+
+    /// Get a reference to the reader
+    pub fn get_ref(&self) -> &R {
+        &self.reader
+    }
+
+    /// Get the reader, discarding any buffered data
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+
+    fn poll_item(&mut self, cx: &mut Context<'_>) -> Poll<Option<Result<D::Item>>> {
+        loop {
+            let needed = match self.decoder.decode()? {
+                Decoded::Item(item) => return Poll::Ready(Some(Ok(item))),
+                Decoded::NeedMoreData { bits } => bits,
+            };
+            match Pin::new(&mut self.reader).poll_read(cx, &mut self.buffer) {
+                Poll::Pending => return Poll::Pending,
+                Poll::Ready(Ok(0)) => {
+                    let bits_left = self.decoder.bits_left();
+                    return Poll::Ready(if bits_left < 8 {
+                        None
+                    } else {
+                        Some(Err(BitError::NotEnoughData {
+                            requested: bits_left + needed,
+                            bits_left,
+                        }))
+                    });
+                }
+                Poll::Ready(Ok(read)) => self.decoder.feed(&self.buffer[..read]),
+                Poll::Ready(Err(e)) if e.kind() == io::ErrorKind::Interrupted => {}
+                Poll::Ready(Err(e)) => return Poll::Ready(Some(Err(e.into()))),
+            }
+        }
+    }
+}
+
+impl<R, D, E> Stream for DecodeStream<R, D, E>
+where
+    R: AsyncRead + Unpin,
+    D: BitDecode<E> + Unpin,
+    E: Endianness,
+{
+    type Item = Result<D::Item>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        if this.done {
+            return Poll::Ready(None);
+        }
+        let result = this.poll_item(cx);
+        if let Poll::Ready(None | Some(Err(_))) = result {
+            this.done = true;
+        }
+        result
+    }
+}
+
+impl<R: Debug, D: Debug, E> Debug for DecodeStream<R, D, E> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DecodeStream")
+            .field("reader", &self.reader)
+            .field("decoder", &self.decoder)
+            .field("done", &self.done)
+            .finish()
+    }
+}
+
+/// Writes bit-packed items to an [`AsyncWrite`](futures_io::AsyncWrite)
+///
+/// Written items are buffered until they're sent to the writer with [`flush_ready`](Self::flush_ready),
+/// [`flush`](Self::flush) or [`finish`](Self::finish). Items don't have to be byte aligned, only full bytes are
+/// sent to the writer until the writer is finished.
+///
+/// To prevent the buffer from growing without bounds when items are produced faster than the writer accepts them,
+/// producers should await [`flush_ready`](Self::flush_ready) between writes. Once the buffer reaches the high watermark,
+/// `flush_ready` waits until the buffer is drained to the low watermark.
+///
+/// # Examples
+///
+/// ```
+/// # use bitbuffer::Result;
+/// use bitbuffer::futures::AsyncBitWriter;
+/// use bitbuffer::LittleEndian;
+/// use futures::executor::block_on;
+///
+/// # fn main() -> Result<()> {
+/// block_on(async {
+///     let mut writer = AsyncBitWriter::new(Vec::new(), LittleEndian).with_watermarks(2, 1);
+///     for value in 0..6u8 {
+///         writer.flush_ready().await?;
+///         writer.write_with(|stream| stream.write_int(value, 4))?;
+///     }
+///     assert_eq!(writer.get_ref(), &[0x10, 0x32]);
+///     assert_eq!(writer.finish().await?, [0x10, 0x32, 0x54]);
+///     Ok(())
+/// })
+/// # }
+/// ```
+pub struct AsyncBitWriter<W, E> {
+    writer: W,
+    /// The full bytes that haven't been sent to the writer yet
+    buffer: Vec<u8>,
+    /// The bits of the last incomplete byte
+    partial: u8,
+    partial_bits: usize,
+    high_watermark: usize,
+    low_watermark: usize,
+    endianness: PhantomData<fn() -> E>,
+}
+
+impl<W, E> AsyncBitWriter<W, E>
+where
+    W: AsyncWrite + Unpin,
+    E: Endianness,
+{
+    /// Create a writer that writes to `writer`, with a high watermark of 64KiB and a low watermark of 16KiB
+    pub fn new(writer: W, _endianness: E) -> Self {
+        AsyncBitWriter {
+            writer,
+            buffer: Vec::new(),
+            partial: 0,
+            partial_bits: 0,
+            high_watermark: DEFAULT_HIGH_WATERMARK,
+            low_watermark: DEFAULT_LOW_WATERMARK,
+            endianness: PhantomData,
+        }
+    }
+
+    /// Set the high and low watermarks in bytes
+    ///
+    /// # Panics
+    ///
+    /// Panics if `low` is larger than `high`
+    pub fn with_watermarks(mut self, high: usize, low: usize) -> Self {
+        assert!(
+            low <= high,
+            "the low watermark can't be larger than the high watermark"
+        );
+        self.high_watermark = high;
+        self.low_watermark = low;
+        self
+    }
+
+    /// The high watermark in bytes
+    pub fn high_watermark(&self) -> usize {
+        self.high_watermark
+    }
+
+    /// The low watermark in bytes
+    pub fn low_watermark(&self) -> usize {
+        self.low_watermark
+    }
+
+    /// The number of full bytes that are buffered
+    pub fn buffered_bytes(&self) -> usize {
+        self.buffer.len()
+    }
+
+    /// Whether the buffer is below the high watermark
+    pub fn is_ready(&self) -> bool {
+        self.buffer.len() < self.high_watermark
+    }
+
+    /// Write a value to the buffer
+    ///
+    /// # Errors
+    ///
+    /// - any error returned by the [`BitWrite`] implementation, nothing is written
+    pub fn write<T: BitWrite<E>>(&mut self, value: &T) -> Result<()> {
+        self.write_with(|stream| stream.write(value))
+    }
+
+    /// Write to the buffer using a stream
+    ///
+    /// # Errors
+    ///
+    /// - any error returned by `f`, nothing is written
+    pub fn write_with<F>(&mut self, f: F) -> Result<()>
+    where
+        F: FnOnce(&mut BitWriteStream<'_, E>) -> Result<()>,
+    {
+        let len = self.buffer.len();
+        let mut stream = BitWriteStream::new(&mut self.buffer, E::endianness());
+        let result = match self.partial_bits {
+            0 => f(&mut stream),
+            bits => stream
+                .write_int(self.partial, bits)
+                .and_then(|_| f(&mut stream)),
+        };
+        let bit_len = stream.bit_len();
+        if let Err(e) = result {
+            self.buffer.truncate(len);
+            return Err(e);
+        }
+
+        let partial_bits = bit_len % 8;
+        if partial_bits > 0 {
+            let last = self.buffer.len() - 1;
+            let mut last_byte =
+                BitReadStream::new(BitReadBuffer::new(&self.buffer[last..], E::endianness()));
+            self.partial = last_byte.read_int(partial_bits)?;
+            self.buffer.truncate(last);
+        }
+        self.partial_bits = partial_bits;
+        Ok(())
+    }
+
+    /// Wait until the writer is ready to accept more data
+    ///
+    /// If the buffer has reached the high watermark, data is sent to the writer until the buffer is drained to the
+    /// low watermark. Otherwise this completes without sending any data.
+    ///
+    /// # Errors
+    ///
+    /// - [`ReadError::Io`]: an error from the underlying writer
+    ///
+    /// [`ReadError::Io`]: crate::BitError::Io
+    pub async fn flush_ready(&mut self) -> Result<()> {
+        if self.is_ready() {
+            return Ok(());
+        }
+        let low_watermark = self.low_watermark;
+        poll_fn(|cx| self.poll_drain(cx, low_watermark)).await
+    }
+
+    /// Send all full bytes to the writer and flush it
+    ///
+    /// # Errors
+    ///
+    /// - [`ReadError::Io`]: an error from the underlying writer
+    ///
+    /// [`ReadError::Io`]: crate::BitError::Io
+    pub async fn flush(&mut self) -> Result<()> {
+        poll_fn(|cx| self.poll_drain(cx, 0)).await?;
+        poll_fn(|cx| Pin::new(&mut self.writer).poll_flush(cx)).await?;
+        Ok(())
+    }
+
+    /// Pad the last byte with zero bits, send all data to the writer and flush it
+    ///
+    /// # Errors
+    ///
+    /// - [`ReadError::Io`]: an error from the underlying writer
+    ///
+    /// [`ReadError::Io`]: crate::BitError::Io
+    pub async fn finish(mut self) -> Result<W> {
+        if self.partial_bits > 0 {
+            self.write_with(|stream| stream.write_int(0u8, 8 - stream.bit_len() % 8))?;
+        }
+        self.flush().await?;
+        Ok(self.writer)
+    }
+
+    /// Get a reference to the writer
+    pub fn get_ref(&self) -> &W {
+        &self.writer
+    }
+
+    /// Get the writer, discarding any buffered data
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+
+    /// Send data to the writer until at most `target` bytes are buffered
+    fn poll_drain(&mut self, cx: &mut Context<'_>, target: usize) -> Poll<Result<()>> {
+        while self.buffer.len() > target {
+            match Pin::new(&mut self.writer).poll_write(cx, &self.buffer) {
+                Poll::Pending => return Poll::Pending,
+                Poll::Ready(Ok(0)) => {
+                    return Poll::Ready(Err(io::Error::from(io::ErrorKind::WriteZero).into()))
+                }
+                Poll::Ready(Ok(written)) => {
+                    self.buffer.drain(0..written);
+                }
+                Poll::Ready(Err(e)) if e.kind() == io::ErrorKind::Interrupted => {}
+                Poll::Ready(Err(e)) => return Poll::Ready(Err(e.into())),
+            }
+        }
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl<W: Debug, E> Debug for AsyncBitWriter<W, E> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AsyncBitWriter")
+            .field("writer", &self.writer)
+            .field(
+                "buffered_bits",
+                &(self.buffer.len() * 8 + self.partial_bits),
+            )
+            .field("high_watermark", &self.high_watermark)
+            .field("low_watermark", &self.low_watermark)
+            .finish()
+    }
+}
@@ -0,0 +1,199 @@
+//! GPS LNAV navigation message words and parity
+//!
+//! The legacy GPS navigation message is sent as 300 bit subframes of ten 30 bit words, most significant bit first.
+//! Every word contains 24 data bits and 6 parity bits computed with the (32, 26) Hamming code from IS-GPS-200,
+//! which also covers the last two bits of the previous word. When the last bit of the previous word is set the data
+//! bits are transmitted inverted, which also lets receivers resolve the polarity of the demodulated signal.
+//!
+//! # Examples
+//!
+//! ```
+//! # use bitbuffer::Result;
+//! use bitbuffer::gps::{read_word, write_word};
+//! use bitbuffer::{BigEndian, BitReadBuffer, BitReadStream, BitWriteStream};
+//!
+//! # fn main() -> Result<()> {
+//! let mut data = Vec::new();
+//! let mut stream = BitWriteStream::new(&mut data, BigEndian);
+//! let first = write_word(&mut stream, 0x8B_1234, 0)?;
+//! write_word(&mut stream, 0xAB_CDEF, first.raw)?;
+//! drop(stream);
+//!
+//! let mut stream = BitReadStream::new(BitReadBuffer::new(&data, BigEndian));
+//! let first = read_word(&mut stream, 0)?;
+//! assert_eq!(first.data, 0x8B_1234);
+//! let second = read_word(&mut stream, first.raw)?;
+//! assert_eq!(second.data, 0xAB_CDEF);
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::{BigEndian, BitError, BitReadStream, BitWriteStream, Result};
+
+/// The number of bits in a word
+pub const WORD_BITS: usize = 30;
+
+/// The number of words in a subframe
+pub const SUBFRAME_WORDS: usize = 10;
+
+/// The 8 bit preamble at the start of every subframe
+pub const PREAMBLE: u32 = 0x8B;
+
+/// The data bits included in each parity bit, with `d1` as the most significant of the 24 data bits,
+/// and whether the parity bit includes bit 30 of the previous word instead of bit 29
+const PARITY_MASKS: [(u32, bool); 6] = [
+    (0xEC_7CD2, false),
+    (0x76_3E69, true),
+    (0xBB_1F34, false),
+    (0x5D_8F9A, true),
+    (0xAE_C7CD, true),
+    (0x2D_EA27, false),
+];
+
+const DATA_MASK: u32 = 0xFF_FFFF;
+
+/// A navigation message word
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Word {
+    /// The 30 transmitted bits of the word, including the parity
+    pub raw: u32,
+    /// The 24 data bits, with the polarity corrected
+    pub data: u32,
+}
+
+/// Compute the 6 parity bits for 24 data bits
+///
+/// `previous` is the previous transmitted word, only its last two bits are used.
+pub fn parity(data: u32, previous: u32) -> u32 {
+    let d29 = previous >> 1 & 1;
+    let d30 = previous & 1;
+    PARITY_MASKS.iter().fold(0, |parity, &(mask, uses_d30)| {
+        let previous_bit = if uses_d30 { d30 } else { d29 };
+        parity << 1 | ((data & mask).count_ones() & 1 ^ previous_bit)
+    })
+}
+
+/// Encode 24 data bits into the 30 bits to transmit
+///
+/// `previous` is the previous transmitted word, only its last two bits are used.
+pub fn encode_word(data: u32, previous: u32) -> Word {
+    let data = data & DATA_MASK;
+    let transmitted = if previous & 1 == 1 {
+        !data & DATA_MASK
+    } else {
+        data
+    };
+    Word {
+        raw: transmitted << 6 | parity(data, previous),
+        data,
+    }
+}
+
+/// Decode 30 transmitted bits, returns `None` if the parity doesn't match
+///
+/// `previous` is the previous transmitted word, only its last two bits are used.
+pub fn decode_word(raw: u32, previous: u32) -> Option<Word> {
+    let transmitted = raw >> 6 & DATA_MASK;
+    let data = if previous & 1 == 1 {
+        !transmitted & DATA_MASK
+    } else {
+        transmitted
+    };
+    if parity(data, previous) == raw & 0x3F {
+        Some(Word { raw, data })
+    } else {
+        None
+    }
+}
+
+/// Read a 30 bit word, checking the parity
+///
+/// `previous` is the previous transmitted word, only its last two bits are used.
+///
+/// # Errors
+///
+/// - [`ReadError::NotEnoughData`]: not enough bits available in the stream
+/// - [`ReadError::Malformed`]: the parity doesn't match
+///
+/// [`ReadError::NotEnoughData`]: crate::BitError::NotEnoughData
+/// [`ReadError::Malformed`]: crate::BitError::Malformed
+pub fn read_word(stream: &mut BitReadStream<BigEndian>, previous: u32) -> Result<Word> {
+    let pos = stream.pos();
+    stream.restore_on_error(|stream| {
+        let raw = stream.read_int(WORD_BITS)?;
+        decode_word(raw, previous).ok_or(BitError::Malformed {
+            pos,
+            reason: "the word parity doesn't match",
+        })
+    })
+}
+
+/// Write 24 data bits as a 30 bit word, returns the written word
+///
+/// `previous` is the previous transmitted word, only its last two bits are used.
+pub fn write_word(
+    stream: &mut BitWriteStream<BigEndian>,
+    data: u32,
+    previous: u32,
+) -> Result<Word> {
+    let word = encode_word(data, previous);
+    stream.write_int(word.raw, WORD_BITS)?;
+    Ok(word)
+}
+
+/// The data bits of a subframe
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Subframe {
+    /// The 24 data bits of each word, starting with the telemetry and handover words
+    pub words: [u32; SUBFRAME_WORDS],
+}
+
+impl Subframe {
+    /// The 3 bit subframe id from the handover word
+    pub fn id(&self) -> u8 {
+        (self.words[1] >> 2 & 0b111) as u8
+    }
+
+    /// The 17 bit truncated time of week count from the handover word, the time of the start of the next subframe
+    /// in units of 6 seconds
+    pub fn tow(&self) -> u32 {
+        self.words[1] >> 7
+    }
+}
+
+/// Read a subframe, checking the preamble and the parity of every word
+///
+/// Subframes are designed so that the last two bits of the last word are zero,
+/// the polarity of the subframe is detected from the preamble.
+///
+/// # Errors
+///
+/// - [`ReadError::NotEnoughData`]: not enough bits available in the stream
+/// - [`ReadError::Malformed`]: the subframe doesn't start with the preamble or a word parity doesn't match
+///
+/// [`ReadError::NotEnoughData`]: crate::BitError::NotEnoughData
+/// [`ReadError::Malformed`]: crate::BitError::Malformed
+pub fn read_subframe(stream: &mut BitReadStream<BigEndian>) -> Result<Subframe> {
+    let pos = stream.pos();
+    stream.restore_on_error(|stream| {
+        let mut previous = match stream.clone().read_int::<u32>(8)? {
+            PREAMBLE => 0,
+            preamble if preamble == !PREAMBLE & 0xFF => 0b11,
+            _ => {
+                return Err(BitError::Malformed {
+                    pos,
+                    reason: "the subframe doesn't start with the preamble",
+                })
+            }
+        };
+        let mut words = [0; SUBFRAME_WORDS];
+        for data in words.iter_mut() {
+            let word = read_word(stream, previous)?;
+            *data = word.data;
+            previous = word.raw;
+        }
+        Ok(Subframe { words })
+    })
+}
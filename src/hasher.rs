@@ -0,0 +1,117 @@
+use crate::Endianness;
+use std::marker::PhantomData;
+
+/// Trait for checksums and hashes that can be attached to a [`BitWriteStream`]
+///
+/// The hasher is fed the bytes of the stream as they are written, with the last partial byte
+/// padded with zeros the same way as in the written data.
+///
+/// Implementations are provided for [`Crc`], [`HasherAdapter`] for any [`std::hash::Hasher`]
+/// and, with the `digest` feature, for any [`digest::Digest`].
+///
+/// [`BitWriteStream`]: crate::BitWriteStream
+/// [`Crc`]: crate::crc::Crc
+/// [`digest::Digest`]: https://docs.rs/digest/latest/digest/trait.Digest.html
+pub trait BitHasher {
+    /// Feed bytes to the hasher
+    fn update(&mut self, bytes: &[u8]);
+
+    /// Get the digest of all bytes fed to the hasher
+    fn finalize(self: Box<Self>) -> Vec<u8>;
+}
+
+impl BitHasher for crate::crc::Crc {
+    fn update(&mut self, bytes: &[u8]) {
+        crate::crc::Crc::update(self, bytes)
+    }
+
+    /// The crc as big endian bytes, using the minimal number of bytes to fit the crc width
+    fn finalize(self: Box<Self>) -> Vec<u8> {
+        let byte_count = self.algorithm().width.div_ceil(8);
+        crate::crc::Crc::finalize(&self).to_be_bytes()[8 - byte_count..].to_vec()
+    }
+}
+
+/// Adapter to use any [`std::hash::Hasher`] as [`BitHasher`]
+///
+/// The digest is the result of [`Hasher::finish`] as big endian bytes.
+///
+/// [`Hasher::finish`]: std::hash::Hasher::finish
+#[derive(Debug, Clone, Default)]
+pub struct HasherAdapter<H: std::hash::Hasher>(pub H);
+
+impl<H: std::hash::Hasher> BitHasher for HasherAdapter<H> {
+    fn update(&mut self, bytes: &[u8]) {
+        self.0.write(bytes)
+    }
+
+    fn finalize(self: Box<Self>) -> Vec<u8> {
+        self.0.finish().to_be_bytes().to_vec()
+    }
+}
+
+#[cfg(feature = "digest")]
+impl<D: digest::Digest> BitHasher for D {
+    fn update(&mut self, bytes: &[u8]) {
+        digest::Digest::update(self, bytes)
+    }
+
+    fn finalize(self: Box<Self>) -> Vec<u8> {
+        digest::Digest::finalize(*self).to_vec()
+    }
+}
+
+/// Collects pushed bits into whole bytes before feeding them to the hasher
+pub(crate) struct HashHook<E: Endianness> {
+    hasher: Box<dyn BitHasher + Send>,
+    /// Bits that don't form a full byte yet, stored the same way as in the output byte
+    partial: u8,
+    partial_bits: usize,
+    endianness: PhantomData<E>,
+}
+
+impl<E: Endianness> HashHook<E> {
+    pub fn new(hasher: Box<dyn BitHasher + Send>) -> Self {
+        HashHook {
+            hasher,
+            partial: 0,
+            partial_bits: 0,
+            endianness: PhantomData,
+        }
+    }
+
+    /// Feed up to an usize worth of bits
+    pub fn push_bits(&mut self, bits: usize, count: usize) {
+        if count == 0 {
+            return;
+        }
+        let bits = (bits as u128) & (u128::MAX >> (128 - count));
+        let total = self.partial_bits + count;
+        let full_bytes = total / 8;
+        let mut bytes = [0; 16];
+
+        if E::is_le() {
+            let merged = self.partial as u128 | bits << self.partial_bits;
+            let merged_bytes = merged.to_le_bytes();
+            bytes[..full_bytes].copy_from_slice(&merged_bytes[..full_bytes]);
+            self.partial = merged_bytes[full_bytes];
+        } else {
+            // align the merged bits to the top of the u128
+            let merged = (self.partial as u128) << 120 | bits << (128 - total);
+            let merged_bytes = merged.to_be_bytes();
+            bytes[..full_bytes].copy_from_slice(&merged_bytes[..full_bytes]);
+            self.partial = merged_bytes[full_bytes];
+        }
+
+        self.partial_bits = total % 8;
+        self.hasher.update(&bytes[..full_bytes]);
+    }
+
+    /// Feed the last partial byte, if any, and get the digest
+    pub fn finalize(mut self) -> Vec<u8> {
+        if self.partial_bits > 0 {
+            self.hasher.update(&[self.partial]);
+        }
+        self.hasher.finalize()
+    }
+}
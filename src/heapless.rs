@@ -0,0 +1,99 @@
+use crate::{
+    BitError, BitRead, BitReadSized, BitReadStream, BitWrite, BitWriteSized, BitWriteStream,
+    Endianness, ReadLimit, Result,
+};
+use ::heapless::{String, Vec};
+
+/// Read `T` `size` times into a `heapless::Vec`
+impl<'a, E: Endianness, T: BitRead<'a, E>, const CAP: usize> BitReadSized<'a, E> for Vec<T, CAP> {
+    fn read(stream: &mut BitReadStream<'a, E>, size: usize) -> Result<Self> {
+        if size > CAP {
+            return Err(BitError::CapacityExceeded {
+                requested: size,
+                capacity: CAP,
+            });
+        }
+        stream.check_limit(ReadLimit::Elements, size)?;
+        let mut vec = Vec::new();
+        for _ in 0..size {
+            // the capacity is checked above
+            let _ = vec.push(stream.read()?);
+        }
+        Ok(vec)
+    }
+
+    #[inline]
+    fn bit_size_sized(size: usize) -> Option<usize> {
+        T::bit_size().map(|element_size| size * element_size)
+    }
+}
+
+impl<E: Endianness, T: BitWrite<E>, const CAP: usize> BitWrite<E> for Vec<T, CAP> {
+    #[inline]
+    fn write(&self, stream: &mut BitWriteStream<E>) -> Result<()> {
+        for element in self.iter() {
+            stream.write(element)?;
+        }
+        Ok(())
+    }
+}
+
+/// Write all elements, `len` has to match the number of elements
+impl<E: Endianness, T: BitWrite<E>, const CAP: usize> BitWriteSized<E> for Vec<T, CAP> {
+    #[inline]
+    fn write_sized(&self, stream: &mut BitWriteStream<E>, len: usize) -> Result<()> {
+        if len != self.len() {
+            return Err(BitError::LengthMismatch {
+                length: self.len(),
+                requested_length: len,
+            });
+        }
+        stream.write(self)
+    }
+}
+
+fn to_heapless_string<const CAP: usize>(string: &str) -> Result<String<CAP>> {
+    let mut result = String::new();
+    result
+        .push_str(string)
+        .map_err(|_| BitError::CapacityExceeded {
+            requested: string.len(),
+            capacity: CAP,
+        })?;
+    Ok(result)
+}
+
+/// Read a null terminated string
+impl<E: Endianness, const CAP: usize> BitRead<'_, E> for String<CAP> {
+    #[inline]
+    fn read(stream: &mut BitReadStream<E>) -> Result<Self> {
+        to_heapless_string(&stream.read_string(None)?)
+    }
+}
+
+/// Read a string of `size` bytes, trailing null bytes are removed
+impl<E: Endianness, const CAP: usize> BitReadSized<'_, E> for String<CAP> {
+    #[inline]
+    fn read(stream: &mut BitReadStream<E>, size: usize) -> Result<Self> {
+        to_heapless_string(&stream.read_string(Some(size))?)
+    }
+
+    #[inline]
+    fn bit_size_sized(size: usize) -> Option<usize> {
+        Some(8 * size)
+    }
+}
+
+impl<E: Endianness, const CAP: usize> BitWrite<E> for String<CAP> {
+    #[inline]
+    fn write(&self, stream: &mut BitWriteStream<E>) -> Result<()> {
+        stream.write_string(self, None)
+    }
+}
+
+impl<E: Endianness, const CAP: usize> BitWriteSized<E> for String<CAP> {
+    #[inline]
+    fn write_sized(&self, stream: &mut BitWriteStream<E>, len: usize) -> Result<()> {
+        stream.write_string(self, Some(len))
+    }
+}
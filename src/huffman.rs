@@ -0,0 +1,96 @@
+use crate::{BitError, BitReadStream, Endianness, Result};
+
+/// The longest supported code in bits
+const MAX_CODE_LENGTH: usize = 16;
+
+/// A canonical huffman code, as used by deflate and jpeg
+///
+/// Codes are read one bit at a time starting with the most significant bit of the code,
+/// see [`read_huffman`](BitReadStream::read_huffman).
+///
+/// # Examples
+///
+/// ```
+/// # use bitbuffer::Result;
+/// use bitbuffer::{BitReadBuffer, BitReadStream, HuffmanTable, LittleEndian};
+///
+/// # fn main() -> Result<()> {
+/// // symbol 0 has code `0`, symbol 1 has code `10` and symbol 2 has code `11`
+/// let table = HuffmanTable::from_lengths(&[1, 2, 2]).unwrap();
+/// let mut stream = BitReadStream::new(BitReadBuffer::new(&[0b0001_1010], LittleEndian));
+/// assert_eq!(stream.read_huffman(&table)?, 0);
+/// assert_eq!(stream.read_huffman(&table)?, 1);
+/// assert_eq!(stream.read_huffman(&table)?, 2);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HuffmanTable {
+    /// The number of codes of every length
+    counts: [u16; MAX_CODE_LENGTH + 1],
+    /// The symbols ordered by code
+    symbols: Vec<u16>,
+}
+
+impl HuffmanTable {
+    /// Create the canonical code from the code length of every symbol, symbols with a length of 0 are unused
+    ///
+    /// Returns `None` if a length is longer than 16 bits or there are more codes of a length than possible.
+    /// Incomplete codes are allowed, reading one of the unused codes results in an error.
+    pub fn from_lengths(lengths: &[u8]) -> Option<Self> {
+        let mut counts = [0u16; MAX_CODE_LENGTH + 1];
+        for &length in lengths {
+            *counts.get_mut(length as usize)? += 1;
+        }
+        counts[0] = 0;
+
+        let mut left = 1i32;
+        for &count in &counts[1..] {
+            left = (left << 1) - count as i32;
+            if left < 0 {
+                return None;
+            }
+        }
+
+        let mut offsets = [0usize; MAX_CODE_LENGTH + 1];
+        for length in 1..MAX_CODE_LENGTH {
+            offsets[length + 1] = offsets[length] + counts[length] as usize;
+        }
+        let mut symbols = vec![0; counts.iter().map(|&count| count as usize).sum()];
+        for (symbol, &length) in lengths.iter().enumerate() {
+            if length != 0 {
+                symbols[offsets[length as usize]] = symbol as u16;
+                offsets[length as usize] += 1;
+            }
+        }
+        Some(HuffmanTable { counts, symbols })
+    }
+
+    pub(crate) fn decode<E: Endianness>(&self, stream: &mut BitReadStream<E>) -> Result<u16> {
+        let pos = stream.pos();
+        stream.restore_on_error(|stream| {
+            // the first code of the current length and the index of its symbol
+            let mut code = 0;
+            let mut first = 0;
+            let mut index = 0;
+            for &count in &self.counts[1..] {
+                code |= stream.read_bool()? as usize;
+                let count = count as usize;
+                if code < first + count {
+                    return Ok(self.symbols[index + code - first]);
+                }
+                index += count;
+                if index == self.symbols.len() {
+                    // no longer codes left
+                    break;
+                }
+                first = (first + count) << 1;
+                code <<= 1;
+            }
+            Err(BitError::Malformed {
+                pos,
+                reason: "invalid huffman code",
+            })
+        })
+    }
+}
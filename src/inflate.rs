@@ -0,0 +1,279 @@
+//! Deflate and zlib decompression
+//!
+//! A small decoder for the deflate format from RFC 1951 and the zlib wrapper from RFC 1950, as used by png and
+//! http compression. It is built only from public primitives of this crate, least significant bit first reads
+//! with [`LittleEndian`], [`read_huffman`], [`align_to_byte`] for stored blocks and [`copy_back`] for back
+//! references, so it also serves as a reference for implementing similar formats.
+//!
+//! The decoder favours clarity over speed, use a dedicated compression crate where performance matters.
+//! The size of the output is limited by [`ReadLimits::max_elements`].
+//!
+//! # Examples
+//!
+//! ```
+//! # use bitbuffer::Result;
+//! use bitbuffer::inflate::zlib_decompress;
+//! use bitbuffer::{BitReadBuffer, BitReadStream, LittleEndian};
+//!
+//! # fn main() -> Result<()> {
+//! let compressed = [
+//!     0x78, 0xDA, 0xCB, 0x48, 0xCD, 0xC9, 0xC9, 0x57, 0xC8, 0x40, 0x90, 0x00, 0x3A, 0x2E, 0x06, 0x7D,
+//! ];
+//! let mut stream = BitReadStream::new(BitReadBuffer::new(&compressed, LittleEndian));
+//! assert_eq!(zlib_decompress(&mut stream)?, b"hello hello hello");
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! [`read_huffman`]: crate::BitReadStream::read_huffman
+//! [`align_to_byte`]: crate::BitReadStream::align_to_byte
+//! [`copy_back`]: crate::BitWriteStream::copy_back
+//! [`ReadLimits::max_elements`]: crate::ReadLimits::max_elements
+
+use crate::{
+    BitError, BitReadStream, BitWriteStream, HuffmanTable, LittleEndian, ReadLimit, Result,
+};
+
+const LENGTH_BASE: [u16; 29] = [
+    3, 4, 5, 6, 7, 8, 9, 10, 11, 13, 15, 17, 19, 23, 27, 31, 35, 43, 51, 59, 67, 83, 99, 115, 131,
+    163, 195, 227, 258,
+];
+const LENGTH_EXTRA: [u8; 29] = [
+    0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2, 3, 3, 3, 3, 4, 4, 4, 4, 5, 5, 5, 5, 0,
+];
+const DISTANCE_BASE: [u16; 30] = [
+    1, 2, 3, 4, 5, 7, 9, 13, 17, 25, 33, 49, 65, 97, 129, 193, 257, 385, 513, 769, 1025, 1537,
+    2049, 3073, 4097, 6145, 8193, 12289, 16385, 24577,
+];
+const DISTANCE_EXTRA: [u8; 30] = [
+    0, 0, 0, 0, 1, 1, 2, 2, 3, 3, 4, 4, 5, 5, 6, 6, 7, 7, 8, 8, 9, 9, 10, 10, 11, 11, 12, 12, 13,
+    13,
+];
+/// The order in which the code lengths of the code length code are stored
+const CODE_LENGTH_ORDER: [usize; 19] = [
+    16, 17, 18, 0, 8, 7, 9, 6, 10, 5, 11, 4, 12, 3, 13, 2, 14, 1, 15,
+];
+
+/// Decompress raw deflate data, leaving the stream after the final block
+///
+/// # Errors
+///
+/// - [`ReadError::NotEnoughData`]: the stream ends before the final block
+/// - [`ReadError::Malformed`]: the data isn't valid deflate data
+/// - [`ReadError::LimitExceeded`]: the output is larger than [`ReadLimits::max_elements`](crate::ReadLimits::max_elements)
+///
+/// [`ReadError::NotEnoughData`]: crate::BitError::NotEnoughData
+/// [`ReadError::Malformed`]: crate::BitError::Malformed
+/// [`ReadError::LimitExceeded`]: crate::BitError::LimitExceeded
+pub fn inflate(stream: &mut BitReadStream<LittleEndian>) -> Result<Vec<u8>> {
+    stream.restore_on_error(|stream| {
+        let mut data = Vec::new();
+        let mut output = BitWriteStream::new(&mut data, LittleEndian);
+        loop {
+            let last = stream.read_bool()?;
+            let pos = stream.pos();
+            match stream.read_int::<u8>(2)? {
+                0 => stored_block(stream, &mut output)?,
+                1 => {
+                    let (literals, distances) = fixed_tables();
+                    compressed_block(stream, &mut output, &literals, &distances)?
+                }
+                2 => {
+                    let (literals, distances) = dynamic_tables(stream)?;
+                    compressed_block(stream, &mut output, &literals, &distances)?
+                }
+                _ => {
+                    return Err(BitError::Malformed {
+                        pos,
+                        reason: "reserved deflate block type",
+                    })
+                }
+            }
+            if last {
+                break;
+            }
+        }
+        drop(output);
+        Ok(data)
+    })
+}
+
+/// Decompress zlib data, checking the adler-32 checksum
+///
+/// Preset dictionaries are not supported.
+///
+/// # Errors
+///
+/// - [`ReadError::NotEnoughData`]: the stream ends before the checksum
+/// - [`ReadError::Malformed`]: the data isn't valid zlib data or the checksum doesn't match
+/// - [`ReadError::LimitExceeded`]: the output is larger than [`ReadLimits::max_elements`](crate::ReadLimits::max_elements)
+///
+/// [`ReadError::NotEnoughData`]: crate::BitError::NotEnoughData
+/// [`ReadError::Malformed`]: crate::BitError::Malformed
+/// [`ReadError::LimitExceeded`]: crate::BitError::LimitExceeded
+pub fn zlib_decompress(stream: &mut BitReadStream<LittleEndian>) -> Result<Vec<u8>> {
+    let start = stream.pos();
+    stream.restore_on_error(|stream| {
+        let cmf: u8 = stream.read_int(8)?;
+        let flags: u8 = stream.read_int(8)?;
+        let malformed = |reason| BitError::Malformed { pos: start, reason };
+        if cmf & 0x0F != 8 || cmf >> 4 > 7 || !(cmf as u16 * 256 + flags as u16).is_multiple_of(31)
+        {
+            return Err(malformed("invalid zlib header"));
+        }
+        if flags & 0x20 != 0 {
+            return Err(malformed("zlib preset dictionaries are not supported"));
+        }
+        let data = inflate(stream)?;
+        stream.align_to_byte()?;
+        let pos = stream.pos();
+        let checksum = stream.read_bytes(4)?;
+        if u32::from_be_bytes([checksum[0], checksum[1], checksum[2], checksum[3]])
+            != adler32(&data)
+        {
+            return Err(BitError::Malformed {
+                pos,
+                reason: "the adler-32 checksum doesn't match",
+            });
+        }
+        Ok(data)
+    })
+}
+
+fn adler32(data: &[u8]) -> u32 {
+    let (a, b) = data.iter().fold((1u32, 0u32), |(a, b), &byte| {
+        let a = (a + byte as u32) % 65521;
+        (a, (b + a) % 65521)
+    });
+    b << 16 | a
+}
+
+fn check_output(
+    stream: &BitReadStream<LittleEndian>,
+    output: &BitWriteStream<LittleEndian>,
+    additional: usize,
+) -> Result<()> {
+    stream.check_limit(
+        ReadLimit::Elements,
+        output.byte_len().saturating_add(additional),
+    )
+}
+
+fn stored_block(
+    stream: &mut BitReadStream<LittleEndian>,
+    output: &mut BitWriteStream<LittleEndian>,
+) -> Result<()> {
+    stream.align_to_byte()?;
+    let pos = stream.pos();
+    let length: u16 = stream.read_int(16)?;
+    let complement: u16 = stream.read_int(16)?;
+    if length != !complement {
+        return Err(BitError::Malformed {
+            pos,
+            reason: "the length of the stored block doesn't match its complement",
+        });
+    }
+    check_output(stream, output, length as usize)?;
+    output.write_bytes(&stream.read_bytes(length as usize)?)
+}
+
+fn fixed_tables() -> (HuffmanTable, HuffmanTable) {
+    let mut lengths = [8; 288];
+    lengths[144..256].fill(9);
+    lengths[256..280].fill(7);
+    let literals = HuffmanTable::from_lengths(&lengths).expect("fixed code is valid");
+    let distances = HuffmanTable::from_lengths(&[5; 30]).expect("fixed code is valid");
+    (literals, distances)
+}
+
+fn dynamic_tables(
+    stream: &mut BitReadStream<LittleEndian>,
+) -> Result<(HuffmanTable, HuffmanTable)> {
+    let pos = stream.pos();
+    let malformed = |reason| BitError::Malformed { pos, reason };
+    let literal_count = stream.read_int::<usize>(5)? + 257;
+    let distance_count = stream.read_int::<usize>(5)? + 1;
+    let code_length_count = stream.read_int::<usize>(4)? + 4;
+
+    let mut code_length_lengths = [0; 19];
+    for &index in &CODE_LENGTH_ORDER[..code_length_count] {
+        code_length_lengths[index] = stream.read_int(3)?;
+    }
+    let code_lengths = HuffmanTable::from_lengths(&code_length_lengths)
+        .ok_or_else(|| malformed("invalid code length code"))?;
+
+    let mut lengths = Vec::with_capacity(literal_count + distance_count);
+    while lengths.len() < literal_count + distance_count {
+        let (length, repeat) = match stream.read_huffman(&code_lengths)? {
+            length @ 0..=15 => (length as u8, 1),
+            16 => {
+                let previous = *lengths
+                    .last()
+                    .ok_or_else(|| malformed("code length repeat without a previous length"))?;
+                (previous, 3 + stream.read_int::<usize>(2)?)
+            }
+            17 => (0, 3 + stream.read_int::<usize>(3)?),
+            _ => (0, 11 + stream.read_int::<usize>(7)?),
+        };
+        if lengths.len() + repeat > literal_count + distance_count {
+            return Err(malformed("code lengths repeat past the end of the table"));
+        }
+        lengths.extend(std::iter::repeat_n(length, repeat));
+    }
+    if lengths[256] == 0 {
+        return Err(malformed("the literal code has no end of block code"));
+    }
+
+    let literals = HuffmanTable::from_lengths(&lengths[..literal_count])
+        .ok_or_else(|| malformed("invalid literal code"))?;
+    let distances = HuffmanTable::from_lengths(&lengths[literal_count..])
+        .ok_or_else(|| malformed("invalid distance code"))?;
+    Ok((literals, distances))
+}
+
+fn compressed_block(
+    stream: &mut BitReadStream<LittleEndian>,
+    output: &mut BitWriteStream<LittleEndian>,
+    literals: &HuffmanTable,
+    distances: &HuffmanTable,
+) -> Result<()> {
+    loop {
+        let pos = stream.pos();
+        let symbol = stream.read_huffman(literals)? as usize;
+        match symbol {
+            0..=255 => {
+                check_output(stream, output, 1)?;
+                output.write_int(symbol as u8, 8)?;
+            }
+            256 => return Ok(()),
+            257..=285 => {
+                let index = symbol - 257;
+                let length = LENGTH_BASE[index] as usize
+                    + stream.read_int::<usize>(LENGTH_EXTRA[index] as usize)?;
+                let index = stream.read_huffman(distances)? as usize;
+                if index >= DISTANCE_BASE.len() {
+                    return Err(BitError::Malformed {
+                        pos,
+                        reason: "invalid deflate distance code",
+                    });
+                }
+                let distance = DISTANCE_BASE[index] as usize
+                    + stream.read_int::<usize>(DISTANCE_EXTRA[index] as usize)?;
+                if distance > output.byte_len() {
+                    return Err(BitError::Malformed {
+                        pos,
+                        reason: "deflate distance points before the start of the output",
+                    });
+                }
+                check_output(stream, output, length)?;
+                output.copy_back(distance, length)?;
+            }
+            _ => {
+                return Err(BitError::Malformed {
+                    pos,
+                    reason: "invalid deflate length code",
+                })
+            }
+        }
+    }
+}
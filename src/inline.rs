@@ -0,0 +1,208 @@
+//! Inline storage for writing short messages without heap allocations
+//!
+//! An [`InlineBuffer`] stores up to `N` bytes inline and only moves the data to the heap
+//! once more than `N` bytes are written, similar to a `SmallVec<[u8; N]>`.
+//!
+//! # Examples
+//!
+//! ```
+//! # use bitbuffer::Result;
+//! use bitbuffer::inline::InlineBuffer;
+//! use bitbuffer::{BitWriteStream, BigEndian};
+//!
+//! # fn main() -> Result<()> {
+//! let mut data = InlineBuffer::<32>::new();
+//! let mut stream = BitWriteStream::new_inline(&mut data, BigEndian);
+//! stream.write_int(0b101u8, 3)?;
+//! stream.write_bytes(&[0xFF, 0x00])?;
+//! assert_eq!(data.as_slice(), &[0b1011_1111, 0b1110_0000, 0b0000_0000]);
+//! assert!(!data.spilled());
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::writebuffer::ByteStorage;
+use std::fmt::{self, Debug, Formatter};
+use std::ops::Deref;
+
+/// Byte storage that keeps up to `N` bytes inline before moving to the heap
+#[derive(Clone)]
+pub struct InlineBuffer<const N: usize> {
+    storage: Storage<N>,
+}
+
+#[derive(Clone)]
+enum Storage<const N: usize> {
+    Inline { bytes: [u8; N], len: usize },
+    Heap(Vec<u8>),
+}
+
+impl<const N: usize> InlineBuffer<N> {
+    /// Create an empty buffer
+    pub const fn new() -> Self {
+        InlineBuffer {
+            storage: Storage::Inline {
+                bytes: [0; N],
+                len: 0,
+            },
+        }
+    }
+
+    /// The stored bytes
+    pub fn as_slice(&self) -> &[u8] {
+        match &self.storage {
+            Storage::Inline { bytes, len } => &bytes[..*len],
+            Storage::Heap(vec) => vec.as_slice(),
+        }
+    }
+
+    /// The number of stored bytes
+    pub fn len(&self) -> usize {
+        self.as_slice().len()
+    }
+
+    /// Whether no bytes are stored
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Whether the data has been moved to the heap because more than `N` bytes were stored
+    pub fn spilled(&self) -> bool {
+        matches!(self.storage, Storage::Heap(_))
+    }
+
+    /// Convert the buffer into a `Vec`
+    pub fn into_vec(self) -> Vec<u8> {
+        match self.storage {
+            Storage::Inline { bytes, len } => bytes[..len].to_vec(),
+            Storage::Heap(vec) => vec,
+        }
+    }
+
+    /// Move the data to the heap, with space for at least `capacity` bytes
+    fn spill(&mut self, capacity: usize) -> &mut Vec<u8> {
+        if let Storage::Inline { bytes, len } = &self.storage {
+            let mut vec = Vec::with_capacity(capacity.max(N * 2));
+            vec.extend_from_slice(&bytes[..*len]);
+            self.storage = Storage::Heap(vec);
+        }
+        match &mut self.storage {
+            Storage::Heap(vec) => vec,
+            Storage::Inline { .. } => unreachable!(),
+        }
+    }
+}
+
+impl<const N: usize> Default for InlineBuffer<N> {
+    fn default() -> Self {
+        InlineBuffer::new()
+    }
+}
+
+impl<const N: usize> Debug for InlineBuffer<N> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        Debug::fmt(self.as_slice(), f)
+    }
+}
+
+impl<const N: usize> Deref for InlineBuffer<N> {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        self.as_slice()
+    }
+}
+
+impl<const N: usize> AsRef<[u8]> for InlineBuffer<N> {
+    fn as_ref(&self) -> &[u8] {
+        self.as_slice()
+    }
+}
+
+impl<const N: usize> PartialEq for InlineBuffer<N> {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_slice() == other.as_slice()
+    }
+}
+
+impl<const N: usize> Eq for InlineBuffer<N> {}
+
+impl<const N: usize> From<InlineBuffer<N>> for Vec<u8> {
+    fn from(buffer: InlineBuffer<N>) -> Self {
+        buffer.into_vec()
+    }
+}
+
+impl<const N: usize> ByteStorage for InlineBuffer<N> {
+    fn as_slice(&self) -> &[u8] {
+        InlineBuffer::as_slice(self)
+    }
+
+    fn as_mut_slice(&mut self) -> &mut [u8] {
+        match &mut self.storage {
+            Storage::Inline { bytes, len } => &mut bytes[..*len],
+            Storage::Heap(vec) => vec.as_mut_slice(),
+        }
+    }
+
+    fn capacity(&self) -> usize {
+        match &self.storage {
+            Storage::Inline { .. } => N,
+            Storage::Heap(vec) => vec.capacity(),
+        }
+    }
+
+    fn reserve(&mut self, additional: usize) {
+        let required = self.len() + additional;
+        match &mut self.storage {
+            Storage::Inline { .. } if required <= N => {}
+            Storage::Inline { .. } => {
+                self.spill(required);
+            }
+            Storage::Heap(vec) => vec.reserve(additional),
+        }
+    }
+
+    fn resize(&mut self, new_len: usize, value: u8) {
+        match &mut self.storage {
+            Storage::Inline { bytes, len } if new_len <= N => {
+                if new_len > *len {
+                    bytes[*len..new_len].fill(value);
+                }
+                *len = new_len;
+            }
+            _ => self.spill(new_len).resize(new_len, value),
+        }
+    }
+
+    fn truncate(&mut self, new_len: usize) {
+        match &mut self.storage {
+            Storage::Inline { len, .. } => *len = new_len.min(*len),
+            Storage::Heap(vec) => vec.truncate(new_len),
+        }
+    }
+
+    fn pop(&mut self) -> Option<u8> {
+        match &mut self.storage {
+            Storage::Inline { len: 0, .. } => None,
+            Storage::Inline { bytes, len } => {
+                *len -= 1;
+                Some(bytes[*len])
+            }
+            Storage::Heap(vec) => vec.pop(),
+        }
+    }
+
+    fn extend_from_slice(&mut self, other: &[u8]) {
+        match &mut self.storage {
+            Storage::Inline { bytes, len } if *len + other.len() <= N => {
+                bytes[*len..*len + other.len()].copy_from_slice(other);
+                *len += other.len();
+            }
+            _ => {
+                let required = self.len() + other.len();
+                self.spill(required).extend_from_slice(other)
+            }
+        }
+    }
+}
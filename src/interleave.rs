@@ -0,0 +1,222 @@
+//! Splitting and merging interleaved channels
+//!
+//! Interleaved data consists of groups containing `width` bits for every channel in turn,
+//! for `width` 1 every n-th bit belongs to channel n, larger widths are used for interleaved samples.
+//!
+//! Channels are processed a machine word at a time when a group fits in 64 bits.
+//!
+//! # Examples
+//!
+//! ```
+//! # use bitbuffer::Result;
+//! use bitbuffer::interleave::{deinterleave, interleave};
+//! use bitbuffer::{BitReadBuffer, BitReadStream, BitWriteStream, BigEndian};
+//!
+//! # fn main() -> Result<()> {
+//! let data = [0b1010_1010, 0b1100_1100];
+//! let mut stream = BitReadStream::new(BitReadBuffer::new(&data, BigEndian));
+//!
+//! let mut even = Vec::new();
+//! let mut odd = Vec::new();
+//! let mut channels = [
+//!     BitWriteStream::new(&mut even, BigEndian),
+//!     BitWriteStream::new(&mut odd, BigEndian),
+//! ];
+//! deinterleave(&mut stream, &mut channels, 1, 8)?;
+//! drop(channels);
+//! assert_eq!(even, [0b1111_1010]);
+//! assert_eq!(odd, [0b0000_1010]);
+//!
+//! let mut channels = [
+//!     BitReadStream::new(BitReadBuffer::new(&even, BigEndian)),
+//!     BitReadStream::new(BitReadBuffer::new(&odd, BigEndian)),
+//! ];
+//! let mut data = Vec::new();
+//! interleave(&mut channels, &mut BitWriteStream::new(&mut data, BigEndian), 1, 8)?;
+//! assert_eq!(data, [0b1010_1010, 0b1100_1100]);
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::readstream::checked_bits;
+use crate::{BitReadBuffer, BitReadStream, BitWriteStream, Endianness, Result};
+
+/// Number of groups that can be processed in one 64 bit word, 0 if a single group doesn't fit
+fn groups_per_word(channels: usize, width: usize) -> usize {
+    64 / (channels * width)
+}
+
+/// Shift of the bits for `channel` in group `group` of a word read as integer
+fn bit_shift<E: Endianness>(
+    channels: usize,
+    width: usize,
+    word_bits: usize,
+    group: usize,
+    channel: usize,
+) -> usize {
+    let offset = (group * channels + channel) * width;
+    if E::is_le() {
+        offset
+    } else {
+        word_bits - offset - width
+    }
+}
+
+fn width_mask(width: usize) -> u64 {
+    u64::MAX >> (64 - width)
+}
+
+/// Read `count` groups of interleaved data from the input and write the bits of every channel to the output for that channel
+///
+/// Every group contains `width` bits for each of the outputs in turn, `outputs.len() * width * count` bits are read from the input.
+///
+/// # Errors
+///
+/// - [`ReadError::NotEnoughData`]: not enough bits available in the input, the input is not advanced
+///
+/// [`ReadError::NotEnoughData`]: enum.ReadError.html#variant.NotEnoughData
+pub fn deinterleave<E: Endianness>(
+    input: &mut BitReadStream<E>,
+    outputs: &mut [BitWriteStream<E>],
+    width: usize,
+    count: usize,
+) -> Result<()> {
+    let channels = outputs.len();
+    if channels == 0 || width == 0 {
+        return Ok(());
+    }
+    input.check_read(checked_bits(count, checked_bits(channels, width)?)?)?;
+
+    let per_word = groups_per_word(channels, width);
+    if per_word == 0 {
+        for _ in 0..count {
+            for output in outputs.iter_mut() {
+                output.write_bits(&input.read_bits(width)?)?;
+            }
+        }
+        return Ok(());
+    }
+
+    let mask = width_mask(width);
+    let mut remaining = count;
+    while remaining > 0 {
+        let groups = remaining.min(per_word);
+        let word_bits = groups * channels * width;
+        let word: u64 = input.read_int(word_bits)?;
+        for (channel, output) in outputs.iter_mut().enumerate() {
+            let mut value = 0u64;
+            for group in 0..groups {
+                let bits =
+                    word >> bit_shift::<E>(channels, width, word_bits, group, channel) & mask;
+                value |= if E::is_le() {
+                    bits << (group * width)
+                } else {
+                    bits << ((groups - group - 1) * width)
+                };
+            }
+            output.write_int(value, groups * width)?;
+        }
+        remaining -= groups;
+    }
+    Ok(())
+}
+
+/// Read `count` groups of interleaved data from the input and split them into a stream for every channel
+///
+/// See [`deinterleave`] for details.
+///
+/// # Examples
+///
+/// ```
+/// # use bitbuffer::Result;
+/// use bitbuffer::interleave::split_channels;
+/// use bitbuffer::{BitReadBuffer, BitReadStream, LittleEndian};
+///
+/// # fn main() -> Result<()> {
+/// // 3 interleaved channels of 4 bit samples
+/// let data = [0x21, 0x33, 0x12];
+/// let mut stream = BitReadStream::new(BitReadBuffer::new(&data, LittleEndian));
+/// let mut channels = split_channels(&mut stream, 3, 4, 2)?;
+/// assert_eq!(channels[0].read_int::<u8>(8)?, 0x31);
+/// assert_eq!(channels[1].read_int::<u8>(8)?, 0x22);
+/// assert_eq!(channels[2].read_int::<u8>(8)?, 0x13);
+/// # Ok(())
+/// # }
+/// ```
+pub fn split_channels<E: Endianness>(
+    input: &mut BitReadStream<E>,
+    channels: usize,
+    width: usize,
+    count: usize,
+) -> Result<Vec<BitReadStream<'static, E>>> {
+    let mut data = vec![Vec::with_capacity((width * count).div_ceil(8)); channels];
+    {
+        let mut outputs: Vec<_> = data
+            .iter_mut()
+            .map(|data| BitWriteStream::new(data, E::endianness()))
+            .collect();
+        deinterleave(input, &mut outputs, width, count)?;
+    }
+    data.into_iter()
+        .map(|data| {
+            BitReadStream::new(BitReadBuffer::new_owned(data, E::endianness()))
+                .read_bits(width * count)
+        })
+        .collect()
+}
+
+/// Read `width * count` bits from every input and write them interleaved to the output
+///
+/// This is the inverse of [`deinterleave`], every group contains `width` bits from each of the inputs in turn.
+///
+/// # Errors
+///
+/// - [`ReadError::NotEnoughData`]: not enough bits available in one of the inputs, none of the inputs are advanced
+///
+/// [`ReadError::NotEnoughData`]: enum.ReadError.html#variant.NotEnoughData
+pub fn interleave<E: Endianness>(
+    inputs: &mut [BitReadStream<E>],
+    output: &mut BitWriteStream<E>,
+    width: usize,
+    count: usize,
+) -> Result<()> {
+    let channels = inputs.len();
+    if channels == 0 || width == 0 {
+        return Ok(());
+    }
+    for input in inputs.iter() {
+        input.check_read(checked_bits(count, width)?)?;
+    }
+
+    let per_word = groups_per_word(channels, width);
+    if per_word == 0 {
+        for _ in 0..count {
+            for input in inputs.iter_mut() {
+                output.write_bits(&input.read_bits(width)?)?;
+            }
+        }
+        return Ok(());
+    }
+
+    let mask = width_mask(width);
+    let mut remaining = count;
+    while remaining > 0 {
+        let groups = remaining.min(per_word);
+        let word_bits = groups * channels * width;
+        let mut word = 0u64;
+        for (channel, input) in inputs.iter_mut().enumerate() {
+            let value: u64 = input.read_int(groups * width)?;
+            for group in 0..groups {
+                let bits = if E::is_le() {
+                    value >> (group * width)
+                } else {
+                    value >> ((groups - group - 1) * width)
+                } & mask;
+                word |= bits << bit_shift::<E>(channels, width, word_bits, group, channel);
+            }
+        }
+        output.write_int(word, word_bits)?;
+        remaining -= groups;
+    }
+    Ok(())
+}
@@ -0,0 +1,104 @@
+use std::collections::HashSet;
+use std::fmt::{self, Debug, Formatter};
+use std::sync::{Arc, Mutex};
+
+/// Deduplicates strings read from a stream
+///
+/// Once an interner is installed with [`BitReadStream::set_interner`], strings read with
+/// [`BitReadStream::read_string_interned`] or as `Arc<str>` are passed trough the interner,
+/// so repeated strings share a single allocation.
+///
+/// This is implemented for [`StringInterner`] and for closures taking a `&str`.
+///
+/// [`BitReadStream::set_interner`]: crate::BitReadStream::set_interner
+/// [`BitReadStream::read_string_interned`]: crate::BitReadStream::read_string_interned
+pub trait Interner: Send + Sync {
+    /// Get the shared copy of `string`
+    fn intern(&self, string: &str) -> Arc<str>;
+}
+
+impl<F: Fn(&str) -> Arc<str> + Send + Sync> Interner for F {
+    fn intern(&self, string: &str) -> Arc<str> {
+        self(string)
+    }
+}
+
+/// An [`Interner`] that keeps every string it has seen
+///
+/// # Examples
+///
+/// ```
+/// # use bitbuffer::Result;
+/// use bitbuffer::{BitReadBuffer, BitReadStream, LittleEndian, StringInterner};
+/// use std::sync::Arc;
+///
+/// # fn main() -> Result<()> {
+/// let bytes = b"m_iHealth\0m_iHealth\0";
+/// let mut stream = BitReadStream::new(BitReadBuffer::new(bytes, LittleEndian));
+/// let interner = Arc::new(StringInterner::new());
+/// stream.set_interner(interner.clone());
+///
+/// let first: Arc<str> = stream.read()?;
+/// let second: Arc<str> = stream.read()?;
+/// assert!(Arc::ptr_eq(&first, &second));
+/// assert_eq!(interner.len(), 1);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Default)]
+pub struct StringInterner {
+    strings: Mutex<HashSet<Arc<str>>>,
+}
+
+impl StringInterner {
+    /// Create an empty interner
+    pub fn new() -> Self {
+        StringInterner::default()
+    }
+
+    /// The number of unique strings in the interner
+    pub fn len(&self) -> usize {
+        self.strings().len()
+    }
+
+    /// Whether the interner is empty
+    pub fn is_empty(&self) -> bool {
+        self.strings().is_empty()
+    }
+
+    /// Remove all strings from the interner
+    pub fn clear(&self) {
+        self.strings().clear()
+    }
+
+    fn strings(&self) -> std::sync::MutexGuard<'_, HashSet<Arc<str>>> {
+        // the set is always in a consistent state, so a panic while holding the lock doesn't matter
+        self.strings
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+impl Interner for StringInterner {
+    fn intern(&self, string: &str) -> Arc<str> {
+        let mut strings = self.strings();
+        match strings.get(string) {
+            Some(interned) => interned.clone(),
+            None => {
+                let interned: Arc<str> = Arc::from(string);
+                strings.insert(interned.clone());
+                interned
+            }
+        }
+    }
+}
+
+/// The interner installed on a stream
+#[derive(Clone)]
+pub(crate) struct InternerRef(pub(crate) Arc<dyn Interner>);
+
+impl Debug for InternerRef {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str("Interner")
+    }
+}
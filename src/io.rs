@@ -0,0 +1,430 @@
+//! Reading bits from an [`io::Read`] and writing bits to an [`io::Write`]
+//!
+//! [`BitReader`] buffers data from a reader and reads values from the buffered data through a regular
+//! [`BitReadStream`], reading more data from the source when needed. Values don't have to be byte aligned
+//! and can span multiple reads.
+//!
+//! The buffered data can be inspected before it's consumed with [`fill_buf`](BitReader::fill_buf) and
+//! [`lookahead`](BitReader::lookahead), which allows reading the length from a frame header before deciding
+//! how to read the rest of the frame.
+//!
+//! # Examples
+//!
+//! ```
+//! # use bitbuffer::Result;
+//! use bitbuffer::io::BitReader;
+//! use bitbuffer::BigEndian;
+//!
+//! # fn main() -> Result<()> {
+//! // frames with a 4 bit type and a 12 bit length in bytes
+//! let data: &[u8] = &[0x10, 0x02, 0xAB, 0xCD, 0x20, 0x01, 0xEF];
+//! let mut reader = BitReader::new(data, BigEndian);
+//! let mut header = reader.lookahead(16)?;
+//! let kind: u8 = header.read_int(4)?;
+//! let length: usize = header.read_int(12)?;
+//! assert_eq!((kind, length), (1, 2));
+//!
+//! // make sure the full frame is available before consuming it
+//! reader.fill_buf(16 + length * 8)?;
+//! reader.consume(16);
+//! assert_eq!(reader.read_sized::<Vec<u8>>(length)?, [0xAB, 0xCD]);
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! [`BitFileWriter`] collects written bits and writes the completed bytes to a writer in large chunks, which allows
+//! writing large outputs without keeping all of it in memory.
+//!
+//! ```no_run
+//! # use bitbuffer::Result;
+//! use bitbuffer::io::BitFileWriter;
+//! use bitbuffer::LittleEndian;
+//!
+//! # fn main() -> Result<()> {
+//! let mut writer = BitFileWriter::create("output.bin", LittleEndian)?;
+//! for i in 0..1_000_000u32 {
+//!     writer.write_with(|stream| stream.write_int(i, 20))?;
+//! }
+//! writer.finish()?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! [`io::Read`]: std::io::Read
+//! [`io::Write`]: std::io::Write
+
+use crate::owned::OwnedWriter;
+use crate::{
+    BitError, BitRead, BitReadBuffer, BitReadSized, BitReadStream, BitWrite, BitWriteSized,
+    BitWriteStream, Endianness, Result,
+};
+use std::fmt::{self, Debug, Formatter};
+use std::fs::File;
+use std::io::{ErrorKind, Read, Write};
+use std::marker::PhantomData;
+use std::path::Path;
+
+const READ_SIZE: usize = 4096;
+const WRITE_SIZE: usize = 64 * 1024;
+
+/// Reads bits from an [`io::Read`](std::io::Read)
+///
+/// See the [module level documentation](self) for details.
+pub struct BitReader<R, E> {
+    reader: R,
+    buffer: Vec<u8>,
+    /// The bit position of the first unconsumed bit in the buffer
+    pos: usize,
+    eof: bool,
+    endianness: PhantomData<fn() -> E>,
+}
+
+impl<R: Read, E: Endianness> BitReader<R, E> {
+    /// Create a reader that reads data from `reader`
+    pub fn new(reader: R, _endianness: E) -> Self {
+        BitReader {
+            reader,
+            buffer: Vec::new(),
+            pos: 0,
+            eof: false,
+            endianness: PhantomData,
+        }
+    }
+
+    /// The number of buffered bits that haven't been consumed yet
+    pub fn buffered_bits(&self) -> usize {
+        self.buffer.len() * 8 - self.pos
+    }
+
+    /// Make sure at least `min_bits` bits are buffered, returning a stream of all buffered bits
+    ///
+    /// Reading from the returned stream doesn't consume any data from the reader, use [`consume`](Self::consume) for that.
+    ///
+    /// # Errors
+    ///
+    /// - [`ReadError::NotEnoughData`]: the reader ended before `min_bits` bits could be buffered
+    /// - [`ReadError::Io`]: an error from the underlying reader
+    ///
+    /// [`ReadError::NotEnoughData`]: crate::BitError::NotEnoughData
+    /// [`ReadError::Io`]: crate::BitError::Io
+    pub fn fill_buf(&mut self, min_bits: usize) -> Result<BitReadStream<'_, E>> {
+        while self.buffered_bits() < min_bits {
+            if !self.read_more()? {
+                return Err(BitError::NotEnoughData {
+                    requested: min_bits,
+                    bits_left: self.buffered_bits(),
+                });
+            }
+        }
+        Ok(self.stream())
+    }
+
+    /// Get a stream of the next `bits` bits without consuming them
+    ///
+    /// # Errors
+    ///
+    /// - [`ReadError::NotEnoughData`]: the reader ended before `bits` bits could be buffered
+    /// - [`ReadError::Io`]: an error from the underlying reader
+    ///
+    /// [`ReadError::NotEnoughData`]: crate::BitError::NotEnoughData
+    /// [`ReadError::Io`]: crate::BitError::Io
+    pub fn lookahead(&mut self, bits: usize) -> Result<BitReadStream<'_, E>> {
+        self.fill_buf(bits)?.read_bits(bits)
+    }
+
+    /// Consume `bits` buffered bits
+    ///
+    /// # Panics
+    ///
+    /// Panics if less than `bits` bits are buffered
+    pub fn consume(&mut self, bits: usize) {
+        assert!(
+            bits <= self.buffered_bits(),
+            "can't consume more bits than are buffered"
+        );
+        self.pos += bits;
+    }
+
+    /// Read from the buffered data using a stream, buffering more data as needed
+    ///
+    /// If `f` succeeds, the bits read from the stream are consumed. If `f` fails because it runs out of data,
+    /// more data is read from the reader and `f` is called again.
+    ///
+    /// # Errors
+    ///
+    /// - [`ReadError::NotEnoughData`]: the reader ended before `f` could complete, nothing is consumed
+    /// - [`ReadError::Io`]: an error from the underlying reader, nothing is consumed
+    /// - any other error returned by `f`, nothing is consumed
+    ///
+    /// [`ReadError::NotEnoughData`]: crate::BitError::NotEnoughData
+    /// [`ReadError::Io`]: crate::BitError::Io
+    pub fn read_with<T, F>(&mut self, mut f: F) -> Result<T>
+    where
+        F: FnMut(&mut BitReadStream<'_, E>) -> Result<T>,
+    {
+        loop {
+            let mut stream = self.stream();
+            match f(&mut stream) {
+                Ok(result) => {
+                    let read = stream.pos();
+                    self.pos += read;
+                    return Ok(result);
+                }
+                Err(BitError::NotEnoughData {
+                    requested,
+                    bits_left,
+                }) if !self.eof => {
+                    let needed = self.buffered_bits() + requested.saturating_sub(bits_left).max(1);
+                    match self.fill_buf(needed) {
+                        Err(BitError::NotEnoughData { .. }) => {
+                            return Err(BitError::NotEnoughData {
+                                requested,
+                                bits_left,
+                            })
+                        }
+                        Err(e) => return Err(e),
+                        Ok(_) => {}
+                    }
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    /// Read a value based on the provided type, consuming the read bits
+    ///
+    /// See [`read_with`](Self::read_with) for details.
+    pub fn read<T>(&mut self) -> Result<T>
+    where
+        T: for<'a> BitRead<'a, E>,
+    {
+        self.read_with(|stream| stream.read())
+    }
+
+    /// Read a value based on the provided type and size, consuming the read bits
+    ///
+    /// See [`read_with`](Self::read_with) for details.
+    pub fn read_sized<T>(&mut self, size: usize) -> Result<T>
+    where
+        T: for<'a> BitReadSized<'a, E>,
+    {
+        self.read_with(|stream| stream.read_sized(size))
+    }
+
+    /// Get a reference to the reader
+    pub fn get_ref(&self) -> &R {
+        &self.reader
+    }
+
+    /// Get the reader, discarding any buffered data
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+
+    fn stream(&self) -> BitReadStream<'_, E> {
+        let start = self.pos / 8;
+        let mut stream =
+            BitReadStream::new(BitReadBuffer::new(&self.buffer[start..], E::endianness()));
+        // the buffer always contains the partially consumed byte, so the stream can't be too short
+        stream
+            .skip_bits(self.pos % 8)
+            .and_then(|_| stream.read_bits(self.buffered_bits()))
+            .expect("unconsumed bits are buffered")
+    }
+
+    /// Read more data from the reader, returns false if the reader is at the end of its data
+    fn read_more(&mut self) -> Result<bool> {
+        if self.eof {
+            return Ok(false);
+        }
+        self.buffer.drain(0..self.pos / 8);
+        self.pos %= 8;
+        let len = self.buffer.len();
+        self.buffer.resize(len + READ_SIZE, 0);
+        loop {
+            match self.reader.read(&mut self.buffer[len..]) {
+                Ok(read) => {
+                    self.buffer.truncate(len + read);
+                    self.eof = read == 0;
+                    return Ok(read > 0);
+                }
+                Err(e) if e.kind() == ErrorKind::Interrupted => {}
+                Err(e) => {
+                    self.buffer.truncate(len);
+                    return Err(e.into());
+                }
+            }
+        }
+    }
+}
+
+impl<R: Debug, E> Debug for BitReader<R, E> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BitReader")
+            .field("reader", &self.reader)
+            .field("buffered_bits", &(self.buffer.len() * 8 - self.pos))
+            .finish()
+    }
+}
+
+/// Writes bits to an [`io::Write`](std::io::Write), usually a [`File`]
+///
+/// Completed bytes are written to the writer once enough of them are collected, the last partial byte is padded with
+/// zero bits and written when the writer is finished or dropped.
+///
+/// Like [`BufWriter`](std::io::BufWriter), errors that happen when writing the remaining data on drop are ignored,
+/// use [`finish`](Self::finish) to handle them.
+///
+/// See the [module level documentation](self) for an example.
+pub struct BitFileWriter<W: Write, E: Endianness> {
+    writer: Option<W>,
+    buffer: OwnedWriter,
+    /// The number of bytes already written to the writer
+    flushed: usize,
+    endianness: PhantomData<fn() -> E>,
+}
+
+impl<E: Endianness> BitFileWriter<File, E> {
+    /// Create a new file at `path` and write to it, truncating any existing file
+    ///
+    /// # Errors
+    ///
+    /// - [`ReadError::Io`]: the file couldn't be created
+    ///
+    /// [`ReadError::Io`]: crate::BitError::Io
+    pub fn create<P: AsRef<Path>>(path: P, endianness: E) -> Result<Self> {
+        Ok(BitFileWriter::new(File::create(path)?, endianness))
+    }
+}
+
+impl<W: Write, E: Endianness> BitFileWriter<W, E> {
+    /// Create a bit writer that writes to `writer`
+    pub fn new(writer: W, _endianness: E) -> Self {
+        BitFileWriter {
+            writer: Some(writer),
+            buffer: OwnedWriter::new(E::is_le()),
+            flushed: 0,
+            endianness: PhantomData,
+        }
+    }
+
+    /// The total number of bits written
+    pub fn bit_len(&self) -> usize {
+        self.flushed * 8 + self.buffer.bit_len()
+    }
+
+    /// Write using a stream that continues after the previously written bits
+    ///
+    /// # Errors
+    ///
+    /// - [`ReadError::Io`]: an error from the underlying writer
+    /// - any error returned by `f`, none of the bits written by `f` are kept
+    ///
+    /// [`ReadError::Io`]: crate::BitError::Io
+    pub fn write_with<T, F>(&mut self, f: F) -> Result<T>
+    where
+        F: FnOnce(&mut BitWriteStream<'_, E>) -> Result<T>,
+    {
+        let result = self.buffer.write_endian(E::endianness(), f)?;
+        if self.buffer.data().len() >= WRITE_SIZE {
+            self.write_full_bytes()?;
+        }
+        Ok(result)
+    }
+
+    /// Write a value based on the provided type
+    ///
+    /// See [`write_with`](Self::write_with) for details.
+    pub fn write<T: BitWrite<E>>(&mut self, value: &T) -> Result<()> {
+        self.write_with(|stream| stream.write(value))
+    }
+
+    /// Write a value based on the provided type and size
+    ///
+    /// See [`write_with`](Self::write_with) for details.
+    pub fn write_sized<T: BitWriteSized<E>>(&mut self, value: &T, len: usize) -> Result<()> {
+        self.write_with(|stream| stream.write_sized(value, len))
+    }
+
+    /// Write all completed bytes and flush the underlying writer
+    ///
+    /// The last partial byte is kept until more bits are written or the writer is finished.
+    ///
+    /// # Errors
+    ///
+    /// - [`ReadError::Io`]: an error from the underlying writer
+    ///
+    /// [`ReadError::Io`]: crate::BitError::Io
+    pub fn flush(&mut self) -> Result<()> {
+        self.write_full_bytes()?;
+        self.writer_mut().flush()?;
+        Ok(())
+    }
+
+    /// Write all remaining bits, padding the last byte with zero bits, and get the writer
+    ///
+    /// # Errors
+    ///
+    /// - [`ReadError::Io`]: an error from the underlying writer
+    ///
+    /// [`ReadError::Io`]: crate::BitError::Io
+    pub fn finish(mut self) -> Result<W> {
+        self.write_remaining()?;
+        Ok(self.writer.take().expect("writer is only taken on finish"))
+    }
+
+    /// Get a reference to the writer
+    pub fn get_ref(&self) -> &W {
+        self.writer
+            .as_ref()
+            .expect("writer is only taken on finish")
+    }
+
+    fn writer_mut(&mut self) -> &mut W {
+        self.writer
+            .as_mut()
+            .expect("writer is only taken on finish")
+    }
+
+    fn write_full_bytes(&mut self) -> Result<()> {
+        let full_bytes = self.buffer.bit_len() / 8;
+        let writer = self
+            .writer
+            .as_mut()
+            .expect("writer is only taken on finish");
+        writer.write_all(&self.buffer.data()[..full_bytes])?;
+        self.buffer.consume(full_bytes);
+        self.flushed += full_bytes;
+        Ok(())
+    }
+
+    fn write_remaining(&mut self) -> Result<()> {
+        let writer = self
+            .writer
+            .as_mut()
+            .expect("writer is only taken on finish");
+        writer.write_all(self.buffer.data())?;
+        writer.flush()?;
+        let bytes = self.buffer.data().len();
+        self.flushed += bytes;
+        self.buffer.consume(bytes);
+        Ok(())
+    }
+}
+
+impl<W: Write, E: Endianness> Drop for BitFileWriter<W, E> {
+    fn drop(&mut self) {
+        if self.writer.is_some() {
+            let _ = self.write_remaining();
+        }
+    }
+}
+
+impl<W: Write + Debug, E: Endianness> Debug for BitFileWriter<W, E> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BitFileWriter")
+            .field("writer", &self.writer)
+            .field("bit_len", &self.bit_len())
+            .finish()
+    }
+}
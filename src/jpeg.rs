@@ -0,0 +1,152 @@
+//! JPEG entropy-coded segments
+//!
+//! The huffman coded scan data of a JPEG image is stored most significant bit first with byte stuffing, every
+//! `0xFF` byte in the data is followed by a `0x00` byte to distinguish it from a marker. The scan can be split
+//! into intervals by restart markers (`RST0` to `RST7`), after which the decoder resets its state and continues
+//! at a byte boundary.
+//!
+//! [`read_entropy_coded_segment`] removes the stuffing up to the next marker, the returned stream can then be
+//! decoded with [`read_huffman`] and [`read_int`] using [`BigEndian`] tables.
+//!
+//! # Examples
+//!
+//! ```
+//! # use bitbuffer::Result;
+//! use bitbuffer::jpeg::{read_entropy_coded_segment, END_OF_IMAGE};
+//! use bitbuffer::{BigEndian, BitReadBuffer, BitReadStream};
+//!
+//! # fn main() -> Result<()> {
+//! let scan = [0x12, 0xFF, 0x00, 0x34, 0xFF, 0xD0, 0x56, 0xFF, 0xD9];
+//! let mut stream = BitReadStream::new(BitReadBuffer::new(&scan, BigEndian));
+//!
+//! let mut interval = read_entropy_coded_segment(&mut stream)?;
+//! assert_eq!(interval.data.read_bytes(3)?.as_ref(), &[0x12, 0xFF, 0x34]);
+//! assert_eq!(interval.restart(), Some(0));
+//!
+//! let mut interval = read_entropy_coded_segment(&mut stream)?;
+//! assert_eq!(interval.data.read_int::<u8>(8)?, 0x56);
+//! assert_eq!(interval.marker, Some(END_OF_IMAGE));
+//! // markers other than restart markers are left in the stream
+//! assert_eq!(stream.read_int::<u16>(16)?, 0xFFD9);
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! [`read_huffman`]: crate::BitReadStream::read_huffman
+//! [`read_int`]: crate::BitReadStream::read_int
+
+use crate::{BigEndian, BitReadBuffer, BitReadStream, BitWriteStream, Result};
+use std::io::SeekFrom;
+
+/// The marker that ends every JPEG image
+pub const END_OF_IMAGE: u8 = 0xD9;
+
+/// The code of the first restart marker, `RST0`, the restart markers cycle through `0xD0` to `0xD7`
+pub const RESTART: u8 = 0xD0;
+
+/// Check if a marker code is one of the restart markers
+pub fn is_restart_marker(marker: u8) -> bool {
+    (RESTART..RESTART + 8).contains(&marker)
+}
+
+/// The data of an entropy-coded segment with the stuffing removed
+#[derive(Debug, Clone)]
+pub struct EntropyCodedSegment {
+    /// The unstuffed data, including the 1 bits used to pad the segment to a byte boundary
+    pub data: BitReadStream<'static, BigEndian>,
+    /// The code of the marker following the segment, `None` if the stream ended without a marker
+    pub marker: Option<u8>,
+}
+
+impl EntropyCodedSegment {
+    /// The number of the restart marker following the segment, if the segment is ended by one
+    pub fn restart(&self) -> Option<u8> {
+        self.marker
+            .filter(|&marker| is_restart_marker(marker))
+            .map(|marker| marker - RESTART)
+    }
+}
+
+/// Read the entropy-coded data up to the next marker, removing the stuffed `0x00` bytes
+///
+/// Restart markers are consumed, so the next interval can be read with another call. Any other marker is left
+/// in the stream to be handled by the caller, preceded by a single `0xFF` when fill bytes are present.
+///
+/// # Errors
+///
+/// - [`ReadError::NotEnoughData`]: the stream ends with an incomplete marker
+///
+/// [`ReadError::NotEnoughData`]: crate::BitError::NotEnoughData
+pub fn read_entropy_coded_segment(
+    stream: &mut BitReadStream<BigEndian>,
+) -> Result<EntropyCodedSegment> {
+    stream.restore_on_error(|stream| {
+        let mut data = Vec::new();
+        let mut marker = None;
+        while stream.bits_left() >= 8 {
+            let byte: u8 = stream.read_int(8)?;
+            if byte != 0xFF {
+                data.push(byte);
+                continue;
+            }
+            let mut code: u8 = stream.read_int(8)?;
+            // skip fill bytes before the marker
+            while code == 0xFF {
+                code = stream.read_int(8)?;
+            }
+            if code == 0 {
+                data.push(0xFF);
+                continue;
+            }
+            if !is_restart_marker(code) {
+                stream.seek(SeekFrom::Current(-16))?;
+            }
+            marker = Some(code);
+            break;
+        }
+        Ok(EntropyCodedSegment {
+            data: BitReadStream::new(BitReadBuffer::new_owned(data, BigEndian)),
+            marker,
+        })
+    })
+}
+
+/// Write all remaining bits from the input as entropy-coded data
+///
+/// The data is padded to a byte boundary with 1 bits and a `0x00` byte is inserted after every `0xFF` byte.
+/// The marker ending the segment isn't written.
+///
+/// # Examples
+///
+/// ```
+/// # use bitbuffer::Result;
+/// use bitbuffer::jpeg::write_entropy_coded_segment;
+/// use bitbuffer::{BigEndian, BitReadBuffer, BitReadStream, BitWriteStream};
+///
+/// # fn main() -> Result<()> {
+/// let input = BitReadStream::new(BitReadBuffer::new(&[0x12, 0xFF, 0b1010_0000], BigEndian));
+/// let input = input.clone().read_bits(20)?;
+///
+/// let mut data = Vec::new();
+/// let mut stream = BitWriteStream::new(&mut data, BigEndian);
+/// write_entropy_coded_segment(&mut stream, &input)?;
+/// assert_eq!(data, [0x12, 0xFF, 0x00, 0b1010_1111]);
+/// # Ok(())
+/// # }
+/// ```
+pub fn write_entropy_coded_segment(
+    stream: &mut BitWriteStream<BigEndian>,
+    input: &BitReadStream<BigEndian>,
+) -> Result<()> {
+    let mut input = input.clone();
+    while input.bits_left() > 0 {
+        let bits = input.bits_left().min(8);
+        let padding = 8 - bits;
+        let byte = input.read_int::<u8>(bits)? << padding | ((1u16 << padding) - 1) as u8;
+        stream.write_int(byte, 8)?;
+        if byte == 0xFF {
+            stream.write_int(0u8, 8)?;
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,132 @@
+/// Trait for types that can describe how they are laid out in a bit stream
+///
+/// The `LayoutDescribe` trait can be used with `#[derive]` on structs and enums, together with
+/// [`BitRead`] or [`BitReadSized`] and using the same attributes, allowing generic tooling
+/// (documentation generators, dissectors, inspectors) to be built on top of the format definitions.
+///
+/// # Examples
+///
+/// ```
+/// use bitbuffer::{BitRead, LayoutDescribe, Layout, SizeSource};
+///
+/// #[derive(BitRead, LayoutDescribe)]
+/// struct Header {
+///     #[size = 3]
+///     version: u8,
+///     flags: u8,
+///     #[size = "flags"]
+///     payload: String,
+/// }
+///
+/// let layout = Header::layout();
+/// let fields = layout.fields();
+/// assert_eq!(fields[0].name, "version");
+/// assert_eq!(fields[0].bit_size, Some(3));
+/// assert_eq!(fields[2].size, SizeSource::Expression("flags"));
+/// assert_eq!(layout.bit_size(), None);
+/// ```
+///
+/// [`BitRead`]: crate::BitRead
+/// [`BitReadSized`]: crate::BitReadSized
+pub trait LayoutDescribe {
+    /// Get the description of the layout
+    fn layout() -> Layout;
+}
+
+/// Description of the layout of a type in the bit stream
+#[derive(Debug, Clone, PartialEq)]
+pub enum Layout {
+    /// A struct, read field by field
+    Struct {
+        /// The name of the struct
+        name: &'static str,
+        /// The fields of the struct, in the order they are read
+        fields: Vec<FieldLayout>,
+    },
+    /// An enum, read as a discriminant followed by the field of the matching variant
+    Enum {
+        /// The name of the enum
+        name: &'static str,
+        /// The number of bits used for the discriminant
+        discriminant_bits: usize,
+        /// The variants of the enum
+        variants: Vec<VariantLayout>,
+    },
+}
+
+impl Layout {
+    /// The name of the described type
+    pub fn name(&self) -> &'static str {
+        match self {
+            Layout::Struct { name, .. } => name,
+            Layout::Enum { name, .. } => name,
+        }
+    }
+
+    /// The fields of the described struct, empty for enums
+    pub fn fields(&self) -> &[FieldLayout] {
+        match self {
+            Layout::Struct { fields, .. } => fields,
+            Layout::Enum { .. } => &[],
+        }
+    }
+
+    /// The total number of bits of the layout, if it doesn't depend on the data being read
+    pub fn bit_size(&self) -> Option<usize> {
+        match self {
+            Layout::Struct { fields, .. } => fields
+                .iter()
+                .try_fold(0, |sum, field| field.bit_size.map(|size| sum + size)),
+            Layout::Enum {
+                discriminant_bits,
+                variants,
+                ..
+            } => {
+                if variants.iter().all(|variant| variant.field.is_none()) {
+                    Some(*discriminant_bits)
+                } else {
+                    None
+                }
+            }
+        }
+    }
+}
+
+/// Description of a single field
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldLayout {
+    /// The name of the field, or the index for tuple structs
+    pub name: &'static str,
+    /// The type of the field, as written in the source
+    pub type_name: &'static str,
+    /// Where the size of the field comes from
+    pub size: SizeSource,
+    /// The number of bits of the field, if it doesn't depend on the data being read
+    pub bit_size: Option<usize>,
+}
+
+/// Description of a single enum variant
+#[derive(Debug, Clone, PartialEq)]
+pub struct VariantLayout {
+    /// The name of the variant
+    pub name: &'static str,
+    /// The discriminant of the variant, `None` for the `"_"` wildcard
+    pub discriminant: Option<usize>,
+    /// The field of the variant, if any
+    pub field: Option<FieldLayout>,
+}
+
+/// The source of the size of a field
+#[derive(Debug, Clone, PartialEq)]
+pub enum SizeSource {
+    /// No size is set, the field is read using `BitRead`
+    Unsized,
+    /// The size is set as a fixed integer
+    Fixed(usize),
+    /// The size depends on previously read fields, using the contained expression
+    Expression(&'static str),
+    /// The size is the input size of a `BitReadSized` implementation
+    InputSize,
+    /// The size is read from the stream as an integer of the given number of bits
+    Bits(usize),
+}
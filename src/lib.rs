@@ -21,6 +21,23 @@
 //!
 //! Just like the read counterparts, [`BitWrite`] and [`BitWriteSized`] traits can be used with `#[derive]` if all fields implement [`BitWrite`] or [`BitWriteSized`].
 //!
+//! # Thread safety
+//!
+//! [`BitReadBuffer`] and [`BitReadStream`] are `Send` and `Sync`, the data they read from is never modified so streams
+//! over the same data can be read from different threads without locking.
+//! Use [`SharedBitReadBuffer`] to share owned data between threads without copying it.
+//!
+//! [`BitWriteStream`] is `Send`, so a stream can be handed to another thread to finish writing.
+//!
+//! # WebAssembly
+//!
+//! The crate builds on stable Rust without any platform specific code, so the same parsers can be used on
+//! `wasm32-unknown-unknown`. The `wasm` feature enables the features that are useful there, currently `inline`
+//! for writing short messages without heap allocations, reads from a borrowed slice never copy the data.
+//!
+//! All other features except `proptest` build for `wasm32-unknown-unknown` as well,
+//! reading and writing files with [`io`] requires a target with file system access.
+//!
 //! # Examples
 //!
 //! ```
@@ -58,6 +75,9 @@
 //! # }
 //! ```
 //!
+//! The `formats` example implements BMP, DNS and MIDI structures with the derives, run it with
+//! `cargo run --example formats`.
+//!
 //! [`read_bool`]: BitReadStream::read_bool
 //! [`read_int`]: BitReadStream::read_int
 //! [`read_float`]: BitReadStream::read_float
@@ -74,30 +94,128 @@
 //! [`write_sized`]: BitWriteStream::write_sized
 
 #![warn(missing_docs)]
+// the impls generated by err-derive trigger this lint on newer compilers
+#![allow(non_local_definitions)]
 
 use err_derive::Error;
 
-pub use bitbuffer_derive::{BitRead, BitReadSized, BitWrite, BitWriteSized};
+#[cfg(feature = "arbitrary")]
+pub use crate::arbitrary::fuzz_roundtrip;
+pub use bitbuffer_derive::{BitRead, BitReadSized, BitWrite, BitWriteSized, LayoutDescribe};
+pub use bitfield::{BitfieldAbi, BitfieldLayout};
+pub use cancel::CancellationToken;
 pub use endianness::*;
+pub use fixup::{Label, LabelPositions};
+pub use hasher::{BitHasher, HasherAdapter};
+pub use huffman::HuffmanTable;
+pub use intern::{Interner, StringInterner};
+pub use layout::{FieldLayout, Layout, LayoutDescribe, SizeSource, VariantLayout};
+pub use limits::{ReadLimit, ReadLimits};
+pub use prefixed::PrefixedString;
+pub use quic::QuicVarInt;
 pub use read::{BitRead, BitReadSized, LazyBitRead, LazyBitReadSized};
 pub use readbuffer::BitReadBuffer;
 pub use readstream::BitReadStream;
+pub use shared::SharedBitReadBuffer;
 use std::str::Utf8Error;
 use std::string::FromUtf8Error;
+#[doc(hidden)]
+pub use testing::bit_diff as __bit_diff;
+pub use testing::{
+    assert_no_panic_on_truncation, assert_no_panic_on_truncation_sized, test_roundtrip,
+    test_roundtrip_sized, RoundtripError,
+};
+pub use value::BitValue;
+pub use vlq::Vlq;
 pub use write::{BitWrite, BitWriteSized};
-pub use writestream::BitWriteStream;
+pub use writestream::{BitWriteStream, TruncatePolicy};
 
+#[cfg(feature = "arbitrary")]
+mod arbitrary;
+#[cfg(feature = "arrayvec")]
+mod arrayvec;
+pub mod asn1;
+pub mod basenc;
+mod bitfield;
+#[cfg(feature = "bitflags")]
+pub mod bitflags;
+#[cfg(feature = "bitvec")]
+pub mod bitvec;
+mod cancel;
+#[cfg(feature = "const_read")]
+pub mod const_read;
+pub mod crc;
+pub mod decode;
+#[cfg(feature = "defmt")]
+mod defmt;
 mod endianness;
+pub mod fault;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+mod fixup;
+#[cfg(feature = "futures")]
+pub mod futures;
+pub mod gps;
+mod hasher;
+#[cfg(feature = "heapless")]
+mod heapless;
+mod huffman;
+pub mod inflate;
+#[cfg(feature = "inline")]
+pub mod inline;
+pub mod interleave;
+mod intern;
+pub mod io;
+pub mod jpeg;
+mod layout;
+mod limits;
+pub mod linecode;
+mod macros;
+pub mod mode_s;
+pub mod mpegts;
 mod num_traits;
+#[doc(hidden)]
+pub mod owned;
+pub mod packbits;
+pub mod pcm;
+mod prefixed;
+#[cfg(feature = "proptest")]
+pub mod proptest;
+#[cfg(feature = "protobuf")]
+pub mod protobuf;
+mod quic;
+pub mod radio;
+#[cfg(feature = "rayon")]
+pub mod rayon;
 mod read;
 mod readbuffer;
 mod readstream;
+pub mod ring;
+#[cfg(feature = "schema")]
+pub mod schema;
+pub mod scramble;
+#[cfg(feature = "serde")]
+pub mod serde;
+mod shared;
+pub mod source;
+#[cfg(feature = "stats")]
+pub mod stats;
+pub mod stuffing;
+mod testing;
+#[cfg(feature = "tokio")]
+pub mod tokio;
+mod value;
+mod vlq;
+pub mod watch;
 mod write;
 mod writebuffer;
 mod writestream;
 
 /// Errors that can be returned when trying to read from or write to a buffer
+///
+/// New variants can be added in minor releases, use [`BitError::kind`] to handle errors by category.
 #[derive(Debug, Error)]
+#[non_exhaustive]
 pub enum BitError {
     /// Too many bits requested to fit in the requested data type
     #[error(
@@ -135,6 +253,21 @@ pub enum BitError {
         /// the number of bits in the buffer
         size: usize,
     },
+    /// A seek would move the position of the stream outside its bounds
+    #[error(
+        display = "Can't seek to position {} from position {}, the stream is only {} bits long",
+        requested,
+        current,
+        size
+    )]
+    SeekOutOfBounds {
+        /// The position before the seek
+        current: usize,
+        /// The requested position, can be negative when seeking backwards
+        requested: i128,
+        /// the number of bits in the stream
+        size: usize,
+    },
     /// Unmatched discriminant found while trying to read an enum
     #[error(
         display = "Unmatched discriminant '{}' found while trying to read enum '{}'",
@@ -150,6 +283,18 @@ pub enum BitError {
     /// The read slice of bytes are not valid utf8
     #[error(display = "The read slice of bytes are not valid utf8: {}", _0)]
     Utf8Error(Utf8Error, usize),
+    /// The read code units are not valid utf16
+    #[error(
+        display = "Unpaired utf16 surrogate {:#06x} found at position {}",
+        unit,
+        pos
+    )]
+    Utf16Error {
+        /// The unpaired surrogate
+        unit: u16,
+        /// The position in the stream of the unpaired surrogate
+        pos: usize,
+    },
     /// The string that was requested to be written does not fit in the specified fixed length
     #[error(
         display = "The string that was requested to be written does not fit in the specified fixed length, string is {} bytes long, while a size of {} has been specified",
@@ -162,6 +307,242 @@ pub enum BitError {
         /// The requested fixed size to encode the string into
         requested_length: usize,
     },
+    /// The data doesn't follow the bit stuffing rules
+    #[error(
+        display = "Invalid bit stuffing found at position {}, expected a stuffed bit after a run of identical bits",
+        pos
+    )]
+    InvalidBitStuffing {
+        /// The position in the stream of the invalid bit
+        pos: usize,
+    },
+    /// The data isn't a valid line code
+    #[error(display = "Invalid line code {:#b} found at position {}", code, pos)]
+    InvalidLineCode {
+        /// The invalid code
+        code: u16,
+        /// The position in the stream of the invalid code
+        pos: usize,
+    },
+    /// A valid 8b/10b code was found that doesn't match the running disparity
+    #[error(
+        display = "8b/10b code {:#012b} found at position {} doesn't match the running disparity",
+        code,
+        pos
+    )]
+    DisparityError {
+        /// The read code
+        code: u16,
+        /// The position in the stream of the code
+        pos: usize,
+    },
+    /// The symbol requested to be written is not a valid 8b/10b control symbol
+    #[error(display = "{:#04x} is not a valid 8b/10b control symbol", value)]
+    InvalidControlSymbol {
+        /// The requested control symbol
+        value: u8,
+    },
+    /// A limit configured with [`BitReadStream::set_limits`] was exceeded
+    #[error(
+        display = "The {} limit was exceeded, requested {} while the limit is {}",
+        limit,
+        requested,
+        max
+    )]
+    LimitExceeded {
+        /// The exceeded limit
+        limit: ReadLimit,
+        /// The requested length, count or depth
+        requested: usize,
+        /// The configured maximum
+        max: usize,
+    },
+    /// The stream wasn't fully consumed
+    #[error(
+        display = "The stream wasn't fully consumed, {} bits are left",
+        bits_left
+    )]
+    TrailingData {
+        /// The number of bits left in the stream
+        bits_left: usize,
+    },
+    /// No null terminator was found within the maximum length of a string
+    #[error(
+        display = "No null terminator found within the maximum string length of {} bytes",
+        max_length
+    )]
+    UnterminatedString {
+        /// The maximum length of the string
+        max_length: usize,
+    },
+    /// A variable length integer doesn't fit in the integer type it's read into
+    #[error(
+        display = "The variable length integer at position {} doesn't fit in {} bits",
+        pos,
+        max
+    )]
+    VarIntOverflow {
+        /// The position in the stream of the start of the integer
+        pos: usize,
+        /// The number of bits that fit in the integer type
+        max: usize,
+    },
+    /// More elements were requested than fit in a bounded collection
+    #[error(
+        display = "Requested {} elements while the collection can only hold {}",
+        requested,
+        capacity
+    )]
+    CapacityExceeded {
+        /// The requested number of elements
+        requested: usize,
+        /// The capacity of the collection
+        capacity: usize,
+    },
+    /// The number of elements in a collection doesn't match the size it's written with
+    #[error(
+        display = "The collection contains {} elements while a size of {} has been specified",
+        length,
+        requested_length
+    )]
+    LengthMismatch {
+        /// The number of elements in the collection
+        length: usize,
+        /// The size the collection is written with
+        requested_length: usize,
+    },
+    /// A field referenced by a schema hasn't been read or doesn't contain a usable integer
+    #[error(
+        display = "The referenced field '{}' hasn't been read or doesn't contain a usable integer",
+        name
+    )]
+    UnresolvedField {
+        /// The name of the referenced field
+        name: String,
+    },
+    /// The type of a field can't be read without knowing the type at compile time
+    #[error(display = "The type '{}' can't be read dynamically", type_name)]
+    UnsupportedType {
+        /// The name of the type
+        type_name: String,
+    },
+    /// A size calculated from a length in the data doesn't fit in a `usize`
+    #[error(
+        display = "The size of {} elements of {} bits each doesn't fit in a usize",
+        count,
+        element_bits
+    )]
+    SizeOverflow {
+        /// The number of elements
+        count: usize,
+        /// The size of every element in bits
+        element_bits: usize,
+    },
+    /// The value that was requested to be written doesn't fit in the specified number of bits
+    #[error(
+        display = "The value that was requested to be written needs {} bits, while a size of {} bits has been specified",
+        required,
+        bits
+    )]
+    ValueOutOfRange {
+        /// The number of bits needed to store the value
+        required: usize,
+        /// The requested number of bits to encode the value into
+        bits: usize,
+    },
+    /// The data violates a rule of the format being read
+    #[error(display = "Malformed data at position {}: {}", pos, reason)]
+    Malformed {
+        /// The position in the stream of the malformed data
+        pos: usize,
+        /// The rule that is violated
+        reason: &'static str,
+    },
+    /// An offset written with [`BitWriteStream::write_offset`] can't be filled in
+    #[error(
+        display = "The offset to label {} can't be resolved: {}",
+        label,
+        reason
+    )]
+    UnresolvedOffset {
+        /// The index of the label
+        label: usize,
+        /// Why the offset can't be resolved
+        reason: &'static str,
+    },
+    /// The read was cancelled through a [`CancellationToken`]
+    #[error(display = "The read was cancelled")]
+    Cancelled,
+    /// An error from the underlying reader or writer
+    #[error(display = "IO error: {}", _0)]
+    Io(std::io::Error),
+}
+
+impl BitError {
+    /// The category of the error
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bitbuffer::{BitReadBuffer, BitReadStream, ErrorKind, LittleEndian};
+    ///
+    /// let bytes = [0xFF];
+    /// let mut stream = BitReadStream::new(BitReadBuffer::new(&bytes, LittleEndian));
+    /// let err = stream.read::<u16>().unwrap_err();
+    /// assert_eq!(err.kind(), ErrorKind::NotEnoughData);
+    /// ```
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            BitError::TooManyBits { .. }
+            | BitError::IndexOutOfBounds { .. }
+            | BitError::SeekOutOfBounds { .. }
+            | BitError::InvalidControlSymbol { .. }
+            | BitError::LengthMismatch { .. }
+            | BitError::UnresolvedField { .. }
+            | BitError::UnsupportedType { .. }
+            | BitError::UnresolvedOffset { .. } => ErrorKind::InvalidInput,
+            BitError::NotEnoughData { .. } => ErrorKind::NotEnoughData,
+            BitError::StringToLong { .. }
+            | BitError::VarIntOverflow { .. }
+            | BitError::CapacityExceeded { .. }
+            | BitError::SizeOverflow { .. }
+            | BitError::ValueOutOfRange { .. } => ErrorKind::Overflow,
+            BitError::UnmatchedDiscriminant { .. }
+            | BitError::InvalidBitStuffing { .. }
+            | BitError::InvalidLineCode { .. }
+            | BitError::DisparityError { .. }
+            | BitError::TrailingData { .. }
+            | BitError::Malformed { .. } => ErrorKind::InvalidData,
+            BitError::Utf8Error(..) | BitError::Utf16Error { .. } => ErrorKind::InvalidString,
+            BitError::LimitExceeded { .. } | BitError::UnterminatedString { .. } => {
+                ErrorKind::LimitExceeded
+            }
+            BitError::Cancelled => ErrorKind::Cancelled,
+            BitError::Io(_) => ErrorKind::Io,
+        }
+    }
+}
+
+/// The category of a [`BitError`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ErrorKind {
+    /// The arguments don't make sense for the operation, like reading more bits than fit in the requested type
+    InvalidInput,
+    /// The stream ended before the requested bits could be read
+    NotEnoughData,
+    /// A value doesn't fit in the space it's read into or written to
+    Overflow,
+    /// The data doesn't follow the expected format
+    InvalidData,
+    /// A string isn't valid in its encoding
+    InvalidString,
+    /// A limit on the size of the read data was exceeded
+    LimitExceeded,
+    /// The read was cancelled
+    Cancelled,
+    /// An error from the underlying reader or writer
+    Io,
 }
 
 impl From<FromUtf8Error> for BitError {
@@ -170,6 +551,12 @@ impl From<FromUtf8Error> for BitError {
     }
 }
 
+impl From<std::io::Error> for BitError {
+    fn from(err: std::io::Error) -> Self {
+        BitError::Io(err)
+    }
+}
+
 /// Either the read bits in the requested format or a [`BitError`]
 pub type Result<T> = std::result::Result<T, BitError>;
 
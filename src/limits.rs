@@ -0,0 +1,88 @@
+use std::fmt::{self, Display, Formatter};
+
+/// Limits for reading untrusted input
+///
+/// The limits are consulted by the built-in [`BitRead`] and [`BitReadSized`] implementations and
+/// derived implementations, so a malicious length field can't cause huge allocations or unbounded recursion.
+///
+/// # Examples
+///
+/// ```
+/// use bitbuffer::{BitError, BitReadBuffer, BitReadStream, LittleEndian, ReadLimit, ReadLimits};
+///
+/// let bytes = vec![b'a'; 64];
+/// let mut stream = BitReadStream::new(BitReadBuffer::new(&bytes, LittleEndian));
+/// stream.set_limits(ReadLimits {
+///     max_string_length: 16,
+///     ..ReadLimits::default()
+/// });
+/// assert!(matches!(
+///     stream.read_sized::<String>(32),
+///     Err(BitError::LimitExceeded {
+///         limit: ReadLimit::StringLength,
+///         requested: 32,
+///         max: 16
+///     })
+/// ));
+/// ```
+///
+/// [`BitRead`]: crate::BitRead
+/// [`BitReadSized`]: crate::BitReadSized
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct ReadLimits {
+    /// The maximum length of strings in bytes
+    pub max_string_length: usize,
+    /// The maximum number of elements in a `Vec` or `HashMap`
+    pub max_elements: usize,
+    /// The maximum nesting depth of derived types
+    pub max_depth: usize,
+}
+
+impl ReadLimits {
+    /// No limits
+    pub const UNLIMITED: ReadLimits = ReadLimits {
+        max_string_length: usize::MAX,
+        max_elements: usize::MAX,
+        max_depth: usize::MAX,
+    };
+}
+
+impl Default for ReadLimits {
+    fn default() -> Self {
+        ReadLimits::UNLIMITED
+    }
+}
+
+/// The kind of limit that was exceeded
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum ReadLimit {
+    /// [`ReadLimits::max_string_length`]
+    StringLength,
+    /// [`ReadLimits::max_elements`]
+    Elements,
+    /// [`ReadLimits::max_depth`]
+    Depth,
+}
+
+impl ReadLimit {
+    /// Get the configured maximum for this limit
+    pub fn max(&self, limits: &ReadLimits) -> usize {
+        match self {
+            ReadLimit::StringLength => limits.max_string_length,
+            ReadLimit::Elements => limits.max_elements,
+            ReadLimit::Depth => limits.max_depth,
+        }
+    }
+}
+
+impl Display for ReadLimit {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            ReadLimit::StringLength => write!(f, "string length"),
+            ReadLimit::Elements => write!(f, "element count"),
+            ReadLimit::Depth => write!(f, "nesting depth"),
+        }
+    }
+}
@@ -0,0 +1,534 @@
+//! Line codes as used by serial links
+//!
+//! - [`Encoder8b10b`] and [`Decoder8b10b`] implement the 8b/10b code used by SerDes links like
+//!   SATA, PCI Express 2.0, DisplayPort and gigabit ethernet, including running disparity tracking.
+//! - [`Manchester`] implements Manchester coding, in both the IEEE 802.3 and G.E. Thomas conventions.
+//!
+//! Codes are read and written in transmission order, for 8b/10b the bits of a code are transmitted
+//! in the order `abcdeifghj`, starting with the least significant bit of the data.
+//!
+//! # Examples
+//!
+//! ```
+//! # use bitbuffer::Result;
+//! use bitbuffer::linecode::{align_to_comma, Decoder8b10b, Encoder8b10b, Symbol};
+//! use bitbuffer::{BitReadBuffer, BitReadStream, BitWriteStream, BigEndian};
+//!
+//! # fn main() -> Result<()> {
+//! let mut data = Vec::new();
+//! let mut stream = BitWriteStream::new(&mut data, BigEndian);
+//! stream.write_int(0b101u8, 3)?;
+//! let mut encoder = Encoder8b10b::new();
+//! encoder.encode(&mut stream, Symbol::K28_5)?;
+//! encoder.encode(&mut stream, Symbol::Data(0x4A))?;
+//!
+//! let mut stream = BitReadStream::new(BitReadBuffer::new(&data, BigEndian));
+//! align_to_comma(&mut stream)?;
+//! let mut decoder = Decoder8b10b::new();
+//! assert_eq!(decoder.decode(&mut stream)?, Symbol::K28_5);
+//! assert_eq!(decoder.decode(&mut stream)?, Symbol::Data(0x4A));
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::readstream::checked_bits;
+use crate::{BitError, BitReadBuffer, BitReadStream, BitWriteStream, Endianness, Result};
+
+/// The running disparity of an 8b/10b encoded stream
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Disparity {
+    /// More zeros than ones have been transmitted, the next code will contain more or an equal number of ones
+    #[default]
+    Negative,
+    /// More ones than zeros have been transmitted, the next code will contain more or an equal number of zeros
+    Positive,
+}
+
+/// A symbol encoded as a single 8b/10b code
+///
+/// Control symbols `K.x.y` are represented by the byte `y << 5 | x`, the same as data symbols.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Symbol {
+    /// A data byte
+    Data(u8),
+    /// A control symbol
+    Control(u8),
+}
+
+impl Symbol {
+    /// K.28.0
+    pub const K28_0: Symbol = Symbol::Control(0x1C);
+    /// K.28.1, contains a comma
+    pub const K28_1: Symbol = Symbol::Control(0x3C);
+    /// K.28.2
+    pub const K28_2: Symbol = Symbol::Control(0x5C);
+    /// K.28.3
+    pub const K28_3: Symbol = Symbol::Control(0x7C);
+    /// K.28.4
+    pub const K28_4: Symbol = Symbol::Control(0x9C);
+    /// K.28.5, contains a comma and is the most commonly used alignment symbol
+    pub const K28_5: Symbol = Symbol::Control(0xBC);
+    /// K.28.6
+    pub const K28_6: Symbol = Symbol::Control(0xDC);
+    /// K.28.7, contains a comma
+    pub const K28_7: Symbol = Symbol::Control(0xFC);
+    /// K.23.7
+    pub const K23_7: Symbol = Symbol::Control(0xF7);
+    /// K.27.7
+    pub const K27_7: Symbol = Symbol::Control(0xFB);
+    /// K.29.7
+    pub const K29_7: Symbol = Symbol::Control(0xFD);
+    /// K.30.7
+    pub const K30_7: Symbol = Symbol::Control(0xFE);
+}
+
+/// 5b/6b codes `abcdei` for negative running disparity, indexed by `EDCBA`
+const CODES_6B: [u16; 32] = [
+    0b100111, 0b011101, 0b101101, 0b110001, 0b110101, 0b101001, 0b011001, 0b111000, 0b111001,
+    0b100101, 0b010101, 0b110100, 0b001101, 0b101100, 0b011100, 0b010111, 0b011011, 0b100011,
+    0b010011, 0b110010, 0b001011, 0b101010, 0b011010, 0b111010, 0b110011, 0b100110, 0b010110,
+    0b110110, 0b001110, 0b101110, 0b011110, 0b101011,
+];
+
+/// 5b/6b code for K.28 with negative running disparity
+const CODE_6B_K28: u16 = 0b001111;
+
+/// 3b/4b data codes `fghj` for negative running disparity, indexed by `HGF`
+const CODES_4B: [u16; 8] = [
+    0b1011, 0b1001, 0b0101, 0b1100, 0b1101, 0b1010, 0b0110, 0b1110,
+];
+
+/// 3b/4b control codes `fghj` for negative running disparity, indexed by `HGF`
+const CODES_4B_CONTROL: [u16; 8] = [
+    0b1011, 0b0110, 0b1010, 0b1100, 0b1101, 0b0101, 0b1001, 0b0111,
+];
+
+/// Alternate x.7 code, used to prevent runs of five identical bits
+const CODE_4B_A7: u16 = 0b0111;
+
+/// Encode a sub block, returning the code and the running disparity after the code
+///
+/// `code` is the code for negative disparity, for positive disparity the complement is used if the
+/// code is unbalanced or `always_complement` is set.
+const fn encode_block(
+    code: u16,
+    bits: u32,
+    always_complement: bool,
+    rd: Disparity,
+) -> (u16, Disparity) {
+    let ones = code.count_ones();
+    let code = if matches!(rd, Disparity::Positive) && (always_complement || ones * 2 != bits) {
+        !code & ((1 << bits) - 1)
+    } else {
+        code
+    };
+    (code, block_disparity(code, bits, rd))
+}
+
+/// The running disparity after a sub block
+const fn block_disparity(code: u16, bits: u32, rd: Disparity) -> Disparity {
+    let ones = code.count_ones();
+    if ones * 2 > bits {
+        Disparity::Positive
+    } else if ones * 2 < bits {
+        Disparity::Negative
+    } else {
+        rd
+    }
+}
+
+/// Encode a symbol, returning `None` for invalid control symbols
+const fn encode_symbol(symbol: Symbol, rd: Disparity) -> Option<(u16, Disparity)> {
+    let (value, control) = match symbol {
+        Symbol::Data(value) => (value, false),
+        Symbol::Control(value) => (value, true),
+    };
+    let x = (value & 0x1F) as usize;
+    let y = (value >> 5) as usize;
+
+    if control && x != 28 && !(y == 7 && matches!(x, 23 | 27 | 29 | 30)) {
+        return None;
+    }
+
+    // D.07 is balanced, but still alternates between two codes
+    let six = if control && x == 28 {
+        CODE_6B_K28
+    } else {
+        CODES_6B[x]
+    };
+    let (six, rd) = encode_block(six, 6, x == 7 && !control, rd);
+
+    let (four, always_complement) = if control {
+        (CODES_4B_CONTROL[y], true)
+    } else if y == 7
+        && ((matches!(rd, Disparity::Negative) && matches!(x, 17 | 18 | 20))
+            || (matches!(rd, Disparity::Positive) && matches!(x, 11 | 13 | 14)))
+    {
+        (CODE_4B_A7, false)
+    } else {
+        // D.x.3 is balanced, but still alternates between two codes
+        (CODES_4B[y], y == 3)
+    };
+    let (four, rd) = encode_block(four, 4, always_complement, rd);
+
+    Some((six << 4 | four, rd))
+}
+
+const DECODE_VALID_NEGATIVE: u16 = 1 << 9;
+const DECODE_VALID_POSITIVE: u16 = 1 << 10;
+const DECODE_CONTROL: u16 = 1 << 8;
+
+/// Symbols for every 10 bit code, with flags for the running disparities the code is valid for
+static DECODE_TABLE: [u16; 1024] = {
+    let mut table = [0; 1024];
+    let mut i = 0;
+    while i < 512 {
+        let value = (i & 0xFF) as u8;
+        let symbol = if i >= 256 {
+            Symbol::Control(value)
+        } else {
+            Symbol::Data(value)
+        };
+        if let Some((code, _)) = encode_symbol(symbol, Disparity::Negative) {
+            table[code as usize] |= i as u16 | DECODE_VALID_NEGATIVE;
+        }
+        if let Some((code, _)) = encode_symbol(symbol, Disparity::Positive) {
+            table[code as usize] |= i as u16 | DECODE_VALID_POSITIVE;
+        }
+        i += 1;
+    }
+    table
+};
+
+/// Convert between the `abcdeifghj` notation and the order of the bits when read as integer
+fn code_order<E: Endianness>(code: u16) -> u16 {
+    if E::is_le() {
+        code.reverse_bits() >> 6
+    } else {
+        code
+    }
+}
+
+/// Encoder for 8b/10b codes
+///
+/// # Examples
+///
+/// ```
+/// # use bitbuffer::Result;
+/// use bitbuffer::linecode::{Disparity, Encoder8b10b, Symbol};
+/// use bitbuffer::{BitWriteStream, BigEndian};
+///
+/// # fn main() -> Result<()> {
+/// let mut data = Vec::new();
+/// let mut stream = BitWriteStream::new(&mut data, BigEndian);
+/// let mut encoder = Encoder8b10b::new();
+/// encoder.encode(&mut stream, Symbol::K28_5)?;
+/// assert_eq!(encoder.disparity(), Disparity::Positive);
+/// assert_eq!(data, [0b0011_1110, 0b1000_0000]);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Encoder8b10b {
+    disparity: Disparity,
+}
+
+impl Encoder8b10b {
+    /// Create an encoder starting with negative running disparity
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create an encoder starting with the provided running disparity
+    pub fn with_disparity(disparity: Disparity) -> Self {
+        Encoder8b10b { disparity }
+    }
+
+    /// The current running disparity
+    pub fn disparity(&self) -> Disparity {
+        self.disparity
+    }
+
+    /// Write the 10 bit code for a symbol
+    ///
+    /// # Errors
+    ///
+    /// - [`ReadError::InvalidControlSymbol`]: the symbol isn't one of the 12 valid control symbols
+    ///
+    /// [`ReadError::InvalidControlSymbol`]: enum.ReadError.html#variant.InvalidControlSymbol
+    pub fn encode<E: Endianness>(
+        &mut self,
+        stream: &mut BitWriteStream<E>,
+        symbol: Symbol,
+    ) -> Result<()> {
+        let (code, disparity) = match encode_symbol(symbol, self.disparity) {
+            Some(result) => result,
+            None => {
+                let (Symbol::Data(value) | Symbol::Control(value)) = symbol;
+                return Err(BitError::InvalidControlSymbol { value });
+            }
+        };
+        stream.write_int(code_order::<E>(code), 10)?;
+        self.disparity = disparity;
+        Ok(())
+    }
+
+    /// Write the codes for a slice of data bytes
+    pub fn encode_bytes<E: Endianness>(
+        &mut self,
+        stream: &mut BitWriteStream<E>,
+        bytes: &[u8],
+    ) -> Result<()> {
+        for byte in bytes {
+            self.encode(stream, Symbol::Data(*byte))?;
+        }
+        Ok(())
+    }
+}
+
+/// Decoder for 8b/10b codes
+///
+/// # Examples
+///
+/// ```
+/// # use bitbuffer::Result;
+/// use bitbuffer::linecode::{Decoder8b10b, Symbol};
+/// use bitbuffer::{BitReadBuffer, BitReadStream, BigEndian};
+///
+/// # fn main() -> Result<()> {
+/// let data = [0b0011_1110, 0b1001_0101, 0b0100_0000];
+/// let mut stream = BitReadStream::new(BitReadBuffer::new(&data, BigEndian));
+/// let mut decoder = Decoder8b10b::new();
+/// assert_eq!(decoder.decode(&mut stream)?, Symbol::K28_5);
+/// assert_eq!(decoder.decode(&mut stream)?, Symbol::Data(0x0A));
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Decoder8b10b {
+    disparity: Disparity,
+}
+
+impl Decoder8b10b {
+    /// Create a decoder expecting negative running disparity
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a decoder expecting the provided running disparity
+    pub fn with_disparity(disparity: Disparity) -> Self {
+        Decoder8b10b { disparity }
+    }
+
+    /// The current running disparity
+    pub fn disparity(&self) -> Disparity {
+        self.disparity
+    }
+
+    /// Set the running disparity, e.g. to re-synchronize after a disparity error
+    pub fn set_disparity(&mut self, disparity: Disparity) {
+        self.disparity = disparity;
+    }
+
+    /// Read a 10 bit code and decode it
+    ///
+    /// The stream and the running disparity are only advanced if a valid code was read.
+    ///
+    /// # Errors
+    ///
+    /// - [`ReadError::NotEnoughData`]: not enough bits available in the stream
+    /// - [`ReadError::InvalidLineCode`]: the read code isn't a valid 8b/10b code
+    /// - [`ReadError::DisparityError`]: the read code isn't valid for the current running disparity
+    ///
+    /// [`ReadError::NotEnoughData`]: enum.ReadError.html#variant.NotEnoughData
+    /// [`ReadError::InvalidLineCode`]: enum.ReadError.html#variant.InvalidLineCode
+    /// [`ReadError::DisparityError`]: enum.ReadError.html#variant.DisparityError
+    pub fn decode<E: Endianness>(&mut self, stream: &mut BitReadStream<E>) -> Result<Symbol> {
+        let pos = stream.pos();
+        let code = code_order::<E>(stream.clone().read_int(10)?);
+        let entry = DECODE_TABLE[code as usize];
+
+        let valid_flag = match self.disparity {
+            Disparity::Negative => DECODE_VALID_NEGATIVE,
+            Disparity::Positive => DECODE_VALID_POSITIVE,
+        };
+        if entry & (DECODE_VALID_NEGATIVE | DECODE_VALID_POSITIVE) == 0 {
+            return Err(BitError::InvalidLineCode { code, pos });
+        }
+        if entry & valid_flag == 0 {
+            return Err(BitError::DisparityError { code, pos });
+        }
+
+        stream.skip_bits(10)?;
+        let disparity = block_disparity(code >> 4, 6, self.disparity);
+        self.disparity = block_disparity(code & 0xF, 4, disparity);
+
+        let value = entry as u8;
+        Ok(if entry & DECODE_CONTROL != 0 {
+            Symbol::Control(value)
+        } else {
+            Symbol::Data(value)
+        })
+    }
+
+    /// Decode `count` data bytes
+    ///
+    /// Any control symbol in the data results in a [`ReadError::InvalidLineCode`],
+    /// the stream and the running disparity are only advanced if all bytes could be decoded.
+    ///
+    /// [`ReadError::InvalidLineCode`]: enum.ReadError.html#variant.InvalidLineCode
+    pub fn decode_bytes<E: Endianness>(
+        &mut self,
+        stream: &mut BitReadStream<E>,
+        count: usize,
+    ) -> Result<Vec<u8>> {
+        let start = stream.pos();
+        let disparity = self.disparity;
+        let result = self.decode_bytes_inner(stream, count);
+        if result.is_err() {
+            stream.set_pos(start)?;
+            self.disparity = disparity;
+        }
+        result
+    }
+
+    fn decode_bytes_inner<E: Endianness>(
+        &mut self,
+        stream: &mut BitReadStream<E>,
+        count: usize,
+    ) -> Result<Vec<u8>> {
+        let mut bytes = Vec::with_capacity(count);
+        for _ in 0..count {
+            let pos = stream.pos();
+            match self.decode(stream)? {
+                Symbol::Data(byte) => bytes.push(byte),
+                Symbol::Control(_) => {
+                    stream.set_pos(pos)?;
+                    let code = code_order::<E>(stream.read_int(10)?);
+                    return Err(BitError::InvalidLineCode { code, pos });
+                }
+            }
+        }
+        Ok(bytes)
+    }
+}
+
+/// Advance the stream to the start of the next 8b/10b code containing a comma
+///
+/// A comma is the sequence `0011111` or `1100000` at the start of a code, which only occurs
+/// in the K.28.1, K.28.5 and K.28.7 control symbols and can be used to find the code boundaries.
+///
+/// # Errors
+///
+/// - [`ReadError::NotEnoughData`]: no comma found in the remaining data, the stream is not advanced
+///
+/// [`ReadError::NotEnoughData`]: enum.ReadError.html#variant.NotEnoughData
+pub fn align_to_comma<E: Endianness>(stream: &mut BitReadStream<E>) -> Result<()> {
+    const COMMA: u8 = 0b0011111;
+
+    let mut search = stream.clone();
+    let mut window = 0u8;
+    while search.bits_left() > 0 {
+        window = (window << 1 | search.read_bool()? as u8) & 0x7F;
+        if search.pos() >= 7 && (window == COMMA || window == !COMMA & 0x7F) {
+            return stream.skip_bits(search.pos() - 7);
+        }
+    }
+    let bits_left = search.pos();
+    Err(BitError::NotEnoughData {
+        requested: bits_left + 1,
+        bits_left,
+    })
+}
+
+/// Manchester coding conventions
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Manchester {
+    /// IEEE 802.3 convention, a 0 is encoded as `10` and a 1 as `01`
+    Ieee,
+    /// G.E. Thomas convention, a 0 is encoded as `01` and a 1 as `10`
+    Thomas,
+}
+
+impl Manchester {
+    fn encode_bit(self, bit: bool) -> u8 {
+        match (self, bit) {
+            (Manchester::Ieee, false) | (Manchester::Thomas, true) => 0b10,
+            (Manchester::Ieee, true) | (Manchester::Thomas, false) => 0b01,
+        }
+    }
+
+    /// Write all remaining bits from the input to the output, encoding every bit as two bits
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use bitbuffer::Result;
+    /// use bitbuffer::linecode::Manchester;
+    /// use bitbuffer::{BitReadBuffer, BitReadStream, BitWriteStream, BigEndian};
+    ///
+    /// # fn main() -> Result<()> {
+    /// let input = [0b1010_0011];
+    /// let input = BitReadStream::new(BitReadBuffer::new(&input, BigEndian));
+    ///
+    /// let mut data = Vec::new();
+    /// let mut stream = BitWriteStream::new(&mut data, BigEndian);
+    /// Manchester::Ieee.encode(&input, &mut stream)?;
+    /// assert_eq!(data, [0b0110_0110, 0b1010_0101]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn encode<E: Endianness>(
+        self,
+        input: &BitReadStream<E>,
+        output: &mut BitWriteStream<E>,
+    ) -> Result<()> {
+        let mut input = input.clone();
+        while input.bits_left() > 0 {
+            let code = self.encode_bit(input.read_bool()?);
+            // write the pair in transmission order regardless of endianness
+            output.write_bool(code & 0b10 != 0)?;
+            output.write_bool(code & 0b01 != 0)?;
+        }
+        Ok(())
+    }
+
+    /// Read and decode `count` bits of data, reading two bits from the input for every decoded bit
+    ///
+    /// The input is only advanced if the data could be decoded successfully.
+    ///
+    /// # Errors
+    ///
+    /// - [`ReadError::NotEnoughData`]: not enough bits available in the stream
+    /// - [`ReadError::InvalidLineCode`]: a pair of bits without transition was found
+    ///
+    /// [`ReadError::NotEnoughData`]: enum.ReadError.html#variant.NotEnoughData
+    /// [`ReadError::InvalidLineCode`]: enum.ReadError.html#variant.InvalidLineCode
+    pub fn decode<E: Endianness>(
+        self,
+        input: &mut BitReadStream<E>,
+        count: usize,
+    ) -> Result<BitReadStream<'static, E>> {
+        input.check_read(checked_bits(count, 2)?)?;
+        let mut read = input.clone();
+        let mut data = Vec::with_capacity(count.div_ceil(8));
+        let mut output = BitWriteStream::new(&mut data, E::endianness());
+        for _ in 0..count {
+            let pos = read.pos();
+            let code = (read.read_bool()? as u8) << 1 | read.read_bool()? as u8;
+            if code == 0b00 || code == 0b11 {
+                return Err(BitError::InvalidLineCode {
+                    code: code as u16,
+                    pos,
+                });
+            }
+            output.write_bool(code == self.encode_bit(true))?;
+        }
+        input.set_pos(read.pos())?;
+        let mut stream = BitReadStream::new(BitReadBuffer::new_owned(data, E::endianness()));
+        stream.read_bits(count)
+    }
+}
@@ -0,0 +1,156 @@
+/// Read a number of values from a stream in one go
+///
+/// Every value is written as `type: bits` to read an integer of `bits` bits, or just `type` to read it using
+/// [`BitRead`](crate::BitRead). The values are returned as a tuple, even if only a single value is read.
+///
+/// This is useful for small headers that don't warrant defining a struct.
+///
+/// # Errors
+///
+/// Any error from reading one of the values, the stream is not advanced if an error is returned.
+///
+/// # Examples
+///
+/// ```
+/// # use bitbuffer::Result;
+/// use bitbuffer::{read_bits, BitReadBuffer, BitReadStream, BigEndian};
+///
+/// # fn main() -> Result<()> {
+/// let bytes = [0b0110_1001, 0x12, 0x34, 0xFF];
+/// let mut stream = BitReadStream::new(BitReadBuffer::new(&bytes, BigEndian));
+/// let (version, flags, length, done) = read_bits!(stream, u8: 3, u8: 5, u16: 16, bool)?;
+/// assert_eq!((version, flags, length, done), (3, 9, 0x1234, true));
+/// # Ok(())
+/// # }
+/// ```
+#[macro_export]
+macro_rules! read_bits {
+    ($stream:expr, $($ty:ty $(: $bits:expr)?),+ $(,)?) => {{
+        let start = $stream.pos();
+        let result = (|| -> $crate::Result<_> {
+            ::std::result::Result::Ok(($($crate::__read_bits_value!($stream, $ty $(, $bits)?),)+))
+        })();
+        if result.is_err() {
+            let _ = $stream.set_pos(start);
+        }
+        result
+    }};
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __read_bits_value {
+    ($stream:expr, $ty:ty) => {
+        $stream.read::<$ty>()?
+    };
+    ($stream:expr, $ty:ty, $bits:expr) => {
+        $stream.read_int::<$ty>($bits)?
+    };
+}
+
+/// Write a number of values to a stream in one go
+///
+/// Every value is written as `value => bits` to write an integer as `bits` bits, or just `value` to write it using
+/// [`BitWrite`](crate::BitWrite).
+///
+/// This is the counterpart of [`read_bits!`].
+///
+/// # Errors
+///
+/// Any error from writing one of the values, nothing is written if an error is returned.
+///
+/// # Examples
+///
+/// ```
+/// # use bitbuffer::Result;
+/// use bitbuffer::{write_bits, BitWriteStream, BigEndian};
+///
+/// # fn main() -> Result<()> {
+/// let mut data = Vec::new();
+/// let mut stream = BitWriteStream::new(&mut data, BigEndian);
+/// let (version, flags, length) = (3u8, 9u8, 0x1234u16);
+/// write_bits!(stream, version => 3, flags => 5, length => 16, true)?;
+/// assert_eq!(stream.bit_len(), 25);
+/// # drop(stream);
+/// assert_eq!(data, [0b0110_1001, 0x12, 0x34, 0x80]);
+/// # Ok(())
+/// # }
+/// ```
+#[macro_export]
+macro_rules! write_bits {
+    ($stream:expr, $($value:expr $(=> $bits:expr)?),+ $(,)?) => {{
+        let start = $stream.bit_len();
+        let result = (|| -> $crate::Result<()> {
+            $($crate::__write_bits_value!($stream, $value $(, $bits)?);)+
+            ::std::result::Result::Ok(())
+        })();
+        if result.is_err() {
+            $stream.truncate(start);
+        }
+        result
+    }};
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __write_bits_value {
+    ($stream:expr, $value:expr) => {
+        $stream.write(&$value)?
+    };
+    ($stream:expr, $value:expr, $bits:expr) => {
+        $stream.write_int($value, $bits)?
+    };
+}
+
+/// Assert that the bits of two values are equal
+///
+/// Both sides can be byte slices, arrays or vectors, a [`BitWriteStream`](crate::BitWriteStream), a
+/// [`BitReadStream`](crate::BitReadStream) or a [`BitReadBuffer`](crate::BitReadBuffer). For a read stream all bits
+/// of the stream are compared, regardless of the current position.
+///
+/// Expected data is often easiest to write with [`BitReadBuffer::from_bit_string`](crate::BitReadBuffer::from_bit_string)
+/// or [`BitReadBuffer::from_hex`](crate::BitReadBuffer::from_hex).
+///
+/// On failure the bytes around the first difference are printed in binary, with the first differing bit marked.
+/// Bytes are printed with the most significant bit first, for little endian streams the marker takes into account
+/// that the first bit of a byte is the least significant bit.
+///
+/// # Examples
+///
+/// ```
+/// # use bitbuffer::Result;
+/// use bitbuffer::{assert_bit_eq, BitWriteStream, BigEndian};
+///
+/// # fn main() -> Result<()> {
+/// let mut data = Vec::new();
+/// let mut stream = BitWriteStream::new(&mut data, BigEndian);
+/// stream.write_int(0b101u8, 3)?;
+/// stream.write_int(0x1234u16, 16)?;
+/// assert_bit_eq!([0b1010_0010, 0b0100_0110, 0b1000_0000], stream);
+/// # Ok(())
+/// # }
+/// ```
+///
+/// A failing assertion prints
+///
+/// ```text
+/// bits differ at bit 13 (byte 1), expected 24 bits, got 19 bits
+///   offset | expected  | actual
+///        0 | 1010_0010 | 1010_0010
+///        1 | 0100_0010 | 0100_0110
+///          |       ^   |       ^
+///        2 | 1000_0000 | 1000_0000
+/// ```
+#[macro_export]
+macro_rules! assert_bit_eq {
+    ($expected:expr, $actual:expr $(,)?) => {
+        if let ::std::result::Result::Err(message) = $crate::__bit_diff(&$expected, &$actual) {
+            ::std::panic!("{}", message);
+        }
+    };
+    ($expected:expr, $actual:expr, $($arg:tt)+) => {
+        if let ::std::result::Result::Err(message) = $crate::__bit_diff(&$expected, &$actual) {
+            ::std::panic!("{}: {}", ::std::format_args!($($arg)+), message);
+        }
+    };
+}
@@ -0,0 +1,188 @@
+//! Mode S and ADS-B downlink messages
+//!
+//! Mode S transponders reply with 56 or 112 bit messages, sent most significant bit first. Every message starts
+//! with the 5 bit downlink format and ends with a 24 bit parity field computed with [`CRC_24_MODE_S`].
+//! For extended squitters (DF 17 and 18), which carry ADS-B data, the parity field is the plain crc,
+//! for most other formats the crc is XOR-ed with the address of the aircraft.
+//!
+//! # Examples
+//!
+//! ```
+//! # use bitbuffer::Result;
+//! use bitbuffer::mode_s::{read_callsign, read_extended_squitter};
+//! use bitbuffer::{BigEndian, BitReadBuffer, BitReadStream};
+//!
+//! # fn main() -> Result<()> {
+//! let bytes = [
+//!     0x8D, 0x48, 0x40, 0xD6, 0x20, 0x2C, 0xC3, 0x71, 0xC3, 0x2C, 0xE0, 0x57, 0x60, 0x98,
+//! ];
+//! let mut stream = BitReadStream::new(BitReadBuffer::new(&bytes, BigEndian));
+//! let mut squitter = read_extended_squitter(&mut stream)?;
+//! assert_eq!(squitter.address, 0x48_40D6);
+//! assert_eq!(squitter.type_code, 4);
+//! squitter.data.skip_bits(3)?;
+//! assert_eq!(read_callsign(&mut squitter.data)?, "KLM1023");
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! [`CRC_24_MODE_S`]: crate::crc::CRC_24_MODE_S
+
+use crate::crc::{Crc, CRC_24_MODE_S};
+use crate::{BigEndian, BitError, BitReadStream, Result};
+
+/// The length of the messages with a downlink format below 16
+pub const SHORT_MESSAGE_BITS: usize = 56;
+
+/// The length of the messages with a downlink format of 16 or higher
+pub const LONG_MESSAGE_BITS: usize = 112;
+
+/// The characters used by aircraft identification messages, `#` marks unused codes
+const CALLSIGN_CHARACTERS: &[u8; 64] =
+    b"#ABCDEFGHIJKLMNOPQRSTUVWXYZ##### ###############0123456789######";
+
+/// Get the length in bits of a message with the given downlink format
+pub fn message_bits(downlink_format: u8) -> usize {
+    if downlink_format < 16 {
+        SHORT_MESSAGE_BITS
+    } else {
+        LONG_MESSAGE_BITS
+    }
+}
+
+/// A Mode S downlink message
+#[derive(Debug, Clone)]
+pub struct Message<'a> {
+    /// The 5 bit downlink format, 24 for all comm-D messages
+    pub downlink_format: u8,
+    /// The bits between the downlink format and the parity field
+    pub fields: BitReadStream<'a, BigEndian>,
+    /// The parity field XOR-ed with the crc of the message
+    ///
+    /// This is zero for valid extended squitters, the aircraft address for address/parity formats
+    /// like DF 4, 5, 20 and 21 and the interrogator code for all-call replies.
+    pub address_parity: u32,
+}
+
+/// Read a 56 or 112 bit message, depending on the downlink format
+///
+/// # Errors
+///
+/// - [`ReadError::NotEnoughData`]: not enough bits available in the stream
+///
+/// [`ReadError::NotEnoughData`]: crate::BitError::NotEnoughData
+pub fn read_message<'a>(stream: &mut BitReadStream<'a, BigEndian>) -> Result<Message<'a>> {
+    stream.restore_on_error(|stream| {
+        let downlink_format: u8 = stream.clone().read_int(5)?;
+        let bits = message_bits(downlink_format);
+        let mut message = stream.read_bits(bits)?;
+        let mut crc = Crc::new(&CRC_24_MODE_S);
+        crc.update_stream(&mut message.clone(), bits - 24)?;
+        message.skip_bits(5)?;
+        let fields = message.read_bits(bits - 29)?;
+        let parity: u32 = message.read_int(24)?;
+        Ok(Message {
+            downlink_format: downlink_format.min(24),
+            fields,
+            address_parity: parity ^ crc.finalize() as u32,
+        })
+    })
+}
+
+/// An extended squitter, a DF 17 or DF 18 message containing ADS-B data
+#[derive(Debug, Clone)]
+pub struct ExtendedSquitter<'a> {
+    /// The downlink format, 17 for transponders and 18 for non-transponder devices
+    pub downlink_format: u8,
+    /// The 3 bit capability for DF 17 or control field for DF 18
+    pub capability: u8,
+    /// The 24 bit aircraft address
+    pub address: u32,
+    /// The 5 bit type code of the ADS-B message
+    pub type_code: u8,
+    /// The 51 bits of the ADS-B message following the type code
+    pub data: BitReadStream<'a, BigEndian>,
+}
+
+/// Read an extended squitter, checking the crc
+///
+/// # Errors
+///
+/// - [`ReadError::NotEnoughData`]: not enough bits available in the stream
+/// - [`ReadError::Malformed`]: the message isn't an extended squitter or the crc doesn't match
+///
+/// [`ReadError::NotEnoughData`]: crate::BitError::NotEnoughData
+/// [`ReadError::Malformed`]: crate::BitError::Malformed
+pub fn read_extended_squitter<'a>(
+    stream: &mut BitReadStream<'a, BigEndian>,
+) -> Result<ExtendedSquitter<'a>> {
+    let pos = stream.pos();
+    stream.restore_on_error(|stream| {
+        let mut message = read_message(stream)?;
+        if !matches!(message.downlink_format, 17 | 18) {
+            return Err(BitError::Malformed {
+                pos,
+                reason: "the message isn't an extended squitter",
+            });
+        }
+        if message.address_parity != 0 {
+            return Err(BitError::Malformed {
+                pos,
+                reason: "the message crc doesn't match",
+            });
+        }
+        let capability = message.fields.read_int(3)?;
+        let address = message.fields.read_int(24)?;
+        let type_code = message.fields.read_int(5)?;
+        Ok(ExtendedSquitter {
+            downlink_format: message.downlink_format,
+            capability,
+            address,
+            type_code,
+            data: message.fields.read_bits(51)?,
+        })
+    })
+}
+
+/// Read the 8 character callsign of an aircraft identification message, without trailing spaces
+///
+/// # Errors
+///
+/// - [`ReadError::NotEnoughData`]: not enough bits available in the stream
+///
+/// [`ReadError::NotEnoughData`]: crate::BitError::NotEnoughData
+pub fn read_callsign(stream: &mut BitReadStream<BigEndian>) -> Result<String> {
+    stream.restore_on_error(|stream| {
+        let mut callsign = String::with_capacity(8);
+        for _ in 0..8 {
+            let code: usize = stream.read_int(6)?;
+            callsign.push(CALLSIGN_CHARACTERS[code] as char);
+        }
+        callsign.truncate(callsign.trim_end().len());
+        Ok(callsign)
+    })
+}
+
+/// Decode the 13 bit altitude code used by surveillance replies, in feet
+///
+/// Returns `None` when the altitude is unavailable, metric or Gillham coded.
+pub fn ac13_altitude(code: u16) -> Option<i32> {
+    let metric = code & 0x40 != 0;
+    if metric {
+        return None;
+    }
+    // remove the M bit and decode the remaining 12 bits
+    ac12_altitude((code >> 7) << 6 | (code & 0x3F))
+}
+
+/// Decode the 12 bit altitude code used by airborne position messages, in feet
+///
+/// Returns `None` when the altitude is unavailable or Gillham coded.
+pub fn ac12_altitude(code: u16) -> Option<i32> {
+    let q = code & 0x10 != 0;
+    if !q {
+        return None;
+    }
+    let increments = ((code >> 5) << 4 | (code & 0xF)) as i32;
+    Some(increments * 25 - 1000)
+}
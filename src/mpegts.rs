@@ -0,0 +1,257 @@
+//! MPEG transport stream packets and PSI sections
+//!
+//! Helpers for splitting a transport stream, as used by DVB, ATSC and IPTV, into its 188 byte packets and for
+//! reading the program specific information sections (PAT, PMT, SDT, ...) carried in their payload.
+//!
+//! Transport streams are most significant bit first, so all helpers work on [`BigEndian`] streams.
+//!
+//! # Examples
+//!
+//! ```
+//! # use bitbuffer::Result;
+//! use bitbuffer::mpegts::{packets, read_section, skip_pointer_field, PACKET_SIZE};
+//! use bitbuffer::{BitReadBuffer, BitReadStream, BigEndian};
+//!
+//! # fn main() -> Result<()> {
+//! // a single packet containing a program association table
+//! let mut data = vec![
+//!     0x47, 0x40, 0x00, 0x10, 0x00, 0x00, 0xB0, 0x0D, 0x00, 0x01, 0xC1, 0x00, 0x00, 0x00, 0x01,
+//!     0xF0, 0x00, 0x2A, 0xB1, 0x04, 0xB2,
+//! ];
+//! data.resize(PACKET_SIZE, 0xFF);
+//!
+//! let stream = BitReadStream::new(BitReadBuffer::new(&data, BigEndian));
+//! for packet in packets(stream) {
+//!     let packet = packet?;
+//!     assert_eq!(packet.header.pid, 0);
+//!     let mut payload = packet.payload.unwrap();
+//!     skip_pointer_field(&mut payload)?;
+//!     let section = read_section(&mut payload)?;
+//!     assert_eq!(section.table_id, 0);
+//!     assert_eq!(section.body.bit_len(), 9 * 8);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::crc::{Crc, CRC_32_MPEG_2};
+use crate::{BigEndian, BitError, BitReadStream, Result};
+
+/// The size of a transport stream packet in bytes
+pub const PACKET_SIZE: usize = 188;
+
+/// The byte every packet starts with
+pub const SYNC_BYTE: u8 = 0x47;
+
+/// The pid of null packets, used for padding the stream
+pub const NULL_PID: u16 = 0x1FFF;
+
+/// The maximum section length of PSI sections
+const MAX_SECTION_LENGTH: usize = 1021;
+
+/// The 4 byte header of a transport stream packet
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct PacketHeader {
+    /// Set by the demodulator when the packet contains uncorrectable errors
+    pub transport_error: bool,
+    /// Whether a PES packet or PSI section starts in the payload of this packet
+    pub payload_unit_start: bool,
+    /// Whether the packet has a higher priority than other packets with the same pid
+    pub priority: bool,
+    /// The 13 bit packet identifier
+    pub pid: u16,
+    /// The 2 bit transport scrambling control
+    pub scrambling: u8,
+    /// Whether the packet contains an adaptation field
+    pub has_adaptation_field: bool,
+    /// Whether the packet contains a payload
+    pub has_payload: bool,
+    /// The 4 bit counter incremented for every packet with a payload on the same pid
+    pub continuity_counter: u8,
+}
+
+/// A transport stream packet
+#[derive(Debug, Clone)]
+pub struct Packet<'a> {
+    /// The packet header
+    pub header: PacketHeader,
+    /// The contents of the adaptation field, without the length byte
+    pub adaptation_field: Option<BitReadStream<'a, BigEndian>>,
+    /// The payload of the packet
+    pub payload: Option<BitReadStream<'a, BigEndian>>,
+}
+
+/// Read a 188 byte transport stream packet
+///
+/// # Errors
+///
+/// - [`ReadError::NotEnoughData`]: not enough bits available in the stream
+/// - [`ReadError::Malformed`]: the packet doesn't start with the sync byte or the adaptation field doesn't fit in the packet
+///
+/// [`ReadError::NotEnoughData`]: crate::BitError::NotEnoughData
+/// [`ReadError::Malformed`]: crate::BitError::Malformed
+pub fn read_packet<'a>(stream: &mut BitReadStream<'a, BigEndian>) -> Result<Packet<'a>> {
+    let pos = stream.pos();
+    stream.restore_on_error(|stream| {
+        let mut packet = stream.read_bits(PACKET_SIZE * 8)?;
+        if packet.read_int::<u8>(8)? != SYNC_BYTE {
+            return Err(BitError::Malformed {
+                pos,
+                reason: "transport stream packet doesn't start with the sync byte",
+            });
+        }
+        let header = PacketHeader {
+            transport_error: packet.read_bool()?,
+            payload_unit_start: packet.read_bool()?,
+            priority: packet.read_bool()?,
+            pid: packet.read_int(13)?,
+            scrambling: packet.read_int(2)?,
+            has_adaptation_field: packet.read_bool()?,
+            has_payload: packet.read_bool()?,
+            continuity_counter: packet.read_int(4)?,
+        };
+        let adaptation_field = if header.has_adaptation_field {
+            let length: u8 = packet.read_int(8)?;
+            if length as usize * 8 > packet.bits_left() {
+                return Err(BitError::Malformed {
+                    pos: pos + 32,
+                    reason: "the adaptation field doesn't fit in the packet",
+                });
+            }
+            Some(packet.read_bits(length as usize * 8)?)
+        } else {
+            None
+        };
+        let payload = if header.has_payload {
+            Some(packet.read_bits(packet.bits_left())?)
+        } else {
+            None
+        };
+        Ok(Packet {
+            header,
+            adaptation_field,
+            payload,
+        })
+    })
+}
+
+/// Iterate over the packets in a transport stream
+///
+/// Iteration stops after the last complete packet or after the first error.
+pub fn packets(stream: BitReadStream<BigEndian>) -> Packets {
+    Packets {
+        stream,
+        failed: false,
+    }
+}
+
+/// Iterator over the packets in a transport stream, see [`packets`]
+#[derive(Debug, Clone)]
+pub struct Packets<'a> {
+    stream: BitReadStream<'a, BigEndian>,
+    failed: bool,
+}
+
+impl<'a> Packets<'a> {
+    /// The stream positioned after the last read packet, containing any trailing data
+    pub fn remainder(&self) -> &BitReadStream<'a, BigEndian> {
+        &self.stream
+    }
+}
+
+impl<'a> Iterator for Packets<'a> {
+    type Item = Result<Packet<'a>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed || self.stream.bits_left() < PACKET_SIZE * 8 {
+            return None;
+        }
+        let result = read_packet(&mut self.stream);
+        self.failed = result.is_err();
+        Some(result)
+    }
+}
+
+/// Skip the pointer field at the start of a payload in which a PSI section starts
+///
+/// The pointer field gives the number of bytes remaining from a section started in a previous packet,
+/// after skipping it the stream is positioned at the start of the new section.
+///
+/// # Errors
+///
+/// - [`ReadError::NotEnoughData`]: the pointer points past the end of the payload
+///
+/// [`ReadError::NotEnoughData`]: crate::BitError::NotEnoughData
+pub fn skip_pointer_field(payload: &mut BitReadStream<BigEndian>) -> Result<()> {
+    payload.restore_on_error(|payload| {
+        let pointer: u8 = payload.read_int(8)?;
+        payload.skip_bits(pointer as usize * 8)
+    })
+}
+
+/// A PSI section
+#[derive(Debug, Clone)]
+pub struct Section<'a> {
+    /// The table the section belongs to
+    pub table_id: u8,
+    /// Whether the section uses the long syntax, with a table id extension, version and crc
+    pub section_syntax_indicator: bool,
+    /// The private indicator bit
+    pub private_indicator: bool,
+    /// The data of the section after the 12 bit length, without the crc for sections using the long syntax
+    pub body: BitReadStream<'a, BigEndian>,
+}
+
+/// Read a PSI section, checking the CRC-32/MPEG-2 of sections using the long syntax
+///
+/// Sections spanning multiple packets have to be reassembled before reading.
+///
+/// # Errors
+///
+/// - [`ReadError::NotEnoughData`]: not enough bits available in the stream
+/// - [`ReadError::Malformed`]: the section length is larger than 1021 bytes or the crc doesn't match
+///
+/// [`ReadError::NotEnoughData`]: crate::BitError::NotEnoughData
+/// [`ReadError::Malformed`]: crate::BitError::Malformed
+pub fn read_section<'a>(stream: &mut BitReadStream<'a, BigEndian>) -> Result<Section<'a>> {
+    let pos = stream.pos();
+    stream.restore_on_error(|stream| {
+        let mut start = stream.clone();
+        let table_id = stream.read_int(8)?;
+        let section_syntax_indicator = stream.read_bool()?;
+        let private_indicator = stream.read_bool()?;
+        stream.skip_bits(2)?;
+        let length: usize = stream.read_int(12)?;
+        if length > MAX_SECTION_LENGTH {
+            return Err(BitError::Malformed {
+                pos: pos + 12,
+                reason: "the section length is larger than 1021 bytes",
+            });
+        }
+        let mut body = stream.read_bits(length * 8)?;
+        if section_syntax_indicator {
+            if length < 4 {
+                return Err(BitError::Malformed {
+                    pos: pos + 12,
+                    reason: "the section is too short to contain a crc",
+                });
+            }
+            let mut crc = Crc::new(&CRC_32_MPEG_2);
+            crc.update_stream(&mut start, (3 + length) * 8)?;
+            if crc.finalize() != 0 {
+                return Err(BitError::Malformed {
+                    pos,
+                    reason: "the section crc doesn't match",
+                });
+            }
+            body = body.read_bits(body.bit_len() - 32)?;
+        }
+        Ok(Section {
+            table_id,
+            section_syntax_indicator,
+            private_indicator,
+            body,
+        })
+    })
+}
@@ -1,4 +1,4 @@
-/// some extra number traits
+//! some extra number traits
 
 /// Allow casting floats unchecked
 pub trait UncheckedPrimitiveFloat: Sized {
@@ -208,7 +208,7 @@ macro_rules! impl_into_bytes {
 
             #[inline(always)]
             fn into_bytes(self) -> Self::Iter {
-                Self::Iter::new(self.to_le_bytes())
+                IntoIterator::into_iter(self.to_le_bytes())
             }
         }
     };
@@ -0,0 +1,129 @@
+//! Owned readers and writers with the endianness chosen at runtime, used by the language bindings and
+//! [`BitFileWriter`](crate::io::BitFileWriter)
+//!
+//! This module is only public for the `bitbuffer_python` crate and isn't part of the stable api.
+
+use crate::{
+    BigEndian, BitReadBuffer, BitReadStream, BitWriteStream, Endianness, LittleEndian, Result,
+};
+
+pub enum OwnedStream {
+    Little(BitReadStream<'static, LittleEndian>),
+    Big(BitReadStream<'static, BigEndian>),
+}
+
+impl OwnedStream {
+    pub fn new(bytes: Vec<u8>, little_endian: bool) -> Self {
+        if little_endian {
+            OwnedStream::Little(BitReadStream::new(BitReadBuffer::new_owned(
+                bytes,
+                LittleEndian,
+            )))
+        } else {
+            OwnedStream::Big(BitReadStream::new(BitReadBuffer::new_owned(
+                bytes, BigEndian,
+            )))
+        }
+    }
+}
+
+/// Run `$body` with `$stream` bound to the stream of an [`OwnedStream`]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! with_owned_stream {
+    ($owned:expr, $stream:ident => $body:expr) => {
+        match $owned {
+            $crate::owned::OwnedStream::Little($stream) => $body,
+            $crate::owned::OwnedStream::Big($stream) => $body,
+        }
+    };
+}
+
+pub use with_owned_stream;
+
+/// A writer that owns its data and keeps track of a trailing partial byte between writes
+pub struct OwnedWriter {
+    data: Vec<u8>,
+    bit_len: usize,
+    little_endian: bool,
+}
+
+impl OwnedWriter {
+    pub fn new(little_endian: bool) -> Self {
+        OwnedWriter {
+            data: Vec::new(),
+            bit_len: 0,
+            little_endian,
+        }
+    }
+
+    /// The written data, the last byte is padded with zero bits
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    pub fn bit_len(&self) -> usize {
+        self.bit_len
+    }
+
+    pub fn is_le(&self) -> bool {
+        self.little_endian
+    }
+
+    /// Remove the first `bytes` bytes from the data
+    pub fn consume(&mut self, bytes: usize) {
+        self.data.drain(..bytes);
+        self.bit_len = self.bit_len.saturating_sub(bytes * 8);
+    }
+
+    /// Write to a stream containing the previously written bits, nothing is written if `f` fails
+    pub fn write_endian<E: Endianness, T, F>(&mut self, endianness: E, f: F) -> Result<T>
+    where
+        F: FnOnce(&mut BitWriteStream<'_, E>) -> Result<T>,
+    {
+        let full_bytes = self.bit_len / 8;
+        let partial_bits = self.bit_len % 8;
+        let partial_byte = self.data.get(full_bytes).copied();
+        let partial: u8 = match partial_byte {
+            Some(byte) if partial_bits > 0 => {
+                BitReadStream::new(BitReadBuffer::new(&[byte], E::endianness()))
+                    .read_int(partial_bits)?
+            }
+            _ => 0,
+        };
+        self.data.truncate(full_bytes);
+
+        let mut stream = BitWriteStream::new(&mut self.data, endianness);
+        let result = match partial_bits {
+            0 => f(&mut stream),
+            bits => stream.write_int(partial, bits).and_then(|_| f(&mut stream)),
+        };
+        let bit_len = full_bytes * 8 + stream.bit_len();
+        match result {
+            Ok(result) => {
+                self.bit_len = bit_len;
+                Ok(result)
+            }
+            Err(e) => {
+                self.data.truncate(full_bytes);
+                self.data.extend(partial_byte.filter(|_| partial_bits > 0));
+                Err(e)
+            }
+        }
+    }
+}
+
+/// Run `$body` with `$stream` bound to a stream that writes to an [`OwnedWriter`]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! with_owned_writer {
+    ($writer:expr, $stream:ident => $body:expr) => {
+        if $writer.is_le() {
+            $writer.write_endian($crate::LittleEndian, |$stream| $body)
+        } else {
+            $writer.write_endian($crate::BigEndian, |$stream| $body)
+        }
+    };
+}
+
+pub use with_owned_writer;
@@ -0,0 +1,116 @@
+//! PackBits run length encoding
+//!
+//! PackBits is the byte oriented run length encoding from Apple, used by TIFF, PSD and ILBM images.
+//! Every run starts with a signed header byte `n`, `0..=127` is followed by `n + 1` literal bytes,
+//! `-127..=-1` is followed by a single byte that is repeated `1 - n` times and `-128` is skipped.
+//!
+//! # Examples
+//!
+//! ```
+//! # use bitbuffer::Result;
+//! use bitbuffer::packbits::{pack, unpack};
+//! use bitbuffer::{BigEndian, BitReadBuffer, BitReadStream, BitWriteStream};
+//!
+//! # fn main() -> Result<()> {
+//! let row = [0xAA, 0xAA, 0xAA, 0x80, 0x00, 0x2A, 0xAA, 0xAA, 0xAA, 0xAA];
+//! let input = BitReadStream::new(BitReadBuffer::new(&row, BigEndian));
+//!
+//! let mut packed = Vec::new();
+//! let mut stream = BitWriteStream::new(&mut packed, BigEndian);
+//! pack(&input, &mut stream)?;
+//! assert_eq!(packed, [0xFE, 0xAA, 0x02, 0x80, 0x00, 0x2A, 0xFD, 0xAA]);
+//!
+//! let mut input = BitReadStream::new(BitReadBuffer::new(&packed, BigEndian));
+//! let mut data = Vec::new();
+//! let mut stream = BitWriteStream::new(&mut data, BigEndian);
+//! unpack(&mut input, &mut stream, row.len())?;
+//! assert_eq!(data, row);
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::{BitError, BitReadStream, BitWriteStream, Endianness, ReadLimit, Result};
+
+/// The maximum number of bytes in a single run
+const MAX_RUN: usize = 128;
+
+/// Encode all remaining whole bytes of the input
+///
+/// Repeated bytes are only encoded as a run when at least 3 bytes are equal, shorter repeats are kept as
+/// part of the surrounding literals. Any bits following the last whole byte of the input are ignored.
+pub fn pack<E: Endianness>(input: &BitReadStream<E>, output: &mut BitWriteStream<E>) -> Result<()> {
+    let mut input = input.clone();
+    let data = input.read_bytes(input.bits_left() / 8)?;
+    let run_length = |start: usize| {
+        data[start..]
+            .iter()
+            .take(MAX_RUN)
+            .take_while(|&&byte| byte == data[start])
+            .count()
+    };
+
+    let mut pos = 0;
+    while pos < data.len() {
+        let run = run_length(pos);
+        if run >= 3 {
+            output.write_int((1 - run as i16) as i8, 8)?;
+            output.write_int(data[pos], 8)?;
+            pos += run;
+            continue;
+        }
+        let start = pos;
+        while pos < data.len() && pos - start < MAX_RUN && run_length(pos) < 3 {
+            pos += 1;
+        }
+        output.write_int((pos - start - 1) as u8, 8)?;
+        output.write_bytes(&data[start..pos])?;
+    }
+    Ok(())
+}
+
+/// Decode runs from the input until `length` bytes have been written to the output
+///
+/// Nothing is written to the output and the input is not advanced when an error occurs.
+///
+/// # Errors
+///
+/// - [`ReadError::NotEnoughData`]: the input ends before `length` bytes are decoded
+/// - [`ReadError::Malformed`]: a run extends past `length` bytes
+/// - [`ReadError::LimitExceeded`]: `length` is larger than [`ReadLimits::max_elements`](crate::ReadLimits::max_elements)
+///
+/// [`ReadError::NotEnoughData`]: crate::BitError::NotEnoughData
+/// [`ReadError::Malformed`]: crate::BitError::Malformed
+/// [`ReadError::LimitExceeded`]: crate::BitError::LimitExceeded
+pub fn unpack<E: Endianness>(
+    input: &mut BitReadStream<E>,
+    output: &mut BitWriteStream<E>,
+    length: usize,
+) -> Result<()> {
+    input.check_limit(ReadLimit::Elements, length)?;
+    let data = input.restore_on_error(|input| {
+        let mut data = Vec::with_capacity(length);
+        while data.len() < length {
+            let pos = input.pos();
+            let header: i8 = input.read_int(8)?;
+            let count = match header {
+                -128 => continue,
+                0..=127 => header as usize + 1,
+                _ => (1 - header as isize) as usize,
+            };
+            if data.len() + count > length {
+                return Err(BitError::Malformed {
+                    pos,
+                    reason: "packbits run extends past the expected length",
+                });
+            }
+            if header >= 0 {
+                data.extend_from_slice(&input.read_bytes(count)?);
+            } else {
+                let byte: u8 = input.read_int(8)?;
+                data.resize(data.len() + count, byte);
+            }
+        }
+        Ok(data)
+    })?;
+    output.write_bytes(&data)
+}
@@ -0,0 +1,316 @@
+//! Reading and writing signed PCM audio samples
+//!
+//! A [`SampleFormat`] describes how a sample is packed into its container, for example 24 bit samples packed in
+//! 3 bytes (`s24le` when read from a little endian stream) or 20 bit samples left justified in 3 bytes.
+//! The byte order of the samples is the endianness of the stream.
+//!
+//! Samples are returned as `i32` sign extended from the sample width, or as `f32` normalized to `-1.0..1.0`.
+//!
+//! # Examples
+//!
+//! ```
+//! # use bitbuffer::Result;
+//! use bitbuffer::pcm::{read_frames, read_sample, ChannelLayout, SampleFormat};
+//! use bitbuffer::{BitReadBuffer, BitReadStream, LittleEndian};
+//!
+//! # fn main() -> Result<()> {
+//! // two channels of s24le, interleaved
+//! let data = [0x01, 0x00, 0x00, 0xFF, 0xFF, 0xFF, 0x00, 0x00, 0x80, 0xFF, 0xFF, 0x7F];
+//! let mut stream = BitReadStream::new(BitReadBuffer::new(&data, LittleEndian));
+//! let channels = read_frames(&mut stream, SampleFormat::S24, ChannelLayout::Interleaved, 2, 2)?;
+//! assert_eq!(channels, [[1, -0x80_0000], [-1, 0x7F_FFFF]]);
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::{BitReadStream, BitWriteStream, Endianness, Result};
+
+/// The way a signed sample is packed into its container
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct SampleFormat {
+    container_bits: usize,
+    sample_bits: usize,
+    left_justified: bool,
+}
+
+impl SampleFormat {
+    /// 8 bit samples
+    pub const S8: SampleFormat = SampleFormat::new(8, 8, false);
+    /// 16 bit samples
+    pub const S16: SampleFormat = SampleFormat::new(16, 16, false);
+    /// 20 bit samples in the top bits of 3 bytes
+    pub const S20_LEFT: SampleFormat = SampleFormat::new(24, 20, true);
+    /// 24 bit samples packed in 3 bytes
+    pub const S24: SampleFormat = SampleFormat::new(24, 24, false);
+    /// 24 bit samples in the bottom bits of 4 bytes
+    pub const S24_32: SampleFormat = SampleFormat::new(32, 24, false);
+    /// 24 bit samples in the top bits of 4 bytes
+    pub const S24_32_LEFT: SampleFormat = SampleFormat::new(32, 24, true);
+    /// 32 bit samples
+    pub const S32: SampleFormat = SampleFormat::new(32, 32, false);
+
+    /// Create a format for `sample_bits` samples in a `container_bits` container
+    ///
+    /// Left justified samples are stored in the top bits of the container, otherwise they are stored in the
+    /// bottom bits. Unused bits are written as zero for left justified samples and as sign extension otherwise,
+    /// and ignored when reading.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `sample_bits` is 0 or larger than `container_bits`, or if `container_bits` is larger than 32.
+    pub const fn new(container_bits: usize, sample_bits: usize, left_justified: bool) -> Self {
+        assert!(
+            container_bits <= 32,
+            "sample containers can be at most 32 bits"
+        );
+        assert!(
+            sample_bits > 0 && sample_bits <= container_bits,
+            "samples have to fit in their container"
+        );
+        SampleFormat {
+            container_bits,
+            sample_bits,
+            left_justified,
+        }
+    }
+
+    /// The number of bits used for every sample in the stream
+    pub fn container_bits(&self) -> usize {
+        self.container_bits
+    }
+
+    /// The number of significant bits in a sample
+    pub fn sample_bits(&self) -> usize {
+        self.sample_bits
+    }
+
+    /// Whether the sample is stored in the top bits of the container
+    pub fn left_justified(&self) -> bool {
+        self.left_justified
+    }
+
+    /// Get the sample from a sign extended container
+    fn unpack(&self, container: i32) -> i32 {
+        let unused = self.container_bits - self.sample_bits;
+        if self.left_justified {
+            container >> unused
+        } else {
+            let shift = 32 - self.sample_bits;
+            (container << shift) >> shift
+        }
+    }
+
+    fn pack(&self, sample: i32) -> i32 {
+        let unused = self.container_bits - self.sample_bits;
+        let shift = 32 - self.sample_bits;
+        let sample = (sample << shift) >> shift;
+        if self.left_justified {
+            sample << unused
+        } else {
+            sample
+        }
+    }
+
+    fn scale(&self) -> f32 {
+        (1u32 << (self.sample_bits - 1)) as f32
+    }
+
+    fn quantize(&self, value: f32) -> i32 {
+        let max = ((1u32 << (self.sample_bits - 1)) - 1) as f32;
+        let value = (value * self.scale()).round();
+        if value.is_nan() {
+            0
+        } else {
+            value.clamp(-self.scale(), max) as i32
+        }
+    }
+}
+
+/// How samples for multiple channels are ordered
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum ChannelLayout {
+    /// One sample for every channel in turn
+    #[default]
+    Interleaved,
+    /// All samples for the first channel, followed by all samples for the next channel
+    Planar,
+}
+
+/// Read a single sample, sign extended to `i32`
+///
+/// # Errors
+///
+/// - [`ReadError::NotEnoughData`]: not enough bits available in the stream
+///
+/// [`ReadError::NotEnoughData`]: enum.ReadError.html#variant.NotEnoughData
+pub fn read_sample<E: Endianness>(
+    stream: &mut BitReadStream<E>,
+    format: SampleFormat,
+) -> Result<i32> {
+    Ok(format.unpack(stream.read_int(format.container_bits)?))
+}
+
+/// Write a single sample, only the bottom `sample_bits` bits are written
+pub fn write_sample<E: Endianness>(
+    stream: &mut BitWriteStream<E>,
+    sample: i32,
+    format: SampleFormat,
+) -> Result<()> {
+    stream.write_int(format.pack(sample), format.container_bits)
+}
+
+/// Read a single sample normalized to `-1.0..1.0`
+///
+/// # Errors
+///
+/// - [`ReadError::NotEnoughData`]: not enough bits available in the stream
+///
+/// [`ReadError::NotEnoughData`]: enum.ReadError.html#variant.NotEnoughData
+pub fn read_sample_f32<E: Endianness>(
+    stream: &mut BitReadStream<E>,
+    format: SampleFormat,
+) -> Result<f32> {
+    Ok(read_sample(stream, format)? as f32 / format.scale())
+}
+
+/// Write a single sample normalized to `-1.0..1.0`
+///
+/// The value is rounded to the nearest sample and clamped to the range of the format, NaN is written as 0.
+pub fn write_sample_f32<E: Endianness>(
+    stream: &mut BitWriteStream<E>,
+    value: f32,
+    format: SampleFormat,
+) -> Result<()> {
+    write_sample(stream, format.quantize(value), format)
+}
+
+/// Read `frames` samples for each of `channels` channels, returning the samples for each channel
+///
+/// # Errors
+///
+/// - [`ReadError::NotEnoughData`]: not enough bits available in the stream, the stream is not advanced
+/// - [`ReadError::LimitExceeded`]: the total number of samples is larger than the configured [`ReadLimits::max_elements`]
+///
+/// [`ReadError::NotEnoughData`]: enum.ReadError.html#variant.NotEnoughData
+/// [`ReadError::LimitExceeded`]: enum.ReadError.html#variant.LimitExceeded
+/// [`ReadLimits::max_elements`]: crate::ReadLimits::max_elements
+pub fn read_frames<E: Endianness>(
+    stream: &mut BitReadStream<E>,
+    format: SampleFormat,
+    layout: ChannelLayout,
+    channels: usize,
+    frames: usize,
+) -> Result<Vec<Vec<i32>>> {
+    let count = channels.saturating_mul(frames);
+    let samples = stream.read_int_vec::<i32>(count, format.container_bits)?;
+    let mut result: Vec<Vec<i32>> = (0..channels).map(|_| Vec::with_capacity(frames)).collect();
+    match layout {
+        ChannelLayout::Interleaved => {
+            for frame in samples.chunks_exact(channels.max(1)) {
+                for (channel, sample) in result.iter_mut().zip(frame) {
+                    channel.push(format.unpack(*sample));
+                }
+            }
+        }
+        ChannelLayout::Planar => {
+            for (channel, samples) in result.iter_mut().zip(samples.chunks_exact(frames.max(1))) {
+                channel.extend(samples.iter().map(|sample| format.unpack(*sample)));
+            }
+        }
+    }
+    Ok(result)
+}
+
+/// Read `frames` samples for each of `channels` channels normalized to `-1.0..1.0`, returning the samples for each channel
+///
+/// # Errors
+///
+/// - [`ReadError::NotEnoughData`]: not enough bits available in the stream, the stream is not advanced
+/// - [`ReadError::LimitExceeded`]: the total number of samples is larger than the configured [`ReadLimits::max_elements`]
+///
+/// [`ReadError::NotEnoughData`]: enum.ReadError.html#variant.NotEnoughData
+/// [`ReadError::LimitExceeded`]: enum.ReadError.html#variant.LimitExceeded
+/// [`ReadLimits::max_elements`]: crate::ReadLimits::max_elements
+pub fn read_frames_f32<E: Endianness>(
+    stream: &mut BitReadStream<E>,
+    format: SampleFormat,
+    layout: ChannelLayout,
+    channels: usize,
+    frames: usize,
+) -> Result<Vec<Vec<f32>>> {
+    let scale = format.scale();
+    Ok(read_frames(stream, format, layout, channels, frames)?
+        .into_iter()
+        .map(|channel| {
+            channel
+                .into_iter()
+                .map(|sample| sample as f32 / scale)
+                .collect()
+        })
+        .collect())
+}
+
+/// Write the samples for each channel
+///
+/// # Panics
+///
+/// Panics if the channels don't all have the same number of samples.
+pub fn write_frames<E: Endianness, C: AsRef<[i32]>>(
+    stream: &mut BitWriteStream<E>,
+    format: SampleFormat,
+    layout: ChannelLayout,
+    channels: &[C],
+) -> Result<()> {
+    let frames = channels.first().map_or(0, |channel| channel.as_ref().len());
+    assert!(
+        channels
+            .iter()
+            .all(|channel| channel.as_ref().len() == frames),
+        "all channels need the same number of samples"
+    );
+    match layout {
+        ChannelLayout::Interleaved => {
+            for frame in 0..frames {
+                for channel in channels {
+                    write_sample(stream, channel.as_ref()[frame], format)?;
+                }
+            }
+        }
+        ChannelLayout::Planar => {
+            for channel in channels {
+                for sample in channel.as_ref() {
+                    write_sample(stream, *sample, format)?;
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Write the samples for each channel normalized to `-1.0..1.0`
+///
+/// Values are rounded to the nearest sample and clamped to the range of the format, NaN is written as 0.
+///
+/// # Panics
+///
+/// Panics if the channels don't all have the same number of samples.
+pub fn write_frames_f32<E: Endianness, C: AsRef<[f32]>>(
+    stream: &mut BitWriteStream<E>,
+    format: SampleFormat,
+    layout: ChannelLayout,
+    channels: &[C],
+) -> Result<()> {
+    let channels: Vec<Vec<i32>> = channels
+        .iter()
+        .map(|channel| {
+            channel
+                .as_ref()
+                .iter()
+                .map(|value| format.quantize(*value))
+                .collect()
+        })
+        .collect();
+    write_frames(stream, format, layout, &channels)
+}
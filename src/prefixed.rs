@@ -0,0 +1,96 @@
+use crate::{BitRead, BitReadStream, BitWrite, BitWriteStream, Endianness, Result};
+use std::fmt::{self, Display, Formatter};
+use std::ops::{Deref, DerefMut};
+
+/// A string prefixed by a `BITS` bits long length in bytes
+///
+/// This allows using length prefixed strings in derived structs,
+/// see [`read_string_prefixed`](BitReadStream::read_string_prefixed) and
+/// [`write_string_prefixed`](BitWriteStream::write_string_prefixed).
+///
+/// # Examples
+///
+/// ```
+/// # use bitbuffer::Result;
+/// use bitbuffer::{
+///     BitRead, BitReadBuffer, BitReadStream, BitWrite, BitWriteStream, LittleEndian, PrefixedString,
+/// };
+///
+/// #[derive(BitRead, BitWrite, Debug, PartialEq)]
+/// struct Player {
+///     name: PrefixedString<5>,
+///     team: PrefixedString<8>,
+/// }
+///
+/// # fn main() -> Result<()> {
+/// let player = Player {
+///     name: "Bob".into(),
+///     team: "red".into(),
+/// };
+/// let mut data = Vec::new();
+/// let mut stream = BitWriteStream::new(&mut data, LittleEndian);
+/// stream.write(&player)?;
+/// assert_eq!(stream.bit_len(), 5 + 3 * 8 + 8 + 3 * 8);
+///
+/// let mut stream = BitReadStream::new(BitReadBuffer::new(&data, LittleEndian));
+/// assert_eq!(stream.read::<Player>()?, player);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct PrefixedString<const BITS: usize>(pub String);
+
+impl<const BITS: usize> PrefixedString<BITS> {
+    /// Get the inner string
+    pub fn into_inner(self) -> String {
+        self.0
+    }
+}
+
+impl<const BITS: usize> Deref for PrefixedString<BITS> {
+    type Target = String;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<const BITS: usize> DerefMut for PrefixedString<BITS> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl<const BITS: usize> From<String> for PrefixedString<BITS> {
+    fn from(string: String) -> Self {
+        PrefixedString(string)
+    }
+}
+
+impl<const BITS: usize> From<&str> for PrefixedString<BITS> {
+    fn from(string: &str) -> Self {
+        PrefixedString(string.to_string())
+    }
+}
+
+impl<const BITS: usize> Display for PrefixedString<BITS> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl<'a, E: Endianness, const BITS: usize> BitRead<'a, E> for PrefixedString<BITS> {
+    #[inline]
+    fn read(stream: &mut BitReadStream<'a, E>) -> Result<Self> {
+        Ok(PrefixedString(
+            stream.read_string_prefixed(BITS)?.into_owned(),
+        ))
+    }
+}
+
+impl<E: Endianness, const BITS: usize> BitWrite<E> for PrefixedString<BITS> {
+    #[inline]
+    fn write(&self, stream: &mut BitWriteStream<E>) -> Result<()> {
+        stream.write_string_prefixed(&self.0, BITS)
+    }
+}
@@ -0,0 +1,136 @@
+//! Proptest strategies for testing bit level formats
+//!
+//! Provides strategies for generating bit widths, positions and values that fit in a number of
+//! bits, and a [`roundtrip_property`] helper to check that any type survives being written and read back.
+//!
+//! # Examples
+//!
+//! ```
+//! use bitbuffer::proptest::unsigned_int;
+//! use bitbuffer::{BitReadBuffer, BitReadStream, BitWriteStream, LittleEndian};
+//! use proptest::prelude::*;
+//!
+//! proptest!(|((value, bits) in unsigned_int(64))| {
+//!     let mut data = Vec::new();
+//!     let mut stream = BitWriteStream::new(&mut data, LittleEndian);
+//!     stream.write_int(value, bits).unwrap();
+//!     let mut read = BitReadStream::new(BitReadBuffer::new(&data, LittleEndian));
+//!     prop_assert_eq!(value, read.read_int::<u64>(bits).unwrap());
+//! });
+//! ```
+
+use crate::{test_roundtrip, BigEndian, BitRead, BitWrite, Endianness, LittleEndian};
+use ::proptest::arbitrary::{any, Arbitrary};
+use ::proptest::collection::vec;
+use ::proptest::strategy::{Just, Strategy};
+use ::proptest::test_runner::{TestCaseError, TestRunner};
+use std::fmt::Debug;
+use std::ops::RangeInclusive;
+
+/// Generate a bit width within the range
+pub fn bit_width(range: RangeInclusive<usize>) -> impl Strategy<Value = usize> {
+    range
+}
+
+/// Generate a bit position within a stream of `bit_len` bits, including the end of the stream
+pub fn bit_position(bit_len: usize) -> impl Strategy<Value = usize> {
+    0..=bit_len
+}
+
+/// Generate up to `max_bytes` bytes of random data
+pub fn bit_data(max_bytes: usize) -> impl Strategy<Value = Vec<u8>> {
+    vec(any::<u8>(), 0..=max_bytes)
+}
+
+/// Generate a bit width between 1 and `max_bits` together with an unsigned value that fits in it
+///
+/// `max_bits` can be at most 64
+pub fn unsigned_int(max_bits: usize) -> impl Strategy<Value = (u64, usize)> {
+    assert!(
+        max_bits > 0 && max_bits <= 64,
+        "max_bits must be between 1 and 64"
+    );
+    bit_width(1..=max_bits).prop_flat_map(|bits| {
+        let mask = u64::MAX >> (64 - bits);
+        (any::<u64>().prop_map(move |value| value & mask), Just(bits))
+    })
+}
+
+/// Generate a bit width between 1 and `max_bits` together with a signed value that fits in it
+///
+/// `max_bits` can be at most 64
+pub fn signed_int(max_bits: usize) -> impl Strategy<Value = (i64, usize)> {
+    unsigned_int(max_bits).prop_map(|(value, bits)| {
+        // sign extend from the top bit of the width
+        let shift = 64 - bits;
+        (((value << shift) as i64) >> shift, bits)
+    })
+}
+
+/// Check that a value can be written and read back as the same value, in the given endianness
+///
+/// Also checks that reading the value consumes exactly as many bits as were written.
+pub fn assert_roundtrip<T, E>(value: &T) -> Result<(), TestCaseError>
+where
+    T: for<'a> BitRead<'a, E> + BitWrite<E> + Debug + PartialEq,
+    E: Endianness,
+{
+    test_roundtrip(value, E::endianness()).map_err(|e| TestCaseError::fail(e.to_string()))
+}
+
+/// Assert that randomly generated values of `T` survive being written and read back
+///
+/// Values are generated using the proptest [`Arbitrary`] implementation of `T` and checked in
+/// both little and big endian.
+///
+/// # Panics
+///
+/// Panics with the minimal failing value if any value fails the round trip
+///
+/// # Examples
+///
+/// ```
+/// use bitbuffer::proptest::roundtrip_property;
+///
+/// roundtrip_property::<(u8, i32, bool, String)>();
+/// ```
+pub fn roundtrip_property<T>()
+where
+    T: for<'a> BitRead<'a, LittleEndian>
+        + BitWrite<LittleEndian>
+        + for<'a> BitRead<'a, BigEndian>
+        + BitWrite<BigEndian>
+        + Arbitrary
+        + Debug
+        + PartialEq,
+{
+    roundtrip_property_with(any::<T>())
+}
+
+/// Assert that values generated by the strategy survive being written and read back
+///
+/// Like [`roundtrip_property`] but for types that don't implement [`Arbitrary`] or
+/// when only a subset of values can be written.
+///
+/// # Panics
+///
+/// Panics with the minimal failing value if any value fails the round trip
+pub fn roundtrip_property_with<T, S>(strategy: S)
+where
+    T: for<'a> BitRead<'a, LittleEndian>
+        + BitWrite<LittleEndian>
+        + for<'a> BitRead<'a, BigEndian>
+        + BitWrite<BigEndian>
+        + Debug
+        + PartialEq,
+    S: Strategy<Value = T>,
+{
+    let mut runner = TestRunner::default();
+    let result = runner.run(&strategy, |value| {
+        assert_roundtrip::<T, LittleEndian>(&value)?;
+        assert_roundtrip::<T, BigEndian>(&value)
+    });
+    if let Err(e) = result {
+        panic!("{}\n{}", e, runner);
+    }
+}
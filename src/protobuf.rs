@@ -0,0 +1,322 @@
+//! Protobuf wire format primitives
+//!
+//! Helpers for reading and writing the fields of [protobuf](https://protobuf.dev/programming-guides/encoding/)
+//! encoded messages without generated code, which is useful when only a few fields of a large message are needed.
+//!
+//! Protobuf is a byte oriented format, all integers are stored little endian regardless of the endianness of the stream.
+//! All read functions leave the stream unchanged when they return an error.
+//!
+//! # Examples
+//!
+//! ```
+//! # use bitbuffer::Result;
+//! use bitbuffer::protobuf::{read_length_delimited, read_tag, read_varint, skip_field, write_tag, write_varint, WireType};
+//! use bitbuffer::{BitReadBuffer, BitReadStream, BitWriteStream, LittleEndian};
+//!
+//! # fn main() -> Result<()> {
+//! // field 1: 150, field 2: "testing", field 3: 1
+//! let bytes = [0x08, 0x96, 0x01, 0x12, 0x07, b't', b'e', b's', b't', b'i', b'n', b'g', 0x18, 0x01];
+//! let mut stream = BitReadStream::new(BitReadBuffer::new(&bytes, LittleEndian));
+//!
+//! let mut name = None;
+//! while stream.bits_left() > 0 {
+//!     let tag = read_tag(&mut stream)?;
+//!     match (tag.field, tag.wire_type) {
+//!         (2, WireType::LengthDelimited) => {
+//!             let mut field = read_length_delimited(&mut stream)?;
+//!             name = Some(field.read_string(Some(field.bit_len() / 8))?);
+//!         }
+//!         _ => skip_field(&mut stream, tag)?,
+//!     }
+//! }
+//! assert_eq!(name.as_deref(), Some("testing"));
+//!
+//! let mut data = Vec::new();
+//! let mut stream = BitWriteStream::new(&mut data, LittleEndian);
+//! write_tag(&mut stream, 1, WireType::Varint)?;
+//! write_varint(&mut stream, 150)?;
+//! assert_eq!(data, [0x08, 0x96, 0x01]);
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::readstream::checked_bits;
+use crate::{BitError, BitReadStream, BitWriteStream, Endianness, Result};
+use std::borrow::Cow;
+use std::convert::TryInto;
+
+/// The encoding of a field value
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum WireType {
+    /// A varint encoded integer
+    Varint = 0,
+    /// A little endian 64 bit value
+    Fixed64 = 1,
+    /// A varint encoded length followed by that many bytes
+    LengthDelimited = 2,
+    /// The start of a group (deprecated)
+    StartGroup = 3,
+    /// The end of a group (deprecated)
+    EndGroup = 4,
+    /// A little endian 32 bit value
+    Fixed32 = 5,
+}
+
+impl WireType {
+    fn from_bits(bits: u8) -> Result<Self> {
+        Ok(match bits {
+            0 => WireType::Varint,
+            1 => WireType::Fixed64,
+            2 => WireType::LengthDelimited,
+            3 => WireType::StartGroup,
+            4 => WireType::EndGroup,
+            5 => WireType::Fixed32,
+            _ => {
+                return Err(BitError::UnmatchedDiscriminant {
+                    discriminant: bits as usize,
+                    enum_name: "WireType".into(),
+                })
+            }
+        })
+    }
+}
+
+/// The key of a field, containing the field number and wire type
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Tag {
+    /// The field number
+    pub field: u32,
+    /// The encoding of the field value
+    pub wire_type: WireType,
+}
+
+/// Read the key of the next field
+///
+/// # Errors
+///
+/// - [`ReadError::NotEnoughData`]: not enough bits available in the stream
+/// - [`ReadError::VarIntOverflow`]: the field number doesn't fit in 32 bits
+/// - [`ReadError::UnmatchedDiscriminant`]: the wire type is not a known wire type
+///
+/// [`ReadError::NotEnoughData`]: crate::BitError::NotEnoughData
+/// [`ReadError::VarIntOverflow`]: crate::BitError::VarIntOverflow
+/// [`ReadError::UnmatchedDiscriminant`]: crate::BitError::UnmatchedDiscriminant
+pub fn read_tag<E: Endianness>(stream: &mut BitReadStream<E>) -> Result<Tag> {
+    let pos = stream.pos();
+    stream.restore_on_error(|stream| {
+        let key = read_varint(stream)?;
+        let field = (key >> 3)
+            .try_into()
+            .map_err(|_| BitError::VarIntOverflow { pos, max: 32 })?;
+        Ok(Tag {
+            field,
+            wire_type: WireType::from_bits(key as u8 & 0b111)?,
+        })
+    })
+}
+
+/// Read a varint encoded integer
+///
+/// The integer is stored with 7 bits per byte, least significant group first, with the top bit set when more bytes follow.
+///
+/// # Errors
+///
+/// - [`ReadError::NotEnoughData`]: not enough bits available in the stream
+/// - [`ReadError::VarIntOverflow`]: the value doesn't fit in 64 bits
+///
+/// [`ReadError::NotEnoughData`]: crate::BitError::NotEnoughData
+/// [`ReadError::VarIntOverflow`]: crate::BitError::VarIntOverflow
+pub fn read_varint<E: Endianness>(stream: &mut BitReadStream<E>) -> Result<u64> {
+    let pos = stream.pos();
+    stream.restore_on_error(|stream| {
+        let mut value = 0;
+        for shift in (0..64).step_by(7) {
+            let byte: u8 = stream.read_int(8)?;
+            if shift == 63 && byte > 1 {
+                break;
+            }
+            value |= ((byte & 0x7F) as u64) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+        Err(BitError::VarIntOverflow { pos, max: 64 })
+    })
+}
+
+/// Read a zigzag encoded signed integer, as used by the `sint32` and `sint64` types
+///
+/// # Errors
+///
+/// - [`ReadError::NotEnoughData`]: not enough bits available in the stream
+/// - [`ReadError::VarIntOverflow`]: the value doesn't fit in 64 bits
+///
+/// [`ReadError::NotEnoughData`]: crate::BitError::NotEnoughData
+/// [`ReadError::VarIntOverflow`]: crate::BitError::VarIntOverflow
+pub fn read_sint<E: Endianness>(stream: &mut BitReadStream<E>) -> Result<i64> {
+    let value = read_varint(stream)?;
+    Ok((value >> 1) as i64 ^ -((value & 1) as i64))
+}
+
+/// Read a little endian 32 bit value
+///
+/// # Errors
+///
+/// - [`ReadError::NotEnoughData`]: not enough bits available in the stream
+///
+/// [`ReadError::NotEnoughData`]: crate::BitError::NotEnoughData
+pub fn read_fixed32<E: Endianness>(stream: &mut BitReadStream<E>) -> Result<u32> {
+    let bytes = stream.read_bytes(4)?;
+    Ok(u32::from_le_bytes(bytes.as_ref().try_into().unwrap()))
+}
+
+/// Read a little endian 64 bit value
+///
+/// # Errors
+///
+/// - [`ReadError::NotEnoughData`]: not enough bits available in the stream
+///
+/// [`ReadError::NotEnoughData`]: crate::BitError::NotEnoughData
+pub fn read_fixed64<E: Endianness>(stream: &mut BitReadStream<E>) -> Result<u64> {
+    let bytes = stream.read_bytes(8)?;
+    Ok(u64::from_le_bytes(bytes.as_ref().try_into().unwrap()))
+}
+
+/// Read a length delimited value as a stream, for reading embedded messages, packed fields or strings
+///
+/// # Errors
+///
+/// - [`ReadError::NotEnoughData`]: not enough bits available in the stream
+/// - [`ReadError::VarIntOverflow`]: the length doesn't fit in 64 bits
+///
+/// [`ReadError::NotEnoughData`]: crate::BitError::NotEnoughData
+/// [`ReadError::VarIntOverflow`]: crate::BitError::VarIntOverflow
+pub fn read_length_delimited<'a, E: Endianness>(
+    stream: &mut BitReadStream<'a, E>,
+) -> Result<BitReadStream<'a, E>> {
+    stream.restore_on_error(|stream| {
+        let length = read_length(stream)?;
+        stream.read_bits(length)
+    })
+}
+
+/// Read a length delimited value as bytes
+///
+/// # Errors
+///
+/// - [`ReadError::NotEnoughData`]: not enough bits available in the stream
+/// - [`ReadError::VarIntOverflow`]: the length doesn't fit in 64 bits
+///
+/// [`ReadError::NotEnoughData`]: crate::BitError::NotEnoughData
+/// [`ReadError::VarIntOverflow`]: crate::BitError::VarIntOverflow
+pub fn read_length_delimited_bytes<'a, E: Endianness>(
+    stream: &mut BitReadStream<'a, E>,
+) -> Result<Cow<'a, [u8]>> {
+    stream.restore_on_error(|stream| {
+        let length = read_varint(stream)?;
+        stream.read_bytes(length.try_into().unwrap_or(usize::MAX))
+    })
+}
+
+/// Read a varint length in bytes and convert it into a number of bits
+fn read_length<E: Endianness>(stream: &mut BitReadStream<E>) -> Result<usize> {
+    let length = read_varint(stream)?;
+    checked_bits(length.try_into().unwrap_or(usize::MAX), 8)
+}
+
+/// Skip the value of a field
+///
+/// Groups are skipped up to and including their matching [`EndGroup`](WireType::EndGroup) tag.
+///
+/// # Errors
+///
+/// - [`ReadError::NotEnoughData`]: not enough bits available in the stream
+/// - [`ReadError::VarIntOverflow`]: a varint in the value doesn't fit in 64 bits
+/// - [`ReadError::UnmatchedDiscriminant`]: a group contains a tag with an unknown wire type
+/// - [`ReadError::LimitExceeded`]: groups are nested deeper than [`ReadLimits::max_depth`](crate::ReadLimits::max_depth)
+///
+/// [`ReadError::NotEnoughData`]: crate::BitError::NotEnoughData
+/// [`ReadError::VarIntOverflow`]: crate::BitError::VarIntOverflow
+/// [`ReadError::UnmatchedDiscriminant`]: crate::BitError::UnmatchedDiscriminant
+/// [`ReadError::LimitExceeded`]: crate::BitError::LimitExceeded
+pub fn skip_field<E: Endianness>(stream: &mut BitReadStream<E>, tag: Tag) -> Result<()> {
+    stream.restore_on_error(|stream| match tag.wire_type {
+        WireType::Varint => read_varint(stream).map(|_| ()),
+        WireType::Fixed64 => stream.skip_bits(64),
+        WireType::LengthDelimited => {
+            let length = read_length(stream)?;
+            stream.skip_bits(length)
+        }
+        WireType::StartGroup => {
+            stream.enter_nested()?;
+            let result = skip_group(stream, tag.field);
+            stream.leave_nested();
+            result
+        }
+        WireType::EndGroup => Ok(()),
+        WireType::Fixed32 => stream.skip_bits(32),
+    })
+}
+
+fn skip_group<E: Endianness>(stream: &mut BitReadStream<E>, field: u32) -> Result<()> {
+    loop {
+        let tag = read_tag(stream)?;
+        if tag.wire_type == WireType::EndGroup && tag.field == field {
+            return Ok(());
+        }
+        skip_field(stream, tag)?;
+    }
+}
+
+/// Write the key of a field
+pub fn write_tag<E: Endianness>(
+    stream: &mut BitWriteStream<E>,
+    field: u32,
+    wire_type: WireType,
+) -> Result<()> {
+    write_varint(stream, (field as u64) << 3 | wire_type as u64)
+}
+
+/// Write a varint encoded integer
+///
+/// See [`read_varint`] for the format.
+pub fn write_varint<E: Endianness>(stream: &mut BitWriteStream<E>, mut value: u64) -> Result<()> {
+    let mut bytes = [0; 10];
+    let mut len = 0;
+    loop {
+        bytes[len] = (value & 0x7F) as u8;
+        value >>= 7;
+        if value == 0 {
+            break;
+        }
+        bytes[len] |= 0x80;
+        len += 1;
+    }
+    stream.write_bytes(&bytes[..=len])
+}
+
+/// Write a zigzag encoded signed integer, as used by the `sint32` and `sint64` types
+pub fn write_sint<E: Endianness>(stream: &mut BitWriteStream<E>, value: i64) -> Result<()> {
+    write_varint(stream, ((value << 1) ^ (value >> 63)) as u64)
+}
+
+/// Write a little endian 32 bit value
+pub fn write_fixed32<E: Endianness>(stream: &mut BitWriteStream<E>, value: u32) -> Result<()> {
+    stream.write_bytes(&value.to_le_bytes())
+}
+
+/// Write a little endian 64 bit value
+pub fn write_fixed64<E: Endianness>(stream: &mut BitWriteStream<E>, value: u64) -> Result<()> {
+    stream.write_bytes(&value.to_le_bytes())
+}
+
+/// Write a length delimited value
+pub fn write_length_delimited<E: Endianness>(
+    stream: &mut BitWriteStream<E>,
+    bytes: &[u8],
+) -> Result<()> {
+    write_varint(stream, bytes.len() as u64)?;
+    stream.write_bytes(bytes)
+}
@@ -0,0 +1,75 @@
+use crate::{BitRead, BitReadStream, BitWrite, BitWriteStream, Endianness, Result};
+use std::fmt::{self, Display, Formatter};
+
+/// An integer encoded as QUIC variable length integer
+///
+/// This allows using QUIC variable length integers in derived structs,
+/// see [`read_quic_varint`](BitReadStream::read_quic_varint) and [`write_quic_varint`](BitWriteStream::write_quic_varint).
+///
+/// # Examples
+///
+/// ```
+/// # use bitbuffer::Result;
+/// use bitbuffer::{BitRead, BitReadBuffer, BitReadStream, BitWrite, BitWriteStream, BigEndian, QuicVarInt};
+///
+/// #[derive(BitRead, BitWrite, Debug, PartialEq)]
+/// struct Frame {
+///     frame_type: QuicVarInt,
+///     length: QuicVarInt,
+/// }
+///
+/// # fn main() -> Result<()> {
+/// let frame = Frame {
+///     frame_type: QuicVarInt(0x06),
+///     length: QuicVarInt(300),
+/// };
+/// let mut data = Vec::new();
+/// let mut stream = BitWriteStream::new(&mut data, BigEndian);
+/// stream.write(&frame)?;
+/// assert_eq!(data, [0x06, 0x41, 0x2C]);
+///
+/// let mut stream = BitReadStream::new(BitReadBuffer::new(&data, BigEndian));
+/// assert_eq!(stream.read::<Frame>()?, frame);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct QuicVarInt(pub u64);
+
+impl QuicVarInt {
+    /// The largest value that can be encoded
+    pub const MAX: QuicVarInt = QuicVarInt((1 << 62) - 1);
+}
+
+impl From<u64> for QuicVarInt {
+    fn from(value: u64) -> Self {
+        QuicVarInt(value)
+    }
+}
+
+impl From<QuicVarInt> for u64 {
+    fn from(value: QuicVarInt) -> Self {
+        value.0
+    }
+}
+
+impl Display for QuicVarInt {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl<'a, E: Endianness> BitRead<'a, E> for QuicVarInt {
+    #[inline]
+    fn read(stream: &mut BitReadStream<'a, E>) -> Result<Self> {
+        stream.read_quic_varint().map(QuicVarInt)
+    }
+}
+
+impl<E: Endianness> BitWrite<E> for QuicVarInt {
+    #[inline]
+    fn write(&self, stream: &mut BitWriteStream<E>) -> Result<()> {
+        stream.write_quic_varint(self.0)
+    }
+}
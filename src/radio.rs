@@ -0,0 +1,352 @@
+//! Bluetooth Low Energy and IEEE 802.15.4 link layer helpers
+//!
+//! Both protocols transmit every field least significant bit first, so captured packets are best read with a
+//! [`LittleEndian`] stream. Demodulators that pack the received bits most significant bit first can be converted
+//! with [`reverse_bit_order`].
+//!
+//! Before reading, whitened packets are descrambled by combining a [`DescrambleStream`] with [`BleWhitening`]
+//! or [`Pn9Whitening`], packets are whitened while writing by attaching the same key stream using
+//! [`attach_scrambler`].
+//!
+//! # Examples
+//!
+//! ```
+//! # use bitbuffer::Result;
+//! use bitbuffer::crc::CRC_24_BLE;
+//! use bitbuffer::radio::{read_ble_pdu, BleWhitening};
+//! use bitbuffer::scramble::DescrambleStream;
+//! use bitbuffer::{BitReadBuffer, BitReadStream, BitWriteStream, LittleEndian};
+//!
+//! # fn main() -> Result<()> {
+//! // an ADV_NONCONN_IND pdu with a 6 byte address and no advertising data
+//! let pdu = [0x02, 0x06, 0x11, 0x22, 0x33, 0x44, 0x55, 0x66];
+//! let crc = CRC_24_BLE.checksum(&pdu);
+//!
+//! let mut data = Vec::new();
+//! let mut stream = BitWriteStream::new(&mut data, LittleEndian);
+//! stream.attach_scrambler(BleWhitening::new(37));
+//! stream.write_bytes(&pdu)?;
+//! stream.write_int(crc, 24)?;
+//! drop(stream);
+//!
+//! let stream = BitReadStream::new(BitReadBuffer::new(&data, LittleEndian));
+//! let mut stream = DescrambleStream::new(stream, BleWhitening::new(37));
+//! let mut dewhitened = stream.read_bits(stream.bits_left())?;
+//! let pdu = read_ble_pdu(&mut dewhitened, &CRC_24_BLE)?;
+//! assert_eq!(pdu.header.pdu_type, 2);
+//! assert_eq!(pdu.payload.bit_len(), 6 * 8);
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! [`DescrambleStream`]: crate::scramble::DescrambleStream
+//! [`attach_scrambler`]: crate::BitWriteStream::attach_scrambler
+
+use crate::crc::{Crc, CrcAlgorithm, CRC_16_KERMIT};
+use crate::scramble::KeyStream;
+use crate::{BitError, BitReadStream, Endianness, LittleEndian, Result};
+
+/// The access address used by all Bluetooth Low Energy advertising packets
+pub const BLE_ADVERTISING_ACCESS_ADDRESS: u32 = 0x8E89_BED6;
+
+/// Reverse the order of the bits within every byte
+///
+/// This converts between bits packed most significant bit first and bits packed least significant bit first.
+///
+/// # Examples
+///
+/// ```
+/// use bitbuffer::radio::reverse_bit_order;
+///
+/// assert_eq!(reverse_bit_order(&[0b1000_0000, 0b1100_1010]), [0b0000_0001, 0b0101_0011]);
+/// ```
+pub fn reverse_bit_order(bytes: &[u8]) -> Vec<u8> {
+    bytes.iter().map(|byte| byte.reverse_bits()).collect()
+}
+
+/// Read an integer of `count` bits with the bit order reversed
+///
+/// For a [`LittleEndian`] stream the first bit read becomes the most significant bit of the result,
+/// for a [`BigEndian`](crate::BigEndian) stream the first bit read becomes the least significant bit.
+///
+/// # Errors
+///
+/// - [`ReadError::NotEnoughData`]: not enough bits available in the stream
+/// - [`ReadError::TooManyBits`]: more than 64 bits requested
+///
+/// [`ReadError::NotEnoughData`]: crate::BitError::NotEnoughData
+/// [`ReadError::TooManyBits`]: crate::BitError::TooManyBits
+///
+/// # Examples
+///
+/// ```
+/// # use bitbuffer::Result;
+/// use bitbuffer::radio::read_reversed;
+/// use bitbuffer::{BitReadBuffer, BitReadStream, LittleEndian};
+///
+/// # fn main() -> Result<()> {
+/// let mut stream = BitReadStream::new(BitReadBuffer::new(&[0b0000_0011], LittleEndian));
+/// assert_eq!(read_reversed(&mut stream, 4)?, 0b1100);
+/// # Ok(())
+/// # }
+/// ```
+pub fn read_reversed<E: Endianness>(stream: &mut BitReadStream<E>, count: usize) -> Result<u64> {
+    let value: u64 = stream.read_int(count)?;
+    if count == 0 {
+        return Ok(0);
+    }
+    Ok(value.reverse_bits() >> (64 - count))
+}
+
+/// The data whitening used by Bluetooth Low Energy
+///
+/// A 7 bit register with the polynomial `x^7 + x^4 + 1`, initialized from the channel index the packet is sent on.
+/// The whitening covers the pdu and crc but not the preamble and access address.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BleWhitening {
+    register: u8,
+}
+
+impl BleWhitening {
+    /// Create the whitening sequence for a channel
+    ///
+    /// # Panics
+    ///
+    /// Panics if `channel` is larger than 39
+    pub fn new(channel: u8) -> Self {
+        assert!(channel <= 39, "channel must be between 0 and 39");
+        BleWhitening {
+            register: channel | 0x40,
+        }
+    }
+}
+
+impl KeyStream for BleWhitening {
+    fn next_bit(&mut self) -> bool {
+        let bit = self.register & 1 == 1;
+        if bit {
+            self.register ^= 0x88;
+        }
+        self.register >>= 1;
+        bit
+    }
+}
+
+/// The PN9 data whitening used by the IEEE 802.15.4 SUN FSK phy
+///
+/// A 9 bit register with the polynomial `x^9 + x^5 + 1` initialized to all ones,
+/// the whitening covers the psdu but not the phy header.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Pn9Whitening {
+    register: u16,
+}
+
+impl Pn9Whitening {
+    /// Create the whitening sequence
+    pub fn new() -> Self {
+        Pn9Whitening { register: 0x1FF }
+    }
+}
+
+impl Default for Pn9Whitening {
+    fn default() -> Self {
+        Pn9Whitening::new()
+    }
+}
+
+impl KeyStream for Pn9Whitening {
+    fn next_bit(&mut self) -> bool {
+        let bit = self.register & 1;
+        let feedback = bit ^ (self.register >> 5 & 1);
+        self.register = self.register >> 1 | feedback << 8;
+        bit == 1
+    }
+}
+
+/// The 16 bit header of a Bluetooth Low Energy pdu
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct PduHeader {
+    /// The 4 bit pdu type
+    pub pdu_type: u8,
+    /// Whether the advertiser supports channel selection algorithm #2
+    pub ch_sel: bool,
+    /// Whether the transmitter address is a random address
+    pub tx_add: bool,
+    /// Whether the receiver address is a random address
+    pub rx_add: bool,
+    /// The length of the payload in bytes
+    pub length: u8,
+}
+
+/// Read the header of a Bluetooth Low Energy advertising pdu
+///
+/// # Errors
+///
+/// - [`ReadError::NotEnoughData`]: not enough bits available in the stream
+///
+/// [`ReadError::NotEnoughData`]: crate::BitError::NotEnoughData
+pub fn read_pdu_header(stream: &mut BitReadStream<LittleEndian>) -> Result<PduHeader> {
+    stream.restore_on_error(|stream| {
+        let pdu_type = stream.read_int(4)?;
+        stream.skip_bits(1)?;
+        Ok(PduHeader {
+            pdu_type,
+            ch_sel: stream.read_bool()?,
+            tx_add: stream.read_bool()?,
+            rx_add: stream.read_bool()?,
+            length: stream.read_int(8)?,
+        })
+    })
+}
+
+/// A Bluetooth Low Energy advertising pdu
+#[derive(Debug, Clone)]
+pub struct BlePdu<'a> {
+    /// The pdu header
+    pub header: PduHeader,
+    /// The payload of the pdu
+    pub payload: BitReadStream<'a, LittleEndian>,
+}
+
+/// Read a dewhitened Bluetooth Low Energy advertising pdu followed by its 24 bit crc, checking the crc
+///
+/// Advertising packets use [`CRC_24_BLE`](crate::crc::CRC_24_BLE), for packets sent over a connection the `init`
+/// value of the algorithm has to be set to the crc init of the connection.
+///
+/// # Errors
+///
+/// - [`ReadError::NotEnoughData`]: not enough bits available in the stream
+/// - [`ReadError::Malformed`]: the crc doesn't match
+///
+/// [`ReadError::NotEnoughData`]: crate::BitError::NotEnoughData
+/// [`ReadError::Malformed`]: crate::BitError::Malformed
+pub fn read_ble_pdu<'a>(
+    stream: &mut BitReadStream<'a, LittleEndian>,
+    crc: &CrcAlgorithm,
+) -> Result<BlePdu<'a>> {
+    let pos = stream.pos();
+    stream.restore_on_error(|stream| {
+        let mut pdu = stream.clone();
+        let header = read_pdu_header(stream)?;
+        let payload = stream.read_bits(header.length as usize * 8)?;
+        let expected: u64 = stream.read_int(24)?;
+        let mut check = Crc::new(crc);
+        check.update_stream(&mut pdu, 16 + header.length as usize * 8)?;
+        if check.finalize() != expected {
+            return Err(BitError::Malformed {
+                pos,
+                reason: "the pdu crc doesn't match",
+            });
+        }
+        Ok(BlePdu { header, payload })
+    })
+}
+
+/// The 16 bit frame control field of an IEEE 802.15.4 mac frame
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct FrameControl {
+    /// The 3 bit frame type, 0 for beacons, 1 for data, 2 for acknowledgements and 3 for mac commands
+    pub frame_type: u8,
+    /// Whether the frame is protected by the mac sublayer
+    pub security_enabled: bool,
+    /// Whether the sender has more data for the recipient
+    pub frame_pending: bool,
+    /// Whether the recipient has to send an acknowledgement
+    pub ack_request: bool,
+    /// Whether the source pan id is omitted because it is the same as the destination pan id
+    pub pan_id_compression: bool,
+    /// Whether the sequence number is omitted, only used by 2015 frames
+    pub sequence_number_suppression: bool,
+    /// Whether the frame contains information elements, only used by 2015 frames
+    pub ie_present: bool,
+    /// The 2 bit destination addressing mode, 0 for none, 2 for short and 3 for extended addresses
+    pub dest_addressing_mode: u8,
+    /// The 2 bit frame version
+    pub frame_version: u8,
+    /// The 2 bit source addressing mode, 0 for none, 2 for short and 3 for extended addresses
+    pub src_addressing_mode: u8,
+}
+
+/// Read the frame control field of an IEEE 802.15.4 mac frame
+///
+/// # Errors
+///
+/// - [`ReadError::NotEnoughData`]: not enough bits available in the stream
+///
+/// [`ReadError::NotEnoughData`]: crate::BitError::NotEnoughData
+pub fn read_frame_control(stream: &mut BitReadStream<LittleEndian>) -> Result<FrameControl> {
+    stream.restore_on_error(|stream| {
+        let frame_type = stream.read_int(3)?;
+        let security_enabled = stream.read_bool()?;
+        let frame_pending = stream.read_bool()?;
+        let ack_request = stream.read_bool()?;
+        let pan_id_compression = stream.read_bool()?;
+        stream.skip_bits(1)?;
+        Ok(FrameControl {
+            frame_type,
+            security_enabled,
+            frame_pending,
+            ack_request,
+            pan_id_compression,
+            sequence_number_suppression: stream.read_bool()?,
+            ie_present: stream.read_bool()?,
+            dest_addressing_mode: stream.read_int(2)?,
+            frame_version: stream.read_int(2)?,
+            src_addressing_mode: stream.read_int(2)?,
+        })
+    })
+}
+
+/// An IEEE 802.15.4 mac frame
+#[derive(Debug, Clone)]
+pub struct MacFrame<'a> {
+    /// The frame control field
+    pub frame_control: FrameControl,
+    /// The rest of the mac header and the payload, without the frame check sequence
+    pub body: BitReadStream<'a, LittleEndian>,
+}
+
+/// Read an IEEE 802.15.4 mac frame of `length` bytes, checking the 16 bit frame check sequence
+///
+/// The `length` is the psdu length from the phy header, which includes the frame check sequence.
+///
+/// # Errors
+///
+/// - [`ReadError::NotEnoughData`]: not enough bits available in the stream
+/// - [`ReadError::Malformed`]: the frame is too short or the frame check sequence doesn't match
+///
+/// [`ReadError::NotEnoughData`]: crate::BitError::NotEnoughData
+/// [`ReadError::Malformed`]: crate::BitError::Malformed
+pub fn read_mac_frame<'a>(
+    stream: &mut BitReadStream<'a, LittleEndian>,
+    length: usize,
+) -> Result<MacFrame<'a>> {
+    let pos = stream.pos();
+    if length < 4 {
+        return Err(BitError::Malformed {
+            pos,
+            reason:
+                "the frame is too short to contain a frame control field and frame check sequence",
+        });
+    }
+    stream.restore_on_error(|stream| {
+        let mut frame = stream.read_bits(length * 8)?;
+        let mut check = Crc::new(&CRC_16_KERMIT);
+        check.update_stream(&mut frame.clone(), (length - 2) * 8)?;
+        let frame_control = read_frame_control(&mut frame)?;
+        let body = frame.read_bits(frame.bits_left() - 16)?;
+        let expected: u64 = frame.read_int(16)?;
+        if check.finalize() != expected {
+            return Err(BitError::Malformed {
+                pos,
+                reason: "the frame check sequence doesn't match",
+            });
+        }
+        Ok(MacFrame {
+            frame_control,
+            body,
+        })
+    })
+}
@@ -0,0 +1,89 @@
+//! Decoding independent sections in parallel using [`rayon`]
+//!
+//! Formats that consist of independent blocks can be decoded across multiple cores by first splitting
+//! the data into a stream per block, for example using [`BitReadBuffer::chunks`] or [`split_length_prefixed`],
+//! and then decoding the streams with [`par_decode`] or [`par_read`].
+//!
+//! # Examples
+//!
+//! ```
+//! # use bitbuffer::Result;
+//! use bitbuffer::rayon::{par_read, split_length_prefixed};
+//! use bitbuffer::{BitReadBuffer, BitReadStream, LittleEndian};
+//!
+//! # fn main() -> Result<()> {
+//! // blocks with an 8 bit length in bytes, followed by the block
+//! let bytes = [2, 0x12, 0x34, 1, 0x56, 2, 0x78, 0x9A];
+//! let mut stream = BitReadStream::new(BitReadBuffer::new(&bytes, LittleEndian));
+//! let blocks = split_length_prefixed(&mut stream, 8, 8)?;
+//! let firsts: Vec<u8> = par_read(blocks)?;
+//! assert_eq!(firsts, [0x12, 0x56, 0x78]);
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! [`rayon`]: https://docs.rs/rayon
+//! [`BitReadBuffer::chunks`]: crate::BitReadBuffer::chunks
+
+use crate::{BitRead, BitReadStream, Endianness, Result};
+use ::rayon::prelude::*;
+
+/// Decode every section in parallel, returning the results in the order of the sections
+///
+/// # Errors
+///
+/// - the error returned by `decode` for the first section that failed
+pub fn par_decode<'a, E, T, F, I>(sections: I, decode: F) -> Result<Vec<T>>
+where
+    E: Endianness + Send + Sync,
+    T: Send,
+    F: Fn(&mut BitReadStream<'a, E>) -> Result<T> + Sync + Send,
+    I: IntoIterator<Item = BitReadStream<'a, E>>,
+{
+    let sections: Vec<_> = sections.into_iter().collect();
+    sections
+        .into_par_iter()
+        .map(|mut section| decode(&mut section))
+        .collect()
+}
+
+/// Read a `T` from the start of every section in parallel
+///
+/// # Errors
+///
+/// - the error from reading the first section that failed
+pub fn par_read<'a, E, T, I>(sections: I) -> Result<Vec<T>>
+where
+    E: Endianness + Send + Sync,
+    T: BitRead<'a, E> + Send,
+    I: IntoIterator<Item = BitReadStream<'a, E>>,
+{
+    par_decode(sections, |section| section.read())
+}
+
+/// Split the rest of the stream into length prefixed blocks
+///
+/// Every block starts with a `length_bits` bits long length, multiplied by `unit` to get the size of the block in bits.
+/// The returned streams contain the blocks without the length prefix.
+///
+/// # Errors
+///
+/// - [`ReadError::NotEnoughData`]: a block is longer than the remaining data, the stream is not advanced
+/// - [`ReadError::TooManyBits`]: `length_bits` is larger than the size of `usize`
+///
+/// [`ReadError::NotEnoughData`]: enum.ReadError.html#variant.NotEnoughData
+/// [`ReadError::TooManyBits`]: enum.ReadError.html#variant.TooManyBits
+pub fn split_length_prefixed<'a, E: Endianness>(
+    stream: &mut BitReadStream<'a, E>,
+    length_bits: usize,
+    unit: usize,
+) -> Result<Vec<BitReadStream<'a, E>>> {
+    let mut reader = stream.clone();
+    let mut blocks = Vec::new();
+    while reader.bits_left() > 0 {
+        let length: usize = reader.read_int(length_bits)?;
+        blocks.push(reader.read_bits(length * unit)?);
+    }
+    stream.skip_bits(reader.pos())?;
+    Ok(blocks)
+}
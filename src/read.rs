@@ -1,5 +1,6 @@
 use crate::endianness::{BigEndian, LittleEndian};
-use crate::{BitReadStream, Endianness, Result};
+use crate::readstream::checked_bits;
+use crate::{BitError, BitReadStream, Endianness, ReadLimit, Result};
 use std::borrow::Cow;
 use std::cell::RefCell;
 use std::cmp::min;
@@ -91,6 +92,9 @@ use std::sync::Arc;
 /// [read]: BitReadStream::read
 pub trait BitRead<'a, E: Endianness>: Sized {
     /// Read the type from stream
+    ///
+    /// Implementations don't need to restore the stream position on failure,
+    /// [`BitReadStream::read`] and [`BitReadStream::read_sized`] take care of that.
     fn read(stream: &mut BitReadStream<'a, E>) -> Result<Self>;
 
     /// Note: only the bounds are unchecked
@@ -258,6 +262,41 @@ impl<E: Endianness> BitRead<'_, E> for bool {
     }
 }
 
+/// Reads zero bits, allowing generic code to use `()` for parts that aren't present
+impl<E: Endianness> BitRead<'_, E> for () {
+    #[inline]
+    fn read(_stream: &mut BitReadStream<E>) -> Result<()> {
+        Ok(())
+    }
+
+    #[inline]
+    fn bit_size() -> Option<usize> {
+        Some(0)
+    }
+}
+
+/// Reads zero bits
+impl<E: Endianness, T: ?Sized> BitRead<'_, E> for PhantomData<T> {
+    #[inline]
+    fn read(_stream: &mut BitReadStream<E>) -> Result<Self> {
+        Ok(PhantomData)
+    }
+
+    #[inline]
+    fn bit_size() -> Option<usize> {
+        Some(0)
+    }
+}
+
+/// Read a single utf8 encoded character, see [`BitReadStream::read_string_chars`]
+impl<E: Endianness> BitRead<'_, E> for char {
+    #[inline]
+    fn read(stream: &mut BitReadStream<E>) -> Result<char> {
+        let string = stream.read_string_chars(1)?;
+        Ok(string.chars().next().expect("one character was read"))
+    }
+}
+
 impl<E: Endianness> BitRead<'_, E> for String {
     #[inline]
     fn read(stream: &mut BitReadStream<E>) -> Result<String> {
@@ -272,6 +311,14 @@ impl<'a, E: Endianness> BitRead<'a, E> for Cow<'a, str> {
     }
 }
 
+/// Read a null terminated string using the interner of the stream, see [`BitReadStream::read_string_interned`]
+impl<E: Endianness> BitRead<'_, E> for Arc<str> {
+    #[inline]
+    fn read(stream: &mut BitReadStream<E>) -> Result<Self> {
+        stream.read_string_interned(None)
+    }
+}
+
 impl<'a, E: Endianness, T: BitRead<'a, E>> BitRead<'a, E> for Rc<T> {
     #[inline]
     fn read(stream: &mut BitReadStream<'a, E>) -> Result<Self> {
@@ -457,6 +504,9 @@ impl<'a, E: Endianness, T: BitRead<'a, E>, const N: usize> BitRead<'a, E> for [T
 /// [read]: BitReadStream::read
 pub trait BitReadSized<'a, E: Endianness>: Sized {
     /// Read the type from stream
+    ///
+    /// Implementations don't need to restore the stream position on failure,
+    /// [`BitReadStream::read`] and [`BitReadStream::read_sized`] take care of that.
     fn read(stream: &mut BitReadStream<'a, E>, size: usize) -> Result<Self>;
 
     #[doc(hidden)]
@@ -524,6 +574,34 @@ impl_read_int_sized!(i32);
 impl_read_int_sized!(i64);
 impl_read_int_sized!(i128);
 
+/// Read a code point stored as an integer of `size` bits, such as a utf32 code unit
+///
+/// Values that aren't a valid unicode scalar value result in a [`BitError::Malformed`] and the stream is not advanced.
+///
+/// [`BitError::Malformed`]: crate::BitError::Malformed
+impl<E: Endianness> BitReadSized<'_, E> for char {
+    #[inline]
+    fn read(stream: &mut BitReadStream<E>, size: usize) -> Result<char> {
+        let pos = stream.pos();
+        let code_point = stream.read_int::<u32>(size)?;
+        match char::from_u32(code_point) {
+            Some(char) => Ok(char),
+            None => {
+                stream.set_pos(pos)?;
+                Err(BitError::Malformed {
+                    pos,
+                    reason: "not a valid unicode scalar value",
+                })
+            }
+        }
+    }
+
+    #[inline]
+    fn bit_size_sized(size: usize) -> Option<usize> {
+        Some(size)
+    }
+}
+
 impl<E: Endianness> BitReadSized<'_, E> for String {
     #[inline]
     fn read(stream: &mut BitReadStream<E>, size: usize) -> Result<String> {
@@ -532,7 +610,7 @@ impl<E: Endianness> BitReadSized<'_, E> for String {
 
     #[inline]
     fn bit_size_sized(size: usize) -> Option<usize> {
-        Some(8 * size)
+        size.checked_mul(8)
     }
 }
 
@@ -544,7 +622,20 @@ impl<'a, E: Endianness> BitReadSized<'a, E> for Cow<'a, str> {
 
     #[inline]
     fn bit_size_sized(size: usize) -> Option<usize> {
-        Some(8 * size)
+        size.checked_mul(8)
+    }
+}
+
+/// Read a fixed length string using the interner of the stream, see [`BitReadStream::read_string_interned`]
+impl<E: Endianness> BitReadSized<'_, E> for Arc<str> {
+    #[inline]
+    fn read(stream: &mut BitReadStream<E>, size: usize) -> Result<Self> {
+        stream.read_string_interned(Some(size))
+    }
+
+    #[inline]
+    fn bit_size_sized(size: usize) -> Option<usize> {
+        size.checked_mul(8)
     }
 }
 
@@ -556,7 +647,7 @@ impl<'a, E: Endianness> BitReadSized<'a, E> for Cow<'a, [u8]> {
 
     #[inline]
     fn bit_size_sized(size: usize) -> Option<usize> {
-        Some(8 * size)
+        size.checked_mul(8)
     }
 }
 
@@ -596,21 +687,25 @@ impl<'a, E: Endianness> BitReadSized<'a, E> for BitReadStream<'a, E> {
 /// Read `T` `size` times and return as `Vec<T>`
 impl<'a, E: Endianness, T: BitRead<'a, E>> BitReadSized<'a, E> for Vec<T> {
     fn read(stream: &mut BitReadStream<'a, E>, size: usize) -> Result<Self> {
+        stream.check_limit(ReadLimit::Elements, size)?;
         let mut vec = Vec::with_capacity(min(size, 128));
         match T::bit_size() {
             Some(bit_size) => {
-                if stream.check_read(bit_size * size)? {
+                if stream.check_read(checked_bits(size, bit_size)?)? {
                     for _ in 0..size {
+                        stream.check_cancelled()?;
                         vec.push(unsafe { stream.read_unchecked(true) }?)
                     }
                 } else {
                     for _ in 0..size {
+                        stream.check_cancelled()?;
                         vec.push(unsafe { stream.read_unchecked(false) }?)
                     }
                 }
             }
             _ => {
                 for _ in 0..size {
+                    stream.check_cancelled()?;
                     vec.push(stream.read()?)
                 }
             }
@@ -624,8 +719,10 @@ impl<'a, E: Endianness, T: BitRead<'a, E>> BitReadSized<'a, E> for Vec<T> {
         size: usize,
         end: bool,
     ) -> Result<Self> {
+        stream.check_limit(ReadLimit::Elements, size)?;
         let mut vec = Vec::with_capacity(min(size, 128));
         for _ in 0..size {
+            stream.check_cancelled()?;
             vec.push(stream.read_unchecked(end)?)
         }
         Ok(vec)
@@ -637,6 +734,36 @@ impl<'a, E: Endianness, T: BitRead<'a, E>> BitReadSized<'a, E> for Vec<T> {
     }
 }
 
+macro_rules! impl_read_sized_slice {
+    ($type:ident) => {
+        #[doc = concat!("Read `T` `size` times and return as `", stringify!($type), "<[T]>`")]
+        impl<'a, E: Endianness, T: BitRead<'a, E>> BitReadSized<'a, E> for $type<[T]> {
+            #[inline]
+            fn read(stream: &mut BitReadStream<'a, E>, size: usize) -> Result<Self> {
+                Vec::<T>::read(stream, size).map($type::from)
+            }
+
+            #[inline]
+            unsafe fn read_unchecked(
+                stream: &mut BitReadStream<'a, E>,
+                size: usize,
+                end: bool,
+            ) -> Result<Self> {
+                Vec::<T>::read_unchecked(stream, size, end).map($type::from)
+            }
+
+            #[inline]
+            fn bit_size_sized(size: usize) -> Option<usize> {
+                Vec::<T>::bit_size_sized(size)
+            }
+        }
+    };
+}
+
+impl_read_sized_slice!(Box);
+impl_read_sized_slice!(Rc);
+impl_read_sized_slice!(Arc);
+
 // Once we have something like https://github.com/rust-lang/rfcs/issues/1053 we can do this optimization
 //impl<E: Endianness> ReadSized<E> for Vec<u8> {
 //    #[inline]
@@ -651,8 +778,10 @@ impl<'a, E: Endianness, K: BitRead<'a, E> + Eq + Hash, T: BitRead<'a, E>> BitRea
     for HashMap<K, T>
 {
     fn read(stream: &mut BitReadStream<'a, E>, size: usize) -> Result<Self> {
+        stream.check_limit(ReadLimit::Elements, size)?;
         let mut map = HashMap::with_capacity(min(size, 128));
         for _ in 0..size {
+            stream.check_cancelled()?;
             let key = stream.read()?;
             let value = stream.read()?;
             map.insert(key, value);
@@ -666,8 +795,10 @@ impl<'a, E: Endianness, K: BitRead<'a, E> + Eq + Hash, T: BitRead<'a, E>> BitRea
         size: usize,
         end: bool,
     ) -> Result<Self> {
+        stream.check_limit(ReadLimit::Elements, size)?;
         let mut map = HashMap::with_capacity(min(size, 128));
         for _ in 0..size {
+            stream.check_cancelled()?;
             let key = stream.read_unchecked(end)?;
             let value = stream.read_unchecked(end)?;
             map.insert(key, value);
@@ -758,7 +889,7 @@ impl<'a, E: Endianness, T: BitReadSized<'a, E>, const N: usize> BitReadSized<'a,
     fn read(stream: &mut BitReadStream<'a, E>, size: usize) -> Result<Self> {
         match T::bit_size_sized(size) {
             Some(bit_size) => {
-                let end = stream.check_read(bit_size * N)?;
+                let end = stream.check_read(checked_bits(N, bit_size)?)?;
                 unsafe { Self::read_unchecked(stream, size, end) }
             }
             None => {
@@ -797,6 +928,6 @@ impl<'a, E: Endianness, T: BitReadSized<'a, E>, const N: usize> BitReadSized<'a,
 
     #[inline]
     fn bit_size_sized(size: usize) -> Option<usize> {
-        T::bit_size_sized(size).map(|size| size * N)
+        T::bit_size_sized(size).and_then(|size| size.checked_mul(N))
     }
 }
@@ -9,41 +9,172 @@ use num_traits::{Float, PrimInt};
 
 use crate::endianness::Endianness;
 use crate::num_traits::{IsSigned, UncheckedPrimitiveFloat, UncheckedPrimitiveInt};
-use crate::{BitError, Result};
+use crate::readstream::checked_bits;
+use crate::{BitError, BitReadStream, Result};
 use std::borrow::{Borrow, Cow};
 use std::convert::TryInto;
-use std::rc::Rc;
+use std::sync::{Arc, OnceLock};
 
 const USIZE_SIZE: usize = size_of::<usize>();
 const USIZE_BIT_SIZE: usize = USIZE_SIZE * 8;
 
-// Cow<[u8]> but with cheap clones using Rc
+// Cow<[u8]> but with cheap clones using Arc
 pub(crate) enum Data<'a> {
     Borrowed(&'a [u8]),
-    Owned(Rc<[u8]>),
+    Owned(Arc<[u8]>),
+    Segmented(Arc<Segments<'a>>),
 }
 
 impl<'a> Data<'a> {
+    /// Note: for segmented data this copies the segments into a contiguous allocation the first time it's called
     pub fn as_slice(&self) -> &[u8] {
         match self {
-            Data::Borrowed(bytes) => *bytes,
+            Data::Borrowed(bytes) => bytes,
             Data::Owned(bytes) => bytes.borrow(),
+            Data::Segmented(segments) => segments.contiguous(),
         }
     }
 
     pub fn len(&self) -> usize {
-        self.as_slice().len()
+        match self {
+            Data::Segmented(segments) => segments.len,
+            _ => self.as_slice().len(),
+        }
+    }
+
+    /// Whether both refer to the same bytes in memory
+    pub fn same(&self, other: &Data) -> bool {
+        match (self, other) {
+            (Data::Borrowed(a), Data::Borrowed(b)) => std::ptr::eq(*a, *b),
+            (Data::Owned(a), Data::Owned(b)) => Arc::ptr_eq(a, b),
+            (Data::Segmented(a), Data::Segmented(b)) => {
+                std::ptr::eq(Arc::as_ptr(a).cast::<()>(), Arc::as_ptr(b).cast::<()>())
+            }
+            _ => false,
+        }
     }
 
     pub fn to_owned(&self) -> Data<'static> {
         let bytes = match self {
-            Data::Borrowed(bytes) => Rc::from(bytes.to_vec()),
-            Data::Owned(bytes) => Rc::clone(bytes),
+            Data::Borrowed(bytes) => Arc::from(bytes.to_vec()),
+            Data::Owned(bytes) => Arc::clone(bytes),
+            Data::Segmented(segments) => Arc::from(segments.contiguous()),
         };
         Data::Owned(bytes)
     }
 }
 
+/// A list of byte slices that are read as if they were a single slice
+pub(crate) struct Segments<'a> {
+    segments: Vec<&'a [u8]>,
+    /// The byte offset of the start of every segment
+    starts: Vec<usize>,
+    len: usize,
+    contiguous: OnceLock<Box<[u8]>>,
+}
+
+impl<'a> Segments<'a> {
+    fn new<I: IntoIterator<Item = &'a [u8]>>(segments: I) -> Self {
+        let segments: Vec<_> = segments
+            .into_iter()
+            .filter(|segment| !segment.is_empty())
+            .collect();
+        let mut starts = Vec::with_capacity(segments.len());
+        let mut len = 0usize;
+        for segment in &segments {
+            starts.push(len);
+            // saturate so the length is rejected when it's converted to bits instead of wrapping around
+            len = len.saturating_add(segment.len());
+        }
+        Segments {
+            segments,
+            starts,
+            len,
+            contiguous: OnceLock::new(),
+        }
+    }
+
+    /// The index of the segment containing `byte_index` and the offset within that segment
+    fn locate(&self, byte_index: usize) -> (usize, usize) {
+        let segment = self.starts.partition_point(|start| *start <= byte_index) - 1;
+        (segment, byte_index - self.starts[segment])
+    }
+
+    #[cold]
+    #[inline(never)]
+    fn byte(&self, byte_index: usize) -> u8 {
+        let (segment, offset) = self.locate(byte_index);
+        self.segments[segment][offset]
+    }
+
+    #[cold]
+    #[inline(never)]
+    fn word_bytes<const N: usize>(&self, byte_index: usize) -> [u8; N] {
+        let mut bytes = [0; N];
+        self.copy_to(byte_index, &mut bytes);
+        bytes
+    }
+
+    /// Copy the bytes starting at `byte_index` into `target`, bytes past the end are set to 0
+    fn copy_to(&self, byte_index: usize, target: &mut [u8]) {
+        target.fill(0);
+        if byte_index >= self.len {
+            return;
+        }
+        let (mut segment, mut offset) = self.locate(byte_index);
+        let mut written = 0;
+        while written < target.len() && segment < self.segments.len() {
+            let source = &self.segments[segment][offset..];
+            let count = min(source.len(), target.len() - written);
+            target[written..written + count].copy_from_slice(&source[..count]);
+            written += count;
+            segment += 1;
+            offset = 0;
+        }
+    }
+
+    /// The bytes in `start..end`, borrowed if the range is within a single segment
+    #[cold]
+    #[inline(never)]
+    fn range(&self, start: usize, end: usize) -> Cow<'a, [u8]> {
+        if start == end {
+            return Cow::Borrowed(&[]);
+        }
+        let (segment, offset) = self.locate(start);
+        let source: &'a [u8] = self.segments[segment];
+        if offset + (end - start) <= source.len() {
+            Cow::Borrowed(&source[offset..offset + (end - start)])
+        } else {
+            let mut bytes = vec![0; end - start];
+            self.copy_to(start, &mut bytes);
+            Cow::Owned(bytes)
+        }
+    }
+
+    /// Find the first null byte at or after `byte_index`, or the end of the data
+    #[cold]
+    #[inline(never)]
+    fn find_null_byte(&self, byte_index: usize) -> usize {
+        if byte_index >= self.len {
+            return self.len;
+        }
+        let (first, offset) = self.locate(byte_index);
+        let mut offset = offset;
+        for segment in first..self.segments.len() {
+            if let Some(index) = memchr::memchr(0, &self.segments[segment][offset..]) {
+                return self.starts[segment] + offset + index;
+            }
+            offset = 0;
+        }
+        self.len
+    }
+
+    fn contiguous(&self) -> &[u8] {
+        self.contiguous
+            .get_or_init(|| self.segments.concat().into_boxed_slice())
+    }
+}
+
 impl<'a> Index<Range<usize>> for Data<'a> {
     type Output = [u8];
 
@@ -71,8 +202,9 @@ impl<'a> Index<usize> for Data<'a> {
 impl<'a> Clone for Data<'a> {
     fn clone(&self) -> Self {
         match self {
-            Data::Borrowed(bytes) => Data::Borrowed(*bytes),
-            Data::Owned(bytes) => Data::Owned(Rc::clone(bytes)),
+            Data::Borrowed(bytes) => Data::Borrowed(bytes),
+            Data::Owned(bytes) => Data::Owned(Arc::clone(bytes)),
+            Data::Segmented(segments) => Data::Segmented(Arc::clone(segments)),
         }
     }
 }
@@ -96,6 +228,11 @@ impl<'a> Clone for Data<'a> {
 /// #     Ok(())
 /// # }
 /// ```
+///
+/// # Thread safety
+///
+/// The buffer is `Send` and `Sync`, cloning it is cheap and never copies the data.
+/// See [`SharedBitReadBuffer`](crate::SharedBitReadBuffer) for a buffer that is guaranteed to own its data.
 pub struct BitReadBuffer<'a, E>
 where
     E: Endianness,
@@ -106,6 +243,29 @@ where
     slice: &'a [u8],
 }
 
+/// The largest read that is served from a [`WordCache`]
+const CACHED_READ_BITS: usize = 32;
+
+/// A 64 bit word of the buffer, kept by a stream to speed up consecutive small reads
+///
+/// Since the buffer is immutable the cached word never goes stale, a read only has to check if the requested bits
+/// are covered by the cached word.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct WordCache {
+    /// The bit position of the first bit in the word
+    start: usize,
+    word: u64,
+}
+
+impl Default for WordCache {
+    fn default() -> Self {
+        WordCache {
+            start: usize::MAX,
+            word: 0,
+        }
+    }
+}
+
 impl<'a, E> BitReadBuffer<'a, E>
 where
     E: Endianness,
@@ -123,15 +283,73 @@ where
     /// ];
     /// let buffer = BitReadBuffer::new(&bytes, LittleEndian);
     /// ```
-    pub fn new(bytes: &'a [u8], _endianness: E) -> Self {
-        let byte_len = bytes.len();
+    ///
+    /// # Panics
+    ///
+    /// Panics if the number of bits in the slice doesn't fit in a `usize`, which can only happen for slices
+    /// of 512MiB or more on 32 bit targets. Use [`try_new`](Self::try_new) to handle this as an error instead.
+    pub fn new(bytes: &'a [u8], endianness: E) -> Self {
+        Self::try_new(bytes, endianness)
+            .expect("the number of bits in the buffer doesn't fit in a usize")
+    }
 
-        BitReadBuffer {
+    /// Create a new BitBuffer from a byte slice, failing if the number of bits in the slice doesn't fit in a `usize`
+    ///
+    /// # Errors
+    ///
+    /// - [`ReadError::SizeOverflow`]: the number of bits in the slice doesn't fit in a `usize`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use bitbuffer::Result;
+    /// use bitbuffer::{BitReadBuffer, LittleEndian};
+    ///
+    /// # fn main() -> Result<()> {
+    /// let bytes = vec![0b1011_0101, 0b0110_1010];
+    /// let buffer = BitReadBuffer::try_new(&bytes, LittleEndian)?;
+    /// assert_eq!(buffer.bit_len(), 16);
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`ReadError::SizeOverflow`]: enum.ReadError.html#variant.SizeOverflow
+    pub fn try_new(bytes: &'a [u8], _endianness: E) -> Result<Self> {
+        Ok(BitReadBuffer {
             bytes: Data::Borrowed(bytes),
-            bit_len: byte_len * 8,
+            bit_len: checked_bits(bytes.len(), 8)?,
             endianness: PhantomData,
             slice: bytes,
-        }
+        })
+    }
+
+    /// Create a new BitBuffer from a byte slice, containing only the first `bit_len` bits
+    ///
+    /// This allows reading data that doesn't end on a byte boundary, like the output of [`BitWriteStream::finish`],
+    /// without the padding bits being readable.
+    ///
+    /// # Errors
+    ///
+    /// - [`ReadError::NotEnoughData`]: `bit_len` is larger than the number of bits in the slice
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use bitbuffer::Result;
+    /// use bitbuffer::{BitReadBuffer, LittleEndian};
+    ///
+    /// # fn main() -> Result<()> {
+    /// let bytes = vec![0b1011_0101, 0b0110_1010];
+    /// let buffer = BitReadBuffer::with_bit_len(&bytes, 12, LittleEndian)?;
+    /// assert_eq!(buffer.bit_len(), 12);
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`BitWriteStream::finish`]: crate::BitWriteStream::finish
+    /// [`ReadError::NotEnoughData`]: enum.ReadError.html#variant.NotEnoughData
+    pub fn with_bit_len(bytes: &'a [u8], bit_len: usize, endianness: E) -> Result<Self> {
+        BitReadBuffer::try_new(bytes, endianness)?.get_sub_buffer(bit_len)
     }
 
     /// Create a static version of this buffer
@@ -139,7 +357,6 @@ where
     /// If the current buffer is borrowed, this will copy the data
     pub fn to_owned(&self) -> BitReadBuffer<'static, E> {
         let bytes = self.bytes.to_owned();
-        let byte_len = bytes.len();
 
         // this is safe because
         //  - the slice can only be access trough this struct
@@ -149,7 +366,7 @@ where
 
         BitReadBuffer {
             bytes,
-            bit_len: byte_len * 8,
+            bit_len: self.bit_len,
             endianness: PhantomData,
             slice,
         }
@@ -173,9 +390,46 @@ where
     /// ];
     /// let buffer = BitReadBuffer::new_owned(bytes, LittleEndian);
     /// ```
-    pub fn new_owned(bytes: Vec<u8>, _endianness: E) -> Self {
-        let byte_len = bytes.len();
-        let bytes = Data::Owned(Rc::from(bytes));
+    ///
+    /// # Panics
+    ///
+    /// Panics if the number of bits in the vector doesn't fit in a `usize`, see [`from_arc`](Self::from_arc).
+    pub fn new_owned(bytes: Vec<u8>, endianness: E) -> Self {
+        BitReadBuffer::from_arc(Arc::from(bytes), endianness)
+    }
+
+    /// Create a new BitBuffer from reference counted bytes, without copying them
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bitbuffer::{BitReadBuffer, LittleEndian};
+    /// use std::sync::Arc;
+    ///
+    /// let bytes: Arc<[u8]> = Arc::from(vec![0b1011_0101, 0b0110_1010]);
+    /// let buffer = BitReadBuffer::from_arc(Arc::clone(&bytes), LittleEndian);
+    /// assert_eq!(buffer.bit_len(), 16);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if the number of bits in the slice doesn't fit in a `usize`, which can only happen for slices
+    /// of 512MiB or more on 32 bit targets. Use [`try_from_arc`](Self::try_from_arc) to handle this as an error instead.
+    pub fn from_arc(bytes: Arc<[u8]>, endianness: E) -> Self {
+        Self::try_from_arc(bytes, endianness)
+            .expect("the number of bits in the buffer doesn't fit in a usize")
+    }
+
+    /// Create a new BitBuffer from reference counted bytes, failing if the number of bits doesn't fit in a `usize`
+    ///
+    /// # Errors
+    ///
+    /// - [`ReadError::SizeOverflow`]: the number of bits in the slice doesn't fit in a `usize`
+    ///
+    /// [`ReadError::SizeOverflow`]: enum.ReadError.html#variant.SizeOverflow
+    pub fn try_from_arc(bytes: Arc<[u8]>, _endianness: E) -> Result<Self> {
+        let bit_len = checked_bits(bytes.len(), 8)?;
+        let bytes = Data::Owned(bytes);
 
         // this is safe because
         //  - the slice can only be access trough this struct
@@ -183,12 +437,211 @@ where
         //  - this struct doesn't allow mutation
         let slice = unsafe { std::slice::from_raw_parts(bytes.as_slice().as_ptr(), bytes.len()) };
 
-        BitReadBuffer {
+        Ok(BitReadBuffer {
             bytes,
-            bit_len: byte_len * 8,
+            bit_len,
             endianness: PhantomData,
             slice,
+        })
+    }
+
+    /// Create a new BitBuffer from a string of hex digits
+    ///
+    /// Whitespace and `_` between the digits are ignored.
+    ///
+    /// # Errors
+    ///
+    /// - [`ReadError::Malformed`]: the string contains a character that isn't a hex digit or an odd number of digits
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use bitbuffer::{BitReadBuffer, BitReadStream, BigEndian, Result};
+    /// #
+    /// # fn main() -> Result<()> {
+    /// let buffer = BitReadBuffer::from_hex("dead BEEF", BigEndian)?;
+    /// assert_eq!(buffer.bit_len(), 32);
+    /// assert_eq!(BitReadStream::new(buffer).read_int::<u32>(32)?, 0xDEAD_BEEF);
+    /// #
+    /// #     Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`ReadError::Malformed`]: enum.ReadError.html#variant.Malformed
+    pub fn from_hex(hex: &str, endianness: E) -> Result<Self> {
+        let mut bytes = Vec::with_capacity(hex.len() / 2);
+        let mut high = None;
+        for digit in hex.chars().filter(|&c| !c.is_whitespace() && c != '_') {
+            let pos = (bytes.len() * 2 + high.is_some() as usize) * 4;
+            let value = digit.to_digit(16).ok_or(BitError::Malformed {
+                pos,
+                reason: "invalid hex digit",
+            })? as u8;
+            match high.take() {
+                Some(high) => bytes.push(high << 4 | value),
+                None => high = Some(value),
+            }
+        }
+        if high.is_some() {
+            return Err(BitError::Malformed {
+                pos: bytes.len() * 8,
+                reason: "odd number of hex digits",
+            });
+        }
+        BitReadBuffer::try_from_arc(Arc::from(bytes), endianness)
+    }
+
+    /// Create a new BitBuffer from a string of `0` and `1` characters, in the order the bits are read
+    ///
+    /// Whitespace and `_` between the bits are ignored, the length doesn't have to be a multiple of 8.
+    ///
+    /// # Errors
+    ///
+    /// - [`ReadError::Malformed`]: the string contains a character other than `0`, `1`, whitespace or `_`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use bitbuffer::{BitReadBuffer, BitReadStream, LittleEndian, Result};
+    /// #
+    /// # fn main() -> Result<()> {
+    /// let buffer = BitReadBuffer::from_bit_string("1010 0111 01", LittleEndian)?;
+    /// assert_eq!(buffer.bit_len(), 10);
+    /// let mut stream = BitReadStream::new(buffer);
+    /// assert_eq!(stream.read_bool()?, true);
+    /// assert_eq!(stream.read_bool()?, false);
+    /// #
+    /// #     Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`ReadError::Malformed`]: enum.ReadError.html#variant.Malformed
+    pub fn from_bit_string(bits: &str, endianness: E) -> Result<Self> {
+        let mut bytes = Vec::with_capacity(bits.len() / 8);
+        let mut bit_len = 0;
+        for bit in bits.chars().filter(|&c| !c.is_whitespace() && c != '_') {
+            let set = match bit {
+                '0' => false,
+                '1' => true,
+                _ => {
+                    return Err(BitError::Malformed {
+                        pos: bit_len,
+                        reason: "invalid character in bit string",
+                    })
+                }
+            };
+            let shift = bit_len % 8;
+            if shift == 0 {
+                bytes.push(0);
+            }
+            if set {
+                let last = bytes.len() - 1;
+                bytes[last] |= if E::is_le() {
+                    1 << shift
+                } else {
+                    0x80 >> shift
+                };
+            }
+            bit_len += 1;
         }
+        BitReadBuffer::new_owned(bytes, endianness).get_sub_buffer(bit_len)
+    }
+}
+
+impl<'a, E> BitReadBuffer<'a, E>
+where
+    E: Endianness,
+{
+    /// Create a new BitBuffer that reads from a list of byte slices as if they were a single slice
+    ///
+    /// This allows reading data that was received in multiple parts, like reassembled network packets,
+    /// without copying it into a single allocation first. Reads that cross the boundary between two
+    /// slices are handled transparently.
+    ///
+    /// Only the `bitvec` conversions need the data to be contiguous, the segments are copied the first time they are used.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use bitbuffer::{BitReadBuffer, BitReadStream, BigEndian, Result};
+    /// use std::io::IoSlice;
+    /// #
+    /// # fn main() -> Result<()> {
+    /// let first = [0x12, 0x34];
+    /// let second = [0x56, b'a', b'b'];
+    /// let third = [b'c', 0];
+    /// let slices = [IoSlice::new(&first), IoSlice::new(&second), IoSlice::new(&third)];
+    /// let buffer = BitReadBuffer::from_segments(slices.iter().map(|slice| &**slice), BigEndian);
+    /// assert_eq!(buffer.byte_len(), 7);
+    ///
+    /// let mut stream = BitReadStream::new(buffer);
+    /// assert_eq!(stream.read_int::<u32>(24)?, 0x123456);
+    /// assert_eq!(stream.read_string(None)?, "abc");
+    /// #
+    /// #     Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if the combined number of bits in the slices doesn't fit in a `usize`.
+    /// Use [`try_from_segments`](Self::try_from_segments) to handle this as an error instead.
+    pub fn from_segments<I>(segments: I, endianness: E) -> Self
+    where
+        I: IntoIterator<Item = &'a [u8]>,
+    {
+        Self::try_from_segments(segments, endianness)
+            .expect("the number of bits in the buffer doesn't fit in a usize")
+    }
+
+    /// Create a new BitBuffer that reads from a list of byte slices, failing if the combined number of bits doesn't fit in a `usize`
+    ///
+    /// # Errors
+    ///
+    /// - [`ReadError::SizeOverflow`]: the combined number of bits in the slices doesn't fit in a `usize`
+    ///
+    /// [`ReadError::SizeOverflow`]: enum.ReadError.html#variant.SizeOverflow
+    pub fn try_from_segments<I>(segments: I, _endianness: E) -> Result<Self>
+    where
+        I: IntoIterator<Item = &'a [u8]>,
+    {
+        let segments = Segments::new(segments);
+
+        Ok(BitReadBuffer {
+            bit_len: checked_bits(segments.len, 8)?,
+            bytes: Data::Segmented(Arc::new(segments)),
+            endianness: PhantomData,
+            // all reads from segmented data go through the segments
+            slice: &[],
+        })
+    }
+}
+
+/// Copy the bytes starting `shift` (1 to 7) bits into `source` into `target`
+///
+/// `source` has to be one byte longer than `target`.
+/// The bytes are merged a u64 at a time, which the compiler can vectorize.
+fn unshift_into<E: Endianness>(source: &[u8], target: &mut [u8], shift: usize) {
+    debug_assert_eq!(source.len(), target.len() + 1);
+    debug_assert!(shift > 0 && shift < 8);
+
+    let full = target.len() / 8 * 8;
+    for i in (0..full).step_by(8) {
+        let word: [u8; 8] = source[i..i + 8].try_into().unwrap();
+        let next = source[i + 8] as u64;
+        let bytes = if E::is_le() {
+            (u64::from_le_bytes(word) >> shift | next << (64 - shift)).to_le_bytes()
+        } else {
+            (u64::from_be_bytes(word) << shift | next >> (8 - shift)).to_be_bytes()
+        };
+        target[i..i + 8].copy_from_slice(&bytes);
+    }
+    for i in full..target.len() {
+        target[i] = if E::is_le() {
+            source[i] >> shift | source[i + 1] << (8 - shift)
+        } else {
+            source[i] << shift | source[i + 1] >> (8 - shift)
+        };
     }
 }
 
@@ -197,14 +650,14 @@ pub(crate) fn get_bits_from_usize<E: Endianness>(
     bit_offset: usize,
     count: usize,
 ) -> usize {
-    let usize_bit_size = size_of::<usize>() * 8;
+    let usize_bit_size = usize::BITS as usize;
 
     let shifted = if E::is_le() {
         val >> bit_offset
     } else {
         val >> (usize_bit_size - bit_offset - count)
     };
-    let mask = !(std::usize::MAX << count);
+    let mask = !(usize::MAX << count);
     shifted & mask
 }
 
@@ -219,31 +672,164 @@ where
 
     /// The available number of bytes in the buffer
     pub fn byte_len(&self) -> usize {
-        self.slice.len()
+        self.bytes.len()
     }
 
-    unsafe fn read_usize_bytes(&self, byte_index: usize, end: bool) -> [u8; USIZE_SIZE] {
-        if end {
-            let mut bytes = [0; USIZE_SIZE];
-            let count = min(USIZE_SIZE, self.slice.len() - byte_index);
-            bytes[0..count]
-                .copy_from_slice(self.slice.get_unchecked(byte_index..byte_index + count));
-            bytes
+    /// Split the buffer into two independent streams at the given bit position
+    ///
+    /// The first stream contains the bits before `bit`, the second stream the bits from `bit` onwards.
+    /// Both streams start at position `0` and can be sent to different threads.
+    ///
+    /// # Errors
+    ///
+    /// - [`ReadError::NotEnoughData`]: `bit` is past the end of the buffer
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use bitbuffer::{BitReadBuffer, LittleEndian, Result};
+    /// #
+    /// # fn main() -> Result<()> {
+    /// let bytes = vec![0b1011_0101, 0b0110_1010];
+    /// let buffer = BitReadBuffer::new(&bytes, LittleEndian);
+    /// let (mut header, mut payload) = buffer.split_at(4)?;
+    /// assert_eq!(header.bit_len(), 4);
+    /// assert_eq!(header.read_int::<u8>(4)?, 0b0101);
+    /// assert_eq!(payload.bit_len(), 12);
+    /// assert_eq!(payload.read_int::<u16>(12)?, 0b0110_1010_1011);
+    /// #
+    /// #     Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`ReadError::NotEnoughData`]: enum.ReadError.html#variant.NotEnoughData
+    pub fn split_at(&self, bit: usize) -> Result<(BitReadStream<'a, E>, BitReadStream<'a, E>)> {
+        BitReadStream::new(self.clone()).split_at(bit)
+    }
+
+    /// Split the buffer into independent streams of `bit_size` bits
+    ///
+    /// The last stream is shorter if the length of the buffer isn't a multiple of `bit_size`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `bit_size` is 0
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use bitbuffer::{BitReadBuffer, BigEndian, Result};
+    /// #
+    /// # fn main() -> Result<()> {
+    /// let bytes = vec![0x12, 0x34, 0x56];
+    /// let buffer = BitReadBuffer::new(&bytes, BigEndian);
+    /// let chunks: Vec<_> = buffer.chunks(10).collect();
+    /// assert_eq!(chunks.len(), 3);
+    /// assert_eq!(chunks[2].bit_len(), 4);
+    /// assert_eq!(chunks[2].clone().read_int::<u8>(4)?, 0x6);
+    /// #
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn chunks(&self, bit_size: usize) -> impl Iterator<Item = BitReadStream<'a, E>> {
+        assert!(bit_size > 0, "chunk size must be non-zero");
+        let mut stream = BitReadStream::new(self.clone());
+        std::iter::from_fn(move || match stream.bits_left() {
+            0 => None,
+            bits_left => stream.read_bits(min(bits_left, bit_size)).ok(),
+        })
+    }
+
+    /// Format the bytes of the buffer as lowercase hex digits
+    ///
+    /// A partial final byte is included with its unused bits set to 0.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use bitbuffer::{BitReadBuffer, BigEndian};
+    /// let bytes = [0xDE, 0xAD, 0xBE, 0xEF];
+    /// assert_eq!(BitReadBuffer::new(&bytes, BigEndian).to_hex(), "deadbeef");
+    /// ```
+    pub fn to_hex(&self) -> String {
+        let mut hex = String::with_capacity(self.bit_len.div_ceil(8) * 2);
+        for pos in (0..self.bit_len).step_by(8) {
+            let count = min(self.bit_len - pos, 8);
+            let byte: u8 = self
+                .read_int(pos, count)
+                .expect("reading bits within the buffer");
+            let byte = if E::is_le() {
+                byte
+            } else {
+                byte << (8 - count)
+            };
+            hex.push_str(&format!("{:02x}", byte));
+        }
+        hex
+    }
+
+    /// Format the bits of the buffer as `0` and `1` characters in the order they are read, grouped per byte
+    ///
+    /// The output can be parsed again with [`from_bit_string`](BitReadBuffer::from_bit_string).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use bitbuffer::{BitReadBuffer, LittleEndian, Result};
+    /// #
+    /// # fn main() -> Result<()> {
+    /// let buffer = BitReadBuffer::from_bit_string("1010011101", LittleEndian)?;
+    /// assert_eq!(buffer.to_bit_string(), "10100111 01");
+    /// #
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn to_bit_string(&self) -> String {
+        let mut bits = String::with_capacity(self.bit_len + self.bit_len / 8);
+        for pos in 0..self.bit_len {
+            if pos > 0 && pos % 8 == 0 {
+                bits.push(' ');
+            }
+            let bit = self.read_bool(pos).expect("reading bits within the buffer");
+            bits.push(if bit { '1' } else { '0' });
+        }
+        bits
+    }
+
+    /// Read the `N` bytes starting at `byte_index`, bytes past the end of the data are read as 0
+    unsafe fn read_word_bytes<const N: usize>(&self, byte_index: usize, end: bool) -> [u8; N] {
+        // segmented data has an empty slice, so it always takes the slow path
+        //
+        // words larger than a usize (u64 on 32-bit platforms) aren't covered by the padding guarantee below
+        // so they need an explicit bounds check
+        if end || self.slice.is_empty() || (N > USIZE_SIZE && byte_index + N > self.slice.len()) {
+            self.read_word_bytes_end(byte_index)
         } else {
-            debug_assert!(byte_index + USIZE_SIZE <= self.slice.len());
             // this is safe because all calling paths check that byte_index is less than the unpadded
             // length (because they check based on bit_len), so with padding byte_index + USIZE_SIZE is
             // always within bounds
             self.slice
-                .get_unchecked(byte_index..byte_index + USIZE_SIZE)
+                .get_unchecked(byte_index..byte_index + N)
                 .try_into()
                 .unwrap()
         }
     }
 
+    #[cold]
+    #[inline(never)]
+    unsafe fn read_word_bytes_end<const N: usize>(&self, byte_index: usize) -> [u8; N] {
+        if let Data::Segmented(segments) = &self.bytes {
+            return segments.word_bytes(byte_index);
+        }
+        let mut bytes = [0; N];
+        let count = min(N, self.slice.len() - byte_index);
+        bytes[0..count].copy_from_slice(self.slice.get_unchecked(byte_index..byte_index + count));
+        bytes
+    }
+
     /// note that only the bottom USIZE - 1 bytes are usable
     unsafe fn read_shifted_usize(&self, byte_index: usize, shift: usize, end: bool) -> usize {
-        let raw_bytes: [u8; USIZE_SIZE] = self.read_usize_bytes(byte_index, end);
+        let raw_bytes: [u8; USIZE_SIZE] = self.read_word_bytes(byte_index, end);
         let raw_usize: usize = usize::from_le_bytes(raw_bytes);
         raw_usize >> shift
     }
@@ -252,7 +838,7 @@ where
         let byte_index = position / 8;
         let bit_offset = position & 7;
 
-        let bytes: [u8; USIZE_SIZE] = self.read_usize_bytes(byte_index, end);
+        let bytes: [u8; USIZE_SIZE] = self.read_word_bytes(byte_index, end);
 
         let container = if E::is_le() {
             usize::from_le_bytes(bytes)
@@ -263,6 +849,30 @@ where
         get_bits_from_usize::<E>(container, bit_offset, count)
     }
 
+    /// Read up to 64 bits using a single u64 load, `count + position % 8` can't be more than 64
+    ///
+    /// On 32-bit platforms this compiles to two word loads instead of the 3 byte reads needed when going through
+    /// [`read_usize`](Self::read_usize)
+    unsafe fn read_u64(&self, position: usize, count: usize, end: bool) -> u64 {
+        let byte_index = position / 8;
+        let bit_offset = position & 7;
+
+        let bytes: [u8; 8] = self.read_word_bytes(byte_index, end);
+
+        let container = if E::is_le() {
+            u64::from_le_bytes(bytes)
+        } else {
+            u64::from_be_bytes(bytes)
+        };
+
+        let shifted = if E::is_le() {
+            container >> bit_offset
+        } else {
+            container >> (64 - bit_offset - count)
+        };
+        shifted & (u64::MAX >> (64 - count))
+    }
+
     /// Read a single bit from the buffer as boolean
     ///
     /// # Errors
@@ -294,7 +904,7 @@ where
         let bit_offset = position & 7;
 
         if position < self.bit_len() {
-            let byte = self.slice[byte_index];
+            let byte = self.byte(byte_index);
             if E::is_le() {
                 let shifted = byte >> bit_offset as u8;
                 Ok(shifted & 1u8 == 1)
@@ -316,9 +926,18 @@ where
         let byte_index = position / 8;
         let bit_offset = position & 7;
 
-        let byte = self.slice.get_unchecked(byte_index);
-        let shifted = byte >> bit_offset;
-        shifted & 1u8 == 1
+        let byte = if self.slice.is_empty() {
+            self.segments().byte(byte_index)
+        } else {
+            *self.slice.get_unchecked(byte_index)
+        };
+        if E::is_le() {
+            let shifted = byte >> bit_offset as u8;
+            shifted & 1u8 == 1
+        } else {
+            let shifted = byte << bit_offset as u8;
+            shifted & 0b1000_0000u8 == 0b1000_0000u8
+        }
     }
 
     /// Read a sequence of bits from the buffer as integer
@@ -362,26 +981,110 @@ where
             });
         }
 
-        if position + count + USIZE_BIT_SIZE > self.bit_len() {
-            if position + count > self.bit_len() {
-                return if position > self.bit_len() {
-                    Err(BitError::IndexOutOfBounds {
-                        pos: position,
-                        size: self.bit_len(),
-                    })
-                } else {
-                    Err(BitError::NotEnoughData {
-                        requested: count,
-                        bits_left: self.bit_len() - position,
-                    })
-                };
+        let end = self.check_bits(position, count)?;
+        Ok(unsafe { self.read_int_unchecked(position, count, end) })
+    }
+
+    /// Check that `count` bits are available at `position`, returns whether the bits are close to the end of the data
+    ///
+    /// This doesn't calculate the end position, so huge positions or counts can't wrap around.
+    #[inline]
+    fn check_bits(&self, position: usize, count: usize) -> Result<bool> {
+        let bits_left = match self.bit_len().checked_sub(position) {
+            Some(bits_left) => bits_left,
+            None => {
+                return Err(BitError::IndexOutOfBounds {
+                    pos: position,
+                    size: self.bit_len(),
+                })
             }
-            Ok(unsafe { self.read_int_unchecked(position, count, true) })
+        };
+        if count > bits_left {
+            Err(BitError::NotEnoughData {
+                requested: count,
+                bits_left,
+            })
         } else {
-            Ok(unsafe { self.read_int_unchecked(position, count, false) })
+            Ok(bits_left - count < USIZE_BIT_SIZE)
         }
     }
 
+    /// Read an integer, serving small reads from the word in `cache`
+    #[inline]
+    pub(crate) fn read_int_cached<T>(
+        &self,
+        cache: &mut WordCache,
+        position: usize,
+        count: usize,
+    ) -> Result<T>
+    where
+        T: PrimInt + BitOrAssign + IsSigned + UncheckedPrimitiveInt + BitXor,
+    {
+        if count > size_of::<T>() * 8 {
+            return self.read_int(position, count);
+        }
+        let end = self.check_bits(position, count)?;
+        Ok(unsafe { self.read_int_cached_unchecked(cache, position, count, end) })
+    }
+
+    /// The caller has to make sure that `count` fits in `T` and that the bits are within the buffer
+    #[inline]
+    pub(crate) unsafe fn read_int_cached_unchecked<T>(
+        &self,
+        cache: &mut WordCache,
+        position: usize,
+        count: usize,
+        end: bool,
+    ) -> T
+    where
+        T: PrimInt + BitOrAssign + IsSigned + UncheckedPrimitiveInt + BitXor,
+    {
+        if count == 0 || count > CACHED_READ_BITS || self.slice.is_empty() {
+            return self.read_int_unchecked(position, count, end);
+        }
+        let value = T::from_u64_unchecked(self.read_cached(cache, position, count));
+        if count == size_of::<T>() * 8 {
+            value
+        } else {
+            self.make_signed(value, count)
+        }
+    }
+
+    /// Read a single bit, using the word in `cache` when possible
+    #[inline]
+    pub(crate) fn read_bool_cached(&self, cache: &mut WordCache, position: usize) -> Result<bool> {
+        if position >= self.bit_len() || self.slice.is_empty() {
+            return self.read_bool(position);
+        }
+        Ok(unsafe { self.read_cached(cache, position, 1) } == 1)
+    }
+
+    /// Read `count` bits (at most [`CACHED_READ_BITS`]) from the cached word, loading a new word if needed
+    ///
+    /// The caller has to make sure that the bits are within the buffer and that the data isn't segmented
+    #[inline]
+    unsafe fn read_cached(&self, cache: &mut WordCache, position: usize, count: usize) -> u64 {
+        if position < cache.start || position - cache.start > 64 - count {
+            let byte_index = position / 8;
+            let bytes: [u8; 8] =
+                self.read_word_bytes(byte_index, byte_index + 8 > self.slice.len());
+            cache.start = byte_index * 8;
+            cache.word = if E::is_le() {
+                u64::from_le_bytes(bytes)
+            } else {
+                u64::from_be_bytes(bytes)
+            };
+        }
+        let offset = position - cache.start;
+
+        let shifted = if E::is_le() {
+            cache.word >> offset
+        } else {
+            cache.word >> (64 - offset - count)
+        };
+        shifted & (u64::MAX >> (64 - count))
+    }
+
     #[doc(hidden)]
     #[inline]
     pub unsafe fn read_int_unchecked<T>(&self, position: usize, count: usize, end: bool) -> T
@@ -389,12 +1092,18 @@ where
         T: PrimInt + BitOrAssign + IsSigned + UncheckedPrimitiveInt + BitXor,
     {
         let type_bit_size = size_of::<T>() * 8;
-        let usize_bit_size = size_of::<usize>() * 8;
+        let usize_bit_size = usize::BITS as usize;
 
         let bit_offset = position & 7;
 
         let fit_usize = count + bit_offset < usize_bit_size;
-        let value = if fit_usize {
+        let value = if (position | count) & 7 == 0 && !self.slice.is_empty() {
+            match self.read_aligned(position / 8, count / 8) {
+                Some(value) => value,
+                None if fit_usize => self.read_fit_usize(position, count, end),
+                None => self.read_no_fit_usize(position, count, end),
+            }
+        } else if fit_usize {
             self.read_fit_usize(position, count, end)
         } else {
             self.read_no_fit_usize(position, count, end)
@@ -407,6 +1116,39 @@ where
         }
     }
 
+    /// Read a byte aligned integer of 1, 2, 4 or 8 bytes directly from the slice
+    ///
+    /// Safe as long as the bytes are within the (non segmented) slice, which is guaranteed for any position
+    /// and count that are within `bit_len`
+    #[inline]
+    unsafe fn read_aligned<T>(&self, byte_index: usize, byte_count: usize) -> Option<T>
+    where
+        T: UncheckedPrimitiveInt,
+    {
+        let bytes = self
+            .slice
+            .get_unchecked(byte_index..byte_index + byte_count);
+        Some(match byte_count {
+            1 => T::from_u8_unchecked(bytes[0]),
+            2 => T::from_u16_unchecked(if E::is_le() {
+                u16::from_le_bytes(bytes.try_into().unwrap())
+            } else {
+                u16::from_be_bytes(bytes.try_into().unwrap())
+            }),
+            4 => T::from_u32_unchecked(if E::is_le() {
+                u32::from_le_bytes(bytes.try_into().unwrap())
+            } else {
+                u32::from_be_bytes(bytes.try_into().unwrap())
+            }),
+            8 => T::from_u64_unchecked(if E::is_le() {
+                u64::from_le_bytes(bytes.try_into().unwrap())
+            } else {
+                u64::from_be_bytes(bytes.try_into().unwrap())
+            }),
+            _ => return None,
+        })
+    }
+
     #[inline]
     unsafe fn read_fit_usize<T>(&self, position: usize, count: usize, end: bool) -> T
     where
@@ -420,15 +1162,21 @@ where
     where
         T: PrimInt + BitOrAssign + IsSigned + UncheckedPrimitiveInt,
     {
+        if count + (position & 7) <= 64 {
+            return T::from_unchecked(self.read_u64(position, count, end));
+        }
+
+        // the values are read in 56 bit chunks, which leaves room for the bit offset in a 64 bit load,
+        // independent of the size of usize
         let mut left_to_read = count;
         let mut acc = T::zero();
-        let max_read = (size_of::<usize>() - 1) * 8;
+        let max_read = 56;
         let mut read_pos = position;
         let mut bit_offset = 0;
         while left_to_read > 0 {
             let bits_left = self.bit_len() - read_pos;
             let read = min(min(left_to_read, max_read), bits_left);
-            let data = T::from_unchecked(self.read_usize(read_pos, read, end));
+            let data = T::from_unchecked(self.read_u64(read_pos, read, end));
             if E::is_le() {
                 acc |= data << bit_offset;
             } else {
@@ -452,7 +1200,7 @@ where
         } else if T::is_signed() {
             let sign_bit = value >> (count - 1) & T::one();
             if sign_bit == T::one() {
-                value | (T::zero() - T::one()) ^ ((T::one() << count) - T::one())
+                value | (!T::zero() << count)
             } else {
                 value
             }
@@ -461,6 +1209,115 @@ where
         }
     }
 
+    /// Read `count` integers of `bit_width` bits each, starting at `position`
+    ///
+    /// The caller has to make sure that `bit_width` fits in `T` and that `count * bit_width` bits are available
+    pub(crate) unsafe fn read_int_vec_unchecked<T>(
+        &self,
+        position: usize,
+        count: usize,
+        bit_width: usize,
+    ) -> Vec<T>
+    where
+        T: PrimInt + BitOrAssign + IsSigned + UncheckedPrimitiveInt + BitXor,
+    {
+        let type_size = size_of::<T>();
+        let mut result = Vec::with_capacity(count);
+
+        // segmented data has an empty slice, so it always takes the slow path
+        let fast = !self.slice.is_empty() && count > 0;
+        if fast && position & 7 == 0 && bit_width == type_size * 8 {
+            let start = position / 8;
+            let bytes = self.slice.get_unchecked(start..start + count * type_size);
+
+            macro_rules! convert {
+                ($int:ty, $from:ident) => {
+                    result.extend(bytes.chunks_exact(type_size).map(|chunk| {
+                        let chunk = chunk.try_into().unwrap();
+                        T::$from(if E::is_le() {
+                            <$int>::from_le_bytes(chunk)
+                        } else {
+                            <$int>::from_be_bytes(chunk)
+                        })
+                    }))
+                };
+            }
+
+            match type_size {
+                1 => result.extend(bytes.iter().map(|byte| T::from_u8_unchecked(*byte))),
+                2 => convert!(u16, from_u16_unchecked),
+                4 => convert!(u32, from_u32_unchecked),
+                8 => convert!(u64, from_u64_unchecked),
+                _ => convert!(u128, from_u128_unchecked),
+            }
+            return result;
+        } else if fast && bit_width <= 32 {
+            // keep a 64 bit accumulator of upcoming bits, refilled 32 bits at a time,
+            // little endian data is consumed from the bottom and big endian data from the top
+            let mut byte_index = position / 8 + 1;
+            let bit_offset = position & 7;
+            let first = *self.slice.get_unchecked(position / 8) as u64;
+            let (mut acc, mut available) = if E::is_le() {
+                (first >> bit_offset, 8 - bit_offset)
+            } else {
+                (first << (56 + bit_offset), 8 - bit_offset)
+            };
+            let mask = !(u64::MAX << bit_width);
+
+            for _ in 0..count {
+                while available < bit_width {
+                    let (bits, read) = if byte_index + 4 <= self.slice.len() {
+                        let bytes = self.slice.get_unchecked(byte_index..byte_index + 4);
+                        let bytes: [u8; 4] = bytes.try_into().unwrap();
+                        if E::is_le() {
+                            (u32::from_le_bytes(bytes) as u64, 4)
+                        } else {
+                            (u32::from_be_bytes(bytes) as u64, 4)
+                        }
+                    } else {
+                        (*self.slice.get_unchecked(byte_index) as u64, 1)
+                    };
+                    if E::is_le() {
+                        acc |= bits << available;
+                    } else {
+                        acc |= bits << (64 - read * 8 - available);
+                    }
+                    byte_index += read;
+                    available += read * 8;
+                }
+
+                let value = if E::is_le() {
+                    let value = acc & mask;
+                    acc >>= bit_width;
+                    value
+                } else if bit_width == 0 {
+                    0
+                } else {
+                    let value = acc >> (64 - bit_width);
+                    acc <<= bit_width;
+                    value
+                };
+                available -= bit_width;
+
+                let value = T::from_u64_unchecked(value);
+                result.push(if bit_width == type_size * 8 {
+                    value
+                } else {
+                    self.make_signed(value, bit_width)
+                });
+            }
+            return result;
+        }
+
+        let mut pos = position;
+        for _ in 0..count {
+            let end = pos + bit_width + USIZE_BIT_SIZE > self.bit_len();
+            result.push(self.read_int_unchecked(pos, bit_width, end));
+            pos += bit_width;
+        }
+        result
+    }
+
     /// Read a series of bytes from the buffer
     ///
     /// # Errors
@@ -491,20 +1348,7 @@ where
     /// [`ReadError::NotEnoughData`]: enum.ReadError.html#variant.NotEnoughData
     #[inline]
     pub fn read_bytes(&self, position: usize, byte_count: usize) -> Result<Cow<'a, [u8]>> {
-        if position + byte_count * 8 > self.bit_len() {
-            if position > self.bit_len() {
-                return Err(BitError::IndexOutOfBounds {
-                    pos: position,
-                    size: self.bit_len(),
-                });
-            } else {
-                return Err(BitError::NotEnoughData {
-                    requested: byte_count * 8,
-                    bits_left: self.bit_len() - position,
-                });
-            }
-        }
-
+        self.check_bits(position, checked_bits(byte_count, 8)?)?;
         Ok(unsafe { self.read_bytes_unchecked(position, byte_count) })
     }
 
@@ -515,7 +1359,25 @@ where
 
         if shift == 0 {
             let byte_pos = position / 8;
-            return Cow::Borrowed(&self.slice[byte_pos..byte_pos + byte_count]);
+            return self.byte_range(byte_pos, byte_pos + byte_count);
+        }
+
+        Cow::Owned(self.read_unaligned_bytes(position, byte_count))
+    }
+
+    unsafe fn read_unaligned_bytes(&self, position: usize, byte_count: usize) -> Vec<u8> {
+        let shift = position & 7;
+        if !self.slice.is_empty() {
+            // the bits end within the slice and don't end at a byte boundary, so the byte after the last
+            // full byte is always there
+            let start = position / 8;
+            let mut data = vec![0; byte_count];
+            unshift_into::<E>(
+                self.slice.get_unchecked(start..start + byte_count + 1),
+                &mut data,
+                shift,
+            );
+            return data;
         }
 
         let mut data = Vec::with_capacity(byte_count);
@@ -544,13 +1406,14 @@ where
         } else {
             let mut pos = position;
             while byte_left > 0 {
-                data.push(self.read_int_unchecked::<u8>(pos, 8, true));
+                let end = pos + 8 + USIZE_BIT_SIZE > self.bit_len();
+                data.push(self.read_int_unchecked::<u8>(pos, 8, end));
                 byte_left -= 1;
                 pos += 8;
             }
         }
 
-        Cow::Owned(data)
+        data
     }
 
     /// Read a series of bytes from the buffer as string
@@ -622,27 +1485,55 @@ where
         }
     }
 
+    // segmented data has an empty slice, so these only need to check the segments when the slice is out of bounds
+
+    #[inline]
+    pub(crate) fn byte(&self, byte_index: usize) -> u8 {
+        match self.slice.get(byte_index) {
+            Some(byte) => *byte,
+            None => self.segments().byte(byte_index),
+        }
+    }
+
+    #[inline]
+    pub(crate) fn byte_range(&self, start: usize, end: usize) -> Cow<'a, [u8]> {
+        match self.slice.get(start..end) {
+            Some(bytes) => Cow::Borrowed(bytes),
+            None => self.segments().range(start, end),
+        }
+    }
+
     #[inline]
     fn find_null_byte(&self, byte_index: usize) -> usize {
+        if let Data::Segmented(segments) = &self.bytes {
+            return segments.find_null_byte(byte_index);
+        }
         memchr::memchr(0, &self.slice[byte_index..])
             .map(|index| index + byte_index)
             .unwrap_or(self.slice.len()) // due to padding we always have 0 bytes at the end
     }
 
+    #[cold]
+    #[inline(never)]
+    fn segments(&self) -> &Segments<'a> {
+        match &self.bytes {
+            Data::Segmented(segments) => segments,
+            _ => panic!("index out of bounds"),
+        }
+    }
+
     #[inline]
     fn read_string_bytes(&self, position: usize) -> Result<Cow<'a, [u8]>> {
         let shift = position & 7;
         if shift == 0 {
             let byte_index = position / 8;
-            Ok(Cow::Borrowed(
-                &self.slice[byte_index..self.find_null_byte(byte_index)],
-            ))
+            Ok(self.byte_range(byte_index, self.find_null_byte(byte_index)))
         } else {
             let mut acc = Vec::with_capacity(32);
             if E::is_le() {
                 let mut byte_index = position / 8;
                 loop {
-                    // note: if less then a usize worth of data is left in the buffer, read_usize_bytes
+                    // note: if less then a usize worth of data is left in the buffer, read_word_bytes
                     // will automatically pad with null bytes, triggering the loop termination
                     // thus no separate logic for dealing with the end of the bytes is required
                     //
@@ -711,25 +1602,8 @@ where
     where
         T: Float + UncheckedPrimitiveFloat,
     {
-        let type_bit_size = size_of::<T>() * 8;
-        if position + type_bit_size + USIZE_BIT_SIZE > self.bit_len() {
-            if position + type_bit_size > self.bit_len() {
-                if position > self.bit_len() {
-                    return Err(BitError::IndexOutOfBounds {
-                        pos: position,
-                        size: self.bit_len(),
-                    });
-                } else {
-                    return Err(BitError::NotEnoughData {
-                        requested: size_of::<T>() * 8,
-                        bits_left: self.bit_len() - position,
-                    });
-                }
-            }
-            Ok(unsafe { self.read_float_unchecked(position, true) })
-        } else {
-            Ok(unsafe { self.read_float_unchecked(position, false) })
-        }
+        let end = self.check_bits(position, size_of::<T>() * 8)?;
+        Ok(unsafe { self.read_float_unchecked(position, end) })
     }
 
     #[doc(hidden)]
@@ -804,7 +1678,7 @@ impl<E: Endianness> Debug for BitReadBuffer<'_, E> {
 
 impl<'a, E: Endianness> PartialEq for BitReadBuffer<'a, E> {
     fn eq(&self, other: &Self) -> bool {
-        self.bit_len == other.bit_len && self.slice == other.slice
+        self.bit_len == other.bit_len && self.bytes.as_slice() == other.bytes.as_slice()
     }
 }
 
@@ -1,15 +1,22 @@
 use std::mem::size_of;
-use std::ops::BitOrAssign;
+use std::ops::{BitOrAssign, Range};
 
-use num_traits::{Float, PrimInt};
+use num_traits::{Float, PrimInt, Signed, WrappingAdd};
 
 use crate::endianness::Endianness;
+use crate::intern::InternerRef;
 use crate::num_traits::{IsSigned, UncheckedPrimitiveFloat, UncheckedPrimitiveInt};
-use crate::readbuffer::Data;
+use crate::readbuffer::{Data, WordCache};
 use crate::BitReadBuffer;
-use crate::{BitError, BitRead, BitReadSized, Result};
+use crate::{
+    BitError, BitRead, BitReadSized, BitValue, CancellationToken, HuffmanTable, Interner, Layout,
+    ReadLimit, ReadLimits, Result,
+};
 use std::borrow::Cow;
 use std::cmp::min;
+use std::fmt::{self, Debug, Formatter};
+use std::io::SeekFrom;
+use std::sync::Arc;
 
 /// Stream that provides an easy way to iterate trough a [`BitBuffer`]
 ///
@@ -26,15 +33,54 @@ use std::cmp::min;
 /// let mut stream = BitReadStream::new(buffer);
 /// ```
 ///
+/// # Failed reads
+///
+/// When a read returns an error, the position of the stream is left unchanged,
+/// so the data can be read again with a different interpretation.
+///
+/// This holds for all `read_*` methods, and for [`read`] and [`read_sized`] with any type,
+/// including types with a derived implementation.
+///
+/// # Thread safety
+///
+/// Streams are `Send` and `Sync`, use [`split_at`](BitReadStream::split_at) or [`read_bits`](BitReadStream::read_bits)
+/// to get independent streams over parts of the data that can be processed on different threads.
+///
 /// [`BitBuffer`]: struct.BitBuffer.html
+/// [`read`]: BitReadStream::read
+/// [`read_sized`]: BitReadStream::read_sized
 #[derive(Debug)]
 pub struct BitReadStream<'a, E>
 where
     E: Endianness,
 {
-    buffer: BitReadBuffer<'a, E>,
+    pub(crate) buffer: BitReadBuffer<'a, E>,
+    /// The position of the first bit of `buffer` in the buffer the stream was originally created from,
+    /// only non-zero when [`to_owned`](Self::to_owned) copied a part of the data
+    base: usize,
     start_pos: usize,
-    pos: usize,
+    pub(crate) pos: usize,
+    limits: ReadLimits,
+    depth: usize,
+    interner: Option<InternerRef>,
+    cancellation: Option<CancellationToken>,
+    cache: WordCache,
+    progress: Option<ProgressHook>,
+    /// The position at which the progress hook is called next, `usize::MAX` without a hook
+    next_progress: usize,
+}
+
+/// The progress hook installed on a stream
+#[derive(Clone)]
+struct ProgressHook {
+    interval: usize,
+    callback: Arc<dyn Fn(usize, usize) + Send + Sync>,
+}
+
+impl Debug for ProgressHook {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "ProgressHook {{ interval: {} }}", self.interval)
+    }
 }
 
 impl<'a, E> BitReadStream<'a, E>
@@ -59,9 +105,17 @@ where
     /// [`BitBuffer`]: struct.BitBuffer.html
     pub fn new(buffer: BitReadBuffer<'a, E>) -> Self {
         BitReadStream {
+            base: 0,
             start_pos: 0,
             pos: 0,
             buffer,
+            limits: ReadLimits::UNLIMITED,
+            depth: 0,
+            interner: None,
+            cancellation: None,
+            cache: WordCache::default(),
+            progress: None,
+            next_progress: usize::MAX,
         }
     }
 
@@ -94,9 +148,10 @@ where
     /// [`ReadError::NotEnoughData`]: enum.ReadError.html#variant.NotEnoughData
     #[inline]
     pub fn read_bool(&mut self) -> Result<bool> {
-        let result = self.buffer.read_bool(self.pos);
+        let result = self.buffer.read_bool_cached(&mut self.cache, self.pos);
         if result.is_ok() {
             self.pos += 1;
+            self.check_progress();
         }
         result
     }
@@ -106,9 +161,133 @@ where
     pub unsafe fn read_bool_unchecked(&mut self) -> bool {
         let result = self.buffer.read_bool_unchecked(self.pos);
         self.pos += 1;
+        self.check_progress();
+        result
+    }
+
+    /// Read a single bit from the stream
+    ///
+    /// This is the same as [`read_bool`](Self::read_bool), but counts as a read of a `bool` when collecting
+    /// stats, this allows derived implementations to use it for `bool` fields.
+    ///
+    /// # Errors
+    ///
+    /// - [`ReadError::NotEnoughData`]: not enough bits available in the stream
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use bitbuffer::{BitReadBuffer, BitReadStream, BigEndian, Result};
+    /// #
+    /// # fn main() -> Result<()> {
+    /// let mut stream = BitReadStream::new(BitReadBuffer::new(&[0b1000_0000], BigEndian));
+    /// assert_eq!(stream.read_bit()?, true);
+    /// assert_eq!(stream.read_bit()?, false);
+    /// #
+    /// #     Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`ReadError::NotEnoughData`]: crate::BitError::NotEnoughData
+    #[inline]
+    #[cfg_attr(feature = "stats", track_caller)]
+    pub fn read_bit(&mut self) -> Result<bool> {
+        #[cfg(feature = "stats")]
+        let timer = crate::stats::start();
+        let result = self.read_bool();
+        #[cfg(feature = "stats")]
+        crate::stats::record::<bool>(
+            timer,
+            std::panic::Location::caller(),
+            result.as_ref().ok().map(|_| 1),
+        );
+        result
+    }
+
+    /// Read 8 bits from the stream as byte
+    ///
+    /// When the stream is at a byte boundary the byte is read directly from the data.
+    /// Like [`read_bit`](Self::read_bit) this counts as a read of a `u8` when collecting stats.
+    ///
+    /// # Errors
+    ///
+    /// - [`ReadError::NotEnoughData`]: not enough bits available in the stream
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use bitbuffer::{BitReadBuffer, BitReadStream, BigEndian, Result};
+    /// #
+    /// # fn main() -> Result<()> {
+    /// let mut stream = BitReadStream::new(BitReadBuffer::new(&[0x12, 0x34, 0x56], BigEndian));
+    /// assert_eq!(stream.read_byte()?, 0x12);
+    /// stream.skip_bits(4)?;
+    /// assert_eq!(stream.read_byte()?, 0x45);
+    /// #
+    /// #     Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`ReadError::NotEnoughData`]: crate::BitError::NotEnoughData
+    #[inline]
+    #[cfg_attr(feature = "stats", track_caller)]
+    pub fn read_byte(&mut self) -> Result<u8> {
+        #[cfg(feature = "stats")]
+        let timer = crate::stats::start();
+        let result = if self.pos & 7 == 0 && self.pos + 8 <= self.buffer.bit_len() {
+            let byte = self.buffer.byte(self.pos / 8);
+            self.pos += 8;
+            self.check_progress();
+            Ok(byte)
+        } else {
+            self.read_int(8)
+        };
+        #[cfg(feature = "stats")]
+        crate::stats::record::<u8>(
+            timer,
+            std::panic::Location::caller(),
+            result.as_ref().ok().map(|_| 8),
+        );
         result
     }
 
+    /// Read `N` single bit booleans from the stream
+    ///
+    /// # Errors
+    ///
+    /// - [`ReadError::NotEnoughData`]: not enough bits available in the stream, the stream is not advanced
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use bitbuffer::{BitReadBuffer, BitReadStream, LittleEndian, Result};
+    /// #
+    /// # fn main() -> Result<()> {
+    /// # let bytes = vec![
+    /// #     0b1011_0101, 0b0110_1010, 0b1010_1100, 0b1001_1001,
+    /// #     0b1001_1001, 0b1001_1001, 0b1001_1001, 0b1110_0111
+    /// # ];
+    /// # let buffer = BitReadBuffer::new(&bytes, LittleEndian);
+    /// # let mut stream = BitReadStream::new(buffer);
+    /// let [enabled, visible, locked] = stream.read_bool_array()?;
+    /// assert_eq!((enabled, visible, locked), (true, false, true));
+    /// assert_eq!(stream.pos(), 3);
+    /// #
+    /// #     Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`ReadError::NotEnoughData`]: enum.ReadError.html#variant.NotEnoughData
+    pub fn read_bool_array<const N: usize>(&mut self) -> Result<[bool; N]> {
+        self.check_read(N)?;
+        let mut result = [false; N];
+        for value in result.iter_mut() {
+            // safe because we checked that enough bits are available
+            *value = unsafe { self.read_bool_unchecked() };
+        }
+        Ok(result)
+    }
+
     /// Read a sequence of bits from the stream as integer
     ///
     /// # Errors
@@ -143,21 +322,211 @@ where
     where
         T: PrimInt + BitOrAssign + IsSigned + UncheckedPrimitiveInt,
     {
-        let result = self.buffer.read_int(self.pos, count);
+        let result = self
+            .buffer
+            .read_int_cached(&mut self.cache, self.pos, count);
         if result.is_ok() {
             self.pos += count;
+            self.check_progress();
         }
         result
     }
 
+    /// Read a two's complement signed integer of `count` bits from the stream
+    ///
+    /// The value is sign extended into `T`, this is the counterpart of
+    /// [`write_signed`](crate::BitWriteStream::write_signed).
+    ///
+    /// # Errors
+    ///
+    /// - [`ReadError::NotEnoughData`]: not enough bits available in the stream
+    /// - [`ReadError::TooManyBits`]: to many bits requested for the chosen integer type
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use bitbuffer::{BitReadBuffer, BitReadStream, BigEndian, Result};
+    /// #
+    /// # fn main() -> Result<()> {
+    /// let bytes = vec![0b1000_0111];
+    /// let buffer = BitReadBuffer::new(&bytes, BigEndian);
+    /// let mut stream = BitReadStream::new(buffer);
+    /// assert_eq!(stream.read_signed::<i8>(4)?, -8);
+    /// assert_eq!(stream.read_signed::<i8>(4)?, 7);
+    /// #
+    /// #     Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`ReadError::NotEnoughData`]: enum.ReadError.html#variant.NotEnoughData
+    /// [`ReadError::TooManyBits`]: enum.ReadError.html#variant.TooManyBits
+    #[inline]
+    pub fn read_signed<T>(&mut self, count: usize) -> Result<T>
+    where
+        T: PrimInt + BitOrAssign + IsSigned + UncheckedPrimitiveInt + Signed,
+    {
+        if count == 0 {
+            return Ok(T::zero());
+        }
+        self.read_int(count)
+    }
+
+    /// Read a sequence of bits from the stream as offset binary integer
+    ///
+    /// Offset binary stores `value + bias` as unsigned integer,
+    /// ADCs commonly use this with a bias of `1 << (count - 1)`.
+    ///
+    /// The result is truncated if it doesn't fit in the chosen integer type.
+    ///
+    /// # Errors
+    ///
+    /// - [`ReadError::NotEnoughData`]: not enough bits available in the stream
+    /// - [`ReadError::TooManyBits`]: to many bits requested for the chosen integer type
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use bitbuffer::{BitReadBuffer, BitReadStream, BigEndian, Result};
+    /// #
+    /// # fn main() -> Result<()> {
+    /// let bytes = vec![0b0000_0000, 0b0000_1000, 0b0000_0000, 0b1111_1111];
+    /// let buffer = BitReadBuffer::new(&bytes, BigEndian);
+    /// let mut stream = BitReadStream::new(buffer);
+    /// assert_eq!(stream.read_int_offset_binary::<i16>(12, 2048)?, -2048);
+    /// assert_eq!(stream.read_int_offset_binary::<i16>(12, 2048)?, 0);
+    /// assert_eq!(stream.read_int_offset_binary::<i16>(8, 2048)?, -2048 + 0xFF);
+    /// #
+    /// #     Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`ReadError::NotEnoughData`]: enum.ReadError.html#variant.NotEnoughData
+    /// [`ReadError::TooManyBits`]: enum.ReadError.html#variant.TooManyBits
+    #[inline]
+    pub fn read_int_offset_binary<T>(&mut self, count: usize, bias: i128) -> Result<T>
+    where
+        T: PrimInt + BitOrAssign + IsSigned + UncheckedPrimitiveInt,
+    {
+        check_int_size::<T>(count)?;
+        let raw: u128 = self.read_int(count)?;
+        Ok(T::from_i128_unchecked((raw as i128).wrapping_sub(bias)))
+    }
+
+    /// Read a sequence of bits from the stream as sign-magnitude integer
+    ///
+    /// The most significant bit of the read bits is the sign, the remaining bits are the magnitude.
+    ///
+    /// The result is truncated if it doesn't fit in the chosen integer type.
+    ///
+    /// # Errors
+    ///
+    /// - [`ReadError::NotEnoughData`]: not enough bits available in the stream
+    /// - [`ReadError::TooManyBits`]: to many bits requested for the chosen integer type
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use bitbuffer::{BitReadBuffer, BitReadStream, BigEndian, Result};
+    /// #
+    /// # fn main() -> Result<()> {
+    /// let bytes = vec![0b1011_0011, 0b1000_0000];
+    /// let buffer = BitReadBuffer::new(&bytes, BigEndian);
+    /// let mut stream = BitReadStream::new(buffer);
+    /// assert_eq!(stream.read_int_sign_magnitude::<i8>(4)?, -3);
+    /// assert_eq!(stream.read_int_sign_magnitude::<i8>(4)?, 3);
+    /// // negative zero
+    /// assert_eq!(stream.read_int_sign_magnitude::<i8>(4)?, 0);
+    /// #
+    /// #     Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`ReadError::NotEnoughData`]: enum.ReadError.html#variant.NotEnoughData
+    /// [`ReadError::TooManyBits`]: enum.ReadError.html#variant.TooManyBits
+    #[inline]
+    pub fn read_int_sign_magnitude<T>(&mut self, count: usize) -> Result<T>
+    where
+        T: PrimInt + BitOrAssign + IsSigned + UncheckedPrimitiveInt,
+    {
+        check_int_size::<T>(count)?;
+        if count == 0 {
+            return Ok(T::zero());
+        }
+        let raw: u128 = self.read_int(count)?;
+        let sign_bit = 1 << (count - 1);
+        let magnitude = (raw & !sign_bit) as i128;
+        let value = if raw & sign_bit != 0 {
+            -magnitude
+        } else {
+            magnitude
+        };
+        Ok(T::from_i128_unchecked(value))
+    }
+
+    /// Read `count` delta encoded integers of `bits` bits each
+    ///
+    /// Every read integer is the difference from the previous value, starting from `initial`.
+    /// Signed integer types allow negative deltas, the values are accumulated with wrapping arithmetic.
+    /// With `bits` set to `0` every delta is zero.
+    ///
+    /// The stream is only advanced if all deltas could be read.
+    ///
+    /// # Errors
+    ///
+    /// - [`ReadError::NotEnoughData`]: not enough bits available in the stream
+    /// - [`ReadError::TooManyBits`]: to many bits requested for the chosen integer type
+    /// - [`ReadError::LimitExceeded`]: `count` is larger than [`ReadLimits::max_elements`]
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use bitbuffer::{BitReadBuffer, BitReadStream, BigEndian, Result};
+    /// #
+    /// # fn main() -> Result<()> {
+    /// // deltas of 1, 2, -1 and 0
+    /// let bytes = vec![0b0001_0010, 0b1111_0000];
+    /// let buffer = BitReadBuffer::new(&bytes, BigEndian);
+    /// let mut stream = BitReadStream::new(buffer);
+    /// assert_eq!(stream.read_deltas::<i32>(4, 4, 100)?, [101, 103, 102, 102]);
+    /// #
+    /// #     Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`ReadError::NotEnoughData`]: enum.ReadError.html#variant.NotEnoughData
+    /// [`ReadError::TooManyBits`]: enum.ReadError.html#variant.TooManyBits
+    /// [`ReadError::LimitExceeded`]: enum.ReadError.html#variant.LimitExceeded
+    pub fn read_deltas<T>(&mut self, count: usize, bits: usize, initial: T) -> Result<Vec<T>>
+    where
+        T: PrimInt + BitOrAssign + IsSigned + UncheckedPrimitiveInt + WrappingAdd,
+    {
+        check_int_size::<T>(bits)?;
+        self.check_limit(ReadLimit::Elements, count)?;
+        self.check_read(checked_bits(count, bits)?)?;
+        let mut value = initial;
+        // with 0 bit deltas the size check above doesn't bound the count
+        let mut values = Vec::with_capacity(count.min(128));
+        for _ in 0..count {
+            if bits > 0 {
+                let delta: T = self.read_int(bits)?;
+                value = value.wrapping_add(&delta);
+            }
+            values.push(value);
+        }
+        Ok(values)
+    }
+
     #[doc(hidden)]
     #[inline]
     pub unsafe fn read_int_unchecked<T>(&mut self, count: usize, end: bool) -> T
     where
         T: PrimInt + BitOrAssign + IsSigned + UncheckedPrimitiveInt,
     {
-        let result = self.buffer.read_int_unchecked(self.pos, count, end);
+        let result = self
+            .buffer
+            .read_int_cached_unchecked(&mut self.cache, self.pos, count, end);
         self.pos += count;
+        self.check_progress();
         result
     }
 
@@ -196,6 +565,7 @@ where
         let result = self.buffer.read_float(self.pos);
         if result.is_ok() {
             self.pos += count;
+            self.check_progress();
         }
         result
     }
@@ -209,14 +579,21 @@ where
         let count = size_of::<T>() * 8;
         let result = self.buffer.read_float_unchecked(self.pos, end);
         self.pos += count;
+        self.check_progress();
         result
     }
 
-    /// Read a series of bytes from the stream
+    /// Read `count` integers of `bit_width` bits each
+    ///
+    /// This is equivalent to calling [`read_int`](Self::read_int) `count` times, but much faster for large counts.
+    /// When the stream is byte aligned and `bit_width` is the full size of `T` the integers are converted directly
+    /// from the underlying bytes.
     ///
     /// # Errors
     ///
-    /// - [`ReadError::NotEnoughData`]: not enough bits available in the stream
+    /// - [`ReadError::NotEnoughData`]: not enough bits available in the stream, the stream is not advanced
+    /// - [`ReadError::TooManyBits`]: to many bits requested for the chosen integer type
+    /// - [`ReadError::LimitExceeded`]: `count` is larger than the configured [`ReadLimits::max_elements`]
     ///
     /// # Examples
     ///
@@ -224,99 +601,465 @@ where
     /// # use bitbuffer::{BitReadBuffer, BitReadStream, LittleEndian, Result};
     /// #
     /// # fn main() -> Result<()> {
-    /// # use std::borrow::Borrow;
-    /// let bytes = vec![
-    /// #     0b1011_0101, 0b0110_1010, 0b1010_1100, 0b1001_1001,
-    /// #     0b1001_1001, 0b1001_1001, 0b1001_1001, 0b1110_0111
-    /// # ];
-    /// # let buffer = BitReadBuffer::new(&bytes, LittleEndian);
-    /// # let mut stream = BitReadStream::new(buffer);
-    /// assert_eq!(stream.read_bytes(3)?.to_vec(), &[0b1011_0101, 0b0110_1010, 0b1010_1100]);
-    /// assert_eq!(stream.pos(), 24);
+    /// let bytes = vec![0x01, 0x00, 0xFF, 0xFF, 0x12, 0x34];
+    /// let buffer = BitReadBuffer::new(&bytes, LittleEndian);
+    /// let mut stream = BitReadStream::new(buffer);
+    /// assert_eq!(stream.read_int_vec::<i16>(2, 16)?, [1, -1]);
+    /// assert_eq!(stream.read_int_vec::<u8>(4, 4)?, [0x2, 0x1, 0x4, 0x3]);
     /// #
     /// #     Ok(())
     /// # }
     /// ```
     ///
     /// [`ReadError::NotEnoughData`]: enum.ReadError.html#variant.NotEnoughData
-    #[inline]
-    pub fn read_bytes(&mut self, byte_count: usize) -> Result<Cow<'a, [u8]>> {
-        let count = byte_count * 8;
-        let result = self.buffer.read_bytes(self.pos, byte_count);
-        if result.is_ok() {
-            self.pos += count;
-        }
-        result
-    }
+    /// [`ReadError::TooManyBits`]: enum.ReadError.html#variant.TooManyBits
+    /// [`ReadError::LimitExceeded`]: enum.ReadError.html#variant.LimitExceeded
+    pub fn read_int_vec<T>(&mut self, count: usize, bit_width: usize) -> Result<Vec<T>>
+    where
+        T: PrimInt + BitOrAssign + IsSigned + UncheckedPrimitiveInt,
+    {
+        check_int_size::<T>(bit_width)?;
+        self.check_limit(ReadLimit::Elements, count)?;
+        self.check_cancelled()?;
+        let total = checked_bits(count, bit_width)?;
+        self.check_read(total)?;
 
-    #[doc(hidden)]
-    #[inline]
-    pub unsafe fn read_bytes_unchecked(&mut self, byte_count: usize) -> Cow<'a, [u8]> {
-        let count = byte_count * 8;
-        let result = self.buffer.read_bytes_unchecked(self.pos, byte_count);
-        self.pos += count;
-        result
+        // safe because we checked that enough bits are available
+        let result = unsafe {
+            self.buffer
+                .read_int_vec_unchecked(self.pos, count, bit_width)
+        };
+        self.pos += total;
+        self.check_progress();
+        Ok(result)
     }
 
-    /// Read a series of bytes from the stream as utf8 string
+    /// Read `count` floats
     ///
-    /// You can either read a fixed number of bytes, or a dynamic length null-terminated string
+    /// This is equivalent to calling [`read_float`](Self::read_float) `count` times, but much faster for large counts.
     ///
     /// # Errors
     ///
-    /// - [`ReadError::NotEnoughData`]: not enough bits available in the stream
-    /// - [`ReadError::Utf8Error`]: the read bytes are not valid utf8
+    /// - [`ReadError::NotEnoughData`]: not enough bits available in the stream, the stream is not advanced
+    /// - [`ReadError::LimitExceeded`]: `count` is larger than the configured [`ReadLimits::max_elements`]
     ///
     /// # Examples
     ///
     /// ```
-    /// # use bitbuffer::{BitReadBuffer, BitReadStream, LittleEndian, Result};
+    /// # use bitbuffer::{BitReadBuffer, BitReadStream, BigEndian, Result};
     /// #
     /// # fn main() -> Result<()> {
-    /// # let bytes = vec![
-    /// #     0x48, 0x65, 0x6c, 0x6c,
-    /// #     0x6f, 0x20, 0x77, 0x6f,
-    /// #     0x72, 0x6c, 0x64, 0,
-    /// #     0,    0,    0,    0
-    /// # ];
-    /// # let buffer = BitReadBuffer::new(&bytes, LittleEndian);
-    /// # let mut stream = BitReadStream::new(buffer);
-    /// // Fixed length string
-    /// stream.set_pos(0);
-    /// assert_eq!(stream.read_string(Some(11))?, "Hello world".to_owned());
-    /// assert_eq!(11 * 8, stream.pos());
-    /// // fixed length with null padding
-    /// stream.set_pos(0);
-    /// assert_eq!(stream.read_string(Some(16))?, "Hello world".to_owned());
-    /// assert_eq!(16 * 8, stream.pos());
-    /// // null terminated
-    /// stream.set_pos(0);
-    /// assert_eq!(stream.read_string(None)?, "Hello world".to_owned());
-    /// assert_eq!(12 * 8, stream.pos()); // 1 more for the terminating null byte
+    /// let bytes = vec![0x3F, 0x80, 0x00, 0x00, 0xC0, 0x00, 0x00, 0x00];
+    /// let buffer = BitReadBuffer::new(&bytes, BigEndian);
+    /// let mut stream = BitReadStream::new(buffer);
+    /// assert_eq!(stream.read_float_vec::<f32>(2)?, [1.0, -2.0]);
     /// #
     /// #     Ok(())
     /// # }
     /// ```
     ///
     /// [`ReadError::NotEnoughData`]: enum.ReadError.html#variant.NotEnoughData
-    /// [`ReadError::Utf8Error`]: enum.ReadError.html#variant.Utf8Error
-    #[inline]
-    pub fn read_string(&mut self, byte_len: Option<usize>) -> Result<Cow<'a, str>> {
-        let max_length = self.bits_left() / 8;
+    /// [`ReadError::LimitExceeded`]: enum.ReadError.html#variant.LimitExceeded
+    pub fn read_float_vec<T>(&mut self, count: usize) -> Result<Vec<T>>
+    where
+        T: Float + UncheckedPrimitiveFloat,
+    {
+        Ok(if size_of::<T>() == 4 {
+            self.read_int_vec::<u32>(count, 32)?
+                .into_iter()
+                .map(|bits| T::from_f32_unchecked(f32::from_bits(bits)))
+                .collect()
+        } else {
+            self.read_int_vec::<u64>(count, 64)?
+                .into_iter()
+                .map(|bits| T::from_f64_unchecked(f64::from_bits(bits)))
+                .collect()
+        })
+    }
+
+    /// Read a float that has been quantized to a `bits` bit integer between `min` and `max`
+    ///
+    /// The integer `0` maps to `min` and the largest `bits` bit integer maps to `max`, with the values in between
+    /// evenly spaced. See [`write_quantized_float`](crate::BitWriteStream::write_quantized_float).
+    ///
+    /// # Errors
+    ///
+    /// - [`ReadError::NotEnoughData`]: not enough bits available in the stream
+    /// - [`ReadError::TooManyBits`]: `bits` is larger than 32
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use bitbuffer::{BitReadBuffer, BitReadStream, LittleEndian, Result};
+    /// #
+    /// # fn main() -> Result<()> {
+    /// let bytes = vec![0b0000_0000, 0b0100_0000];
+    /// let buffer = BitReadBuffer::new(&bytes, LittleEndian);
+    /// let mut stream = BitReadStream::new(buffer);
+    /// assert_eq!(stream.read_quantized_float::<f32>(8, -1.0, 1.0)?, -1.0);
+    /// assert_eq!(stream.read_quantized_float::<f32>(7, 0.0, 127.0)?, 64.0);
+    /// #
+    /// #     Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`ReadError::NotEnoughData`]: enum.ReadError.html#variant.NotEnoughData
+    /// [`ReadError::TooManyBits`]: enum.ReadError.html#variant.TooManyBits
+    #[inline]
+    pub fn read_quantized_float<T>(&mut self, bits: usize, min: T, max: T) -> Result<T>
+    where
+        T: Float + UncheckedPrimitiveFloat,
+    {
+        if bits > 32 {
+            return Err(BitError::TooManyBits {
+                requested: bits,
+                max: 32,
+            });
+        }
+        if bits == 0 {
+            return Ok(min);
+        }
+        let quantized = self.read_int::<u32>(bits)?;
+        let steps = (1u64 << bits) - 1;
+        if quantized as u64 == steps {
+            return Ok(max);
+        }
+        let (min_f64, max_f64) = (min.to_f64().unwrap(), max.to_f64().unwrap());
+        let value = min_f64 + (max_f64 - min_f64) * quantized as f64 / steps as f64;
+        Ok(T::from(value).unwrap())
+    }
+
+    /// Read an SQLite style variable length integer
+    ///
+    /// The integer is stored big endian in 1 to 9 bytes, the first 8 bytes contain 7 bits each with the top bit set
+    /// when more bytes follow, a 9th byte contributes all 8 of its bits.
+    ///
+    /// # Errors
+    ///
+    /// - [`ReadError::NotEnoughData`]: not enough bits available in the stream, the stream is not advanced
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use bitbuffer::{BitReadBuffer, BitReadStream, BigEndian, Result};
+    /// #
+    /// # fn main() -> Result<()> {
+    /// let bytes = vec![0x7F, 0x81, 0x00];
+    /// let buffer = BitReadBuffer::new(&bytes, BigEndian);
+    /// let mut stream = BitReadStream::new(buffer);
+    /// assert_eq!(stream.read_varint_sqlite()?, 127);
+    /// assert_eq!(stream.read_varint_sqlite()?, 128);
+    /// #
+    /// #     Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`ReadError::NotEnoughData`]: enum.ReadError.html#variant.NotEnoughData
+    pub fn read_varint_sqlite(&mut self) -> Result<u64> {
+        let start = self.pos;
+        self.read_varint_sqlite_inner()
+            .inspect_err(|_| self.pos = start)
+    }
 
-        let result = self.buffer.read_string(self.pos, byte_len).map_err(|err| {
-            // still advance the stream on malformed utf8
-            if let BitError::Utf8Error(_, len) = &err {
-                self.pos += match byte_len {
-                    Some(len) => len * 8,
-                    None => min((len + 1) * 8, max_length * 8),
-                };
+    fn read_varint_sqlite_inner(&mut self) -> Result<u64> {
+        let mut value = 0;
+        for _ in 0..8 {
+            let byte: u8 = self.read_int(8)?;
+            value = (value << 7) | (byte & 0x7F) as u64;
+            if byte & 0x80 == 0 {
+                return Ok(value);
             }
-            err
-        })?;
+        }
+        let byte: u8 = self.read_int(8)?;
+        Ok((value << 8) | byte as u64)
+    }
+
+    /// Read a git style offset variable length integer, as used for the base offset of `OFS_DELTA` pack entries
+    ///
+    /// The integer is stored big endian with 7 bits per byte and the top bit set when more bytes follow,
+    /// every continuation adds one to the value before shifting so there are no redundant encodings.
+    ///
+    /// # Errors
+    ///
+    /// - [`ReadError::NotEnoughData`]: not enough bits available in the stream, the stream is not advanced
+    /// - [`ReadError::VarIntOverflow`]: the value doesn't fit in 64 bits, the stream is not advanced
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use bitbuffer::{BitReadBuffer, BitReadStream, BigEndian, Result};
+    /// #
+    /// # fn main() -> Result<()> {
+    /// let bytes = vec![0x7F, 0x80, 0x00];
+    /// let buffer = BitReadBuffer::new(&bytes, BigEndian);
+    /// let mut stream = BitReadStream::new(buffer);
+    /// assert_eq!(stream.read_varint_git_offset()?, 127);
+    /// assert_eq!(stream.read_varint_git_offset()?, 128);
+    /// #
+    /// #     Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`ReadError::NotEnoughData`]: enum.ReadError.html#variant.NotEnoughData
+    /// [`ReadError::VarIntOverflow`]: enum.ReadError.html#variant.VarIntOverflow
+    pub fn read_varint_git_offset(&mut self) -> Result<u64> {
+        let start = self.pos;
+        self.read_varint_git_offset_inner(start)
+            .inspect_err(|_| self.pos = start)
+    }
+
+    fn read_varint_git_offset_inner(&mut self, start: usize) -> Result<u64> {
+        let mut byte: u8 = self.read_int(8)?;
+        let mut value = (byte & 0x7F) as u64;
+        while byte & 0x80 != 0 {
+            byte = self.read_int(8)?;
+            value = value
+                .checked_add(1)
+                .and_then(|value| value.checked_mul(0x80))
+                .ok_or(BitError::VarIntOverflow {
+                    pos: start - self.start_pos,
+                    max: 64,
+                })?
+                | (byte & 0x7F) as u64;
+        }
+        Ok(value)
+    }
+
+    /// Read a MIDI style variable length quantity
+    ///
+    /// The integer is stored big endian with 7 bits per byte and the top bit set when more bytes follow.
+    ///
+    /// # Errors
+    ///
+    /// - [`ReadError::NotEnoughData`]: not enough bits available in the stream, the stream is not advanced
+    /// - [`ReadError::VarIntOverflow`]: the value doesn't fit in 64 bits, the stream is not advanced
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use bitbuffer::{BitReadBuffer, BitReadStream, BigEndian, Result};
+    /// #
+    /// # fn main() -> Result<()> {
+    /// let bytes = vec![0x7F, 0x81, 0x00, 0xFF, 0xFF, 0x7F];
+    /// let buffer = BitReadBuffer::new(&bytes, BigEndian);
+    /// let mut stream = BitReadStream::new(buffer);
+    /// assert_eq!(stream.read_vlq()?, 0x7F);
+    /// assert_eq!(stream.read_vlq()?, 0x80);
+    /// assert_eq!(stream.read_vlq()?, 0x1F_FFFF);
+    /// #
+    /// #     Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`ReadError::NotEnoughData`]: enum.ReadError.html#variant.NotEnoughData
+    /// [`ReadError::VarIntOverflow`]: enum.ReadError.html#variant.VarIntOverflow
+    pub fn read_vlq(&mut self) -> Result<u64> {
+        let start = self.pos;
+        self.read_vlq_inner(start).inspect_err(|_| self.pos = start)
+    }
+
+    fn read_vlq_inner(&mut self, start: usize) -> Result<u64> {
+        let mut value: u64 = 0;
+        loop {
+            let byte: u8 = self.read_int(8)?;
+            if value >> 57 != 0 {
+                return Err(BitError::VarIntOverflow {
+                    pos: start - self.start_pos,
+                    max: 64,
+                });
+            }
+            value = (value << 7) | (byte & 0x7F) as u64;
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+    }
+
+    /// Read a QUIC variable length integer, as defined in RFC 9000
+    ///
+    /// The top 2 bits of the first byte give the length of the integer as 1, 2, 4 or 8 bytes,
+    /// the remaining bits contain the integer in big endian order.
+    ///
+    /// # Errors
+    ///
+    /// - [`ReadError::NotEnoughData`]: not enough bits available in the stream, the stream is not advanced
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use bitbuffer::{BitReadBuffer, BitReadStream, BigEndian, Result};
+    /// #
+    /// # fn main() -> Result<()> {
+    /// let bytes = vec![0x25, 0x7B, 0xBD, 0x9D, 0x7F, 0x3E, 0x7D];
+    /// let buffer = BitReadBuffer::new(&bytes, BigEndian);
+    /// let mut stream = BitReadStream::new(buffer);
+    /// assert_eq!(stream.read_quic_varint()?, 37);
+    /// assert_eq!(stream.read_quic_varint()?, 15_293);
+    /// assert_eq!(stream.read_quic_varint()?, 494_878_333);
+    /// #
+    /// #     Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`ReadError::NotEnoughData`]: enum.ReadError.html#variant.NotEnoughData
+    pub fn read_quic_varint(&mut self) -> Result<u64> {
+        let start = self.pos;
+        self.read_quic_varint_inner()
+            .inspect_err(|_| self.pos = start)
+    }
+
+    fn read_quic_varint_inner(&mut self) -> Result<u64> {
+        let first: u8 = self.read_int(8)?;
+        let mut value = (first & 0x3F) as u64;
+        for _ in 1..(1 << (first >> 6)) {
+            let byte: u8 = self.read_int(8)?;
+            value = (value << 8) | byte as u64;
+        }
+        Ok(value)
+    }
+
+    /// Read a symbol encoded with a canonical huffman code
+    ///
+    /// The code is read one bit at a time, starting with the most significant bit of the code,
+    /// which is how both deflate and jpeg store their codes.
+    ///
+    /// # Errors
+    ///
+    /// - [`ReadError::NotEnoughData`]: not enough bits available in the stream, the stream is not advanced
+    /// - [`ReadError::Malformed`]: the bits don't match any code of the table, the stream is not advanced
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use bitbuffer::{BitReadBuffer, BitReadStream, BigEndian, HuffmanTable, Result};
+    /// #
+    /// # fn main() -> Result<()> {
+    /// // symbols 0 and 1 have 2 bit codes, symbols 2 to 5 have 3 bit codes
+    /// let table = HuffmanTable::from_lengths(&[2, 2, 3, 3, 3, 3]).unwrap();
+    /// let bytes = vec![0b0111_1100];
+    /// let buffer = BitReadBuffer::new(&bytes, BigEndian);
+    /// let mut stream = BitReadStream::new(buffer);
+    /// assert_eq!(stream.read_huffman(&table)?, 1);
+    /// assert_eq!(stream.read_huffman(&table)?, 5);
+    /// assert_eq!(stream.read_huffman(&table)?, 2);
+    /// #
+    /// #     Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`ReadError::NotEnoughData`]: enum.ReadError.html#variant.NotEnoughData
+    /// [`ReadError::Malformed`]: enum.ReadError.html#variant.Malformed
+    pub fn read_huffman(&mut self, table: &HuffmanTable) -> Result<u16> {
+        table.decode(self)
+    }
+
+    /// Read a series of bytes from the stream
+    ///
+    /// # Errors
+    ///
+    /// - [`ReadError::NotEnoughData`]: not enough bits available in the stream
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use bitbuffer::{BitReadBuffer, BitReadStream, LittleEndian, Result};
+    /// #
+    /// # fn main() -> Result<()> {
+    /// # use std::borrow::Borrow;
+    /// let bytes = vec![
+    /// #     0b1011_0101, 0b0110_1010, 0b1010_1100, 0b1001_1001,
+    /// #     0b1001_1001, 0b1001_1001, 0b1001_1001, 0b1110_0111
+    /// # ];
+    /// # let buffer = BitReadBuffer::new(&bytes, LittleEndian);
+    /// # let mut stream = BitReadStream::new(buffer);
+    /// assert_eq!(stream.read_bytes(3)?.to_vec(), &[0b1011_0101, 0b0110_1010, 0b1010_1100]);
+    /// assert_eq!(stream.pos(), 24);
+    /// #
+    /// #     Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`ReadError::NotEnoughData`]: enum.ReadError.html#variant.NotEnoughData
+    #[inline]
+    pub fn read_bytes(&mut self, byte_count: usize) -> Result<Cow<'a, [u8]>> {
+        self.check_cancelled()?;
+        let result = self.buffer.read_bytes(self.pos, byte_count);
+        if result.is_ok() {
+            self.pos += byte_count * 8;
+            self.check_progress();
+        }
+        result
+    }
+
+    #[doc(hidden)]
+    #[inline]
+    pub unsafe fn read_bytes_unchecked(&mut self, byte_count: usize) -> Cow<'a, [u8]> {
+        let count = byte_count * 8;
+        let result = self.buffer.read_bytes_unchecked(self.pos, byte_count);
+        self.pos += count;
+        self.check_progress();
+        result
+    }
+
+    /// Read a series of bytes from the stream as utf8 string
+    ///
+    /// You can either read a fixed number of bytes, or a dynamic length null-terminated string
+    ///
+    /// # Errors
+    ///
+    /// - [`ReadError::NotEnoughData`]: not enough bits available in the stream
+    /// - [`ReadError::Utf8Error`]: the read bytes are not valid utf8, the error contains the length of the string in bytes
+    ///   so the string can be skipped
+    /// - [`ReadError::LimitExceeded`]: the string is longer than the configured [`ReadLimits::max_string_length`]
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use bitbuffer::{BitReadBuffer, BitReadStream, LittleEndian, Result};
+    /// #
+    /// # fn main() -> Result<()> {
+    /// # let bytes = vec![
+    /// #     0x48, 0x65, 0x6c, 0x6c,
+    /// #     0x6f, 0x20, 0x77, 0x6f,
+    /// #     0x72, 0x6c, 0x64, 0,
+    /// #     0,    0,    0,    0
+    /// # ];
+    /// # let buffer = BitReadBuffer::new(&bytes, LittleEndian);
+    /// # let mut stream = BitReadStream::new(buffer);
+    /// // Fixed length string
+    /// stream.set_pos(0);
+    /// assert_eq!(stream.read_string(Some(11))?, "Hello world".to_owned());
+    /// assert_eq!(11 * 8, stream.pos());
+    /// // fixed length with null padding
+    /// stream.set_pos(0);
+    /// assert_eq!(stream.read_string(Some(16))?, "Hello world".to_owned());
+    /// assert_eq!(16 * 8, stream.pos());
+    /// // null terminated
+    /// stream.set_pos(0);
+    /// assert_eq!(stream.read_string(None)?, "Hello world".to_owned());
+    /// assert_eq!(12 * 8, stream.pos()); // 1 more for the terminating null byte
+    /// #
+    /// #     Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`ReadError::NotEnoughData`]: enum.ReadError.html#variant.NotEnoughData
+    /// [`ReadError::Utf8Error`]: enum.ReadError.html#variant.Utf8Error
+    /// [`ReadError::LimitExceeded`]: enum.ReadError.html#variant.LimitExceeded
+    #[inline]
+    pub fn read_string(&mut self, byte_len: Option<usize>) -> Result<Cow<'a, str>> {
+        if let Some(len) = byte_len {
+            self.check_limit(ReadLimit::StringLength, len)?;
+        }
+        let max_length = self.bits_left() / 8;
+
+        let result = self.buffer.read_string(self.pos, byte_len)?;
         let read = match byte_len {
             Some(len) => len * 8,
-            None => (result.len() + 1) * 8,
+            None => {
+                self.check_limit(ReadLimit::StringLength, result.len())?;
+                (result.len() + 1) * 8
+            }
         };
 
         // due to how sub buffer/streams work, the result string can be longer than the current stream
@@ -332,12 +1075,325 @@ where
                 acc.push(c);
             }
             self.pos += acc.len() * 8;
+            self.check_progress();
             return Ok(Cow::Owned(acc));
         }
         self.pos += read;
+        self.check_progress();
         Ok(result)
     }
 
+    /// Read a string, passing it trough the installed [`Interner`]
+    ///
+    /// Without an interner a new `Arc<str>` is allocated for every string.
+    /// The string is read the same way as [`read_string`](Self::read_string).
+    ///
+    /// # Errors
+    ///
+    /// - [`ReadError::NotEnoughData`]: not enough bits available in the stream
+    /// - [`ReadError::Utf8Error`]: the read bytes are not valid utf8
+    /// - [`ReadError::LimitExceeded`]: the string is longer than the configured [`ReadLimits::max_string_length`]
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use bitbuffer::{BitReadBuffer, BitReadStream, LittleEndian, Result};
+    /// # use std::sync::Arc;
+    /// #
+    /// # fn main() -> Result<()> {
+    /// let bytes = b"DT_Player\0";
+    /// let buffer = BitReadBuffer::new(bytes, LittleEndian);
+    /// let mut stream = BitReadStream::new(buffer);
+    /// stream.set_interner(Arc::new(|string: &str| Arc::from(string.to_ascii_lowercase())));
+    /// assert_eq!(&*stream.read_string_interned(None)?, "dt_player");
+    /// #
+    /// #     Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`ReadError::NotEnoughData`]: enum.ReadError.html#variant.NotEnoughData
+    /// [`ReadError::Utf8Error`]: enum.ReadError.html#variant.Utf8Error
+    /// [`ReadError::LimitExceeded`]: enum.ReadError.html#variant.LimitExceeded
+    pub fn read_string_interned(&mut self, byte_len: Option<usize>) -> Result<Arc<str>> {
+        let string = self.read_string(byte_len)?;
+        Ok(match &self.interner {
+            Some(interner) => interner.0.intern(&string),
+            None => Arc::from(string),
+        })
+    }
+
+    /// Read a null terminated string of at most `max_length` bytes
+    ///
+    /// This behaves like `read_string(None)`, but stops looking for the null terminator after `max_length` bytes
+    /// instead of scanning the rest of the data, so corrupted data can't cause excessive scanning or allocation.
+    ///
+    /// # Errors
+    ///
+    /// - [`ReadError::UnterminatedString`]: no null terminator was found within `max_length` bytes
+    /// - [`ReadError::Utf8Error`]: the read bytes are not valid utf8
+    /// - [`ReadError::LimitExceeded`]: the string is longer than the configured [`ReadLimits::max_string_length`]
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use bitbuffer::{BitReadBuffer, BitReadStream, LittleEndian, Result};
+    /// #
+    /// # fn main() -> Result<()> {
+    /// let bytes = b"Hello\0world\0";
+    /// let buffer = BitReadBuffer::new(bytes, LittleEndian);
+    /// let mut stream = BitReadStream::new(buffer);
+    /// assert_eq!(stream.read_string_bounded(5)?, "Hello");
+    /// assert_eq!(6 * 8, stream.pos());
+    /// assert!(stream.read_string_bounded(4).is_err());
+    /// assert_eq!(6 * 8, stream.pos());
+    /// #
+    /// #     Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`ReadError::UnterminatedString`]: enum.ReadError.html#variant.UnterminatedString
+    /// [`ReadError::Utf8Error`]: enum.ReadError.html#variant.Utf8Error
+    /// [`ReadError::LimitExceeded`]: enum.ReadError.html#variant.LimitExceeded
+    pub fn read_string_bounded(&mut self, max_length: usize) -> Result<Cow<'a, str>> {
+        let available = self.bits_left() / 8;
+        let scan = min(max_length.saturating_add(1), available);
+
+        // scan in growing chunks to avoid copying a lot of data for short strings in unaligned or owned data
+        let mut reader = self.clone();
+        let mut scanned = 0;
+        let mut chunk = 64;
+        let mut length = None;
+        while scanned < scan {
+            let size = min(chunk, scan - scanned);
+            let bytes = reader.read_bytes(size)?;
+            if let Some(index) = memchr::memchr(0, &bytes) {
+                length = Some(scanned + index);
+                break;
+            }
+            scanned += size;
+            chunk *= 2;
+        }
+
+        match length {
+            Some(length) => {
+                let string = self.read_string(Some(length))?;
+                self.pos += 8;
+                self.check_progress();
+                Ok(string)
+            }
+            // like `read_string(None)`, the end of the data counts as terminator
+            None if available <= max_length => self.read_string(Some(available)),
+            None => Err(BitError::UnterminatedString { max_length }),
+        }
+    }
+
+    /// Read a string prefixed by a `length_bits` bits long length in bytes
+    ///
+    /// Unlike fixed length strings read with [`read_string`](Self::read_string), null bytes in the string are kept.
+    ///
+    /// # Errors
+    ///
+    /// - [`ReadError::NotEnoughData`]: not enough bits available in the stream
+    /// - [`ReadError::TooManyBits`]: `length_bits` is larger than the size of `usize`
+    /// - [`ReadError::Utf8Error`]: the read bytes are not valid utf8
+    /// - [`ReadError::LimitExceeded`]: the string is longer than the configured [`ReadLimits::max_string_length`]
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use bitbuffer::{BitReadBuffer, BitReadStream, LittleEndian, Result};
+    /// #
+    /// # fn main() -> Result<()> {
+    /// let bytes = vec![0x05, 0x48, 0x65, 0x6c, 0x6c, 0x6f];
+    /// let buffer = BitReadBuffer::new(&bytes, LittleEndian);
+    /// let mut stream = BitReadStream::new(buffer);
+    /// assert_eq!(stream.read_string_prefixed(8)?, "Hello");
+    /// assert_eq!(6 * 8, stream.pos());
+    /// #
+    /// #     Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`ReadError::NotEnoughData`]: enum.ReadError.html#variant.NotEnoughData
+    /// [`ReadError::TooManyBits`]: enum.ReadError.html#variant.TooManyBits
+    /// [`ReadError::Utf8Error`]: enum.ReadError.html#variant.Utf8Error
+    /// [`ReadError::LimitExceeded`]: enum.ReadError.html#variant.LimitExceeded
+    pub fn read_string_prefixed(&mut self, length_bits: usize) -> Result<Cow<'a, str>> {
+        let start = self.pos;
+        self.read_string_prefixed_inner(length_bits)
+            .inspect_err(|_| self.pos = start)
+    }
+
+    fn read_string_prefixed_inner(&mut self, length_bits: usize) -> Result<Cow<'a, str>> {
+        let len: usize = self.read_int(length_bits)?;
+        self.check_limit(ReadLimit::StringLength, len)?;
+        self.read_utf8(len)
+    }
+
+    fn read_utf8(&mut self, len: usize) -> Result<Cow<'a, str>> {
+        match self.read_bytes(len)? {
+            Cow::Borrowed(bytes) => std::str::from_utf8(bytes)
+                .map(Cow::Borrowed)
+                .map_err(|err| BitError::Utf8Error(err, len)),
+            Cow::Owned(bytes) => Ok(Cow::Owned(String::from_utf8(bytes)?)),
+        }
+    }
+
+    /// Read a string of `char_count` unicode scalar values
+    ///
+    /// This is intended for formats that specify the length of a string in characters instead of bytes,
+    /// null bytes in the string are kept.
+    ///
+    /// # Errors
+    ///
+    /// - [`ReadError::NotEnoughData`]: not enough bits available in the stream, the stream is not advanced
+    /// - [`ReadError::Utf8Error`]: the read bytes are not valid utf8, the error contains the number of bytes read up to
+    ///   and including the invalid sequence. The stream is not advanced
+    /// - [`ReadError::LimitExceeded`]: the string is longer than the configured [`ReadLimits::max_string_length`]
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use bitbuffer::{BitReadBuffer, BitReadStream, LittleEndian, Result};
+    /// #
+    /// # fn main() -> Result<()> {
+    /// let bytes = "Grüße!".as_bytes();
+    /// let buffer = BitReadBuffer::new(bytes, LittleEndian);
+    /// let mut stream = BitReadStream::new(buffer);
+    /// assert_eq!(stream.read_string_chars(5)?, "Grüße");
+    /// assert_eq!(7 * 8, stream.pos());
+    /// #
+    /// #     Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`ReadError::NotEnoughData`]: enum.ReadError.html#variant.NotEnoughData
+    /// [`ReadError::Utf8Error`]: enum.ReadError.html#variant.Utf8Error
+    /// [`ReadError::LimitExceeded`]: enum.ReadError.html#variant.LimitExceeded
+    pub fn read_string_chars(&mut self, char_count: usize) -> Result<Cow<'a, str>> {
+        let start = self.pos;
+        let mut len = 0;
+        for _ in 0..char_count {
+            let char_len = match self.peek_byte(len)?.leading_ones() {
+                0 => 1,
+                2 => 2,
+                3 => 3,
+                4 => 4,
+                _ => return Err(self.utf8_error(len + 1)),
+            };
+            len += 1;
+            for _ in 1..char_len {
+                let continuation = self.peek_byte(len)?;
+                len += 1;
+                if continuation & 0xC0 != 0x80 {
+                    return Err(self.utf8_error(len));
+                }
+            }
+            self.check_limit(ReadLimit::StringLength, len)?;
+        }
+        // overlong encodings and surrogates are rejected here
+        self.read_utf8(len).inspect_err(|_| self.pos = start)
+    }
+
+    /// Read the byte `offset` bytes after the current position without advancing the stream
+    fn peek_byte(&self, offset: usize) -> Result<u8> {
+        self.check_read((offset + 1) * 8)?;
+        self.buffer.read_int(self.pos + offset * 8, 8)
+    }
+
+    /// Get the error for the invalid utf8 in the `len` bytes after the current position
+    fn utf8_error(&self, len: usize) -> BitError {
+        match self.buffer.read_bytes(self.pos, len) {
+            Ok(bytes) => match std::str::from_utf8(&bytes) {
+                Err(err) => BitError::Utf8Error(err, len),
+                Ok(_) => unreachable!("utf8_error called for valid utf8"),
+            },
+            Err(err) => err,
+        }
+    }
+
+    /// Read a utf16 string in the endianness of the stream
+    ///
+    /// If a length is provided, exactly `length` code units will be read and trailing null characters are removed,
+    /// otherwise code units are read until a null code unit is found.
+    ///
+    /// # Errors
+    ///
+    /// - [`ReadError::NotEnoughData`]: not enough bits available in the stream, the stream is not advanced
+    /// - [`ReadError::Utf16Error`]: the read code units contain an unpaired surrogate, the stream is not advanced
+    /// - [`ReadError::LimitExceeded`]: the string is longer than the configured [`ReadLimits::max_string_length`] in bytes
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use bitbuffer::{BitReadBuffer, BitReadStream, LittleEndian, Result};
+    /// #
+    /// # fn main() -> Result<()> {
+    /// let bytes = [0x48, 0x00, 0x69, 0x00, 0x3D, 0xD8, 0x00, 0xDE, 0x00, 0x00];
+    /// let buffer = BitReadBuffer::new(&bytes, LittleEndian);
+    /// let mut stream = BitReadStream::new(buffer);
+    /// assert_eq!(stream.read_string_utf16(None)?, "Hi\u{1F600}");
+    /// assert_eq!(10 * 8, stream.pos());
+    /// #
+    /// #     Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`ReadError::NotEnoughData`]: enum.ReadError.html#variant.NotEnoughData
+    /// [`ReadError::Utf16Error`]: enum.ReadError.html#variant.Utf16Error
+    /// [`ReadError::LimitExceeded`]: enum.ReadError.html#variant.LimitExceeded
+    pub fn read_string_utf16(&mut self, length: Option<usize>) -> Result<String> {
+        let start = self.pos;
+        self.read_string_utf16_inner(length)
+            .inspect_err(|_| self.pos = start)
+    }
+
+    fn read_string_utf16_inner(&mut self, length: Option<usize>) -> Result<String> {
+        let start = self.pos - self.start_pos;
+        let units = match length {
+            Some(length) => {
+                self.check_limit(ReadLimit::StringLength, length.saturating_mul(2))?;
+                self.check_read(length.saturating_mul(16))?;
+                let mut units = (0..length)
+                    .map(|_| self.read_int(16))
+                    .collect::<Result<Vec<u16>>>()?;
+                while units.last() == Some(&0) {
+                    units.pop();
+                }
+                units
+            }
+            None => {
+                let mut units = Vec::new();
+                loop {
+                    let unit: u16 = self.read_int(16)?;
+                    if unit == 0 {
+                        break;
+                    }
+                    units.push(unit);
+                    self.check_limit(ReadLimit::StringLength, units.len() * 2)?;
+                }
+                units
+            }
+        };
+        let mut string = String::with_capacity(units.len());
+        let mut offset = 0;
+        for c in char::decode_utf16(units) {
+            match c {
+                Ok(c) => {
+                    offset += c.len_utf16();
+                    string.push(c);
+                }
+                Err(err) => {
+                    return Err(BitError::Utf16Error {
+                        unit: err.unpaired_surrogate(),
+                        pos: start + offset * 16,
+                    })
+                }
+            }
+        }
+        Ok(string)
+    }
+
     /// Read a sequence of bits from the stream as a BitStream
     ///
     /// # Errors
@@ -370,15 +1426,238 @@ where
     ///
     /// [`ReadError::NotEnoughData`]: enum.ReadError.html#variant.NotEnoughData
     pub fn read_bits(&mut self, count: usize) -> Result<Self> {
+        if count > self.bits_left() {
+            return Err(BitError::NotEnoughData {
+                requested: count,
+                bits_left: self.bits_left(),
+            });
+        }
         let result = BitReadStream {
             buffer: self.buffer.get_sub_buffer(self.pos + count)?,
+            base: self.base,
             start_pos: self.pos,
             pos: self.pos,
+            limits: self.limits,
+            depth: self.depth,
+            interner: self.interner.clone(),
+            cancellation: self.cancellation.clone(),
+            cache: WordCache::default(),
+            progress: None,
+            next_progress: usize::MAX,
         };
         self.pos += count;
+        self.check_progress();
         Ok(result)
     }
 
+    /// Read a series of bits from the stream as a new owned stream
+    ///
+    /// This works like [`read_bits`](Self::read_bits), but the returned stream doesn't borrow the data of this stream,
+    /// so it can be stored or queued for later processing.
+    ///
+    /// If the stream borrows its data, only the bytes covered by the new stream are copied,
+    /// streams that own their data share it with the new stream without copying.
+    ///
+    /// # Errors
+    ///
+    /// - [`ReadError::NotEnoughData`]: not enough bits available in the stream
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use bitbuffer::{BitReadBuffer, BitReadStream, LittleEndian, Result};
+    /// #
+    /// # fn main() -> Result<()> {
+    /// let mut queue = Vec::new();
+    /// {
+    ///     let bytes = vec![0x12, 0x34, 0x56];
+    ///     let mut stream = BitReadStream::new(BitReadBuffer::new(&bytes, LittleEndian));
+    ///     stream.skip_bits(4)?;
+    ///     queue.push(stream.read_bits_owned(12)?);
+    ///     assert_eq!(stream.pos(), 16);
+    /// }
+    /// let mut bits = queue.pop().unwrap();
+    /// assert_eq!(bits.bit_len(), 12);
+    /// assert_eq!(bits.read_int::<u16>(12)?, 0x341);
+    /// #
+    /// #     Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`ReadError::NotEnoughData`]: enum.ReadError.html#variant.NotEnoughData
+    pub fn read_bits_owned(&mut self, count: usize) -> Result<BitReadStream<'static, E>> {
+        self.read_bits(count).map(|bits| bits.to_owned())
+    }
+
+    /// Split the remainder of the stream into two independent streams at `bit` bits after the current position
+    ///
+    /// The first stream contains the next `bit` bits, the second stream the bits after that.
+    /// Both streams start at position `0`, don't overlap and can be sent to different threads.
+    /// The stream itself is not advanced.
+    ///
+    /// # Errors
+    ///
+    /// - [`ReadError::NotEnoughData`]: not enough bits available in the stream
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use bitbuffer::{BitReadBuffer, BitReadStream, BigEndian, Result};
+    /// #
+    /// # fn main() -> Result<()> {
+    /// let bytes = vec![0x01, 0x12, 0x34, 0x56];
+    /// let mut stream = BitReadStream::new(BitReadBuffer::new(&bytes, BigEndian));
+    /// let version: u8 = stream.read()?;
+    /// let (mut header, mut payload) = stream.split_at(4)?;
+    /// assert_eq!(header.read_int::<u8>(4)?, 0x1);
+    /// assert_eq!(payload.bit_len(), 20);
+    /// assert_eq!(payload.read_int::<u32>(20)?, 0x23456);
+    /// assert_eq!(stream.pos(), 8);
+    /// #
+    /// #     Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`ReadError::NotEnoughData`]: enum.ReadError.html#variant.NotEnoughData
+    pub fn split_at(&self, bit: usize) -> Result<(Self, Self)> {
+        let mut stream = self.clone();
+        let head = stream.read_bits(bit)?;
+        let tail = stream.read_bits(stream.bits_left())?;
+        Ok((head, tail))
+    }
+
+    /// Create a second cursor into the stream
+    ///
+    /// Unlike [`clone`](Clone::clone), which creates a stream starting at the current position, the fork has the
+    /// same start, length and position as this stream, so positions from the fork can be used with this stream.
+    /// Both streams read the same data but advance independently, which allows reading ahead speculatively or
+    /// reading the same data with different assumptions. Once the right interpretation is known, the position
+    /// can be taken over with [`reunite`](Self::reunite).
+    ///
+    /// Forking is `O(1)`, the data is shared and never copied. A [progress hook](Self::set_progress_hook) isn't
+    /// carried over to the fork.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use bitbuffer::{BitReadBuffer, BitReadStream, BigEndian, Result};
+    /// #
+    /// # fn main() -> Result<()> {
+    /// let bytes = vec![0x01, 0x00, 0x2A, 0xFF];
+    /// let mut stream = BitReadStream::new(BitReadBuffer::new(&bytes, BigEndian));
+    /// stream.skip_bits(8)?;
+    ///
+    /// // try a 16 bit field first, and fall back to an 8 bit field with a flag
+    /// let mut wide = stream.fork();
+    /// let value: u16 = wide.read()?;
+    /// if value < 0x100 {
+    ///     stream.reunite(wide);
+    /// } else {
+    ///     let _flag: u8 = stream.read()?;
+    /// }
+    /// assert_eq!(value, 0x2A);
+    /// assert_eq!(stream.pos(), 24);
+    /// #
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn fork(&self) -> Self {
+        BitReadStream {
+            buffer: self.buffer.clone(),
+            base: self.base,
+            start_pos: self.start_pos,
+            pos: self.pos,
+            limits: self.limits,
+            depth: self.depth,
+            interner: self.interner.clone(),
+            cancellation: self.cancellation.clone(),
+            cache: self.cache,
+            progress: None,
+            next_progress: usize::MAX,
+        }
+    }
+
+    /// Move to the position of a stream created with [`fork`](Self::fork)
+    ///
+    /// The position can be before or after the current position.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `fork` doesn't read the same data with the same start and length as this stream
+    pub fn reunite(&mut self, fork: Self) {
+        assert!(
+            self.buffer.bytes.same(&fork.buffer.bytes)
+                && self.start_pos == fork.start_pos
+                && self.buffer.bit_len() == fork.buffer.bit_len(),
+            "the stream isn't a fork of this stream"
+        );
+        self.pos = fork.pos;
+        self.next_progress = self.next_progress_pos();
+    }
+
+    /// Skip forward to the position `pos`
+    ///
+    /// This is useful to continue after data that was read by a [fork](Self::fork) without taking over the fork itself.
+    ///
+    /// # Errors
+    ///
+    /// - [`ReadError::IndexOutOfBounds`]: `pos` is before the current position or after the end of the stream
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use bitbuffer::{BitReadBuffer, BitReadStream, LittleEndian, Result};
+    /// #
+    /// # fn main() -> Result<()> {
+    /// let bytes = vec![0x12, 0x34, 0x56];
+    /// let mut stream = BitReadStream::new(BitReadBuffer::new(&bytes, LittleEndian));
+    /// let mut fork = stream.fork();
+    /// fork.skip_bits(12)?;
+    /// stream.skip_to(fork.pos())?;
+    /// assert_eq!(stream.read_int::<u16>(12)?, 0x563);
+    /// assert!(stream.skip_to(4).is_err());
+    /// #
+    /// #     Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`ReadError::IndexOutOfBounds`]: enum.ReadError.html#variant.IndexOutOfBounds
+    pub fn skip_to(&mut self, pos: usize) -> Result<()> {
+        if pos < self.pos() || pos > self.bit_len() {
+            return Err(BitError::IndexOutOfBounds {
+                pos,
+                size: self.bit_len(),
+            });
+        }
+        self.skip_bits(pos - self.pos())
+    }
+
+    /// A copy of the stream that ends `bit_len` bits after the start of the stream, keeping the position
+    ///
+    /// The end is clamped between the current position and the end of the stream.
+    pub(crate) fn truncated(&self, bit_len: usize) -> Self {
+        let end = self
+            .start_pos
+            .saturating_add(bit_len)
+            .clamp(self.pos, self.buffer.bit_len());
+        BitReadStream {
+            buffer: self
+                .buffer
+                .get_sub_buffer(end)
+                .expect("end is within the buffer"),
+            base: self.base,
+            start_pos: self.start_pos,
+            pos: self.pos,
+            limits: self.limits,
+            depth: self.depth,
+            interner: self.interner.clone(),
+            cancellation: self.cancellation.clone(),
+            cache: WordCache::default(),
+            progress: None,
+            next_progress: usize::MAX,
+        }
+    }
+
     /// Skip a number of bits in the stream
     ///
     /// # Errors
@@ -407,8 +1686,10 @@ where
     ///
     /// [`ReadError::NotEnoughData`]: enum.ReadError.html#variant.NotEnoughData
     pub fn skip_bits(&mut self, count: usize) -> Result<()> {
+        self.check_cancelled()?;
         if count <= self.bits_left() {
             self.pos += count;
+            self.check_progress();
             Ok(())
         } else {
             Err(BitError::NotEnoughData {
@@ -418,6 +1699,60 @@ where
         }
     }
 
+    /// Skip all remaining bits in the stream, returns the number of skipped bits
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use bitbuffer::{BitReadBuffer, BitReadStream, LittleEndian};
+    /// #
+    /// # let bytes = vec![0b1011_0101, 0b0110_1010];
+    /// # let buffer = BitReadBuffer::new(&bytes, LittleEndian);
+    /// # let mut stream = BitReadStream::new(buffer);
+    /// assert_eq!(stream.skip_to_end(), 16);
+    /// assert_eq!(stream.skip_to_end(), 0);
+    /// assert_eq!(stream.bits_left(), 0);
+    /// ```
+    pub fn skip_to_end(&mut self) -> usize {
+        let skipped = self.bits_left();
+        self.pos += skipped;
+        self.check_progress();
+        skipped
+    }
+
+    /// Skip to the next byte boundary, returns the number of skipped bits
+    ///
+    /// The boundary is relative to the start of the stream.
+    ///
+    /// # Errors
+    ///
+    /// - [`ReadError::NotEnoughData`]: the stream ends before the next byte boundary
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use bitbuffer::{BitReadBuffer, BitReadStream, LittleEndian, Result};
+    /// #
+    /// # fn main() -> Result<()> {
+    /// # let bytes = vec![0b1011_0101, 0b0110_1010];
+    /// # let buffer = BitReadBuffer::new(&bytes, LittleEndian);
+    /// # let mut stream = BitReadStream::new(buffer);
+    /// stream.skip_bits(3)?;
+    /// assert_eq!(stream.align_to_byte()?, 5);
+    /// assert_eq!(stream.pos(), 8);
+    /// assert_eq!(stream.align_to_byte()?, 0);
+    /// #
+    /// #     Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`ReadError::NotEnoughData`]: enum.ReadError.html#variant.NotEnoughData
+    pub fn align_to_byte(&mut self) -> Result<usize> {
+        let padding = (8 - self.pos() % 8) % 8;
+        self.skip_bits(padding)?;
+        Ok(padding)
+    }
+
     /// Set the position of the stream
     ///
     /// # Errors
@@ -453,9 +1788,62 @@ where
             });
         }
         self.pos = pos + self.start_pos;
+        self.next_progress = self.next_progress_pos();
         Ok(())
     }
 
+    /// Move the position of the stream relative to the start, end or current position, in bits
+    ///
+    /// Returns the new position, counted from the start of the stream.
+    /// Unlike computing the position for [`set_pos`](Self::set_pos) by hand, moving before the start of the stream
+    /// or after the end of the stream can't over- or underflow and is reported with both the current and requested position.
+    ///
+    /// # Errors
+    ///
+    /// - [`ReadError::SeekOutOfBounds`]: the new position is before the start or after the end of the stream,
+    ///   the position is not changed
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use bitbuffer::{BitError, BitReadBuffer, BitReadStream, LittleEndian, Result};
+    /// use std::io::SeekFrom;
+    /// #
+    /// # fn main() -> Result<()> {
+    /// # let bytes = vec![0b1011_0101, 0b0110_1010];
+    /// # let buffer = BitReadBuffer::new(&bytes, LittleEndian);
+    /// # let mut stream = BitReadStream::new(buffer);
+    /// assert_eq!(stream.seek(SeekFrom::End(-4))?, 12);
+    /// assert_eq!(stream.seek(SeekFrom::Current(-9))?, 3);
+    /// assert_eq!(stream.read_int::<u8>(3)?, 0b110);
+    /// assert!(matches!(
+    ///     stream.seek(SeekFrom::Current(-10)),
+    ///     Err(BitError::SeekOutOfBounds { current: 6, requested: -4, size: 16 })
+    /// ));
+    /// #
+    /// #     Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`ReadError::SeekOutOfBounds`]: enum.ReadError.html#variant.SeekOutOfBounds
+    pub fn seek(&mut self, pos: SeekFrom) -> Result<usize> {
+        let requested = match pos {
+            SeekFrom::Start(offset) => offset as i128,
+            SeekFrom::End(offset) => self.bit_len() as i128 + offset as i128,
+            SeekFrom::Current(offset) => self.pos() as i128 + offset as i128,
+        };
+        if requested < 0 || requested > self.bit_len() as i128 {
+            return Err(BitError::SeekOutOfBounds {
+                current: self.pos(),
+                requested,
+                size: self.bit_len(),
+            });
+        }
+        let pos = requested as usize;
+        self.set_pos(pos)?;
+        Ok(pos)
+    }
+
     /// Get the length of the stream in bits
     ///
     /// # Examples
@@ -504,6 +1892,47 @@ where
         self.pos - self.start_pos
     }
 
+    /// Get the position of the start of the stream in the buffer the stream was originally created from
+    ///
+    /// Streams created by [`read_bits`](Self::read_bits), [`split_at`](Self::split_at) or [`clone`](Clone::clone)
+    /// have their own positions starting at `0`, adding a position to the absolute start maps it back to the
+    /// original buffer. This allows reporting positions in errors or offset tables in the coordinates of the
+    /// complete file, also for streams created by [`to_owned`](Self::to_owned) that only copied a part of the data.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use bitbuffer::{BitReadBuffer, BitReadStream, LittleEndian, Result};
+    /// #
+    /// # fn main() -> Result<()> {
+    /// let bytes = vec![0; 8];
+    /// let mut stream = BitReadStream::new(BitReadBuffer::new(&bytes, LittleEndian));
+    /// stream.skip_bits(12)?;
+    /// let mut chunk = stream.read_bits(32)?;
+    /// chunk.skip_bits(3)?;
+    /// assert_eq!(chunk.pos(), 3);
+    /// assert_eq!(chunk.absolute_start(), 12);
+    /// assert_eq!(chunk.absolute_pos(), 15);
+    ///
+    /// let (_, mut tail) = chunk.split_at(5)?;
+    /// tail.skip_bits(1)?;
+    /// assert_eq!(tail.absolute_pos(), 21);
+    /// assert_eq!(tail.to_owned().absolute_pos(), 21);
+    /// #
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn absolute_start(&self) -> usize {
+        self.base + self.start_pos
+    }
+
+    /// Get the current position in the buffer the stream was originally created from
+    ///
+    /// See [`absolute_start`](Self::absolute_start).
+    pub fn absolute_pos(&self) -> usize {
+        self.base + self.pos
+    }
+
     /// Get the number of bits left in the stream
     ///
     /// # Examples
@@ -529,6 +1958,99 @@ where
         self.bit_len() - self.pos()
     }
 
+    /// Check that the stream has been fully consumed
+    ///
+    /// This can be used at the end of parsing to detect trailing data or parsers that don't read
+    /// all the fields they should.
+    ///
+    /// # Errors
+    ///
+    /// - [`ReadError::TrailingData`]: there are bits left in the stream
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use bitbuffer::{BitError, BitReadBuffer, BitReadStream, LittleEndian, Result};
+    /// #
+    /// # fn main() -> Result<()> {
+    /// # let bytes = vec![0b1011_0101, 0b0110_1010];
+    /// # let buffer = BitReadBuffer::new(&bytes, LittleEndian);
+    /// # let mut stream = BitReadStream::new(buffer);
+    /// stream.skip_bits(12)?;
+    /// assert!(matches!(stream.finish(), Err(BitError::TrailingData { bits_left: 4 })));
+    /// stream.skip_bits(4)?;
+    /// stream.finish()?;
+    /// #
+    /// #     Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`ReadError::TrailingData`]: enum.ReadError.html#variant.TrailingData
+    pub fn finish(&self) -> Result<()> {
+        match self.bits_left() {
+            0 => Ok(()),
+            bits_left => Err(BitError::TrailingData { bits_left }),
+        }
+    }
+
+    /// The bytes backing the stream
+    ///
+    /// Streams created with [`read_bits`](Self::read_bits) share the data of the stream they were read from,
+    /// use [`bit_range`](Self::bit_range) to find the part of the data covered by this stream.
+    ///
+    /// For buffers created with [`BitReadBuffer::from_segments`] the segments are copied into a contiguous
+    /// allocation the first time this is called.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use bitbuffer::{BitReadBuffer, BitReadStream, LittleEndian, Result};
+    /// #
+    /// # fn main() -> Result<()> {
+    /// let bytes = vec![0x02, 0xab, 0xcd, 0xef];
+    /// let buffer = BitReadBuffer::new(&bytes, LittleEndian);
+    /// let mut stream = BitReadStream::new(buffer);
+    /// let length: u8 = stream.read()?;
+    /// let message = stream.read_bits(length as usize * 8)?;
+    /// let range = message.bit_range();
+    /// assert_eq!(range, 8..24);
+    /// assert_eq!(&message.data()[range.start / 8..range.end / 8], &[0xab, 0xcd]);
+    /// #
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn data(&self) -> &[u8] {
+        self.buffer.bytes.as_slice()
+    }
+
+    /// The range of bits in [`data`](Self::data) covered by the stream
+    ///
+    /// Unlike [`pos`](Self::pos) and [`bit_len`](Self::bit_len), the start and end of the range are absolute
+    /// offsets in the backing data.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use bitbuffer::{BitReadBuffer, BitReadStream, LittleEndian, Result};
+    /// #
+    /// # fn main() -> Result<()> {
+    /// # let bytes = vec![0b1011_0101, 0b0110_1010];
+    /// # let buffer = BitReadBuffer::new(&bytes, LittleEndian);
+    /// # let mut stream = BitReadStream::new(buffer);
+    /// assert_eq!(stream.bit_range(), 0..16);
+    /// stream.skip_bits(3)?;
+    /// let mut bits = stream.read_bits(5)?;
+    /// assert_eq!(bits.bit_range(), 3..8);
+    /// bits.skip_bits(2)?;
+    /// assert_eq!(bits.bit_range(), 3..8);
+    /// #
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn bit_range(&self) -> Range<usize> {
+        self.start_pos..self.buffer.bit_len()
+    }
+
     /// Read a value based on the provided type
     ///
     /// # Examples
@@ -582,14 +2104,35 @@ where
     /// # }
     /// ```
     #[inline]
+    #[cfg_attr(feature = "stats", track_caller)]
     pub fn read<T: BitRead<'a, E>>(&mut self) -> Result<T> {
-        T::read(self)
+        let start = self.pos;
+        #[cfg(feature = "stats")]
+        let timer = crate::stats::start();
+        let result = T::read(self).inspect_err(|_| self.pos = start);
+        #[cfg(feature = "stats")]
+        crate::stats::record::<T>(
+            timer,
+            std::panic::Location::caller(),
+            result.as_ref().ok().map(|_| self.pos - start),
+        );
+        result
     }
 
     #[doc(hidden)]
     #[inline]
+    #[cfg_attr(feature = "stats", track_caller)]
     pub unsafe fn read_unchecked<T: BitRead<'a, E>>(&mut self, end: bool) -> Result<T> {
-        T::read_unchecked(self, end)
+        #[cfg(feature = "stats")]
+        let (start, timer) = (self.pos, crate::stats::start());
+        let result = T::read_unchecked(self, end);
+        #[cfg(feature = "stats")]
+        crate::stats::record::<T>(
+            timer,
+            std::panic::Location::caller(),
+            result.as_ref().ok().map(|_| self.pos - start),
+        );
+        result
     }
 
     /// Read a value based on the provided type and size
@@ -632,23 +2175,375 @@ where
     /// # }
     /// ```
     #[inline]
+    #[cfg_attr(feature = "stats", track_caller)]
     pub fn read_sized<T: BitReadSized<'a, E>>(&mut self, size: usize) -> Result<T> {
-        T::read(self, size)
+        let start = self.pos;
+        #[cfg(feature = "stats")]
+        let timer = crate::stats::start();
+        let result = T::read(self, size).inspect_err(|_| self.pos = start);
+        #[cfg(feature = "stats")]
+        crate::stats::record::<T>(
+            timer,
+            std::panic::Location::caller(),
+            result.as_ref().ok().map(|_| self.pos - start),
+        );
+        result
     }
 
     #[doc(hidden)]
     #[inline]
+    #[cfg_attr(feature = "stats", track_caller)]
     pub unsafe fn read_sized_unchecked<T: BitReadSized<'a, E>>(
         &mut self,
         size: usize,
         end: bool,
     ) -> Result<T> {
-        T::read_unchecked(self, size, end)
+        #[cfg(feature = "stats")]
+        let (start, timer) = (self.pos, crate::stats::start());
+        let result = T::read_unchecked(self, size, end);
+        #[cfg(feature = "stats")]
+        crate::stats::record::<T>(
+            timer,
+            std::panic::Location::caller(),
+            result.as_ref().ok().map(|_| self.pos - start),
+        );
+        result
+    }
+
+    /// Read a value described by a [`Layout`] without knowing its type at compile time
+    ///
+    /// Fields are read based on the name of their type, which has to be one of the primitive integer and float types,
+    /// `bool`, `String` or a `Vec` of these types. Sizes set with an expression have to be the name of a previous field.
+    ///
+    /// See [`BitValue`] for an example.
+    ///
+    /// # Errors
+    ///
+    /// - [`ReadError::NotEnoughData`]: not enough bits available in the stream
+    /// - [`ReadError::UnsupportedType`]: a field has a type that can't be read dynamically
+    /// - [`ReadError::UnresolvedField`]: the size of a field can't be determined
+    /// - [`ReadError::UnmatchedDiscriminant`]: the read discriminant doesn't match any variant of the enum
+    /// - any error returned while reading a field
+    ///
+    /// The stream is not advanced if an error is returned.
+    ///
+    /// [`ReadError::NotEnoughData`]: enum.ReadError.html#variant.NotEnoughData
+    /// [`ReadError::UnsupportedType`]: enum.ReadError.html#variant.UnsupportedType
+    /// [`ReadError::UnresolvedField`]: enum.ReadError.html#variant.UnresolvedField
+    /// [`ReadError::UnmatchedDiscriminant`]: enum.ReadError.html#variant.UnmatchedDiscriminant
+    pub fn read_dynamic(&mut self, layout: &Layout) -> Result<BitValue> {
+        let start = self.pos;
+        crate::value::read_layout(self, layout).inspect_err(|_| self.pos = start)
+    }
+
+    /// Read `runs` run length encoded pairs and expand them
+    ///
+    /// Every pair consists of a `count_bits` bits long repeat count followed by a value read using `read_value`,
+    /// the value is repeated the number of times specified by the count.
+    ///
+    /// The stream is only advanced if all pairs could be read.
+    ///
+    /// # Errors
+    ///
+    /// - [`ReadError::NotEnoughData`]: not enough bits available in the stream
+    /// - [`ReadError::TooManyBits`]: `count_bits` is larger than the size of `usize`
+    /// - [`ReadError::LimitExceeded`]: the expanded length is larger than [`ReadLimits::max_elements`]
+    /// - [`ReadError::Cancelled`]: the read was cancelled
+    /// - any error returned by `read_value`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use bitbuffer::{BitReadBuffer, BitReadStream, BigEndian, Result};
+    /// #
+    /// # fn main() -> Result<()> {
+    /// // 3 times 'a', 1 time 'b', 0 times 'c' and 2 times 'd'
+    /// let bytes = vec![3, b'a', 1, b'b', 0, b'c', 2, b'd'];
+    /// let buffer = BitReadBuffer::new(&bytes, BigEndian);
+    /// let mut stream = BitReadStream::new(buffer);
+    /// let expanded = stream.read_rle(8, 4, |stream| stream.read_int::<u8>(8))?;
+    /// assert_eq!(expanded, b"aaabdd");
+    /// #
+    /// #     Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`ReadError::NotEnoughData`]: enum.ReadError.html#variant.NotEnoughData
+    /// [`ReadError::TooManyBits`]: enum.ReadError.html#variant.TooManyBits
+    /// [`ReadError::LimitExceeded`]: enum.ReadError.html#variant.LimitExceeded
+    /// [`ReadError::Cancelled`]: enum.ReadError.html#variant.Cancelled
+    pub fn read_rle<T, F>(
+        &mut self,
+        count_bits: usize,
+        runs: usize,
+        read_value: F,
+    ) -> Result<Vec<T>>
+    where
+        T: Clone,
+        F: FnMut(&mut Self) -> Result<T>,
+    {
+        let mut result = Vec::new();
+        self.read_rle_with(count_bits, runs, read_value, |count, value| {
+            result.extend(std::iter::repeat_n(value, count))
+        })?;
+        Ok(result)
+    }
+
+    /// Read `runs` run length encoded pairs and pass every run to a callback
+    ///
+    /// The callback receives the repeat count and value of every run,
+    /// see [`read_rle`](Self::read_rle) for the format of the pairs.
+    ///
+    /// The stream is only advanced if all pairs could be read, the callback is called for every pair that has been read
+    /// before an error occurred.
+    ///
+    /// # Errors
+    ///
+    /// - [`ReadError::NotEnoughData`]: not enough bits available in the stream
+    /// - [`ReadError::TooManyBits`]: `count_bits` is larger than the size of `usize`
+    /// - [`ReadError::LimitExceeded`]: the expanded length is larger than [`ReadLimits::max_elements`]
+    /// - [`ReadError::Cancelled`]: the read was cancelled
+    /// - any error returned by `read_value`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use bitbuffer::{BitReadBuffer, BitReadStream, LittleEndian, Result};
+    /// #
+    /// # fn main() -> Result<()> {
+    /// // runs of 1 bit pixels, with 4 bit counts
+    /// let bytes = vec![0b1101_0011, 0b0101_0100];
+    /// let buffer = BitReadBuffer::new(&bytes, LittleEndian);
+    /// let mut stream = BitReadStream::new(buffer);
+    /// let mut row = String::new();
+    /// stream.read_rle_with(4, 3, |stream| stream.read_bool(), |count, pixel| {
+    ///     row.extend(std::iter::repeat_n(if pixel { '#' } else { '.' }, count))
+    /// })?;
+    /// assert_eq!(row, "###......#####");
+    /// #
+    /// #     Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`ReadError::NotEnoughData`]: enum.ReadError.html#variant.NotEnoughData
+    /// [`ReadError::TooManyBits`]: enum.ReadError.html#variant.TooManyBits
+    /// [`ReadError::LimitExceeded`]: enum.ReadError.html#variant.LimitExceeded
+    /// [`ReadError::Cancelled`]: enum.ReadError.html#variant.Cancelled
+    pub fn read_rle_with<T, F, C>(
+        &mut self,
+        count_bits: usize,
+        runs: usize,
+        mut read_value: F,
+        mut callback: C,
+    ) -> Result<()>
+    where
+        F: FnMut(&mut Self) -> Result<T>,
+        C: FnMut(usize, T),
+    {
+        let start = self.pos;
+        let mut total = 0usize;
+        for _ in 0..runs {
+            let run = self.check_cancelled().and_then(|_| {
+                let count = self.read_int::<usize>(count_bits)?;
+                total = total.saturating_add(count);
+                self.check_limit(ReadLimit::Elements, total)?;
+                Ok((count, read_value(self)?))
+            });
+            match run {
+                Ok((count, value)) => callback(count, value),
+                Err(e) => {
+                    self.pos = start;
+                    return Err(e);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// The limits for reading untrusted input
+    pub fn limits(&self) -> ReadLimits {
+        self.limits
+    }
+
+    /// Set the limits for reading untrusted input
+    ///
+    /// The limits are carried over to streams created from this stream.
+    /// See [`ReadLimits`] for details.
+    pub fn set_limits(&mut self, limits: ReadLimits) {
+        self.limits = limits;
+    }
+
+    /// Install an interner used by [`read_string_interned`](Self::read_string_interned)
+    ///
+    /// The interner is carried over to streams created from this stream. See [`Interner`] for details.
+    pub fn set_interner<I: Interner + 'static>(&mut self, interner: Arc<I>) {
+        self.interner = Some(InternerRef(interner));
+    }
+
+    /// Remove the installed interner
+    pub fn clear_interner(&mut self) {
+        self.interner = None;
+    }
+
+    /// Install a token to cancel bulk reads from another thread
+    ///
+    /// The token is carried over to streams created from this stream. See [`CancellationToken`] for details.
+    pub fn set_cancellation_token(&mut self, token: CancellationToken) {
+        self.cancellation = Some(token);
+    }
+
+    /// Remove the installed cancellation token
+    pub fn clear_cancellation_token(&mut self) {
+        self.cancellation = None;
+    }
+
+    /// Check if the installed cancellation token has been cancelled
+    ///
+    /// This is called by the bulk reads of this crate, custom implementations that read a large number of
+    /// values can call it to support cancellation.
+    ///
+    /// # Errors
+    ///
+    /// - [`ReadError::Cancelled`]: the installed token has been cancelled
+    ///
+    /// [`ReadError::Cancelled`]: enum.ReadError.html#variant.Cancelled
+    #[inline]
+    pub fn check_cancelled(&self) -> Result<()> {
+        match &self.cancellation {
+            Some(token) if token.is_cancelled() => Err(BitError::Cancelled),
+            _ => Ok(()),
+        }
+    }
+
+    /// Install a hook that is called every time the stream has advanced past a multiple of `interval` bits
+    ///
+    /// The hook is called with the current position and the length of the stream, and once more when the end of
+    /// the stream is reached. A read that advances over multiple intervals calls the hook once.
+    /// Moving the position back with [`set_pos`](Self::set_pos) doesn't call the hook.
+    ///
+    /// Unlike the limits, the hook isn't carried over to streams created from this stream or to clones,
+    /// data read through those streams is reported when this stream advances past it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `interval` is 0
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use bitbuffer::{BitReadBuffer, BitReadStream, LittleEndian, Result};
+    /// use std::sync::atomic::{AtomicUsize, Ordering};
+    /// use std::sync::Arc;
+    /// #
+    /// # fn main() -> Result<()> {
+    /// let bytes = vec![0; 1024];
+    /// let mut stream = BitReadStream::new(BitReadBuffer::new(&bytes, LittleEndian));
+    ///
+    /// let progress = Arc::new(AtomicUsize::new(0));
+    /// let hook_progress = Arc::clone(&progress);
+    /// stream.set_progress_hook(1024, move |pos, bit_len| {
+    ///     hook_progress.store(pos * 100 / bit_len, Ordering::Relaxed);
+    /// });
+    ///
+    /// stream.read_bytes(300)?;
+    /// assert_eq!(progress.load(Ordering::Relaxed), 29);
+    /// stream.skip_bits(stream.bits_left())?;
+    /// assert_eq!(progress.load(Ordering::Relaxed), 100);
+    /// #
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn set_progress_hook<F>(&mut self, interval: usize, hook: F)
+    where
+        F: Fn(usize, usize) + Send + Sync + 'static,
+    {
+        assert!(interval > 0, "progress interval must be non-zero");
+        self.progress = Some(ProgressHook {
+            interval,
+            callback: Arc::new(hook),
+        });
+        self.next_progress = self.next_progress_pos();
+    }
+
+    /// Remove the installed progress hook
+    pub fn clear_progress_hook(&mut self) {
+        self.progress = None;
+        self.next_progress = usize::MAX;
+    }
+
+    #[inline(always)]
+    fn check_progress(&mut self) {
+        if self.pos >= self.next_progress {
+            self.report_progress();
+        }
+    }
+
+    #[cold]
+    #[inline(never)]
+    fn report_progress(&mut self) {
+        if let Some(progress) = &self.progress {
+            (progress.callback)(self.pos(), self.bit_len());
+        }
+        self.next_progress = self.next_progress_pos();
+    }
+
+    /// The absolute position at which the progress hook should be called next
+    fn next_progress_pos(&self) -> usize {
+        match &self.progress {
+            Some(progress) if self.pos() < self.bit_len() => {
+                let next = (self.pos() / progress.interval + 1).saturating_mul(progress.interval);
+                self.start_pos + next.min(self.bit_len())
+            }
+            _ => usize::MAX,
+        }
+    }
+
+    /// Check that `requested` doesn't exceed the configured limit
+    ///
+    /// # Errors
+    ///
+    /// - [`ReadError::LimitExceeded`]: `requested` is larger than the configured limit
+    ///
+    /// [`ReadError::LimitExceeded`]: enum.ReadError.html#variant.LimitExceeded
+    pub fn check_limit(&self, limit: ReadLimit, requested: usize) -> Result<()> {
+        let max = limit.max(&self.limits);
+        if requested > max {
+            Err(BitError::LimitExceeded {
+                limit,
+                requested,
+                max,
+            })
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Run a read, restoring the position of the stream if it fails
+    pub(crate) fn restore_on_error<T>(
+        &mut self,
+        read: impl FnOnce(&mut Self) -> Result<T>,
+    ) -> Result<T> {
+        let start = self.pos;
+        read(self).inspect_err(|_| self.pos = start)
+    }
+
+    /// Enter a nested type, checking the depth limit
+    #[doc(hidden)]
+    pub fn enter_nested(&mut self) -> Result<()> {
+        self.check_limit(ReadLimit::Depth, self.depth + 1)?;
+        self.depth += 1;
+        Ok(())
+    }
+
+    /// Leave a nested type entered with `enter_nested`
+    #[doc(hidden)]
+    pub fn leave_nested(&mut self) {
+        self.depth -= 1;
     }
 
     /// Check if we can read a number of bits from the stream
     pub fn check_read(&self, count: usize) -> Result<bool> {
-        if self.bits_left() < count + 64 {
+        if self.bits_left() < count.saturating_add(64) {
             if self.bits_left() < count {
                 Err(BitError::NotEnoughData {
                     requested: count,
@@ -667,11 +2562,23 @@ where
         match self.buffer.bytes {
             Data::Owned(_) => BitReadStream {
                 // already owned, so buffer.to_owned is a cheap rc clone
-                buffer: self.buffer.to_owned(),
-                start_pos: self.pos,
+                buffer: self
+                    .buffer
+                    .to_owned()
+                    .get_sub_buffer(self.buffer.bit_len())
+                    .expect("bit_len is within the buffer"),
+                base: self.base,
+                start_pos: self.start_pos,
                 pos: self.pos,
+                limits: self.limits,
+                depth: self.depth,
+                interner: self.interner.clone(),
+                cancellation: self.cancellation.clone(),
+                cache: WordCache::default(),
+                progress: None,
+                next_progress: usize::MAX,
             },
-            Data::Borrowed(bytes) => {
+            Data::Borrowed(_) | Data::Segmented(_) => {
                 // instead of calling buffer.to_owned blindly, we only copy the bytes that this stream covers
                 let byte_pos = self.start_pos / 8;
                 let bit_offset = self.start_pos & 7;
@@ -679,15 +2586,23 @@ where
                 let end = self.buffer.bit_len() / 8 + 1;
                 let end = min(end, self.buffer.byte_len());
 
-                let sub_bytes = bytes[byte_pos..end].to_vec();
+                let sub_bytes = self.buffer.byte_range(byte_pos, end).into_owned();
                 let buffer = BitReadBuffer::from(sub_bytes)
                     .get_sub_buffer(self.buffer.bit_len() - self.start_pos + bit_offset)
                     .unwrap();
 
                 BitReadStream {
                     buffer,
+                    base: self.base + byte_pos * 8,
                     start_pos: bit_offset,
                     pos: bit_offset + (self.pos - self.start_pos),
+                    limits: self.limits,
+                    depth: self.depth,
+                    interner: self.interner.clone(),
+                    cancellation: self.cancellation.clone(),
+                    cache: WordCache::default(),
+                    progress: None,
+                    next_progress: usize::MAX,
                 }
             }
         }
@@ -698,8 +2613,16 @@ impl<'a, E: Endianness> Clone for BitReadStream<'a, E> {
     fn clone(&self) -> Self {
         BitReadStream {
             buffer: self.buffer.clone(),
+            base: self.base,
             start_pos: self.pos,
             pos: self.pos,
+            limits: self.limits,
+            depth: self.depth,
+            interner: self.interner.clone(),
+            cancellation: self.cancellation.clone(),
+            cache: WordCache::default(),
+            progress: None,
+            next_progress: usize::MAX,
         }
     }
 }
@@ -743,3 +2666,30 @@ impl<'a, E: Endianness> From<&'a [u8]> for BitReadStream<'a, E> {
         BitReadStream::new(BitReadBuffer::from(bytes))
     }
 }
+
+/// The number of bits in `count` elements of `element_bits` bits each
+///
+/// Counts read from the data can be arbitrarily large, so this errors instead of wrapping around.
+#[inline]
+pub(crate) fn checked_bits(count: usize, element_bits: usize) -> Result<usize> {
+    count
+        .checked_mul(element_bits)
+        .ok_or(BitError::SizeOverflow {
+            count,
+            element_bits,
+        })
+}
+
+/// Check that `count` bits fit in the integer type
+#[inline]
+pub(crate) fn check_int_size<T>(count: usize) -> Result<()> {
+    let type_bit_size = size_of::<T>() * 8;
+    if type_bit_size < count {
+        Err(BitError::TooManyBits {
+            requested: count,
+            max: type_bit_size,
+        })
+    } else {
+        Ok(())
+    }
+}
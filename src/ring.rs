@@ -0,0 +1,230 @@
+//! Ring buffer for reading live data
+//!
+//! [`ring_buffer`] creates a fixed size circular buffer with a [`RingProducer`] that appends bytes at the tail
+//! and a [`RingConsumer`] that reads bits from the head. The two halves can be used from different threads,
+//! allowing a capture thread to keep appending data while another thread parses it.
+//!
+//! The consumer reads the buffered data through a regular [`BitReadStream`], data that wraps around the end of
+//! the ring is read without copying. Since a failed read doesn't advance the stream, an incomplete message can
+//! simply be read again once more data has arrived.
+//!
+//! # Examples
+//!
+//! ```
+//! # use bitbuffer::Result;
+//! use bitbuffer::ring::ring_buffer;
+//! use bitbuffer::{BitError, LittleEndian};
+//!
+//! # fn main() -> Result<()> {
+//! let (mut producer, mut consumer) = ring_buffer(4, LittleEndian);
+//! producer.push(&[0x12, 0x34, 0x56]);
+//! assert_eq!(consumer.read_with(|stream| stream.read_int::<u16>(12))?, 0x412);
+//!
+//! // the next 2 bytes wrap around the end of the ring
+//! assert_eq!(producer.push(&[0x78, 0x9A]), 2);
+//! assert_eq!(consumer.read::<u32>().unwrap_err().to_string(), BitError::NotEnoughData {
+//!     requested: 32,
+//!     bits_left: 28,
+//! }.to_string());
+//! assert_eq!(consumer.read_with(|stream| stream.read_int::<u32>(28))?, 0x9A78563);
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::{BitRead, BitReadBuffer, BitReadStream, Endianness, Result};
+use std::cell::UnsafeCell;
+use std::marker::PhantomData;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+
+struct Shared {
+    bytes: Box<[UnsafeCell<u8>]>,
+    /// The position of the first unconsumed byte
+    head: AtomicUsize,
+    /// The position after the last written byte
+    tail: AtomicUsize,
+    closed: AtomicBool,
+}
+
+// the producer only writes to the free part of the ring and the consumer only reads from the filled part,
+// with the head and tail synchronizing ownership of the bytes between them
+unsafe impl Send for Shared {}
+unsafe impl Sync for Shared {}
+
+impl Shared {
+    fn capacity(&self) -> usize {
+        self.bytes.len()
+    }
+
+    fn ptr(&self) -> *mut u8 {
+        UnsafeCell::raw_get(self.bytes.as_ptr())
+    }
+
+    // positions wrap around at twice the capacity instead of counting up forever,
+    // so they can't overflow while a full ring can still be told apart from an empty one
+
+    /// Move a position `count` bytes forward
+    fn advance(&self, position: usize, count: usize) -> usize {
+        let wrap = 2 * self.capacity();
+        if count >= wrap - position {
+            count - (wrap - position)
+        } else {
+            position + count
+        }
+    }
+
+    /// The number of bytes between the head and the tail
+    fn filled(&self, head: usize, tail: usize) -> usize {
+        if tail >= head {
+            tail - head
+        } else {
+            tail + (2 * self.capacity() - head)
+        }
+    }
+}
+
+/// Create a ring buffer that can hold `capacity` bytes
+///
+/// # Panics
+///
+/// Panics if `capacity` is 0
+pub fn ring_buffer<E: Endianness>(
+    capacity: usize,
+    _endianness: E,
+) -> (RingProducer, RingConsumer<E>) {
+    assert!(capacity > 0, "ring buffer capacity must be non-zero");
+    let shared = Arc::new(Shared {
+        bytes: (0..capacity).map(|_| UnsafeCell::new(0)).collect(),
+        head: AtomicUsize::new(0),
+        tail: AtomicUsize::new(0),
+        closed: AtomicBool::new(false),
+    });
+    (
+        RingProducer {
+            shared: Arc::clone(&shared),
+        },
+        RingConsumer {
+            shared,
+            head: 0,
+            head_bit: 0,
+            endianness: PhantomData,
+        },
+    )
+}
+
+/// The writing half of a ring buffer
+///
+/// Dropping the producer marks the ring as closed, see [`RingConsumer::is_closed`].
+pub struct RingProducer {
+    shared: Arc<Shared>,
+}
+
+impl RingProducer {
+    /// The size of the ring in bytes
+    pub fn capacity(&self) -> usize {
+        self.shared.capacity()
+    }
+
+    /// The number of bytes that can currently be pushed
+    pub fn free(&self) -> usize {
+        let head = self.shared.head.load(Ordering::Acquire);
+        let tail = self.shared.tail.load(Ordering::Relaxed);
+        self.capacity() - self.shared.filled(head, tail)
+    }
+
+    /// Append as many bytes from `data` as fit in the ring, returning the number of bytes that were appended
+    pub fn push(&mut self, data: &[u8]) -> usize {
+        let count = data.len().min(self.free());
+        let tail = self.shared.tail.load(Ordering::Relaxed);
+        let start = tail % self.capacity();
+        let first = count.min(self.capacity() - start);
+        // safety: the bytes from the tail up to the head are not accessed by the consumer until the tail is updated
+        unsafe {
+            let ptr = self.shared.ptr();
+            std::ptr::copy_nonoverlapping(data.as_ptr(), ptr.add(start), first);
+            std::ptr::copy_nonoverlapping(data.as_ptr().add(first), ptr, count - first);
+        }
+        self.shared
+            .tail
+            .store(self.shared.advance(tail, count), Ordering::Release);
+        count
+    }
+}
+
+impl Drop for RingProducer {
+    fn drop(&mut self) {
+        self.shared.closed.store(true, Ordering::Release);
+    }
+}
+
+/// The reading half of a ring buffer
+pub struct RingConsumer<E: Endianness> {
+    shared: Arc<Shared>,
+    /// The position of the byte containing the next bit to read
+    head: usize,
+    /// The number of bits of the head byte that have already been read
+    head_bit: usize,
+    endianness: PhantomData<E>,
+}
+
+impl<E: Endianness> RingConsumer<E> {
+    /// The number of bits that are available for reading
+    pub fn bits_left(&self) -> usize {
+        let tail = self.shared.tail.load(Ordering::Acquire);
+        self.shared.filled(self.head, tail) * 8 - self.head_bit
+    }
+
+    /// Whether the producer has been dropped
+    ///
+    /// Once the ring is closed no more data will arrive, but the remaining bits can still be read.
+    pub fn is_closed(&self) -> bool {
+        self.shared.closed.load(Ordering::Acquire)
+    }
+
+    /// Read from the available data using a stream
+    ///
+    /// If `f` succeeds, the bits read from the stream are consumed and the space they used is made available to the producer.
+    /// If `f` fails nothing is consumed, so the read can be retried once more data is available.
+    ///
+    /// # Errors
+    ///
+    /// - any error returned by `f`
+    pub fn read_with<T, F>(&mut self, f: F) -> Result<T>
+    where
+        F: FnOnce(&mut BitReadStream<'_, E>) -> Result<T>,
+    {
+        let tail = self.shared.tail.load(Ordering::Acquire);
+        let capacity = self.shared.capacity();
+        let start = self.head % capacity;
+        let len = self.shared.filled(self.head, tail);
+        let first = len.min(capacity - start);
+        // safety: the bytes from the head up to the tail are not written by the producer until the head is updated,
+        // and the borrow can't outlive this function since `T` can't reference the stream lifetime
+        let segments = unsafe {
+            let ptr = self.shared.ptr();
+            [
+                std::slice::from_raw_parts(ptr.add(start), first),
+                std::slice::from_raw_parts(ptr, len - first),
+            ]
+        };
+        let mut stream =
+            BitReadStream::new(BitReadBuffer::from_segments(segments, E::endianness()));
+        stream.skip_bits(self.head_bit)?;
+        let result = f(&mut stream)?;
+        let consumed = stream.pos();
+        self.head = self.shared.advance(self.head, consumed / 8);
+        self.head_bit = consumed % 8;
+        self.shared.head.store(self.head, Ordering::Release);
+        Ok(result)
+    }
+
+    /// Read a value based on the provided type, consuming the read bits
+    ///
+    /// See [`read_with`](Self::read_with) for details.
+    pub fn read<T>(&mut self) -> Result<T>
+    where
+        T: for<'a> BitRead<'a, E>,
+    {
+        self.read_with(|stream| stream.read())
+    }
+}
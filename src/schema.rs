@@ -0,0 +1,295 @@
+//! Parsing formats described at runtime
+//!
+//! A [`Schema`] describes the fields of a format as data, so tools can handle formats that aren't known
+//! at compile time. Schemas implement `Deserialize`, so they can be loaded from json, toml or any other format
+//! supported by serde.
+//!
+//! Reading a schema from a stream results in a [`BitValue`] tree.
+//!
+//! - `uint` and `int` fields are read as integers of up to 64 bits
+//! - `bool` fields are read as a single bit
+//! - `f32` and `f64` fields are read as floats
+//! - `string` fields are read as a fixed number of bytes, or null terminated if no length is set
+//! - `bytes` fields are read as a fixed number of bytes
+//! - `array` fields are read as `count` elements of the `element` type
+//! - `struct` fields are read field by field
+//!
+//! Bit widths, lengths and counts can either be a number or the name of a previously read integer field,
+//! fields in the current struct are looked up first, followed by the fields of the enclosing structs.
+//! Fields can be made conditional with `if`, reading them only if a previously read field is non-zero,
+//! equal to the `equals` value or has any of the bits in `mask` set.
+//!
+//! # Examples
+//!
+//! ```
+//! # use bitbuffer::Result;
+//! use bitbuffer::schema::Schema;
+//! use bitbuffer::{BitReadBuffer, BitReadStream, BitValue, LittleEndian};
+//!
+//! # fn main() -> Result<()> {
+//! let schema: Schema = serde_json::from_str(r#"{
+//!     "fields": [
+//!         { "name": "version", "type": "uint", "bits": 4 },
+//!         { "name": "has_name", "type": "bool" },
+//!         { "name": "name", "type": "string", "if": { "field": "has_name" } },
+//!         { "name": "count", "type": "uint", "bits": 3 },
+//!         {
+//!             "name": "points",
+//!             "type": "array",
+//!             "count": "count",
+//!             "element": { "type": "int", "bits": 8 }
+//!         }
+//!     ]
+//! }"#).unwrap();
+//!
+//! let bytes = [0x42, 0xFC, 0x1F];
+//! let mut stream = BitReadStream::new(BitReadBuffer::new(&bytes, LittleEndian));
+//! let value = schema.read(&mut stream)?;
+//! assert_eq!(value.get("version"), Some(&BitValue::Int(2)));
+//! assert_eq!(value.get("name"), None);
+//! assert_eq!(value.get("points"), Some(&BitValue::List(vec![BitValue::Int(-4), BitValue::Int(0x1F)])));
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::{BitError, BitReadStream, BitValue, Endianness, ReadLimit, Result};
+use ::serde::{Deserialize, Serialize};
+use std::convert::TryFrom;
+
+/// A runtime description of a format
+///
+/// See the [module level documentation](self) for details.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Schema {
+    /// The fields of the format, in the order they are read
+    pub fields: Vec<Field>,
+}
+
+impl Schema {
+    /// Read the fields of the schema from the stream
+    ///
+    /// The result is always a [`BitValue::Struct`](crate::BitValue::Struct) without a name, fields which are skipped because of their
+    /// condition are left out.
+    ///
+    /// # Errors
+    ///
+    /// - [`ReadError::NotEnoughData`]: not enough bits available in the stream
+    /// - [`ReadError::TooManyBits`]: an integer field is wider than 64 bits
+    /// - [`ReadError::Utf8Error`]: a string field is not valid utf8
+    /// - [`ReadError::UnresolvedField`]: a referenced field hasn't been read or doesn't contain a usable integer
+    /// - [`ReadError::LimitExceeded`]: a string, byte or array field exceeds the configured [`ReadLimits`]
+    ///
+    /// The stream is not advanced if an error is returned.
+    ///
+    /// [`ReadError::NotEnoughData`]: crate::BitError::NotEnoughData
+    /// [`ReadError::TooManyBits`]: crate::BitError::TooManyBits
+    /// [`ReadError::Utf8Error`]: crate::BitError::Utf8Error
+    /// [`ReadError::UnresolvedField`]: crate::BitError::UnresolvedField
+    /// [`ReadError::LimitExceeded`]: crate::BitError::LimitExceeded
+    /// [`ReadLimits`]: crate::ReadLimits
+    pub fn read<E: Endianness>(&self, stream: &mut BitReadStream<E>) -> Result<BitValue> {
+        let start = stream.pos();
+        let mut scopes = Vec::new();
+        read_struct(&self.fields, stream, &mut scopes).inspect_err(|_| {
+            let _ = stream.set_pos(start);
+        })
+    }
+}
+
+/// A named field in a [`Schema`]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Field {
+    /// The name of the field
+    pub name: String,
+    /// The type of the field
+    #[serde(flatten)]
+    pub ty: FieldType,
+    /// Only read the field if the condition holds
+    #[serde(rename = "if", default, skip_serializing_if = "Option::is_none")]
+    pub condition: Option<Condition>,
+}
+
+/// The type of a field
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum FieldType {
+    /// An unsigned integer
+    Uint {
+        /// The number of bits of the integer
+        bits: Size,
+    },
+    /// A signed integer
+    Int {
+        /// The number of bits of the integer
+        bits: Size,
+    },
+    /// A single bit boolean
+    Bool,
+    /// A 32 bit float
+    F32,
+    /// A 64 bit float
+    F64,
+    /// A utf8 string
+    String {
+        /// The length of the string in bytes, the string is null terminated if no length is set
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        length: Option<Size>,
+    },
+    /// A series of bytes
+    Bytes {
+        /// The number of bytes
+        length: Size,
+    },
+    /// A series of elements of the same type
+    Array {
+        /// The number of elements
+        count: Size,
+        /// The type of the elements
+        element: Box<FieldType>,
+    },
+    /// A nested struct
+    Struct {
+        /// The fields of the struct
+        fields: Vec<Field>,
+    },
+}
+
+/// A width, length or count, either fixed or taken from a previously read field
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum Size {
+    /// A fixed size
+    Fixed(usize),
+    /// The value of the named field
+    Field(String),
+}
+
+/// A condition on a previously read field
+///
+/// Without `equals` or `mask` the condition holds if the field is non-zero.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Condition {
+    /// The name of the field
+    pub field: String,
+    /// The condition holds if the field is equal to this value
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub equals: Option<i128>,
+    /// The condition holds if any of these bits are set in the field
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mask: Option<u64>,
+}
+
+fn read_struct<E: Endianness>(
+    fields: &[Field],
+    stream: &mut BitReadStream<E>,
+    scopes: &mut Vec<Vec<(String, BitValue)>>,
+) -> Result<BitValue> {
+    stream.enter_nested()?;
+    scopes.push(Vec::with_capacity(fields.len()));
+    let result = read_fields(fields, stream, scopes);
+    let values = scopes.pop().unwrap_or_default();
+    stream.leave_nested();
+    result.map(|_| BitValue::Struct {
+        name: String::new(),
+        fields: values,
+    })
+}
+
+fn read_fields<E: Endianness>(
+    fields: &[Field],
+    stream: &mut BitReadStream<E>,
+    scopes: &mut Vec<Vec<(String, BitValue)>>,
+) -> Result<()> {
+    for field in fields {
+        if let Some(condition) = &field.condition {
+            let value = resolve(&condition.field, scopes)?;
+            let holds = match (condition.equals, condition.mask) {
+                (None, None) => value != 0,
+                (equals, mask) => {
+                    equals.is_none_or(|equals| value == equals)
+                        && mask.is_none_or(|mask| value as u64 & mask != 0)
+                }
+            };
+            if !holds {
+                continue;
+            }
+        }
+        let value = read_value(&field.ty, stream, scopes)?;
+        if let Some(scope) = scopes.last_mut() {
+            scope.push((field.name.clone(), value));
+        }
+    }
+    Ok(())
+}
+
+fn read_value<E: Endianness>(
+    ty: &FieldType,
+    stream: &mut BitReadStream<E>,
+    scopes: &mut Vec<Vec<(String, BitValue)>>,
+) -> Result<BitValue> {
+    Ok(match ty {
+        FieldType::Uint { bits } => {
+            let bits = size(bits, scopes)?;
+            BitValue::Int(if bits == 0 {
+                0
+            } else {
+                stream.read_int::<u64>(bits)? as i128
+            })
+        }
+        FieldType::Int { bits } => {
+            let bits = size(bits, scopes)?;
+            BitValue::Int(if bits == 0 {
+                0
+            } else {
+                stream.read_int::<i64>(bits)? as i128
+            })
+        }
+        FieldType::Bool => BitValue::Bool(stream.read_bool()?),
+        FieldType::F32 => BitValue::Float(stream.read_float::<f32>()? as f64),
+        FieldType::F64 => BitValue::Float(stream.read_float()?),
+        FieldType::String { length } => {
+            let length = length
+                .as_ref()
+                .map(|length| size(length, scopes))
+                .transpose()?;
+            BitValue::Str(stream.read_string(length)?.into_owned())
+        }
+        FieldType::Bytes { length } => {
+            let length = size(length, scopes)?;
+            stream.check_limit(ReadLimit::Elements, length)?;
+            BitValue::Bytes(stream.read_bytes(length)?.into_owned())
+        }
+        FieldType::Array { count, element } => {
+            let count = size(count, scopes)?;
+            stream.check_limit(ReadLimit::Elements, count)?;
+            let mut values = Vec::with_capacity(count.min(1024));
+            for _ in 0..count {
+                stream.check_cancelled()?;
+                values.push(read_value(element, stream, scopes)?);
+            }
+            BitValue::List(values)
+        }
+        FieldType::Struct { fields } => read_struct(fields, stream, scopes)?,
+    })
+}
+
+fn size(size: &Size, scopes: &[Vec<(String, BitValue)>]) -> Result<usize> {
+    match size {
+        Size::Fixed(size) => Ok(*size),
+        Size::Field(name) => usize::try_from(resolve(name, scopes)?)
+            .map_err(|_| BitError::UnresolvedField { name: name.clone() }),
+    }
+}
+
+/// Get the integer value of a previously read field, starting at the innermost struct
+fn resolve(name: &str, scopes: &[Vec<(String, BitValue)>]) -> Result<i128> {
+    scopes
+        .iter()
+        .rev()
+        .flat_map(|scope| scope.iter().rev())
+        .find(|(field, _)| field == name)
+        .and_then(|(_, value)| value.as_int())
+        .ok_or_else(|| BitError::UnresolvedField {
+            name: name.to_string(),
+        })
+}
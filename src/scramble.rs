@@ -0,0 +1,352 @@
+//! XOR scrambling and descrambling of bit streams
+//!
+//! Many protocols XOR their data with a pseudo random bit sequence (energy dispersal in DVB,
+//! whitening in radio protocols) or a repeating key (simple obfuscation in game protocols).
+//!
+//! A [`KeyStream`] generates the bits to XOR the data with, it can be attached to a [`BitWriteStream`]
+//! using [`attach_scrambler`] to scramble everything written to the stream,
+//! or used with a [`DescrambleStream`] to descramble data while reading it.
+//!
+//! # Examples
+//!
+//! ```
+//! # use bitbuffer::Result;
+//! use bitbuffer::scramble::{DescrambleStream, RepeatingKey};
+//! use bitbuffer::{BitReadBuffer, BitReadStream, BitWriteStream, LittleEndian};
+//!
+//! # fn main() -> Result<()> {
+//! let mut data = Vec::new();
+//! let mut stream = BitWriteStream::new(&mut data, LittleEndian);
+//! stream.attach_scrambler(RepeatingKey::new(vec![0x5A, 0xA5], LittleEndian));
+//! stream.write_int(12u8, 5)?;
+//! stream.write_string("foo", None)?;
+//! assert_eq!(data[0] & 0b1_1111, 12 ^ 0x1A);
+//!
+//! let stream = BitReadStream::new(BitReadBuffer::new(&data, LittleEndian));
+//! let mut stream = DescrambleStream::new(stream, RepeatingKey::new(vec![0x5A, 0xA5], LittleEndian));
+//! assert_eq!(stream.read_int::<u8>(5)?, 12);
+//! assert_eq!(stream.read_bytes(4)?, b"foo\0");
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! [`BitWriteStream`]: crate::BitWriteStream
+//! [`attach_scrambler`]: crate::BitWriteStream::attach_scrambler
+
+use crate::num_traits::{IsSigned, UncheckedPrimitiveInt};
+use crate::{BitError, BitReadBuffer, BitReadStream, BitWriteStream, Endianness, Result};
+use num_traits::PrimInt;
+use std::marker::PhantomData;
+use std::mem::size_of;
+use std::ops::BitOrAssign;
+
+/// A source of bits to XOR data with
+pub trait KeyStream {
+    /// Get the next bit of the key stream
+    fn next_bit(&mut self) -> bool;
+
+    /// Get the next `count` bits of the key stream, at most 64
+    ///
+    /// The bits are packed the same way as an integer of `count` bits would be read from a stream
+    /// with endianness `E`, the first bit is the least significant bit for little endian streams and
+    /// the most significant bit for big endian streams.
+    fn next_bits<E: Endianness>(&mut self, count: usize) -> u64
+    where
+        Self: Sized,
+    {
+        debug_assert!(count <= 64);
+        let mut bits = 0;
+        for i in 0..count {
+            let bit = self.next_bit() as u64;
+            if E::is_le() {
+                bits |= bit << i;
+            } else {
+                bits |= bit << (count - 1 - i);
+            }
+        }
+        bits
+    }
+}
+
+/// Pseudo random bit sequence generated by a linear feedback shift register
+///
+/// Every step, the register bits selected by the taps are XOR-ed together to form the output bit,
+/// which is then shifted into the register.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Prbs {
+    state: u64,
+    seed: u64,
+    taps: u64,
+    mask: u64,
+}
+
+impl Prbs {
+    /// Create a sequence for a register of `degree` bits with the polynomial `x^degree + x^tap + 1`
+    ///
+    /// The `seed` is the initial value of the register with stage 1 as the least significant bit.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `degree` isn't between 2 and 64 or `tap` isn't between 1 and `degree`
+    pub fn new(degree: u32, tap: u32, seed: u64) -> Self {
+        assert!(
+            (2..=64).contains(&degree),
+            "degree must be between 2 and 64"
+        );
+        assert!(
+            (1..degree).contains(&tap),
+            "tap must be between 1 and degree"
+        );
+        let mask = u64::MAX >> (64 - degree);
+        Prbs {
+            state: seed & mask,
+            seed: seed & mask,
+            taps: (1 << (degree - 1)) | (1 << (tap - 1)),
+            mask,
+        }
+    }
+
+    /// The DVB energy dispersal sequence, `x^15 + x^14 + 1` with the register initialized to `100101010000000`
+    pub fn dvb() -> Self {
+        Prbs::new(15, 14, 0b000_0000_1010_1001)
+    }
+
+    /// The ITU-T O.150 PRBS7 sequence, `x^7 + x^6 + 1`
+    pub fn prbs7() -> Self {
+        Prbs::new(7, 6, 0x7F)
+    }
+
+    /// The ITU-T O.150 PRBS9 sequence, `x^9 + x^5 + 1`
+    pub fn prbs9() -> Self {
+        Prbs::new(9, 5, 0x1FF)
+    }
+
+    /// The ITU-T O.150 PRBS15 sequence, `x^15 + x^14 + 1`
+    pub fn prbs15() -> Self {
+        Prbs::new(15, 14, 0x7FFF)
+    }
+
+    /// The ITU-T O.150 PRBS23 sequence, `x^23 + x^18 + 1`
+    pub fn prbs23() -> Self {
+        Prbs::new(23, 18, 0x7F_FFFF)
+    }
+
+    /// The ITU-T O.150 PRBS31 sequence, `x^31 + x^28 + 1`
+    pub fn prbs31() -> Self {
+        Prbs::new(31, 28, 0x7FFF_FFFF)
+    }
+
+    /// Reset the register to the seed
+    pub fn reset(&mut self) {
+        self.state = self.seed;
+    }
+}
+
+impl KeyStream for Prbs {
+    fn next_bit(&mut self) -> bool {
+        let bit = (self.state & self.taps).count_ones() & 1 == 1;
+        self.state = ((self.state << 1) | bit as u64) & self.mask;
+        bit
+    }
+}
+
+/// A key that is repeated for the length of the data
+///
+/// The key is laid out the same way as data in a stream with endianness `E`,
+/// so XOR-ing a byte aligned stream with the key gives the same result as XOR-ing the bytes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RepeatingKey<E: Endianness> {
+    key: Vec<u8>,
+    pos: usize,
+    endianness: PhantomData<E>,
+}
+
+impl<E: Endianness> RepeatingKey<E> {
+    /// Create a key stream repeating the key bytes
+    ///
+    /// # Panics
+    ///
+    /// Panics if the key is empty
+    pub fn new(key: Vec<u8>, _endianness: E) -> Self {
+        assert!(!key.is_empty(), "key can't be empty");
+        RepeatingKey {
+            key,
+            pos: 0,
+            endianness: PhantomData,
+        }
+    }
+}
+
+impl<E: Endianness> KeyStream for RepeatingKey<E> {
+    fn next_bit(&mut self) -> bool {
+        let byte = self.key[self.pos / 8];
+        let offset = self.pos % 8;
+        self.pos = (self.pos + 1) % (self.key.len() * 8);
+        if E::is_le() {
+            byte >> offset & 1 == 1
+        } else {
+            byte << offset & 0b1000_0000 == 0b1000_0000
+        }
+    }
+}
+
+/// Get up to 128 bits from the key stream
+fn next_bits_wide<K: KeyStream, E: Endianness>(key: &mut K, count: usize) -> u128 {
+    if count <= 64 {
+        key.next_bits::<E>(count) as u128
+    } else if E::is_le() {
+        let low = key.next_bits::<E>(64) as u128;
+        low | (key.next_bits::<E>(count - 64) as u128) << 64
+    } else {
+        let high = key.next_bits::<E>(64) as u128;
+        high << (count - 64) | key.next_bits::<E>(count - 64) as u128
+    }
+}
+
+/// Type erased scrambler attached to a write buffer
+pub(crate) struct ScrambleHook<E: Endianness> {
+    key: Box<dyn FnMut(usize) -> u64 + Send>,
+    endianness: PhantomData<E>,
+}
+
+impl<E: Endianness> ScrambleHook<E> {
+    pub fn new<K: KeyStream + Send + 'static>(mut key: K) -> Self {
+        ScrambleHook {
+            key: Box::new(move |count| key.next_bits::<E>(count)),
+            endianness: PhantomData,
+        }
+    }
+
+    /// Scramble up to an usize worth of bits
+    pub fn scramble(&mut self, bits: usize, count: usize) -> usize {
+        bits ^ (self.key)(count) as usize
+    }
+}
+
+/// Read stream adapter that descrambles the data while reading
+#[derive(Debug)]
+pub struct DescrambleStream<'a, E: Endianness, K: KeyStream> {
+    stream: BitReadStream<'a, E>,
+    key: K,
+}
+
+impl<'a, E: Endianness, K: KeyStream> DescrambleStream<'a, E, K> {
+    /// Descramble the data read from the stream using the key stream
+    pub fn new(stream: BitReadStream<'a, E>, key: K) -> Self {
+        DescrambleStream { stream, key }
+    }
+
+    /// Get the underlying stream and key stream back
+    pub fn into_inner(self) -> (BitReadStream<'a, E>, K) {
+        (self.stream, self.key)
+    }
+
+    /// The current position of the underlying stream
+    pub fn pos(&self) -> usize {
+        self.stream.pos()
+    }
+
+    /// The number of bits left in the underlying stream
+    pub fn bits_left(&self) -> usize {
+        self.stream.bits_left()
+    }
+
+    /// Read a single descrambled bit as boolean
+    ///
+    /// # Errors
+    ///
+    /// - [`ReadError::NotEnoughData`]: not enough bits available in the stream
+    ///
+    /// [`ReadError::NotEnoughData`]: enum.ReadError.html#variant.NotEnoughData
+    pub fn read_bool(&mut self) -> Result<bool> {
+        let raw = self.stream.read_bool()?;
+        Ok(raw ^ self.key.next_bit())
+    }
+
+    /// Read a descrambled sequence of bits as integer
+    ///
+    /// # Errors
+    ///
+    /// - [`ReadError::NotEnoughData`]: not enough bits available in the stream
+    /// - [`ReadError::TooManyBits`]: to many bits requested for the chosen integer type
+    ///
+    /// [`ReadError::NotEnoughData`]: enum.ReadError.html#variant.NotEnoughData
+    /// [`ReadError::TooManyBits`]: enum.ReadError.html#variant.TooManyBits
+    pub fn read_int<T>(&mut self, count: usize) -> Result<T>
+    where
+        T: PrimInt + BitOrAssign + IsSigned + UncheckedPrimitiveInt,
+    {
+        let type_bit_size = size_of::<T>() * 8;
+        if type_bit_size < count {
+            return Err(BitError::TooManyBits {
+                requested: count,
+                max: type_bit_size,
+            });
+        }
+
+        // read unsigned to get the raw bits, and apply the sign after descrambling
+        let raw: u128 = self.stream.read_int(count)?;
+        let value = raw ^ next_bits_wide::<K, E>(&mut self.key, count);
+        if T::is_signed() && count > 0 && count < 128 && value >> (count - 1) & 1 == 1 {
+            Ok(T::from_i128_unchecked(
+                (value | (u128::MAX << count)) as i128,
+            ))
+        } else {
+            Ok(T::from_u128_unchecked(value))
+        }
+    }
+
+    /// Read a series of descrambled bytes
+    ///
+    /// # Errors
+    ///
+    /// - [`ReadError::NotEnoughData`]: not enough bits available in the stream
+    ///
+    /// [`ReadError::NotEnoughData`]: enum.ReadError.html#variant.NotEnoughData
+    pub fn read_bytes(&mut self, byte_count: usize) -> Result<Vec<u8>> {
+        let mut bytes = self.stream.read_bytes(byte_count)?.into_owned();
+        for byte in bytes.iter_mut() {
+            *byte ^= self.key.next_bits::<E>(8) as u8;
+        }
+        Ok(bytes)
+    }
+
+    /// Read a number of bits and descramble them into a new stream
+    ///
+    /// This allows reading any type implementing [`BitRead`] from the descrambled data.
+    ///
+    /// # Errors
+    ///
+    /// - [`ReadError::NotEnoughData`]: not enough bits available in the stream
+    ///
+    /// [`BitRead`]: crate::BitRead
+    /// [`ReadError::NotEnoughData`]: enum.ReadError.html#variant.NotEnoughData
+    pub fn read_bits(&mut self, count: usize) -> Result<BitReadStream<'static, E>> {
+        let mut raw = self.stream.read_bits(count)?;
+        let mut data = Vec::with_capacity(count.div_ceil(8));
+        let mut write = BitWriteStream::new(&mut data, E::endianness());
+        while raw.bits_left() > 0 {
+            let chunk = raw.bits_left().min(32);
+            let bits: u32 = raw.read_int(chunk)?;
+            let key = self.key.next_bits::<E>(chunk) as u32;
+            write.write_int(bits ^ key, chunk)?;
+        }
+        let mut stream = BitReadStream::new(BitReadBuffer::new_owned(data, E::endianness()));
+        stream.read_bits(count)
+    }
+
+    /// Skip a number of bits in the stream, advancing the key stream by the same amount
+    ///
+    /// # Errors
+    ///
+    /// - [`ReadError::NotEnoughData`]: not enough bits available in the stream
+    ///
+    /// [`ReadError::NotEnoughData`]: enum.ReadError.html#variant.NotEnoughData
+    pub fn skip_bits(&mut self, count: usize) -> Result<()> {
+        self.stream.skip_bits(count)?;
+        for _ in 0..count {
+            self.key.next_bit();
+        }
+        Ok(())
+    }
+}
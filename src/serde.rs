@@ -0,0 +1,747 @@
+//! Bridge between serde and the bit streams
+//!
+//! Allows any type implementing `Serialize` or `Deserialize` to be written to a [`BitWriteStream`]
+//! or read from a [`BitReadStream`].
+//!
+//! Since the bit streams are not self-describing, the data is written without any type information,
+//! and types have to be read back the same way as they were written.
+//!
+//! - integers are written using their full width or as variable length integers, depending on the [`IntEncoding`]
+//! - floats are written using their full width
+//! - `bool` is written as a single bit
+//! - `Option` is written as a bool followed by the value if present
+//! - strings are written null-terminated or length-prefixed, depending on the [`StringEncoding`]
+//! - byte arrays, sequences and maps are written as a length of [`Config::length_bits`] bits followed by the items
+//! - enum variants are written as a variant index of [`Config::variant_bits`] bits followed by the variant contents
+//! - structs and tuples are written field by field
+//!
+//! # Examples
+//!
+//! ```
+//! # use bitbuffer::{BitReadBuffer, BitReadStream, BitWriteStream, LittleEndian};
+//! use bitbuffer::serde::{deserialize, serialize};
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Serialize, Deserialize, Debug, PartialEq)]
+//! struct Packet {
+//!     id: u16,
+//!     name: String,
+//!     flags: Vec<bool>,
+//! }
+//!
+//! # fn main() -> Result<(), bitbuffer::serde::Error> {
+//! let packet = Packet {
+//!     id: 12,
+//!     name: "foo".to_string(),
+//!     flags: vec![true, false, true],
+//! };
+//!
+//! let mut data = Vec::new();
+//! let mut stream = BitWriteStream::new(&mut data, LittleEndian);
+//! serialize(&packet, &mut stream)?;
+//!
+//! let mut stream = BitReadStream::new(BitReadBuffer::new(&data, LittleEndian));
+//! let result: Packet = deserialize(&mut stream)?;
+//! assert_eq!(packet, result);
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::{BitError, BitRead, BitReadStream, BitWrite, BitWriteStream, Endianness, ReadLimit};
+use ::serde::de::{
+    self, DeserializeSeed, EnumAccess, IntoDeserializer, MapAccess, SeqAccess, VariantAccess,
+    Visitor,
+};
+use ::serde::ser::{self, Serialize};
+use err_derive::Error;
+use std::any::type_name;
+use std::borrow::Cow;
+use std::convert::TryFrom;
+use std::fmt::Display;
+
+/// Errors that can occur while serializing or deserializing
+#[derive(Debug, Error)]
+pub enum Error {
+    /// Error from the underlying stream
+    #[error(display = "{}", _0)]
+    Bit(#[error(source, no_from)] BitError),
+    /// Error reported by the type being serialized or deserialized
+    #[error(display = "{}", _0)]
+    Message(String),
+    /// The requested operation is not possible for non self-describing formats
+    #[error(display = "{} is not supported by the bit stream format", _0)]
+    Unsupported(&'static str),
+}
+
+impl From<BitError> for Error {
+    fn from(err: BitError) -> Self {
+        Error::Bit(err)
+    }
+}
+
+impl ser::Error for Error {
+    fn custom<T: Display>(msg: T) -> Self {
+        Error::Message(msg.to_string())
+    }
+}
+
+impl de::Error for Error {
+    fn custom<T: Display>(msg: T) -> Self {
+        Error::Message(msg.to_string())
+    }
+}
+
+/// Either the serialized value or an [`Error`](enum@Error)
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// How strings are encoded in the stream
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StringEncoding {
+    /// The bytes of the string, followed by a null byte
+    NullTerminated,
+    /// The length of the string in bytes using [`Config::length_bits`] bits, followed by the bytes
+    LengthPrefixed,
+}
+
+/// How integers are encoded in the stream
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IntEncoding {
+    /// The full width of the integer type
+    Fixed,
+    /// 16 to 64 bit integers are written as SQLite style variable length integers, see
+    /// [`read_varint_sqlite`](BitReadStream::read_varint_sqlite), with signed integers zigzag encoded
+    /// so that small negative values are small as well.
+    ///
+    /// 8 and 128 bit integers are still written using their full width.
+    Varint,
+}
+
+/// Configuration for the layout of serialized data
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Config {
+    /// The number of bits used to store the length of sequences, maps and byte arrays
+    pub length_bits: usize,
+    /// The number of bits used to store the index of enum variants
+    pub variant_bits: usize,
+    /// How strings are encoded
+    pub string_encoding: StringEncoding,
+    /// How integers are encoded
+    pub int_encoding: IntEncoding,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            length_bits: 32,
+            variant_bits: 32,
+            string_encoding: StringEncoding::NullTerminated,
+            int_encoding: IntEncoding::Fixed,
+        }
+    }
+}
+
+/// Serialize a value into the stream using the default [`Config`]
+pub fn serialize<T: Serialize + ?Sized, E: Endianness>(
+    value: &T,
+    stream: &mut BitWriteStream<E>,
+) -> Result<()> {
+    value.serialize(&mut Serializer::new(stream, Config::default()))
+}
+
+/// Deserialize a value from the stream using the default [`Config`]
+pub fn deserialize<'a, T: de::Deserialize<'a>, E: Endianness>(
+    stream: &mut BitReadStream<'a, E>,
+) -> Result<T> {
+    T::deserialize(&mut Deserializer::new(stream, Config::default()))
+}
+
+/// Serde serializer backed by a [`BitWriteStream`]
+pub struct Serializer<'r, 'a, E: Endianness> {
+    stream: &'r mut BitWriteStream<'a, E>,
+    config: Config,
+}
+
+impl<'r, 'a, E: Endianness> Serializer<'r, 'a, E> {
+    /// Create a serializer writing into the stream
+    pub fn new(stream: &'r mut BitWriteStream<'a, E>, config: Config) -> Self {
+        Serializer { stream, config }
+    }
+
+    fn write_length(&mut self, length: Option<usize>) -> Result<()> {
+        let length = length.ok_or(Error::Unsupported(
+            "Serializing sequences of unknown length",
+        ))?;
+        self.stream.write_int(length, self.config.length_bits)?;
+        Ok(())
+    }
+
+    fn write_variant(&mut self, variant_index: u32) -> Result<()> {
+        self.stream
+            .write_int(variant_index, self.config.variant_bits)?;
+        Ok(())
+    }
+
+    fn write_unsigned<T: BitWrite<E> + Into<u64> + Copy>(&mut self, value: T) -> Result<()> {
+        match self.config.int_encoding {
+            IntEncoding::Fixed => self.stream.write(&value)?,
+            IntEncoding::Varint => self.stream.write_varint_sqlite(value.into())?,
+        }
+        Ok(())
+    }
+
+    fn write_signed<T: BitWrite<E> + Into<i64> + Copy>(&mut self, value: T) -> Result<()> {
+        match self.config.int_encoding {
+            IntEncoding::Fixed => self.stream.write(&value)?,
+            IntEncoding::Varint => {
+                let value = value.into();
+                self.stream
+                    .write_varint_sqlite(((value << 1) ^ (value >> 63)) as u64)?
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<'s, 'r, 'a, E: Endianness> ser::Serializer for &'s mut Serializer<'r, 'a, E> {
+    type Ok = ();
+    type Error = Error;
+    type SerializeSeq = Self;
+    type SerializeTuple = Self;
+    type SerializeTupleStruct = Self;
+    type SerializeTupleVariant = Self;
+    type SerializeMap = Self;
+    type SerializeStruct = Self;
+    type SerializeStructVariant = Self;
+
+    fn serialize_bool(self, v: bool) -> Result<()> {
+        Ok(self.stream.write_bool(v)?)
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<()> {
+        Ok(self.stream.write(&v)?)
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<()> {
+        self.write_signed(v)
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<()> {
+        self.write_signed(v)
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<()> {
+        self.write_signed(v)
+    }
+
+    fn serialize_i128(self, v: i128) -> Result<()> {
+        Ok(self.stream.write(&v)?)
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<()> {
+        Ok(self.stream.write(&v)?)
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<()> {
+        self.write_unsigned(v)
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<()> {
+        self.write_unsigned(v)
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<()> {
+        self.write_unsigned(v)
+    }
+
+    fn serialize_u128(self, v: u128) -> Result<()> {
+        Ok(self.stream.write(&v)?)
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<()> {
+        Ok(self.stream.write(&v)?)
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<()> {
+        Ok(self.stream.write(&v)?)
+    }
+
+    fn serialize_char(self, v: char) -> Result<()> {
+        Ok(self.stream.write(&(v as u32))?)
+    }
+
+    fn serialize_str(self, v: &str) -> Result<()> {
+        match self.config.string_encoding {
+            StringEncoding::NullTerminated => self.stream.write_string(v, None)?,
+            StringEncoding::LengthPrefixed => {
+                self.write_length(Some(v.len()))?;
+                self.stream.write_bytes(v.as_bytes())?;
+            }
+        }
+        Ok(())
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<()> {
+        self.write_length(Some(v.len()))?;
+        Ok(self.stream.write_bytes(v)?)
+    }
+
+    fn serialize_none(self) -> Result<()> {
+        Ok(self.stream.write_bool(false)?)
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<()> {
+        self.stream.write_bool(true)?;
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<()> {
+        Ok(())
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<()> {
+        Ok(())
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        variant_index: u32,
+        _variant: &'static str,
+    ) -> Result<()> {
+        self.write_variant(variant_index)
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<()> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        variant_index: u32,
+        _variant: &'static str,
+        value: &T,
+    ) -> Result<()> {
+        self.write_variant(variant_index)?;
+        value.serialize(self)
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<Self> {
+        self.write_length(len)?;
+        Ok(self)
+    }
+
+    fn serialize_tuple(self, _len: usize) -> Result<Self> {
+        Ok(self)
+    }
+
+    fn serialize_tuple_struct(self, _name: &'static str, _len: usize) -> Result<Self> {
+        Ok(self)
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self> {
+        self.write_variant(variant_index)?;
+        Ok(self)
+    }
+
+    fn serialize_map(self, len: Option<usize>) -> Result<Self> {
+        self.write_length(len)?;
+        Ok(self)
+    }
+
+    fn serialize_struct(self, _name: &'static str, _len: usize) -> Result<Self> {
+        Ok(self)
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self> {
+        self.write_variant(variant_index)?;
+        Ok(self)
+    }
+
+    fn is_human_readable(&self) -> bool {
+        false
+    }
+}
+
+macro_rules! impl_serialize_compound {
+    ($trait:ident, $method:ident) => {
+        impl<'s, 'r, 'a, E: Endianness> ser::$trait for &'s mut Serializer<'r, 'a, E> {
+            type Ok = ();
+            type Error = Error;
+
+            fn $method<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<()> {
+                value.serialize(&mut **self)
+            }
+
+            fn end(self) -> Result<()> {
+                Ok(())
+            }
+        }
+    };
+}
+
+impl_serialize_compound!(SerializeSeq, serialize_element);
+impl_serialize_compound!(SerializeTuple, serialize_element);
+impl_serialize_compound!(SerializeTupleStruct, serialize_field);
+impl_serialize_compound!(SerializeTupleVariant, serialize_field);
+
+impl<'s, 'r, 'a, E: Endianness> ser::SerializeMap for &'s mut Serializer<'r, 'a, E> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result<()> {
+        key.serialize(&mut **self)
+    }
+
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<()> {
+        value.serialize(&mut **self)
+    }
+
+    fn end(self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl<'s, 'r, 'a, E: Endianness> ser::SerializeStruct for &'s mut Serializer<'r, 'a, E> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        _key: &'static str,
+        value: &T,
+    ) -> Result<()> {
+        value.serialize(&mut **self)
+    }
+
+    fn end(self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl<'s, 'r, 'a, E: Endianness> ser::SerializeStructVariant for &'s mut Serializer<'r, 'a, E> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        _key: &'static str,
+        value: &T,
+    ) -> Result<()> {
+        value.serialize(&mut **self)
+    }
+
+    fn end(self) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// Serde deserializer backed by a [`BitReadStream`]
+pub struct Deserializer<'r, 'a, E: Endianness> {
+    stream: &'r mut BitReadStream<'a, E>,
+    config: Config,
+}
+
+impl<'r, 'a, E: Endianness> Deserializer<'r, 'a, E> {
+    /// Create a deserializer reading from the stream
+    pub fn new(stream: &'r mut BitReadStream<'a, E>, config: Config) -> Self {
+        Deserializer { stream, config }
+    }
+
+    fn read_length(&mut self, limit: ReadLimit) -> Result<usize> {
+        let length = self.stream.read_int(self.config.length_bits)?;
+        self.stream.check_limit(limit, length)?;
+        Ok(length)
+    }
+
+    fn read_unsigned<T: BitRead<'a, E> + TryFrom<u64>>(&mut self) -> Result<T> {
+        match self.config.int_encoding {
+            IntEncoding::Fixed => Ok(self.stream.read()?),
+            IntEncoding::Varint => {
+                let value = self.stream.read_varint_sqlite()?;
+                T::try_from(value).map_err(|_| {
+                    Error::Message(format!(
+                        "Varint {} doesn't fit in {}",
+                        value,
+                        type_name::<T>()
+                    ))
+                })
+            }
+        }
+    }
+
+    fn read_signed<T: BitRead<'a, E> + TryFrom<i64>>(&mut self) -> Result<T> {
+        match self.config.int_encoding {
+            IntEncoding::Fixed => Ok(self.stream.read()?),
+            IntEncoding::Varint => {
+                let raw = self.stream.read_varint_sqlite()?;
+                let value = (raw >> 1) as i64 ^ -((raw & 1) as i64);
+                T::try_from(value).map_err(|_| {
+                    Error::Message(format!(
+                        "Varint {} doesn't fit in {}",
+                        value,
+                        type_name::<T>()
+                    ))
+                })
+            }
+        }
+    }
+}
+
+macro_rules! impl_deserialize_primitive {
+    ($method:ident, $visit:ident, $type:ty) => {
+        fn $method<V: Visitor<'a>>(self, visitor: V) -> Result<V::Value> {
+            visitor.$visit(self.stream.read::<$type>()?)
+        }
+    };
+    ($method:ident, $visit:ident, $type:ty, $read:ident) => {
+        fn $method<V: Visitor<'a>>(self, visitor: V) -> Result<V::Value> {
+            visitor.$visit(self.$read::<$type>()?)
+        }
+    };
+}
+
+impl<'s, 'r, 'a, E: Endianness> de::Deserializer<'a> for &'s mut Deserializer<'r, 'a, E> {
+    type Error = Error;
+
+    fn deserialize_any<V: Visitor<'a>>(self, _visitor: V) -> Result<V::Value> {
+        Err(Error::Unsupported("Deserializing without type information"))
+    }
+
+    impl_deserialize_primitive!(deserialize_bool, visit_bool, bool);
+    impl_deserialize_primitive!(deserialize_i8, visit_i8, i8);
+    impl_deserialize_primitive!(deserialize_i16, visit_i16, i16, read_signed);
+    impl_deserialize_primitive!(deserialize_i32, visit_i32, i32, read_signed);
+    impl_deserialize_primitive!(deserialize_i64, visit_i64, i64, read_signed);
+    impl_deserialize_primitive!(deserialize_i128, visit_i128, i128);
+    impl_deserialize_primitive!(deserialize_u8, visit_u8, u8);
+    impl_deserialize_primitive!(deserialize_u16, visit_u16, u16, read_unsigned);
+    impl_deserialize_primitive!(deserialize_u32, visit_u32, u32, read_unsigned);
+    impl_deserialize_primitive!(deserialize_u64, visit_u64, u64, read_unsigned);
+    impl_deserialize_primitive!(deserialize_u128, visit_u128, u128);
+    impl_deserialize_primitive!(deserialize_f32, visit_f32, f32);
+    impl_deserialize_primitive!(deserialize_f64, visit_f64, f64);
+
+    fn deserialize_char<V: Visitor<'a>>(self, visitor: V) -> Result<V::Value> {
+        let code_point = self.stream.read::<u32>()?;
+        let c = std::char::from_u32(code_point).ok_or_else(|| {
+            Error::Message(format!("Invalid unicode code point {:#x}", code_point))
+        })?;
+        visitor.visit_char(c)
+    }
+
+    fn deserialize_str<V: Visitor<'a>>(self, visitor: V) -> Result<V::Value> {
+        let string = match self.config.string_encoding {
+            StringEncoding::NullTerminated => self.stream.read_string(None)?,
+            StringEncoding::LengthPrefixed => {
+                let length = self.read_length(ReadLimit::StringLength)?;
+                self.stream.read_string(Some(length))?
+            }
+        };
+        match string {
+            Cow::Borrowed(string) => visitor.visit_borrowed_str(string),
+            Cow::Owned(string) => visitor.visit_string(string),
+        }
+    }
+
+    fn deserialize_string<V: Visitor<'a>>(self, visitor: V) -> Result<V::Value> {
+        self.deserialize_str(visitor)
+    }
+
+    fn deserialize_bytes<V: Visitor<'a>>(self, visitor: V) -> Result<V::Value> {
+        let length = self.read_length(ReadLimit::Elements)?;
+        match self.stream.read_bytes(length)? {
+            Cow::Borrowed(bytes) => visitor.visit_borrowed_bytes(bytes),
+            Cow::Owned(bytes) => visitor.visit_byte_buf(bytes),
+        }
+    }
+
+    fn deserialize_byte_buf<V: Visitor<'a>>(self, visitor: V) -> Result<V::Value> {
+        self.deserialize_bytes(visitor)
+    }
+
+    fn deserialize_option<V: Visitor<'a>>(self, visitor: V) -> Result<V::Value> {
+        if self.stream.read_bool()? {
+            visitor.visit_some(self)
+        } else {
+            visitor.visit_none()
+        }
+    }
+
+    fn deserialize_unit<V: Visitor<'a>>(self, visitor: V) -> Result<V::Value> {
+        visitor.visit_unit()
+    }
+
+    fn deserialize_unit_struct<V: Visitor<'a>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value> {
+        visitor.visit_unit()
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'a>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_seq<V: Visitor<'a>>(self, visitor: V) -> Result<V::Value> {
+        let length = self.read_length(ReadLimit::Elements)?;
+        visitor.visit_seq(Access {
+            deserializer: self,
+            length,
+        })
+    }
+
+    fn deserialize_tuple<V: Visitor<'a>>(self, len: usize, visitor: V) -> Result<V::Value> {
+        visitor.visit_seq(Access {
+            deserializer: self,
+            length: len,
+        })
+    }
+
+    fn deserialize_tuple_struct<V: Visitor<'a>>(
+        self,
+        _name: &'static str,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value> {
+        self.deserialize_tuple(len, visitor)
+    }
+
+    fn deserialize_map<V: Visitor<'a>>(self, visitor: V) -> Result<V::Value> {
+        let length = self.read_length(ReadLimit::Elements)?;
+        visitor.visit_map(Access {
+            deserializer: self,
+            length,
+        })
+    }
+
+    fn deserialize_struct<V: Visitor<'a>>(
+        self,
+        _name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value> {
+        self.deserialize_tuple(fields.len(), visitor)
+    }
+
+    fn deserialize_enum<V: Visitor<'a>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value> {
+        visitor.visit_enum(self)
+    }
+
+    fn deserialize_identifier<V: Visitor<'a>>(self, _visitor: V) -> Result<V::Value> {
+        Err(Error::Unsupported("Deserializing identifiers"))
+    }
+
+    fn deserialize_ignored_any<V: Visitor<'a>>(self, _visitor: V) -> Result<V::Value> {
+        Err(Error::Unsupported("Skipping unknown data"))
+    }
+
+    fn is_human_readable(&self) -> bool {
+        false
+    }
+}
+
+struct Access<'s, 'r, 'a, E: Endianness> {
+    deserializer: &'s mut Deserializer<'r, 'a, E>,
+    length: usize,
+}
+
+impl<'s, 'r, 'a, E: Endianness> SeqAccess<'a> for Access<'s, 'r, 'a, E> {
+    type Error = Error;
+
+    fn next_element_seed<T: DeserializeSeed<'a>>(&mut self, seed: T) -> Result<Option<T::Value>> {
+        if self.length > 0 {
+            self.length -= 1;
+            seed.deserialize(&mut *self.deserializer).map(Some)
+        } else {
+            Ok(None)
+        }
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.length)
+    }
+}
+
+impl<'s, 'r, 'a, E: Endianness> MapAccess<'a> for Access<'s, 'r, 'a, E> {
+    type Error = Error;
+
+    fn next_key_seed<K: DeserializeSeed<'a>>(&mut self, seed: K) -> Result<Option<K::Value>> {
+        if self.length > 0 {
+            self.length -= 1;
+            seed.deserialize(&mut *self.deserializer).map(Some)
+        } else {
+            Ok(None)
+        }
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'a>>(&mut self, seed: V) -> Result<V::Value> {
+        seed.deserialize(&mut *self.deserializer)
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.length)
+    }
+}
+
+impl<'s, 'r, 'a, E: Endianness> EnumAccess<'a> for &'s mut Deserializer<'r, 'a, E> {
+    type Error = Error;
+    type Variant = Self;
+
+    fn variant_seed<V: DeserializeSeed<'a>>(self, seed: V) -> Result<(V::Value, Self)> {
+        let variant_index: u32 = self.stream.read_int(self.config.variant_bits)?;
+        let value =
+            seed.deserialize(IntoDeserializer::<Error>::into_deserializer(variant_index))?;
+        Ok((value, self))
+    }
+}
+
+impl<'s, 'r, 'a, E: Endianness> VariantAccess<'a> for &'s mut Deserializer<'r, 'a, E> {
+    type Error = Error;
+
+    fn unit_variant(self) -> Result<()> {
+        Ok(())
+    }
+
+    fn newtype_variant_seed<T: DeserializeSeed<'a>>(self, seed: T) -> Result<T::Value> {
+        seed.deserialize(self)
+    }
+
+    fn tuple_variant<V: Visitor<'a>>(self, len: usize, visitor: V) -> Result<V::Value> {
+        de::Deserializer::deserialize_tuple(self, len, visitor)
+    }
+
+    fn struct_variant<V: Visitor<'a>>(
+        self,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value> {
+        de::Deserializer::deserialize_tuple(self, fields.len(), visitor)
+    }
+}
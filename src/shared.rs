@@ -0,0 +1,90 @@
+use crate::{BitReadBuffer, BitReadStream, Endianness};
+use std::ops::Deref;
+use std::sync::Arc;
+
+/// An immutable, reference counted read buffer that can be shared between threads
+///
+/// All read buffers and streams are `Send` and `Sync` already, this type additionally guarantees that the buffer
+/// owns its data, so it can be stored in long-lived structs or handed to other threads without borrowing from anything.
+///
+/// Cloning the buffer or creating streams from it doesn't copy the data, no locking is needed
+/// since the data can't be modified.
+///
+/// The buffer derefs to [`BitReadBuffer`] so it can be read from directly.
+///
+/// # Examples
+///
+/// ```
+/// # use bitbuffer::Result;
+/// use bitbuffer::{LittleEndian, SharedBitReadBuffer};
+/// use std::thread;
+///
+/// # fn main() -> Result<()> {
+/// let buffer = SharedBitReadBuffer::new(vec![0x12, 0x34, 0x56, 0x78], LittleEndian);
+/// let handles: Vec<_> = (0..4)
+///     .map(|index| {
+///         let buffer = buffer.clone();
+///         thread::spawn(move || buffer.read_int::<u8>(index * 8, 8))
+///     })
+///     .collect();
+/// let bytes = handles
+///     .into_iter()
+///     .map(|handle| handle.join().unwrap())
+///     .collect::<Result<Vec<u8>>>()?;
+/// assert_eq!(bytes, [0x12, 0x34, 0x56, 0x78]);
+///
+/// let mut stream = buffer.stream();
+/// assert_eq!(stream.read::<u16>()?, 0x3412);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, PartialEq)]
+pub struct SharedBitReadBuffer<E: Endianness> {
+    buffer: BitReadBuffer<'static, E>,
+}
+
+impl<E: Endianness> SharedBitReadBuffer<E> {
+    /// Create a shared buffer from owned bytes
+    ///
+    /// Bytes that are already in an `Arc<[u8]>` are shared without copying.
+    pub fn new<B: Into<Arc<[u8]>>>(bytes: B, endianness: E) -> Self {
+        SharedBitReadBuffer {
+            buffer: BitReadBuffer::from_arc(bytes.into(), endianness),
+        }
+    }
+
+    /// Create a stream reading the full buffer
+    pub fn stream(&self) -> BitReadStream<'static, E> {
+        BitReadStream::new(self.buffer.clone())
+    }
+
+    /// Get the underlying read buffer
+    pub fn into_inner(self) -> BitReadBuffer<'static, E> {
+        self.buffer
+    }
+}
+
+impl<E: Endianness> Clone for SharedBitReadBuffer<E> {
+    fn clone(&self) -> Self {
+        SharedBitReadBuffer {
+            buffer: self.buffer.clone(),
+        }
+    }
+}
+
+impl<E: Endianness> Deref for SharedBitReadBuffer<E> {
+    type Target = BitReadBuffer<'static, E>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.buffer
+    }
+}
+
+/// Copies the data if the buffer borrows it, owned data is shared without copying
+impl<E: Endianness> From<BitReadBuffer<'_, E>> for SharedBitReadBuffer<E> {
+    fn from(buffer: BitReadBuffer<'_, E>) -> Self {
+        SharedBitReadBuffer {
+            buffer: buffer.to_owned(),
+        }
+    }
+}
@@ -0,0 +1,291 @@
+//! Float encodings used by the Source engine network protocol and demo files
+//!
+//! - [`read_bit_coord`] and [`write_bit_coord`] handle world coordinates with presence flags for the integer and
+//!   fractional part, a sign bit, a 14 bit integer and a 5 bit fraction.
+//! - [`read_bit_coord_mp`] and [`write_bit_coord_mp`] handle the multiplayer optimized variant that uses fewer
+//!   bits for coordinates within the playable area, with optional integral or low precision encoding.
+//! - [`read_bit_normal`] and [`write_bit_normal`] handle normal vector components as a sign bit and an 11 bit fraction.
+//!
+//! The wrapper types [`BitCoord`], [`BitCoordMp`] and [`BitNormal`] implement [`BitRead`] and [`BitWrite`]
+//! so they can be used as fields in derived structs.
+//!
+//! # Examples
+//!
+//! ```
+//! # use bitbuffer::Result;
+//! use bitbuffer::source::{BitCoord, BitNormal};
+//! use bitbuffer::{BitRead, BitReadBuffer, BitReadStream, BitWrite, BitWriteStream, LittleEndian};
+//!
+//! #[derive(BitRead, BitWrite, Debug, PartialEq)]
+//! struct Impact {
+//!     origin: [BitCoord; 3],
+//!     normal: [BitNormal; 3],
+//! }
+//!
+//! # fn main() -> Result<()> {
+//! let impact = Impact {
+//!     origin: [BitCoord(-128.5), BitCoord(0.0), BitCoord(1024.25)],
+//!     normal: [BitNormal(0.0), BitNormal(0.0), BitNormal(1.0)],
+//! };
+//! let mut data = Vec::new();
+//! let mut stream = BitWriteStream::new(&mut data, LittleEndian);
+//! stream.write(&impact)?;
+//!
+//! let mut stream = BitReadStream::new(BitReadBuffer::new(&data, LittleEndian));
+//! assert_eq!(stream.read::<Impact>()?, impact);
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::{BitRead, BitReadStream, BitWrite, BitWriteStream, Endianness, Result};
+
+const COORD_INTEGER_BITS: usize = 14;
+const COORD_INTEGER_BITS_MP: usize = 11;
+const COORD_FRACTIONAL_BITS: usize = 5;
+const COORD_DENOMINATOR: u32 = 1 << COORD_FRACTIONAL_BITS;
+const COORD_FRACTIONAL_BITS_LOW_PRECISION: usize = 3;
+const COORD_DENOMINATOR_LOW_PRECISION: u32 = 1 << COORD_FRACTIONAL_BITS_LOW_PRECISION;
+const NORMAL_FRACTIONAL_BITS: usize = 11;
+const NORMAL_DENOMINATOR: u32 = (1 << NORMAL_FRACTIONAL_BITS) - 1;
+
+/// Read a coordinate as encoded by `CBitRead::ReadBitCoord`
+///
+/// Coordinates have a resolution of 1/32 and a range of ±16384.
+pub fn read_bit_coord<E: Endianness>(stream: &mut BitReadStream<E>) -> Result<f32> {
+    let has_int = stream.read_bool()?;
+    let has_fract = stream.read_bool()?;
+    if !has_int && !has_fract {
+        return Ok(0.0);
+    }
+    let negative = stream.read_bool()?;
+    let int = if has_int {
+        stream.read_int::<u32>(COORD_INTEGER_BITS)? + 1
+    } else {
+        0
+    };
+    let fract = if has_fract {
+        stream.read_int::<u32>(COORD_FRACTIONAL_BITS)?
+    } else {
+        0
+    };
+    let value = int as f32 + fract as f32 / COORD_DENOMINATOR as f32;
+    Ok(if negative { -value } else { value })
+}
+
+/// Write a coordinate as encoded by `CBitWrite::WriteBitCoord`
+///
+/// The value is truncated to a multiple of 1/32, values outside of ±16384 can't be read back correctly.
+pub fn write_bit_coord<E: Endianness>(stream: &mut BitWriteStream<E>, value: f32) -> Result<()> {
+    let negative = value <= -1.0 / COORD_DENOMINATOR as f32;
+    let int = value.abs() as u32;
+    let fract = (value * COORD_DENOMINATOR as f32).abs() as u32 & (COORD_DENOMINATOR - 1);
+    stream.write_bool(int != 0)?;
+    stream.write_bool(fract != 0)?;
+    if int != 0 || fract != 0 {
+        stream.write_bool(negative)?;
+        if int != 0 {
+            stream.write_int(int - 1, COORD_INTEGER_BITS)?;
+        }
+        if fract != 0 {
+            stream.write_int(fract, COORD_FRACTIONAL_BITS)?;
+        }
+    }
+    Ok(())
+}
+
+/// The precision of a coordinate encoded with [`read_bit_coord_mp`] or [`write_bit_coord_mp`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum CoordPrecision {
+    /// A 5 bit fraction, with a resolution of 1/32
+    #[default]
+    Full,
+    /// A 3 bit fraction, with a resolution of 1/8
+    Low,
+    /// Whole numbers only
+    Integral,
+}
+
+impl CoordPrecision {
+    fn fractional_bits(self) -> usize {
+        match self {
+            CoordPrecision::Low => COORD_FRACTIONAL_BITS_LOW_PRECISION,
+            _ => COORD_FRACTIONAL_BITS,
+        }
+    }
+
+    fn denominator(self) -> u32 {
+        match self {
+            CoordPrecision::Low => COORD_DENOMINATOR_LOW_PRECISION,
+            _ => COORD_DENOMINATOR,
+        }
+    }
+}
+
+/// Read a coordinate as encoded by `CBitRead::ReadBitCoordMP`
+///
+/// Coordinates within ±2048 use an 11 bit integer part, other coordinates use 14 bits.
+pub fn read_bit_coord_mp<E: Endianness>(
+    stream: &mut BitReadStream<E>,
+    precision: CoordPrecision,
+) -> Result<f32> {
+    let in_bounds = stream.read_bool()?;
+    let int_bits = if in_bounds {
+        COORD_INTEGER_BITS_MP
+    } else {
+        COORD_INTEGER_BITS
+    };
+    let has_int = stream.read_bool()?;
+    let (negative, value) = if precision == CoordPrecision::Integral {
+        if has_int {
+            let negative = stream.read_bool()?;
+            (negative, (stream.read_int::<u32>(int_bits)? + 1) as f32)
+        } else {
+            (false, 0.0)
+        }
+    } else {
+        let negative = stream.read_bool()?;
+        let int = if has_int {
+            stream.read_int::<u32>(int_bits)? + 1
+        } else {
+            0
+        };
+        let fract = stream.read_int::<u32>(precision.fractional_bits())?;
+        (
+            negative,
+            int as f32 + fract as f32 / precision.denominator() as f32,
+        )
+    };
+    Ok(if negative { -value } else { value })
+}
+
+/// Write a coordinate as encoded by `CBitWrite::WriteBitCoordMP`
+///
+/// The value is truncated to the resolution of the precision, values outside of ±16384 can't be read back correctly.
+pub fn write_bit_coord_mp<E: Endianness>(
+    stream: &mut BitWriteStream<E>,
+    value: f32,
+    precision: CoordPrecision,
+) -> Result<()> {
+    let denominator = precision.denominator();
+    let negative = value <= -1.0 / denominator as f32;
+    let int = value.abs() as u32;
+    let fract = (value * denominator as f32).abs() as u32 & (denominator - 1);
+    let in_bounds = int < (1 << COORD_INTEGER_BITS_MP);
+    let int_bits = if in_bounds {
+        COORD_INTEGER_BITS_MP
+    } else {
+        COORD_INTEGER_BITS
+    };
+    stream.write_bool(in_bounds)?;
+    stream.write_bool(int != 0)?;
+    if precision == CoordPrecision::Integral {
+        if int != 0 {
+            stream.write_bool(negative)?;
+            stream.write_int(int - 1, int_bits)?;
+        }
+    } else {
+        stream.write_bool(negative)?;
+        if int != 0 {
+            stream.write_int(int - 1, int_bits)?;
+        }
+        stream.write_int(fract, precision.fractional_bits())?;
+    }
+    Ok(())
+}
+
+/// Read a normal vector component as encoded by `CBitRead::ReadBitNormal`
+///
+/// Normals have a range of ±1 with a resolution of 1/2047.
+pub fn read_bit_normal<E: Endianness>(stream: &mut BitReadStream<E>) -> Result<f32> {
+    let negative = stream.read_bool()?;
+    let fract = stream.read_int::<u32>(NORMAL_FRACTIONAL_BITS)?;
+    let value = fract as f32 / NORMAL_DENOMINATOR as f32;
+    Ok(if negative { -value } else { value })
+}
+
+/// Write a normal vector component as encoded by `CBitWrite::WriteBitNormal`
+///
+/// The value is truncated to a multiple of 1/2047 and clamped to ±1.
+pub fn write_bit_normal<E: Endianness>(stream: &mut BitWriteStream<E>, value: f32) -> Result<()> {
+    let negative = value <= -1.0 / NORMAL_DENOMINATOR as f32;
+    let fract = ((value * NORMAL_DENOMINATOR as f32).abs() as u32).min(NORMAL_DENOMINATOR);
+    stream.write_bool(negative)?;
+    stream.write_int(fract, NORMAL_FRACTIONAL_BITS)
+}
+
+/// A coordinate encoded with [`read_bit_coord`] and [`write_bit_coord`]
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct BitCoord(pub f32);
+
+impl<'a, E: Endianness> BitRead<'a, E> for BitCoord {
+    fn read(stream: &mut BitReadStream<'a, E>) -> Result<Self> {
+        read_bit_coord(stream).map(BitCoord)
+    }
+}
+
+impl<E: Endianness> BitWrite<E> for BitCoord {
+    fn write(&self, stream: &mut BitWriteStream<E>) -> Result<()> {
+        write_bit_coord(stream, self.0)
+    }
+}
+
+/// A coordinate encoded with [`read_bit_coord_mp`] and [`write_bit_coord_mp`]
+///
+/// `INTEGRAL` and `LOW_PRECISION` select the [`CoordPrecision`], with `INTEGRAL` taking precedence.
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct BitCoordMp<const INTEGRAL: bool = false, const LOW_PRECISION: bool = false>(pub f32);
+
+impl<const INTEGRAL: bool, const LOW_PRECISION: bool> BitCoordMp<INTEGRAL, LOW_PRECISION> {
+    /// The precision used to encode the coordinate
+    pub const PRECISION: CoordPrecision = if INTEGRAL {
+        CoordPrecision::Integral
+    } else if LOW_PRECISION {
+        CoordPrecision::Low
+    } else {
+        CoordPrecision::Full
+    };
+}
+
+impl<'a, E: Endianness, const INTEGRAL: bool, const LOW_PRECISION: bool> BitRead<'a, E>
+    for BitCoordMp<INTEGRAL, LOW_PRECISION>
+{
+    fn read(stream: &mut BitReadStream<'a, E>) -> Result<Self> {
+        read_bit_coord_mp(stream, Self::PRECISION).map(BitCoordMp)
+    }
+}
+
+impl<E: Endianness, const INTEGRAL: bool, const LOW_PRECISION: bool> BitWrite<E>
+    for BitCoordMp<INTEGRAL, LOW_PRECISION>
+{
+    fn write(&self, stream: &mut BitWriteStream<E>) -> Result<()> {
+        write_bit_coord_mp(stream, self.0, Self::PRECISION)
+    }
+}
+
+/// A normal vector component encoded with [`read_bit_normal`] and [`write_bit_normal`]
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct BitNormal(pub f32);
+
+impl<'a, E: Endianness> BitRead<'a, E> for BitNormal {
+    fn read(stream: &mut BitReadStream<'a, E>) -> Result<Self> {
+        read_bit_normal(stream).map(BitNormal)
+    }
+
+    fn bit_size() -> Option<usize> {
+        Some(1 + NORMAL_FRACTIONAL_BITS)
+    }
+}
+
+impl<E: Endianness> BitWrite<E> for BitNormal {
+    fn write(&self, stream: &mut BitWriteStream<E>) -> Result<()> {
+        write_bit_normal(stream, self.0)
+    }
+
+    fn bit_size_hint() -> Option<usize> {
+        Some(1 + NORMAL_FRACTIONAL_BITS)
+    }
+}
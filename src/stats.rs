@@ -0,0 +1,211 @@
+//! Per type read statistics
+//!
+//! When the `stats` feature is enabled, every [`BitReadStream::read`] and [`BitReadStream::read_sized`] call made
+//! inside [`collect`] is counted by the type that is read and the location of the call.
+//! For derived implementations the location is the `#[derive]` of the containing type.
+//!
+//! The recorded time for a type includes the time spent reading the fields of that type,
+//! so the statistics for a top level type cover the entire parse.
+//!
+//! Statistics are collected per thread, reads on other threads are not recorded.
+//!
+//! On `wasm32-unknown-unknown` there is no clock available, reads are counted but not timed.
+//!
+//! # Examples
+//!
+//! ```
+//! # use bitbuffer::Result;
+//! use bitbuffer::stats::collect;
+//! use bitbuffer::{BitRead, BitReadBuffer, BitReadStream, LittleEndian};
+//!
+//! #[derive(BitRead)]
+//! struct Packet {
+//!     id: u8,
+//!     #[size = 2]
+//!     values: Vec<u16>,
+//! }
+//!
+//! # fn main() -> Result<()> {
+//! let bytes = [0; 10];
+//! let (packets, stats) = collect(|| {
+//!     let mut stream = BitReadStream::new(BitReadBuffer::new(&bytes, LittleEndian));
+//!     stream.read_sized::<Vec<Packet>>(2)
+//! });
+//! assert_eq!(packets?.len(), 2);
+//!
+//! let values = stats.get::<u16>();
+//! assert_eq!(values.reads, 4);
+//! assert_eq!(values.bits, 64);
+//! assert_eq!(stats.get::<Packet>().reads, 2);
+//! println!("{}", stats);
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! [`BitReadStream::read`]: crate::BitReadStream::read
+//! [`BitReadStream::read_sized`]: crate::BitReadStream::read_sized
+
+use std::any::type_name;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fmt::{self, Display, Formatter};
+use std::ops::AddAssign;
+use std::panic::Location;
+use std::time::Duration;
+#[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+use std::time::Instant;
+
+thread_local! {
+    static COLLECTOR: RefCell<Option<ReadStats>> = const { RefCell::new(None) };
+}
+
+/// The statistics for a type or call site
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TypeStats {
+    /// The number of reads, including failed reads
+    pub reads: u64,
+    /// The total number of bits read by successful reads
+    pub bits: u64,
+    /// The total time spent reading, always zero on `wasm32-unknown-unknown`
+    pub time: Duration,
+}
+
+impl AddAssign for TypeStats {
+    fn add_assign(&mut self, rhs: Self) {
+        self.reads += rhs.reads;
+        self.bits += rhs.bits;
+        self.time += rhs.time;
+    }
+}
+
+/// A type that has been read at a specific location
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ReadSite {
+    /// The name of the read type, as given by [`std::any::type_name`]
+    pub type_name: &'static str,
+    /// The location of the `read` or `read_sized` call
+    pub location: &'static Location<'static>,
+}
+
+/// Statistics collected by [`collect`]
+#[derive(Debug, Clone, Default)]
+pub struct ReadStats {
+    sites: HashMap<ReadSite, TypeStats>,
+}
+
+impl ReadStats {
+    /// The statistics for every call site
+    pub fn sites(&self) -> impl Iterator<Item = (&ReadSite, &TypeStats)> {
+        self.sites.iter()
+    }
+
+    /// The statistics combined by type, sorted by the time spent reading the type
+    pub fn by_type(&self) -> Vec<(&'static str, TypeStats)> {
+        let mut types: HashMap<&'static str, TypeStats> = HashMap::new();
+        for (site, stats) in &self.sites {
+            *types.entry(site.type_name).or_default() += *stats;
+        }
+        let mut types: Vec<_> = types.into_iter().collect();
+        types.sort_by(|a, b| b.1.time.cmp(&a.1.time).then(a.0.cmp(b.0)));
+        types
+    }
+
+    /// The combined statistics for all reads of `T`
+    pub fn get<T: ?Sized>(&self) -> TypeStats {
+        let name = type_name::<T>();
+        let mut result = TypeStats::default();
+        for (_, stats) in self.sites.iter().filter(|(site, _)| site.type_name == name) {
+            result += *stats;
+        }
+        result
+    }
+}
+
+/// Print a table of the statistics by type
+impl Display for ReadStats {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{:>12} {:>14} {:>12}  type", "reads", "bits", "time")?;
+        for (name, stats) in self.by_type() {
+            writeln!(
+                f,
+                "{:>12} {:>14} {:>12}  {}",
+                stats.reads,
+                stats.bits,
+                format!("{:.3?}", stats.time),
+                name
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Run `f` while collecting read statistics for the current thread
+///
+/// Nested calls collect separately, reads inside a nested call are not included in the outer statistics.
+pub fn collect<R, F: FnOnce() -> R>(f: F) -> (R, ReadStats) {
+    let outer = COLLECTOR.with(|collector| collector.replace(Some(ReadStats::default())));
+    let result = f();
+    let stats = COLLECTOR.with(|collector| collector.replace(outer));
+    (result, stats.unwrap_or_default())
+}
+
+/// The start time of a read
+///
+/// `Instant::now` panics on `wasm32-unknown-unknown`, so reads aren't timed there.
+#[derive(Clone, Copy)]
+pub(crate) struct Timer {
+    #[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+    start: Instant,
+}
+
+impl Timer {
+    fn now() -> Self {
+        Timer {
+            #[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+            start: Instant::now(),
+        }
+    }
+
+    #[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+    fn elapsed(&self) -> Duration {
+        self.start.elapsed()
+    }
+
+    #[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+    fn elapsed(&self) -> Duration {
+        Duration::ZERO
+    }
+}
+
+/// Start timing a read, returns `None` if no statistics are being collected
+#[inline]
+pub(crate) fn start() -> Option<Timer> {
+    COLLECTOR.with(|collector| collector.borrow().is_some().then(Timer::now))
+}
+
+/// Record a read started with [`start`]
+#[inline]
+pub(crate) fn record<T: ?Sized>(
+    start: Option<Timer>,
+    location: &'static Location<'static>,
+    bits: Option<usize>,
+) {
+    if let Some(start) = start {
+        let time = start.elapsed();
+        COLLECTOR.with(|collector| {
+            if let Some(stats) = collector.borrow_mut().as_mut() {
+                *stats
+                    .sites
+                    .entry(ReadSite {
+                        type_name: type_name::<T>(),
+                        location,
+                    })
+                    .or_default() += TypeStats {
+                    reads: 1,
+                    bits: bits.unwrap_or_default() as u64,
+                    time,
+                };
+            }
+        });
+    }
+}
@@ -0,0 +1,286 @@
+//! Bit stuffing and HDLC style framing
+//!
+//! Bit stuffing inserts an extra bit after a run of identical bits, to prevent the data from containing
+//! frame delimiters (HDLC, AX.25) or to guarantee enough transitions for clock recovery (CAN).
+//!
+//! # Examples
+//!
+//! ```
+//! # use bitbuffer::Result;
+//! use bitbuffer::stuffing::{read_hdlc_frame, write_hdlc_frame};
+//! use bitbuffer::{BitReadBuffer, BitReadStream, BitWriteStream, LittleEndian};
+//!
+//! # fn main() -> Result<()> {
+//! let payload = [0xFF, 0x7E, 0x12];
+//! let payload = BitReadStream::new(BitReadBuffer::new(&payload, LittleEndian));
+//!
+//! let mut data = Vec::new();
+//! let mut stream = BitWriteStream::new(&mut data, LittleEndian);
+//! write_hdlc_frame(&mut stream, &payload)?;
+//!
+//! let mut stream = BitReadStream::new(BitReadBuffer::new(&data, LittleEndian));
+//! let mut frame = read_hdlc_frame(&mut stream)?;
+//! assert_eq!(frame.read_bytes(3)?.as_ref(), &[0xFF, 0x7E, 0x12]);
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::{BitError, BitReadBuffer, BitReadStream, BitWriteStream, Endianness, Result};
+use std::io::SeekFrom;
+
+/// The HDLC frame delimiter
+pub const HDLC_FLAG: u8 = 0b0111_1110;
+
+/// Rules for inserting stuffed bits
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BitStuffing {
+    /// The number of identical bits after which a stuffed bit is inserted
+    pub run_length: usize,
+    /// Whether runs of zeros are also stuffed, otherwise only runs of ones are
+    pub stuff_zeros: bool,
+}
+
+impl BitStuffing {
+    /// Stuffing as used by HDLC and AX.25, a 0 is inserted after five consecutive 1s
+    pub const HDLC: BitStuffing = BitStuffing {
+        run_length: 5,
+        stuff_zeros: false,
+    };
+
+    /// Stuffing as used by CAN, the complement bit is inserted after five identical bits
+    pub const CAN: BitStuffing = BitStuffing {
+        run_length: 5,
+        stuff_zeros: true,
+    };
+
+    /// Write all remaining bits from the input to the output, inserting stuffed bits
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use bitbuffer::Result;
+    /// use bitbuffer::stuffing::BitStuffing;
+    /// use bitbuffer::{BitReadBuffer, BitReadStream, BitWriteStream, BigEndian};
+    ///
+    /// # fn main() -> Result<()> {
+    /// let input = [0b1111_1111];
+    /// let input = BitReadStream::new(BitReadBuffer::new(&input, BigEndian));
+    ///
+    /// let mut data = Vec::new();
+    /// let mut stream = BitWriteStream::new(&mut data, BigEndian);
+    /// BitStuffing::HDLC.stuff(&input, &mut stream)?;
+    /// assert_eq!(stream.bit_len(), 9);
+    /// assert_eq!(data, [0b1111_1011, 0b1000_0000]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn stuff<E: Endianness>(
+        &self,
+        input: &BitReadStream<E>,
+        output: &mut BitWriteStream<E>,
+    ) -> Result<()> {
+        let mut input = input.clone();
+        let mut run = RunTracker::new(*self);
+        while input.bits_left() > 0 {
+            let bit = input.read_bool()?;
+            output.write_bool(bit)?;
+            if let Some(stuffed) = run.push(bit) {
+                output.write_bool(stuffed)?;
+                run.push(stuffed);
+            }
+        }
+        Ok(())
+    }
+
+    /// Read stuffed bits from the input until `count` bits of data have been read, removing the stuffed bits
+    ///
+    /// The input is only advanced if the data could be read successfully.
+    ///
+    /// # Errors
+    ///
+    /// - [`ReadError::NotEnoughData`]: not enough bits available in the stream
+    /// - [`ReadError::InvalidBitStuffing`]: a run of identical bits wasn't followed by a stuffed bit
+    ///
+    /// [`ReadError::NotEnoughData`]: enum.ReadError.html#variant.NotEnoughData
+    /// [`ReadError::InvalidBitStuffing`]: enum.ReadError.html#variant.InvalidBitStuffing
+    pub fn unstuff<E: Endianness>(
+        &self,
+        input: &mut BitReadStream<E>,
+        count: usize,
+    ) -> Result<BitReadStream<'static, E>> {
+        let mut raw = input.clone();
+        let mut bits = Vec::with_capacity(count);
+        let mut run = RunTracker::new(*self);
+        while bits.len() < count {
+            let bit = raw.read_bool().map_err(|_| BitError::NotEnoughData {
+                requested: count,
+                bits_left: bits.len(),
+            })?;
+            bits.push(bit);
+            if let Some(expected) = run.push(bit) {
+                // don't require a stuffed bit after the last data bit
+                if bits.len() == count && raw.bits_left() == 0 {
+                    break;
+                }
+                let pos = input.pos() + raw.pos();
+                if raw.read_bool()? != expected {
+                    return Err(BitError::InvalidBitStuffing { pos });
+                }
+                run.push(expected);
+            }
+        }
+        input.skip_bits(raw.pos())?;
+        collect_bits(&bits)
+    }
+}
+
+/// Tracks runs of identical bits to determine when a bit needs to be stuffed
+struct RunTracker {
+    rules: BitStuffing,
+    last: Option<bool>,
+    run: usize,
+}
+
+impl RunTracker {
+    fn new(rules: BitStuffing) -> Self {
+        RunTracker {
+            rules,
+            last: None,
+            run: 0,
+        }
+    }
+
+    /// Add a bit to the run, returning the bit that needs to be stuffed after it, if any
+    fn push(&mut self, bit: bool) -> Option<bool> {
+        if self.last == Some(bit) {
+            self.run += 1;
+        } else {
+            self.last = Some(bit);
+            self.run = 1;
+        }
+        if self.run == self.rules.run_length && (bit || self.rules.stuff_zeros) {
+            Some(!bit)
+        } else {
+            None
+        }
+    }
+}
+
+fn collect_bits<E: Endianness>(bits: &[bool]) -> Result<BitReadStream<'static, E>> {
+    let mut data = Vec::with_capacity(bits.len().div_ceil(8));
+    let mut stream = BitWriteStream::new(&mut data, E::endianness());
+    for bit in bits {
+        stream.write_bool(*bit)?;
+    }
+    let mut stream = BitReadStream::new(BitReadBuffer::new_owned(data, E::endianness()));
+    stream.read_bits(bits.len())
+}
+
+/// Advance the stream until after the next HDLC flag
+///
+/// # Errors
+///
+/// - [`ReadError::NotEnoughData`]: no flag found in the remaining data, the stream is not advanced
+///
+/// [`ReadError::NotEnoughData`]: enum.ReadError.html#variant.NotEnoughData
+pub fn skip_to_flag<E: Endianness>(stream: &mut BitReadStream<E>) -> Result<()> {
+    let start = stream.pos();
+    let mut window = 0u8;
+    let mut seen = 0;
+    while stream.bits_left() > 0 {
+        window = window << 1 | stream.read_bool()? as u8;
+        seen += 1;
+        if seen >= 8 && window == HDLC_FLAG {
+            return Ok(());
+        }
+    }
+    let bits_left = stream.pos() - start;
+    stream.set_pos(start)?;
+    Err(BitError::NotEnoughData {
+        requested: bits_left + 1,
+        bits_left,
+    })
+}
+
+/// Write a HDLC frame, the stuffed payload surrounded by flags
+pub fn write_hdlc_frame<E: Endianness>(
+    stream: &mut BitWriteStream<E>,
+    payload: &BitReadStream<E>,
+) -> Result<()> {
+    write_flag(stream)?;
+    BitStuffing::HDLC.stuff(payload, stream)?;
+    write_flag(stream)
+}
+
+fn write_flag<E: Endianness>(stream: &mut BitWriteStream<E>) -> Result<()> {
+    // the flag is symmetric, so it is the same regardless of bit order
+    stream.write_int(HDLC_FLAG, 8)
+}
+
+/// Read the next HDLC frame from the stream
+///
+/// Any data before the opening flag and any repeated flags are skipped,
+/// the closing flag isn't consumed, since it can also serve as the opening flag of the next frame.
+///
+/// # Errors
+///
+/// - [`ReadError::NotEnoughData`]: no complete frame found in the remaining data
+/// - [`ReadError::InvalidBitStuffing`]: an abort sequence of seven or more 1s was found in the frame
+///
+/// [`ReadError::NotEnoughData`]: enum.ReadError.html#variant.NotEnoughData
+/// [`ReadError::InvalidBitStuffing`]: enum.ReadError.html#variant.InvalidBitStuffing
+pub fn read_hdlc_frame<E: Endianness>(
+    stream: &mut BitReadStream<E>,
+) -> Result<BitReadStream<'static, E>> {
+    let start = stream.pos();
+    let result = read_frame_inner(stream);
+    if result.is_err() {
+        stream.set_pos(start)?;
+    }
+    result
+}
+
+fn read_frame_inner<E: Endianness>(
+    stream: &mut BitReadStream<E>,
+) -> Result<BitReadStream<'static, E>> {
+    skip_to_flag(stream)?;
+    // skip repeated flags
+    while stream.bits_left() >= 8 {
+        let mut peek = stream.clone();
+        if peek.read_int::<u8>(8)? == HDLC_FLAG {
+            stream.skip_bits(8)?;
+        } else {
+            break;
+        }
+    }
+
+    let mut bits = Vec::new();
+    let mut ones = 0;
+    loop {
+        let bit = stream.read_bool()?;
+        if bit {
+            ones += 1;
+            continue;
+        }
+        match ones {
+            0..=4 => {
+                bits.extend(std::iter::repeat_n(true, ones));
+                bits.push(false);
+            }
+            // stuffed bit
+            5 => bits.extend(std::iter::repeat_n(true, ones)),
+            6 => {
+                // closing flag, the leading 0 of the flag has already been added to the frame
+                bits.pop();
+                stream.seek(SeekFrom::Current(-8))?;
+                return collect_bits(&bits);
+            }
+            _ => {
+                return Err(BitError::InvalidBitStuffing {
+                    pos: stream.pos() - 1,
+                })
+            }
+        }
+        ones = 0;
+    }
+}
@@ -0,0 +1,502 @@
+use crate::{
+    BitError, BitRead, BitReadBuffer, BitReadSized, BitReadStream, BitWrite, BitWriteSized,
+    BitWriteStream, Endianness,
+};
+use std::borrow::Cow;
+use std::fmt::{self, Debug, Display, Formatter};
+use std::panic::{self, AssertUnwindSafe};
+
+/// Errors returned when a value fails to round trip
+#[derive(Debug)]
+pub enum RoundtripError {
+    /// Writing the value failed
+    Write {
+        /// The debug representation of the value
+        value: String,
+        /// The error returned while writing
+        error: BitError,
+    },
+    /// Reading back the written value failed
+    Read {
+        /// The debug representation of the value
+        value: String,
+        /// The number of bits that were written
+        written_bits: usize,
+        /// The error returned while reading
+        error: BitError,
+    },
+    /// The read value is different from the written value
+    Mismatch {
+        /// The debug representation of the written value
+        expected: String,
+        /// The debug representation of the read value
+        actual: String,
+        /// The first bit at which the encoding of the written value differs from the encoding of the read value
+        ///
+        /// `None` if both values have the same encoding
+        first_difference: Option<usize>,
+    },
+    /// Reading the value consumed a different number of bits than were written
+    LengthMismatch {
+        /// The debug representation of the value
+        value: String,
+        /// The number of bits that were written
+        written_bits: usize,
+        /// The number of bits that were read
+        read_bits: usize,
+    },
+}
+
+impl Display for RoundtripError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            RoundtripError::Write { value, error } => {
+                write!(f, "Failed to write value {}: {}", value, error)
+            }
+            RoundtripError::Read {
+                value,
+                written_bits,
+                error,
+            } => write!(
+                f,
+                "Failed to read back value {} from {} written bits: {}",
+                value, written_bits, error
+            ),
+            RoundtripError::Mismatch {
+                expected,
+                actual,
+                first_difference,
+            } => {
+                write!(
+                    f,
+                    "Read value {} differs from written value {}",
+                    actual, expected
+                )?;
+                if let Some(bit) = first_difference {
+                    write!(f, ", encodings first differ at bit {}", bit)?;
+                }
+                Ok(())
+            }
+            RoundtripError::LengthMismatch {
+                value,
+                written_bits,
+                read_bits,
+            } => write!(
+                f,
+                "Read {} bits for value {} while {} bits were written",
+                read_bits, value, written_bits
+            ),
+        }
+    }
+}
+
+impl std::error::Error for RoundtripError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            RoundtripError::Write { error, .. } | RoundtripError::Read { error, .. } => Some(error),
+            _ => None,
+        }
+    }
+}
+
+/// Write a value, read it back and check that the same value is read
+///
+/// Returns a [`RoundtripError`] describing what went wrong if the value doesn't survive the
+/// round trip, including the first bit where the encodings differ when a different value is read.
+///
+/// # Examples
+///
+/// ```
+/// use bitbuffer::{test_roundtrip, BitRead, BitWrite, BigEndian};
+///
+/// #[derive(BitRead, BitWrite, Debug, PartialEq)]
+/// struct Packet {
+///     #[size = 3]
+///     kind: u8,
+///     name: String,
+/// }
+///
+/// test_roundtrip(&Packet { kind: 5, name: "foo".into() }, BigEndian).unwrap();
+///
+/// // 9 doesn't fit in 3 bits
+/// let error = test_roundtrip(&Packet { kind: 9, name: "foo".into() }, BigEndian).unwrap_err();
+/// assert_eq!(
+///     error.to_string(),
+///     r#"Read value Packet { kind: 1, name: "foo" } differs from written value Packet { kind: 9, name: "foo" }"#
+/// );
+/// ```
+pub fn test_roundtrip<T, E>(value: &T, endianness: E) -> Result<(), RoundtripError>
+where
+    T: for<'a> BitRead<'a, E> + BitWrite<E> + Debug + PartialEq,
+    E: Endianness,
+{
+    roundtrip_with(
+        value,
+        endianness,
+        |stream, value| stream.write(value),
+        |stream| stream.read(),
+    )
+}
+
+/// Write a value with a fixed size, read it back and check that the same value is read
+///
+/// See [`test_roundtrip`] for details.
+///
+/// # Examples
+///
+/// ```
+/// use bitbuffer::{test_roundtrip_sized, LittleEndian};
+///
+/// test_roundtrip_sized(&String::from("foo"), 3, LittleEndian).unwrap();
+/// test_roundtrip_sized(&-3i32, 5, LittleEndian).unwrap();
+/// ```
+pub fn test_roundtrip_sized<T, E>(
+    value: &T,
+    size: usize,
+    endianness: E,
+) -> Result<(), RoundtripError>
+where
+    T: for<'a> BitReadSized<'a, E> + BitWriteSized<E> + Debug + PartialEq,
+    E: Endianness,
+{
+    roundtrip_with(
+        value,
+        endianness,
+        |stream, value| stream.write_sized(value, size),
+        |stream| stream.read_sized(size),
+    )
+}
+
+fn roundtrip_with<T, E, W, R>(
+    value: &T,
+    endianness: E,
+    write: W,
+    read: R,
+) -> Result<(), RoundtripError>
+where
+    T: Debug + PartialEq,
+    E: Endianness,
+    W: Fn(&mut BitWriteStream<E>, &T) -> crate::Result<()>,
+    R: for<'a> Fn(&mut BitReadStream<'a, E>) -> crate::Result<T>,
+{
+    let encode = |value: &T| -> Result<(Vec<u8>, usize), RoundtripError> {
+        let mut data = Vec::new();
+        let mut stream = BitWriteStream::new(&mut data, E::endianness());
+        write(&mut stream, value).map_err(|error| RoundtripError::Write {
+            value: format!("{:?}", value),
+            error,
+        })?;
+        let bit_len = stream.bit_len();
+        Ok((data, bit_len))
+    };
+
+    let (data, written_bits) = encode(value)?;
+
+    let mut stream = BitReadStream::new(BitReadBuffer::new(&data, endianness));
+    let result = read(&mut stream).map_err(|error| RoundtripError::Read {
+        value: format!("{:?}", value),
+        written_bits,
+        error,
+    })?;
+
+    if &result != value {
+        let first_difference = encode(&result).ok().and_then(|(result_data, result_bits)| {
+            first_difference::<E>(&data, written_bits, &result_data, result_bits)
+        });
+        return Err(RoundtripError::Mismatch {
+            expected: format!("{:?}", value),
+            actual: format!("{:?}", result),
+            first_difference,
+        });
+    }
+
+    if stream.pos() != written_bits {
+        return Err(RoundtripError::LengthMismatch {
+            value: format!("{:?}", value),
+            written_bits,
+            read_bits: stream.pos(),
+        });
+    }
+
+    Ok(())
+}
+
+/// Parse every prefix of `bytes` and check that parsing fails without panicking
+///
+/// The full input is parsed first to find the number of bits the value needs, after which every shorter
+/// prefix of the input, down to the bit, is parsed. Every parse of a prefix that is shorter than the
+/// number of needed bits has to return an error. If the full input can't be parsed, the prefixes are only
+/// checked for panics.
+///
+/// This catches overreads and off-by-one errors in format definitions, like a length field that is
+/// trusted without checking the remaining data.
+///
+/// # Panics
+///
+/// Panics if parsing any prefix panics, or if parsing a prefix succeeds while it's shorter than the full value.
+///
+/// # Examples
+///
+/// ```
+/// use bitbuffer::{assert_no_panic_on_truncation, BitRead, LittleEndian};
+///
+/// #[derive(BitRead, Debug)]
+/// struct Packet {
+///     length: u8,
+///     #[size = "length"]
+///     name: String,
+/// }
+///
+/// assert_no_panic_on_truncation::<Packet, _>(&[0x03, b'f', b'o', b'o'], LittleEndian);
+/// ```
+#[track_caller]
+pub fn assert_no_panic_on_truncation<T, E>(bytes: &[u8], _endianness: E)
+where
+    T: for<'a> BitRead<'a, E> + Debug,
+    E: Endianness,
+{
+    truncation_with(bytes, |stream| stream.read::<T>())
+}
+
+/// Parse every prefix of `bytes` with a fixed size and check that parsing fails without panicking
+///
+/// See [`assert_no_panic_on_truncation`] for details.
+///
+/// # Panics
+///
+/// Panics if parsing any prefix panics, or if parsing a prefix succeeds while it's shorter than the full value.
+///
+/// # Examples
+///
+/// ```
+/// use bitbuffer::{assert_no_panic_on_truncation_sized, BigEndian};
+///
+/// assert_no_panic_on_truncation_sized::<Vec<u16>, _>(&[1, 2, 3, 4, 5, 6], 3, BigEndian);
+/// ```
+#[track_caller]
+pub fn assert_no_panic_on_truncation_sized<T, E>(bytes: &[u8], size: usize, _endianness: E)
+where
+    T: for<'a> BitReadSized<'a, E> + Debug,
+    E: Endianness,
+{
+    truncation_with(bytes, |stream| stream.read_sized::<T>(size))
+}
+
+#[track_caller]
+fn truncation_with<T, E, R>(bytes: &[u8], read: R)
+where
+    T: Debug,
+    E: Endianness,
+    R: for<'a> Fn(&mut BitReadStream<'a, E>) -> crate::Result<T>,
+{
+    let parse = |bit_len: usize| {
+        panic::catch_unwind(AssertUnwindSafe(|| {
+            let buffer = BitReadBuffer::with_bit_len(bytes, bit_len, E::endianness())
+                .expect("prefix is within the input");
+            let mut stream = BitReadStream::new(buffer);
+            read(&mut stream).map(|value| (value, stream.pos()))
+        }))
+    };
+
+    let total_bits = bytes.len() * 8;
+    let needed_bits = match parse(total_bits) {
+        Ok(Ok((_, read_bits))) => read_bits,
+        Ok(Err(_)) => 0,
+        Err(_) => panic!("parsing the full input of {} bits panicked", total_bits),
+    };
+
+    for bit_len in 0..total_bits {
+        match parse(bit_len) {
+            Err(_) => panic!("parsing a prefix of {} bits panicked", bit_len),
+            Ok(Ok((value, _))) if bit_len < needed_bits => panic!(
+                "parsing a prefix of {} bits succeeded while the full value needs {} bits, parsed {:?}",
+                bit_len, needed_bits, value
+            ),
+            Ok(_) => {}
+        }
+    }
+}
+
+/// Find the first bit that differs between two encoded values
+fn first_difference<E: Endianness>(
+    a: &[u8],
+    a_bits: usize,
+    b: &[u8],
+    b_bits: usize,
+) -> Option<usize> {
+    let a = BitReadBuffer::new(a, E::endianness());
+    let b = BitReadBuffer::new(b, E::endianness());
+    let common = a_bits.min(b_bits);
+    (0..common)
+        .find(|&pos| a.read_bool(pos).ok() != b.read_bool(pos).ok())
+        .or(if a_bits != b_bits { Some(common) } else { None })
+}
+
+/// The bytes of a value compared by [`assert_bit_eq!`](crate::assert_bit_eq)
+#[doc(hidden)]
+pub struct BitView<'a> {
+    bytes: Cow<'a, [u8]>,
+    bit_len: usize,
+    /// The order of the bits in a byte, `None` for plain bytes
+    little_endian: Option<bool>,
+}
+
+/// Values that can be compared by [`assert_bit_eq!`](crate::assert_bit_eq)
+#[doc(hidden)]
+pub trait AsBitView {
+    fn bit_view(&self) -> BitView<'_>;
+}
+
+impl AsBitView for [u8] {
+    fn bit_view(&self) -> BitView<'_> {
+        BitView {
+            bytes: Cow::Borrowed(self),
+            bit_len: self.len() * 8,
+            little_endian: None,
+        }
+    }
+}
+
+impl<const N: usize> AsBitView for [u8; N] {
+    fn bit_view(&self) -> BitView<'_> {
+        self[..].bit_view()
+    }
+}
+
+impl AsBitView for Vec<u8> {
+    fn bit_view(&self) -> BitView<'_> {
+        self[..].bit_view()
+    }
+}
+
+impl<T: AsBitView + ?Sized> AsBitView for &T {
+    fn bit_view(&self) -> BitView<'_> {
+        (**self).bit_view()
+    }
+}
+
+impl<T: AsBitView + ?Sized> AsBitView for &mut T {
+    fn bit_view(&self) -> BitView<'_> {
+        (**self).bit_view()
+    }
+}
+
+/// All written bits
+impl<E: Endianness> AsBitView for BitWriteStream<'_, E> {
+    fn bit_view(&self) -> BitView<'_> {
+        collect_bits::<E, _>(self.bit_len(), |pos, count| {
+            self.read_written_bits(pos, count) as u8
+        })
+    }
+}
+
+/// All bits of the stream, regardless of the current position
+impl<E: Endianness> AsBitView for BitReadStream<'_, E> {
+    fn bit_view(&self) -> BitView<'_> {
+        let mut stream = self.clone();
+        stream.set_pos(0).expect("start of the stream is in bounds");
+        collect_bits::<E, _>(stream.bit_len(), |_, count| {
+            stream
+                .read_int(count)
+                .expect("reading bits within the stream")
+        })
+    }
+}
+
+/// All bits of the buffer
+impl<E: Endianness> AsBitView for BitReadBuffer<'_, E> {
+    fn bit_view(&self) -> BitView<'_> {
+        collect_bits::<E, _>(self.bit_len(), |pos, count| {
+            self.read_int(pos, count)
+                .expect("reading bits within the buffer")
+        })
+    }
+}
+
+fn collect_bits<'a, E: Endianness, F: FnMut(usize, usize) -> u8>(
+    bit_len: usize,
+    mut read: F,
+) -> BitView<'a> {
+    let mut bytes = Vec::with_capacity(bit_len.div_ceil(8));
+    for pos in (0..bit_len).step_by(8) {
+        let count = (bit_len - pos).min(8);
+        let bits = read(pos, count);
+        bytes.push(if E::is_le() {
+            bits
+        } else {
+            bits << (8 - count)
+        });
+    }
+    BitView {
+        bytes: Cow::Owned(bytes),
+        bit_len,
+        little_endian: Some(E::is_le()),
+    }
+}
+
+/// Compare the bits of two values, returning a description of the difference if they differ
+#[doc(hidden)]
+pub fn bit_diff<A, B>(expected: &A, actual: &B) -> Result<(), String>
+where
+    A: AsBitView + ?Sized,
+    B: AsBitView + ?Sized,
+{
+    let expected = expected.bit_view();
+    let actual = actual.bit_view();
+    let little_endian = actual
+        .little_endian
+        .or(expected.little_endian)
+        .unwrap_or(false);
+
+    let first_byte = match expected
+        .bytes
+        .iter()
+        .zip(actual.bytes.iter())
+        .position(|(a, b)| a != b)
+    {
+        Some(byte) => byte,
+        None if expected.bytes.len() == actual.bytes.len() => return Ok(()),
+        None => expected.bytes.len().min(actual.bytes.len()),
+    };
+
+    // the column of the differing bit in the binary representation, which has the most significant bit first
+    let column = match (expected.bytes.get(first_byte), actual.bytes.get(first_byte)) {
+        (Some(a), Some(b)) => {
+            let diff = a ^ b;
+            if little_endian {
+                7 - diff.trailing_zeros() as usize
+            } else {
+                diff.leading_zeros() as usize
+            }
+        }
+        _ if little_endian => 7,
+        _ => 0,
+    };
+    let first_bit = first_byte * 8 + if little_endian { 7 - column } else { column };
+
+    let mut message = format!(
+        "bits differ at bit {} (byte {}), expected {} bits, got {} bits\n",
+        first_bit, first_byte, expected.bit_len, actual.bit_len
+    );
+    let binary = |bytes: &[u8], index: usize| match bytes.get(index) {
+        Some(byte) => format!("{:04b}_{:04b}", byte >> 4, byte & 0xF),
+        None => "---------".to_string(),
+    };
+    message.push_str("  offset | expected  | actual\n");
+    let rows = first_byte.saturating_sub(2)
+        ..(first_byte + 3).min(expected.bytes.len().max(actual.bytes.len()));
+    for index in rows {
+        message.push_str(&format!(
+            "{:>8} | {} | {}\n",
+            index,
+            binary(&expected.bytes, index),
+            binary(&actual.bytes, index)
+        ));
+        if index == first_byte {
+            let offset = column + if column >= 4 { 1 } else { 0 };
+            let marker = format!("{:>1$}", "^", offset + 1);
+            message.push_str(&format!("         | {:<9} | {}\n", marker, marker));
+        }
+    }
+    Err(message)
+}
@@ -0,0 +1,207 @@
+//! Length prefixed framing for [`tokio_util::codec`]
+//!
+//! [`BitCodec`] implements [`Decoder`] and [`Encoder`], framing every item with a length prefix.
+//! A frame consists of the length, followed by the bit-packed item, padded to the next byte boundary.
+//!
+//! # Examples
+//!
+//! ```
+//! # use bitbuffer::Result;
+//! use bitbuffer::tokio::BitCodec;
+//! use bitbuffer::{BitRead, BitWrite, LittleEndian};
+//! use bytes::BytesMut;
+//! use tokio_util::codec::{Decoder, Encoder};
+//!
+//! #[derive(BitRead, BitWrite, Debug, PartialEq)]
+//! struct Reading {
+//!     #[size = 3]
+//!     sensor: u8,
+//!     value: i16,
+//! }
+//!
+//! # fn main() -> Result<()> {
+//! // an 8 bit length, counting bytes
+//! let mut codec = BitCodec::<Reading, _>::new(8, 8, LittleEndian);
+//! let mut buffer = BytesMut::new();
+//! codec.encode(Reading { sensor: 5, value: -2 }, &mut buffer)?;
+//! assert_eq!(&buffer[..], [3, 0xF5, 0xFF, 0x07]);
+//!
+//! let mut partial = buffer.split_to(3);
+//! assert_eq!(codec.decode(&mut partial)?, None);
+//! partial.unsplit(buffer);
+//! assert_eq!(codec.decode(&mut partial)?, Some(Reading { sensor: 5, value: -2 }));
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Using the codec with a [`Framed`](tokio_util::codec::Framed) turns any `AsyncRead + AsyncWrite` into a
+//! `Stream` and `Sink` of items:
+//!
+//! ```ignore
+//! let framed = Framed::new(socket, BitCodec::<Reading, _>::new(16, 8, BigEndian));
+//! ```
+
+use crate::{
+    BitError, BitRead, BitReadBuffer, BitReadStream, BitWrite, BitWriteStream, Endianness, Result,
+};
+use ::bytes::{Buf, BytesMut};
+use ::tokio_util::codec::{Decoder, Encoder};
+use std::fmt::{self, Debug, Formatter};
+use std::io;
+use std::marker::PhantomData;
+
+/// A codec that frames items with a length prefix
+///
+/// See the [module level documentation](self) for details.
+pub struct BitCodec<T, E> {
+    length_bits: usize,
+    unit: usize,
+    max_frame_length: usize,
+    item: PhantomData<fn(T) -> T>,
+    endianness: PhantomData<E>,
+}
+
+impl<T, E: Endianness> BitCodec<T, E> {
+    /// Create a codec with a `length_bits` long length prefix that is multiplied by `unit` to get the size of the item in bits
+    ///
+    /// Use a `unit` of 8 for lengths in bytes or 1 for lengths in bits.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `length_bits` is 0 or larger than the size of `usize`, or if `unit` is 0
+    pub fn new(length_bits: usize, unit: usize, _endianness: E) -> Self {
+        assert!(
+            length_bits > 0 && length_bits <= usize::BITS as usize,
+            "length prefix must be between 1 and {} bits",
+            usize::BITS as usize
+        );
+        assert!(unit > 0, "length unit must be non-zero");
+        BitCodec {
+            length_bits,
+            unit,
+            max_frame_length: 8 * 1024 * 1024,
+            item: PhantomData,
+            endianness: PhantomData,
+        }
+    }
+
+    /// Set the maximum size of a frame in bytes, including the length prefix, defaults to 8MB
+    pub fn with_max_frame_length(mut self, max_frame_length: usize) -> Self {
+        self.max_frame_length = max_frame_length;
+        self
+    }
+
+    /// The maximum size of a frame in bytes
+    pub fn max_frame_length(&self) -> usize {
+        self.max_frame_length
+    }
+
+    fn frame_length(&self, length: usize) -> Result<usize> {
+        length
+            .checked_mul(self.unit)
+            .and_then(|bits| bits.checked_add(self.length_bits))
+            .map(|bits| bits.div_ceil(8))
+            .filter(|bytes| *bytes <= self.max_frame_length)
+            .ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    "frame size exceeds the maximum frame length",
+                )
+                .into()
+            })
+    }
+}
+
+impl<T, E> Decoder for BitCodec<T, E>
+where
+    T: for<'a> BitRead<'a, E>,
+    E: Endianness,
+{
+    type Item = T;
+    type Error = BitError;
+
+    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<T>> {
+        let header_length = self.length_bits.div_ceil(8);
+        if src.len() < header_length {
+            src.reserve(header_length - src.len());
+            return Ok(None);
+        }
+        let mut stream = BitReadStream::new(BitReadBuffer::new(&src[..], E::endianness()));
+        let length: usize = stream.read_int(self.length_bits)?;
+        let frame_length = self.frame_length(length)?;
+        if src.len() < frame_length {
+            src.reserve(frame_length - src.len());
+            return Ok(None);
+        }
+        let item = stream.read_bits(length * self.unit)?.read()?;
+        src.advance(frame_length);
+        Ok(Some(item))
+    }
+}
+
+impl<T, E> Encoder<T> for BitCodec<T, E>
+where
+    T: BitWrite<E>,
+    E: Endianness,
+{
+    type Error = BitError;
+
+    fn encode(&mut self, item: T, dst: &mut BytesMut) -> Result<()> {
+        let mut payload = Vec::new();
+        let mut payload_stream = BitWriteStream::new(&mut payload, E::endianness());
+        payload_stream.write(&item)?;
+        let payload_bits = payload_stream.bit_len();
+
+        let length = payload_bits.div_ceil(self.unit);
+        if self.length_bits < usize::BITS as usize && length >> self.length_bits != 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "item is too large for the length prefix",
+            )
+            .into());
+        }
+        let frame_length = self.frame_length(length)?;
+
+        let mut frame = Vec::with_capacity(frame_length);
+        let mut frame_stream = BitWriteStream::new(&mut frame, E::endianness());
+        frame_stream.write_int(length, self.length_bits)?;
+        let mut payload = BitReadStream::new(BitReadBuffer::new(&payload, E::endianness()));
+        let mut remaining = length * self.unit;
+        while remaining > 0 {
+            let chunk = remaining.min(32);
+            let bits = chunk.min(payload.bits_left());
+            if bits > 0 {
+                frame_stream.write_int(payload.read_int::<u32>(bits)?, bits)?;
+            }
+            if bits < chunk {
+                frame_stream.write_int(0u32, chunk - bits)?;
+            }
+            remaining -= chunk;
+        }
+
+        dst.extend_from_slice(&frame);
+        Ok(())
+    }
+}
+
+impl<T, E> Clone for BitCodec<T, E> {
+    fn clone(&self) -> Self {
+        BitCodec {
+            length_bits: self.length_bits,
+            unit: self.unit,
+            max_frame_length: self.max_frame_length,
+            item: PhantomData,
+            endianness: PhantomData,
+        }
+    }
+}
+
+impl<T, E> Debug for BitCodec<T, E> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BitCodec")
+            .field("length_bits", &self.length_bits)
+            .field("unit", &self.unit)
+            .field("max_frame_length", &self.max_frame_length)
+            .finish()
+    }
+}
@@ -0,0 +1,267 @@
+use crate::{
+    BitError, BitReadStream, Endianness, FieldLayout, Layout, ReadLimit, Result, SizeSource,
+};
+use std::convert::TryFrom;
+use std::fmt::{self, Display, Formatter};
+
+/// A value read without knowing its type at compile time
+///
+/// This allows tools like debuggers and hex viewers to represent parsed data generically,
+/// see [`BitReadStream::read_dynamic`].
+///
+/// # Examples
+///
+/// ```
+/// # use bitbuffer::Result;
+/// use bitbuffer::{BitRead, BitReadBuffer, BitReadStream, BitValue, LayoutDescribe, LittleEndian};
+///
+/// #[derive(BitRead, LayoutDescribe)]
+/// struct Header {
+///     #[size = 4]
+///     version: u8,
+///     #[size = 4]
+///     length: u8,
+///     #[size = "length"]
+///     name: String,
+/// }
+///
+/// # fn main() -> Result<()> {
+/// let bytes = [0x21, b'h', b'i'];
+/// let mut stream = BitReadStream::new(BitReadBuffer::new(&bytes, LittleEndian));
+/// let value = stream.read_dynamic(&Header::layout())?;
+/// assert_eq!(value.get("version"), Some(&BitValue::Int(1)));
+/// assert_eq!(value.to_string(), r#"Header { version: 1, length: 2, name: "hi" }"#);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub enum BitValue {
+    /// A boolean
+    Bool(bool),
+    /// A signed or unsigned integer, `u128` values larger than `i128::MAX` wrap around
+    Int(i128),
+    /// A float
+    Float(f64),
+    /// A string
+    Str(String),
+    /// A series of bytes
+    Bytes(Vec<u8>),
+    /// A series of values
+    List(Vec<BitValue>),
+    /// A struct with named fields, in the order they are read
+    Struct {
+        /// The name of the struct, empty if the struct has no name
+        name: String,
+        /// The fields of the struct
+        fields: Vec<(String, BitValue)>,
+    },
+    /// A variant of an enum
+    Enum {
+        /// The name of the variant
+        variant: String,
+        /// The field of the variant, if any
+        value: Option<Box<BitValue>>,
+    },
+}
+
+impl BitValue {
+    /// Get a field of a struct by name
+    pub fn get(&self, name: &str) -> Option<&BitValue> {
+        match self {
+            BitValue::Struct { fields, .. } => fields
+                .iter()
+                .find(|(field, _)| field == name)
+                .map(|(_, value)| value),
+            _ => None,
+        }
+    }
+
+    /// Get the value of an integer or boolean
+    pub fn as_int(&self) -> Option<i128> {
+        match self {
+            BitValue::Int(value) => Some(*value),
+            BitValue::Bool(value) => Some(*value as i128),
+            _ => None,
+        }
+    }
+}
+
+impl Display for BitValue {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            BitValue::Bool(value) => write!(f, "{}", value),
+            BitValue::Int(value) => write!(f, "{}", value),
+            BitValue::Float(value) => write!(f, "{}", value),
+            BitValue::Str(value) => write!(f, "{:?}", value),
+            BitValue::Bytes(bytes) => {
+                f.write_str("0x")?;
+                for byte in bytes {
+                    write!(f, "{:02x}", byte)?;
+                }
+                Ok(())
+            }
+            BitValue::List(values) => {
+                f.write_str("[")?;
+                for (i, value) in values.iter().enumerate() {
+                    if i > 0 {
+                        f.write_str(", ")?;
+                    }
+                    write!(f, "{}", value)?;
+                }
+                f.write_str("]")
+            }
+            BitValue::Struct { name, fields } => {
+                if !name.is_empty() {
+                    write!(f, "{} ", name)?;
+                }
+                f.write_str("{")?;
+                for (i, (field, value)) in fields.iter().enumerate() {
+                    if i > 0 {
+                        f.write_str(",")?;
+                    }
+                    write!(f, " {}: {}", field, value)?;
+                }
+                f.write_str(" }")
+            }
+            BitValue::Enum { variant, value } => match value {
+                Some(value) => write!(f, "{}({})", variant, value),
+                None => f.write_str(variant),
+            },
+        }
+    }
+}
+
+pub(crate) fn read_layout<E: Endianness>(
+    stream: &mut BitReadStream<E>,
+    layout: &Layout,
+) -> Result<BitValue> {
+    stream.enter_nested()?;
+    let result = read_layout_inner(stream, layout);
+    stream.leave_nested();
+    result
+}
+
+fn read_layout_inner<E: Endianness>(
+    stream: &mut BitReadStream<E>,
+    layout: &Layout,
+) -> Result<BitValue> {
+    match layout {
+        Layout::Struct { name, fields } => {
+            let mut values = Vec::with_capacity(fields.len());
+            for field in fields {
+                let value = read_field(stream, field, &values)?;
+                values.push((field.name.to_string(), value));
+            }
+            Ok(BitValue::Struct {
+                name: name.to_string(),
+                fields: values,
+            })
+        }
+        Layout::Enum {
+            name,
+            discriminant_bits,
+            variants,
+        } => {
+            let discriminant: usize = stream.read_int(*discriminant_bits)?;
+            let variant = variants
+                .iter()
+                .find(|variant| variant.discriminant == Some(discriminant))
+                .or_else(|| {
+                    variants
+                        .iter()
+                        .find(|variant| variant.discriminant.is_none())
+                })
+                .ok_or_else(|| BitError::UnmatchedDiscriminant {
+                    discriminant,
+                    enum_name: name.to_string(),
+                })?;
+            let value = match &variant.field {
+                Some(field) => Some(Box::new(read_field(stream, field, &[])?)),
+                None => None,
+            };
+            Ok(BitValue::Enum {
+                variant: variant.name.to_string(),
+                value,
+            })
+        }
+    }
+}
+
+fn read_field<E: Endianness>(
+    stream: &mut BitReadStream<E>,
+    field: &FieldLayout,
+    previous: &[(String, BitValue)],
+) -> Result<BitValue> {
+    let size = match field.size {
+        SizeSource::Unsized => None,
+        SizeSource::Fixed(size) => Some(size),
+        SizeSource::Bits(bits) => Some(stream.read_int(bits)?),
+        SizeSource::Expression(name) => Some(
+            previous
+                .iter()
+                .find(|(field, _)| field == name)
+                .and_then(|(_, value)| value.as_int())
+                .and_then(|value| usize::try_from(value).ok())
+                .ok_or_else(|| BitError::UnresolvedField {
+                    name: name.to_string(),
+                })?,
+        ),
+        SizeSource::InputSize => {
+            return Err(BitError::UnresolvedField {
+                name: "input_size".to_string(),
+            })
+        }
+    };
+    read_type(stream, field.type_name, size)
+}
+
+/// Read a value based on the name of its type
+fn read_type<E: Endianness>(
+    stream: &mut BitReadStream<E>,
+    type_name: &str,
+    size: Option<usize>,
+) -> Result<BitValue> {
+    let int_bits = |bits: usize| size.unwrap_or(bits);
+    Ok(match type_name {
+        "bool" => BitValue::Bool(stream.read_bool()?),
+        "u8" => BitValue::Int(stream.read_int::<u8>(int_bits(8))? as i128),
+        "u16" => BitValue::Int(stream.read_int::<u16>(int_bits(16))? as i128),
+        "u32" => BitValue::Int(stream.read_int::<u32>(int_bits(32))? as i128),
+        "u64" => BitValue::Int(stream.read_int::<u64>(int_bits(64))? as i128),
+        "u128" => BitValue::Int(stream.read_int::<u128>(int_bits(128))? as i128),
+        "i8" => BitValue::Int(stream.read_int::<i8>(int_bits(8))? as i128),
+        "i16" => BitValue::Int(stream.read_int::<i16>(int_bits(16))? as i128),
+        "i32" => BitValue::Int(stream.read_int::<i32>(int_bits(32))? as i128),
+        "i64" => BitValue::Int(stream.read_int::<i64>(int_bits(64))? as i128),
+        "i128" => BitValue::Int(stream.read_int::<i128>(int_bits(128))?),
+        "f32" => BitValue::Float(stream.read_float::<f32>()? as f64),
+        "f64" => BitValue::Float(stream.read_float::<f64>()?),
+        "String" => BitValue::Str(stream.read_string(size)?.into_owned()),
+        "Vec<u8>" => match size {
+            Some(size) => {
+                stream.check_limit(ReadLimit::Elements, size)?;
+                BitValue::Bytes(stream.read_bytes(size)?.into_owned())
+            }
+            None => return Err(unsupported(type_name)),
+        },
+        _ => match (type_name.strip_prefix("Vec<"), size) {
+            (Some(inner), Some(size)) if type_name.ends_with('>') => {
+                let inner = &inner[..inner.len() - 1];
+                stream.check_limit(ReadLimit::Elements, size)?;
+                let mut values = Vec::with_capacity(size.min(1024));
+                for _ in 0..size {
+                    stream.check_cancelled()?;
+                    values.push(read_type(stream, inner, None)?);
+                }
+                BitValue::List(values)
+            }
+            _ => return Err(unsupported(type_name)),
+        },
+    })
+}
+
+fn unsupported(type_name: &str) -> BitError {
+    BitError::UnsupportedType {
+        type_name: type_name.to_string(),
+    }
+}
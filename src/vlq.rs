@@ -0,0 +1,70 @@
+use crate::{BitRead, BitReadStream, BitWrite, BitWriteStream, Endianness, Result};
+use std::fmt::{self, Display, Formatter};
+
+/// An integer encoded as MIDI style variable length quantity
+///
+/// This allows using variable length quantities in derived structs,
+/// see [`read_vlq`](BitReadStream::read_vlq) and [`write_vlq`](BitWriteStream::write_vlq).
+///
+/// # Examples
+///
+/// ```
+/// # use bitbuffer::Result;
+/// use bitbuffer::{BitRead, BitReadBuffer, BitReadStream, BitWrite, BitWriteStream, BigEndian, Vlq};
+///
+/// #[derive(BitRead, BitWrite, Debug, PartialEq)]
+/// struct Event {
+///     delta_time: Vlq,
+///     status: u8,
+/// }
+///
+/// # fn main() -> Result<()> {
+/// let event = Event {
+///     delta_time: Vlq(200),
+///     status: 0x90,
+/// };
+/// let mut data = Vec::new();
+/// let mut stream = BitWriteStream::new(&mut data, BigEndian);
+/// stream.write(&event)?;
+/// assert_eq!(data, [0x81, 0x48, 0x90]);
+///
+/// let mut stream = BitReadStream::new(BitReadBuffer::new(&data, BigEndian));
+/// assert_eq!(stream.read::<Event>()?, event);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Vlq(pub u64);
+
+impl From<u64> for Vlq {
+    fn from(value: u64) -> Self {
+        Vlq(value)
+    }
+}
+
+impl From<Vlq> for u64 {
+    fn from(value: Vlq) -> Self {
+        value.0
+    }
+}
+
+impl Display for Vlq {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl<'a, E: Endianness> BitRead<'a, E> for Vlq {
+    #[inline]
+    fn read(stream: &mut BitReadStream<'a, E>) -> Result<Self> {
+        stream.read_vlq().map(Vlq)
+    }
+}
+
+impl<E: Endianness> BitWrite<E> for Vlq {
+    #[inline]
+    fn write(&self, stream: &mut BitWriteStream<E>) -> Result<()> {
+        stream.write_vlq(self.0)
+    }
+}
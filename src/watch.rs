@@ -0,0 +1,177 @@
+//! Callbacks fired when the read position crosses given offsets
+//!
+//! A [`WatchedReadStream`] wraps a [`BitReadStream`] and calls the registered callbacks every time a read
+//! moves the position from before a watched offset to at or past it.
+//! The callbacks receive the position after the read, which makes them usable both for progress reporting
+//! and for enforcing that a parser didn't read past the end of a section.
+//!
+//! If a callback returns an error the read fails with that error and the position is restored.
+//!
+//! # Examples
+//!
+//! ```
+//! # use bitbuffer::Result;
+//! use bitbuffer::watch::WatchedReadStream;
+//! use bitbuffer::{BitError, BitReadBuffer, BitReadStream, LittleEndian};
+//!
+//! # fn main() -> Result<()> {
+//! let bytes = [2, 0xAA, 0xBB, 0xCC, 0xDD];
+//! let mut stream = WatchedReadStream::new(BitReadStream::new(BitReadBuffer::new(&bytes, LittleEndian)));
+//!
+//! // the header contains the length of the section in bytes
+//! let section_len: u8 = stream.read()?;
+//! let section_end = stream.pos() + section_len as usize * 8;
+//! stream.watch(section_end, move |pos| {
+//!     if pos > section_end {
+//!         Err(BitError::IndexOutOfBounds { pos, size: section_end })
+//!     } else {
+//!         Ok(())
+//!     }
+//! });
+//!
+//! // reading 3 bytes overruns the section
+//! assert!(stream.read_bytes(3).is_err());
+//! assert_eq!(stream.read::<u16>()?, 0xBBAA);
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::{BitRead, BitReadSized, BitReadStream, Endianness, Result};
+use std::borrow::Cow;
+use std::fmt::{self, Debug, Formatter};
+use std::ops::Deref;
+
+struct Watchpoint<'w> {
+    offset: usize,
+    callback: Box<dyn FnMut(usize) -> Result<()> + 'w>,
+}
+
+/// A read stream that fires callbacks when the position crosses watched offsets
+///
+/// See the [module level documentation](self) for details.
+///
+/// The wrapped stream can be accessed through [`Deref`] for methods that don't move the position.
+pub struct WatchedReadStream<'a, 'w, E: Endianness> {
+    stream: BitReadStream<'a, E>,
+    watchpoints: Vec<Watchpoint<'w>>,
+}
+
+impl<'a, 'w, E: Endianness> WatchedReadStream<'a, 'w, E> {
+    /// Wrap a stream without any watchpoints
+    pub fn new(stream: BitReadStream<'a, E>) -> Self {
+        WatchedReadStream {
+            stream,
+            watchpoints: Vec::new(),
+        }
+    }
+
+    /// Call `callback` with the new position whenever a read moves the position from before `offset` to at or past it
+    ///
+    /// The offset is relative to the start of the stream, like [`BitReadStream::pos`].
+    /// Multiple callbacks crossed by the same read are called in the order of their offsets.
+    pub fn watch<F>(&mut self, offset: usize, callback: F)
+    where
+        F: FnMut(usize) -> Result<()> + 'w,
+    {
+        let index = self
+            .watchpoints
+            .partition_point(|watch| watch.offset <= offset);
+        self.watchpoints.insert(
+            index,
+            Watchpoint {
+                offset,
+                callback: Box::new(callback),
+            },
+        );
+    }
+
+    /// Remove all watchpoints
+    pub fn clear_watchpoints(&mut self) {
+        self.watchpoints.clear();
+    }
+
+    /// Perform an arbitrary operation on the wrapped stream, firing the callbacks for any crossed offsets afterwards
+    ///
+    /// # Errors
+    ///
+    /// - any error returned by `f`
+    /// - any error returned by a callback, the position is restored to the position before `f` was called
+    pub fn with<T, F>(&mut self, f: F) -> Result<T>
+    where
+        F: FnOnce(&mut BitReadStream<'a, E>) -> Result<T>,
+    {
+        let start = self.stream.pos();
+        let result = f(&mut self.stream)?;
+        let end = self.stream.pos();
+        for watch in self.watchpoints.iter_mut() {
+            if start < watch.offset && watch.offset <= end {
+                if let Err(e) = (watch.callback)(end) {
+                    self.stream.set_pos(start)?;
+                    return Err(e);
+                }
+            }
+        }
+        Ok(result)
+    }
+
+    /// Read a value based on the provided type, see [`BitReadStream::read`]
+    pub fn read<T: BitRead<'a, E>>(&mut self) -> Result<T> {
+        self.with(|stream| stream.read())
+    }
+
+    /// Read a value based on the provided type and size, see [`BitReadStream::read_sized`]
+    pub fn read_sized<T: BitReadSized<'a, E>>(&mut self, size: usize) -> Result<T> {
+        self.with(|stream| stream.read_sized(size))
+    }
+
+    /// Read a single bit as boolean, see [`BitReadStream::read_bool`]
+    pub fn read_bool(&mut self) -> Result<bool> {
+        self.with(|stream| stream.read_bool())
+    }
+
+    /// Read a series of bytes, see [`BitReadStream::read_bytes`]
+    pub fn read_bytes(&mut self, byte_count: usize) -> Result<Cow<'a, [u8]>> {
+        self.with(|stream| stream.read_bytes(byte_count))
+    }
+
+    /// Skip a number of bits, see [`BitReadStream::skip_bits`]
+    pub fn skip_bits(&mut self, count: usize) -> Result<()> {
+        self.with(|stream| stream.skip_bits(count))
+    }
+
+    /// Set the position of the stream, see [`BitReadStream::set_pos`]
+    ///
+    /// Only moving the position forward can cross an offset.
+    pub fn set_pos(&mut self, pos: usize) -> Result<()> {
+        self.with(|stream| stream.set_pos(pos))
+    }
+
+    /// Get the wrapped stream, removing all watchpoints
+    pub fn into_inner(self) -> BitReadStream<'a, E> {
+        self.stream
+    }
+}
+
+impl<'a, E: Endianness> Deref for WatchedReadStream<'a, '_, E> {
+    type Target = BitReadStream<'a, E>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.stream
+    }
+}
+
+impl<E: Endianness + Debug> Debug for WatchedReadStream<'_, '_, E> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WatchedReadStream")
+            .field("stream", &self.stream)
+            .field(
+                "watchpoints",
+                &self
+                    .watchpoints
+                    .iter()
+                    .map(|watch| watch.offset)
+                    .collect::<Vec<_>>(),
+            )
+            .finish()
+    }
+}
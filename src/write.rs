@@ -1,4 +1,5 @@
 use crate::{BitReadStream, BitWriteStream, Endianness, Result};
+use std::marker::PhantomData;
 use std::mem::size_of;
 use std::rc::Rc;
 use std::sync::Arc;
@@ -82,6 +83,18 @@ use std::sync::Arc;
 pub trait BitWrite<E: Endianness> {
     /// Write the type to stream
     fn write(&self, stream: &mut BitWriteStream<E>) -> Result<()>;
+
+    /// The number of bits that will be written or None if the number of bits will change depending
+    /// on the value
+    ///
+    /// This is used as a hint to allocate enough space before writing.
+    #[inline]
+    fn bit_size_hint() -> Option<usize>
+    where
+        Self: Sized,
+    {
+        None
+    }
 }
 
 macro_rules! impl_write_int {
@@ -91,6 +104,11 @@ macro_rules! impl_write_int {
             fn write(&self, stream: &mut BitWriteStream<E>) -> Result<()> {
                 stream.write_int::<$type>(*self, size_of::<$type>() * 8)
             }
+
+            #[inline]
+            fn bit_size_hint() -> Option<usize> {
+                Some(size_of::<$type>() * 8)
+            }
         }
     };
 }
@@ -111,6 +129,11 @@ impl<E: Endianness> BitWrite<E> for f32 {
     fn write(&self, stream: &mut BitWriteStream<E>) -> Result<()> {
         stream.write_float::<f32>(*self)
     }
+
+    #[inline]
+    fn bit_size_hint() -> Option<usize> {
+        Some(32)
+    }
 }
 
 impl<E: Endianness> BitWrite<E> for f64 {
@@ -118,12 +141,56 @@ impl<E: Endianness> BitWrite<E> for f64 {
     fn write(&self, stream: &mut BitWriteStream<E>) -> Result<()> {
         stream.write_float::<f64>(*self)
     }
+
+    #[inline]
+    fn bit_size_hint() -> Option<usize> {
+        Some(64)
+    }
 }
 
 impl<E: Endianness> BitWrite<E> for bool {
     #[inline]
     fn write(&self, stream: &mut BitWriteStream<E>) -> Result<()> {
-        stream.write_bool(*self)
+        stream.write_bit(*self)
+    }
+
+    #[inline]
+    fn bit_size_hint() -> Option<usize> {
+        Some(1)
+    }
+}
+
+/// Writes zero bits, allowing generic code to use `()` for parts that aren't present
+impl<E: Endianness> BitWrite<E> for () {
+    #[inline]
+    fn write(&self, _stream: &mut BitWriteStream<E>) -> Result<()> {
+        Ok(())
+    }
+
+    #[inline]
+    fn bit_size_hint() -> Option<usize> {
+        Some(0)
+    }
+}
+
+/// Writes zero bits
+impl<E: Endianness, T: ?Sized> BitWrite<E> for PhantomData<T> {
+    #[inline]
+    fn write(&self, _stream: &mut BitWriteStream<E>) -> Result<()> {
+        Ok(())
+    }
+
+    #[inline]
+    fn bit_size_hint() -> Option<usize> {
+        Some(0)
+    }
+}
+
+/// Write a single character as utf8
+impl<E: Endianness> BitWrite<E> for char {
+    #[inline]
+    fn write(&self, stream: &mut BitWriteStream<E>) -> Result<()> {
+        stream.write_bytes(self.encode_utf8(&mut [0; 4]).as_bytes())
     }
 }
 
@@ -156,29 +223,67 @@ impl<E: Endianness, T: BitWrite<E>, const N: usize> BitWrite<E> for [T; N] {
         }
         Ok(())
     }
+
+    #[inline]
+    fn bit_size_hint() -> Option<usize> {
+        T::bit_size_hint().map(|size| size * N)
+    }
 }
 
 impl<T: BitWrite<E>, E: Endianness> BitWrite<E> for Box<T> {
     #[inline]
     fn write(&self, stream: &mut BitWriteStream<E>) -> Result<()> {
-        stream.write(self)
+        T::write(self, stream)
+    }
+
+    #[inline]
+    fn bit_size_hint() -> Option<usize> {
+        T::bit_size_hint()
     }
 }
 
 impl<T: BitWrite<E>, E: Endianness> BitWrite<E> for Rc<T> {
     #[inline]
     fn write(&self, stream: &mut BitWriteStream<E>) -> Result<()> {
-        stream.write(self)
+        T::write(self, stream)
+    }
+
+    #[inline]
+    fn bit_size_hint() -> Option<usize> {
+        T::bit_size_hint()
     }
 }
 
 impl<T: BitWrite<E>, E: Endianness> BitWrite<E> for Arc<T> {
     #[inline]
     fn write(&self, stream: &mut BitWriteStream<E>) -> Result<()> {
-        stream.write(self)
+        T::write(self, stream)
     }
+
+    #[inline]
+    fn bit_size_hint() -> Option<usize> {
+        T::bit_size_hint()
+    }
+}
+
+macro_rules! impl_write_slice {
+    ($type:ident) => {
+        impl<E: Endianness, T: BitWrite<E>> BitWrite<E> for $type<[T]> {
+            #[inline]
+            fn write(&self, stream: &mut BitWriteStream<E>) -> Result<()> {
+                for element in self.iter() {
+                    stream.write(element)?;
+                }
+                Ok(())
+            }
+        }
+    };
 }
 
+impl_write_slice!(Box);
+impl_write_slice!(Rc);
+impl_write_slice!(Arc);
+
 macro_rules! impl_write_tuple {
     ($($i:tt: $type:ident),*) => {
         impl<'a, E: Endianness, $($type: BitWrite<E>),*> BitWrite<E> for ($($type),*) {
@@ -187,6 +292,11 @@ macro_rules! impl_write_tuple {
                 $(self.$i.write(stream)?;)*
                 Ok(())
             }
+
+            #[inline]
+            fn bit_size_hint() -> Option<usize> {
+                Some(0)$(.and_then(|sum| Some(sum + $type::bit_size_hint()?)))*
+            }
         }
     };
 }
@@ -276,6 +386,14 @@ impl<E: Endianness> BitWriteSized<E> for String {
     }
 }
 
+/// Write the code point of the character as an integer of `len` bits, such as a utf32 code unit
+impl<E: Endianness> BitWriteSized<E> for char {
+    #[inline]
+    fn write_sized(&self, stream: &mut BitWriteStream<E>, len: usize) -> Result<()> {
+        stream.write_int(*self as u32, len)
+    }
+}
+
 macro_rules! impl_write_sized_int {
     ($type:ty) => {
         impl<E: Endianness> BitWriteSized<E> for $type {
@@ -318,23 +436,23 @@ impl<E: Endianness, T: BitWriteSized<E>, const N: usize> BitWriteSized<E> for [T
     }
 }
 
-impl<T: BitWrite<E>, E: Endianness> BitWriteSized<E> for Box<T> {
+impl<T: BitWriteSized<E>, E: Endianness> BitWriteSized<E> for Box<T> {
     #[inline]
     fn write_sized(&self, stream: &mut BitWriteStream<E>, len: usize) -> Result<()> {
-        stream.write_sized(self, len)
+        T::write_sized(self, stream, len)
     }
 }
 
-impl<T: BitWrite<E>, E: Endianness> BitWriteSized<E> for Rc<T> {
+impl<T: BitWriteSized<E>, E: Endianness> BitWriteSized<E> for Rc<T> {
     #[inline]
     fn write_sized(&self, stream: &mut BitWriteStream<E>, len: usize) -> Result<()> {
-        stream.write_sized(self, len)
+        T::write_sized(self, stream, len)
     }
 }
 
-impl<T: BitWrite<E>, E: Endianness> BitWriteSized<E> for Arc<T> {
+impl<T: BitWriteSized<E>, E: Endianness> BitWriteSized<E> for Arc<T> {
     #[inline]
     fn write_sized(&self, stream: &mut BitWriteStream<E>, len: usize) -> Result<()> {
-        stream.write_sized(self, len)
+        T::write_sized(self, stream, len)
     }
 }
@@ -1,110 +1,365 @@
-use crate::Endianness;
-use std::cmp::min;
-use std::iter::{once, repeat};
+use crate::hasher::{BitHasher, HashHook};
+use crate::scramble::ScrambleHook;
+use crate::{BitReadBuffer, BitReadStream, Endianness, Result};
+use std::convert::TryInto;
 use std::marker::PhantomData;
-use std::mem::size_of;
+use std::ops::Range;
+
+const USIZE_BITS: usize = usize::BITS as usize;
+/// The maximum number of bits that can be pushed at once, leaving room for a partially written byte
+const MAX_PUSH_BITS: usize = USIZE_BITS - 9;
+
+/// Growable byte storage that can back a [`WriteBuffer`]
+pub(crate) trait ByteStorage {
+    fn as_slice(&self) -> &[u8];
+    fn as_mut_slice(&mut self) -> &mut [u8];
+    fn capacity(&self) -> usize;
+    fn reserve(&mut self, additional: usize);
+    fn resize(&mut self, new_len: usize, value: u8);
+    fn truncate(&mut self, new_len: usize);
+    fn pop(&mut self) -> Option<u8>;
+    fn extend_from_slice(&mut self, other: &[u8]);
+
+    fn len(&self) -> usize {
+        self.as_slice().len()
+    }
+}
+
+impl ByteStorage for Vec<u8> {
+    fn as_slice(&self) -> &[u8] {
+        Vec::as_slice(self)
+    }
+
+    fn as_mut_slice(&mut self) -> &mut [u8] {
+        Vec::as_mut_slice(self)
+    }
+
+    fn capacity(&self) -> usize {
+        Vec::capacity(self)
+    }
+
+    fn reserve(&mut self, additional: usize) {
+        Vec::reserve(self, additional)
+    }
+
+    fn resize(&mut self, new_len: usize, value: u8) {
+        Vec::resize(self, new_len, value)
+    }
+
+    fn truncate(&mut self, new_len: usize) {
+        Vec::truncate(self, new_len)
+    }
 
-const USIZE_BITS: usize = size_of::<usize>() * 8;
+    fn pop(&mut self) -> Option<u8> {
+        Vec::pop(self)
+    }
+
+    fn extend_from_slice(&mut self, other: &[u8]) {
+        Vec::extend_from_slice(self, other)
+    }
 
-pub struct WriteBuffer<'a, E: Endianness>(CowWriteBuffer<'a, E>);
+    fn len(&self) -> usize {
+        Vec::len(self)
+    }
+}
+
+pub struct WriteBuffer<'a, E: Endianness> {
+    buffer: CowWriteBuffer<'a, E>,
+    hasher: Option<HashHook<E>>,
+    scrambler: Option<ScrambleHook<E>>,
+}
 
 impl<'a, E: Endianness> WriteBuffer<'a, E> {
     pub fn new(bytes: &'a mut Vec<u8>, endianness: E) -> Self {
-        WriteBuffer(CowWriteBuffer::ExpandBorrowed(ExpandWriteBuffer::new(
-            bytes, endianness,
-        )))
+        WriteBuffer {
+            buffer: CowWriteBuffer::ExpandBorrowed(ExpandWriteBuffer::new(bytes, endianness)),
+            hasher: None,
+            scrambler: None,
+        }
+    }
+
+    /// Create a buffer backed by inline storage
+    #[cfg(feature = "inline")]
+    pub fn new_inline(bytes: &'a mut (dyn ByteStorage + Send + 'static), endianness: E) -> Self {
+        WriteBuffer {
+            buffer: CowWriteBuffer::InlineBorrowed(ExpandWriteBuffer::new(bytes, endianness)),
+            hasher: None,
+            scrambler: None,
+        }
     }
 
     /// The number of written bits in the buffer
     pub fn bit_len(&self) -> usize {
-        self.0.bit_len()
+        self.buffer.bit_len()
     }
 
+    /// Feed all bits pushed from now on to the hasher
+    pub fn attach_hasher(&mut self, hasher: Box<dyn BitHasher + Send>) {
+        self.hasher = Some(HashHook::new(hasher));
+    }
+
+    /// XOR all bits pushed from now on with the key stream
+    pub fn attach_scrambler(&mut self, scrambler: Option<ScrambleHook<E>>) {
+        self.scrambler = scrambler;
+    }
+
+    /// Whether any hook is attached that requires the bits to be pushed in order
+    pub fn has_hooks(&self) -> bool {
+        self.hasher.is_some() || self.scrambler.is_some()
+    }
+
+    /// Detach the hasher and get the digest
+    pub fn finalize_hash(&mut self) -> Option<Vec<u8>> {
+        self.hasher.take().map(HashHook::finalize)
+    }
+
+    /// Push up to 128 bits, given as little endian bytes
+    ///
+    /// The bits are pushed in chunks that are as large as [`push_bits`](Self::push_bits) allows, so a 64 bit value
+    /// takes 2 chunks on 64-bit platforms and 3 on 32-bit platforms
     pub fn push_non_fit_bits<I>(&mut self, bits: I, count: usize)
     where
         I: ExactSizeIterator,
         I: DoubleEndedIterator<Item = u8>,
     {
-        let full_bytes = min(bits.len() - 1, count / 8);
+        debug_assert!(count <= 128);
 
-        let counts = repeat(8)
-            .take(full_bytes)
-            .chain(once(count - full_bytes * 8));
-        if E::is_le() {
-            bits.zip(counts)
-                .for_each(|(chunk, count)| self.push_bits(chunk as usize, count))
+        let mut bytes = [0; 16];
+        for (target, byte) in bytes.iter_mut().zip(bits) {
+            *target = byte;
+        }
+        let value = u128::from_le_bytes(bytes);
+
+        let mut left = count;
+        while left > 0 {
+            let chunk = left.min(MAX_PUSH_BITS);
+            let shift = if E::is_le() {
+                count - left
+            } else {
+                left - chunk
+            };
+            let mask = usize::MAX >> (USIZE_BITS - chunk);
+            self.push_bits((value >> shift) as usize & mask, chunk);
+            left -= chunk;
+        }
+    }
+
+    /// Push a single bit
+    pub fn push_bit(&mut self, bit: bool) {
+        if self.has_hooks() {
+            self.push_bits(bit as usize, 1);
         } else {
-            bits.take(count / 8 + 1)
-                .rev()
-                .zip(counts)
-                .for_each(|(chunk, count)| self.push_bits(chunk as usize, count))
+            self.buffer.push_bit(bit);
+        }
+    }
+
+    /// Push whole bytes, shifting them in bulk when no hooks are attached
+    pub fn push_bytes(&mut self, bytes: &[u8]) {
+        if self.has_hooks() {
+            for byte in bytes {
+                self.push_bits(*byte as usize, 8);
+            }
+        } else {
+            self.buffer.push_bytes(bytes);
         }
     }
 
     /// Push up to an usize worth of bits
     pub fn push_bits(&mut self, bits: usize, count: usize) {
-        self.0.push_bits(bits, count)
+        if count == 0 {
+            return;
+        }
+        let bits = match self.scrambler.as_mut() {
+            Some(scrambler) => scrambler.scramble(bits, count),
+            None => bits,
+        };
+        if let Some(hasher) = self.hasher.as_mut() {
+            hasher.push_bits(bits, count);
+        }
+        self.buffer.push_bits(bits, count)
+    }
+
+    /// Read up to an usize worth of previously written bits, as stored in the output
+    pub fn read_bits(&self, pos: usize, count: usize) -> usize {
+        self.buffer.read_bits(pos, count)
+    }
+
+    /// Replace the bits in `range` with the bits from `source`, moving the bits after the range
+    ///
+    /// The bits are written directly to the output, bypassing any attached hasher or scrambler
+    pub fn splice(&mut self, range: Range<usize>, mut source: BitReadStream<E>) -> Result<()> {
+        let end = self.bit_len();
+        let mut tail = Vec::with_capacity((end - range.end).div_ceil(8));
+        let mut tail_buffer = ExpandWriteBuffer::new(&mut tail, E::endianness());
+        let mut pos = range.end;
+        while pos < end {
+            let count = (end - pos).min(32);
+            tail_buffer.push_bits(self.buffer.read_bits(pos, count), count);
+            pos += count;
+        }
+        let tail_len = tail_buffer.bit_len();
+
+        self.buffer.truncate(range.start);
+        while source.bits_left() > 0 {
+            let count = source.bits_left().min(32);
+            self.buffer.push_bits(source.read_int(count)?, count);
+        }
+        let tail = BitReadBuffer::new(&tail, E::endianness());
+        let mut pos = 0;
+        while pos < tail_len {
+            let count = (tail_len - pos).min(32);
+            self.buffer.push_bits(tail.read_int(pos, count)?, count);
+            pos += count;
+        }
+        Ok(())
+    }
+
+    /// Overwrite the bits starting at `pos` with the bits from `source`, without moving any other bits
+    ///
+    /// The bits are written directly to the output, bypassing any attached hasher or scrambler
+    pub fn overwrite(&mut self, mut pos: usize, mut source: BitReadStream<E>) -> Result<()> {
+        debug_assert!(pos + source.bits_left() <= self.bit_len());
+        while source.bits_left() > 0 {
+            let count = source.bits_left().min(32);
+            self.buffer
+                .overwrite_bits(pos, source.read_int(count)?, count);
+            pos += count;
+        }
+        Ok(())
+    }
+
+    /// Get the written bytes and the number of written bits
+    pub fn finish(self) -> (&'a [u8], usize) {
+        match self.buffer {
+            CowWriteBuffer::ExpandBorrowed(buffer) => buffer.finish(),
+            #[cfg(feature = "inline")]
+            CowWriteBuffer::InlineBorrowed(buffer) => buffer.finish(),
+        }
+    }
+
+    /// Remove all bits after `bit_len`
+    pub fn truncate(&mut self, bit_len: usize) {
+        self.buffer.truncate(bit_len)
     }
 
-    pub fn reserve(&mut self, length: usize) -> (WriteBuffer<E>, WriteBuffer<E>) {
-        let (head, tail) = self.0.reserve(length);
-        (WriteBuffer(head), WriteBuffer(tail))
+    /// The number of bits the buffer can hold without reallocating
+    pub fn capacity(&self) -> usize {
+        self.buffer.capacity()
+    }
+
+    /// Ensure the buffer can hold at least `count` more bits without reallocating
+    pub fn reserve_capacity(&mut self, count: usize) {
+        self.buffer.reserve_capacity(count)
     }
 }
 
+#[allow(clippy::enum_variant_names)]
 enum CowWriteBuffer<'a, E: Endianness> {
-    FixedBorrowed(FixedWriteBuffer<'a, E>),
     ExpandBorrowed(ExpandWriteBuffer<'a, E>),
+    #[cfg(feature = "inline")]
+    InlineBorrowed(ExpandWriteBuffer<'a, E, dyn ByteStorage + Send>),
 }
 
 impl<'a, E: Endianness> CowWriteBuffer<'a, E> {
     /// The number of written bits in the buffer
     fn bit_len(&self) -> usize {
         match self {
-            CowWriteBuffer::FixedBorrowed(buffer) => buffer.bit_len(),
             CowWriteBuffer::ExpandBorrowed(buffer) => buffer.bit_len(),
+            #[cfg(feature = "inline")]
+            CowWriteBuffer::InlineBorrowed(buffer) => buffer.bit_len(),
         }
     }
 
     /// Push up to an usize worth of bits
     fn push_bits(&mut self, bits: usize, count: usize) {
         match self {
-            CowWriteBuffer::FixedBorrowed(buffer) => buffer.push_bits(bits, count),
             CowWriteBuffer::ExpandBorrowed(buffer) => buffer.push_bits(bits, count),
+            #[cfg(feature = "inline")]
+            CowWriteBuffer::InlineBorrowed(buffer) => buffer.push_bits(bits, count),
         }
     }
 
-    /// Reserve some bits to be written later by splitting of two parts
-    fn reserve(&mut self, length: usize) -> (CowWriteBuffer<E>, CowWriteBuffer<E>) {
+    fn push_bit(&mut self, bit: bool) {
         match self {
-            CowWriteBuffer::FixedBorrowed(buffer) => {
-                let (head, tail) = buffer.reserve(length);
-                (
-                    CowWriteBuffer::FixedBorrowed(head),
-                    CowWriteBuffer::FixedBorrowed(tail),
-                )
-            }
-            CowWriteBuffer::ExpandBorrowed(buffer) => {
-                let (head, tail) = buffer.reserve(length);
-                (
-                    CowWriteBuffer::FixedBorrowed(head),
-                    CowWriteBuffer::ExpandBorrowed(tail),
-                )
-            }
+            CowWriteBuffer::ExpandBorrowed(buffer) => buffer.push_bit(bit),
+            #[cfg(feature = "inline")]
+            CowWriteBuffer::InlineBorrowed(buffer) => buffer.push_bit(bit),
+        }
+    }
+
+    fn push_bytes(&mut self, bytes: &[u8]) {
+        match self {
+            CowWriteBuffer::ExpandBorrowed(buffer) => buffer.push_bytes(bytes),
+            #[cfg(feature = "inline")]
+            CowWriteBuffer::InlineBorrowed(buffer) => buffer.push_bytes(bytes),
+        }
+    }
+
+    /// Read up to an usize worth of previously written bits
+    fn read_bits(&self, pos: usize, count: usize) -> usize {
+        debug_assert!(pos + count <= self.bit_len());
+        let bytes = match self {
+            CowWriteBuffer::ExpandBorrowed(buffer) => buffer.written_bytes(),
+            #[cfg(feature = "inline")]
+            CowWriteBuffer::InlineBorrowed(buffer) => buffer.written_bytes(),
+        };
+        BitReadBuffer::new(bytes, E::endianness())
+            .read_int(pos, count)
+            .expect("reading previously written bits")
+    }
+
+    /// Overwrite up to an usize worth of previously written bits
+    fn overwrite_bits(&mut self, pos: usize, bits: usize, count: usize) {
+        debug_assert!(pos + count <= self.bit_len());
+        let bytes = match self {
+            CowWriteBuffer::ExpandBorrowed(buffer) => buffer.written_bytes_mut(),
+            #[cfg(feature = "inline")]
+            CowWriteBuffer::InlineBorrowed(buffer) => buffer.written_bytes_mut(),
+        };
+        set_bits::<E>(bytes, pos, bits, count);
+    }
+
+    /// Remove all bits after `bit_len`
+    fn truncate(&mut self, bit_len: usize) {
+        match self {
+            CowWriteBuffer::ExpandBorrowed(buffer) => buffer.truncate(bit_len),
+            #[cfg(feature = "inline")]
+            CowWriteBuffer::InlineBorrowed(buffer) => buffer.truncate(bit_len),
+        }
+    }
+
+    /// The number of bits the buffer can hold without reallocating
+    fn capacity(&self) -> usize {
+        match self {
+            CowWriteBuffer::ExpandBorrowed(buffer) => buffer.capacity(),
+            #[cfg(feature = "inline")]
+            CowWriteBuffer::InlineBorrowed(buffer) => buffer.capacity(),
+        }
+    }
+
+    /// Ensure the buffer can hold at least `count` more bits without reallocating
+    fn reserve_capacity(&mut self, count: usize) {
+        match self {
+            CowWriteBuffer::ExpandBorrowed(buffer) => buffer.reserve_capacity(count),
+            #[cfg(feature = "inline")]
+            CowWriteBuffer::InlineBorrowed(buffer) => buffer.reserve_capacity(count),
         }
     }
 }
 
-struct ExpandWriteBuffer<'a, E: Endianness> {
+struct ExpandWriteBuffer<'a, E: Endianness, S: ByteStorage + ?Sized = Vec<u8>> {
     bit_len: usize,
-    bytes: &'a mut Vec<u8>,
+    /// Index of the first byte written by this buffer, any bytes before that were in the vec already
+    byte_start: usize,
+    bytes: &'a mut S,
     endianness: PhantomData<E>,
 }
 
-impl<'a, E: Endianness> ExpandWriteBuffer<'a, E> {
-    fn new(bytes: &'a mut Vec<u8>, _endianness: E) -> Self {
+impl<'a, E: Endianness, S: ByteStorage + ?Sized> ExpandWriteBuffer<'a, E, S> {
+    fn new(bytes: &'a mut S, _endianness: E) -> Self {
         ExpandWriteBuffer {
             bit_len: 0,
+            byte_start: bytes.len(),
             bytes,
             endianness: PhantomData,
         }
@@ -115,6 +370,35 @@ impl<'a, E: Endianness> ExpandWriteBuffer<'a, E> {
         self.bit_len
     }
 
+    /// The bytes written by this buffer
+    fn written_bytes(&self) -> &[u8] {
+        &self.bytes.as_slice()[self.byte_start..]
+    }
+
+    /// The bytes written by this buffer, for patching previously written bits
+    fn written_bytes_mut(&mut self) -> &mut [u8] {
+        let start = self.byte_start;
+        &mut self.bytes.as_mut_slice()[start..]
+    }
+
+    /// Get the written bytes and the number of written bits
+    fn finish(self) -> (&'a [u8], usize) {
+        let bytes: &'a S = self.bytes;
+        (&bytes.as_slice()[self.byte_start..], self.bit_len)
+    }
+
+    /// The number of bits the vec can hold without reallocating, not counting any bytes from before the buffer was created
+    fn capacity(&self) -> usize {
+        (self.bytes.capacity() - self.byte_start) * 8
+    }
+
+    /// Ensure the vec can hold at least `count` more bits without reallocating
+    fn reserve_capacity(&mut self, count: usize) {
+        let required = self.byte_start + (self.bit_len + count).div_ceil(8);
+        self.bytes
+            .reserve(required.saturating_sub(self.bytes.len()));
+    }
+
     /// Push up to an usize worth of bits
     fn push_bits(&mut self, bits: usize, count: usize) {
         debug_assert!(count < USIZE_BITS - 8);
@@ -128,7 +412,7 @@ impl<'a, E: Endianness> ExpandWriteBuffer<'a, E> {
         } else {
             0
         };
-        let merged_byte_count = (count + bit_offset + 7) / 8;
+        let merged_byte_count = (count + bit_offset).div_ceil(8);
 
         if E::is_le() {
             let merged = last_written_byte as usize | bits << bit_offset;
@@ -143,40 +427,109 @@ impl<'a, E: Endianness> ExpandWriteBuffer<'a, E> {
         self.bit_len += count;
     }
 
-    /// Reserve some bits to be written later by splitting of two parts
-    ///
-    /// One fixed size part and one expanding part
-    fn reserve(&mut self, length: usize) -> (FixedWriteBuffer<E>, ExpandWriteBuffer<E>) {
-        let byte_count = (length + 7) / 8;
+    /// Push a single bit, setting it in the last byte when that isn't full yet
+    fn push_bit(&mut self, bit: bool) {
+        let bit_offset = self.bit_len & 7;
+        let shift = if E::is_le() {
+            bit_offset
+        } else {
+            7 - bit_offset
+        };
+        let byte = (bit as u8) << shift;
+        if bit_offset == 0 {
+            self.bytes.extend_from_slice(&[byte]);
+        } else if let Some(last) = self.bytes.as_mut_slice().last_mut() {
+            *last |= byte;
+        }
+        self.bit_len += 1;
+    }
 
+    /// Push whole bytes, merging them with the partially written last byte
+    fn push_bytes(&mut self, bytes: &[u8]) {
         let bit_offset = self.bit_len & 7;
-        let byte_index = self.bit_len / 8;
+        if bit_offset == 0 {
+            self.bytes.extend_from_slice(bytes);
+        } else {
+            let carry = self.bytes.pop().unwrap_or(0);
+            let start = self.bytes.len();
+            self.bytes.resize(start + bytes.len() + 1, 0);
+            shift_into::<E>(
+                bytes,
+                &mut self.bytes.as_mut_slice()[start..],
+                carry,
+                bit_offset,
+            );
+        }
+        self.bit_len += bytes.len() * 8;
+    }
 
-        let end_byte = byte_index + byte_count;
+    /// Remove all bits after `bit_len`
+    fn truncate(&mut self, bit_len: usize) {
+        if bit_len >= self.bit_len {
+            return;
+        }
+        let byte_len = bit_len.div_ceil(8);
+        self.bytes.truncate(self.byte_start + byte_len);
+        clear_bits::<E>(
+            &mut self.bytes.as_mut_slice()[self.byte_start..],
+            bit_len,
+            byte_len * 8,
+        );
+        self.bit_len = bit_len;
+    }
+}
 
-        self.bytes.resize(end_byte, 0);
-        self.bit_len += length;
+/// Mask for the bits `from..to` of a byte, in the order the bits are written
+fn byte_mask<E: Endianness>(from: usize, to: usize) -> u8 {
+    let mask = 0xFFu8 >> (8 - (to - from));
+    if E::is_le() {
+        mask << from
+    } else {
+        mask << (8 - to)
+    }
+}
+
+/// Clear the bits `start..end` of the bytes
+fn clear_bits<E: Endianness>(bytes: &mut [u8], start: usize, end: usize) {
+    if start >= end {
+        return;
+    }
+    let first = start / 8;
+    let last = (end - 1) / 8;
+    if first == last {
+        bytes[first] &= !byte_mask::<E>(start & 7, end - first * 8);
+        return;
+    }
+    bytes[first] &= !byte_mask::<E>(start & 7, 8);
+    bytes[first + 1..last].fill(0);
+    bytes[last] &= !byte_mask::<E>(0, end - last * 8);
+}
 
-        // take a mut slice without telling the borrow checker
-        // this is safe because
-        // 1. the buffers are append only, meaning that the "expand" part can't mess with the reserved bits
-        // 2. the underlying vec can only be used again after both parts have been dropped
-        let bytes = unsafe {
-            let ptr = self.bytes[byte_index..end_byte].as_mut_ptr();
-            std::slice::from_raw_parts_mut(ptr, byte_count)
+/// Replace the bits `pos..pos + count` of the bytes with the lowest `count` bits of `bits`
+fn set_bits<E: Endianness>(bytes: &mut [u8], pos: usize, bits: usize, count: usize) {
+    let mut done = 0;
+    while done < count {
+        let offset = (pos + done) & 7;
+        let chunk = (8 - offset).min(count - done);
+        let shift = if E::is_le() {
+            done
+        } else {
+            count - done - chunk
         };
-        (
-            FixedWriteBuffer::new(bytes, bit_offset, length + bit_offset, E::endianness()),
-            ExpandWriteBuffer {
-                bit_len: self.bit_len,
-                bytes: self.bytes,
-                endianness: PhantomData,
-            },
-        )
+        let value = (bits >> shift) as u8 & (0xFF >> (8 - chunk));
+        let value = if E::is_le() {
+            value << offset
+        } else {
+            value << (8 - offset - chunk)
+        };
+        let byte = &mut bytes[(pos + done) / 8];
+        *byte = *byte & !byte_mask::<E>(offset, offset + chunk) | value;
+        done += chunk;
     }
 }
 
 #[test]
+#[allow(clippy::unusual_byte_groupings)]
 fn test_push_expand_be() {
     use crate::BigEndian;
 
@@ -191,6 +544,7 @@ fn test_push_expand_be() {
 }
 
 #[test]
+#[allow(clippy::unusual_byte_groupings)]
 fn test_push_expand_le() {
     use crate::LittleEndian;
 
@@ -205,177 +559,67 @@ fn test_push_expand_le() {
 }
 
 #[test]
-fn test_push_expand_reserve_be() {
+#[allow(clippy::unusual_byte_groupings)]
+fn test_truncate_shared_byte() {
     use crate::BigEndian;
 
     let mut buffer = vec![];
-    let mut write = ExpandWriteBuffer::new(&mut buffer, BigEndian);
-    write.push_bits(0b1101, 4);
-
-    let (mut reserved, mut rest) = write.reserve(2);
-    rest.push_bits(0b101_01010, 8);
-
-    reserved.push_bits(0b1, 1);
-    reserved.push_bits(0b0, 1);
-
-    assert_eq!(vec![0b1101_1_0_10, 0b101010_00], buffer)
-}
-
-#[test]
-fn test_push_expand_reserve_le() {
-    use crate::LittleEndian;
-
-    let mut buffer = vec![];
-    let mut write = ExpandWriteBuffer::new(&mut buffer, LittleEndian);
+    let mut write = WriteBuffer::new(&mut buffer, BigEndian);
     write.push_bits(0b1101, 4);
-
-    let (mut reserved, mut rest) = write.reserve(2);
-    rest.push_bits(0b101_01010, 8);
-
-    reserved.push_bits(0b1, 1);
-    reserved.push_bits(0b0, 1);
-
-    assert_eq!(vec![0b10_0_1_1101, 0b00101010], buffer)
-}
-
-struct FixedWriteBuffer<'a, E: Endianness> {
-    bit_start: usize,
-    bit_len: usize,
-    bytes: &'a mut [u8],
-    endianness: PhantomData<E>,
-    bit_size: usize,
+    // 3 reserved bits, patched in place after the following bits are written
+    write.push_bits(0, 3);
+    write.push_bits(0b111111, 6);
+
+    let patch = [0b111_00000];
+    let mut patch = BitReadStream::new(BitReadBuffer::new(&patch, BigEndian));
+    write.overwrite(4, patch.read_bits(3).unwrap()).unwrap();
+    let patch = [0b00_000000];
+    let mut patch = BitReadStream::new(BitReadBuffer::new(&patch, BigEndian));
+    write.overwrite(5, patch.read_bits(2).unwrap()).unwrap();
+    assert_eq!(13, write.bit_len());
+    assert_eq!(0b1101_1_0_0_111111, write.read_bits(0, 13));
+
+    // truncating in the middle of a byte clears the bits after the new length
+    write.truncate(5);
+    assert_eq!(5, write.bit_len());
+    write.push_bits(0b0, 1);
+    write.push_bits(0b10, 2);
+    assert_eq!(vec![0b1101_1_0_10], buffer)
 }
 
-impl<'a, E: Endianness> FixedWriteBuffer<'a, E> {
-    fn new(bytes: &'a mut [u8], bit_start: usize, bit_size: usize, _endianness: E) -> Self {
-        FixedWriteBuffer {
-            bit_start,
-            bit_len: bit_start,
-            bytes,
-            endianness: PhantomData,
-            bit_size,
-        }
-    }
-
-    /// The number of written bits in the buffer
-    fn bit_len(&self) -> usize {
-        self.bit_len - self.bit_start
+/// Copy `source` into `target` shifted by `shift` (1 to 7) bits, after the `shift` bits already in `carry`
+///
+/// `target` has to be one byte longer than `source`, the last byte receives the remaining `shift` bits.
+/// The bytes are merged a u64 at a time, which the compiler can vectorize.
+fn shift_into<E: Endianness>(source: &[u8], target: &mut [u8], mut carry: u8, shift: usize) {
+    debug_assert_eq!(source.len() + 1, target.len());
+    debug_assert!(shift > 0 && shift < 8);
+
+    let full = source.len() / 8 * 8;
+    for i in (0..full).step_by(8) {
+        let word: [u8; 8] = source[i..i + 8].try_into().unwrap();
+        let merged = if E::is_le() {
+            let word = u64::from_le_bytes(word);
+            let merged = word << shift | carry as u64;
+            carry = (word >> (64 - shift)) as u8;
+            merged.to_le_bytes()
+        } else {
+            let word = u64::from_be_bytes(word);
+            let merged = word >> shift | (carry as u64) << 56;
+            carry = (word << (8 - shift)) as u8;
+            merged.to_be_bytes()
+        };
+        target[i..i + 8].copy_from_slice(&merged);
     }
-
-    /// Push up to an usize worth of bits
-    fn push_bits(&mut self, bits: usize, count: usize) {
-        debug_assert!(count < USIZE_BITS - 8);
-        assert!(self.bit_len + count <= self.bit_size);
-
-        // ensure there are no stray bits
-        let bits = bits & (usize::MAX >> (USIZE_BITS - count));
-
-        let bit_offset = self.bit_len & 7;
-        let byte_index = self.bit_len / 8;
-        let last_written_byte = self.bytes[byte_index];
-        let merged_byte_count = (count + bit_offset + 7) / 8;
-
+    for i in full..source.len() {
+        let byte = source[i];
         if E::is_le() {
-            let merged = last_written_byte as usize | bits << bit_offset;
-            self.bytes[byte_index..byte_index + merged_byte_count]
-                .copy_from_slice(&merged.to_le_bytes()[0..merged_byte_count]);
+            target[i] = byte << shift | carry;
+            carry = byte >> (8 - shift);
         } else {
-            let merged = ((last_written_byte as usize) << (USIZE_BITS - 8))
-                | (bits << (USIZE_BITS - bit_offset - count));
-            self.bytes[byte_index..byte_index + merged_byte_count]
-                .copy_from_slice(&merged.to_be_bytes()[0..merged_byte_count]);
+            target[i] = byte >> shift | carry;
+            carry = byte << (8 - shift);
         }
-        self.bit_len += count;
-    }
-
-    fn reserve(&mut self, length: usize) -> (FixedWriteBuffer<E>, FixedWriteBuffer<E>) {
-        assert!(self.bit_len + length <= self.bit_size);
-        let byte_count = (length + 7) / 8;
-
-        let bit_offset = self.bit_len & 7;
-        let byte_index = self.bit_len / 8;
-
-        self.bit_len += length;
-
-        // take a mut slice without telling the borrow checker
-        // this is safe because
-        // 1. the buffers are append only, meaning that the last part can't mess with the reserved bits
-        // 2. the underlying vec can only be used again after both parts have been dropped
-        let bytes = unsafe {
-            let ptr = self.bytes[byte_index..byte_count + byte_count].as_mut_ptr();
-            std::slice::from_raw_parts_mut(ptr, byte_count)
-        };
-        (
-            FixedWriteBuffer::new(bytes, bit_offset, length + bit_offset, E::endianness()),
-            FixedWriteBuffer::new(self.bytes, self.bit_len, self.bit_size, E::endianness()),
-        )
     }
-}
-
-#[test]
-fn test_push_fixed_be() {
-    use crate::BigEndian;
-
-    let mut buffer = vec![0; 2];
-    let mut write = FixedWriteBuffer::new(&mut buffer, 0, 16, BigEndian);
-    write.push_bits(0b1101, 4);
-    assert_eq!(4, write.bit_len());
-    write.push_bits(0b1, 1);
-    assert_eq!(5, write.bit_len());
-    write.push_bits(0b0, 1);
-    assert_eq!(6, write.bit_len());
-    write.push_bits(0b101_01010, 8);
-
-    assert_eq!(vec![0b1101_1_0_10, 0b101010_00], buffer)
-}
-
-#[test]
-fn test_push_fixed_le() {
-    use crate::LittleEndian;
-
-    let mut buffer = vec![0; 2];
-    let mut write = FixedWriteBuffer::new(&mut buffer, 0, 16, LittleEndian);
-    write.push_bits(0b1101, 4);
-    assert_eq!(4, write.bit_len());
-    write.push_bits(0b1, 1);
-    assert_eq!(5, write.bit_len());
-    write.push_bits(0b0, 1);
-    assert_eq!(6, write.bit_len());
-    write.push_bits(0b101_01010, 8);
-
-    assert_eq!(vec![0b10_0_1_1101, 0b00101010], buffer)
-}
-
-#[test]
-fn test_push_fixed_reserve_be() {
-    use crate::BigEndian;
-
-    let mut buffer = vec![0; 2];
-    let mut write = FixedWriteBuffer::new(&mut buffer, 0, 16, BigEndian);
-    write.push_bits(0b1101, 4);
-
-    let (mut reserved, mut rest) = write.reserve(2);
-    rest.push_bits(0b101_01010, 8);
-
-    reserved.push_bits(0b1, 1);
-    reserved.push_bits(0b0, 1);
-
-    assert_eq!(vec![0b1101_1_0_10, 0b101010_00], buffer)
-}
-
-#[test]
-fn test_push_fixed_reserve_le() {
-    use crate::LittleEndian;
-
-    let mut buffer = vec![0; 2];
-    let mut write = FixedWriteBuffer::new(&mut buffer, 0, 16, LittleEndian);
-    write.push_bits(0b1101, 4);
-
-    let (mut reserved, mut rest) = write.reserve(2);
-    rest.push_bits(0b101_01010, 8);
-
-    reserved.push_bits(0b1, 1);
-    reserved.push_bits(0b0, 1);
-
-    assert_eq!(vec![0b10_0_1_1101, 0b00101010], buffer)
+    target[source.len()] = carry;
 }
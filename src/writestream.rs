@@ -1,15 +1,37 @@
-use num_traits::{Float, PrimInt};
-use std::mem::size_of;
-use std::ops::{BitOrAssign, BitXor};
+use num_traits::{Float, PrimInt, Signed, WrappingSub};
+use std::mem::{size_of, take};
+use std::ops::{BitOrAssign, BitXor, Range};
 
 use crate::endianness::Endianness;
+use crate::fixup::Fixups;
 use crate::num_traits::{IntoBytes, IsSigned, UncheckedPrimitiveFloat, UncheckedPrimitiveInt};
+use crate::readstream::{check_int_size, checked_bits};
+use crate::scramble::{KeyStream, ScrambleHook};
 use crate::writebuffer::WriteBuffer;
-use crate::{BitError, BitReadStream, BitWrite, BitWriteSized, Result};
+use crate::{
+    BitError, BitHasher, BitReadBuffer, BitReadStream, BitWrite, BitWriteSized, Label,
+    LabelPositions, Result,
+};
 use std::fmt::Debug;
 
 const USIZE_SIZE: usize = size_of::<usize>();
 const USIZE_BITS: usize = USIZE_SIZE * 8;
+/// The maximum number of times [`BitWriteStream::write_with_offsets`] writes the data before giving up
+const MAX_OFFSET_PASSES: usize = 8;
+
+/// What to do when a string doesn't fit in a fixed length field,
+/// see [`write_string_fixed`](BitWriteStream::write_string_fixed)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum TruncatePolicy {
+    /// Return a [`StringToLong`](crate::BitError::StringToLong) error
+    #[default]
+    Error,
+    /// Cut the string at the last character boundary that fits, so the field always contains valid utf8
+    Truncate,
+    /// Cut the string at exactly the length of the field, possibly inside a multi byte character
+    TruncateLossy,
+}
 
 /// Stream that provides an a way to write non bit aligned adata
 ///
@@ -29,12 +51,18 @@ const USIZE_BITS: usize = USIZE_SIZE * 8;
 /// # }
 /// ```
 ///
+/// # Thread safety
+///
+/// The stream is `Send`, attached hashers and scramblers are required to be `Send` so this holds with hooks attached.
+///
 /// [`BitBuffer`]: struct.BitBuffer.html
 pub struct BitWriteStream<'a, E>
 where
     E: Endianness,
 {
     buffer: WriteBuffer<'a, E>,
+    fixups: Fixups,
+    strict: bool,
 }
 
 impl<'a, E> BitWriteStream<'a, E>
@@ -54,8 +82,52 @@ where
     pub fn new(data: &'a mut Vec<u8>, endianness: E) -> Self {
         BitWriteStream {
             buffer: WriteBuffer::new(data, endianness),
+            fixups: Fixups::default(),
+            strict: false,
+        }
+    }
+
+    /// Create a new write stream backed by inline storage
+    ///
+    /// No heap allocations are made unless more than `N` bytes are written.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bitbuffer::inline::InlineBuffer;
+    /// use bitbuffer::{BitWriteStream, LittleEndian};
+    ///
+    /// let mut data = InlineBuffer::<64>::new();
+    /// let mut stream = BitWriteStream::new_inline(&mut data, LittleEndian);
+    /// ```
+    #[cfg(feature = "inline")]
+    pub fn new_inline<const N: usize>(
+        data: &'a mut crate::inline::InlineBuffer<N>,
+        endianness: E,
+    ) -> Self {
+        BitWriteStream {
+            buffer: WriteBuffer::new_inline(data, endianness),
+            fixups: Fixups::default(),
+            strict: false,
         }
     }
+
+    /// Create a new write stream with space for at least `capacity` bits
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bitbuffer::{BitWriteStream, LittleEndian};
+    ///
+    /// let mut data = Vec::new();
+    /// let stream = BitWriteStream::with_capacity(&mut data, 100, LittleEndian);
+    /// assert!(stream.capacity() >= 100);
+    /// ```
+    pub fn with_capacity(data: &'a mut Vec<u8>, capacity: usize, endianness: E) -> Self {
+        let mut stream = BitWriteStream::new(data, endianness);
+        stream.reserve(capacity);
+        stream
+    }
 }
 
 impl<'a, E> BitWriteStream<'a, E>
@@ -69,7 +141,77 @@ where
 
     /// The number of written bytes in the buffer
     pub fn byte_len(&self) -> usize {
-        (self.buffer.bit_len() + 7) / 8
+        self.buffer.bit_len().div_ceil(8)
+    }
+
+    /// Read up to an usize worth of previously written bits
+    pub(crate) fn read_written_bits(&self, pos: usize, count: usize) -> usize {
+        self.buffer.read_bits(pos, count)
+    }
+
+    /// The number of bits the stream can hold without reallocating the backing vec
+    pub fn capacity(&self) -> usize {
+        self.buffer.capacity()
+    }
+
+    /// Reserve space for at least `additional` more bits to be written without reallocating the backing vec
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use bitbuffer::Result;
+    /// use bitbuffer::{BitWriteStream, LittleEndian};
+    ///
+    /// # fn main() -> Result<()> {
+    /// let mut data = Vec::new();
+    /// let mut stream = BitWriteStream::new(&mut data, LittleEndian);
+    /// stream.write_int(1u8, 3)?;
+    /// stream.reserve(1000);
+    /// assert!(stream.capacity() >= 1003);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn reserve(&mut self, additional: usize) {
+        self.buffer.reserve_capacity(additional)
+    }
+
+    /// Enable or disable checking that written integers fit in the requested number of bits
+    ///
+    /// By default [`write_int`](Self::write_int) silently truncates values that have significant bits above the
+    /// requested number of bits. In strict mode an error is returned instead, catching encoder bugs at the write
+    /// site instead of as corrupt data when decoding.
+    /// Signed values are checked as two's complement, so `-1` fits in a single bit.
+    ///
+    /// To only pay for the checks in debug builds, use `stream.set_strict(cfg!(debug_assertions))`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use bitbuffer::Result;
+    /// use bitbuffer::{BitError, BitWriteStream, LittleEndian};
+    ///
+    /// # fn main() -> Result<()> {
+    /// let mut data = Vec::new();
+    /// let mut stream = BitWriteStream::new(&mut data, LittleEndian);
+    /// stream.write_int(0b1_0101u8, 4)?;
+    /// stream.set_strict(true);
+    /// stream.write_int(0b0101u8, 4)?;
+    /// assert!(matches!(
+    ///     stream.write_int(0b1_0101u8, 4),
+    ///     Err(BitError::ValueOutOfRange { required: 5, bits: 4 })
+    /// ));
+    /// assert_eq!(data, [0b0101_0101]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn set_strict(&mut self, strict: bool) {
+        self.strict = strict;
+    }
+
+    /// Whether the stream checks that written integers fit in the requested number of bits,
+    /// see [`set_strict`](Self::set_strict)
+    pub fn is_strict(&self) -> bool {
+        self.strict
     }
 
     fn push_non_fit_bits<I>(&mut self, bits: I, count: usize)
@@ -104,12 +246,47 @@ where
     /// ```
     #[inline]
     pub fn write_bool(&mut self, value: bool) -> Result<()> {
-        self.push_bits(value as usize, 1);
+        self.write_bit(value)
+    }
+
+    /// Write a single bit into the buffer
+    ///
+    /// This is the same as [`write_bool`](Self::write_bool), it sets the bit directly without going through the
+    /// integer writing code.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use bitbuffer::{BigEndian, BitWriteStream, Result};
+    /// #
+    /// # fn main() -> Result<()> {
+    /// let mut data = Vec::new();
+    /// let mut stream = BitWriteStream::new(&mut data, BigEndian);
+    /// stream.write_bit(true)?;
+    /// stream.write_bit(false)?;
+    /// stream.write_bit(true)?;
+    /// assert_eq!(data, [0b1010_0000]);
+    /// #
+    /// #     Ok(())
+    /// # }
+    /// ```
+    #[inline]
+    pub fn write_bit(&mut self, value: bool) -> Result<()> {
+        self.buffer.push_bit(value);
         Ok(())
     }
 
     /// Write an integer into the buffer
     ///
+    /// Only the lowest `count` bits of the value are written,
+    /// unless the stream is in [strict mode](Self::set_strict).
+    ///
+    /// # Errors
+    ///
+    /// - [`ReadError::TooManyBits`]: to many bits requested for the chosen integer type
+    /// - [`ReadError::ValueOutOfRange`]: the stream is in strict mode and the value doesn't fit in `count` bits,
+    ///   nothing is written
+    ///
     /// # Examples
     ///
     /// ```
@@ -125,6 +302,9 @@ where
     /// #     Ok(())
     /// # }
     /// ```
+    ///
+    /// [`ReadError::TooManyBits`]: enum.ReadError.html#variant.TooManyBits
+    /// [`ReadError::ValueOutOfRange`]: enum.ReadError.html#variant.ValueOutOfRange
     #[inline]
     pub fn write_int<T>(&mut self, value: T, count: usize) -> Result<()>
     where
@@ -139,6 +319,16 @@ where
             });
         }
 
+        if self.strict {
+            let required = int_bit_size(value);
+            if required > count {
+                return Err(BitError::ValueOutOfRange {
+                    required,
+                    bits: count,
+                });
+            }
+        }
+
         if type_bit_size < USIZE_BITS {
             self.push_bits(value.into_usize_unchecked(), count);
         } else {
@@ -148,6 +338,186 @@ where
         Ok(())
     }
 
+    /// Write a signed integer into the buffer as two's complement, checking that it fits in `count` bits
+    ///
+    /// Unlike [`write_int`](Self::write_int), which silently truncates values that don't fit,
+    /// an error is returned if the value is outside the range `-2^(count-1)..2^(count-1)`.
+    /// See [`read_signed`](BitReadStream::read_signed) for reading the value back.
+    ///
+    /// # Errors
+    ///
+    /// - [`ReadError::TooManyBits`]: to many bits requested for the chosen integer type
+    /// - [`ReadError::ValueOutOfRange`]: the value doesn't fit in `count` bits, nothing is written
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use bitbuffer::{BigEndian, BitError, Result};
+    /// #
+    /// # fn main() -> Result<()> {
+    /// # use bitbuffer::BitWriteStream;
+    ///
+    /// let mut data = Vec::new();
+    /// let mut stream = BitWriteStream::new(&mut data, BigEndian);
+    /// stream.write_signed(-8i8, 4)?;
+    /// stream.write_signed(7i8, 4)?;
+    /// assert!(matches!(
+    ///     stream.write_signed(8i8, 4),
+    ///     Err(BitError::ValueOutOfRange { required: 5, bits: 4 })
+    /// ));
+    /// assert_eq!(data, [0b1000_0111]);
+    /// #
+    /// #     Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`ReadError::TooManyBits`]: enum.ReadError.html#variant.TooManyBits
+    /// [`ReadError::ValueOutOfRange`]: enum.ReadError.html#variant.ValueOutOfRange
+    #[inline]
+    pub fn write_signed<T>(&mut self, value: T, count: usize) -> Result<()>
+    where
+        T: PrimInt
+            + BitOrAssign
+            + IsSigned
+            + UncheckedPrimitiveInt
+            + BitXor
+            + IntoBytes
+            + Debug
+            + Signed,
+    {
+        check_int_size::<T>(count)?;
+        let required = signed_bit_size(value.into_i128_unchecked());
+        if required > count {
+            return Err(BitError::ValueOutOfRange {
+                required,
+                bits: count,
+            });
+        }
+        self.write_int(value, count)
+    }
+
+    /// Write an integer into the buffer as offset binary
+    ///
+    /// Offset binary stores `value + bias` as unsigned integer,
+    /// see [`read_int_offset_binary`](BitReadStream::read_int_offset_binary).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use bitbuffer::{BigEndian, Result};
+    /// #
+    /// # fn main() -> Result<()> {
+    /// # use bitbuffer::BitWriteStream;
+    ///
+    /// let mut data = Vec::new();
+    /// let mut stream = BitWriteStream::new(&mut data, BigEndian);
+    /// stream.write_int_offset_binary(-2048i16, 12, 2048)?;
+    /// stream.write_int_offset_binary(1i16, 12, 2048)?;
+    /// assert_eq!(data, [0b0000_0000, 0b0000_1000, 0b0000_0001]);
+    /// #
+    /// #     Ok(())
+    /// # }
+    /// ```
+    #[inline]
+    pub fn write_int_offset_binary<T>(&mut self, value: T, count: usize, bias: i128) -> Result<()>
+    where
+        T: PrimInt + BitOrAssign + IsSigned + UncheckedPrimitiveInt + BitXor + IntoBytes + Debug,
+    {
+        check_int_size::<T>(count)?;
+        let raw = value.into_i128_unchecked().wrapping_add(bias) as u128;
+        self.write_int(raw, count)
+    }
+
+    /// Write an integer into the buffer as sign-magnitude
+    ///
+    /// The most significant bit of the written bits is the sign, the remaining bits are the magnitude.
+    /// Magnitudes that don't fit in the remaining bits are truncated unless the stream is in [strict mode](Self::set_strict).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use bitbuffer::{BigEndian, Result};
+    /// #
+    /// # fn main() -> Result<()> {
+    /// # use bitbuffer::BitWriteStream;
+    ///
+    /// let mut data = Vec::new();
+    /// let mut stream = BitWriteStream::new(&mut data, BigEndian);
+    /// stream.write_int_sign_magnitude(-3i8, 4)?;
+    /// stream.write_int_sign_magnitude(3i8, 4)?;
+    /// assert_eq!(data, [0b1011_0011]);
+    /// #
+    /// #     Ok(())
+    /// # }
+    /// ```
+    #[inline]
+    pub fn write_int_sign_magnitude<T>(&mut self, value: T, count: usize) -> Result<()>
+    where
+        T: PrimInt + BitOrAssign + IsSigned + UncheckedPrimitiveInt + BitXor + IntoBytes + Debug,
+    {
+        check_int_size::<T>(count)?;
+        if count == 0 {
+            return Ok(());
+        }
+        let (negative, magnitude) = if value < T::zero() {
+            (true, value.into_i128_unchecked().unsigned_abs())
+        } else {
+            (false, value.into_u128_unchecked())
+        };
+        let sign_bit = 1u128 << (count - 1);
+        if self.strict && magnitude >= sign_bit {
+            return Err(BitError::ValueOutOfRange {
+                required: (u128::BITS - magnitude.leading_zeros()) as usize + 1,
+                bits: count,
+            });
+        }
+        let raw = (magnitude & (sign_bit - 1)) | if negative { sign_bit } else { 0 };
+        self.write_int(raw, count)
+    }
+
+    /// Write integers delta encoded, using `bits` bits for every delta
+    ///
+    /// Every value is written as the difference from the previous value, starting from `initial`,
+    /// see [`read_deltas`](BitReadStream::read_deltas).
+    /// Like with [`write_int`](Self::write_int), deltas that don't fit in `bits` bits are truncated
+    /// unless the stream is in [strict mode](Self::set_strict).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use bitbuffer::{BigEndian, Result};
+    /// #
+    /// # fn main() -> Result<()> {
+    /// # use bitbuffer::BitWriteStream;
+    ///
+    /// let mut data = Vec::new();
+    /// let mut stream = BitWriteStream::new(&mut data, BigEndian);
+    /// stream.write_deltas(&[101i32, 103, 102, 102], 4, 100)?;
+    /// assert_eq!(data, [0b0001_0010, 0b1111_0000]);
+    /// #
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn write_deltas<T>(&mut self, values: &[T], bits: usize, initial: T) -> Result<()>
+    where
+        T: PrimInt
+            + BitOrAssign
+            + IsSigned
+            + UncheckedPrimitiveInt
+            + BitXor
+            + IntoBytes
+            + Debug
+            + WrappingSub,
+    {
+        check_int_size::<T>(bits)?;
+        let mut previous = initial;
+        for value in values {
+            self.write_int(value.wrapping_sub(&previous), bits)?;
+            previous = *value;
+        }
+        Ok(())
+    }
+
     /// Write a float into the buffer
     ///
     /// # Examples
@@ -183,128 +553,1262 @@ where
         Ok(())
     }
 
-    /// Write a number of bytes into the buffer
+    /// Write a float quantized to a `bits` bit integer between `min` and `max`
+    ///
+    /// The value is mapped onto the range of a `bits` bit integer, rounding to the nearest step.
+    /// Values outside of `min..=max` are clamped and `NaN` is written as `min`.
+    /// See [`read_quantized_float`](crate::BitReadStream::read_quantized_float).
+    ///
+    /// # Errors
+    ///
+    /// - [`ReadError::TooManyBits`]: `bits` is larger than 32
     ///
     /// # Examples
     ///
     /// ```
-    /// # use bitbuffer::{BitReadBuffer, LittleEndian, Result};
+    /// # use bitbuffer::{BitReadBuffer, BitReadStream, LittleEndian, Result};
     /// #
     /// # fn main() -> Result<()> {
-    /// # use bitbuffer::{BitWriteStream, LittleEndian};
+    /// # use bitbuffer::BitWriteStream;
     ///
     /// let mut data = Vec::new();
     /// let mut stream = BitWriteStream::new(&mut data, LittleEndian);
-    /// stream.write_bytes(&[0, 1, 2 ,3])?;
+    /// stream.write_quantized_float(0.3f32, 10, -1.0, 1.0)?;
+    ///
+    /// let mut stream = BitReadStream::new(BitReadBuffer::new(&data, LittleEndian));
+    /// let value = stream.read_quantized_float::<f32>(10, -1.0, 1.0)?;
+    /// assert!((value - 0.3).abs() <= 1.0 / 1023.0);
     /// #
     /// #     Ok(())
     /// # }
     /// ```
+    ///
+    /// [`ReadError::TooManyBits`]: enum.ReadError.html#variant.TooManyBits
     #[inline]
-    pub fn write_bytes(&mut self, bytes: &[u8]) -> Result<()> {
-        bytes
-            .iter()
-            .copied()
-            .for_each(|chunk| self.push_bits(chunk as usize, 8));
-        Ok(())
-    }
-
-    /// Write bits from a read stream into the buffer
-    #[inline]
-    pub fn write_bits(&mut self, bits: &BitReadStream<E>) -> Result<()> {
-        let mut bits = bits.clone();
-        let bit_offset = self.bit_len() % 8;
-        if bit_offset > 0 {
-            let start = bits.read_int::<u8>(8 - bit_offset)?;
-            self.push_bits(start as usize, 8 - bit_offset);
-        }
-
-        while bits.bits_left() > 32 {
-            let chunk = bits.read::<u32>()?;
-            self.push_bits(chunk as usize, 32);
+    pub fn write_quantized_float<T>(&mut self, value: T, bits: usize, min: T, max: T) -> Result<()>
+    where
+        T: Float + UncheckedPrimitiveFloat,
+    {
+        if bits > 32 {
+            return Err(BitError::TooManyBits {
+                requested: bits,
+                max: 32,
+            });
         }
-
-        if bits.bits_left() > 0 {
-            let end_bits = bits.bits_left();
-            let end = bits.read_int::<u32>(end_bits)?;
-            self.push_bits(end as usize, end_bits);
+        if bits == 0 {
+            return Ok(());
         }
-        Ok(())
+        let steps = (1u64 << bits) - 1;
+        let (value, min, max) = (
+            value.to_f64().unwrap(),
+            min.to_f64().unwrap(),
+            max.to_f64().unwrap(),
+        );
+        let normalized = ((value - min) / (max - min)).clamp(0.0, 1.0);
+        // `as` maps NaN to 0
+        let quantized = (normalized * steps as f64).round() as u32;
+        self.write_int(quantized, bits)
     }
 
-    /// Write a string into the buffer
+    /// Write an SQLite style variable length integer
+    ///
+    /// See [`read_varint_sqlite`](BitReadStream::read_varint_sqlite) for the format.
     ///
     /// # Examples
     ///
     /// ```
-    /// # use bitbuffer::{BitReadBuffer, LittleEndian, Result};
+    /// # use bitbuffer::{BigEndian, Result};
     /// #
     /// # fn main() -> Result<()> {
-    /// # use bitbuffer::{BitWriteStream, LittleEndian};
+    /// # use bitbuffer::BitWriteStream;
     ///
     /// let mut data = Vec::new();
-    /// let mut stream = BitWriteStream::new(&mut data, LittleEndian);
-    /// stream.write_string("zero terminated string", None)?;
-    /// stream.write_string("fixed size string, zero padded", Some(64))?;
+    /// let mut stream = BitWriteStream::new(&mut data, BigEndian);
+    /// stream.write_varint_sqlite(127)?;
+    /// stream.write_varint_sqlite(128)?;
+    /// assert_eq!(data, [0x7F, 0x81, 0x00]);
     /// #
     /// #     Ok(())
     /// # }
     /// ```
-    pub fn write_string(&mut self, string: &str, length: Option<usize>) -> Result<()> {
-        match length {
-            Some(length) => {
-                if length < string.len() {
-                    return Err(BitError::StringToLong {
-                        string_length: string.len(),
-                        requested_length: length,
-                    });
+    pub fn write_varint_sqlite(&mut self, mut value: u64) -> Result<()> {
+        let mut bytes = [0; 9];
+        let mut start = bytes.len();
+        if value >> 56 != 0 {
+            start -= 1;
+            bytes[start] = value as u8;
+            value >>= 8;
+            for _ in 0..8 {
+                start -= 1;
+                bytes[start] = 0x80 | (value & 0x7F) as u8;
+                value >>= 7;
+            }
+        } else {
+            loop {
+                start -= 1;
+                bytes[start] = (value & 0x7F) as u8;
+                if start < bytes.len() - 1 {
+                    bytes[start] |= 0x80;
                 }
-                self.write_bytes(&string.as_bytes())?;
-                for _ in 0..(length - string.len()) {
-                    self.push_bits(0, 8)
+                value >>= 7;
+                if value == 0 {
+                    break;
                 }
             }
-            None => {
-                self.write_bytes(&string.as_bytes())?;
-                self.push_bits(0, 8)
-            }
         }
-        Ok(())
-    }
-
-    /// Write the type to stream
-    #[inline]
-    pub fn write<T: BitWrite<E>>(&mut self, value: &T) -> Result<()> {
-        value.write(self)
-    }
-
-    /// Write the type to stream
-    #[inline]
-    pub fn write_sized<T: BitWriteSized<E>>(&mut self, value: &T, length: usize) -> Result<()> {
-        value.write_sized(self, length)
+        self.write_bytes(&bytes[start..])
     }
 
-    /// Reserve some bits to be written later by splitting of two parts
+    /// Write a git style offset variable length integer
+    ///
+    /// See [`read_varint_git_offset`](BitReadStream::read_varint_git_offset) for the format.
+    ///
+    /// # Examples
     ///
-    /// This allows skipping a few bits to write later
-    fn reserve(&mut self, count: usize) -> (BitWriteStream<E>, BitWriteStream<E>) {
-        let (head, tail) = self.buffer.reserve(count);
-        (
-            BitWriteStream { buffer: head },
-            BitWriteStream { buffer: tail },
-        )
+    /// ```
+    /// # use bitbuffer::{BigEndian, Result};
+    /// #
+    /// # fn main() -> Result<()> {
+    /// # use bitbuffer::BitWriteStream;
+    ///
+    /// let mut data = Vec::new();
+    /// let mut stream = BitWriteStream::new(&mut data, BigEndian);
+    /// stream.write_varint_git_offset(127)?;
+    /// stream.write_varint_git_offset(128)?;
+    /// assert_eq!(data, [0x7F, 0x80, 0x00]);
+    /// #
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn write_varint_git_offset(&mut self, mut value: u64) -> Result<()> {
+        let mut bytes = [0; 10];
+        let mut start = bytes.len() - 1;
+        bytes[start] = (value & 0x7F) as u8;
+        value >>= 7;
+        while value != 0 {
+            value -= 1;
+            start -= 1;
+            bytes[start] = 0x80 | (value & 0x7F) as u8;
+            value >>= 7;
+        }
+        self.write_bytes(&bytes[start..])
     }
 
-    /// Write the length of a section before the section
-    pub fn reserve_length<F: Fn(&mut BitWriteStream<E>) -> Result<()>>(
-        &mut self,
-        length_bit_size: usize,
-        body_fn: F,
-    ) -> Result<()> {
-        let (mut head, mut tail) = self.reserve(length_bit_size);
-        let start = tail.bit_len();
-        body_fn(&mut tail)?;
-        let end = tail.bit_len();
-        head.write_sized(&(end - start), length_bit_size)
+    /// Write a MIDI style variable length quantity
+    ///
+    /// See [`read_vlq`](BitReadStream::read_vlq) for the format.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use bitbuffer::{BigEndian, Result};
+    /// #
+    /// # fn main() -> Result<()> {
+    /// # use bitbuffer::BitWriteStream;
+    ///
+    /// let mut data = Vec::new();
+    /// let mut stream = BitWriteStream::new(&mut data, BigEndian);
+    /// stream.write_vlq(0x7F)?;
+    /// stream.write_vlq(0x80)?;
+    /// assert_eq!(data, [0x7F, 0x81, 0x00]);
+    /// #
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn write_vlq(&mut self, mut value: u64) -> Result<()> {
+        let mut bytes = [0; 10];
+        let mut start = bytes.len() - 1;
+        bytes[start] = (value & 0x7F) as u8;
+        value >>= 7;
+        while value != 0 {
+            start -= 1;
+            bytes[start] = 0x80 | (value & 0x7F) as u8;
+            value >>= 7;
+        }
+        self.write_bytes(&bytes[start..])
+    }
+
+    /// Write a QUIC variable length integer, as defined in RFC 9000
+    ///
+    /// The shortest encoding that fits the value is used.
+    /// See [`read_quic_varint`](BitReadStream::read_quic_varint) for the format.
+    ///
+    /// # Errors
+    ///
+    /// - [`ReadError::TooManyBits`]: the value doesn't fit in 62 bits
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use bitbuffer::{BigEndian, Result};
+    /// #
+    /// # fn main() -> Result<()> {
+    /// # use bitbuffer::BitWriteStream;
+    ///
+    /// let mut data = Vec::new();
+    /// let mut stream = BitWriteStream::new(&mut data, BigEndian);
+    /// stream.write_quic_varint(37)?;
+    /// stream.write_quic_varint(15_293)?;
+    /// assert_eq!(data, [0x25, 0x7B, 0xBD]);
+    /// #
+    /// #     Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`ReadError::TooManyBits`]: enum.ReadError.html#variant.TooManyBits
+    pub fn write_quic_varint(&mut self, value: u64) -> Result<()> {
+        let (prefix, len) = match value {
+            0..=0x3F => (0, 1),
+            0x40..=0x3FFF => (1, 2),
+            0x4000..=0x3FFF_FFFF => (2, 4),
+            0x4000_0000..=0x3FFF_FFFF_FFFF_FFFF => (3, 8),
+            _ => {
+                return Err(BitError::TooManyBits {
+                    requested: 64 - value.leading_zeros() as usize,
+                    max: 62,
+                })
+            }
+        };
+        let mut bytes = value.to_be_bytes();
+        bytes[8 - len] |= prefix << 6;
+        self.write_bytes(&bytes[8 - len..])
+    }
+
+    /// Write a number of bytes into the buffer
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use bitbuffer::{BitReadBuffer, LittleEndian, Result};
+    /// #
+    /// # fn main() -> Result<()> {
+    /// # use bitbuffer::{BitWriteStream, LittleEndian};
+    ///
+    /// let mut data = Vec::new();
+    /// let mut stream = BitWriteStream::new(&mut data, LittleEndian);
+    /// stream.write_bytes(&[0, 1, 2 ,3])?;
+    /// #
+    /// #     Ok(())
+    /// # }
+    /// ```
+    #[inline]
+    pub fn write_bytes(&mut self, bytes: &[u8]) -> Result<()> {
+        self.buffer.push_bytes(bytes);
+        Ok(())
+    }
+
+    /// Write bits from a read stream into the buffer
+    #[inline]
+    pub fn write_bits(&mut self, bits: &BitReadStream<E>) -> Result<()> {
+        let mut bits = bits.clone();
+        let bit_offset = self.bit_len() % 8;
+        if bit_offset > 0 {
+            let start = bits.read_int::<u8>(8 - bit_offset)?;
+            self.push_bits(start as usize, 8 - bit_offset);
+        }
+
+        while bits.bits_left() > 32 {
+            let chunk = bits.read::<u32>()?;
+            self.push_bits(chunk as usize, 32);
+        }
+
+        if bits.bits_left() > 0 {
+            let end_bits = bits.bits_left();
+            let end = bits.read_int::<u32>(end_bits)?;
+            self.push_bits(end as usize, end_bits);
+        }
+        Ok(())
+    }
+
+    /// Append a copy of bits that have been written earlier in the stream
+    ///
+    /// The range may extend past the current end of the stream, in which case the bits written by the copy
+    /// itself are repeated, the same as with back references in LZ77 style compression.
+    ///
+    /// The bits are copied as they are stored in the output,
+    /// if a scrambler is attached the copied bits are scrambled again.
+    ///
+    /// # Errors
+    ///
+    /// - [`ReadError::IndexOutOfBounds`]: the range starts at or after the current end of the stream
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use bitbuffer::{BigEndian, Result};
+    /// #
+    /// # fn main() -> Result<()> {
+    /// # use bitbuffer::BitWriteStream;
+    ///
+    /// let mut data = Vec::new();
+    /// let mut stream = BitWriteStream::new(&mut data, BigEndian);
+    /// stream.write_int(0b101u8, 3)?;
+    /// stream.copy_within(0..3)?;
+    /// stream.write_int(0b11u8, 2)?;
+    /// // repeat the last 5 bits twice
+    /// stream.copy_within(3..13)?;
+    /// assert_eq!(data, [0b1011_0111, 0b1011_1101, 0b1100_0000]);
+    /// #
+    /// #     Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`ReadError::IndexOutOfBounds`]: enum.ReadError.html#variant.IndexOutOfBounds
+    pub fn copy_within(&mut self, range: Range<usize>) -> Result<()> {
+        if range.is_empty() {
+            return Ok(());
+        }
+        let distance = match self.bit_len().checked_sub(range.start) {
+            Some(distance) if distance > 0 => distance,
+            _ => {
+                return Err(BitError::IndexOutOfBounds {
+                    pos: range.start,
+                    size: self.bit_len(),
+                })
+            }
+        };
+
+        let mut pos = range.start;
+        while pos < range.end {
+            // only read bits that have already been written
+            let count = (range.end - pos).min(distance).min(32);
+            let bits = self.buffer.read_bits(pos, count);
+            self.push_bits(bits, count);
+            pos += count;
+        }
+        Ok(())
+    }
+
+    /// Append a copy of `length` bytes starting `distance` bytes before the end of the stream
+    ///
+    /// This is the back reference of LZ77 style compression like deflate, where the output window is the
+    /// data written so far. When `length` is larger than `distance` the copied bytes are repeated.
+    ///
+    /// # Errors
+    ///
+    /// - [`ReadError::IndexOutOfBounds`]: `distance` is zero or points before the start of the stream
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use bitbuffer::{LittleEndian, Result};
+    /// #
+    /// # fn main() -> Result<()> {
+    /// # use bitbuffer::BitWriteStream;
+    ///
+    /// let mut data = Vec::new();
+    /// let mut stream = BitWriteStream::new(&mut data, LittleEndian);
+    /// stream.write_bytes(b"abc")?;
+    /// stream.copy_back(2, 5)?;
+    /// assert_eq!(data, b"abcbcbcb");
+    /// #
+    /// #     Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`ReadError::IndexOutOfBounds`]: enum.ReadError.html#variant.IndexOutOfBounds
+    pub fn copy_back(&mut self, distance: usize, length: usize) -> Result<()> {
+        let start = distance
+            .checked_mul(8)
+            .and_then(|distance| self.bit_len().checked_sub(distance))
+            .filter(|_| distance > 0)
+            .ok_or(BitError::IndexOutOfBounds {
+                pos: distance,
+                size: self.bit_len() / 8,
+            })?;
+        let end = start
+            .checked_add(checked_bits(length, 8)?)
+            .ok_or(BitError::SizeOverflow {
+                count: length,
+                element_bits: 8,
+            })?;
+        self.copy_within(start..end)
+    }
+
+    /// Insert bits at a position in the stream, moving all bits after the position
+    ///
+    /// The inserted and moved bits are written directly to the output, they are not passed to an attached hasher
+    /// or scrambler.
+    ///
+    /// # Errors
+    ///
+    /// - [`ReadError::IndexOutOfBounds`]: the position is after the end of the stream
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use bitbuffer::{BigEndian, Result};
+    /// #
+    /// # fn main() -> Result<()> {
+    /// # use bitbuffer::{BitReadBuffer, BitReadStream, BitWriteStream};
+    ///
+    /// let mut data = Vec::new();
+    /// let mut stream = BitWriteStream::new(&mut data, BigEndian);
+    /// stream.write_int(0b1111u8, 4)?;
+    /// stream.write_int(0b0000u8, 4)?;
+    ///
+    /// let inserted = [0b1010_0000];
+    /// let inserted = BitReadStream::new(BitReadBuffer::new(&inserted, BigEndian));
+    /// stream.insert_bits(4, &inserted.clone().read_bits(3)?)?;
+    /// assert_eq!(stream.bit_len(), 11);
+    /// assert_eq!(data, [0b1111_1010, 0b0000_0000]);
+    /// #
+    /// #     Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`ReadError::IndexOutOfBounds`]: enum.ReadError.html#variant.IndexOutOfBounds
+    pub fn insert_bits(&mut self, position: usize, bits: &BitReadStream<E>) -> Result<()> {
+        self.replace_bits(position..position, bits)
+    }
+
+    /// Replace a range of bits in the stream, moving all bits after the range
+    ///
+    /// The replacement can be shorter or longer than the replaced range.
+    ///
+    /// The replacement and moved bits are written directly to the output, they are not passed to an attached hasher
+    /// or scrambler.
+    ///
+    /// # Errors
+    ///
+    /// - [`ReadError::IndexOutOfBounds`]: the range ends after the end of the stream
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use bitbuffer::{BigEndian, Result};
+    /// #
+    /// # fn main() -> Result<()> {
+    /// # use bitbuffer::{BitReadBuffer, BitReadStream, BitWriteStream};
+    ///
+    /// let mut data = Vec::new();
+    /// let mut stream = BitWriteStream::new(&mut data, BigEndian);
+    /// // placeholder for a length field
+    /// stream.write_int(0u8, 1)?;
+    /// stream.write_string("foo", None)?;
+    ///
+    /// // replace the placeholder once the length is known
+    /// let mut length = Vec::new();
+    /// BitWriteStream::new(&mut length, BigEndian).write_int(4u16, 16)?;
+    /// let length = BitReadStream::new(BitReadBuffer::new(&length, BigEndian));
+    /// stream.replace_bits(0..1, &length)?;
+    /// assert_eq!(data, [0, 4, b'f', b'o', b'o', 0]);
+    /// #
+    /// #     Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`ReadError::IndexOutOfBounds`]: enum.ReadError.html#variant.IndexOutOfBounds
+    pub fn replace_bits(&mut self, range: Range<usize>, bits: &BitReadStream<E>) -> Result<()> {
+        if range.end > self.bit_len() || range.start > range.end {
+            return Err(BitError::IndexOutOfBounds {
+                pos: range.end,
+                size: self.bit_len(),
+            });
+        }
+        self.buffer.splice(range, bits.clone())
+    }
+
+    /// Overwrite previously written bits starting at `position`, without moving any other bits
+    ///
+    /// This allows filling in a placeholder, like a length or checksum, once the value is known.
+    /// Unlike [`replace_bits`](BitWriteStream::replace_bits) only the overwritten bits are touched,
+    /// so the cost doesn't depend on the number of bits written after the placeholder.
+    ///
+    /// The bits are written directly to the output, they are not passed to an attached hasher or scrambler.
+    ///
+    /// # Errors
+    ///
+    /// - [`ReadError::IndexOutOfBounds`]: the overwritten bits extend past the end of the stream
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use bitbuffer::{BigEndian, Result};
+    /// #
+    /// # fn main() -> Result<()> {
+    /// # use bitbuffer::{BitReadBuffer, BitReadStream, BitWriteStream};
+    ///
+    /// let mut data = Vec::new();
+    /// let mut stream = BitWriteStream::new(&mut data, BigEndian);
+    /// // placeholder for a length field
+    /// let length_pos = stream.bit_len();
+    /// stream.write_int(0u16, 16)?;
+    /// stream.write_string("foo", None)?;
+    ///
+    /// // fill in the placeholder once the length is known
+    /// let mut length = Vec::new();
+    /// BitWriteStream::new(&mut length, BigEndian).write_int(4u16, 16)?;
+    /// let length = BitReadStream::new(BitReadBuffer::new(&length, BigEndian));
+    /// stream.overwrite_bits(length_pos, &length)?;
+    /// assert_eq!(data, [0, 4, b'f', b'o', b'o', 0]);
+    /// #
+    /// #     Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`ReadError::IndexOutOfBounds`]: enum.ReadError.html#variant.IndexOutOfBounds
+    pub fn overwrite_bits(&mut self, position: usize, bits: &BitReadStream<E>) -> Result<()> {
+        let end = position.saturating_add(bits.bits_left());
+        if end > self.bit_len() {
+            return Err(BitError::IndexOutOfBounds {
+                pos: end,
+                size: self.bit_len(),
+            });
+        }
+        self.buffer.overwrite(position, bits.clone())
+    }
+
+    /// Finish writing, returning the written bytes and the number of written bits
+    ///
+    /// The number of bits can be used with [`BitReadBuffer::with_bit_len`] to read back exactly the written bits,
+    /// without the padding of the last partial byte.
+    ///
+    /// Any data that was already in the `Vec` before the stream was created is not included in the returned bytes.
+    ///
+    /// All offsets written with [`write_offset`](BitWriteStream::write_offset) are filled in.
+    ///
+    /// # Panics
+    ///
+    /// Panics if an offset can't be filled in, use [`resolve_offsets`](BitWriteStream::resolve_offsets) before
+    /// finishing the stream to handle this as an error.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use bitbuffer::{BitReadBuffer, BitReadStream, LittleEndian, Result};
+    /// #
+    /// # fn main() -> Result<()> {
+    /// # use bitbuffer::BitWriteStream;
+    ///
+    /// let mut data = Vec::new();
+    /// let mut stream = BitWriteStream::new(&mut data, LittleEndian);
+    /// stream.write_int(0b101u8, 3)?;
+    /// stream.write_bool(true)?;
+    /// let (bytes, bit_len) = stream.finish();
+    /// assert_eq!(bit_len, 4);
+    ///
+    /// let mut read = BitReadStream::new(BitReadBuffer::with_bit_len(bytes, bit_len, LittleEndian)?);
+    /// assert_eq!(read.read_int::<u8>(3)?, 0b101);
+    /// assert_eq!(read.read_bool()?, true);
+    /// assert_eq!(read.bits_left(), 0);
+    /// #
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn finish(mut self) -> (&'a [u8], usize) {
+        if let Err(e) = self.resolve_offsets() {
+            panic!("{}", e);
+        }
+        self.buffer.finish()
+    }
+
+    /// Shorten the stream to `bit_len` bits, removing all bits written after that
+    ///
+    /// This has no effect if the stream is already shorter than `bit_len`.
+    /// Bits that have already been passed to an attached hasher or scrambler aren't rolled back.
+    /// Offsets written after `bit_len` are forgotten and labels placed after `bit_len` are no longer placed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use bitbuffer::{BigEndian, Result};
+    /// #
+    /// # fn main() -> Result<()> {
+    /// # use bitbuffer::BitWriteStream;
+    ///
+    /// let mut data = Vec::new();
+    /// let mut stream = BitWriteStream::new(&mut data, BigEndian);
+    /// stream.write_int(0b101u8, 3)?;
+    /// let checkpoint = stream.bit_len();
+    /// stream.write_int(0xFFFFu16, 16)?;
+    /// // roll back the last write
+    /// stream.truncate(checkpoint);
+    /// stream.write_bool(true)?;
+    /// assert_eq!(data, [0b1011_0000]);
+    /// #
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn truncate(&mut self, bit_len: usize) {
+        self.buffer.truncate(bit_len);
+        self.fixups.truncate(bit_len);
+    }
+
+    /// Remove all written bits from the stream
+    ///
+    /// The capacity of the underlying `Vec` is kept, allowing the stream to be reused without re-allocating.
+    /// Any data that was already in the `Vec` before the stream was created is kept.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use bitbuffer::{LittleEndian, Result};
+    /// #
+    /// # fn main() -> Result<()> {
+    /// # use bitbuffer::BitWriteStream;
+    ///
+    /// let mut data = Vec::new();
+    /// let mut stream = BitWriteStream::new(&mut data, LittleEndian);
+    /// for packet in 0..3u8 {
+    ///     stream.clear();
+    ///     stream.write_int(packet, 8)?;
+    ///     stream.write_string("packet", None)?;
+    ///     assert_eq!(stream.byte_len(), 8);
+    /// }
+    /// #
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn clear(&mut self) {
+        self.truncate(0)
+    }
+
+    /// Write a string into the buffer
+    ///
+    /// Fixed length strings that don't fit return a [`StringToLong`](crate::BitError::StringToLong) error,
+    /// use [`write_string_fixed`](Self::write_string_fixed) to truncate them instead.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use bitbuffer::{BitReadBuffer, LittleEndian, Result};
+    /// #
+    /// # fn main() -> Result<()> {
+    /// # use bitbuffer::{BitWriteStream, LittleEndian};
+    ///
+    /// let mut data = Vec::new();
+    /// let mut stream = BitWriteStream::new(&mut data, LittleEndian);
+    /// stream.write_string("zero terminated string", None)?;
+    /// stream.write_string("fixed size string, zero padded", Some(64))?;
+    /// #
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn write_string(&mut self, string: &str, length: Option<usize>) -> Result<()> {
+        match length {
+            Some(length) => {
+                if length < string.len() {
+                    return Err(BitError::StringToLong {
+                        string_length: string.len(),
+                        requested_length: length,
+                    });
+                }
+                self.write_bytes(string.as_bytes())?;
+                for _ in 0..(length - string.len()) {
+                    self.push_bits(0, 8)
+                }
+            }
+            None => {
+                self.write_bytes(string.as_bytes())?;
+                self.push_bits(0, 8)
+            }
+        }
+        Ok(())
+    }
+
+    /// Write a string into a fixed length field of `length` bytes, padded with null bytes
+    ///
+    /// Unlike [`write_string`](Self::write_string), strings that don't fit can be truncated depending on the `policy`.
+    ///
+    /// # Errors
+    ///
+    /// - [`ReadError::StringToLong`]: the string is longer than `length` bytes and the policy is [`TruncatePolicy::Error`]
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use bitbuffer::{LittleEndian, Result};
+    /// #
+    /// # fn main() -> Result<()> {
+    /// # use bitbuffer::{BitWriteStream, TruncatePolicy};
+    ///
+    /// let mut data = Vec::new();
+    /// let mut stream = BitWriteStream::new(&mut data, LittleEndian);
+    /// stream.write_string_fixed("Grüße", 3, TruncatePolicy::Truncate)?;
+    /// stream.write_string_fixed("Grüße", 3, TruncatePolicy::TruncateLossy)?;
+    /// assert_eq!(data, [b'G', b'r', 0, b'G', b'r', 0xC3]);
+    /// #
+    /// #     Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`ReadError::StringToLong`]: enum.ReadError.html#variant.StringToLong
+    pub fn write_string_fixed(
+        &mut self,
+        string: &str,
+        length: usize,
+        policy: TruncatePolicy,
+    ) -> Result<()> {
+        let bytes = match policy {
+            _ if string.len() <= length => string.as_bytes(),
+            TruncatePolicy::Error => {
+                return Err(BitError::StringToLong {
+                    string_length: string.len(),
+                    requested_length: length,
+                })
+            }
+            TruncatePolicy::Truncate => {
+                let end = (0..=length)
+                    .rev()
+                    .find(|end| string.is_char_boundary(*end))
+                    .unwrap_or_default();
+                &string.as_bytes()[..end]
+            }
+            TruncatePolicy::TruncateLossy => &string.as_bytes()[..length],
+        };
+        self.write_bytes(bytes)?;
+        for _ in 0..(length - bytes.len()) {
+            self.push_bits(0, 8)
+        }
+        Ok(())
+    }
+
+    /// Write a string into a fixed length field of `bit_length` bits, padded with zero bits
+    ///
+    /// This is like [`write_string`](Self::write_string) with a fixed length, for formats where the size of the
+    /// field isn't a whole number of bytes.
+    ///
+    /// # Errors
+    ///
+    /// - [`ReadError::StringToLong`]: the string doesn't fit in `bit_length` bits
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use bitbuffer::{BigEndian, Result};
+    /// #
+    /// # fn main() -> Result<()> {
+    /// # use bitbuffer::BitWriteStream;
+    ///
+    /// let mut data = Vec::new();
+    /// let mut stream = BitWriteStream::new(&mut data, BigEndian);
+    /// stream.write_string_bits("ab", 20)?;
+    /// stream.write_int(0xF, 4)?;
+    /// assert_eq!(data, [b'a', b'b', 0x0F]);
+    /// #
+    /// #     Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`ReadError::StringToLong`]: enum.ReadError.html#variant.StringToLong
+    pub fn write_string_bits(&mut self, string: &str, bit_length: usize) -> Result<()> {
+        let max_length = bit_length / 8;
+        if string.len() > max_length {
+            return Err(BitError::StringToLong {
+                string_length: string.len(),
+                requested_length: max_length,
+            });
+        }
+        self.write_bytes(string.as_bytes())?;
+        let mut padding = bit_length - string.len() * 8;
+        while padding > 0 {
+            let bits = padding.min(32);
+            self.push_bits(0, bits);
+            padding -= bits;
+        }
+        Ok(())
+    }
+
+    /// Write a string prefixed by its length in bytes, as a `length_bits` bits long integer
+    ///
+    /// # Errors
+    ///
+    /// - [`ReadError::StringToLong`]: the length of the string doesn't fit in `length_bits` bits
+    /// - [`ReadError::TooManyBits`]: `length_bits` is larger than the size of `usize`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use bitbuffer::{LittleEndian, Result};
+    /// #
+    /// # fn main() -> Result<()> {
+    /// # use bitbuffer::BitWriteStream;
+    ///
+    /// let mut data = Vec::new();
+    /// let mut stream = BitWriteStream::new(&mut data, LittleEndian);
+    /// stream.write_string_prefixed("Hello", 8)?;
+    /// assert_eq!(data, [0x05, 0x48, 0x65, 0x6c, 0x6c, 0x6f]);
+    /// #
+    /// #     Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`ReadError::StringToLong`]: enum.ReadError.html#variant.StringToLong
+    /// [`ReadError::TooManyBits`]: enum.ReadError.html#variant.TooManyBits
+    pub fn write_string_prefixed(&mut self, string: &str, length_bits: usize) -> Result<()> {
+        check_int_size::<usize>(length_bits)?;
+        if length_bits < USIZE_BITS && string.len() >> length_bits != 0 {
+            return Err(BitError::StringToLong {
+                string_length: string.len(),
+                requested_length: (1 << length_bits) - 1,
+            });
+        }
+        if length_bits > 0 {
+            self.write_int(string.len(), length_bits)?;
+        }
+        self.write_bytes(string.as_bytes())
+    }
+
+    /// Write a utf16 string in the endianness of the stream
+    ///
+    /// If a length is provided, the string is padded with null characters to `length` code units,
+    /// otherwise a null code unit is written after the string.
+    ///
+    /// # Errors
+    ///
+    /// - [`ReadError::StringToLong`]: the string is longer than `length` code units
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use bitbuffer::{BigEndian, Result};
+    /// #
+    /// # fn main() -> Result<()> {
+    /// # use bitbuffer::BitWriteStream;
+    ///
+    /// let mut data = Vec::new();
+    /// let mut stream = BitWriteStream::new(&mut data, BigEndian);
+    /// stream.write_string_utf16("Hi", Some(3))?;
+    /// assert_eq!(data, [0x00, 0x48, 0x00, 0x69, 0x00, 0x00]);
+    /// #
+    /// #     Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`ReadError::StringToLong`]: enum.ReadError.html#variant.StringToLong
+    pub fn write_string_utf16(&mut self, string: &str, length: Option<usize>) -> Result<()> {
+        let units: Vec<u16> = string.encode_utf16().collect();
+        let padding = match length {
+            Some(length) if length < units.len() => {
+                return Err(BitError::StringToLong {
+                    string_length: units.len(),
+                    requested_length: length,
+                });
+            }
+            Some(length) => length - units.len(),
+            None => 1,
+        };
+        for unit in units {
+            self.push_bits(unit as usize, 16);
+        }
+        for _ in 0..padding {
+            self.push_bits(0, 16);
+        }
+        Ok(())
+    }
+
+    /// Write the type to stream
+    ///
+    /// If the number of bits for the type is known up front, space for it is reserved before writing.
+    #[inline]
+    pub fn write<T: BitWrite<E>>(&mut self, value: &T) -> Result<()> {
+        if let Some(bits) = T::bit_size_hint() {
+            self.reserve(bits);
+        }
+        value.write(self)
+    }
+
+    /// Write the type to stream
+    #[inline]
+    pub fn write_sized<T: BitWriteSized<E>>(&mut self, value: &T, length: usize) -> Result<()> {
+        value.write_sized(self, length)
+    }
+
+    /// Attach a hasher that is fed every bit written from now on
+    ///
+    /// The hasher is fed the bytes of the stream as they are written, allowing a checksum or digest
+    /// of the written data to be calculated without reading it back.
+    /// Any previously attached hasher is discarded.
+    ///
+    /// The hasher has to be `Send` so the stream can still be moved to another thread.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use bitbuffer::Result;
+    /// use bitbuffer::crc::{Crc, CRC_32};
+    /// use bitbuffer::{BitWriteStream, LittleEndian};
+    ///
+    /// # fn main() -> Result<()> {
+    /// let mut data = Vec::new();
+    /// let mut stream = BitWriteStream::new(&mut data, LittleEndian);
+    /// stream.attach_hasher(Crc::new(&CRC_32));
+    /// stream.write_bytes(b"123456789")?;
+    /// let checksum = stream.finalize_hash().unwrap();
+    /// stream.write_bytes(&checksum)?;
+    /// assert_eq!(checksum, vec![0xCB, 0xF4, 0x39, 0x26]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn attach_hasher<H: BitHasher + Send + 'static>(&mut self, hasher: H) {
+        self.buffer.attach_hasher(Box::new(hasher))
+    }
+
+    /// Detach the hasher and get the digest of all bits written since it was attached
+    ///
+    /// If the number of written bits isn't a multiple of 8, the last byte is padded with zeros
+    /// the same way as the written data.
+    ///
+    /// Returns `None` if no hasher is attached.
+    pub fn finalize_hash(&mut self) -> Option<Vec<u8>> {
+        self.buffer.finalize_hash()
+    }
+
+    /// Attach a key stream that every bit written from now on is XOR-ed with
+    ///
+    /// If a hasher is attached, it is fed the scrambled bits.
+    /// Any previously attached scrambler is discarded.
+    ///
+    /// The key stream has to be `Send` so the stream can still be moved to another thread.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use bitbuffer::Result;
+    /// use bitbuffer::scramble::Prbs;
+    /// use bitbuffer::{BitWriteStream, BigEndian};
+    ///
+    /// # fn main() -> Result<()> {
+    /// let mut data = Vec::new();
+    /// let mut stream = BitWriteStream::new(&mut data, BigEndian);
+    /// stream.attach_scrambler(Prbs::dvb());
+    /// stream.write_bytes(&[0, 0, 0])?;
+    /// assert_eq!(data, [0x03, 0xF6, 0x08]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn attach_scrambler<K: KeyStream + Send + 'static>(&mut self, key: K) {
+        self.buffer.attach_scrambler(Some(ScrambleHook::new(key)))
+    }
+
+    /// Stop scrambling the written bits
+    pub fn detach_scrambler(&mut self) {
+        self.buffer.attach_scrambler(None)
+    }
+
+    /// Write the length of a section before the section
+    ///
+    /// A placeholder of `length_bit_size` bits is written, after which `body_fn` writes the section to this stream.
+    /// Once the section is written, the placeholder is filled in with the number of bits in the section.
+    /// Because the section is written to the same stream, calls can be nested to write sections containing
+    /// other length prefixed sections, and positions retrieved with [`bit_len`](BitWriteStream::bit_len)
+    /// inside the section are positions in the full stream.
+    ///
+    /// If the section or the length can't be written, the stream is truncated to the state before the call.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use bitbuffer::{BigEndian, Result};
+    /// #
+    /// # fn main() -> Result<()> {
+    /// # use bitbuffer::BitWriteStream;
+    ///
+    /// let mut data = Vec::new();
+    /// let mut stream = BitWriteStream::new(&mut data, BigEndian);
+    /// stream.reserve_length(8, |stream| {
+    ///     stream.write_int(1u8, 8)?;
+    ///     stream.reserve_length(8, |stream| stream.write_bytes(b"ab"))
+    /// })?;
+    /// assert_eq!(data, [32, 1, 16, b'a', b'b']);
+    /// #
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn reserve_length<F: Fn(&mut BitWriteStream<E>) -> Result<()>>(
+        &mut self,
+        length_bit_size: usize,
+        body_fn: F,
+    ) -> Result<()> {
+        if self.buffer.has_hooks() {
+            // hashers and scramblers need to see the bits in order, so we can't write the length afterwards
+            let mut body = Vec::new();
+            let mut body_stream = BitWriteStream::new(&mut body, E::endianness());
+            body_stream.strict = self.strict;
+            body_stream.fixups = self.fixups.split_off(self.bit_len() + length_bit_size);
+            let result = body_fn(&mut body_stream);
+            let body_len = body_stream.bit_len();
+            self.fixups.join(take(&mut body_stream.fixups));
+            result?;
+            self.write_sized(&body_len, length_bit_size)?;
+            let mut body_read = BitReadStream::new(BitReadBuffer::new(&body, E::endianness()));
+            return self.write_bits(&body_read.read_bits(body_len)?);
+        }
+
+        let start = self.bit_len();
+        let result = self.write_reserved_length(length_bit_size, body_fn);
+        if result.is_err() {
+            self.truncate(start);
+        }
+        result
+    }
+
+    fn write_reserved_length<F: Fn(&mut BitWriteStream<E>) -> Result<()>>(
+        &mut self,
+        length_bit_size: usize,
+        body_fn: F,
+    ) -> Result<()> {
+        let length_pos = self.bit_len();
+        self.write_sized(&0usize, length_bit_size)?;
+        let body_start = self.bit_len();
+        body_fn(self)?;
+        let body_len = self.bit_len() - body_start;
+        self.overwrite_sized(length_pos, body_len, length_bit_size)
+    }
+
+    /// Overwrite `bit_size` previously written bits at `position` with `value`
+    fn overwrite_sized(&mut self, position: usize, value: usize, bit_size: usize) -> Result<()> {
+        let mut bytes = Vec::new();
+        let mut stream = BitWriteStream::new(&mut bytes, E::endianness());
+        stream.strict = self.strict;
+        stream.write_sized(&value, bit_size)?;
+        let bits = BitReadStream::new(BitReadBuffer::new(&bytes, E::endianness()));
+        self.overwrite_bits(position, &bits.clone().read_bits(bit_size)?)
+    }
+
+    /// Create a label that offsets can refer to before it's placed
+    ///
+    /// See [`write_offset`](BitWriteStream::write_offset) for an example.
+    pub fn create_label(&mut self) -> Label {
+        self.fixups.create_label()
+    }
+
+    /// Place a label at the current position in the stream
+    ///
+    /// Placing a label that was already placed moves it to the current position.
+    ///
+    /// # Errors
+    ///
+    /// - [`ReadError::UnresolvedOffset`]: the label was created by a different stream
+    ///
+    /// [`ReadError::UnresolvedOffset`]: enum.ReadError.html#variant.UnresolvedOffset
+    pub fn place_label(&mut self, label: Label) -> Result<()> {
+        self.fixups.place(label, self.bit_len())
+    }
+
+    /// The position of a placed label, in bits from the start of the stream
+    pub fn label_position(&self, label: Label) -> Option<usize> {
+        self.fixups.position(label)
+    }
+
+    /// Write a placeholder of `bit_size` bits that will hold the offset of a label in bits
+    ///
+    /// The offset is counted from the start of the stream and filled in by
+    /// [`resolve_offsets`](BitWriteStream::resolve_offsets) or when the stream is [finished](BitWriteStream::finish),
+    /// so the label can be placed after the offset is written.
+    /// This allows writing tables of offsets, like the index of a container format, before the data they point to.
+    ///
+    /// Like [`overwrite_bits`](BitWriteStream::overwrite_bits) the offsets are written directly to the output,
+    /// an attached hasher or scrambler only sees the placeholder.
+    ///
+    /// # Errors
+    ///
+    /// - [`ReadError::TooManyBits`]: `bit_size` is larger than the size of a `usize`
+    /// - [`ReadError::UnresolvedOffset`]: the label was created by a different stream
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use bitbuffer::{BigEndian, Result};
+    /// #
+    /// # fn main() -> Result<()> {
+    /// # use bitbuffer::BitWriteStream;
+    ///
+    /// let mut data = Vec::new();
+    /// let mut stream = BitWriteStream::new(&mut data, BigEndian);
+    /// let first = stream.create_label();
+    /// let second = stream.create_label();
+    /// // a table with the offsets of both chunks
+    /// stream.write_offset(first, 8)?;
+    /// stream.write_offset(second, 8)?;
+    ///
+    /// stream.place_label(first)?;
+    /// stream.write_bytes(b"foo")?;
+    /// stream.place_label(second)?;
+    /// stream.write_bytes(b"bar")?;
+    /// stream.finish();
+    ///
+    /// assert_eq!(data, [16, 40, b'f', b'o', b'o', b'b', b'a', b'r']);
+    /// #
+    /// #     Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`ReadError::TooManyBits`]: enum.ReadError.html#variant.TooManyBits
+    /// [`ReadError::UnresolvedOffset`]: enum.ReadError.html#variant.UnresolvedOffset
+    pub fn write_offset(&mut self, label: Label, bit_size: usize) -> Result<()> {
+        self.write_offset_in(label, bit_size, 1)
+    }
+
+    /// Write a placeholder of `bit_size` bits that will hold the offset of a label in bytes
+    ///
+    /// See [`write_offset`](BitWriteStream::write_offset), the label has to be placed at a byte boundary.
+    ///
+    /// # Errors
+    ///
+    /// - [`ReadError::TooManyBits`]: `bit_size` is larger than the size of a `usize`
+    /// - [`ReadError::UnresolvedOffset`]: the label was created by a different stream
+    ///
+    /// [`ReadError::TooManyBits`]: enum.ReadError.html#variant.TooManyBits
+    /// [`ReadError::UnresolvedOffset`]: enum.ReadError.html#variant.UnresolvedOffset
+    pub fn write_byte_offset(&mut self, label: Label, bit_size: usize) -> Result<()> {
+        self.write_offset_in(label, bit_size, 8)
+    }
+
+    fn write_offset_in(&mut self, label: Label, bit_size: usize, unit_bits: usize) -> Result<()> {
+        let pos = self.bit_len();
+        self.fixups.add(label, pos, bit_size, unit_bits)?;
+        if let Err(e) = self.write_sized(&0usize, bit_size) {
+            self.fixups.truncate(pos);
+            return Err(e);
+        }
+        Ok(())
+    }
+
+    /// Fill in all offsets written with [`write_offset`](BitWriteStream::write_offset)
+    ///
+    /// This is done automatically when the stream is [finished](BitWriteStream::finish),
+    /// resolving the offsets before allows handling unresolved offsets without panicking.
+    /// The stream can still be written to afterwards, offsets that are written later are filled in by the next call.
+    ///
+    /// # Errors
+    ///
+    /// - [`ReadError::UnresolvedOffset`]: a label is never placed, isn't placed at a byte boundary for a byte offset,
+    ///   or the offset doesn't fit in the placeholder. No offsets are filled in.
+    ///
+    /// [`ReadError::UnresolvedOffset`]: enum.ReadError.html#variant.UnresolvedOffset
+    pub fn resolve_offsets(&mut self) -> Result<()> {
+        for (position, bit_size, offset) in self.fixups.resolve()? {
+            self.overwrite_sized(position, offset, bit_size)?;
+        }
+        Ok(())
+    }
+
+    /// Write data that contains offsets to later parts of the data, by writing it in multiple passes
+    ///
+    /// `body_fn` is first called with a temporary stream to determine the positions of the labels it places,
+    /// during this sizing pass all positions are unknown. It's then called again with the real stream and the
+    /// positions of the labels from the previous pass, allowing it to write the offsets as normal values.
+    /// If the written offsets change the positions of the labels, like when they are written as variable length integers,
+    /// the data is written again with the new positions until they no longer change.
+    ///
+    /// Unlike [`write_offset`](BitWriteStream::write_offset) the offsets are written in order, so they are seen by
+    /// an attached hasher or scrambler and can have any encoding. With a hasher or scrambler attached all passes
+    /// except the last are done on a temporary stream, since the hooks can't be rolled back.
+    ///
+    /// `body_fn` has to write the same data in every pass, apart from the offsets, and create the same labels.
+    /// If the call fails, the stream is truncated to the state before the call.
+    ///
+    /// # Errors
+    ///
+    /// - [`ReadError::UnresolvedOffset`]: the positions of the labels keep changing between passes
+    /// - any error returned by `body_fn`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use bitbuffer::{BigEndian, Result};
+    /// #
+    /// # fn main() -> Result<()> {
+    /// # use bitbuffer::{BitWriteStream, Vlq};
+    ///
+    /// let mut data = Vec::new();
+    /// let mut stream = BitWriteStream::new(&mut data, BigEndian);
+    /// stream.write_with_offsets(|stream, positions| {
+    ///     let chunks: Vec<_> = (0..2).map(|_| stream.create_label()).collect();
+    ///     // an index with the byte offsets of the chunks as variable length integers
+    ///     for chunk in &chunks {
+    ///         stream.write(&Vlq(positions.get(*chunk) as u64 / 8))?;
+    ///     }
+    ///     for (chunk, content) in chunks.iter().zip([&[1; 126][..], b"foo"]) {
+    ///         stream.place_label(*chunk)?;
+    ///         stream.write_bytes(content)?;
+    ///     }
+    ///     Ok(())
+    /// })?;
+    ///
+    /// // the second offset doesn't fit in a single byte, moving both chunks by a byte
+    /// assert_eq!(data[..4], [3, 0x81, 0x01, 1]);
+    /// assert_eq!(data[3 + 125..], [1, b'f', b'o', b'o']);
+    /// #
+    /// #     Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`ReadError::UnresolvedOffset`]: enum.ReadError.html#variant.UnresolvedOffset
+    pub fn write_with_offsets<F>(&mut self, body_fn: F) -> Result<()>
+    where
+        F: Fn(&mut BitWriteStream<E>, &LabelPositions) -> Result<()>,
+    {
+        let start = self.bit_len();
+        let label_count = self.fixups.label_count();
+        let mut positions = self.size_with_offsets(&body_fn, &LabelPositions::default())?;
+        let mut moved = label_count;
+        for _ in 0..MAX_OFFSET_PASSES {
+            if self.buffer.has_hooks() {
+                let next = self.size_with_offsets(&body_fn, &positions)?;
+                if next != positions {
+                    moved = next.first_moved(&positions);
+                    positions = next;
+                    continue;
+                }
+            }
+
+            let result = body_fn(self, &positions);
+            let written = self.fixups.positions();
+            if result.is_ok() && written == positions {
+                return Ok(());
+            }
+            self.truncate(start);
+            self.fixups.truncate_labels(label_count);
+            result?;
+            moved = written.first_moved(&positions);
+            positions = written;
+        }
+        Err(BitError::UnresolvedOffset {
+            label: moved,
+            reason: "the positions of the labels keep changing",
+        })
+    }
+
+    /// Write `body_fn` to a temporary stream to get the positions of the labels
+    fn size_with_offsets<F>(
+        &self,
+        body_fn: &F,
+        positions: &LabelPositions,
+    ) -> Result<LabelPositions>
+    where
+        F: Fn(&mut BitWriteStream<E>, &LabelPositions) -> Result<()>,
+    {
+        let mut scratch = Vec::new();
+        let mut stream = BitWriteStream::new(&mut scratch, E::endianness());
+        stream.strict = self.strict;
+        stream.fixups = self.fixups.copy_labels(self.bit_len());
+        body_fn(&mut stream, positions)?;
+        Ok(stream.fixups.positions())
+    }
+}
+
+/// The number of bits needed to store `value`, as two's complement for signed types
+fn int_bit_size<T: IsSigned + UncheckedPrimitiveInt>(value: T) -> usize {
+    if T::is_signed() {
+        signed_bit_size(value.into_i128_unchecked())
+    } else {
+        (u128::BITS - value.into_u128_unchecked().leading_zeros()) as usize
+    }
+}
+
+/// The number of bits needed to store `value` as two's complement
+fn signed_bit_size(value: i128) -> usize {
+    // zero is the only value that fits in no bits
+    if value == 0 {
+        return 0;
     }
+    let redundant = if value < 0 {
+        value.leading_ones()
+    } else {
+        value.leading_zeros()
+    };
+    (i128::BITS - redundant) as usize + 1
 }
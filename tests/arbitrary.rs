@@ -0,0 +1,62 @@
+#![cfg(feature = "arbitrary")]
+
+use arbitrary::{Arbitrary, Unstructured};
+use bitbuffer::{fuzz_roundtrip, BigEndian, BitRead, BitReadStream, BitWrite, LittleEndian};
+
+#[test]
+fn test_arbitrary_stream() {
+    let data = [0b1011_0101, 0b0110_1010, 0b1010_1100];
+    let mut stream =
+        BitReadStream::<LittleEndian>::arbitrary_take_rest(Unstructured::new(&data)).unwrap();
+    assert_eq!(stream.bit_len(), 24);
+    assert_eq!(stream.read_int::<u8>(8).unwrap(), 0b1011_0101);
+}
+
+#[test]
+fn test_fuzz_roundtrip() {
+    #[derive(BitRead, BitWrite, Debug, PartialEq)]
+    #[discriminant_bits = 2]
+    enum Kind {
+        A,
+        B(u8),
+        C(#[size = 5] u16),
+    }
+
+    #[derive(BitRead, BitWrite, Debug, PartialEq)]
+    struct Packet {
+        #[size = 3]
+        version: u8,
+        kind: Kind,
+        name: String,
+        #[size = 7]
+        signed: i32,
+    }
+
+    let mut data = [0u8; 64];
+    for i in 0..data.len() {
+        data[i] = (i * 37) as u8;
+        fuzz_roundtrip::<Packet, LittleEndian>(&data[..i]);
+        fuzz_roundtrip::<Packet, BigEndian>(&data[..i]);
+    }
+}
+
+#[test]
+#[should_panic(expected = "written value has a different size")]
+fn test_fuzz_roundtrip_size_mismatch() {
+    #[derive(Debug, PartialEq)]
+    struct Lossy(u8);
+
+    impl<E: bitbuffer::Endianness> BitRead<'_, E> for Lossy {
+        fn read(stream: &mut BitReadStream<'_, E>) -> bitbuffer::Result<Self> {
+            stream.read_int(6).map(Lossy)
+        }
+    }
+
+    impl<E: bitbuffer::Endianness> BitWrite<E> for Lossy {
+        fn write(&self, stream: &mut bitbuffer::BitWriteStream<E>) -> bitbuffer::Result<()> {
+            stream.write_int(self.0, 8)
+        }
+    }
+
+    fuzz_roundtrip::<Lossy, LittleEndian>(&[0b0000_0101]);
+}
@@ -0,0 +1,80 @@
+#![cfg(feature = "arrayvec")]
+
+use arrayvec::{ArrayString, ArrayVec};
+use bitbuffer::{
+    BigEndian, BitError, BitRead, BitReadBuffer, BitReadStream, BitWrite, BitWriteStream,
+    LittleEndian,
+};
+
+#[derive(BitRead, BitWrite, Debug, PartialEq)]
+struct Packet {
+    #[size = 3]
+    len: u8,
+    #[size = "len"]
+    values: ArrayVec<u16, 4>,
+    #[size = 4]
+    tag: ArrayString<4>,
+    name: ArrayString<8>,
+}
+
+#[test]
+fn test_roundtrip() {
+    let packet = Packet {
+        len: 2,
+        values: [0x1234, 0x5678].iter().copied().collect(),
+        tag: ArrayString::from("ab").unwrap(),
+        name: ArrayString::from("bounded").unwrap(),
+    };
+    let mut data = Vec::new();
+    let mut stream = BitWriteStream::new(&mut data, LittleEndian);
+    stream.write(&packet).unwrap();
+    assert_eq!(stream.bit_len(), 3 + 2 * 16 + 4 * 8 + 8 * 8);
+
+    let mut stream = BitReadStream::new(BitReadBuffer::new(&data, LittleEndian));
+    assert_eq!(stream.read::<Packet>().unwrap(), packet);
+}
+
+#[test]
+fn test_capacity() {
+    let data = [0xFF; 16];
+    let mut stream = BitReadStream::new(BitReadBuffer::new(&data, BigEndian));
+    assert!(matches!(
+        stream.read_sized::<ArrayVec<u8, 4>>(5),
+        Err(BitError::CapacityExceeded {
+            requested: 5,
+            capacity: 4
+        })
+    ));
+    assert_eq!(stream.read_sized::<ArrayVec<u8, 4>>(4).unwrap().len(), 4);
+
+    let data = b"abcdef\0\0";
+    let mut stream = BitReadStream::new(BitReadBuffer::new(data, BigEndian));
+    assert!(matches!(
+        stream.read::<ArrayString<4>>(),
+        Err(BitError::CapacityExceeded {
+            requested: 6,
+            capacity: 4
+        })
+    ));
+    assert_eq!(stream.pos(), 0);
+    assert_eq!(
+        stream.read_sized::<ArrayString<6>>(8).unwrap().as_str(),
+        "abcdef"
+    );
+}
+
+#[test]
+fn test_write_length_mismatch() {
+    let values: ArrayVec<u8, 4> = [1, 2, 3].iter().copied().collect();
+    let mut data = Vec::new();
+    let mut stream = BitWriteStream::new(&mut data, BigEndian);
+    assert!(matches!(
+        stream.write_sized(&values, 2),
+        Err(BitError::LengthMismatch {
+            length: 3,
+            requested_length: 2
+        })
+    ));
+    stream.write_sized(&values, 3).unwrap();
+    assert_eq!(data, [1, 2, 3]);
+}
@@ -0,0 +1,176 @@
+use bitbuffer::asn1::{
+    read_element, read_length, read_tag, write_element, write_end_of_contents, write_length,
+    write_tag, Length, Tag, TagClass,
+};
+use bitbuffer::{BigEndian, BitError, BitReadBuffer, BitReadStream, BitWriteStream, ReadLimits};
+
+#[test]
+fn test_tag_roundtrip() {
+    let cases: [(Tag, &[u8]); 5] = [
+        (Tag::SEQUENCE, &[0x30]),
+        (Tag::INTEGER, &[0x02]),
+        (Tag::context(3, true), &[0xA3]),
+        (
+            Tag {
+                class: TagClass::Application,
+                constructed: false,
+                number: 31,
+            },
+            &[0x5F, 0x1F],
+        ),
+        (
+            Tag {
+                class: TagClass::Private,
+                constructed: true,
+                number: u32::MAX,
+            },
+            &[0xFF, 0x8F, 0xFF, 0xFF, 0xFF, 0x7F],
+        ),
+    ];
+    for (tag, encoded) in cases {
+        let mut data = Vec::new();
+        let mut writer = BitWriteStream::new(&mut data, BigEndian);
+        write_tag(&mut writer, tag).unwrap();
+        assert_eq!(data, encoded);
+        let mut reader = BitReadStream::new(BitReadBuffer::new(encoded, BigEndian));
+        assert_eq!(read_tag(&mut reader).unwrap(), tag);
+        assert_eq!(reader.bits_left(), 0);
+    }
+
+    let buffer = BitReadBuffer::new(&[0x1F, 0x90, 0x80, 0x80, 0x80, 0x00], BigEndian);
+    let mut reader = BitReadStream::new(buffer);
+    assert!(matches!(
+        read_tag(&mut reader),
+        Err(BitError::VarIntOverflow { max: 32, .. })
+    ));
+    assert_eq!(reader.pos(), 0);
+}
+
+#[test]
+fn test_length_roundtrip() {
+    let cases: [(Length, &[u8]); 6] = [
+        (Length::Definite(0), &[0x00]),
+        (Length::Definite(0x7F), &[0x7F]),
+        (Length::Definite(0x80), &[0x81, 0x80]),
+        (Length::Definite(0x1234), &[0x82, 0x12, 0x34]),
+        (
+            Length::Definite(0x0100_0000),
+            &[0x84, 0x01, 0x00, 0x00, 0x00],
+        ),
+        (Length::Indefinite, &[0x80]),
+    ];
+    for (length, encoded) in cases {
+        let mut data = Vec::new();
+        let mut writer = BitWriteStream::new(&mut data, BigEndian);
+        write_length(&mut writer, length).unwrap();
+        assert_eq!(data, encoded);
+        let mut reader = BitReadStream::new(BitReadBuffer::new(encoded, BigEndian));
+        assert_eq!(read_length(&mut reader).unwrap(), length);
+    }
+
+    // non minimal BER lengths are accepted
+    let mut reader = BitReadStream::new(BitReadBuffer::new(&[0x82, 0x00, 0x05], BigEndian));
+    assert_eq!(read_length(&mut reader).unwrap(), Length::Definite(5));
+    let mut reader = BitReadStream::new(BitReadBuffer::new(&[0xFF], BigEndian));
+    assert!(matches!(
+        read_length(&mut reader),
+        Err(BitError::Malformed { pos: 0, .. })
+    ));
+    let buffer = BitReadBuffer::new(&[0x89, 1, 0, 0, 0, 0, 0, 0, 0, 0], BigEndian);
+    let mut reader = BitReadStream::new(buffer);
+    assert!(matches!(
+        read_length(&mut reader),
+        Err(BitError::VarIntOverflow { .. })
+    ));
+    let mut reader = BitReadStream::new(BitReadBuffer::new(&[0x82, 0x01], BigEndian));
+    assert!(matches!(
+        read_length(&mut reader),
+        Err(BitError::NotEnoughData { .. })
+    ));
+    assert_eq!(reader.pos(), 0);
+}
+
+#[test]
+fn test_indefinite_length() {
+    // SEQUENCE (indefinite) { SEQUENCE (indefinite) { INTEGER 1 }, OCTET STRING "a" }, NULL
+    let mut data = Vec::new();
+    let mut writer = BitWriteStream::new(&mut data, BigEndian);
+    write_tag(&mut writer, Tag::SEQUENCE).unwrap();
+    write_length(&mut writer, Length::Indefinite).unwrap();
+    write_tag(&mut writer, Tag::SEQUENCE).unwrap();
+    write_length(&mut writer, Length::Indefinite).unwrap();
+    write_element(&mut writer, Tag::INTEGER, &[1]).unwrap();
+    write_end_of_contents(&mut writer).unwrap();
+    write_element(&mut writer, Tag::OCTET_STRING, b"a").unwrap();
+    write_end_of_contents(&mut writer).unwrap();
+    write_element(&mut writer, Tag::NULL, &[]).unwrap();
+
+    let mut reader = BitReadStream::new(BitReadBuffer::new(&data, BigEndian));
+    let (tag, mut sequence) = read_element(&mut reader).unwrap();
+    assert_eq!(tag, Tag::SEQUENCE);
+    assert_eq!(sequence.bit_len(), (2 + 3 + 2 + 3) * 8);
+    let (tag, mut inner) = read_element(&mut sequence).unwrap();
+    assert_eq!(tag, Tag::SEQUENCE);
+    let (tag, mut integer) = read_element(&mut inner).unwrap();
+    assert_eq!(tag, Tag::INTEGER);
+    assert_eq!(integer.read_int::<u8>(8).unwrap(), 1);
+    assert_eq!(inner.bits_left(), 0);
+    let (tag, mut string) = read_element(&mut sequence).unwrap();
+    assert_eq!(tag, Tag::OCTET_STRING);
+    assert_eq!(string.read_bytes(1).unwrap().as_ref(), b"a");
+    assert_eq!(sequence.bits_left(), 0);
+    let (tag, null) = read_element(&mut reader).unwrap();
+    assert_eq!(tag, Tag::NULL);
+    assert_eq!(null.bit_len(), 0);
+    assert_eq!(reader.bits_left(), 0);
+
+    let mut reader = BitReadStream::new(BitReadBuffer::new(&data, BigEndian));
+    reader.set_limits(ReadLimits {
+        max_depth: 1,
+        ..ReadLimits::default()
+    });
+    assert!(matches!(
+        read_element(&mut reader),
+        Err(BitError::LimitExceeded { .. })
+    ));
+    assert_eq!(reader.pos(), 0);
+}
+
+#[test]
+fn test_element_errors() {
+    let mut reader = BitReadStream::new(BitReadBuffer::new(&[0x04, 0x80, 0x00, 0x00], BigEndian));
+    assert!(matches!(
+        read_element(&mut reader),
+        Err(BitError::Malformed { pos: 8, .. })
+    ));
+    assert_eq!(reader.pos(), 0);
+
+    let buffer = BitReadBuffer::new(&[0x30, 0x80, 0x02, 0x01, 0x01], BigEndian);
+    let mut reader = BitReadStream::new(buffer);
+    assert!(matches!(
+        read_element(&mut reader),
+        Err(BitError::NotEnoughData { .. })
+    ));
+    assert_eq!(reader.pos(), 0);
+
+    let mut reader = BitReadStream::new(BitReadBuffer::new(&[0x04, 0x05, 0x00], BigEndian));
+    assert!(matches!(
+        read_element(&mut reader),
+        Err(BitError::NotEnoughData { .. })
+    ));
+    assert_eq!(reader.pos(), 0);
+}
+
+#[test]
+fn test_long_element() {
+    let contents = vec![0xAB; 300];
+    let mut data = Vec::new();
+    let mut writer = BitWriteStream::new(&mut data, BigEndian);
+    write_element(&mut writer, Tag::OCTET_STRING, &contents).unwrap();
+    assert_eq!(&data[..4], [0x04, 0x82, 0x01, 0x2C]);
+
+    let mut reader = BitReadStream::new(BitReadBuffer::new(&data, BigEndian));
+    let (tag, mut value) = read_element(&mut reader).unwrap();
+    assert_eq!(tag, Tag::OCTET_STRING);
+    assert_eq!(value.read_bytes(300).unwrap().as_ref(), &contents[..]);
+}
@@ -0,0 +1,109 @@
+use bitbuffer::basenc::{BASE32, BASE32_HEX, BASE64, BASE64_URL};
+use bitbuffer::{BigEndian, BitError, BitReadStream, BitWriteStream, LittleEndian};
+
+const VECTORS: [&[u8]; 7] = [b"", b"f", b"fo", b"foo", b"foob", b"fooba", b"foobar"];
+
+#[test]
+fn test_rfc_4648_vectors() {
+    let base64 = [
+        "", "Zg==", "Zm8=", "Zm9v", "Zm9vYg==", "Zm9vYmE=", "Zm9vYmFy",
+    ];
+    let base32 = [
+        "",
+        "MY======",
+        "MZXQ====",
+        "MZXW6===",
+        "MZXW6YQ=",
+        "MZXW6YTB",
+        "MZXW6YTBOI======",
+    ];
+    let base32_hex = [
+        "",
+        "CO======",
+        "CPNG====",
+        "CPNMU===",
+        "CPNMUOG=",
+        "CPNMUOJ1",
+        "CPNMUOJ1E8======",
+    ];
+    for (index, bytes) in VECTORS.iter().enumerate() {
+        for (alphabet, text) in [
+            (BASE64, base64[index]),
+            (BASE32, base32[index]),
+            (BASE32_HEX, base32_hex[index]),
+        ] {
+            assert_eq!(alphabet.encode(bytes), text);
+            let buffer = alphabet.decode(text, BigEndian).unwrap();
+            assert_eq!(buffer.byte_len(), bytes.len());
+            assert_eq!(
+                BitReadStream::new(buffer)
+                    .read_bytes(bytes.len())
+                    .unwrap()
+                    .as_ref(),
+                *bytes
+            );
+        }
+    }
+}
+
+#[test]
+fn test_url_alphabet() {
+    assert_eq!(BASE64_URL.encode(&[0xFB, 0xFF]), "-_8");
+    assert_eq!(BASE64.encode(&[0xFB, 0xFF]), "+/8=");
+    // padding is optional when decoding
+    assert_eq!(BASE64_URL.decode("-_8=", BigEndian).unwrap().byte_len(), 2);
+    assert_eq!(BASE64.decode("+/8", BigEndian).unwrap().byte_len(), 2);
+}
+
+#[test]
+fn test_partial_byte() {
+    for alphabet in [BASE64, BASE32] {
+        let mut data = Vec::new();
+        let mut stream = BitWriteStream::new(&mut data, LittleEndian);
+        stream.write_int(0x3FFu16, 10).unwrap();
+        stream.write_bool(true).unwrap();
+        // bits that are written and rolled back are not encoded
+        stream.write_int(0xFu8, 4).unwrap();
+        stream.truncate(11);
+        let text = alphabet.encode_stream(stream);
+        assert_eq!(alphabet.decode(&text, LittleEndian).unwrap().byte_len(), 2);
+
+        let buffer = alphabet.decode_bits(&text, 11, LittleEndian).unwrap();
+        assert_eq!(buffer.bit_len(), 11);
+        let mut stream = BitReadStream::new(buffer);
+        assert_eq!(stream.read_int::<u16>(10).unwrap(), 0x3FF);
+        assert!(stream.read_bool().unwrap());
+
+        assert!(matches!(
+            alphabet.decode_bits(&text, 17, LittleEndian),
+            Err(BitError::NotEnoughData { .. })
+        ));
+    }
+
+    let mut data = Vec::new();
+    let mut stream = BitWriteStream::new(&mut data, BigEndian);
+    stream.write_int(0b111u8, 3).unwrap();
+    assert_eq!(BASE64.encode_stream(stream), "4A==");
+}
+
+#[test]
+fn test_decode_errors() {
+    assert!(matches!(
+        BASE64.decode("Zm9v!A==", BigEndian),
+        Err(BitError::Malformed { pos: 24, .. })
+    ));
+    // a single symbol doesn't encode a byte
+    assert!(matches!(
+        BASE64.decode("Zm9vY", BigEndian),
+        Err(BitError::Malformed { .. })
+    ));
+    assert!(matches!(
+        BASE32.decode("MZX", BigEndian),
+        Err(BitError::Malformed { .. })
+    ));
+    // non-canonical encoding of "f"
+    assert!(matches!(
+        BASE64.decode("Zh==", BigEndian),
+        Err(BitError::Malformed { pos: 8, .. })
+    ));
+}
@@ -0,0 +1,47 @@
+#![cfg(feature = "bitflags")]
+
+use bitbuffer::{
+    impl_bitflags, BigEndian, BitReadBuffer, BitReadSized, BitReadStream, BitWriteStream,
+};
+use bitflags::bitflags;
+
+bitflags! {
+    #[derive(Debug, PartialEq, Clone, Copy)]
+    struct Flags: u16 {
+        const A = 1 << 0;
+        const B = 1 << 1;
+        const C = 1 << 9;
+    }
+}
+
+impl_bitflags!(Flags);
+
+#[test]
+fn test_flags_roundtrip() {
+    let mut data = Vec::new();
+    let mut stream = BitWriteStream::new(&mut data, BigEndian);
+    stream.write_bool(true).unwrap();
+    stream.write_sized(&(Flags::A | Flags::C), 10).unwrap();
+    stream.write_int(0b1_0000_0100u16, 10).unwrap();
+    assert_eq!(stream.bit_len(), 21);
+
+    let mut stream = BitReadStream::new(BitReadBuffer::new(&data, BigEndian));
+    assert!(stream.read_bool().unwrap());
+    assert_eq!(stream.read_sized::<Flags>(10).unwrap(), Flags::A | Flags::C);
+    // unknown bits are retained
+    let flags: Flags = stream.read_sized(10).unwrap();
+    assert_eq!(flags.bits(), 0b1_0000_0100);
+    assert!(!flags.contains(Flags::A));
+}
+
+#[test]
+fn test_flags_size() {
+    assert_eq!(
+        <Flags as BitReadSized<BigEndian>>::bit_size_sized(7),
+        Some(7)
+    );
+
+    let mut data = Vec::new();
+    let mut stream = BitWriteStream::new(&mut data, BigEndian);
+    assert!(stream.write_sized(&Flags::all(), 17).is_err());
+}
@@ -0,0 +1,60 @@
+#![cfg(feature = "bitvec")]
+
+use bitbuffer::{BigEndian, BitReadBuffer, BitReadStream, BitWriteStream, LittleEndian};
+use bitvec::prelude::*;
+
+#[test]
+fn test_buffer_to_bitvec() {
+    let bytes = [0b1011_0101, 0b0110_1010];
+    let buffer = BitReadBuffer::new(&bytes, LittleEndian);
+    assert_eq!(buffer.as_bitslice(), bytes.view_bits::<Lsb0>());
+    assert_eq!(BitVec::from(&buffer).into_vec(), bytes.to_vec());
+
+    let buffer = BitReadBuffer::new(&bytes, BigEndian);
+    assert_eq!(
+        buffer.as_bitslice(),
+        bits![u8, Msb0; 1, 0, 1, 1, 0, 1, 0, 1, 0, 1, 1, 0, 1, 0, 1, 0]
+    );
+}
+
+#[test]
+fn test_stream_remaining_bits() {
+    let bytes = [0b1011_0101, 0b0110_1010];
+    let mut stream = BitReadStream::new(BitReadBuffer::new(&bytes, BigEndian));
+    stream.skip_bits(3).unwrap();
+    let mut sub = stream.read_bits(6).unwrap();
+    sub.skip_bits(1).unwrap();
+    assert_eq!(sub.as_bitslice(), bits![u8, Msb0; 0, 1, 0, 1, 0]);
+    assert_eq!(BitVec::from(&stream), bits![u8, Msb0; 1, 1, 0, 1, 0, 1, 0]);
+}
+
+#[test]
+fn test_unaligned_bitvec_to_stream() {
+    let bits = bits![u8, Lsb0; 0, 0, 1, 1, 0, 1, 1, 1, 1, 0, 1];
+    let mut stream = BitReadStream::from(&bits[2..]);
+    assert_eq!(stream.bit_len(), 9);
+    assert_eq!(stream.read_int::<u16>(9).unwrap(), 0b1_0111_1011);
+    assert!(stream.read_bool().is_err());
+}
+
+#[test]
+fn test_bitvec_read_write() {
+    let expected = bits![u16, Msb0; 1, 0, 0, 1, 1, 1, 0, 1, 0, 0, 1, 0, 1, 1, 1, 1, 0, 0, 1, 0, 1, 1, 0, 1, 0, 1, 1, 1, 0, 0, 0, 1, 0, 1, 1];
+
+    let mut data = Vec::new();
+    let mut stream = BitWriteStream::new(&mut data, LittleEndian);
+    stream.write_bool(true).unwrap();
+    stream.write(&expected.to_bitvec()).unwrap();
+    assert_eq!(stream.bit_len(), 36);
+
+    let mut stream = BitReadStream::new(BitReadBuffer::new(&data, LittleEndian));
+    assert!(stream.read_bool().unwrap());
+    let bits: BitVec<u16, Msb0> = stream.read_sized(expected.len()).unwrap();
+    assert_eq!(bits, expected);
+    assert_eq!(
+        stream.as_bitslice(),
+        &BitVec::<u8, Lsb0>::from(&stream.to_owned())
+    );
+    assert!(stream.read_sized::<BitVec>(5).is_err());
+    assert_eq!(stream.pos(), 36);
+}
@@ -0,0 +1,129 @@
+#![cfg(feature = "const_read")]
+
+use bitbuffer::const_read::{ConstBitReader, ConstReadError};
+use bitbuffer::{BigEndian, BitError, BitReadBuffer, BitReadStream, LittleEndian};
+
+const BYTES: &[u8] = &[
+    0b1011_0101,
+    0b0110_1010,
+    0b1010_1100,
+    0b1001_1001,
+    0b1001_1001,
+    0b1001_1001,
+    0b1001_1001,
+    0b1110_0111,
+    0b0011_1100,
+    0b1000_0001,
+];
+
+const fn unwrap(result: Result<u64, ConstReadError>) -> u64 {
+    match result {
+        Ok(value) => value,
+        Err(_) => panic!("read failed"),
+    }
+}
+
+const LE_HEADER: [u64; 3] = {
+    let mut reader = ConstBitReader::new_le(BYTES);
+    [
+        unwrap(reader.read_uint(3)),
+        unwrap(reader.read_uint(13)),
+        unwrap(reader.read_uint(64)),
+    ]
+};
+
+#[test]
+fn test_const_eval() {
+    let mut stream = BitReadStream::new(BitReadBuffer::new(BYTES, LittleEndian));
+    assert_eq!(
+        LE_HEADER,
+        [
+            stream.read_int::<u64>(3).unwrap(),
+            stream.read_int::<u64>(13).unwrap(),
+            stream.read_int::<u64>(64).unwrap(),
+        ]
+    );
+}
+
+#[test]
+fn test_matches_stream() {
+    for sizes in [[1, 7, 9, 17, 30], [5, 64, 3, 1, 0], [8, 8, 16, 32, 15]] {
+        let mut le = ConstBitReader::new_le(BYTES);
+        let mut le_stream = BitReadStream::new(BitReadBuffer::new(BYTES, LittleEndian));
+        let mut be = ConstBitReader::new_be(BYTES);
+        let mut be_stream = BitReadStream::new(BitReadBuffer::new(BYTES, BigEndian));
+        for &bits in sizes.iter().filter(|bits| **bits > 0) {
+            assert_eq!(
+                le.read_uint(bits),
+                Ok(le_stream.read_int::<u64>(bits).unwrap())
+            );
+            assert_eq!(
+                be.read_uint(bits),
+                Ok(be_stream.read_int::<u64>(bits).unwrap())
+            );
+        }
+        assert_eq!(le.pos(), le_stream.pos());
+        assert_eq!(be.pos(), be_stream.pos());
+    }
+}
+
+#[test]
+fn test_signed() {
+    let mut reader = ConstBitReader::new_be(BYTES);
+    let mut stream = BitReadStream::new(BitReadBuffer::new(BYTES, BigEndian));
+    for bits in [3, 5, 12, 4, 56] {
+        assert_eq!(
+            reader.read_sint(bits),
+            Ok(stream.read_int::<i64>(bits).unwrap())
+        );
+    }
+}
+
+#[test]
+fn test_bool_and_bytes() {
+    let mut reader = ConstBitReader::new_le(BYTES);
+    assert_eq!(reader.read_bool(), Ok(true));
+    assert_eq!(reader.read_bool(), Ok(false));
+    reader.skip_bits(6).unwrap();
+    assert_eq!(reader.read_bytes::<2>(), Ok([0b0110_1010, 0b1010_1100]));
+    assert_eq!(reader.bits_left(), 56);
+}
+
+#[test]
+fn test_errors() {
+    let mut reader = ConstBitReader::new_le(BYTES);
+    reader.skip_bits(72).unwrap();
+    assert_eq!(
+        reader.read_uint(65),
+        Err(ConstReadError::TooManyBits {
+            requested: 65,
+            max: 64
+        })
+    );
+    let err = reader.read_uint(9).unwrap_err();
+    assert_eq!(
+        err,
+        ConstReadError::NotEnoughData {
+            requested: 9,
+            bits_left: 8
+        }
+    );
+    assert_eq!(
+        reader.read_bytes::<2>().unwrap_err(),
+        ConstReadError::NotEnoughData {
+            requested: 16,
+            bits_left: 8
+        }
+    );
+    assert!(reader.skip_bits(9).is_err());
+    assert_eq!(reader.pos(), 72);
+
+    let err: BitError = err.into();
+    assert!(matches!(
+        err,
+        BitError::NotEnoughData {
+            requested: 9,
+            bits_left: 8
+        }
+    ));
+}
@@ -0,0 +1,103 @@
+use bitbuffer::crc::*;
+use bitbuffer::{BigEndian, BitReadBuffer, BitReadStream, BitWriteStream, LittleEndian};
+
+const CHECK: &[u8] = b"123456789";
+
+#[test]
+fn test_presets() {
+    assert_eq!(CRC_8.checksum(CHECK), 0xF4);
+    assert_eq!(CRC_16_ARC.checksum(CHECK), 0xBB3D);
+    assert_eq!(CRC_16_IBM_3740.checksum(CHECK), 0x29B1);
+    assert_eq!(CRC_16_KERMIT.checksum(CHECK), 0x2189);
+    assert_eq!(CRC_24_BLE.checksum(CHECK), 0xC2_5A56);
+    assert_eq!(CRC_24_MODE_S.checksum(CHECK), 0x05_4268);
+    assert_eq!(CRC_32.checksum(CHECK), 0xCBF4_3926);
+    assert_eq!(CRC_32C.checksum(CHECK), 0xE306_9283);
+    assert_eq!(CRC_32_MPEG_2.checksum(CHECK), 0x0376_E6E7);
+    assert_eq!(CRC_64_ECMA_182.checksum(CHECK), 0x6C40_DF5F_0B49_7347);
+    assert_eq!(CRC_64_XZ.checksum(CHECK), 0x995D_C9BB_DF19_39FA);
+}
+
+#[test]
+fn test_small_width() {
+    // CRC-5/USB
+    let crc5 = CrcAlgorithm {
+        width: 5,
+        poly: 0x05,
+        init: 0x1F,
+        reflect_in: true,
+        reflect_out: true,
+        xor_out: 0x1F,
+    };
+    assert_eq!(crc5.checksum(CHECK), 0x19);
+
+    // CRC-3/GSM
+    let crc3 = CrcAlgorithm {
+        width: 3,
+        poly: 0x3,
+        init: 0x0,
+        reflect_in: false,
+        reflect_out: false,
+        xor_out: 0x7,
+    };
+    assert_eq!(crc3.checksum(CHECK), 0x4);
+}
+
+#[test]
+fn test_aligned_bits_match_bytes() {
+    let buffer = BitReadBuffer::new(CHECK, LittleEndian);
+    assert_eq!(
+        CRC_32.checksum_bits(&buffer, 8, 64).unwrap(),
+        CRC_32.checksum(&CHECK[1..])
+    );
+
+    let buffer = BitReadBuffer::new(CHECK, BigEndian);
+    assert_eq!(
+        CRC_16_IBM_3740.checksum_bits(&buffer, 0, 72).unwrap(),
+        CRC_16_IBM_3740.checksum(CHECK)
+    );
+}
+
+#[test]
+fn test_unaligned_bits() {
+    // write the check bytes after 3 bits of padding
+    let mut data = Vec::new();
+    let mut stream = BitWriteStream::new(&mut data, BigEndian);
+    stream.write_int(0b101u8, 3).unwrap();
+    stream.write_bytes(CHECK).unwrap();
+    stream.write_int(0b110u8, 3).unwrap();
+
+    let mut read = BitReadStream::new(BitReadBuffer::new(&data, BigEndian));
+    read.skip_bits(3).unwrap();
+    let mut crc = Crc::new(&CRC_16_IBM_3740);
+    crc.update_stream(&mut read, 72 + 3).unwrap();
+    assert_eq!(read.bits_left(), data.len() * 8 - 78);
+
+    let mut expected = Crc::new(&CRC_16_IBM_3740);
+    expected.update(CHECK);
+    expected.update_bits(0b110, 3);
+    assert_eq!(crc.finalize(), expected.finalize());
+}
+
+#[test]
+fn test_bitwise_matches_bytewise() {
+    for algorithm in &[CRC_8, CRC_16_ARC, CRC_32, CRC_64_XZ] {
+        let mut crc = Crc::new(algorithm);
+        for byte in CHECK {
+            crc.update_bits(*byte as u64, 8);
+        }
+        assert_eq!(crc.finalize(), algorithm.checksum(CHECK));
+
+        crc.reset();
+        crc.update(CHECK);
+        assert_eq!(crc.finalize(), algorithm.checksum(CHECK));
+    }
+}
+
+#[test]
+fn test_not_enough_data() {
+    let mut read = BitReadStream::new(BitReadBuffer::new(CHECK, LittleEndian));
+    let mut crc = Crc::new(&CRC_32);
+    assert!(crc.update_stream(&mut read, 73).is_err());
+    assert_eq!(read.pos(), 0);
+}
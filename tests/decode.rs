@@ -0,0 +1,48 @@
+use bitbuffer::decode::{Decoded, Decoder, ReadDecoder};
+use bitbuffer::{BigEndian, BitError, BitReadStream, LittleEndian};
+
+#[test]
+fn test_decode_byte_by_byte() {
+    let mut decoder = Decoder::new(
+        |stream: &mut BitReadStream<BigEndian>| -> bitbuffer::Result<String> {
+            let len = stream.read_int::<usize>(4)?;
+            let bytes = stream.read_bytes(len)?;
+            Ok(String::from_utf8_lossy(&bytes).into_owned())
+        },
+        BigEndian,
+    );
+    // 3 "abc" 2 "de" and the start of a 15 byte string, each with a 4 bit length
+    let data = [0x36, 0x16, 0x26, 0x32, 0x64, 0x65, 0xF0];
+    let mut items = Vec::new();
+    for byte in data {
+        decoder.feed(&[byte]);
+        while let Decoded::Item(item) = decoder.decode().unwrap() {
+            items.push(item);
+        }
+    }
+    assert_eq!(items, vec!["abc".to_string(), "de".to_string()]);
+    assert_eq!(decoder.bits_left(), 8);
+}
+
+#[test]
+fn test_decode_need_more_data() {
+    let mut decoder = Decoder::new(ReadDecoder::<u32>::new(), LittleEndian);
+    assert_eq!(
+        decoder.decode().unwrap(),
+        Decoded::NeedMoreData { bits: 32 }
+    );
+    decoder.feed(&[1, 2, 3]);
+    assert_eq!(decoder.decode().unwrap(), Decoded::NeedMoreData { bits: 8 });
+    assert_eq!(decoder.bits_left(), 24);
+    decoder.feed(&[4, 5]);
+    assert_eq!(decoder.decode().unwrap().item(), Some(0x04030201));
+    assert_eq!(decoder.bits_left(), 8);
+}
+
+#[test]
+fn test_decode_error() {
+    let mut decoder = Decoder::new(ReadDecoder::<String>::new(), LittleEndian);
+    decoder.feed(&[0xFF, 0]);
+    assert!(matches!(decoder.decode(), Err(BitError::Utf8Error(..))));
+    assert_eq!(decoder.bits_left(), 16);
+}
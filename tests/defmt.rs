@@ -0,0 +1,32 @@
+#![cfg(feature = "defmt")]
+
+use bitbuffer::linecode::{Disparity, Manchester, Symbol};
+use bitbuffer::pcm::{ChannelLayout, SampleFormat};
+use bitbuffer::source::{BitCoord, BitNormal, CoordPrecision};
+use bitbuffer::{
+    BigEndian, BitError, BitfieldAbi, ErrorKind, LittleEndian, ReadLimit, ReadLimits,
+    TruncatePolicy, Vlq,
+};
+
+fn assert_format<T: defmt::Format>() {}
+
+#[test]
+fn test_format_impls() {
+    assert_format::<BitError>();
+    assert_format::<ErrorKind>();
+    assert_format::<ReadLimit>();
+    assert_format::<ReadLimits>();
+    assert_format::<BigEndian>();
+    assert_format::<LittleEndian>();
+    assert_format::<Disparity>();
+    assert_format::<Symbol>();
+    assert_format::<Manchester>();
+    assert_format::<SampleFormat>();
+    assert_format::<ChannelLayout>();
+    assert_format::<CoordPrecision>();
+    assert_format::<BitCoord>();
+    assert_format::<BitNormal>();
+    assert_format::<BitfieldAbi>();
+    assert_format::<TruncatePolicy>();
+    assert_format::<Vlq>();
+}
@@ -0,0 +1,105 @@
+use bitbuffer::fault::FaultyReadStream;
+use bitbuffer::{BigEndian, BitError, BitRead, BitReadBuffer, BitReadStream, ErrorKind, Result};
+
+#[derive(BitRead, Debug, PartialEq)]
+struct Packet {
+    kind: u8,
+    #[size = 12]
+    length: u16,
+    flag: bool,
+}
+
+#[test]
+fn test_fail_at() {
+    let bytes = [0x12, 0x34, 0x56, 0x78];
+    let mut stream =
+        FaultyReadStream::new(BitReadStream::new(BitReadBuffer::new(&bytes, BigEndian)));
+    stream.fail_at(20);
+
+    // the packet is 21 bits long
+    assert!(matches!(
+        stream.read::<Packet>(),
+        Err(BitError::NotEnoughData { .. })
+    ));
+    assert_eq!(stream.pos(), 0);
+
+    assert_eq!(stream.read_int::<u16>(16).unwrap(), 0x1234);
+    assert_eq!(stream.read_int::<u8>(4).unwrap(), 0x5);
+    assert!(stream.read_bool().is_err());
+    assert!(stream.skip_bits(1).is_err());
+    assert_eq!(stream.pos(), 20);
+    assert_eq!(stream.bits_left(), 12);
+
+    // the offset is relative to the start of the stream
+    stream.set_pos(0).unwrap();
+    stream.fail_at(21);
+    assert_eq!(
+        stream.read::<Packet>().unwrap(),
+        Packet {
+            kind: 0x12,
+            length: 0x345,
+            flag: false
+        }
+    );
+}
+
+#[test]
+fn test_fail_at_sub_stream() {
+    let bytes = [0x12, 0x34, 0x56, 0x78];
+    let mut inner = BitReadStream::new(BitReadBuffer::new(&bytes, BigEndian));
+    inner.skip_bits(8).unwrap();
+    let sub = inner.read_bits(16).unwrap();
+    let mut stream = FaultyReadStream::new(sub);
+    stream.fail_at(12);
+    assert!(stream.read_int::<u16>(16).is_err());
+    assert_eq!(stream.read_int::<u16>(12).unwrap(), 0x345);
+}
+
+#[test]
+fn test_fail_after() {
+    let bytes = [0x12, 0x34, 0x56, 0x78];
+    let mut stream =
+        FaultyReadStream::new(BitReadStream::new(BitReadBuffer::new(&bytes, BigEndian)));
+    stream.fail_after(2);
+    assert_eq!(stream.read::<u8>().unwrap(), 0x12);
+    assert_eq!(stream.read_bytes(1).unwrap(), &[0x34][..]);
+    let error = stream.read::<u8>().unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::Io);
+    assert!(stream.read_bool().is_err());
+    assert_eq!(stream.reads(), 4);
+    assert_eq!(stream.pos(), 16);
+
+    stream.clear_faults();
+    assert_eq!(stream.read::<u8>().unwrap(), 0x56);
+    assert_eq!(stream.into_inner().pos(), 24);
+}
+
+fn parse_without_rollback(stream: &mut BitReadStream<BigEndian>) -> Result<(u8, u16)> {
+    Ok((stream.read()?, stream.read()?))
+}
+
+fn parse_with_rollback(stream: &mut BitReadStream<BigEndian>) -> Result<(u8, u16)> {
+    let start = stream.pos();
+    parse_without_rollback(stream).inspect_err(|_| {
+        let _ = stream.set_pos(start);
+    })
+}
+
+#[test]
+fn test_rollback() {
+    let bytes = [0x12, 0x34, 0x56, 0x78];
+    let mut stream =
+        FaultyReadStream::new(BitReadStream::new(BitReadBuffer::new(&bytes, BigEndian)));
+    stream.fail_at(16);
+
+    // a parser that doesn't restore the position is caught
+    assert!(stream.with(parse_without_rollback).is_err());
+    assert_eq!(stream.pos(), 8);
+
+    stream.set_pos(0).unwrap();
+    assert!(stream.with(parse_with_rollback).is_err());
+    assert_eq!(stream.pos(), 0);
+
+    stream.fail_at(24);
+    assert_eq!(stream.with(parse_with_rollback).unwrap(), (0x12, 0x3456));
+}
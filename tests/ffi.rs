@@ -0,0 +1,170 @@
+#![cfg(feature = "ffi")]
+
+use bitbuffer::ffi::*;
+use std::ffi::CStr;
+use std::ptr;
+use std::slice;
+
+#[test]
+fn test_reader() {
+    let bytes = [0b1011_0101, 0b0110_1010, 0b1010_1100, 0b1001_1001];
+    unsafe {
+        let reader = bitbuffer_reader_new(bytes.as_ptr(), bytes.len(), true);
+        let mut uint = 0u64;
+        let mut sint = 0i64;
+        let mut boolean = false;
+        let mut out = [0u8; 2];
+
+        assert_eq!(
+            bitbuffer_reader_read_uint(reader, 3, &mut uint),
+            BitbufferStatus::Ok
+        );
+        assert_eq!(uint, 0b101);
+        assert_eq!(
+            bitbuffer_reader_read_bool(reader, &mut boolean),
+            BitbufferStatus::Ok
+        );
+        assert!(!boolean);
+        assert_eq!(
+            bitbuffer_reader_read_sint(reader, 4, &mut sint),
+            BitbufferStatus::Ok
+        );
+        assert_eq!(sint, -5);
+        assert_eq!(bitbuffer_reader_pos(reader), 8);
+        assert_eq!(
+            bitbuffer_reader_read_bytes(reader, out.as_mut_ptr(), 2),
+            BitbufferStatus::Ok
+        );
+        assert_eq!(out, [0b0110_1010, 0b1010_1100]);
+        assert_eq!(bitbuffer_reader_bits_left(reader), 8);
+
+        assert_eq!(
+            bitbuffer_reader_read_uint(reader, 65, &mut uint),
+            BitbufferStatus::InvalidInput
+        );
+        assert_eq!(
+            bitbuffer_reader_read_uint(reader, 9, &mut uint),
+            BitbufferStatus::NotEnoughData
+        );
+        assert_eq!(bitbuffer_reader_pos(reader), 24);
+        assert_eq!(
+            bitbuffer_reader_read_uint(reader, 0, &mut uint),
+            BitbufferStatus::Ok
+        );
+        assert_eq!(uint, 0);
+
+        assert_eq!(bitbuffer_reader_set_pos(reader, 4), BitbufferStatus::Ok);
+        assert_eq!(bitbuffer_reader_skip_bits(reader, 4), BitbufferStatus::Ok);
+        assert_eq!(bitbuffer_reader_pos(reader), 8);
+        assert_eq!(
+            bitbuffer_reader_set_pos(reader, 33),
+            BitbufferStatus::InvalidInput
+        );
+
+        bitbuffer_reader_free(reader);
+    }
+}
+
+#[test]
+fn test_null_pointers() {
+    unsafe {
+        assert!(bitbuffer_reader_new(ptr::null(), 1, true).is_null());
+        let reader = bitbuffer_reader_new(ptr::null(), 0, false);
+        assert!(!reader.is_null());
+        assert_eq!(
+            bitbuffer_reader_read_uint(reader, 1, ptr::null_mut()),
+            BitbufferStatus::NullPointer
+        );
+        assert_eq!(
+            bitbuffer_reader_read_bytes(reader, ptr::null_mut(), 1),
+            BitbufferStatus::NullPointer
+        );
+        assert_eq!(
+            bitbuffer_reader_skip_bits(ptr::null_mut(), 1),
+            BitbufferStatus::NullPointer
+        );
+        assert_eq!(bitbuffer_reader_bits_left(ptr::null_mut()), 0);
+        bitbuffer_reader_free(reader);
+        bitbuffer_reader_free(ptr::null_mut());
+        bitbuffer_writer_free(ptr::null_mut());
+
+        let mut len = 0;
+        assert!(bitbuffer_writer_data(ptr::null(), &mut len).is_null());
+    }
+}
+
+fn written(writer: *const BitbufferWriter) -> Vec<u8> {
+    unsafe {
+        let mut len = 0;
+        let data = bitbuffer_writer_data(writer, &mut len);
+        slice::from_raw_parts(data, len).to_vec()
+    }
+}
+
+#[test]
+fn test_writer() {
+    unsafe {
+        let writer = bitbuffer_writer_new(false);
+        assert_eq!(
+            bitbuffer_writer_write_uint(writer, 0b101, 3),
+            BitbufferStatus::Ok
+        );
+        assert_eq!(
+            bitbuffer_writer_write_bool(writer, true),
+            BitbufferStatus::Ok
+        );
+        assert_eq!(written(writer), [0b1011_0000]);
+        assert_eq!(
+            bitbuffer_writer_write_sint(writer, -2, 8),
+            BitbufferStatus::Ok
+        );
+        assert_eq!(
+            bitbuffer_writer_write_bytes(writer, [0xAB].as_ptr(), 1),
+            BitbufferStatus::Ok
+        );
+        assert_eq!(bitbuffer_writer_bit_len(writer), 20);
+        assert_eq!(written(writer), [0b1011_1111, 0b1110_1010, 0b1011_0000]);
+
+        // failed writes leave the writer unchanged
+        assert_eq!(
+            bitbuffer_writer_write_uint(writer, 1, 65),
+            BitbufferStatus::InvalidInput
+        );
+        assert_eq!(bitbuffer_writer_bit_len(writer), 20);
+        assert_eq!(written(writer), [0b1011_1111, 0b1110_1010, 0b1011_0000]);
+        bitbuffer_writer_free(writer);
+    }
+}
+
+#[test]
+fn test_writer_roundtrip() {
+    unsafe {
+        let writer = bitbuffer_writer_new(true);
+        for (value, bits) in [(0b101, 3), (0x1234, 13), (u64::MAX, 64), (0, 1), (7, 5)] {
+            assert_eq!(
+                bitbuffer_writer_write_uint(writer, value, bits),
+                BitbufferStatus::Ok
+            );
+        }
+        let data = written(writer);
+        bitbuffer_writer_free(writer);
+
+        let reader = bitbuffer_reader_new(data.as_ptr(), data.len(), true);
+        let mut value = 0;
+        for (expected, bits) in [(0b101, 3), (0x1234, 13), (u64::MAX, 64), (0, 1), (7, 5)] {
+            assert_eq!(
+                bitbuffer_reader_read_uint(reader, bits, &mut value),
+                BitbufferStatus::Ok
+            );
+            assert_eq!(value, expected);
+        }
+        bitbuffer_reader_free(reader);
+    }
+}
+
+#[test]
+fn test_status_message() {
+    let message =
+        unsafe { CStr::from_ptr(bitbuffer_status_message(BitbufferStatus::NotEnoughData)) };
+    assert_eq!(message.to_str().unwrap(), "not enough data");
+}
@@ -0,0 +1,182 @@
+#![cfg(feature = "futures")]
+
+use bitbuffer::futures::{read_items, AsyncBitWriter, DecodeStream};
+use bitbuffer::{BigEndian, BitError, BitReadStream, LittleEndian};
+use futures::executor::block_on;
+use futures::io::{AsyncRead, AsyncWrite, Cursor};
+use futures::StreamExt;
+use std::io;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+/// Returns the data one byte at a time, alternating with `Pending`
+struct Trickle {
+    data: Vec<u8>,
+    pos: usize,
+    pending: bool,
+    error: bool,
+}
+
+impl Trickle {
+    fn new(data: &[u8]) -> Self {
+        Trickle {
+            data: data.to_vec(),
+            pos: 0,
+            pending: false,
+            error: false,
+        }
+    }
+}
+
+impl AsyncRead for Trickle {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        self.pending = !self.pending;
+        if self.pending {
+            cx.waker().wake_by_ref();
+            return Poll::Pending;
+        }
+        if self.pos == self.data.len() {
+            return Poll::Ready(if self.error {
+                Err(io::Error::new(io::ErrorKind::ConnectionReset, "reset"))
+            } else {
+                Ok(0)
+            });
+        }
+        buf[0] = self.data[self.pos];
+        self.pos += 1;
+        Poll::Ready(Ok(1))
+    }
+}
+
+#[test]
+fn test_stream_partial_reads() {
+    // 5 values of 12 bits with 4 bits of padding
+    let data = [0x01, 0x20, 0x00, 0x03, 0x40, 0x00, 0x05, 0x60];
+    let items: Vec<u16> = block_on(
+        DecodeStream::new(
+            Trickle::new(&data),
+            |stream: &mut BitReadStream<LittleEndian>| stream.read_int::<u16>(12),
+            LittleEndian,
+        )
+        .map(|item| item.unwrap())
+        .collect(),
+    );
+    assert_eq!(items, [1, 2, 3, 4, 5]);
+}
+
+#[test]
+fn test_stream_trailing_data() {
+    let mut stream = read_items::<u32, _, _>(Cursor::new([1, 0, 0, 0, 2, 0]), LittleEndian);
+    block_on(async {
+        assert_eq!(stream.next().await.unwrap().unwrap(), 1);
+        assert!(matches!(
+            stream.next().await,
+            Some(Err(BitError::NotEnoughData {
+                requested: 32,
+                bits_left: 16
+            }))
+        ));
+        assert!(stream.next().await.is_none());
+    });
+}
+
+#[test]
+fn test_stream_io_error() {
+    let mut reader = Trickle::new(&[1, 2, 3]);
+    reader.error = true;
+    let mut stream = read_items::<u16, _, _>(reader, LittleEndian);
+    block_on(async {
+        assert_eq!(stream.next().await.unwrap().unwrap(), 0x0201);
+        assert!(matches!(stream.next().await, Some(Err(BitError::Io(_)))));
+        assert!(stream.next().await.is_none());
+    });
+}
+
+/// Accepts a single byte at a time, alternating with `Pending`
+#[derive(Default)]
+struct Drip {
+    data: Vec<u8>,
+    pending: bool,
+    flushed: bool,
+}
+
+impl AsyncWrite for Drip {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        self.pending = !self.pending;
+        if self.pending {
+            cx.waker().wake_by_ref();
+            return Poll::Pending;
+        }
+        self.data.push(buf[0]);
+        Poll::Ready(Ok(1))
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.flushed = true;
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_close(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+}
+
+#[test]
+fn test_writer_watermarks() {
+    let mut writer = AsyncBitWriter::new(Drip::default(), LittleEndian).with_watermarks(4, 2);
+    assert_eq!((writer.high_watermark(), writer.low_watermark()), (4, 2));
+    block_on(async {
+        writer.write(&0x0201u16).unwrap();
+        writer
+            .write_with(|stream| stream.write_int(3u16, 12))
+            .unwrap();
+        assert_eq!(writer.buffered_bytes(), 3);
+        assert!(writer.is_ready());
+        writer.flush_ready().await.unwrap();
+        assert!(writer.get_ref().data.is_empty());
+
+        writer
+            .write_with(|stream| stream.write_int(0x40u16, 12))
+            .unwrap();
+        assert_eq!(writer.buffered_bytes(), 5);
+        assert!(!writer.is_ready());
+        writer.flush_ready().await.unwrap();
+        assert_eq!(writer.get_ref().data, [0x01, 0x02, 0x03]);
+        assert_eq!(writer.buffered_bytes(), 2);
+
+        writer.write_with(|stream| stream.write_bool(true)).unwrap();
+        writer.flush().await.unwrap();
+        assert_eq!(writer.get_ref().data, [0x01, 0x02, 0x03, 0x00, 0x04]);
+        assert!(writer.get_ref().flushed);
+
+        let drip = writer.finish().await.unwrap();
+        assert_eq!(drip.data, [0x01, 0x02, 0x03, 0x00, 0x04, 0x01]);
+    });
+}
+
+#[test]
+fn test_writer_failed_write() {
+    let mut writer = AsyncBitWriter::new(Vec::new(), BigEndian);
+    writer
+        .write_with(|stream| stream.write_int(0b101u8, 3))
+        .unwrap();
+    assert!(writer
+        .write_with(|stream| {
+            stream.write(&0xFFFFu16)?;
+            stream.write_int(0u8, 9)
+        })
+        .is_err());
+    assert_eq!(writer.buffered_bytes(), 0);
+    writer
+        .write_with(|stream| stream.write_int(0u8, 5))
+        .unwrap();
+    assert_eq!(block_on(writer.finish()).unwrap(), [0b1010_0000]);
+}
@@ -0,0 +1,143 @@
+use bitbuffer::gps::{
+    decode_word, encode_word, parity, read_subframe, read_word, write_word, PREAMBLE,
+    SUBFRAME_WORDS, WORD_BITS,
+};
+use bitbuffer::{BigEndian, BitError, BitReadBuffer, BitReadStream, BitWriteStream};
+
+#[test]
+fn test_parity() {
+    assert_eq!(parity(0, 0), 0);
+    // every parity bit includes one of the previous bits
+    assert_eq!(parity(0, 0b11), 0b11_1111);
+    assert_eq!(parity(0, 0b10), 0b10_1001);
+    assert_eq!(parity(0, 0b01), 0b01_0110);
+    // d1 is included in parity bits 25, 27 and 29
+    assert_eq!(parity(0x80_0000, 0), 0b10_1010);
+    // d24 is included in parity bits 26, 29 and 30
+    assert_eq!(parity(0x00_0001, 0), 0b01_0011);
+}
+
+#[test]
+fn test_word_roundtrip() {
+    for &data in &[0, 0xFF_FFFF, 0x8B_0000, 0x12_3456, 0xA5_5A5A] {
+        for previous in 0..4 {
+            let word = encode_word(data, previous);
+            assert_eq!(word.data, data);
+            assert!(word.raw < 1 << WORD_BITS);
+            assert_eq!(decode_word(word.raw, previous), Some(word));
+        }
+    }
+    // the data bits are inverted after a word ending with a one
+    assert_eq!(encode_word(0x12_3456, 1).raw >> 6, 0xED_CBA9);
+}
+
+#[test]
+fn test_single_bit_errors_are_detected() {
+    for previous in 0..4 {
+        let word = encode_word(0x5C_1D2E, previous);
+        for bit in 0..WORD_BITS {
+            assert_eq!(decode_word(word.raw ^ 1 << bit, previous), None);
+        }
+        // wrong previous bits are detected too
+        assert_eq!(decode_word(word.raw, previous ^ 0b10), None);
+    }
+}
+
+#[test]
+fn test_read_write_word() {
+    let mut data = Vec::new();
+    let mut stream = BitWriteStream::new(&mut data, BigEndian);
+    let mut previous = 0;
+    let mut written = Vec::new();
+    for value in 0..5 {
+        let word = write_word(&mut stream, value * 0x03_3333, previous).unwrap();
+        written.push(word);
+        previous = word.raw;
+    }
+    assert_eq!(stream.bit_len(), 5 * WORD_BITS);
+    drop(stream);
+
+    let mut stream = BitReadStream::new(BitReadBuffer::new(&data, BigEndian));
+    let mut previous = 0;
+    for expected in written {
+        let word = read_word(&mut stream, previous).unwrap();
+        assert_eq!(word, expected);
+        previous = word.raw;
+    }
+
+    let mut stream = BitReadStream::new(BitReadBuffer::new(&data, BigEndian));
+    stream.skip_bits(WORD_BITS).unwrap();
+    assert!(matches!(
+        read_word(&mut stream, 0b01),
+        Err(BitError::Malformed { pos: 30, .. })
+    ));
+    assert_eq!(stream.pos(), WORD_BITS);
+}
+
+fn subframe(words: &[u32; SUBFRAME_WORDS]) -> Vec<u8> {
+    let mut data = Vec::new();
+    let mut stream = BitWriteStream::new(&mut data, BigEndian);
+    let mut previous = 0;
+    for &word in words {
+        previous = write_word(&mut stream, word, previous).unwrap().raw;
+    }
+    drop(stream);
+    data
+}
+
+fn subframe_words() -> [u32; SUBFRAME_WORDS] {
+    let mut words = [0x55_AA55; SUBFRAME_WORDS];
+    words[0] = PREAMBLE << 16 | 0x1234;
+    // tow 1000, subframe 3
+    words[1] = 1000 << 7 | 3 << 2;
+    words
+}
+
+#[test]
+fn test_read_subframe() {
+    let words = subframe_words();
+    let data = subframe(&words);
+    let mut stream = BitReadStream::new(BitReadBuffer::new(&data, BigEndian));
+    let subframe = read_subframe(&mut stream).unwrap();
+    assert_eq!(stream.pos(), 300);
+    assert_eq!(subframe.words, words);
+    assert_eq!(subframe.id(), 3);
+    assert_eq!(subframe.tow(), 1000);
+}
+
+#[test]
+fn test_read_inverted_subframe() {
+    let words = subframe_words();
+    let data: Vec<u8> = subframe(&words).iter().map(|byte| !byte).collect();
+    let mut stream = BitReadStream::new(BitReadBuffer::new(&data, BigEndian));
+    let subframe = read_subframe(&mut stream).unwrap();
+    assert_eq!(subframe.words, words);
+}
+
+#[test]
+fn test_subframe_errors() {
+    let mut words = subframe_words();
+    let mut data = subframe(&words);
+    data[20] ^= 0x10;
+    let mut stream = BitReadStream::new(BitReadBuffer::new(&data, BigEndian));
+    assert!(matches!(
+        read_subframe(&mut stream),
+        Err(BitError::Malformed { pos: 150, .. })
+    ));
+    assert_eq!(stream.pos(), 0);
+
+    words[0] = 0x12_3456;
+    let data = subframe(&words);
+    let mut stream = BitReadStream::new(BitReadBuffer::new(&data, BigEndian));
+    assert!(matches!(
+        read_subframe(&mut stream),
+        Err(BitError::Malformed { pos: 0, .. })
+    ));
+
+    let data = subframe(&subframe_words());
+    let mut stream = BitReadStream::new(BitReadBuffer::new(&data[..37], BigEndian));
+    assert!(matches!(
+        read_subframe(&mut stream),
+        Err(BitError::NotEnoughData { .. })
+    ));
+}
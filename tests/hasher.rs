@@ -0,0 +1,168 @@
+use bitbuffer::crc::{Crc, CRC_16_IBM_3740, CRC_32};
+use bitbuffer::{BigEndian, BitWriteStream, Endianness, HasherAdapter, LittleEndian, Vlq};
+use std::collections::hash_map::DefaultHasher;
+use std::hash::Hasher;
+
+fn write_data<E: Endianness>(stream: &mut BitWriteStream<E>) {
+    stream.write_bool(true).unwrap();
+    stream.write_int(0b1_0110u8, 5).unwrap();
+    stream.write_int(1234567u32, 27).unwrap();
+    stream.write_string("foobar", None).unwrap();
+    stream.write_int(-12345i64, 64).unwrap();
+    stream.write_float(1.5f32).unwrap();
+    stream.write_int(0b101u8, 3).unwrap();
+}
+
+fn test_crc_matches<E: Endianness>(endianness: E) {
+    let mut data = Vec::new();
+    let mut stream = BitWriteStream::new(&mut data, endianness);
+    stream.attach_hasher(Crc::new(&CRC_32));
+    write_data(&mut stream);
+    let hash = stream.finalize_hash().unwrap();
+    assert_eq!(stream.finalize_hash(), None);
+
+    let expected = CRC_32.checksum(&data) as u32;
+    assert_eq!(hash, expected.to_be_bytes().to_vec());
+}
+
+#[test]
+fn test_crc_hasher() {
+    test_crc_matches(LittleEndian);
+    test_crc_matches(BigEndian);
+}
+
+#[test]
+fn test_hasher_attached_midway() {
+    let mut data = Vec::new();
+    let mut stream = BitWriteStream::new(&mut data, BigEndian);
+    stream.write_int(0xABu8, 8).unwrap();
+    stream.attach_hasher(Crc::new(&CRC_16_IBM_3740));
+    stream.write_bytes(b"123456789").unwrap();
+    assert_eq!(stream.finalize_hash().unwrap(), vec![0x29, 0xB1]);
+}
+
+#[test]
+fn test_std_hasher() {
+    let mut data = Vec::new();
+    let mut stream = BitWriteStream::new(&mut data, LittleEndian);
+    stream.attach_hasher(HasherAdapter(DefaultHasher::new()));
+    write_data(&mut stream);
+    let hash = stream.finalize_hash().unwrap();
+
+    let mut expected = DefaultHasher::new();
+    expected.write(&data);
+    assert_eq!(hash, expected.finish().to_be_bytes().to_vec());
+}
+
+#[test]
+fn test_hasher_reserve_length() {
+    let write = |data: &mut Vec<u8>, hash: bool| {
+        let mut stream = BitWriteStream::new(data, LittleEndian);
+        if hash {
+            stream.attach_hasher(Crc::new(&CRC_32));
+        }
+        stream.write_bool(true).unwrap();
+        stream
+            .reserve_length(7, |stream| {
+                stream.write_int(12u8, 5)?;
+                stream.write_string("foo", None)
+            })
+            .unwrap();
+        stream.write_bool(true).unwrap();
+        stream.finalize_hash()
+    };
+
+    let mut plain = Vec::new();
+    assert_eq!(write(&mut plain, false), None);
+    let mut hashed = Vec::new();
+    let hash = write(&mut hashed, true).unwrap();
+
+    assert_eq!(plain, hashed);
+    assert_eq!(
+        hash,
+        (CRC_32.checksum(&hashed) as u32).to_be_bytes().to_vec()
+    );
+}
+
+#[cfg(feature = "digest")]
+#[test]
+fn test_digest_hasher() {
+    use sha2::{Digest, Sha256};
+
+    let mut data = Vec::new();
+    let mut stream = BitWriteStream::new(&mut data, BigEndian);
+    stream.attach_hasher(Sha256::new());
+    write_data(&mut stream);
+    let hash = stream.finalize_hash().unwrap();
+
+    assert_eq!(hash, Sha256::digest(&data).to_vec());
+}
+
+#[test]
+fn test_hasher_offsets() {
+    let write = |data: &mut Vec<u8>, hash: bool| {
+        let mut stream = BitWriteStream::new(data, BigEndian);
+        if hash {
+            stream.attach_hasher(Crc::new(&CRC_32));
+        }
+        let inner = stream.create_label();
+        let outer = stream.create_label();
+        stream.write_offset(inner, 8).unwrap();
+        stream
+            .reserve_length(8, |stream| {
+                stream.write_offset(outer, 8)?;
+                stream.write_bool(true)?;
+                stream.place_label(inner)
+            })
+            .unwrap();
+        stream.place_label(outer).unwrap();
+        stream.write_int(0xFFu8, 8).unwrap();
+        stream.resolve_offsets().unwrap();
+        stream.finalize_hash()
+    };
+
+    let mut plain = Vec::new();
+    write(&mut plain, false);
+    let mut hashed = Vec::new();
+    write(&mut hashed, true);
+
+    assert_eq!(plain, [25, 9, 25, 0xFF, 0x80]);
+    assert_eq!(plain, hashed);
+}
+
+#[test]
+fn test_hasher_write_with_offsets() {
+    let write = |data: &mut Vec<u8>, hash: bool| {
+        let mut stream = BitWriteStream::new(data, LittleEndian);
+        if hash {
+            stream.attach_hasher(Crc::new(&CRC_32));
+        }
+        stream
+            .write_with_offsets(|stream, positions| {
+                let labels = [stream.create_label(), stream.create_label()];
+                for label in labels {
+                    stream.write(&Vlq(positions.get(label) as u64))?;
+                }
+                stream.write_bytes(&[0; 20])?;
+                for label in labels {
+                    stream.place_label(label)?;
+                    stream.write_string("foo", None)?;
+                }
+                Ok(())
+            })
+            .unwrap();
+        stream.finalize_hash()
+    };
+
+    let mut plain = Vec::new();
+    write(&mut plain, false);
+    let mut hashed = Vec::new();
+    let hash = write(&mut hashed, true).unwrap();
+
+    assert_eq!(plain[..4], [0x81, 0x40, 0x81, 0x60]);
+    assert_eq!(plain, hashed);
+    assert_eq!(
+        hash,
+        (CRC_32.checksum(&hashed) as u32).to_be_bytes().to_vec()
+    );
+}
@@ -0,0 +1,133 @@
+use bitbuffer::inflate::{inflate, zlib_decompress};
+use bitbuffer::{BitError, BitReadBuffer, BitReadStream, LittleEndian, ReadLimit, ReadLimits};
+
+const STORED: [u8; 28] = [
+    0x78, 0x01, 0x01, 0x11, 0x00, 0xEE, 0xFF, 0x68, 0x65, 0x6C, 0x6C, 0x6F, 0x20, 0x68, 0x65, 0x6C,
+    0x6C, 0x6F, 0x20, 0x68, 0x65, 0x6C, 0x6C, 0x6F, 0x3A, 0x2E, 0x06, 0x7D,
+];
+
+const FIXED: [u8; 16] = [
+    0x78, 0xDA, 0xCB, 0x48, 0xCD, 0xC9, 0xC9, 0x57, 0xC8, 0x40, 0x90, 0x00, 0x3A, 0x2E, 0x06, 0x7D,
+];
+
+const DYNAMIC: [u8; 97] = [
+    0x78, 0xDA, 0x75, 0xCE, 0xC7, 0x0D, 0x80, 0x30, 0x10, 0x05, 0xD1, 0x3B, 0x55, 0xFC, 0x12, 0x8C,
+    0x93, 0x44, 0x1F, 0x34, 0x40, 0x30, 0x19, 0x0C, 0x06, 0x93, 0xAA, 0x87, 0x02, 0x76, 0xEF, 0x6F,
+    0xA4, 0xC9, 0x3B, 0x87, 0x2D, 0xF6, 0xD5, 0x88, 0x32, 0xF8, 0x6B, 0x41, 0xE3, 0x6F, 0x08, 0x0C,
+    0x71, 0x5E, 0x77, 0xF8, 0xD3, 0x05, 0x1C, 0x3F, 0x98, 0x8A, 0xF7, 0x41, 0xED, 0x5B, 0x88, 0x24,
+    0x27, 0x7C, 0xCA, 0xFA, 0x94, 0xF4, 0x92, 0xF5, 0x9A, 0xF4, 0x8A, 0xF5, 0x19, 0xE9, 0x35, 0xFF,
+    0x63, 0xC9, 0xC0, 0xB0, 0x81, 0x34, 0x64, 0x60, 0xD9, 0x40, 0xD9, 0xE4, 0x03, 0xDA, 0xE2, 0x74,
+    0x60,
+];
+
+fn dynamic_text() -> Vec<u8> {
+    (0..7)
+        .map(|i| {
+            format!(
+                "The quick brown fox {} jumps over the lazy dog {}\n",
+                i,
+                i * i
+            )
+        })
+        .collect::<String>()
+        .into_bytes()
+}
+
+#[test]
+fn test_stored() {
+    let mut stream = BitReadStream::new(BitReadBuffer::new(&STORED, LittleEndian));
+    assert_eq!(zlib_decompress(&mut stream).unwrap(), b"hello hello hello");
+    assert_eq!(stream.bits_left(), 0);
+}
+
+#[test]
+fn test_fixed() {
+    let mut stream = BitReadStream::new(BitReadBuffer::new(&FIXED, LittleEndian));
+    assert_eq!(zlib_decompress(&mut stream).unwrap(), b"hello hello hello");
+    assert_eq!(stream.bits_left(), 0);
+}
+
+#[test]
+fn test_dynamic() {
+    let mut stream = BitReadStream::new(BitReadBuffer::new(&DYNAMIC, LittleEndian));
+    assert_eq!(zlib_decompress(&mut stream).unwrap(), dynamic_text());
+    assert_eq!(stream.bits_left(), 0);
+}
+
+#[test]
+fn test_raw_deflate() {
+    // overlapping back reference
+    let data = [0x4B, 0x4C, 0x4A, 0x4E, 0xC4, 0x40, 0x29, 0x00, 0xAA];
+    let mut stream = BitReadStream::new(BitReadBuffer::new(&data, LittleEndian));
+    assert_eq!(inflate(&mut stream).unwrap(), b"abcabcabcabcabcabcabcd");
+    stream.align_to_byte().unwrap();
+    assert_eq!(stream.bits_left(), 8);
+}
+
+#[test]
+fn test_output_limit() {
+    let mut stream = BitReadStream::new(BitReadBuffer::new(&DYNAMIC, LittleEndian));
+    stream.set_limits(ReadLimits {
+        max_elements: 100,
+        ..ReadLimits::default()
+    });
+    assert!(matches!(
+        zlib_decompress(&mut stream),
+        Err(BitError::LimitExceeded {
+            limit: ReadLimit::Elements,
+            max: 100,
+            ..
+        })
+    ));
+    assert_eq!(stream.pos(), 0);
+}
+
+#[test]
+fn test_errors() {
+    let mut data = DYNAMIC;
+    data[96] ^= 1;
+    let mut stream = BitReadStream::new(BitReadBuffer::new(&data, LittleEndian));
+    assert!(matches!(
+        zlib_decompress(&mut stream),
+        Err(BitError::Malformed { pos: 744, .. })
+    ));
+    assert_eq!(stream.pos(), 0);
+
+    let mut data = FIXED;
+    data[1] ^= 1;
+    let mut stream = BitReadStream::new(BitReadBuffer::new(&data, LittleEndian));
+    assert!(matches!(
+        zlib_decompress(&mut stream),
+        Err(BitError::Malformed { pos: 0, .. })
+    ));
+
+    // stored block length doesn't match the complement
+    let mut data = STORED;
+    data[5] ^= 1;
+    let mut stream = BitReadStream::new(BitReadBuffer::new(&data, LittleEndian));
+    assert!(matches!(
+        zlib_decompress(&mut stream),
+        Err(BitError::Malformed { pos: 24, .. })
+    ));
+
+    // reserved block type
+    let mut stream = BitReadStream::new(BitReadBuffer::new(&[0b111], LittleEndian));
+    assert!(matches!(
+        inflate(&mut stream),
+        Err(BitError::Malformed { pos: 1, .. })
+    ));
+
+    // back reference before the start of the output, fixed block with length code 257 and distance code 0
+    let mut stream = BitReadStream::new(BitReadBuffer::new(&[0x03, 0x02, 0x00], LittleEndian));
+    assert!(matches!(
+        inflate(&mut stream),
+        Err(BitError::Malformed { pos: 3, .. })
+    ));
+
+    let mut stream = BitReadStream::new(BitReadBuffer::new(&DYNAMIC[..50], LittleEndian));
+    assert!(matches!(
+        zlib_decompress(&mut stream),
+        Err(BitError::NotEnoughData { .. })
+    ));
+    assert_eq!(stream.pos(), 0);
+}
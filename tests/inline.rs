@@ -0,0 +1,79 @@
+#![cfg(feature = "inline")]
+
+use bitbuffer::inline::InlineBuffer;
+use bitbuffer::{BigEndian, BitReadBuffer, BitReadStream, BitWriteStream, LittleEndian};
+
+#[test]
+fn test_inline_matches_vec() {
+    let mut vec = Vec::new();
+    let mut inline = InlineBuffer::<16>::new();
+    {
+        let mut vec_stream = BitWriteStream::new(&mut vec, LittleEndian);
+        let mut inline_stream = BitWriteStream::new_inline(&mut inline, LittleEndian);
+        for stream in [&mut vec_stream, &mut inline_stream] {
+            stream.write_int(0b101u8, 3).unwrap();
+            stream.write_bool(true).unwrap();
+            stream.write_int(0x1234u16, 13).unwrap();
+            stream.write_string("foo", None).unwrap();
+        }
+        assert_eq!(inline_stream.bit_len(), vec_stream.bit_len());
+    }
+    assert_eq!(inline.as_slice(), vec.as_slice());
+    assert!(!inline.spilled());
+}
+
+#[test]
+fn test_inline_spill() {
+    let mut data = InlineBuffer::<4>::new();
+    let mut stream = BitWriteStream::new_inline(&mut data, BigEndian);
+    stream.write_int(0b1u8, 1).unwrap();
+    stream.write_bytes(&[1, 2, 3, 4, 5, 6]).unwrap();
+    let (bytes, bit_len) = stream.finish();
+    assert_eq!(bit_len, 49);
+    assert_eq!(bytes.len(), 7);
+    assert!(data.spilled());
+
+    let mut read = BitReadStream::new(BitReadBuffer::new(&data, BigEndian));
+    assert!(read.read_bool().unwrap());
+    assert_eq!(read.read_bytes(6).unwrap().as_ref(), &[1, 2, 3, 4, 5, 6]);
+}
+
+#[test]
+fn test_inline_reserve_length() {
+    let mut data = InlineBuffer::<8>::new();
+    let mut stream = BitWriteStream::new_inline(&mut data, LittleEndian);
+    stream.write_int(0xFFu8, 8).unwrap();
+    stream
+        .reserve_length(8, |stream| stream.write_int(0xABCu16, 12))
+        .unwrap();
+    stream.truncate(20);
+    assert_eq!(stream.bit_len(), 20);
+    assert_eq!(data.into_vec(), vec![0xFF, 12, 0x0C]);
+}
+
+#[test]
+fn test_inline_existing_data() {
+    let mut vec = vec![0xAA];
+    let mut inline = InlineBuffer::<8>::new();
+    BitWriteStream::new_inline(&mut inline, LittleEndian)
+        .write_int(0xAAu8, 8)
+        .unwrap();
+
+    for (bytes, bit_len) in [
+        {
+            let mut stream = BitWriteStream::new(&mut vec, LittleEndian);
+            stream.write_int(0x1u8, 4).unwrap();
+            stream.copy_within(0..4).unwrap();
+            stream.finish()
+        },
+        {
+            let mut stream = BitWriteStream::new_inline(&mut inline, LittleEndian);
+            stream.write_int(0x1u8, 4).unwrap();
+            stream.copy_within(0..4).unwrap();
+            stream.finish()
+        },
+    ] {
+        assert_eq!(bit_len, 8);
+        assert_eq!(bytes, [0x11]);
+    }
+}
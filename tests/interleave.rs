@@ -0,0 +1,136 @@
+use bitbuffer::interleave::{deinterleave, interleave, split_channels};
+use bitbuffer::{
+    BigEndian, BitError, BitReadBuffer, BitReadStream, BitWriteStream, Endianness, LittleEndian,
+};
+
+fn test_data() -> Vec<u8> {
+    (0..64u32).map(|i| (i * 37 + 11) as u8).collect()
+}
+
+/// Per bit reference implementation
+fn naive_deinterleave<E: Endianness>(
+    data: &[u8],
+    offset: usize,
+    channels: usize,
+    width: usize,
+    count: usize,
+) -> Vec<Vec<bool>> {
+    let buffer = BitReadBuffer::new(data, E::endianness());
+    let mut result = vec![Vec::new(); channels];
+    for i in 0..channels * width * count {
+        let channel = (i / width) % channels;
+        result[channel].push(buffer.read_bool(offset + i).unwrap());
+    }
+    result
+}
+
+fn read_all_bits<E: Endianness>(mut stream: BitReadStream<E>) -> Vec<bool> {
+    (0..stream.bit_len())
+        .map(|_| stream.read_bool().unwrap())
+        .collect()
+}
+
+fn test_deinterleave_with<E: Endianness>(channels: usize, width: usize, count: usize) {
+    let data = test_data();
+    for offset in [0, 3] {
+        let mut stream = BitReadStream::new(BitReadBuffer::new(&data, E::endianness()));
+        stream.skip_bits(offset).unwrap();
+        let split = split_channels(&mut stream, channels, width, count).unwrap();
+        assert_eq!(stream.pos(), offset + channels * width * count);
+
+        let expected = naive_deinterleave::<E>(&data, offset, channels, width, count);
+        let actual: Vec<_> = split.into_iter().map(read_all_bits).collect();
+        assert_eq!(
+            actual, expected,
+            "{} channels, width {}, count {}",
+            channels, width, count
+        );
+    }
+}
+
+fn test_interleave_with<E: Endianness>(channels: usize, width: usize, count: usize) {
+    let data = test_data();
+    let bits = channels * width * count;
+    let mut stream = BitReadStream::new(BitReadBuffer::new(&data, E::endianness()));
+    let mut split = split_channels(&mut stream, channels, width, count).unwrap();
+
+    let mut output = Vec::new();
+    let mut output_stream = BitWriteStream::new(&mut output, E::endianness());
+    output_stream.write_bool(true).unwrap();
+    interleave(&mut split, &mut output_stream, width, count).unwrap();
+    assert_eq!(output_stream.bit_len(), bits + 1);
+    assert!(split.iter().all(|channel| channel.bits_left() == 0));
+
+    let mut result = BitReadStream::new(BitReadBuffer::new(&output, E::endianness()));
+    result.skip_bits(1).unwrap();
+    let mut original = BitReadStream::new(BitReadBuffer::new(&data, E::endianness()));
+    assert!(
+        result.read_bits(bits).unwrap() == original.read_bits(bits).unwrap(),
+        "{} channels, width {}, count {}",
+        channels,
+        width,
+        count
+    );
+}
+
+#[test]
+fn test_deinterleave() {
+    for (channels, width, count) in [
+        (1, 1, 100),
+        (2, 1, 100),
+        (3, 1, 50),
+        (4, 2, 20),
+        (5, 3, 17),
+        (2, 16, 9),
+        (3, 24, 5),
+        (1, 64, 3),
+        (2, 33, 3),
+        (3, 70, 2),
+    ] {
+        test_deinterleave_with::<LittleEndian>(channels, width, count);
+        test_deinterleave_with::<BigEndian>(channels, width, count);
+        test_interleave_with::<LittleEndian>(channels, width, count);
+        test_interleave_with::<BigEndian>(channels, width, count);
+    }
+}
+
+#[test]
+fn test_deinterleave_not_enough_data() {
+    let data = [0xFF; 4];
+    let mut stream = BitReadStream::new(BitReadBuffer::new(&data, LittleEndian));
+    let mut a = Vec::new();
+    let mut b = Vec::new();
+    let mut outputs = [
+        BitWriteStream::new(&mut a, LittleEndian),
+        BitWriteStream::new(&mut b, LittleEndian),
+    ];
+    assert!(matches!(
+        deinterleave(&mut stream, &mut outputs, 4, 5),
+        Err(BitError::NotEnoughData {
+            requested: 40,
+            bits_left: 32
+        })
+    ));
+    assert_eq!(stream.pos(), 0);
+    assert_eq!(outputs[0].bit_len(), 0);
+}
+
+#[test]
+fn test_interleave_not_enough_data() {
+    let a = [0xFF; 4];
+    let b = [0xFF; 3];
+    let mut inputs = [
+        BitReadStream::new(BitReadBuffer::new(&a, LittleEndian)),
+        BitReadStream::new(BitReadBuffer::new(&b, LittleEndian)),
+    ];
+    let mut output = Vec::new();
+    assert!(interleave(
+        &mut inputs,
+        &mut BitWriteStream::new(&mut output, LittleEndian),
+        8,
+        4
+    )
+    .is_err());
+    assert_eq!(inputs[0].pos(), 0);
+    assert!(output.is_empty());
+}
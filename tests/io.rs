@@ -0,0 +1,147 @@
+use bitbuffer::io::{BitFileWriter, BitReader};
+use bitbuffer::{BigEndian, BitError, BitWriteStream, LittleEndian};
+use std::io::{self, Read};
+
+/// A reader that returns a single byte for every read
+struct ByteByByte<'a> {
+    data: &'a [u8],
+    interrupt: bool,
+}
+
+impl Read for ByteByByte<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.interrupt = !self.interrupt;
+        if self.interrupt {
+            return Err(io::ErrorKind::Interrupted.into());
+        }
+        match self.data.split_first() {
+            Some((first, rest)) if !buf.is_empty() => {
+                buf[0] = *first;
+                self.data = rest;
+                Ok(1)
+            }
+            _ => Ok(0),
+        }
+    }
+}
+
+#[test]
+fn test_read_across_reads() {
+    let reader = ByteByByte {
+        data: &[0x21, 0x43, 0x65, 0x87],
+        interrupt: false,
+    };
+    let mut reader = BitReader::new(reader, LittleEndian);
+    assert_eq!(
+        reader.read_with(|stream| stream.read_int::<u8>(4)).unwrap(),
+        1
+    );
+    assert_eq!(reader.read::<u16>().unwrap(), 0x5432);
+    assert_eq!(reader.buffered_bits(), 4);
+    assert_eq!(reader.read_sized::<u16>(12).unwrap(), 0x876);
+    assert!(matches!(
+        reader.read::<u8>(),
+        Err(BitError::NotEnoughData {
+            requested: 8,
+            bits_left: 0
+        })
+    ));
+}
+
+#[test]
+fn test_lookahead() {
+    let data: &[u8] = &[0x10, 0x02, 0xAB, 0xCD, 0x20];
+    let reader = ByteByByte {
+        data,
+        interrupt: false,
+    };
+    let mut reader = BitReader::new(reader, BigEndian);
+    let mut header = reader.lookahead(16).unwrap();
+    assert_eq!(header.read_int::<u16>(16).unwrap(), 0x1002);
+    assert_eq!(header.bits_left(), 0);
+    assert_eq!(reader.buffered_bits(), 16);
+
+    reader.consume(4);
+    let stream = reader.fill_buf(28).unwrap();
+    assert_eq!(stream.bits_left(), 28);
+    assert_eq!(stream.clone().read_int::<u16>(12).unwrap(), 0x002);
+    reader.consume(12);
+    assert_eq!(reader.read::<u16>().unwrap(), 0xABCD);
+
+    assert!(matches!(
+        reader.lookahead(16),
+        Err(BitError::NotEnoughData {
+            requested: 16,
+            bits_left: 8
+        })
+    ));
+    assert_eq!(reader.read::<u8>().unwrap(), 0x20);
+}
+
+#[test]
+fn test_io_error() {
+    struct Failing;
+
+    impl Read for Failing {
+        fn read(&mut self, _buf: &mut [u8]) -> io::Result<usize> {
+            Err(io::ErrorKind::BrokenPipe.into())
+        }
+    }
+
+    let mut reader = BitReader::new(Failing, LittleEndian);
+    assert!(matches!(reader.read::<u8>(), Err(BitError::Io(_))));
+    assert_eq!(reader.buffered_bits(), 0);
+}
+
+#[test]
+fn test_file_writer() {
+    let mut expected = Vec::new();
+    let mut stream = BitWriteStream::new(&mut expected, BigEndian);
+    let mut writer = BitFileWriter::new(Vec::new(), BigEndian);
+    for i in 0..100_000u32 {
+        stream.write_int(i, 19).unwrap();
+        writer.write_with(|stream| stream.write_int(i, 19)).unwrap();
+    }
+    stream.write_bool(true).unwrap();
+    writer.write(&true).unwrap();
+    assert_eq!(writer.bit_len(), 100_000 * 19 + 1);
+    // completed bytes have been written in chunks
+    assert!(!writer.get_ref().is_empty());
+    assert!(writer.get_ref().len() < expected.len());
+
+    // failed writes don't leave any bits behind
+    assert!(writer
+        .write_with(|stream| {
+            stream.write_int(0xffu8, 8)?;
+            stream.write_int(1u8, 9)
+        })
+        .is_err());
+    assert_eq!(writer.bit_len(), 100_000 * 19 + 1);
+
+    writer.flush().unwrap();
+    assert_eq!(writer.get_ref().len(), (100_000 * 19 + 1) / 8);
+    assert_eq!(writer.finish().unwrap(), expected);
+}
+
+#[test]
+fn test_file_writer_drop() {
+    let mut data = Vec::new();
+    {
+        let mut writer = BitFileWriter::new(&mut data, LittleEndian);
+        writer.write_sized(&0b1_0110u8, 5).unwrap();
+        writer.write(&0xabcdu16).unwrap();
+    }
+    assert_eq!(data, [0b1011_0110, 0b0111_1001, 0b0001_0101]);
+}
+
+#[test]
+fn test_file_writer_file() {
+    let path = std::env::temp_dir().join(format!("bitbuffer-file-writer-{}", std::process::id()));
+    let mut writer = BitFileWriter::create(&path, BigEndian).unwrap();
+    writer.write_sized(&0b101u8, 3).unwrap();
+    writer.write(&0x1234u16).unwrap();
+    writer.finish().unwrap();
+    let data = std::fs::read(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(data, [0b1010_0010, 0b0100_0110, 0b1000_0000]);
+}
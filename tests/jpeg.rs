@@ -0,0 +1,91 @@
+use bitbuffer::jpeg::{
+    is_restart_marker, read_entropy_coded_segment, write_entropy_coded_segment, END_OF_IMAGE,
+};
+use bitbuffer::{BigEndian, BitError, BitReadBuffer, BitReadStream, BitWriteStream, HuffmanTable};
+
+#[test]
+fn test_restart_marker() {
+    assert!(is_restart_marker(0xD0));
+    assert!(is_restart_marker(0xD7));
+    assert!(!is_restart_marker(0xD8));
+    assert!(!is_restart_marker(0xCF));
+}
+
+#[test]
+fn test_read_segments() {
+    let scan = [
+        0xFF, 0x00, 0x01, 0xFF, 0xD3, 0x02, 0xFF, 0xFF, 0xFF, 0xD9, 0x00,
+    ];
+    let mut stream = BitReadStream::new(BitReadBuffer::new(&scan, BigEndian));
+
+    let mut segment = read_entropy_coded_segment(&mut stream).unwrap();
+    assert_eq!(segment.data.bit_len(), 16);
+    assert_eq!(segment.data.read_bytes(2).unwrap().as_ref(), &[0xFF, 0x01]);
+    assert_eq!(segment.marker, Some(0xD3));
+    assert_eq!(segment.restart(), Some(3));
+    assert_eq!(stream.pos(), 5 * 8);
+
+    let segment = read_entropy_coded_segment(&mut stream).unwrap();
+    assert_eq!(segment.data.bit_len(), 8);
+    assert_eq!(segment.marker, Some(END_OF_IMAGE));
+    assert_eq!(segment.restart(), None);
+    // the fill bytes are skipped, leaving only the marker
+    assert_eq!(stream.pos(), 8 * 8);
+
+    stream.skip_bits(16).unwrap();
+    let segment = read_entropy_coded_segment(&mut stream).unwrap();
+    assert_eq!(segment.data.bit_len(), 8);
+    assert_eq!(segment.marker, None);
+    assert_eq!(stream.bits_left(), 0);
+}
+
+#[test]
+fn test_incomplete_marker() {
+    let mut stream = BitReadStream::new(BitReadBuffer::new(&[0x01, 0xFF, 0xFF], BigEndian));
+    assert!(matches!(
+        read_entropy_coded_segment(&mut stream),
+        Err(BitError::NotEnoughData { .. })
+    ));
+    assert_eq!(stream.pos(), 0);
+}
+
+#[test]
+fn test_huffman_roundtrip() {
+    // symbol 0 has code `0`, symbol 1 has code `10` and symbol 2 has code `11`
+    let table = HuffmanTable::from_lengths(&[1, 2, 2]).unwrap();
+    let symbols = [2, 2, 2, 2, 0, 1, 2, 2, 2, 2, 2, 1];
+
+    let mut raw = Vec::new();
+    let mut raw_stream = BitWriteStream::new(&mut raw, BigEndian);
+    for symbol in symbols {
+        match symbol {
+            0 => raw_stream.write_int(0u8, 1).unwrap(),
+            1 => raw_stream.write_int(0b10u8, 2).unwrap(),
+            _ => raw_stream.write_int(0b11u8, 2).unwrap(),
+        }
+    }
+    let bits = raw_stream.bit_len();
+    drop(raw_stream);
+    let input = BitReadStream::new(BitReadBuffer::new(&raw, BigEndian))
+        .read_bits(bits)
+        .unwrap();
+
+    let mut data = Vec::new();
+    let mut stream = BitWriteStream::new(&mut data, BigEndian);
+    write_entropy_coded_segment(&mut stream, &input).unwrap();
+    stream
+        .write_int(0xFF00u16 | END_OF_IMAGE as u16, 16)
+        .unwrap();
+    drop(stream);
+    assert_eq!(data, [0xFF, 0x00, 0x5F, 0xFD, 0xFF, 0xD9]);
+
+    let mut stream = BitReadStream::new(BitReadBuffer::new(&data, BigEndian));
+    let mut segment = read_entropy_coded_segment(&mut stream).unwrap();
+    assert_eq!(segment.marker, Some(END_OF_IMAGE));
+    for symbol in symbols {
+        assert_eq!(segment.data.read_huffman(&table).unwrap(), symbol);
+    }
+    // the padding is all 1 bits
+    assert_eq!(segment.data.bits_left(), 1);
+    assert_eq!(segment.data.read_int::<u8>(1).unwrap(), 1);
+}
@@ -0,0 +1,232 @@
+#![allow(clippy::unusual_byte_groupings)]
+
+use bitbuffer::linecode::{
+    align_to_comma, Decoder8b10b, Disparity, Encoder8b10b, Manchester, Symbol,
+};
+use bitbuffer::{
+    BigEndian, BitError, BitReadBuffer, BitReadStream, BitWriteStream, Endianness, LittleEndian,
+};
+
+fn all_symbols() -> Vec<Symbol> {
+    let mut symbols: Vec<Symbol> = (0..=255).map(Symbol::Data).collect();
+    symbols.extend_from_slice(&[
+        Symbol::K28_0,
+        Symbol::K28_1,
+        Symbol::K28_2,
+        Symbol::K28_3,
+        Symbol::K28_4,
+        Symbol::K28_5,
+        Symbol::K28_6,
+        Symbol::K28_7,
+        Symbol::K23_7,
+        Symbol::K27_7,
+        Symbol::K29_7,
+        Symbol::K30_7,
+    ]);
+    symbols
+}
+
+fn encode(symbol: Symbol, disparity: Disparity) -> u16 {
+    let mut data = Vec::new();
+    let mut stream = BitWriteStream::new(&mut data, BigEndian);
+    Encoder8b10b::with_disparity(disparity)
+        .encode(&mut stream, symbol)
+        .unwrap();
+    BitReadStream::new(BitReadBuffer::new(&data, BigEndian))
+        .read_int(10)
+        .unwrap()
+}
+
+#[test]
+fn test_known_codes() {
+    use Disparity::*;
+
+    let cases = [
+        (Symbol::Data(0x00), 0b100111_0100, 0b011000_1011),
+        (Symbol::Data(0x07), 0b111000_1011, 0b000111_0100),
+        (Symbol::Data(0x63), 0b110001_1100, 0b110001_0011),
+        (Symbol::Data(0xB5), 0b101010_1010, 0b101010_1010),
+        (Symbol::Data(0xEB), 0b110100_1110, 0b110100_1000),
+        (Symbol::Data(0xF1), 0b100011_0111, 0b100011_0001),
+        (Symbol::Data(0xFF), 0b101011_0001, 0b010100_1110),
+        (Symbol::K28_1, 0b001111_1001, 0b110000_0110),
+        (Symbol::K28_5, 0b001111_1010, 0b110000_0101),
+        (Symbol::K28_7, 0b001111_1000, 0b110000_0111),
+        (Symbol::K23_7, 0b111010_1000, 0b000101_0111),
+    ];
+    for (symbol, negative, positive) in cases {
+        assert_eq!(encode(symbol, Negative), negative, "{:?} RD-", symbol);
+        assert_eq!(encode(symbol, Positive), positive, "{:?} RD+", symbol);
+    }
+}
+
+#[test]
+fn test_invalid_control() {
+    let mut data = Vec::new();
+    let mut stream = BitWriteStream::new(&mut data, BigEndian);
+    assert!(matches!(
+        Encoder8b10b::new().encode(&mut stream, Symbol::Control(0x00)),
+        Err(BitError::InvalidControlSymbol { value: 0x00 })
+    ));
+    assert_eq!(stream.bit_len(), 0);
+}
+
+fn test_roundtrip_all<E: Endianness>(endianness: E) {
+    let symbols = all_symbols();
+    // encode every symbol with both disparities
+    let symbols: Vec<Symbol> = symbols
+        .iter()
+        .chain(symbols.iter().rev())
+        .copied()
+        .collect();
+
+    let mut data = Vec::new();
+    let mut stream = BitWriteStream::new(&mut data, E::endianness());
+    let mut encoder = Encoder8b10b::new();
+    for symbol in &symbols {
+        encoder.encode(&mut stream, *symbol).unwrap();
+    }
+    let bit_len = stream.bit_len();
+
+    let mut read = BitReadStream::new(BitReadBuffer::new(&data, endianness));
+    let mut decoder = Decoder8b10b::new();
+    for symbol in &symbols {
+        assert_eq!(decoder.decode(&mut read).unwrap(), *symbol);
+    }
+    assert_eq!(decoder.disparity(), encoder.disparity());
+
+    // running digital sum stays bounded and there are no runs longer than 5 bits
+    let mut read = BitReadStream::new(BitReadBuffer::new(&data, E::endianness()));
+    // negative running disparity corresponds to a running digital sum of -1
+    let mut sum = -1i32;
+    let mut run = 0;
+    let mut last = None;
+    for _ in 0..bit_len {
+        let bit = read.read_bool().unwrap();
+        sum += if bit { 1 } else { -1 };
+        assert!((-3..=3).contains(&sum));
+        run = if last == Some(bit) { run + 1 } else { 1 };
+        last = Some(bit);
+        assert!(run <= 5);
+    }
+}
+
+#[test]
+fn test_roundtrip() {
+    test_roundtrip_all(LittleEndian);
+    test_roundtrip_all(BigEndian);
+}
+
+#[test]
+fn test_decode_errors() {
+    // 000000 0000 is never valid
+    let data = [0, 0];
+    let mut stream = BitReadStream::new(BitReadBuffer::new(&data, BigEndian));
+    let mut decoder = Decoder8b10b::new();
+    assert!(matches!(
+        decoder.decode(&mut stream),
+        Err(BitError::InvalidLineCode { code: 0, pos: 0 })
+    ));
+    assert_eq!(stream.pos(), 0);
+
+    // D.00.0 with RD+ while the decoder expects RD-
+    let data = [0b0110_0010, 0b1100_0000];
+    let mut stream = BitReadStream::new(BitReadBuffer::new(&data, BigEndian));
+    assert!(matches!(
+        decoder.decode(&mut stream),
+        Err(BitError::DisparityError { pos: 0, .. })
+    ));
+    assert_eq!(stream.pos(), 0);
+    decoder.set_disparity(Disparity::Positive);
+    assert_eq!(decoder.decode(&mut stream).unwrap(), Symbol::Data(0));
+    assert_eq!(decoder.disparity(), Disparity::Positive);
+}
+
+#[test]
+fn test_bytes() {
+    let mut data = Vec::new();
+    let mut stream = BitWriteStream::new(&mut data, LittleEndian);
+    let mut encoder = Encoder8b10b::new();
+    encoder.encode_bytes(&mut stream, b"hello").unwrap();
+    encoder.encode(&mut stream, Symbol::K28_5).unwrap();
+
+    let mut read = BitReadStream::new(BitReadBuffer::new(&data, LittleEndian));
+    let mut decoder = Decoder8b10b::new();
+    assert!(matches!(
+        decoder.decode_bytes(&mut read, 6),
+        Err(BitError::InvalidLineCode { pos: 50, .. })
+    ));
+    assert_eq!(read.pos(), 0);
+    assert_eq!(decoder.decode_bytes(&mut read, 5).unwrap(), b"hello");
+    assert_eq!(decoder.decode(&mut read).unwrap(), Symbol::K28_5);
+}
+
+#[test]
+fn test_align_to_comma() {
+    let mut data = Vec::new();
+    let mut stream = BitWriteStream::new(&mut data, BigEndian);
+    let mut encoder = Encoder8b10b::new();
+    stream.write_int(0b10110u8, 5).unwrap();
+    encoder
+        .encode_bytes(&mut stream, &[0x00, 0xFF, 0x55])
+        .unwrap();
+    encoder.encode(&mut stream, Symbol::K28_1).unwrap();
+    encoder.encode(&mut stream, Symbol::Data(0x12)).unwrap();
+    let disparity = encoder.disparity();
+
+    let mut read = BitReadStream::new(BitReadBuffer::new(&data, BigEndian));
+    align_to_comma(&mut read).unwrap();
+    assert_eq!(read.pos(), 35);
+    let mut decoder = Decoder8b10b::new();
+    assert_eq!(decoder.decode(&mut read).unwrap(), Symbol::K28_1);
+    assert_eq!(decoder.decode(&mut read).unwrap(), Symbol::Data(0x12));
+    assert_eq!(decoder.disparity(), disparity);
+
+    assert!(align_to_comma(&mut read).is_err());
+    assert_eq!(read.pos(), 55);
+}
+
+fn test_manchester_roundtrip<E: Endianness>(convention: Manchester, endianness: E) {
+    let input = [0x12, 0xFF, 0x00, 0xA5];
+    let mut data = Vec::new();
+    let mut stream = BitWriteStream::new(&mut data, E::endianness());
+    convention
+        .encode(
+            &BitReadStream::new(BitReadBuffer::new(&input, E::endianness())),
+            &mut stream,
+        )
+        .unwrap();
+    assert_eq!(stream.bit_len(), 64);
+
+    let mut read = BitReadStream::new(BitReadBuffer::new(&data, endianness));
+    let mut decoded = convention.decode(&mut read, 32).unwrap();
+    assert_eq!(decoded.read_bytes(4).unwrap().as_ref(), &input);
+    assert_eq!(read.bits_left(), 0);
+}
+
+#[test]
+fn test_manchester() {
+    test_manchester_roundtrip(Manchester::Ieee, LittleEndian);
+    test_manchester_roundtrip(Manchester::Ieee, BigEndian);
+    test_manchester_roundtrip(Manchester::Thomas, LittleEndian);
+    test_manchester_roundtrip(Manchester::Thomas, BigEndian);
+
+    let data = [0b1001_0110];
+    let mut read = BitReadStream::new(BitReadBuffer::new(&data, BigEndian));
+    assert_eq!(
+        Manchester::Thomas
+            .decode(&mut read, 4)
+            .unwrap()
+            .read_int::<u8>(4)
+            .unwrap(),
+        0b1001
+    );
+
+    let data = [0b0110_1101];
+    let mut read = BitReadStream::new(BitReadBuffer::new(&data, BigEndian));
+    assert!(matches!(
+        Manchester::Ieee.decode(&mut read, 4),
+        Err(BitError::InvalidLineCode { code: 0b11, pos: 4 })
+    ));
+    assert_eq!(read.pos(), 0);
+}
@@ -0,0 +1,100 @@
+use bitbuffer::{
+    read_bits, write_bits, BigEndian, BitError, BitReadBuffer, BitReadStream, BitWriteStream,
+    LittleEndian,
+};
+
+#[test]
+fn test_read_bits() {
+    let bytes = [0x12, 0x34, 0x56, 0x78, 0x9A, 0xBC];
+    let mut stream = BitReadStream::new(BitReadBuffer::new(&bytes, LittleEndian));
+    let (a, b, c) = read_bits!(stream, u8: 4, i16: 12, u32).unwrap();
+    assert_eq!(a, 2);
+    assert_eq!(b, 0x341);
+    assert_eq!(c, 0xBC9A7856);
+    assert_eq!(stream.bits_left(), 0);
+
+    // single values are returned as a tuple
+    stream.set_pos(0).unwrap();
+    let (single,) = read_bits!(&mut stream, u8: 4,).unwrap();
+    assert_eq!(single, 2);
+
+    // the stream isn't advanced on failure
+    let result = read_bits!(stream, u16: 16, u8: 9);
+    assert!(matches!(result, Err(BitError::TooManyBits { .. })));
+    assert_eq!(stream.pos(), 4);
+    let result = read_bits!(stream, u32, u16);
+    assert!(matches!(result, Err(BitError::NotEnoughData { .. })));
+    assert_eq!(stream.pos(), 4);
+}
+
+#[test]
+fn test_write_bits() {
+    let mut data = Vec::new();
+    let mut stream = BitWriteStream::new(&mut data, BigEndian);
+    let value = -2i8;
+    write_bits!(stream, 1u8 => 1, value => 7, 0x1234u16).unwrap();
+    assert_eq!(stream.bit_len(), 24);
+
+    // nothing is written on failure
+    let result = write_bits!(&mut stream, 1u8 => 4, 1u8 => 9);
+    assert!(matches!(result, Err(BitError::TooManyBits { .. })));
+    assert_eq!(stream.bit_len(), 24);
+    write_bits!(stream, false, 0xFFu8 => 7).unwrap();
+
+    let mut stream = BitReadStream::new(BitReadBuffer::new(&data, BigEndian));
+    let read = read_bits!(stream, u8: 1, i8: 7, u16, bool, u8: 7).unwrap();
+    assert_eq!(read, (1, -2, 0x1234, false, 0x7F));
+}
+
+#[test]
+fn test_assert_bit_eq() {
+    use bitbuffer::assert_bit_eq;
+    use std::panic::{catch_unwind, AssertUnwindSafe};
+
+    let mut data = Vec::new();
+    let mut stream = BitWriteStream::new(&mut data, BigEndian);
+    stream.write_int(0b101u8, 3).unwrap();
+    stream.write_int(0x1234u16, 16).unwrap();
+    assert_bit_eq!([0b1010_0010, 0b0100_0110, 0b1000_0000], stream);
+    assert_bit_eq!(&[0b1010_0010u8, 0b0100_0110, 0b1000_0000][..], &mut stream);
+    assert_bit_eq!(
+        BitReadBuffer::from_bit_string("101 0001 0010 0011 0100", BigEndian).unwrap(),
+        stream
+    );
+    drop(stream);
+
+    let bytes = data.clone();
+    let panic = catch_unwind(|| {
+        assert_bit_eq!([0b1010_0010, 0b0100_0010, 0b1000_0000], bytes);
+    })
+    .unwrap_err();
+    assert_eq!(
+        panic.downcast_ref::<String>().unwrap(),
+        "bits differ at bit 13 (byte 1), expected 24 bits, got 24 bits
+  offset | expected  | actual
+       0 | 1010_0010 | 1010_0010
+       1 | 0100_0010 | 0100_0110
+         |       ^   |       ^
+       2 | 1000_0000 | 1000_0000
+"
+    );
+
+    // little endian streams mark the bit relative to the least significant bit
+    let stream = BitReadStream::new(BitReadBuffer::new(&[0x00, 0x00, 0x10, 0, 0], LittleEndian));
+    let panic = catch_unwind(AssertUnwindSafe(|| {
+        assert_bit_eq!(vec![0u8; 4], stream, "packet {}", 1);
+    }))
+    .unwrap_err();
+    assert_eq!(
+        panic.downcast_ref::<String>().unwrap(),
+        "packet 1: bits differ at bit 20 (byte 2), expected 32 bits, got 40 bits
+  offset | expected  | actual
+       0 | 0000_0000 | 0000_0000
+       1 | 0000_0000 | 0000_0000
+       2 | 0000_0000 | 0001_0000
+         |    ^      |    ^
+       3 | 0000_0000 | 0000_0000
+       4 | --------- | 0000_0000
+"
+    );
+}
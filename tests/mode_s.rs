@@ -0,0 +1,111 @@
+use bitbuffer::crc::CRC_24_MODE_S;
+use bitbuffer::mode_s::{
+    ac12_altitude, ac13_altitude, message_bits, read_callsign, read_extended_squitter,
+    read_message, LONG_MESSAGE_BITS, SHORT_MESSAGE_BITS,
+};
+use bitbuffer::{BigEndian, BitError, BitReadBuffer, BitReadStream};
+
+const IDENTIFICATION: [u8; 14] = [
+    0x8D, 0x48, 0x40, 0xD6, 0x20, 0x2C, 0xC3, 0x71, 0xC3, 0x2C, 0xE0, 0x57, 0x60, 0x98,
+];
+
+const POSITION: [u8; 14] = [
+    0x8D, 0x40, 0x62, 0x1D, 0x58, 0xC3, 0x82, 0xD6, 0x90, 0xC8, 0xAC, 0x28, 0x63, 0xA7,
+];
+
+#[test]
+fn test_message_bits() {
+    assert_eq!(message_bits(0), SHORT_MESSAGE_BITS);
+    assert_eq!(message_bits(11), SHORT_MESSAGE_BITS);
+    assert_eq!(message_bits(17), LONG_MESSAGE_BITS);
+    assert_eq!(message_bits(31), LONG_MESSAGE_BITS);
+}
+
+#[test]
+fn test_identification() {
+    let mut stream = BitReadStream::new(BitReadBuffer::new(&IDENTIFICATION, BigEndian));
+    let mut squitter = read_extended_squitter(&mut stream).unwrap();
+    assert_eq!(stream.bits_left(), 0);
+    assert_eq!(squitter.downlink_format, 17);
+    assert_eq!(squitter.capability, 5);
+    assert_eq!(squitter.address, 0x48_40D6);
+    assert_eq!(squitter.type_code, 4);
+    assert_eq!(squitter.data.bit_len(), 51);
+    assert_eq!(squitter.data.read_int::<u8>(3).unwrap(), 0);
+    assert_eq!(read_callsign(&mut squitter.data).unwrap(), "KLM1023");
+}
+
+#[test]
+fn test_airborne_position() {
+    let mut stream = BitReadStream::new(BitReadBuffer::new(&POSITION, BigEndian));
+    let mut squitter = read_extended_squitter(&mut stream).unwrap();
+    assert_eq!(squitter.address, 0x40_621D);
+    assert_eq!(squitter.type_code, 11);
+    squitter.data.skip_bits(3).unwrap();
+    let altitude: u16 = squitter.data.read_int(12).unwrap();
+    assert_eq!(ac12_altitude(altitude), Some(38000));
+}
+
+#[test]
+fn test_address_parity() {
+    // a DF 4 surveillance reply with the crc XOR-ed with the address
+    let mut bytes = [0x20, 0x00, 0x18, 0x38, 0, 0, 0];
+    let address = 0xAB_CDEFu64;
+    let parity = CRC_24_MODE_S.checksum(&bytes[..4]) ^ address;
+    bytes[4..].copy_from_slice(&parity.to_be_bytes()[5..]);
+
+    let mut stream = BitReadStream::new(BitReadBuffer::new(&bytes, BigEndian));
+    let mut message = read_message(&mut stream).unwrap();
+    assert_eq!(stream.bits_left(), 0);
+    assert_eq!(message.downlink_format, 4);
+    assert_eq!(message.address_parity as u64, address);
+    assert_eq!(message.fields.bit_len(), 27);
+    message.fields.skip_bits(14).unwrap();
+    let altitude: u16 = message.fields.read_int(13).unwrap();
+    assert_eq!(ac13_altitude(altitude), Some(38000));
+
+    // extended squitters have a plain crc
+    let mut stream = BitReadStream::new(BitReadBuffer::new(&IDENTIFICATION, BigEndian));
+    let message = read_message(&mut stream).unwrap();
+    assert_eq!(message.address_parity, 0);
+}
+
+#[test]
+fn test_squitter_errors() {
+    let mut bytes = IDENTIFICATION;
+    bytes[6] ^= 0x01;
+    let mut stream = BitReadStream::new(BitReadBuffer::new(&bytes, BigEndian));
+    assert!(matches!(
+        read_extended_squitter(&mut stream),
+        Err(BitError::Malformed { pos: 0, .. })
+    ));
+    assert_eq!(stream.pos(), 0);
+
+    // DF 20
+    let mut bytes = IDENTIFICATION;
+    bytes[0] = 0xA0;
+    let mut stream = BitReadStream::new(BitReadBuffer::new(&bytes, BigEndian));
+    assert!(matches!(
+        read_extended_squitter(&mut stream),
+        Err(BitError::Malformed { pos: 0, .. })
+    ));
+
+    let mut stream = BitReadStream::new(BitReadBuffer::new(&IDENTIFICATION[..13], BigEndian));
+    assert!(matches!(
+        read_message(&mut stream),
+        Err(BitError::NotEnoughData { .. })
+    ));
+    assert_eq!(stream.pos(), 0);
+}
+
+#[test]
+fn test_altitude() {
+    assert_eq!(ac12_altitude(0), None);
+    // Gillham coded
+    assert_eq!(ac12_altitude(0xC28), None);
+    assert_eq!(ac12_altitude(0x010), Some(-1000));
+    // metric
+    assert_eq!(ac13_altitude(0x1040), None);
+    assert_eq!(ac13_altitude(0x0010), Some(-1000));
+    assert_eq!(ac13_altitude(0x1830), ac12_altitude(0xC30));
+}
@@ -0,0 +1,180 @@
+use bitbuffer::mpegts::{
+    packets, read_packet, read_section, skip_pointer_field, PacketHeader, NULL_PID, PACKET_SIZE,
+};
+use bitbuffer::{BigEndian, BitError, BitReadBuffer, BitReadStream};
+
+const PAT: [u8; 16] = [
+    0x00, 0xB0, 0x0D, 0x00, 0x01, 0xC1, 0x00, 0x00, 0x00, 0x01, 0xF0, 0x00, 0x2A, 0xB1, 0x04, 0xB2,
+];
+
+fn packet_bytes(header: [u8; 4], contents: &[u8]) -> Vec<u8> {
+    let mut data = header.to_vec();
+    data.extend_from_slice(contents);
+    data.resize(PACKET_SIZE, 0xFF);
+    data
+}
+
+#[test]
+fn test_read_packet() {
+    let mut data = packet_bytes([0x47, 0x40, 0x00, 0x10], &[0x00]);
+    data.extend_from_slice(&PAT);
+    data.truncate(PACKET_SIZE);
+    let mut stream = BitReadStream::new(BitReadBuffer::new(&data, BigEndian));
+    let packet = read_packet(&mut stream).unwrap();
+    assert_eq!(stream.bits_left(), 0);
+    assert_eq!(
+        packet.header,
+        PacketHeader {
+            transport_error: false,
+            payload_unit_start: true,
+            priority: false,
+            pid: 0,
+            scrambling: 0,
+            has_adaptation_field: false,
+            has_payload: true,
+            continuity_counter: 0,
+        }
+    );
+    assert!(packet.adaptation_field.is_none());
+    assert_eq!(packet.payload.unwrap().bit_len(), (PACKET_SIZE - 4) * 8);
+}
+
+#[test]
+fn test_adaptation_field() {
+    // adaptation field only, 183 bytes of stuffing
+    let data = packet_bytes([0x47, 0x1F, 0xFF, 0x2A], &[183]);
+    let mut stream = BitReadStream::new(BitReadBuffer::new(&data, BigEndian));
+    let packet = read_packet(&mut stream).unwrap();
+    assert_eq!(packet.header.pid, NULL_PID);
+    assert_eq!(packet.header.continuity_counter, 10);
+    assert_eq!(packet.adaptation_field.unwrap().bit_len(), 183 * 8);
+    assert!(packet.payload.is_none());
+
+    // adaptation field followed by payload
+    let data = packet_bytes([0x47, 0x01, 0x00, 0x35], &[1, 0x80, 0xAB]);
+    let mut stream = BitReadStream::new(BitReadBuffer::new(&data, BigEndian));
+    let packet = read_packet(&mut stream).unwrap();
+    assert_eq!(packet.header.pid, 0x100);
+    let mut adaptation_field = packet.adaptation_field.unwrap();
+    assert_eq!(adaptation_field.read_int::<u8>(8).unwrap(), 0x80);
+    let mut payload = packet.payload.unwrap();
+    assert_eq!(payload.bit_len(), 182 * 8);
+    assert_eq!(payload.read_int::<u8>(8).unwrap(), 0xAB);
+}
+
+#[test]
+fn test_packet_errors() {
+    let data = packet_bytes([0x46, 0x00, 0x00, 0x10], &[]);
+    let mut stream = BitReadStream::new(BitReadBuffer::new(&data, BigEndian));
+    assert!(matches!(
+        read_packet(&mut stream),
+        Err(BitError::Malformed { pos: 0, .. })
+    ));
+    assert_eq!(stream.pos(), 0);
+
+    let data = packet_bytes([0x47, 0x00, 0x00, 0x30], &[184]);
+    let mut stream = BitReadStream::new(BitReadBuffer::new(&data, BigEndian));
+    assert!(matches!(
+        read_packet(&mut stream),
+        Err(BitError::Malformed { pos: 32, .. })
+    ));
+
+    let data = packet_bytes([0x47, 0x00, 0x00, 0x10], &[]);
+    let mut stream = BitReadStream::new(BitReadBuffer::new(&data[..PACKET_SIZE - 1], BigEndian));
+    assert!(matches!(
+        read_packet(&mut stream),
+        Err(BitError::NotEnoughData { .. })
+    ));
+}
+
+#[test]
+fn test_packets() {
+    let mut data = Vec::new();
+    for counter in 0..3 {
+        data.extend(packet_bytes([0x47, 0x01, 0x00, 0x10 | counter], &[]));
+    }
+    data.extend_from_slice(&[0x47, 0x00]);
+    let mut iter = packets(BitReadStream::new(BitReadBuffer::new(&data, BigEndian)));
+    let counters: Vec<u8> = iter
+        .by_ref()
+        .map(|packet| packet.unwrap().header.continuity_counter)
+        .collect();
+    assert_eq!(counters, [0, 1, 2]);
+    assert_eq!(iter.remainder().bits_left(), 16);
+
+    // iteration stops after the first error
+    data[PACKET_SIZE] = 0;
+    let stream = BitReadStream::new(BitReadBuffer::new(&data, BigEndian));
+    let results: Vec<_> = packets(stream).collect();
+    assert_eq!(results.len(), 2);
+    assert!(results[0].is_ok());
+    assert!(results[1].is_err());
+}
+
+#[test]
+fn test_pointer_field() {
+    let mut payload = BitReadStream::new(BitReadBuffer::new(&[0x02, 0xAA, 0xBB, 0xCC], BigEndian));
+    skip_pointer_field(&mut payload).unwrap();
+    assert_eq!(payload.read_int::<u8>(8).unwrap(), 0xCC);
+
+    let mut payload = BitReadStream::new(BitReadBuffer::new(&[0x04, 0xAA, 0xBB, 0xCC], BigEndian));
+    assert!(skip_pointer_field(&mut payload).is_err());
+    assert_eq!(payload.pos(), 0);
+}
+
+#[test]
+fn test_read_section() {
+    let mut data = PAT.to_vec();
+    data.extend_from_slice(&[0xFF, 0xFF]);
+    let mut stream = BitReadStream::new(BitReadBuffer::new(&data, BigEndian));
+    let mut section = read_section(&mut stream).unwrap();
+    assert_eq!(stream.bits_left(), 16);
+    assert_eq!(section.table_id, 0);
+    assert!(section.section_syntax_indicator);
+    assert!(!section.private_indicator);
+    assert_eq!(section.body.bit_len(), 9 * 8);
+    assert_eq!(section.body.read_int::<u16>(16).unwrap(), 1);
+}
+
+#[test]
+fn test_short_section() {
+    // sections without the syntax indicator have no crc
+    let data = [0x70, 0x70, 0x05, 0xE0, 0x00, 0x00, 0x00, 0x00];
+    let mut stream = BitReadStream::new(BitReadBuffer::new(&data, BigEndian));
+    let section = read_section(&mut stream).unwrap();
+    assert_eq!(section.table_id, 0x70);
+    assert!(!section.section_syntax_indicator);
+    assert_eq!(section.body.bit_len(), 5 * 8);
+}
+
+#[test]
+fn test_section_errors() {
+    let mut data = PAT;
+    data[15] ^= 1;
+    let mut stream = BitReadStream::new(BitReadBuffer::new(&data, BigEndian));
+    assert!(matches!(
+        read_section(&mut stream),
+        Err(BitError::Malformed { pos: 0, .. })
+    ));
+    assert_eq!(stream.pos(), 0);
+
+    let data = [0x00, 0xB3, 0xFE];
+    let mut stream = BitReadStream::new(BitReadBuffer::new(&data, BigEndian));
+    assert!(matches!(
+        read_section(&mut stream),
+        Err(BitError::Malformed { pos: 12, .. })
+    ));
+
+    let data = [0x00, 0xB0, 0x02, 0x00, 0x00];
+    let mut stream = BitReadStream::new(BitReadBuffer::new(&data, BigEndian));
+    assert!(matches!(
+        read_section(&mut stream),
+        Err(BitError::Malformed { pos: 12, .. })
+    ));
+
+    let mut stream = BitReadStream::new(BitReadBuffer::new(&PAT[..15], BigEndian));
+    assert!(matches!(
+        read_section(&mut stream),
+        Err(BitError::NotEnoughData { .. })
+    ));
+}
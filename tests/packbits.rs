@@ -0,0 +1,129 @@
+use bitbuffer::packbits::{pack, unpack};
+use bitbuffer::{
+    BigEndian, BitError, BitReadBuffer, BitReadStream, BitWriteStream, LittleEndian, ReadLimit,
+    ReadLimits,
+};
+
+fn pack_bytes(bytes: &[u8]) -> Vec<u8> {
+    let mut data = Vec::new();
+    pack(
+        &BitReadStream::new(BitReadBuffer::new(bytes, BigEndian)),
+        &mut BitWriteStream::new(&mut data, BigEndian),
+    )
+    .unwrap();
+    data
+}
+
+fn unpack_bytes(bytes: &[u8], length: usize) -> Vec<u8> {
+    let mut data = Vec::new();
+    let mut stream = BitReadStream::new(BitReadBuffer::new(bytes, BigEndian));
+    unpack(
+        &mut stream,
+        &mut BitWriteStream::new(&mut data, BigEndian),
+        length,
+    )
+    .unwrap();
+    assert_eq!(stream.bits_left(), 0);
+    data
+}
+
+#[test]
+fn test_apple_example() {
+    // the example from Apple technical note TN1023
+    let packed = [
+        0xFE, 0xAA, 0x02, 0x80, 0x00, 0x2A, 0xFD, 0xAA, 0x03, 0x80, 0x00, 0x2A, 0x22, 0xF7, 0xAA,
+    ];
+    let unpacked = [
+        0xAA, 0xAA, 0xAA, 0x80, 0x00, 0x2A, 0xAA, 0xAA, 0xAA, 0xAA, 0x80, 0x00, 0x2A, 0x22, 0xAA,
+        0xAA, 0xAA, 0xAA, 0xAA, 0xAA, 0xAA, 0xAA, 0xAA, 0xAA,
+    ];
+    assert_eq!(unpack_bytes(&packed, unpacked.len()), unpacked);
+    assert_eq!(pack_bytes(&unpacked), packed);
+}
+
+#[test]
+fn test_pack_long_runs() {
+    assert_eq!(pack_bytes(&[]), []);
+    assert_eq!(pack_bytes(&[7]), [0x00, 7]);
+    assert_eq!(pack_bytes(&[7, 7]), [0x01, 7, 7]);
+
+    let run = [5; 300];
+    assert_eq!(pack_bytes(&run), [0x81, 5, 0x81, 5, 0xD5, 5]);
+    assert_eq!(unpack_bytes(&pack_bytes(&run), 300), run);
+
+    let literals: Vec<u8> = (0..=255).chain(0..=255).collect();
+    let packed = pack_bytes(&literals);
+    assert_eq!(packed.len(), literals.len() + 4);
+    assert_eq!(packed[0], 0x7F);
+    assert_eq!(unpack_bytes(&packed, literals.len()), literals);
+}
+
+#[test]
+fn test_pack_little_endian() {
+    let bytes = [1, 2, 2, 2, 2, 3];
+    let input = BitReadStream::new(BitReadBuffer::new(&bytes, LittleEndian));
+    let mut data = Vec::new();
+    pack(&input, &mut BitWriteStream::new(&mut data, LittleEndian)).unwrap();
+    assert_eq!(data, [0x00, 1, 0xFD, 2, 0x00, 3]);
+}
+
+#[test]
+fn test_unpack_noop() {
+    assert_eq!(unpack_bytes(&[0x80, 0xFF, 1, 0x80, 0x00, 2], 3), [1, 1, 2]);
+
+    // decoding stops as soon as the length is reached
+    let mut data = Vec::new();
+    let mut stream = BitReadStream::new(BitReadBuffer::new(&[0xFF, 1, 0x80], BigEndian));
+    unpack(
+        &mut stream,
+        &mut BitWriteStream::new(&mut data, BigEndian),
+        2,
+    )
+    .unwrap();
+    assert_eq!(stream.pos(), 16);
+}
+
+#[test]
+fn test_unpack_errors() {
+    let mut data = Vec::new();
+    let mut stream = BitReadStream::new(BitReadBuffer::new(&[0x00, 1, 0xFD, 2], BigEndian));
+    assert!(matches!(
+        unpack(
+            &mut stream,
+            &mut BitWriteStream::new(&mut data, BigEndian),
+            3
+        ),
+        Err(BitError::Malformed { pos: 16, .. })
+    ));
+    assert_eq!(stream.pos(), 0);
+    assert!(data.is_empty());
+
+    let mut stream = BitReadStream::new(BitReadBuffer::new(&[0x02, 1, 2], BigEndian));
+    assert!(matches!(
+        unpack(
+            &mut stream,
+            &mut BitWriteStream::new(&mut data, BigEndian),
+            3
+        ),
+        Err(BitError::NotEnoughData { .. })
+    ));
+    assert_eq!(stream.pos(), 0);
+
+    let mut stream = BitReadStream::new(BitReadBuffer::new(&[0x81, 0], BigEndian));
+    stream.set_limits(ReadLimits {
+        max_elements: 100,
+        ..ReadLimits::default()
+    });
+    assert!(matches!(
+        unpack(
+            &mut stream,
+            &mut BitWriteStream::new(&mut data, BigEndian),
+            128
+        ),
+        Err(BitError::LimitExceeded {
+            limit: ReadLimit::Elements,
+            max: 100,
+            ..
+        })
+    ));
+}
@@ -0,0 +1,192 @@
+use bitbuffer::pcm::{
+    read_frames, read_frames_f32, read_sample, read_sample_f32, write_frames, write_frames_f32,
+    write_sample, write_sample_f32, ChannelLayout, SampleFormat,
+};
+use bitbuffer::{BigEndian, BitError, BitReadBuffer, BitReadStream, BitWriteStream, LittleEndian};
+
+#[test]
+fn test_sample_formats() {
+    let data = [0x56, 0x34, 0x12, 0x00, 0x00, 0x80];
+    let mut stream = BitReadStream::new(BitReadBuffer::new(&data, LittleEndian));
+    assert_eq!(
+        read_sample(&mut stream, SampleFormat::S24).unwrap(),
+        0x12_3456
+    );
+    assert_eq!(
+        read_sample(&mut stream, SampleFormat::S24).unwrap(),
+        -0x80_0000
+    );
+
+    // 20 bit left justified, the bottom nibble is ignored
+    let data = [0xFF, 0xFF, 0xEF, 0x8F, 0x00, 0x00];
+    let mut stream = BitReadStream::new(BitReadBuffer::new(&data, BigEndian));
+    assert_eq!(
+        read_sample(&mut stream, SampleFormat::S20_LEFT).unwrap(),
+        -2
+    );
+    assert_eq!(
+        read_sample(&mut stream, SampleFormat::S20_LEFT).unwrap(),
+        -0x7_1000
+    );
+
+    // 24 bits in 32, the unused byte is ignored
+    let data = [0xFF, 0xFF, 0xFF, 0x12, 0x00, 0x00, 0x80, 0xFF];
+    let mut stream = BitReadStream::new(BitReadBuffer::new(&data, LittleEndian));
+    assert_eq!(read_sample(&mut stream, SampleFormat::S24_32).unwrap(), -1);
+    assert_eq!(
+        read_sample(&mut stream, SampleFormat::S24_32_LEFT).unwrap(),
+        -0x8000
+    );
+
+    let mut stream = BitReadStream::new(BitReadBuffer::new(&data[..2], LittleEndian));
+    assert!(matches!(
+        read_sample(&mut stream, SampleFormat::S24),
+        Err(BitError::NotEnoughData { .. })
+    ));
+}
+
+#[test]
+fn test_sample_roundtrip() {
+    let formats = [
+        SampleFormat::S8,
+        SampleFormat::S16,
+        SampleFormat::S20_LEFT,
+        SampleFormat::S24,
+        SampleFormat::S24_32,
+        SampleFormat::S24_32_LEFT,
+        SampleFormat::S32,
+        SampleFormat::new(12, 10, false),
+    ];
+    for format in formats {
+        let max = ((1i64 << (format.sample_bits() - 1)) - 1) as i32;
+        let min = -max - 1;
+        let samples = [0, 1, -1, max, min, max / 3, min / 5];
+
+        let mut data = Vec::new();
+        let mut stream = BitWriteStream::new(&mut data, BigEndian);
+        stream.write_bool(true).unwrap();
+        for sample in samples {
+            write_sample(&mut stream, sample, format).unwrap();
+        }
+        assert_eq!(
+            stream.bit_len(),
+            1 + samples.len() * format.container_bits()
+        );
+
+        let mut stream = BitReadStream::new(BitReadBuffer::new(&data, BigEndian));
+        stream.skip_bits(1).unwrap();
+        for sample in samples {
+            assert_eq!(read_sample(&mut stream, format).unwrap(), sample);
+        }
+    }
+
+    // left justified samples have zeroed padding
+    let mut data = Vec::new();
+    write_sample(
+        &mut BitWriteStream::new(&mut data, LittleEndian),
+        -1,
+        SampleFormat::S20_LEFT,
+    )
+    .unwrap();
+    assert_eq!(data, [0xF0, 0xFF, 0xFF]);
+}
+
+#[test]
+fn test_sample_f32() {
+    let mut data = Vec::new();
+    let mut stream = BitWriteStream::new(&mut data, LittleEndian);
+    for value in [0.0, 0.5, -1.0, 1.0, 2.0, -3.0, f32::NAN] {
+        write_sample_f32(&mut stream, value, SampleFormat::S16).unwrap();
+    }
+    assert_eq!(
+        data,
+        [0x00, 0x00, 0x00, 0x40, 0x00, 0x80, 0xFF, 0x7F, 0xFF, 0x7F, 0x00, 0x80, 0x00, 0x00]
+    );
+
+    let mut stream = BitReadStream::new(BitReadBuffer::new(&data, LittleEndian));
+    let values: Vec<f32> = (0..7)
+        .map(|_| read_sample_f32(&mut stream, SampleFormat::S16).unwrap())
+        .collect();
+    let max = 32767.0 / 32768.0;
+    assert_eq!(values, [0.0, 0.5, -1.0, max, max, -1.0, 0.0]);
+}
+
+#[test]
+fn test_frames() {
+    let channels = vec![vec![1, 2, 3], vec![-1, -2, -3]];
+    for layout in [ChannelLayout::Interleaved, ChannelLayout::Planar] {
+        let mut data = Vec::new();
+        let mut stream = BitWriteStream::new(&mut data, LittleEndian);
+        write_frames(&mut stream, SampleFormat::S24, layout, &channels).unwrap();
+
+        let expected_second = match layout {
+            ChannelLayout::Interleaved => -1,
+            ChannelLayout::Planar => 2,
+        };
+        let mut stream = BitReadStream::new(BitReadBuffer::new(&data, LittleEndian));
+        stream.skip_bits(24).unwrap();
+        assert_eq!(
+            read_sample(&mut stream, SampleFormat::S24).unwrap(),
+            expected_second
+        );
+
+        let mut stream = BitReadStream::new(BitReadBuffer::new(&data, LittleEndian));
+        assert_eq!(
+            read_frames(&mut stream, SampleFormat::S24, layout, 2, 3).unwrap(),
+            channels
+        );
+        assert_eq!(stream.bits_left(), 0);
+
+        let mut stream = BitReadStream::new(BitReadBuffer::new(&data, LittleEndian));
+        assert!(matches!(
+            read_frames(&mut stream, SampleFormat::S24, layout, 2, 4),
+            Err(BitError::NotEnoughData { .. })
+        ));
+        assert_eq!(stream.pos(), 0);
+    }
+
+    let mut stream = BitReadStream::new(BitReadBuffer::new(&[], LittleEndian));
+    assert_eq!(
+        read_frames(&mut stream, SampleFormat::S16, ChannelLayout::Planar, 2, 0).unwrap(),
+        [Vec::<i32>::new(), Vec::new()]
+    );
+}
+
+#[test]
+fn test_frames_f32() {
+    let channels = [[0.25f32, -0.5], [1.0, -1.0]];
+    let mut data = Vec::new();
+    let mut stream = BitWriteStream::new(&mut data, BigEndian);
+    write_frames_f32(
+        &mut stream,
+        SampleFormat::S8,
+        ChannelLayout::Interleaved,
+        &channels,
+    )
+    .unwrap();
+    assert_eq!(data, [0x20, 0x7F, 0xC0, 0x80]);
+
+    let mut stream = BitReadStream::new(BitReadBuffer::new(&data, BigEndian));
+    let read = read_frames_f32(
+        &mut stream,
+        SampleFormat::S8,
+        ChannelLayout::Interleaved,
+        2,
+        2,
+    )
+    .unwrap();
+    assert_eq!(read, [[0.25, -0.5], [127.0 / 128.0, -1.0]]);
+}
+
+#[test]
+#[should_panic]
+fn test_frames_uneven_channels() {
+    let mut data = Vec::new();
+    let mut stream = BitWriteStream::new(&mut data, BigEndian);
+    let _ = write_frames(
+        &mut stream,
+        SampleFormat::S8,
+        ChannelLayout::Planar,
+        &[vec![1, 2], vec![1]],
+    );
+}
@@ -0,0 +1,67 @@
+#![cfg(feature = "proptest")]
+
+use bitbuffer::proptest::{
+    bit_data, bit_position, roundtrip_property, roundtrip_property_with, signed_int, unsigned_int,
+};
+use bitbuffer::{
+    BigEndian, BitRead, BitReadBuffer, BitReadStream, BitWrite, BitWriteStream, LittleEndian,
+};
+use proptest::prelude::*;
+
+proptest! {
+    #[test]
+    fn test_unsigned_fits((value, bits) in unsigned_int(64)) {
+        prop_assert!((1..=64).contains(&bits));
+        prop_assert!(bits == 64 || value < 1 << bits);
+    }
+
+    #[test]
+    fn test_signed_roundtrip((value, bits) in signed_int(64)) {
+        let mut data = Vec::new();
+        let mut stream = BitWriteStream::new(&mut data, BigEndian);
+        stream.write_int(value, bits).unwrap();
+        let mut read = BitReadStream::new(BitReadBuffer::new(&data, BigEndian));
+        prop_assert_eq!(value, read.read_int::<i64>(bits).unwrap());
+    }
+
+    #[test]
+    fn test_position((data, pos) in bit_data(16).prop_flat_map(|data| {
+        let len = data.len() * 8;
+        (Just(data), bit_position(len))
+    })) {
+        let mut read = BitReadStream::new(BitReadBuffer::new(&data, LittleEndian));
+        read.set_pos(pos).unwrap();
+        prop_assert_eq!(read.bits_left(), data.len() * 8 - pos);
+    }
+}
+
+#[test]
+fn test_roundtrip_property() {
+    roundtrip_property::<u32>();
+    roundtrip_property::<(i16, bool, String)>();
+    roundtrip_property::<[u8; 4]>();
+}
+
+#[test]
+fn test_roundtrip_property_with() {
+    #[derive(BitRead, BitWrite, Debug, PartialEq)]
+    struct Foo {
+        #[size = 5]
+        small: u8,
+        flag: bool,
+    }
+
+    roundtrip_property_with((0u8..32, any::<bool>()).prop_map(|(small, flag)| Foo { small, flag }));
+}
+
+#[test]
+#[should_panic]
+fn test_roundtrip_property_failure() {
+    #[derive(BitRead, BitWrite, Debug, PartialEq)]
+    struct Foo {
+        #[size = 5]
+        small: u8,
+    }
+
+    roundtrip_property_with(any::<u8>().prop_map(|small| Foo { small }));
+}
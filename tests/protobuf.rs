@@ -0,0 +1,190 @@
+#![cfg(feature = "protobuf")]
+
+use bitbuffer::protobuf::{
+    read_fixed32, read_fixed64, read_length_delimited, read_length_delimited_bytes, read_sint,
+    read_tag, read_varint, skip_field, write_fixed32, write_fixed64, write_length_delimited,
+    write_sint, write_tag, write_varint, Tag, WireType,
+};
+use bitbuffer::{
+    BigEndian, BitError, BitReadBuffer, BitReadStream, BitWriteStream, Endianness, LittleEndian,
+    ReadLimits,
+};
+
+fn write_message<E: Endianness>(endianness: E) -> Vec<u8> {
+    let mut data = Vec::new();
+    let mut stream = BitWriteStream::new(&mut data, endianness);
+    write_tag(&mut stream, 1, WireType::Varint).unwrap();
+    write_varint(&mut stream, 150).unwrap();
+    write_tag(&mut stream, 2, WireType::LengthDelimited).unwrap();
+    write_length_delimited(&mut stream, b"testing").unwrap();
+    write_tag(&mut stream, 3, WireType::Fixed32).unwrap();
+    write_fixed32(&mut stream, 0x1234_5678).unwrap();
+    write_tag(&mut stream, 4, WireType::Fixed64).unwrap();
+    write_fixed64(&mut stream, u64::MAX - 1).unwrap();
+    write_tag(&mut stream, 5, WireType::Varint).unwrap();
+    write_sint(&mut stream, -3).unwrap();
+    write_tag(&mut stream, 100_000, WireType::Varint).unwrap();
+    write_varint(&mut stream, u64::MAX).unwrap();
+    data
+}
+
+fn read_message<E: Endianness>(data: &[u8], endianness: E) {
+    let mut stream = BitReadStream::new(BitReadBuffer::new(data, endianness));
+    let tag = read_tag(&mut stream).unwrap();
+    assert_eq!(
+        tag,
+        Tag {
+            field: 1,
+            wire_type: WireType::Varint
+        }
+    );
+    assert_eq!(read_varint(&mut stream).unwrap(), 150);
+    assert_eq!(read_tag(&mut stream).unwrap().field, 2);
+    assert_eq!(
+        read_length_delimited_bytes(&mut stream).unwrap().as_ref(),
+        b"testing"
+    );
+    assert_eq!(read_tag(&mut stream).unwrap().wire_type, WireType::Fixed32);
+    assert_eq!(read_fixed32(&mut stream).unwrap(), 0x1234_5678);
+    assert_eq!(read_tag(&mut stream).unwrap().wire_type, WireType::Fixed64);
+    assert_eq!(read_fixed64(&mut stream).unwrap(), u64::MAX - 1);
+    assert_eq!(read_tag(&mut stream).unwrap().field, 5);
+    assert_eq!(read_sint(&mut stream).unwrap(), -3);
+    assert_eq!(read_tag(&mut stream).unwrap().field, 100_000);
+    assert_eq!(read_varint(&mut stream).unwrap(), u64::MAX);
+    assert_eq!(stream.bits_left(), 0);
+}
+
+#[test]
+fn test_roundtrip() {
+    let le = write_message(LittleEndian);
+    let be = write_message(BigEndian);
+    assert_eq!(le, be);
+    assert_eq!(&le[..12], b"\x08\x96\x01\x12\x07testing");
+    read_message(&le, LittleEndian);
+    read_message(&be, BigEndian);
+}
+
+#[test]
+fn test_skip_fields() {
+    let data = write_message(LittleEndian);
+    let mut stream = BitReadStream::new(BitReadBuffer::new(&data, LittleEndian));
+    let mut fields = Vec::new();
+    while stream.bits_left() > 0 {
+        let tag = read_tag(&mut stream).unwrap();
+        fields.push(tag.field);
+        skip_field(&mut stream, tag).unwrap();
+    }
+    assert_eq!(fields, [1, 2, 3, 4, 5, 100_000]);
+}
+
+#[test]
+fn test_sint() {
+    for value in [0, -1, 1, -2, i64::MAX, i64::MIN] {
+        let mut data = Vec::new();
+        let mut stream = BitWriteStream::new(&mut data, LittleEndian);
+        write_sint(&mut stream, value).unwrap();
+        let mut stream = BitReadStream::new(BitReadBuffer::new(&data, LittleEndian));
+        assert_eq!(read_sint(&mut stream).unwrap(), value);
+    }
+    let mut data = Vec::new();
+    let mut stream = BitWriteStream::new(&mut data, LittleEndian);
+    write_sint(&mut stream, -1).unwrap();
+    write_sint(&mut stream, 1).unwrap();
+    assert_eq!(data, [1, 2]);
+}
+
+#[test]
+fn test_groups() {
+    // field 1 group containing field 2 varint and a nested group 3, followed by field 4
+    let data = [0x0B, 0x10, 0x01, 0x1B, 0x1C, 0x0C, 0x20, 0x05];
+    let mut stream = BitReadStream::new(BitReadBuffer::new(&data, LittleEndian));
+    let tag = read_tag(&mut stream).unwrap();
+    assert_eq!(tag.wire_type, WireType::StartGroup);
+    skip_field(&mut stream, tag).unwrap();
+    assert_eq!(read_tag(&mut stream).unwrap().field, 4);
+    assert_eq!(read_varint(&mut stream).unwrap(), 5);
+
+    let mut stream = BitReadStream::new(BitReadBuffer::new(&data, LittleEndian));
+    stream.set_limits(ReadLimits {
+        max_depth: 1,
+        ..ReadLimits::default()
+    });
+    let tag = read_tag(&mut stream).unwrap();
+    assert!(matches!(
+        skip_field(&mut stream, tag),
+        Err(BitError::LimitExceeded { .. })
+    ));
+    assert_eq!(stream.pos(), 8);
+}
+
+#[test]
+fn test_nested_message() {
+    let mut inner = Vec::new();
+    let mut stream = BitWriteStream::new(&mut inner, LittleEndian);
+    write_tag(&mut stream, 1, WireType::Varint).unwrap();
+    write_varint(&mut stream, 42).unwrap();
+
+    let mut data = Vec::new();
+    let mut stream = BitWriteStream::new(&mut data, LittleEndian);
+    write_tag(&mut stream, 7, WireType::LengthDelimited).unwrap();
+    write_length_delimited(&mut stream, &inner).unwrap();
+
+    let mut stream = BitReadStream::new(BitReadBuffer::new(&data, LittleEndian));
+    read_tag(&mut stream).unwrap();
+    let mut message = read_length_delimited(&mut stream).unwrap();
+    assert_eq!(message.bit_len(), 16);
+    assert_eq!(read_tag(&mut message).unwrap().field, 1);
+    assert_eq!(read_varint(&mut message).unwrap(), 42);
+    assert_eq!(stream.bits_left(), 0);
+}
+
+#[test]
+fn test_errors() {
+    let check = |data: &[u8]| {
+        let mut stream = BitReadStream::new(BitReadBuffer::new(data, LittleEndian));
+        match read_tag(&mut stream) {
+            Ok(tag) => {
+                let pos = stream.pos();
+                let err = skip_field(&mut stream, tag).unwrap_err();
+                assert_eq!(stream.pos(), pos);
+                err
+            }
+            Err(err) => {
+                assert_eq!(stream.pos(), 0);
+                err
+            }
+        }
+    };
+    assert!(matches!(
+        check(&[0x0E]),
+        BitError::UnmatchedDiscriminant {
+            discriminant: 6,
+            ..
+        }
+    ));
+    assert!(matches!(
+        check(&[0x08, 0x80]),
+        BitError::NotEnoughData { .. }
+    ));
+    assert!(matches!(
+        check(&[0x12, 0x05, 0x00]),
+        BitError::NotEnoughData { .. }
+    ));
+    assert!(matches!(
+        check(&[0x12, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x01]),
+        BitError::SizeOverflow { .. } | BitError::NotEnoughData { .. }
+    ));
+    assert!(matches!(
+        check(&[0x80, 0x80, 0x80, 0x80, 0x80, 0x02]),
+        BitError::VarIntOverflow { max: 32, .. }
+    ));
+
+    let overlong = [0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x02];
+    let mut stream = BitReadStream::new(BitReadBuffer::new(&overlong, LittleEndian));
+    assert!(matches!(
+        read_varint(&mut stream),
+        Err(BitError::VarIntOverflow { max: 64, .. })
+    ));
+    assert_eq!(stream.pos(), 0);
+}
@@ -0,0 +1,210 @@
+use bitbuffer::crc::{CrcAlgorithm, CRC_16_KERMIT, CRC_24_BLE};
+use bitbuffer::radio::{
+    read_ble_pdu, read_frame_control, read_mac_frame, read_pdu_header, read_reversed,
+    reverse_bit_order, BleWhitening, FrameControl, PduHeader, Pn9Whitening,
+};
+use bitbuffer::scramble::{DescrambleStream, KeyStream};
+use bitbuffer::{BigEndian, BitError, BitReadBuffer, BitReadStream, BitWriteStream, LittleEndian};
+
+fn key_bytes<K: KeyStream>(mut key: K, count: usize) -> Vec<u8> {
+    (0..count)
+        .map(|_| key.next_bits::<LittleEndian>(8) as u8)
+        .collect()
+}
+
+#[test]
+fn test_whitening_sequences() {
+    assert_eq!(
+        key_bytes(BleWhitening::new(37), 4),
+        [0x8D, 0xD2, 0x57, 0xA1]
+    );
+    assert_eq!(key_bytes(Pn9Whitening::new(), 4), [0xFF, 0xE1, 0x1D, 0x9A]);
+
+    // both are maximal length sequences
+    let mut key = BleWhitening::new(0);
+    let start: Vec<bool> = (0..16).map(|_| key.next_bit()).collect();
+    for _ in 16..127 {
+        key.next_bit();
+    }
+    let repeated: Vec<bool> = (0..16).map(|_| key.next_bit()).collect();
+    assert_eq!(start, repeated);
+}
+
+#[test]
+#[should_panic]
+fn test_invalid_channel() {
+    BleWhitening::new(40);
+}
+
+#[test]
+fn test_reverse_bit_order() {
+    let bytes = [0x12, 0x80, 0xF0];
+    let reversed = reverse_bit_order(&bytes);
+    assert_eq!(reversed, [0x48, 0x01, 0x0F]);
+    assert_eq!(reverse_bit_order(&reversed), bytes);
+
+    // reading reversed bytes little endian gives the bits in the same order as reading the original big endian
+    let mut big = BitReadStream::new(BitReadBuffer::new(&bytes, BigEndian));
+    let mut little = BitReadStream::new(BitReadBuffer::new(&reversed, LittleEndian));
+    for _ in 0..24 {
+        assert_eq!(big.read_bool().unwrap(), little.read_bool().unwrap());
+    }
+}
+
+#[test]
+fn test_read_reversed() {
+    let mut little = BitReadStream::new(BitReadBuffer::new(&[0b0000_0110, 0xFF], LittleEndian));
+    assert_eq!(read_reversed(&mut little, 3).unwrap(), 0b011);
+    assert_eq!(read_reversed(&mut little, 0).unwrap(), 0);
+    assert_eq!(read_reversed(&mut little, 13).unwrap(), 0x00FF);
+
+    let mut big = BitReadStream::new(BitReadBuffer::new(&[0b1100_0000], BigEndian));
+    assert_eq!(read_reversed(&mut big, 4).unwrap(), 0b0011);
+    assert!(matches!(
+        read_reversed(&mut big, 65),
+        Err(BitError::TooManyBits { .. })
+    ));
+}
+
+fn whitened_pdu(pdu: &[u8], crc: &CrcAlgorithm, channel: u8) -> Vec<u8> {
+    let mut data = Vec::new();
+    let mut stream = BitWriteStream::new(&mut data, LittleEndian);
+    stream.attach_scrambler(BleWhitening::new(channel));
+    stream.write_bytes(pdu).unwrap();
+    stream.write_int(crc.checksum(pdu), 24).unwrap();
+    drop(stream);
+    data
+}
+
+#[test]
+fn test_read_ble_pdu() {
+    let pdu = [0x42, 0x09, 1, 2, 3, 4, 5, 6, 0x02, 0x01, 0x06];
+    let data = whitened_pdu(&pdu, &CRC_24_BLE, 38);
+    assert_ne!(&data[..pdu.len()], pdu);
+
+    let stream = BitReadStream::new(BitReadBuffer::new(&data, LittleEndian));
+    let mut descramble = DescrambleStream::new(stream, BleWhitening::new(38));
+    let mut dewhitened = descramble.read_bits(descramble.bits_left()).unwrap();
+    let mut result = read_ble_pdu(&mut dewhitened, &CRC_24_BLE).unwrap();
+    assert_eq!(dewhitened.bits_left(), 0);
+    assert_eq!(
+        result.header,
+        PduHeader {
+            pdu_type: 2,
+            ch_sel: false,
+            tx_add: true,
+            rx_add: false,
+            length: 9,
+        }
+    );
+    assert_eq!(result.payload.read_bytes(9).unwrap(), &pdu[2..]);
+
+    // connections use a different crc init
+    let connection_crc = CrcAlgorithm {
+        init: 0x12_3456,
+        ..CRC_24_BLE
+    };
+    let data = whitened_pdu(&[0x01, 0x00], &connection_crc, 5);
+    let stream = BitReadStream::new(BitReadBuffer::new(&data, LittleEndian));
+    let mut dewhitened = DescrambleStream::new(stream, BleWhitening::new(5))
+        .read_bits(40)
+        .unwrap();
+    assert!(read_ble_pdu(&mut dewhitened.clone(), &CRC_24_BLE).is_err());
+    let result = read_ble_pdu(&mut dewhitened, &connection_crc).unwrap();
+    assert_eq!(result.header.length, 0);
+}
+
+#[test]
+fn test_ble_pdu_errors() {
+    let pdu = [0x00, 0x02, 0xAA, 0xBB];
+    let mut data = pdu.to_vec();
+    data.extend_from_slice(&(CRC_24_BLE.checksum(&pdu) as u32 ^ 1).to_le_bytes()[..3]);
+    let mut stream = BitReadStream::new(BitReadBuffer::new(&data, LittleEndian));
+    assert!(matches!(
+        read_ble_pdu(&mut stream, &CRC_24_BLE),
+        Err(BitError::Malformed { pos: 0, .. })
+    ));
+    assert_eq!(stream.pos(), 0);
+
+    let mut stream = BitReadStream::new(BitReadBuffer::new(&data[..6], LittleEndian));
+    assert!(matches!(
+        read_ble_pdu(&mut stream, &CRC_24_BLE),
+        Err(BitError::NotEnoughData { .. })
+    ));
+    assert_eq!(stream.pos(), 0);
+
+    let mut stream = BitReadStream::new(BitReadBuffer::new(&data[..1], LittleEndian));
+    assert!(read_pdu_header(&mut stream).is_err());
+    assert_eq!(stream.pos(), 0);
+}
+
+fn mac_frame(contents: &[u8]) -> Vec<u8> {
+    let mut data = contents.to_vec();
+    data.extend_from_slice(&(CRC_16_KERMIT.checksum(contents) as u16).to_le_bytes());
+    data
+}
+
+#[test]
+fn test_frame_control() {
+    // data frame, ack request, pan id compression, short addresses, 2006 version
+    let mut stream = BitReadStream::new(BitReadBuffer::new(&[0x61, 0x98], LittleEndian));
+    assert_eq!(
+        read_frame_control(&mut stream).unwrap(),
+        FrameControl {
+            frame_type: 1,
+            security_enabled: false,
+            frame_pending: false,
+            ack_request: true,
+            pan_id_compression: true,
+            sequence_number_suppression: false,
+            ie_present: false,
+            dest_addressing_mode: 2,
+            frame_version: 1,
+            src_addressing_mode: 2,
+        }
+    );
+}
+
+#[test]
+fn test_read_mac_frame() {
+    let contents = [
+        0x61, 0x88, 0x2A, 0xCD, 0xAB, 0x01, 0x00, 0x02, 0x00, b'h', b'i',
+    ];
+    let mut data = mac_frame(&contents);
+    data.push(0xFF);
+    let mut stream = BitReadStream::new(BitReadBuffer::new(&data, LittleEndian));
+    let mut frame = read_mac_frame(&mut stream, contents.len() + 2).unwrap();
+    assert_eq!(stream.bits_left(), 8);
+    assert_eq!(frame.frame_control.frame_type, 1);
+    assert_eq!(frame.body.bit_len(), (contents.len() - 2) * 8);
+    assert_eq!(frame.body.read_int::<u8>(8).unwrap(), 0x2A);
+
+    // acknowledgement frames only contain the frame control and sequence number
+    let ack = mac_frame(&[0x02, 0x00, 0x2A]);
+    let mut stream = BitReadStream::new(BitReadBuffer::new(&ack, LittleEndian));
+    let frame = read_mac_frame(&mut stream, 5).unwrap();
+    assert_eq!(frame.frame_control.frame_type, 2);
+    assert_eq!(frame.body.bit_len(), 8);
+}
+
+#[test]
+fn test_mac_frame_errors() {
+    let mut data = mac_frame(&[0x02, 0x00, 0x2A]);
+    data[4] ^= 0x80;
+    let mut stream = BitReadStream::new(BitReadBuffer::new(&data, LittleEndian));
+    assert!(matches!(
+        read_mac_frame(&mut stream, 5),
+        Err(BitError::Malformed { pos: 0, .. })
+    ));
+    assert_eq!(stream.pos(), 0);
+
+    assert!(matches!(
+        read_mac_frame(&mut stream, 3),
+        Err(BitError::Malformed { .. })
+    ));
+    assert!(matches!(
+        read_mac_frame(&mut stream, 6),
+        Err(BitError::NotEnoughData { .. })
+    ));
+    assert_eq!(stream.pos(), 0);
+}
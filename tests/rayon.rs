@@ -0,0 +1,99 @@
+#![cfg(feature = "rayon")]
+
+use bitbuffer::rayon::{par_decode, par_read, split_length_prefixed};
+use bitbuffer::{BigEndian, BitError, BitRead, BitReadBuffer, BitReadStream, LittleEndian};
+
+#[derive(BitRead, Debug, PartialEq)]
+struct Block {
+    id: u8,
+    #[size_bits = 4]
+    values: Vec<u8>,
+}
+
+#[test]
+fn test_par_decode_chunks() {
+    let bytes: Vec<u8> = (0..=255).collect();
+    let buffer = BitReadBuffer::new(&bytes, BigEndian);
+    let sums = par_decode(buffer.chunks(32 * 8), |section| {
+        let mut sum = 0u32;
+        while section.bits_left() > 0 {
+            sum += section.read_int::<u32>(8)?;
+        }
+        Ok(sum)
+    })
+    .unwrap();
+    assert_eq!(sums.len(), 8);
+    for (i, sum) in sums.into_iter().enumerate() {
+        assert_eq!(sum, (i as u32 * 32..(i as u32 + 1) * 32).sum());
+    }
+}
+
+#[test]
+fn test_par_read_length_prefixed() {
+    let mut data = Vec::new();
+    for id in 0..100u8 {
+        let count = id % 16;
+        let block_bits = 8 + 4 + count as usize * 8;
+        data.push(block_bits.div_ceil(8) as u8);
+        let mut block = vec![0; block_bits.div_ceil(8)];
+        block[0] = id;
+        block[1] = count;
+        for i in 0..count as usize {
+            // values start at bit 12
+            let value = (id as u16 + i as u16) as u8;
+            let bit = 12 + i * 8;
+            block[bit / 8] |= value << (bit % 8);
+            if bit % 8 != 0 {
+                block[bit / 8 + 1] |= value >> (8 - bit % 8);
+            }
+        }
+        data.extend_from_slice(&block);
+    }
+
+    let mut stream = BitReadStream::new(BitReadBuffer::new(&data, LittleEndian));
+    let blocks = split_length_prefixed(&mut stream, 8, 8).unwrap();
+    assert_eq!(stream.bits_left(), 0);
+    let blocks: Vec<Block> = par_read(blocks).unwrap();
+    for (id, block) in blocks.into_iter().enumerate() {
+        assert_eq!(block.id as usize, id);
+        let expected: Vec<u8> = (0..id as u8 % 16).map(|i| id as u8 + i).collect();
+        assert_eq!(block.values, expected);
+    }
+}
+
+#[test]
+fn test_par_errors() {
+    let bytes = [2, 0x12, 0x34, 4, 0x56];
+    let mut stream = BitReadStream::new(BitReadBuffer::new(&bytes, LittleEndian));
+    assert!(matches!(
+        split_length_prefixed(&mut stream, 8, 8),
+        Err(BitError::NotEnoughData { .. })
+    ));
+    assert_eq!(stream.pos(), 0);
+
+    let buffer = BitReadBuffer::new(&bytes, LittleEndian);
+    let (head, tail) = buffer.split_at(12).unwrap();
+    assert!(par_read::<_, u16, _>([head, tail]).is_err());
+}
+
+#[test]
+fn test_streams_are_send() {
+    fn assert_send<T: Send + Sync>(_: &T) {}
+    let bytes = vec![0; 4];
+    let buffer = BitReadBuffer::new_owned(bytes, LittleEndian);
+    let (head, tail) = buffer.split_at(8).unwrap();
+    assert_send(&head);
+    let handle = std::thread::spawn(move || tail.clone().read_int::<u32>(24));
+    assert_eq!(handle.join().unwrap().unwrap(), 0);
+}
+
+#[test]
+fn test_stream_split_at_send() {
+    let bytes = vec![0x12, 0x34, 0x56, 0x78];
+    let mut stream = BitReadStream::new(BitReadBuffer::new_owned(bytes, BigEndian));
+    stream.skip_bits(8).unwrap();
+    let (mut head, mut tail) = stream.split_at(8).unwrap();
+    let handle = std::thread::spawn(move || tail.read_int::<u16>(16));
+    assert_eq!(head.read_int::<u8>(8).unwrap(), 0x34);
+    assert_eq!(handle.join().unwrap().unwrap(), 0x5678);
+}
@@ -1,9 +1,14 @@
+use std::borrow::Cow;
 use std::collections::HashMap;
 use std::num::NonZeroU16;
+use std::sync::Arc;
 
 use maplit::hashmap;
 
-use bitbuffer::{BigEndian, BitError, BitRead, BitReadBuffer, BitReadStream, LittleEndian};
+use bitbuffer::{
+    BigEndian, BitError, BitRead, BitReadBuffer, BitReadStream, BitWriteStream, ErrorKind,
+    HuffmanTable, LittleEndian, ReadLimit, ReadLimits, StringInterner,
+};
 
 const BYTES: &'static [u8] = &[
     0b1011_0101,
@@ -124,6 +129,124 @@ fn read_u64_be() {
     );
 }
 
+fn read_bitwise<E: bitbuffer::Endianness>(
+    buffer: &BitReadBuffer<E>,
+    pos: usize,
+    count: usize,
+) -> u128 {
+    let mut value = 0;
+    for i in 0..count {
+        let bit = buffer.read_bool(pos + i).unwrap() as u128;
+        if E::is_le() {
+            value |= bit << i;
+        } else {
+            value = (value << 1) | bit;
+        }
+    }
+    value
+}
+
+fn check_wide_reads<E: bitbuffer::Endianness>(endianness: E) {
+    let bytes: Vec<u8> = (0..40u8).map(|i| i.wrapping_mul(151) ^ 0x5a).collect();
+    let buffer = BitReadBuffer::new(&bytes, endianness);
+    // include positions close to the end of the buffer to cover the padded reads
+    for pos in (0..8).chain(bytes.len() * 8 - 136..bytes.len() * 8 - 128) {
+        for count in 1..=128 {
+            let expected = read_bitwise(&buffer, pos, count);
+            assert_eq!(
+                buffer.read_int::<u128>(pos, count).unwrap(),
+                expected,
+                "u128 at {} with {} bits",
+                pos,
+                count
+            );
+            if count <= 64 {
+                assert_eq!(
+                    buffer.read_int::<u64>(pos, count).unwrap() as u128,
+                    expected,
+                    "u64 at {} with {} bits",
+                    pos,
+                    count
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn read_wide_int_le() {
+    check_wide_reads(LittleEndian);
+}
+
+#[test]
+fn read_wide_int_be() {
+    check_wide_reads(BigEndian);
+}
+
+#[test]
+fn read_huge_sizes() {
+    let bytes = [0x12, 0x34, 0x56, 0x78];
+    let buffer = BitReadBuffer::new(&bytes, LittleEndian);
+
+    assert!(matches!(
+        buffer.read_int::<u8>(usize::MAX - 4, 8),
+        Err(BitError::IndexOutOfBounds { .. })
+    ));
+    assert!(matches!(
+        buffer.read_float::<f32>(usize::MAX - 4),
+        Err(BitError::IndexOutOfBounds { .. })
+    ));
+    assert!(matches!(
+        buffer.read_bytes(0, usize::MAX / 4),
+        Err(BitError::SizeOverflow { .. })
+    ));
+    assert!(matches!(
+        buffer.read_bytes(8, usize::MAX / 8),
+        Err(BitError::NotEnoughData { .. })
+    ));
+
+    let mut stream = BitReadStream::new(buffer);
+    stream.skip_bits(4).unwrap();
+    assert!(matches!(
+        stream.read_bytes(usize::MAX / 4),
+        Err(BitError::SizeOverflow { .. })
+    ));
+    assert!(matches!(
+        stream.read_sized::<Vec<u32>>(usize::MAX / 16),
+        Err(BitError::SizeOverflow { .. })
+    ));
+    assert!(matches!(
+        stream.read_sized::<[String; 4]>(usize::MAX / 16),
+        Err(BitError::SizeOverflow { .. })
+    ));
+    assert!(stream.read_bits(usize::MAX).is_err());
+    assert_eq!(stream.pos(), 4);
+    assert_eq!(stream.read::<u8>().unwrap(), 0x41);
+}
+
+#[test]
+fn read_aligned_int() {
+    let bytes = [0xfe, 0xff, 0x12, 0x34, 0x56, 0x78, 0x9a, 0xbc, 0xde, 0xf0];
+    let le = BitReadBuffer::new(&bytes, LittleEndian);
+    let be = BitReadBuffer::new(&bytes, BigEndian);
+    let segmented = BitReadBuffer::from_segments([&bytes[..3], &bytes[3..]], BigEndian);
+
+    assert_eq!(le.read_int::<i16>(0, 16).unwrap(), -2);
+    assert_eq!(le.read_int::<i32>(0, 16).unwrap(), -2);
+    assert_eq!(le.read_int::<u32>(0, 16).unwrap(), 0xfffe);
+    assert_eq!(be.read_int::<i16>(0, 16).unwrap(), -257);
+    assert_eq!(be.read_int::<u32>(16, 32).unwrap(), 0x1234_5678);
+    assert_eq!(le.read_int::<u32>(16, 32).unwrap(), 0x7856_3412);
+    assert_eq!(be.read_int::<u64>(16, 64).unwrap(), 0x1234_5678_9abc_def0);
+    assert_eq!(le.read_int::<u64>(16, 64).unwrap(), 0xf0de_bc9a_7856_3412);
+    assert_eq!(be.read_int::<u32>(16, 24).unwrap(), 0x12_3456);
+    assert_eq!(segmented.read_int::<u32>(16, 32).unwrap(), 0x1234_5678);
+    assert!(matches!(
+        be.read_int::<u32>(56, 32),
+        Err(BitError::NotEnoughData { .. })
+    ));
+}
+
 #[test]
 fn read_i8_le() {
     let buffer = BitReadBuffer::new(BYTES, LittleEndian);
@@ -175,6 +298,15 @@ fn read_i32_be() {
     assert_eq!(buffer.read_int::<i32>(7, 24).unwrap(), -4893108);
 }
 
+#[test]
+fn read_i64_near_full_width() {
+    let bytes = [0xff; 16];
+    let buffer = BitReadBuffer::new(&bytes, LittleEndian);
+
+    assert_eq!(buffer.read_int::<i64>(0, 63).unwrap(), -1);
+    assert_eq!(buffer.read_int::<i32>(1, 31).unwrap(), -1);
+}
+
 #[test]
 fn read_i64_le() {
     let buffer = BitReadBuffer::new(BYTES, LittleEndian);
@@ -485,7 +617,7 @@ fn test_invalid_utf8() {
         Err(BitError::Utf8Error(_, 4))
     ));
 
-    assert_eq!(stream.pos(), 5 * 8);
+    assert_eq!(stream.pos(), 0);
 
     let mut stream = BitReadStream::new(buffer);
 
@@ -494,5 +626,1315 @@ fn test_invalid_utf8() {
         Err(BitError::Utf8Error(_, 6))
     ));
 
-    assert_eq!(stream.pos(), 6 * 8);
+    assert_eq!(stream.pos(), 0);
+}
+
+#[test]
+fn read_offset_binary() {
+    let bytes = vec![0x00, 0x80, 0xFF, 0x7F];
+    let buffer = BitReadBuffer::new(&bytes, BigEndian);
+    let mut stream = BitReadStream::new(buffer);
+    assert_eq!(stream.read_int_offset_binary::<i8>(8, 128).unwrap(), -128);
+    assert_eq!(stream.read_int_offset_binary::<i8>(8, 128).unwrap(), 0);
+    assert_eq!(stream.read_int_offset_binary::<i8>(8, 128).unwrap(), 127);
+    assert_eq!(stream.read_int_offset_binary::<u8>(8, 0).unwrap(), 0x7F);
+    assert!(matches!(
+        stream.read_int_offset_binary::<i8>(9, 0),
+        Err(BitError::TooManyBits {
+            requested: 9,
+            max: 8
+        })
+    ));
+}
+
+#[test]
+fn read_sign_magnitude() {
+    let bytes = vec![0xFF, 0x7F, 0x80, 0x00];
+    let buffer = BitReadBuffer::new(&bytes, BigEndian);
+    let mut stream = BitReadStream::new(buffer);
+    assert_eq!(stream.read_int_sign_magnitude::<i8>(8).unwrap(), -127);
+    assert_eq!(stream.read_int_sign_magnitude::<i8>(8).unwrap(), 127);
+    assert_eq!(stream.read_int_sign_magnitude::<i8>(8).unwrap(), 0);
+    assert_eq!(stream.read_int_sign_magnitude::<i32>(0).unwrap(), 0);
+    assert_eq!(stream.read_int_sign_magnitude::<i32>(1).unwrap(), 0);
+    assert_eq!(stream.pos(), 25);
+}
+
+#[test]
+fn read_deltas_not_enough_data() {
+    let bytes = vec![0xFF, 0xFF];
+    let buffer = BitReadBuffer::new(&bytes, LittleEndian);
+    let mut stream = BitReadStream::new(buffer);
+    stream.skip_bits(1).unwrap();
+    assert!(matches!(
+        stream.read_deltas::<i8>(4, 4, 0),
+        Err(BitError::NotEnoughData {
+            requested: 16,
+            bits_left: 15
+        })
+    ));
+    assert_eq!(stream.pos(), 1);
+    assert_eq!(stream.read_deltas::<i8>(3, 5, 0).unwrap(), [-1, -2, -3]);
+}
+
+#[test]
+fn read_deltas_limits() {
+    let bytes = vec![0xFF, 0xFF];
+    let buffer = BitReadBuffer::new(&bytes, BigEndian);
+    let mut stream = BitReadStream::new(buffer);
+    stream.set_limits(ReadLimits {
+        max_elements: 8,
+        ..ReadLimits::default()
+    });
+    assert!(matches!(
+        stream.read_deltas::<u8>(usize::MAX, 0, 1),
+        Err(BitError::LimitExceeded {
+            limit: ReadLimit::Elements,
+            requested: usize::MAX,
+            max: 8
+        })
+    ));
+    assert_eq!(stream.read_deltas::<u8>(3, 0, 7).unwrap(), [7, 7, 7]);
+    assert_eq!(stream.pos(), 0);
+}
+
+#[test]
+fn read_rle_error_restores_position() {
+    let bytes = vec![2, 5, 3];
+    let buffer = BitReadBuffer::new(&bytes, LittleEndian);
+    let mut stream = BitReadStream::new(buffer);
+    let mut runs = Vec::new();
+    assert!(stream
+        .read_rle_with(
+            8,
+            2,
+            |stream| stream.read_int::<u8>(8),
+            |count, value| { runs.push((count, value)) }
+        )
+        .is_err());
+    assert_eq!(runs, [(2, 5)]);
+    assert_eq!(stream.pos(), 0);
+    assert_eq!(
+        stream
+            .read_rle(8, 1, |stream| stream.read_int::<u8>(8))
+            .unwrap(),
+        [5, 5]
+    );
+    assert_eq!(stream.pos(), 16);
+}
+
+#[test]
+fn read_rle_limits() {
+    // 200 times 1, 255 times 2
+    let bytes = vec![200, 1, 255, 2];
+    let buffer = BitReadBuffer::new(&bytes, LittleEndian);
+    let mut stream = BitReadStream::new(buffer);
+    stream.set_limits(ReadLimits {
+        max_elements: 256,
+        ..ReadLimits::default()
+    });
+    assert!(matches!(
+        stream.read_rle(8, 2, |stream| stream.read_int::<u8>(8)),
+        Err(BitError::LimitExceeded {
+            limit: ReadLimit::Elements,
+            requested: 455,
+            max: 256
+        })
+    ));
+    assert_eq!(stream.pos(), 0);
+    assert_eq!(
+        stream
+            .read_rle(8, 1, |stream| stream.read_int::<u8>(8))
+            .unwrap(),
+        vec![1; 200]
+    );
+}
+
+#[test]
+fn read_with_bit_len() {
+    let bytes = vec![0xFF, 0xFF];
+    let buffer = BitReadBuffer::with_bit_len(&bytes, 9, LittleEndian).unwrap();
+    assert_eq!(buffer.bit_len(), 9);
+    assert!(buffer.read_int::<u16>(0, 10).is_err());
+    assert_eq!(buffer.read_int::<u16>(0, 9).unwrap(), 0x1FF);
+    assert!(matches!(
+        BitReadBuffer::with_bit_len(&bytes, 17, LittleEndian),
+        Err(BitError::NotEnoughData {
+            requested: 17,
+            bits_left: 16
+        })
+    ));
+}
+
+#[test]
+fn read_bool_array() {
+    let bytes = vec![0b1011_0101, 0b0110_1010];
+    let buffer = BitReadBuffer::new(&bytes, BigEndian);
+    let mut stream = BitReadStream::new(buffer);
+    assert_eq!(
+        stream.read_bool_array::<5>().unwrap(),
+        [true, false, true, true, false]
+    );
+    assert_eq!(stream.read_bool_array::<0>().unwrap(), [false; 0]);
+    assert!(stream.read_bool_array::<12>().is_err());
+    assert_eq!(stream.pos(), 5);
+    assert_eq!(
+        stream.read_bool_array().unwrap(),
+        [true, false, true, false, true, true, false, true, false, true, false]
+    );
+}
+
+#[test]
+fn read_limits_string() {
+    let bytes = b"Hello world\0Hi\0".to_vec();
+    let buffer = BitReadBuffer::new(&bytes, LittleEndian);
+    let mut stream = BitReadStream::new(buffer);
+    stream.set_limits(ReadLimits {
+        max_string_length: 5,
+        ..ReadLimits::default()
+    });
+    assert!(matches!(
+        stream.read_string(Some(11)),
+        Err(BitError::LimitExceeded {
+            limit: ReadLimit::StringLength,
+            requested: 11,
+            max: 5
+        })
+    ));
+    assert!(matches!(
+        stream.read_string(None),
+        Err(BitError::LimitExceeded {
+            limit: ReadLimit::StringLength,
+            requested: 11,
+            max: 5
+        })
+    ));
+    assert_eq!(stream.pos(), 0);
+    assert_eq!(stream.read_string(Some(5)).unwrap(), "Hello");
+
+    // limits are carried over to sub streams
+    stream.set_pos(12 * 8).unwrap();
+    let mut sub = stream.read_bits(3 * 8).unwrap();
+    assert_eq!(sub.limits().max_string_length, 5);
+    assert_eq!(sub.read_string(None).unwrap(), "Hi");
+}
+
+#[test]
+fn read_limits_elements() {
+    let bytes = vec![0; 64];
+    let buffer = BitReadBuffer::new(&bytes, LittleEndian);
+    let mut stream = BitReadStream::new(buffer);
+    stream.set_limits(ReadLimits {
+        max_elements: 8,
+        ..ReadLimits::default()
+    });
+    assert!(matches!(
+        stream.read_sized::<Vec<u8>>(usize::MAX),
+        Err(BitError::LimitExceeded {
+            limit: ReadLimit::Elements,
+            requested: usize::MAX,
+            max: 8
+        })
+    ));
+    assert!(matches!(
+        stream.read_sized::<HashMap<u8, String>>(9),
+        Err(BitError::LimitExceeded {
+            limit: ReadLimit::Elements,
+            requested: 9,
+            max: 8
+        })
+    ));
+    assert_eq!(stream.read_sized::<Vec<u8>>(8).unwrap(), vec![0; 8]);
+}
+
+#[test]
+fn read_finish() {
+    let bytes = vec![0b1011_0101, 0b0110_1010];
+    let buffer = BitReadBuffer::new(&bytes, LittleEndian);
+    let mut stream = BitReadStream::new(buffer);
+    assert!(matches!(
+        stream.finish(),
+        Err(BitError::TrailingData { bits_left: 16 })
+    ));
+    let mut sub = stream.read_bits(3).unwrap();
+    sub.skip_bits(3).unwrap();
+    sub.finish().unwrap();
+    assert!(matches!(
+        stream.finish(),
+        Err(BitError::TrailingData { bits_left: 13 })
+    ));
+    stream.skip_bits(13).unwrap();
+    stream.finish().unwrap();
+}
+
+#[test]
+fn failed_reads_dont_advance() {
+    let bytes = vec![0b1011_0101, 0b0110_1010, b'a', 129, 0];
+    let buffer = BitReadBuffer::new(&bytes, LittleEndian);
+    let mut stream = BitReadStream::new(buffer);
+    stream.skip_bits(2).unwrap();
+
+    assert!(stream.read_int::<u64>(64).is_err());
+    assert!(stream.read_float::<f64>().is_err());
+    assert!(stream.read_bytes(5).is_err());
+    assert!(stream.read_bits(39).is_err());
+    assert!(stream.read_bool_array::<39>().is_err());
+    assert!(stream.read_sized::<Vec<u16>>(3).is_err());
+    assert!(stream.read_sized::<HashMap<u8, u8>>(3).is_err());
+    assert!(stream.read::<(u16, u32)>().is_err());
+    assert!(stream.read::<[u8; 5]>().is_err());
+    assert!(stream.read::<Option<u64>>().is_err());
+    assert_eq!(stream.pos(), 2);
+
+    stream.set_pos(16).unwrap();
+    assert!(stream.read_string(None).is_err());
+    assert!(stream.read_sized::<String>(2).is_err());
+    assert!(stream.read::<(u8, String)>().is_err());
+    assert_eq!(stream.pos(), 16);
+    assert_eq!(stream.read_string(Some(1)).unwrap(), "a");
+}
+
+#[test]
+fn buffer_split_at_and_chunks() {
+    let bytes = vec![0b1011_0101, 0b0110_1010, 0b1010_1100];
+    let buffer = BitReadBuffer::new(&bytes, LittleEndian);
+
+    let (mut head, mut tail) = buffer.split_at(5).unwrap();
+    assert_eq!(head.pos(), 0);
+    assert_eq!(tail.pos(), 0);
+    assert_eq!(head.bits_left(), 5);
+    assert_eq!(tail.bits_left(), 19);
+    assert_eq!(head.read_int::<u8>(5).unwrap(), 0b1_0101);
+    assert!(head.read_bool().is_err());
+    assert_eq!(tail.read_int::<u8>(3).unwrap(), 0b101);
+
+    let (head, tail) = buffer.split_at(24).unwrap();
+    assert_eq!(head.bit_len(), 24);
+    assert_eq!(tail.bit_len(), 0);
+    assert!(buffer.split_at(25).is_err());
+
+    let chunks: Vec<_> = buffer.chunks(7).collect();
+    assert_eq!(
+        chunks
+            .iter()
+            .map(|chunk| chunk.bit_len())
+            .collect::<Vec<_>>(),
+        [7, 7, 7, 3]
+    );
+    let mut last = chunks[3].clone();
+    assert_eq!(last.read_int::<u8>(3).unwrap(), 0b101);
+    assert_eq!(buffer.chunks(8).count(), 3);
+}
+
+#[test]
+fn read_bits_owned() {
+    fn read_owned(bytes: Vec<u8>, owned: bool) -> BitReadStream<'static, BigEndian> {
+        let buffer = if owned {
+            BitReadBuffer::new_owned(bytes.clone(), BigEndian)
+        } else {
+            BitReadBuffer::new(&bytes, BigEndian)
+        };
+        let mut stream = BitReadStream::new(buffer);
+        stream.skip_bits(4).unwrap();
+        let mut sub = stream.read_bits(24).unwrap();
+        sub.skip_bits(4).unwrap();
+        assert!(matches!(
+            sub.read_bits_owned(21),
+            Err(BitError::NotEnoughData { .. })
+        ));
+        assert_eq!(sub.pos(), 4);
+        let result = sub.read_bits_owned(12).unwrap();
+        assert_eq!(sub.pos(), 16);
+        assert_eq!(stream.pos(), 28);
+        result
+    }
+
+    for owned in [false, true] {
+        let mut bits = read_owned(vec![0x12, 0x34, 0x56, 0x78], owned);
+        assert_eq!(bits.pos(), 0);
+        assert_eq!(bits.bit_len(), 12);
+        assert_eq!(bits.read_int::<u16>(12).unwrap(), 0x345);
+        assert!(bits.read_bool().is_err());
+    }
+}
+
+#[test]
+fn stream_split_at() {
+    let bytes = vec![0b1011_0101, 0b0110_1010, 0b1010_1100];
+    let mut stream = BitReadStream::new(BitReadBuffer::new(&bytes, LittleEndian));
+    stream.skip_bits(3).unwrap();
+
+    let (mut head, mut tail) = stream.split_at(6).unwrap();
+    assert_eq!(stream.pos(), 3);
+    assert_eq!(head.bit_len(), 6);
+    assert_eq!(tail.bit_len(), 15);
+    assert_eq!(head.bit_range(), 3..9);
+    assert_eq!(tail.bit_range(), 9..24);
+    assert_eq!(head.read_int::<u8>(6).unwrap(), 0b01_0110);
+    assert!(head.read_bool().is_err());
+    assert_eq!(tail.read_int::<u8>(7).unwrap(), 0b011_0101);
+
+    let (head, tail) = stream.split_at(21).unwrap();
+    assert_eq!(head.bit_len(), 21);
+    assert_eq!(tail.bit_len(), 0);
+    assert!(matches!(
+        stream.split_at(22),
+        Err(BitError::NotEnoughData { .. })
+    ));
+
+    let mut sub = stream.read_bits(10).unwrap();
+    let (head, tail) = sub.split_at(4).unwrap();
+    assert_eq!(head.bit_range(), 3..7);
+    assert_eq!(tail.bit_range(), 7..13);
+    sub.skip_bits(10).unwrap();
+    assert!(sub.split_at(1).is_err());
+}
+
+fn check_segmented<E: bitbuffer::Endianness>(bytes: &[u8], splits: &[usize]) {
+    let mut segments = Vec::new();
+    let mut start = 0;
+    for &split in splits {
+        segments.push(&bytes[start..split]);
+        start = split;
+    }
+    segments.push(&bytes[start..]);
+
+    let contiguous = BitReadBuffer::new(bytes, E::endianness());
+    let segmented = BitReadBuffer::from_segments(segments, E::endianness());
+    assert_eq!(segmented.byte_len(), bytes.len());
+    assert_eq!(segmented.bit_len(), contiguous.bit_len());
+    assert_eq!(segmented, contiguous);
+
+    for pos in 0..contiguous.bit_len() {
+        assert_eq!(
+            segmented.read_bool(pos).unwrap(),
+            contiguous.read_bool(pos).unwrap()
+        );
+        for count in [1, 7, 8, 13, 32, 57, 64] {
+            assert_eq!(
+                segmented.read_int::<u64>(pos, count).ok(),
+                contiguous.read_int::<u64>(pos, count).ok(),
+                "{} bits at {}",
+                count,
+                pos
+            );
+        }
+        for count in [1, 3, 9] {
+            assert_eq!(
+                segmented.read_bytes(pos, count).ok(),
+                contiguous.read_bytes(pos, count).ok()
+            );
+        }
+        assert_eq!(
+            segmented.read_string(pos, None).ok(),
+            contiguous.read_string(pos, None).ok()
+        );
+        assert_eq!(
+            segmented.read_float::<f64>(pos).ok(),
+            contiguous.read_float::<f64>(pos).ok()
+        );
+    }
+
+    let mut stream = BitReadStream::new(segmented);
+    stream.skip_bits(11).unwrap();
+    let mut owned = stream.to_owned();
+    let mut expected = BitReadStream::new(contiguous);
+    expected.skip_bits(11).unwrap();
+    while expected.bits_left() > 0 {
+        let count = expected.bits_left().min(5);
+        assert_eq!(
+            owned.read_int::<u8>(count).unwrap(),
+            expected.read_int::<u8>(count).unwrap()
+        );
+    }
+}
+
+#[test]
+fn read_segmented() {
+    let bytes = b"\x12\x34abc\0\x56\x78\x9a\xbc\0def\xde\xf0ghi\0\x01".to_vec();
+    for splits in [
+        &[][..],
+        &[1],
+        &[4, 5, 5, 6],
+        &[
+            1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21,
+        ],
+        &[3, 11, 17],
+    ] {
+        check_segmented::<LittleEndian>(&bytes, splits);
+        check_segmented::<BigEndian>(&bytes, splits);
+    }
+}
+
+#[test]
+fn read_segmented_borrowed() {
+    let first = b"ab\0c";
+    let second = b"def\0";
+    let buffer = BitReadBuffer::from_segments([&first[..], &second[..]], LittleEndian);
+    // within a single segment the data is borrowed
+    assert!(matches!(
+        buffer.read_string(0, None).unwrap(),
+        Cow::Borrowed("ab")
+    ));
+    assert!(matches!(
+        buffer.read_bytes(32, 3).unwrap(),
+        Cow::Borrowed(b"def")
+    ));
+    // crossing segments copies the data
+    assert!(matches!(buffer.read_string(24, None).unwrap(), Cow::Owned(ref s) if s == "cdef"));
+    assert_eq!(BitReadBuffer::from_segments([], LittleEndian).bit_len(), 0);
+
+    // reading at the end behaves the same as for contiguous data
+    let bytes = [&first[..], &second[..]].concat();
+    let mut contiguous = BitReadStream::new(BitReadBuffer::new(&bytes, LittleEndian));
+    let mut stream = BitReadStream::new(buffer);
+    stream.skip_bits(64).unwrap();
+    contiguous.skip_bits(64).unwrap();
+    assert_eq!(
+        stream.read_string(None).ok(),
+        contiguous.read_string(None).ok()
+    );
+    assert!(stream.read_bool().is_err());
+}
+
+#[test]
+fn read_string_prefixed() {
+    let bytes = [0x40, 0x10, 0x06, 0x26, 0x3F, 0xFF, 0xFF, 0xFF];
+    let buffer = BitReadBuffer::new(&bytes, BigEndian);
+    let mut stream = BitReadStream::new(buffer);
+    // 4 bit length, null bytes are kept
+    assert_eq!(stream.read_string_prefixed(4).unwrap(), "\u{1}\u{0}bc");
+    assert_eq!(stream.pos(), 36);
+    // length too long for the data
+    assert!(stream.read_string_prefixed(4).is_err());
+    assert_eq!(stream.pos(), 36);
+    assert!(stream.read_string_prefixed(65).is_err());
+
+    let bytes = [3, 0xFF, 0xFE, 0xFD];
+    let mut stream = BitReadStream::new(BitReadBuffer::new(&bytes, LittleEndian));
+    assert!(matches!(
+        stream.read_string_prefixed(8),
+        Err(BitError::Utf8Error(_, 3))
+    ));
+    assert_eq!(stream.pos(), 0);
+
+    let bytes = [5, b'a', b'b', b'c', b'd', b'e'];
+    let mut stream = BitReadStream::new(BitReadBuffer::new(&bytes, LittleEndian));
+    stream.set_limits(ReadLimits {
+        max_string_length: 4,
+        ..ReadLimits::default()
+    });
+    assert!(matches!(
+        stream.read_string_prefixed(8),
+        Err(BitError::LimitExceeded { .. })
+    ));
+    assert_eq!(stream.pos(), 0);
+}
+
+#[test]
+fn read_string_bounded() {
+    for offset in [0, 3] {
+        let mut bytes = Vec::new();
+        let mut write = BitWriteStream::new(&mut bytes, BigEndian);
+        if offset > 0 {
+            write.write_int(0u8, offset).unwrap();
+        }
+        write.write_string(&"a".repeat(150), None).unwrap();
+        write.write_bytes(&[b'b'; 48]).unwrap();
+
+        let mut stream = BitReadStream::new(BitReadBuffer::new(&bytes, BigEndian));
+        stream.skip_bits(offset).unwrap();
+        assert!(matches!(
+            stream.read_string_bounded(149),
+            Err(BitError::UnterminatedString { max_length: 149 })
+        ));
+        assert_eq!(stream.pos(), offset);
+        assert_eq!(stream.read_string_bounded(150).unwrap(), "a".repeat(150));
+        assert_eq!(stream.pos(), offset + 151 * 8);
+
+        // the end of the data counts as terminator
+        assert_eq!(stream.read_string_bounded(100).unwrap(), "b".repeat(48));
+        assert!(stream.bits_left() < 8);
+    }
+
+    let bytes = [b'a'; 20];
+    let mut stream = BitReadStream::new(BitReadBuffer::new(&bytes, BigEndian));
+    stream.set_limits(ReadLimits {
+        max_string_length: 10,
+        ..ReadLimits::default()
+    });
+    assert!(matches!(
+        stream.read_string_bounded(1000),
+        Err(BitError::LimitExceeded { .. })
+    ));
+    assert_eq!(stream.pos(), 0);
+}
+
+#[test]
+fn read_interned() {
+    let bytes = b"name\0name\0other\0namename";
+    let mut stream = BitReadStream::new(BitReadBuffer::new(bytes, LittleEndian));
+    let without: Arc<str> = stream.read().unwrap();
+    assert_eq!(&*without, "name");
+
+    let interner = Arc::new(StringInterner::new());
+    stream.set_interner(interner.clone());
+    let first: Arc<str> = stream.read().unwrap();
+    let mut sub = stream.read_bits(6 * 8).unwrap();
+    let other: Arc<str> = sub.read().unwrap();
+    let second: Arc<str> = stream.read_sized(4).unwrap();
+    let third: Arc<str> = stream.clone().read_sized(4).unwrap();
+    assert_eq!(&*other, "other");
+    assert_eq!(&*second, "name");
+    assert!(Arc::ptr_eq(&first, &second));
+    assert!(Arc::ptr_eq(&first, &third));
+    assert!(!Arc::ptr_eq(&first, &without));
+    assert_eq!(interner.len(), 2);
+
+    stream.clear_interner();
+    let fourth: Arc<str> = stream.read_sized(4).unwrap();
+    assert!(!Arc::ptr_eq(&first, &fourth));
+    interner.clear();
+    assert!(interner.is_empty());
+}
+
+macro_rules! check_int_vec {
+    ($ty:ty, $endianness:expr, $bytes:expr, $offset:expr, $count:expr, $bit_width:expr) => {{
+        let mut stream = BitReadStream::new(BitReadBuffer::new($bytes, $endianness));
+        stream.skip_bits($offset).unwrap();
+        let mut single = stream.clone();
+        let expected: Vec<$ty> = (0..$count)
+            .map(|_| single.read_int($bit_width).unwrap())
+            .collect();
+        assert_eq!(
+            stream.read_int_vec::<$ty>($count, $bit_width).unwrap(),
+            expected
+        );
+        assert_eq!(stream.bits_left(), single.bits_left());
+    }};
+}
+
+#[test]
+fn read_int_vec() {
+    let bytes: Vec<u8> = (0..=255u8).map(|i| i.wrapping_mul(97) ^ 0x5A).collect();
+    for offset in [0, 3, 8] {
+        check_int_vec!(u16, LittleEndian, &bytes, offset, 100, 16);
+        check_int_vec!(u16, BigEndian, &bytes, offset, 100, 16);
+        check_int_vec!(i32, LittleEndian, &bytes, offset, 50, 32);
+        check_int_vec!(i32, BigEndian, &bytes, offset, 50, 32);
+        check_int_vec!(u64, LittleEndian, &bytes, offset, 25, 64);
+        check_int_vec!(u64, BigEndian, &bytes, offset, 25, 64);
+        check_int_vec!(i16, LittleEndian, &bytes, offset, 150, 13);
+        check_int_vec!(i16, BigEndian, &bytes, offset, 150, 13);
+        check_int_vec!(u128, LittleEndian, &bytes, offset, 10, 100);
+    }
+
+    // reads up to the last bit of the buffer
+    check_int_vec!(u32, LittleEndian, &bytes, 0, 64, 32);
+    check_int_vec!(u8, BigEndian, &bytes, 0, 512, 4);
+    check_int_vec!(i8, BigEndian, &bytes, 5, 2043, 1);
+    check_int_vec!(u8, LittleEndian, &bytes, 5, 10, 0);
+
+    let segmented = BitReadBuffer::from_segments([&bytes[..100], &bytes[100..]], BigEndian);
+    let mut stream = BitReadStream::new(segmented);
+    stream.skip_bits(5).unwrap();
+    let mut single = stream.clone();
+    let expected: Vec<i32> = (0..80).map(|_| single.read_int(21).unwrap()).collect();
+    assert_eq!(stream.read_int_vec::<i32>(80, 21).unwrap(), expected);
+
+    let mut stream = BitReadStream::new(BitReadBuffer::new(&bytes, LittleEndian));
+    assert!(matches!(
+        stream.read_int_vec::<u32>(65, 32),
+        Err(BitError::NotEnoughData { .. })
+    ));
+    assert!(matches!(
+        stream.read_int_vec::<u8>(1, 9),
+        Err(BitError::TooManyBits { .. })
+    ));
+    assert!(matches!(
+        stream.read_int_vec::<u8>(usize::MAX, 8),
+        Err(BitError::SizeOverflow { .. })
+    ));
+    assert_eq!(stream.pos(), 0);
+    assert_eq!(stream.read_int_vec::<u8>(0, 8).unwrap(), []);
+
+    stream.set_limits(ReadLimits {
+        max_elements: 16,
+        ..ReadLimits::default()
+    });
+    assert!(matches!(
+        stream.read_int_vec::<u8>(17, 8),
+        Err(BitError::LimitExceeded { .. })
+    ));
+}
+
+#[test]
+fn read_float_vec() {
+    let values = [1.5f32, -0.25, 1e10, f32::MIN_POSITIVE];
+    let mut data = Vec::new();
+    let mut stream = BitWriteStream::new(&mut data, BigEndian);
+    stream.write_int(0b101u8, 3).unwrap();
+    for value in values {
+        stream.write_float(value).unwrap();
+    }
+    for value in values {
+        stream.write_float(value as f64).unwrap();
+    }
+
+    let mut stream = BitReadStream::new(BitReadBuffer::new(&data, BigEndian));
+    stream.skip_bits(3).unwrap();
+    assert_eq!(stream.read_float_vec::<f32>(4).unwrap(), values);
+    let doubles: Vec<f64> = values.iter().map(|value| *value as f64).collect();
+    assert_eq!(stream.read_float_vec::<f64>(4).unwrap(), doubles);
+    assert_eq!(stream.bits_left(), 5);
+}
+
+#[test]
+fn read_string_chars() {
+    let text = "a\0ßç€😀z";
+    let mut stream = BitReadStream::new(BitReadBuffer::new(text.as_bytes(), BigEndian));
+    assert_eq!(stream.read_string_chars(6).unwrap(), "a\0ßç€😀");
+    assert!(matches!(
+        stream.read_string_chars(2),
+        Err(BitError::NotEnoughData { .. })
+    ));
+    assert_eq!(stream.read_string_chars(1).unwrap(), "z");
+    assert_eq!(stream.read_string_chars(0).unwrap(), "");
+
+    // unaligned
+    let mut data = Vec::new();
+    let mut write = BitWriteStream::new(&mut data, LittleEndian);
+    write.write_int(0b101u8, 3).unwrap();
+    write.write_bytes(text.as_bytes()).unwrap();
+    let mut stream = BitReadStream::new(BitReadBuffer::new(&data, LittleEndian));
+    stream.skip_bits(3).unwrap();
+    assert_eq!(stream.read_string_chars(7).unwrap(), text);
+
+    // a truncated multi byte sequence doesn't advance the stream
+    let bytes = [b'a', 0xE2, 0x82];
+    let mut stream = BitReadStream::new(BitReadBuffer::new(&bytes, LittleEndian));
+    assert!(matches!(
+        stream.read_string_chars(2),
+        Err(BitError::NotEnoughData { .. })
+    ));
+    assert_eq!(stream.pos(), 0);
+
+    for (bytes, char_count, len) in [
+        (&[b'a', 0xFF, b'b'][..], 2, 2),
+        (&[b'a', 0xC3, b'b'][..], 2, 3),
+        (&[0x80, b'a'][..], 1, 1),
+        // overlong encoding
+        (&[0xC0, 0x80][..], 1, 2),
+        // surrogate
+        (&[0xED, 0xA0, 0x80][..], 1, 3),
+    ] {
+        let mut stream = BitReadStream::new(BitReadBuffer::new(bytes, LittleEndian));
+        match stream.read_string_chars(char_count) {
+            Err(BitError::Utf8Error(_, error_len)) => assert_eq!(error_len, len),
+            result => panic!("unexpected result {:?}", result),
+        }
+        assert_eq!(stream.pos(), 0);
+    }
+
+    let mut stream = BitReadStream::new(BitReadBuffer::new(text.as_bytes(), LittleEndian));
+    stream.set_limits(ReadLimits {
+        max_string_length: 4,
+        ..ReadLimits::default()
+    });
+    assert!(matches!(
+        stream.read_string_chars(4),
+        Err(BitError::LimitExceeded { .. })
+    ));
+    assert_eq!(stream.read_string_chars(3).unwrap(), "a\0ß");
+}
+
+#[test]
+fn read_string_utf16() {
+    let bytes = [
+        0x00, 0x61, 0xD8, 0x3D, 0xDE, 0x00, 0x00, 0x00, 0x00, 0x62, 0x00, 0x00,
+    ];
+    let mut stream = BitReadStream::new(BitReadBuffer::new(&bytes, BigEndian));
+    assert_eq!(stream.read_string_utf16(None).unwrap(), "a😀");
+    assert_eq!(stream.pos(), 8 * 8);
+    assert_eq!(stream.read_string_utf16(Some(2)).unwrap(), "b");
+    assert_eq!(stream.bits_left(), 0);
+
+    let mut stream = BitReadStream::new(BitReadBuffer::new(&bytes, BigEndian));
+    assert!(matches!(
+        stream.read_string_utf16(Some(7)),
+        Err(BitError::NotEnoughData { .. })
+    ));
+    assert_eq!(stream.pos(), 0);
+
+    // unpaired surrogates
+    let bytes = [0x61, 0x00, 0x3D, 0xD8, 0x62, 0x00, 0x00, 0x00];
+    let mut stream = BitReadStream::new(BitReadBuffer::new(&bytes, LittleEndian));
+    stream.skip_bits(16).unwrap();
+    let mut stream = stream.read_bits(48).unwrap();
+    assert!(matches!(
+        stream.read_string_utf16(None),
+        Err(BitError::Utf16Error {
+            unit: 0xD83D,
+            pos: 0
+        })
+    ));
+    assert_eq!(stream.pos(), 0);
+    let bytes = [0x61, 0x00, 0x00, 0xDE];
+    let mut stream = BitReadStream::new(BitReadBuffer::new(&bytes, LittleEndian));
+    let err = stream.read_string_utf16(Some(2)).unwrap_err();
+    assert!(matches!(
+        err,
+        BitError::Utf16Error {
+            unit: 0xDE00,
+            pos: 16
+        }
+    ));
+    assert_eq!(err.kind(), ErrorKind::InvalidString);
+
+    let bytes = [0x61, 0x00, 0x62, 0x00, 0x63, 0x00, 0x00, 0x00];
+    let mut stream = BitReadStream::new(BitReadBuffer::new(&bytes, LittleEndian));
+    stream.set_limits(ReadLimits {
+        max_string_length: 4,
+        ..ReadLimits::default()
+    });
+    assert!(matches!(
+        stream.read_string_utf16(None),
+        Err(BitError::LimitExceeded { .. })
+    ));
+    assert!(matches!(
+        stream.read_string_utf16(Some(3)),
+        Err(BitError::LimitExceeded { .. })
+    ));
+    assert_eq!(stream.pos(), 0);
+    assert_eq!(stream.read_string_utf16(Some(2)).unwrap(), "ab");
+}
+
+#[test]
+fn error_kind() {
+    let mut stream = BitReadStream::new(BitReadBuffer::new(BYTES, LittleEndian));
+    assert_eq!(
+        stream.read_int::<u8>(9).unwrap_err().kind(),
+        ErrorKind::InvalidInput
+    );
+    assert_eq!(
+        stream.read_bits(BYTES.len() * 8 + 1).unwrap_err().kind(),
+        ErrorKind::NotEnoughData
+    );
+    assert_eq!(
+        stream.set_pos(BYTES.len() * 8 + 1).unwrap_err().kind(),
+        ErrorKind::InvalidInput
+    );
+    assert_eq!(
+        stream.read_string(None).unwrap_err().kind(),
+        ErrorKind::InvalidString
+    );
+    stream.set_limits(ReadLimits {
+        max_string_length: 1,
+        ..ReadLimits::default()
+    });
+    assert_eq!(
+        stream.read_string(Some(2)).unwrap_err().kind(),
+        ErrorKind::LimitExceeded
+    );
+    let err = BitError::from(std::io::Error::from(std::io::ErrorKind::UnexpectedEof));
+    assert_eq!(err.kind(), ErrorKind::Io);
+}
+
+#[test]
+fn read_sized_slices() {
+    let buffer = BitReadBuffer::new(BYTES, BigEndian);
+    let mut stream = BitReadStream::new(buffer);
+    let expected = [0b1011_0101_0110_1010u16, 0b1010_1100_1001_1001];
+
+    let boxed: Box<[u16]> = stream.read_sized(2).unwrap();
+    assert_eq!(&*boxed, expected);
+    stream.set_pos(0).unwrap();
+    let rc: std::rc::Rc<[u16]> = stream.read_sized(2).unwrap();
+    assert_eq!(&*rc, expected);
+    stream.set_pos(0).unwrap();
+    let arc: Arc<[u16]> = stream.read_sized(2).unwrap();
+    assert_eq!(&*arc, expected);
+    stream.set_pos(0).unwrap();
+    let arc: Arc<[u16]> = unsafe { stream.read_sized_unchecked(2, true) }.unwrap();
+    assert_eq!(&*arc, expected);
+    assert_eq!(bitbuffer::bit_size_of_sized::<Box<[u16]>>(3), Some(3 * 16));
+
+    assert!(matches!(
+        stream.read_sized::<Box<[u16]>>(100),
+        Err(BitError::NotEnoughData { .. })
+    ));
+
+    #[derive(BitRead)]
+    struct Table {
+        len: u8,
+        #[size = "len"]
+        entries: Arc<[u8]>,
+    }
+
+    let mut stream = BitReadStream::new(BitReadBuffer::new(&[2, 4, 5, 6], BigEndian));
+    let table: Table = stream.read().unwrap();
+    assert_eq!(table.len, 2);
+    assert_eq!(&*table.entries, [4, 5]);
+}
+
+#[test]
+fn read_small_fields_stream() {
+    let bytes: Vec<u8> = (0..64u8).map(|i| i.wrapping_mul(73) ^ 0xc5).collect();
+    let buffer = BitReadBuffer::new(&bytes, BigEndian);
+    let mut stream = BitReadStream::new(buffer.clone());
+    let mut pos = 0;
+    for count in (1..=20).cycle().take(40) {
+        assert_eq!(
+            stream.read_int::<u32>(count).unwrap(),
+            buffer.read_int::<u32>(pos, count).unwrap()
+        );
+        assert_eq!(
+            stream.read_bool().unwrap(),
+            buffer.read_bool(pos + count).unwrap()
+        );
+        pos += count + 1;
+    }
+
+    // moving backwards and reading signed values after a cached read
+    stream.set_pos(61).unwrap();
+    assert_eq!(
+        stream.read_int::<i8>(5).unwrap(),
+        buffer.read_int::<i8>(61, 5).unwrap()
+    );
+    stream.set_pos(3).unwrap();
+    assert_eq!(
+        stream.read_int::<i16>(12).unwrap(),
+        buffer.read_int::<i16>(3, 12).unwrap()
+    );
+
+    // the end of the stream still errors after a cached read
+    let mut sub = stream.read_bits(10).unwrap();
+    assert_eq!(
+        sub.read_int::<u16>(6).unwrap(),
+        buffer.read_int::<u16>(15, 6).unwrap()
+    );
+    assert!(matches!(
+        sub.read_int::<u8>(5),
+        Err(BitError::NotEnoughData { .. })
+    ));
+    assert_eq!(
+        sub.read_int::<u8>(4).unwrap(),
+        buffer.read_int::<u8>(21, 4).unwrap()
+    );
+    assert!(sub.read_bool().is_err());
+}
+
+#[test]
+fn stream_data_and_bit_range() {
+    let bytes = [0x12, 0x34, 0x56, 0x78];
+    let mut stream = BitReadStream::new(BitReadBuffer::new(&bytes, BigEndian));
+    assert_eq!(stream.data(), &bytes);
+    assert_eq!(stream.bit_range(), 0..32);
+
+    stream.skip_bits(4).unwrap();
+    let mut outer = stream.read_bits(20).unwrap();
+    outer.skip_bits(4).unwrap();
+    let inner = outer.read_bits(8).unwrap();
+    assert_eq!(outer.bit_range(), 4..24);
+    assert_eq!(inner.bit_range(), 8..16);
+    assert_eq!(inner.data(), &bytes);
+    let owned = inner.to_owned();
+    let range = owned.bit_range();
+    assert_eq!(range.len(), 8);
+    assert_eq!(&owned.data()[range.start / 8..range.end / 8], &[0x34]);
+
+    let bounded = BitReadBuffer::with_bit_len(&bytes, 12, BigEndian).unwrap();
+    assert_eq!(BitReadStream::new(bounded).bit_range(), 0..12);
+
+    let segmented = BitReadBuffer::from_segments([&bytes[..1], &bytes[1..]], BigEndian);
+    let mut stream = BitReadStream::new(segmented);
+    let sub = stream.read_bits(16).unwrap();
+    assert_eq!(sub.data(), &bytes);
+    assert_eq!(sub.bit_range(), 0..16);
+}
+
+#[test]
+fn read_bit_and_byte() {
+    let bytes = [0x12, 0x34, 0x56];
+    for buffer in [
+        BitReadBuffer::new(&bytes, BigEndian),
+        BitReadBuffer::from_segments([&bytes[..1], &bytes[1..]], BigEndian),
+    ] {
+        let mut stream = BitReadStream::new(buffer);
+        assert_eq!(stream.read_byte().unwrap(), 0x12);
+        assert!(!stream.read_bit().unwrap());
+        assert!(!stream.read_bit().unwrap());
+        assert!(stream.read_bit().unwrap());
+        assert_eq!(stream.read_byte().unwrap(), 0b1_0100_010);
+        assert_eq!(stream.pos(), 19);
+        assert!(matches!(
+            stream.read_byte(),
+            Err(BitError::NotEnoughData { .. })
+        ));
+        stream.set_pos(16).unwrap();
+        assert_eq!(stream.read_byte().unwrap(), 0x56);
+        assert!(matches!(
+            stream.read_bit(),
+            Err(BitError::NotEnoughData { .. })
+        ));
+        assert_eq!(stream.pos(), 24);
+    }
+
+    let mut stream = BitReadStream::new(BitReadBuffer::new(&bytes, LittleEndian));
+    stream.skip_bits(4).unwrap();
+    assert_eq!(stream.read_byte().unwrap(), 0x41);
+    assert!(stream.read_bit().unwrap());
+}
+
+#[test]
+fn read_huffman() {
+    // codes 0, 10, 110 and 111, with symbol 1 unused
+    let table = HuffmanTable::from_lengths(&[1, 0, 2, 3, 3]).unwrap();
+    let codes = [0b0_10_110_11, 0b1_0000000];
+    let mut stream = BitReadStream::new(BitReadBuffer::new(&codes, BigEndian));
+    let symbols: Vec<u16> = (0..4)
+        .map(|_| stream.read_huffman(&table).unwrap())
+        .collect();
+    assert_eq!(symbols, [0, 2, 3, 4]);
+    assert_eq!(stream.pos(), 9);
+    assert_eq!(stream.read_huffman(&table).unwrap(), 0);
+
+    // the same codes stored least significant bit first
+    let codes = [0b11_011_01_0, 0b0000000_1];
+    let mut stream = BitReadStream::new(BitReadBuffer::new(&codes, LittleEndian));
+    let symbols: Vec<u16> = (0..4)
+        .map(|_| stream.read_huffman(&table).unwrap())
+        .collect();
+    assert_eq!(symbols, [0, 2, 3, 4]);
+
+    // incomplete codes
+    let table = HuffmanTable::from_lengths(&[2, 2]).unwrap();
+    let mut stream = BitReadStream::new(BitReadBuffer::new(&[0b0110_0000], BigEndian));
+    assert_eq!(stream.read_huffman(&table).unwrap(), 1);
+    assert!(matches!(
+        stream.read_huffman(&table),
+        Err(BitError::Malformed { pos: 2, .. })
+    ));
+    assert_eq!(stream.pos(), 2);
+    stream.skip_bits(5).unwrap();
+    assert!(matches!(
+        stream.read_huffman(&table),
+        Err(BitError::NotEnoughData { .. })
+    ));
+    assert_eq!(stream.pos(), 7);
+}
+
+#[test]
+fn huffman_table_from_lengths() {
+    assert!(HuffmanTable::from_lengths(&[1, 1, 1]).is_none());
+    assert!(HuffmanTable::from_lengths(&[17]).is_none());
+    assert!(HuffmanTable::from_lengths(&[16, 16]).is_some());
+    let empty = HuffmanTable::from_lengths(&[0, 0]).unwrap();
+    let mut stream = BitReadStream::new(BitReadBuffer::new(&[0xFF, 0xFF, 0xFF], BigEndian));
+    assert!(stream.read_huffman(&empty).is_err());
+}
+
+#[test]
+fn align_to_byte() {
+    let bytes = [0xFF, 0x12];
+    let mut stream = BitReadStream::new(BitReadBuffer::new(&bytes, LittleEndian));
+    assert_eq!(stream.align_to_byte().unwrap(), 0);
+    stream.skip_bits(1).unwrap();
+    assert_eq!(stream.align_to_byte().unwrap(), 7);
+    assert_eq!(stream.read_int::<u8>(8).unwrap(), 0x12);
+
+    // alignment is relative to the start of the stream
+    let mut stream = BitReadStream::new(BitReadBuffer::new(&bytes, LittleEndian));
+    stream.skip_bits(3).unwrap();
+    let mut sub = stream.read_bits(10).unwrap();
+    sub.skip_bits(9).unwrap();
+    assert!(matches!(
+        sub.align_to_byte(),
+        Err(BitError::NotEnoughData { .. })
+    ));
+    sub.set_pos(2).unwrap();
+    assert_eq!(sub.align_to_byte().unwrap(), 6);
+    assert_eq!(sub.pos(), 8);
+}
+
+#[test]
+fn test_from_hex() {
+    let buffer = BitReadBuffer::from_hex("12 34_ab\nCD", BigEndian).unwrap();
+    assert_eq!(buffer.bit_len(), 32);
+    assert_eq!(buffer.read_int::<u32>(0, 32).unwrap(), 0x1234_ABCD);
+    assert_eq!(buffer.to_hex(), "1234abcd");
+
+    let buffer = BitReadBuffer::from_hex("", LittleEndian).unwrap();
+    assert_eq!(buffer.bit_len(), 0);
+    assert_eq!(buffer.to_hex(), "");
+
+    assert!(matches!(
+        BitReadBuffer::from_hex("12 3g", BigEndian),
+        Err(BitError::Malformed { pos: 12, .. })
+    ));
+    assert!(matches!(
+        BitReadBuffer::from_hex("123", BigEndian),
+        Err(BitError::Malformed { pos: 8, .. })
+    ));
+}
+
+#[test]
+fn test_from_bit_string() {
+    let buffer = BitReadBuffer::from_bit_string("1010 0111 01", BigEndian).unwrap();
+    assert_eq!(buffer.bit_len(), 10);
+    assert_eq!(buffer.read_int::<u16>(0, 10).unwrap(), 0b1010_0111_01);
+    assert_eq!(buffer.to_hex(), "a740");
+    assert_eq!(buffer.to_bit_string(), "10100111 01");
+
+    let buffer = BitReadBuffer::from_bit_string("1010_0111_01", LittleEndian).unwrap();
+    assert_eq!(buffer.read_int::<u8>(0, 4).unwrap(), 0b0101);
+    assert_eq!(buffer.to_hex(), "e502");
+    assert_eq!(buffer.to_bit_string(), "10100111 01");
+
+    let buffer = BitReadBuffer::new(&[0x12, 0x34], BigEndian);
+    let parsed = BitReadBuffer::from_bit_string(&buffer.to_bit_string(), BigEndian).unwrap();
+    assert_eq!(parsed, buffer);
+
+    assert!(matches!(
+        BitReadBuffer::from_bit_string("10 12", BigEndian),
+        Err(BitError::Malformed { pos: 3, .. })
+    ));
+}
+
+#[test]
+fn test_progress_hook() {
+    use std::sync::Mutex;
+
+    let bytes = vec![0; 16];
+    let mut stream = BitReadStream::new(BitReadBuffer::new(&bytes, BigEndian));
+    let calls = Arc::new(Mutex::new(Vec::new()));
+    let hook_calls = Arc::clone(&calls);
+    stream.set_progress_hook(32, move |pos, bit_len| {
+        hook_calls.lock().unwrap().push((pos, bit_len));
+    });
+
+    stream.read_int::<u32>(31).unwrap();
+    assert!(calls.lock().unwrap().is_empty());
+    stream.read_bool().unwrap();
+    assert_eq!(*calls.lock().unwrap(), [(32, 128)]);
+
+    // a single read over multiple intervals reports once
+    stream.read_bytes(9).unwrap();
+    assert_eq!(calls.lock().unwrap().len(), 2);
+    assert_eq!(calls.lock().unwrap()[1], (104, 128));
+
+    // moving back doesn't report, passing the interval again does
+    stream.set_pos(0).unwrap();
+    stream.read::<u32>().unwrap();
+    assert_eq!(calls.lock().unwrap().len(), 3);
+
+    // clones and sub streams don't report
+    stream.clone().read_int::<u64>(64).unwrap();
+    let mut sub = stream.clone().read_bits(64).unwrap();
+    sub.read_int::<u64>(64).unwrap();
+    assert_eq!(calls.lock().unwrap().len(), 3);
+
+    // the end of the stream is always reported
+    stream.set_pos(100).unwrap();
+    stream.skip_bits(28).unwrap();
+    assert_eq!(calls.lock().unwrap().last(), Some(&(128, 128)));
+    let count = calls.lock().unwrap().len();
+
+    stream.clear_progress_hook();
+    stream.set_pos(0).unwrap();
+    stream.skip_bits(128).unwrap();
+    assert_eq!(calls.lock().unwrap().len(), count);
+}
+
+#[test]
+fn test_cancellation() {
+    use bitbuffer::CancellationToken;
+
+    let bytes = vec![0; 64];
+    let mut stream = BitReadStream::new(BitReadBuffer::new(&bytes, LittleEndian));
+    let token = CancellationToken::new();
+    stream.set_cancellation_token(token.clone());
+
+    assert_eq!(stream.read_sized::<Vec<u8>>(4).unwrap().len(), 4);
+    assert!(stream.check_cancelled().is_ok());
+
+    token.cancel();
+    assert!(token.is_cancelled());
+    let err = stream.read_sized::<Vec<u8>>(4).unwrap_err();
+    assert!(matches!(err, BitError::Cancelled));
+    assert_eq!(err.kind(), ErrorKind::Cancelled);
+    assert_eq!(stream.pos(), 32);
+    assert!(matches!(
+        stream.read_sized::<HashMap<u8, u8>>(2),
+        Err(BitError::Cancelled)
+    ));
+    assert!(matches!(stream.read_bytes(4), Err(BitError::Cancelled)));
+    assert!(matches!(stream.skip_bits(8), Err(BitError::Cancelled)));
+    assert!(matches!(
+        stream.read_int_vec::<u8>(2, 4),
+        Err(BitError::Cancelled)
+    ));
+    // single values are still read
+    assert_eq!(stream.read::<u8>().unwrap(), 0);
+
+    // the token is carried over to sub streams
+    let mut sub = stream.read_bits(64).unwrap();
+    assert!(matches!(sub.skip_bits(8), Err(BitError::Cancelled)));
+
+    stream.clear_cancellation_token();
+    stream.skip_bits(8).unwrap();
+}
+
+#[test]
+fn test_fork() {
+    let bytes = vec![0x12, 0x34, 0x56, 0x78];
+    let mut stream = BitReadStream::new(BitReadBuffer::new(&bytes, BigEndian));
+    stream.skip_bits(8).unwrap();
+    let mut sub = stream.read_bits(16).unwrap();
+    sub.skip_bits(4).unwrap();
+
+    // a fork keeps the coordinates of the stream, unlike a clone
+    let mut fork = sub.fork();
+    assert_eq!(fork.pos(), 4);
+    assert_eq!(fork.bit_len(), 16);
+    assert_eq!(sub.clone().pos(), 0);
+
+    assert_eq!(fork.read_int::<u8>(8).unwrap(), 0x45);
+    assert_eq!(sub.pos(), 4);
+    assert_eq!(sub.read_int::<u8>(4).unwrap(), 0x4);
+    sub.reunite(fork.fork());
+    assert_eq!(sub.pos(), 12);
+
+    // reuniting can move backwards
+    fork.set_pos(0).unwrap();
+    sub.reunite(fork);
+    assert_eq!(sub.read_int::<u16>(16).unwrap(), 0x3456);
+
+    let owned = stream.to_owned();
+    let mut fork = owned.fork();
+    fork.skip_bits(4).unwrap();
+    let mut owned = owned;
+    owned.reunite(fork);
+    assert_eq!(owned.read_int::<u8>(4).unwrap(), 0x8);
+}
+
+#[test]
+#[should_panic(expected = "the stream isn't a fork of this stream")]
+fn test_reunite_clone() {
+    let bytes = vec![0x12, 0x34];
+    let mut stream = BitReadStream::new(BitReadBuffer::new(&bytes, BigEndian));
+    stream.skip_bits(4).unwrap();
+    let clone = stream.clone();
+    stream.reunite(clone);
+}
+
+#[test]
+fn test_skip_to() {
+    let bytes = vec![0x12, 0x34];
+    let mut stream = BitReadStream::new(BitReadBuffer::new(&bytes, BigEndian));
+    stream.skip_to(4).unwrap();
+    stream.skip_to(4).unwrap();
+    assert!(matches!(
+        stream.skip_to(3),
+        Err(BitError::IndexOutOfBounds { pos: 3, size: 16 })
+    ));
+    assert!(matches!(
+        stream.skip_to(17),
+        Err(BitError::IndexOutOfBounds { pos: 17, size: 16 })
+    ));
+    stream.skip_to(16).unwrap();
+    assert_eq!(stream.bits_left(), 0);
+}
+
+#[test]
+fn test_absolute_positions() {
+    let bytes: Vec<u8> = (0..32).collect();
+    let mut stream = BitReadStream::new(BitReadBuffer::new(&bytes, LittleEndian));
+    assert_eq!(stream.absolute_start(), 0);
+    stream.skip_bits(19).unwrap();
+    assert_eq!(stream.absolute_pos(), 19);
+
+    let mut outer = stream.read_bits(100).unwrap();
+    outer.skip_bits(10).unwrap();
+    let mut inner = outer.read_bits(50).unwrap();
+    inner.skip_bits(7).unwrap();
+    assert_eq!(inner.pos(), 7);
+    assert_eq!(inner.absolute_start(), 29);
+    assert_eq!(inner.absolute_pos(), 36);
+    assert_eq!(inner.clone().absolute_start(), 36);
+    assert_eq!(inner.fork().absolute_start(), 29);
+
+    let (head, tail) = inner.split_at(20).unwrap();
+    assert_eq!(head.absolute_start(), 36);
+    assert_eq!(tail.absolute_start(), 56);
+
+    // owned copies of borrowed data only copy part of the bytes, but keep the absolute positions
+    let mut owned = tail.to_owned();
+    assert_eq!(owned.absolute_start(), 56);
+    owned.skip_bits(5).unwrap();
+    let owned_pos = owned.absolute_pos();
+    assert_eq!(owned_pos, 61);
+    let mut owned_sub = owned.read_bits_owned(9).unwrap();
+    assert_eq!(owned_sub.absolute_start(), 61);
+    owned_sub.skip_bits(4).unwrap();
+    assert_eq!(owned_sub.to_owned().absolute_pos(), 65);
+    stream.set_pos(65).unwrap();
+    assert_eq!(
+        owned_sub.read_int::<u8>(5).unwrap(),
+        stream.read_int::<u8>(5).unwrap()
+    );
+}
+
+#[test]
+fn test_seek() {
+    use std::io::SeekFrom;
+
+    let bytes = vec![0x12, 0x34, 0x56, 0x78];
+    let mut stream = BitReadStream::new(BitReadBuffer::new(&bytes, BigEndian));
+    let mut sub = stream.read_bits(24).unwrap();
+    assert_eq!(sub.seek(SeekFrom::Start(24)).unwrap(), 24);
+    assert_eq!(sub.seek(SeekFrom::End(-8)).unwrap(), 16);
+    assert_eq!(sub.read_int::<u8>(8).unwrap(), 0x56);
+    assert_eq!(sub.seek(SeekFrom::Current(-12)).unwrap(), 12);
+    assert_eq!(sub.read_int::<u8>(4).unwrap(), 0x4);
+    assert_eq!(sub.seek(SeekFrom::Current(0)).unwrap(), 16);
+
+    assert!(matches!(
+        sub.seek(SeekFrom::Start(25)),
+        Err(BitError::SeekOutOfBounds {
+            current: 16,
+            requested: 25,
+            size: 24
+        })
+    ));
+    assert!(matches!(
+        sub.seek(SeekFrom::End(1)),
+        Err(BitError::SeekOutOfBounds { requested: 25, .. })
+    ));
+    assert!(matches!(
+        sub.seek(SeekFrom::Current(i64::MIN)),
+        Err(BitError::SeekOutOfBounds { .. })
+    ));
+    assert!(matches!(
+        sub.seek(SeekFrom::Start(u64::MAX)),
+        Err(BitError::SeekOutOfBounds { .. })
+    ));
+    assert_eq!(
+        sub.seek(SeekFrom::End(-25)).unwrap_err().kind(),
+        ErrorKind::InvalidInput
+    );
+    assert_eq!(sub.pos(), 16);
+}
+
+#[test]
+fn test_skip_to_end() {
+    let bytes = vec![0x12, 0x34];
+    let mut stream = BitReadStream::new(BitReadBuffer::new(&bytes, BigEndian));
+    stream.skip_bits(3).unwrap();
+    let mut sub = stream.read_bits(9).unwrap();
+    sub.skip_bits(2).unwrap();
+    assert_eq!(sub.skip_to_end(), 7);
+    assert_eq!(sub.pos(), 9);
+    assert_eq!(stream.skip_to_end(), 4);
+    assert_eq!(stream.bits_left(), 0);
 }
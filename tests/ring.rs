@@ -0,0 +1,104 @@
+use bitbuffer::ring::ring_buffer;
+use bitbuffer::{BigEndian, BitError, BitReadBuffer, BitReadStream, LittleEndian};
+use std::thread;
+
+#[test]
+fn test_ring_wrap_around() {
+    let (mut producer, mut consumer) = ring_buffer(5, BigEndian);
+    assert_eq!(producer.push(&[0x12, 0x34, 0x56, 0x78, 0x9A, 0xBC]), 5);
+    assert_eq!(producer.free(), 0);
+    assert_eq!(consumer.read::<u16>().unwrap(), 0x1234);
+    assert_eq!(
+        consumer
+            .read_with(|stream| stream.read_int::<u8>(3))
+            .unwrap(),
+        0b010
+    );
+    assert_eq!(producer.free(), 2);
+
+    assert_eq!(producer.push(&[0xBC, 0xDE, 0xF0]), 2);
+    assert_eq!(consumer.bits_left(), 37);
+    assert_eq!(
+        consumer
+            .read_with(|stream| stream.read_int::<u64>(37))
+            .unwrap(),
+        0x16_789A_BCDE
+    );
+    assert_eq!(consumer.bits_left(), 0);
+    assert_eq!(producer.free(), 5);
+}
+
+#[test]
+fn test_ring_wrap_around_repeatedly() {
+    let data: Vec<u8> = (0..3_000u32).map(|i| (i * 7 % 251) as u8).collect();
+    let (mut producer, mut consumer) = ring_buffer(3, BigEndian);
+    let mut expected = BitReadStream::new(BitReadBuffer::new(&data, BigEndian));
+
+    // reading 11 bits at a time keeps the head at a different bit offset on every pass around the ring
+    let mut remaining = &data[..];
+    while !remaining.is_empty() || consumer.bits_left() >= 11 {
+        let pushed = producer.push(remaining);
+        remaining = &remaining[pushed..];
+        assert_eq!(producer.free(), 3 - consumer.bits_left().div_ceil(8));
+        let value = consumer
+            .read_with(|stream| stream.read_int::<u16>(11))
+            .unwrap();
+        assert_eq!(value, expected.read_int::<u16>(11).unwrap());
+    }
+    assert_eq!(consumer.bits_left(), expected.bits_left());
+}
+
+#[test]
+fn test_ring_failed_read_retry() {
+    let (mut producer, mut consumer) = ring_buffer(4, LittleEndian);
+    producer.push(&[3, b'a']);
+    let read_message = |stream: &mut BitReadStream<LittleEndian>| -> bitbuffer::Result<String> {
+        let len: u8 = stream.read()?;
+        Ok(stream
+            .read_bytes(len as usize)?
+            .iter()
+            .map(|b| *b as char)
+            .collect())
+    };
+    assert!(matches!(
+        consumer.read_with(read_message),
+        Err(BitError::NotEnoughData { .. })
+    ));
+    assert_eq!(consumer.bits_left(), 16);
+
+    producer.push(b"bc");
+    assert_eq!(consumer.read_with(read_message).unwrap(), "abc");
+    assert_eq!(consumer.bits_left(), 0);
+}
+
+#[test]
+fn test_ring_threaded() {
+    let data: Vec<u8> = (0..10_000u32).map(|i| (i * 7 % 251) as u8).collect();
+    let (mut producer, mut consumer) = ring_buffer(13, LittleEndian);
+
+    let input = data.clone();
+    let handle = thread::spawn(move || {
+        let mut remaining = &input[..];
+        while !remaining.is_empty() {
+            let pushed = producer.push(&remaining[..remaining.len().min(5)]);
+            remaining = &remaining[pushed..];
+            thread::yield_now();
+        }
+    });
+
+    let mut values = Vec::new();
+    loop {
+        match consumer.read_with(|stream| stream.read_int::<u16>(10)) {
+            Ok(value) => values.push(value),
+            Err(_) if consumer.is_closed() && consumer.bits_left() < 10 => break,
+            Err(_) => thread::yield_now(),
+        }
+    }
+    handle.join().unwrap();
+
+    let mut expected = BitReadStream::new(BitReadBuffer::new(&data, LittleEndian));
+    for value in values {
+        assert_eq!(value, expected.read_int::<u16>(10).unwrap());
+    }
+    assert_eq!(expected.bits_left(), consumer.bits_left());
+}
@@ -102,3 +102,228 @@ fn test_tuple() {
     roundtrip((1, false));
     roundtrip((1, 10.12, String::from("asd")));
 }
+
+#[test]
+fn test_zero_sized() {
+    use bitbuffer::bit_size_of;
+    use std::marker::PhantomData;
+
+    #[derive(Debug, PartialEq, BitRead, BitWrite)]
+    struct Tagged<T> {
+        #[size = 3]
+        value: u8,
+        unit: (),
+        marker: PhantomData<T>,
+        flag: bool,
+    }
+    roundtrip(Tagged::<String> {
+        value: 5,
+        unit: (),
+        marker: PhantomData,
+        flag: true,
+    });
+    roundtrip(((), 1u8, PhantomData::<str>));
+    assert_eq!(bit_size_of::<()>(), Some(0));
+    assert_eq!(bit_size_of::<PhantomData<String>>(), Some(0));
+    assert_eq!(bit_size_of::<Tagged<u64>>(), Some(4));
+
+    let mut data = Vec::new();
+    let mut stream = BitWriteStream::new(&mut data, LittleEndian);
+    stream.write(&()).unwrap();
+    stream.write(&PhantomData::<u32>).unwrap();
+    assert_eq!(stream.bit_len(), 0);
+}
+
+#[test]
+fn test_char() {
+    use bitbuffer::BitError;
+
+    #[derive(Debug, PartialEq, BitRead, BitWrite)]
+    struct Glyph {
+        flag: bool,
+        text: char,
+        #[size = 21]
+        code_point: char,
+    }
+    roundtrip('a');
+    roundtrip('ß');
+    roundtrip('€');
+    roundtrip('🦀');
+    roundtrip(Glyph {
+        flag: true,
+        text: 'ü',
+        code_point: '🦀',
+    });
+
+    let mut data = Vec::new();
+    let mut stream = BitWriteStream::new(&mut data, LittleEndian);
+    stream.write(&'€').unwrap();
+    stream.write_sized(&'€', 32).unwrap();
+    assert_eq!(data, [0xE2, 0x82, 0xAC, 0xAC, 0x20, 0, 0]);
+
+    let buffer = BitReadBuffer::new(&[0x00, 0xD8, 0, 0, 0xFF], LittleEndian);
+    let mut stream = BitReadStream::new(buffer);
+    assert!(matches!(
+        stream.read_sized::<char>(32),
+        Err(BitError::Malformed { pos: 0, .. })
+    ));
+    assert_eq!(stream.pos(), 0);
+    stream.set_pos(32).unwrap();
+    assert!(matches!(
+        stream.read::<char>(),
+        Err(BitError::Utf8Error(_, 1))
+    ));
+    assert_eq!(stream.pos(), 32);
+}
+
+#[test]
+fn test_unaligned_bool() {
+    #[derive(Debug, PartialEq, BitRead, BitWrite)]
+    struct Foo {
+        #[size = 5]
+        small: u8,
+        flag: bool,
+        other: bool,
+    }
+    roundtrip(Foo {
+        small: 8,
+        flag: true,
+        other: false,
+    });
+    roundtrip(Foo {
+        small: 31,
+        flag: false,
+        other: true,
+    });
+}
+
+#[test]
+fn test_roundtrip_helper() {
+    use bitbuffer::{test_roundtrip, test_roundtrip_sized, RoundtripError};
+
+    #[derive(Debug, PartialEq, BitRead, BitWrite)]
+    struct Foo {
+        #[size = 3]
+        small: u8,
+        flag: bool,
+    }
+
+    test_roundtrip(
+        &Foo {
+            small: 5,
+            flag: true,
+        },
+        LittleEndian,
+    )
+    .unwrap();
+    test_roundtrip(
+        &Foo {
+            small: 5,
+            flag: true,
+        },
+        BigEndian,
+    )
+    .unwrap();
+    test_roundtrip_sized(&String::from("asd"), 3, BigEndian).unwrap();
+
+    match test_roundtrip(
+        &Foo {
+            small: 9,
+            flag: true,
+        },
+        LittleEndian,
+    ) {
+        Err(RoundtripError::Mismatch {
+            first_difference, ..
+        }) => assert_eq!(first_difference, None),
+        result => panic!("unexpected result {:?}", result),
+    }
+
+    // the value is written with 5 bits but read back with only 3
+    #[derive(Debug, PartialEq)]
+    struct Truncating(u8);
+
+    impl<E: bitbuffer::Endianness> BitRead<'_, E> for Truncating {
+        fn read(stream: &mut BitReadStream<'_, E>) -> bitbuffer::Result<Self> {
+            let value = stream.read_int(3)?;
+            stream.skip_bits(2)?;
+            Ok(Truncating(value))
+        }
+    }
+
+    impl<E: bitbuffer::Endianness> BitWrite<E> for Truncating {
+        fn write(&self, stream: &mut BitWriteStream<E>) -> bitbuffer::Result<()> {
+            stream.write_int(self.0, 5)
+        }
+    }
+
+    let error = test_roundtrip(&Truncating(0b10101), LittleEndian).unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "Read value Truncating(5) differs from written value Truncating(21), encodings first differ at bit 4"
+    );
+
+    let error = test_roundtrip_sized(&5u8, 2, BigEndian).unwrap_err();
+    assert!(matches!(error, RoundtripError::Mismatch { .. }));
+}
+
+#[test]
+fn test_truncation_helper() {
+    use bitbuffer::{assert_no_panic_on_truncation, assert_no_panic_on_truncation_sized};
+    use std::panic::catch_unwind;
+
+    #[derive(BitRead, Debug)]
+    #[allow(dead_code)]
+    struct Packet {
+        #[size = 4]
+        kind: u8,
+        length: u8,
+        #[size = "length"]
+        payload: Vec<u8>,
+        flag: bool,
+    }
+
+    assert_no_panic_on_truncation::<Packet, _>(&[0x31, 0x02, 0xAA, 0xBB, 0x80], BigEndian);
+    assert_no_panic_on_truncation::<Packet, _>(&[0x31, 0xFF, 0xAA], LittleEndian);
+    assert_no_panic_on_truncation_sized::<String, _>(b"foo\0bar", 5, LittleEndian);
+
+    // trusts the length prefix without checking the remaining data
+    #[derive(Debug)]
+    struct Overread;
+
+    impl<E: bitbuffer::Endianness> BitRead<'_, E> for Overread {
+        fn read(stream: &mut BitReadStream<'_, E>) -> bitbuffer::Result<Self> {
+            let length: usize = stream.read_int(8)?;
+            let bytes = stream.read_bytes(stream.bits_left() / 8)?;
+            let _ = bytes[length - 1];
+            Ok(Overread)
+        }
+    }
+
+    let panic = catch_unwind(|| {
+        assert_no_panic_on_truncation::<Overread, _>(&[2, 0xAA, 0xBB], LittleEndian)
+    })
+    .unwrap_err();
+    assert_eq!(
+        panic.downcast_ref::<String>().unwrap(),
+        "parsing a prefix of 8 bits panicked"
+    );
+
+    // reads whatever data is left
+    #[derive(Debug)]
+    struct Greedy;
+
+    impl<E: bitbuffer::Endianness> BitRead<'_, E> for Greedy {
+        fn read(stream: &mut BitReadStream<'_, E>) -> bitbuffer::Result<Self> {
+            stream.skip_bits(stream.bits_left())?;
+            Ok(Greedy)
+        }
+    }
+
+    let panic = catch_unwind(|| assert_no_panic_on_truncation::<Greedy, _>(&[1, 2], LittleEndian))
+        .unwrap_err();
+    assert_eq!(
+        panic.downcast_ref::<String>().unwrap(),
+        "parsing a prefix of 0 bits succeeded while the full value needs 16 bits, parsed Greedy"
+    );
+}
@@ -0,0 +1,176 @@
+#![cfg(feature = "schema")]
+
+use bitbuffer::schema::{Condition, Field, FieldType, Schema, Size};
+use bitbuffer::{
+    BigEndian, BitError, BitReadBuffer, BitReadStream, BitValue, BitWriteStream, LittleEndian,
+    ReadLimits,
+};
+
+const SCHEMA: &str = r#"{
+    "fields": [
+        { "name": "flags", "type": "uint", "bits": 4 },
+        { "name": "width", "type": "uint", "bits": 4 },
+        { "name": "offset", "type": "int", "bits": "width", "if": { "field": "flags", "mask": 1 } },
+        { "name": "scale", "type": "f32", "if": { "field": "flags", "mask": 2 } },
+        { "name": "label", "type": "string", "length": 3, "if": { "field": "flags", "equals": 7 } },
+        { "name": "count", "type": "uint", "bits": 8 },
+        {
+            "name": "entries",
+            "type": "array",
+            "count": "count",
+            "element": {
+                "type": "struct",
+                "fields": [
+                    { "name": "len", "type": "uint", "bits": 3 },
+                    { "name": "data", "type": "bytes", "length": "len" },
+                    { "name": "wide", "type": "uint", "bits": "width" }
+                ]
+            }
+        },
+        { "name": "name", "type": "string" }
+    ]
+}"#;
+
+fn write_data(flags: u8) -> Vec<u8> {
+    let mut data = Vec::new();
+    let mut stream = BitWriteStream::new(&mut data, BigEndian);
+    stream.write_int(flags, 4).unwrap();
+    stream.write_int(5u8, 4).unwrap();
+    if flags & 1 != 0 {
+        stream.write_int(-3i8, 5).unwrap();
+    }
+    if flags & 2 != 0 {
+        stream.write_float(1.5f32).unwrap();
+    }
+    if flags == 7 {
+        stream.write_string("abc", Some(3)).unwrap();
+    }
+    stream.write_int(2u8, 8).unwrap();
+    stream.write_int(1u8, 3).unwrap();
+    stream.write_bytes(&[0xAA]).unwrap();
+    stream.write_int(17u8, 5).unwrap();
+    stream.write_int(0u8, 3).unwrap();
+    stream.write_int(31u8, 5).unwrap();
+    stream.write_string("end", None).unwrap();
+    data
+}
+
+fn entry(data: &[u8], wide: i128) -> BitValue {
+    BitValue::Struct {
+        name: String::new(),
+        fields: vec![
+            ("len".to_string(), BitValue::Int(data.len() as i128)),
+            ("data".to_string(), BitValue::Bytes(data.to_vec())),
+            ("wide".to_string(), BitValue::Int(wide)),
+        ],
+    }
+}
+
+#[test]
+fn test_schema_conditions() {
+    let schema = serde_json::from_str::<Schema>(SCHEMA).unwrap();
+
+    let data = write_data(7);
+    let mut stream = BitReadStream::new(BitReadBuffer::new(&data, BigEndian));
+    let value = schema.read(&mut stream).unwrap();
+    assert_eq!(
+        value,
+        BitValue::Struct {
+            name: String::new(),
+            fields: vec![
+                ("flags".to_string(), BitValue::Int(7)),
+                ("width".to_string(), BitValue::Int(5)),
+                ("offset".to_string(), BitValue::Int(-3)),
+                ("scale".to_string(), BitValue::Float(1.5)),
+                ("label".to_string(), BitValue::Str("abc".to_string())),
+                ("count".to_string(), BitValue::Int(2)),
+                (
+                    "entries".to_string(),
+                    BitValue::List(vec![entry(&[0xAA], 17), entry(&[], 31)])
+                ),
+                ("name".to_string(), BitValue::Str("end".to_string())),
+            ],
+        }
+    );
+    assert!(stream.bits_left() < 8);
+
+    let data = write_data(2);
+    let mut stream = BitReadStream::new(BitReadBuffer::new(&data, BigEndian));
+    let value = schema.read(&mut stream).unwrap();
+    assert_eq!(value.get("offset"), None);
+    assert_eq!(value.get("scale"), Some(&BitValue::Float(1.5)));
+    assert_eq!(value.get("label"), None);
+    assert_eq!(value.get("name"), Some(&BitValue::Str("end".to_string())));
+}
+
+#[test]
+fn test_schema_errors() {
+    let schema = serde_json::from_str::<Schema>(SCHEMA).unwrap();
+    let data = write_data(7);
+
+    let mut stream = BitReadStream::new(BitReadBuffer::new(&data[..data.len() - 2], BigEndian));
+    assert!(matches!(
+        schema.read(&mut stream),
+        Err(BitError::NotEnoughData { .. })
+    ));
+    assert_eq!(stream.pos(), 0);
+
+    let mut stream = BitReadStream::new(BitReadBuffer::new(&data, BigEndian));
+    stream.set_limits(ReadLimits {
+        max_elements: 1,
+        ..ReadLimits::default()
+    });
+    assert!(matches!(
+        schema.read(&mut stream),
+        Err(BitError::LimitExceeded { .. })
+    ));
+    assert_eq!(stream.pos(), 0);
+
+    let schema = Schema {
+        fields: vec![Field {
+            name: "value".to_string(),
+            ty: FieldType::Uint {
+                bits: Size::Field("missing".to_string()),
+            },
+            condition: None,
+        }],
+    };
+    let mut stream = BitReadStream::new(BitReadBuffer::new(&data, LittleEndian));
+    assert!(matches!(
+        schema.read(&mut stream),
+        Err(BitError::UnresolvedField { name }) if name == "missing"
+    ));
+
+    let schema = Schema {
+        fields: vec![
+            Field {
+                name: "name".to_string(),
+                ty: FieldType::String {
+                    length: Some(Size::Fixed(1)),
+                },
+                condition: None,
+            },
+            Field {
+                name: "value".to_string(),
+                ty: FieldType::Bool,
+                condition: Some(Condition {
+                    field: "name".to_string(),
+                    equals: None,
+                    mask: None,
+                }),
+            },
+        ],
+    };
+    let mut stream = BitReadStream::new(BitReadBuffer::new(b"a", LittleEndian));
+    assert!(matches!(
+        schema.read(&mut stream),
+        Err(BitError::UnresolvedField { .. })
+    ));
+}
+
+#[test]
+fn test_schema_roundtrip_json() {
+    let schema = serde_json::from_str::<Schema>(SCHEMA).unwrap();
+    let json = serde_json::to_string(&schema).unwrap();
+    assert_eq!(serde_json::from_str::<Schema>(&json).unwrap(), schema);
+}
@@ -0,0 +1,128 @@
+use bitbuffer::scramble::{DescrambleStream, KeyStream, Prbs, RepeatingKey};
+use bitbuffer::{
+    BigEndian, BitRead, BitReadBuffer, BitReadStream, BitWrite, BitWriteStream, Endianness,
+    LittleEndian,
+};
+
+#[test]
+fn test_dvb_sequence() {
+    let mut prbs = Prbs::dvb();
+    let bytes: Vec<u8> = (0..8)
+        .map(|_| prbs.next_bits::<BigEndian>(8) as u8)
+        .collect();
+    assert_eq!(bytes, vec![0x03, 0xF6, 0x08, 0x34, 0x30, 0xB8, 0xA3, 0x93]);
+}
+
+#[test]
+fn test_prbs_period() {
+    let mut prbs = Prbs::prbs7();
+    let start: Vec<bool> = (0..20).map(|_| prbs.next_bit()).collect();
+    for _ in 20..127 {
+        prbs.next_bit();
+    }
+    let repeated: Vec<bool> = (0..20).map(|_| prbs.next_bit()).collect();
+    assert_eq!(start, repeated);
+}
+
+#[test]
+fn test_repeating_key_matches_bytes() {
+    let key = vec![0x12, 0x34, 0x56];
+    let input = b"hello world";
+    let expected: Vec<u8> = input
+        .iter()
+        .zip(key.iter().cycle())
+        .map(|(byte, key)| byte ^ key)
+        .collect();
+
+    let mut data = Vec::new();
+    let mut stream = BitWriteStream::new(&mut data, LittleEndian);
+    stream.attach_scrambler(RepeatingKey::new(key.clone(), LittleEndian));
+    stream.write_bytes(input).unwrap();
+    assert_eq!(data, expected);
+
+    let mut data = Vec::new();
+    let mut stream = BitWriteStream::new(&mut data, BigEndian);
+    stream.attach_scrambler(RepeatingKey::new(key, BigEndian));
+    stream.write_bytes(input).unwrap();
+    assert_eq!(data, expected);
+}
+
+#[derive(BitRead, BitWrite, Debug, PartialEq)]
+struct Packet {
+    #[size = 3]
+    kind: u8,
+    flag: bool,
+    #[size = 11]
+    signed: i16,
+    name: String,
+    big: u128,
+}
+
+fn packet() -> Packet {
+    Packet {
+        kind: 5,
+        flag: true,
+        signed: -300,
+        name: "foobar".to_string(),
+        big: 0x1234_5678_9ABC_DEF0_1122_3344_5566_7788,
+    }
+}
+
+fn test_roundtrip<E: Endianness>(endianness: E) {
+    let mut data = Vec::new();
+    let mut stream = BitWriteStream::new(&mut data, endianness);
+    stream.attach_scrambler(Prbs::prbs9());
+    stream.write_int(-3i8, 5).unwrap();
+    stream.write(&packet()).unwrap();
+    stream.write_bool(false).unwrap();
+    stream.write_int(0x1_2345_6789_ABCD_EF01_u128, 77).unwrap();
+    stream.write_bytes(&[1, 2, 3]).unwrap();
+    stream.detach_scrambler();
+    stream.write_int(0xABu8, 8).unwrap();
+    let bit_len = stream.bit_len();
+
+    let raw = BitReadStream::new(BitReadBuffer::new(&data, E::endianness()));
+
+    let mut stream = DescrambleStream::new(raw, Prbs::prbs9());
+    assert_eq!(stream.read_int::<i8>(5).unwrap(), -3);
+    let packet_bits = bit_len - 5 - 1 - 77 - 24 - 8;
+    let mut packet_stream = stream.read_bits(packet_bits).unwrap();
+    assert_eq!(packet_stream.read::<Packet>().unwrap(), packet());
+    assert!(!stream.read_bool().unwrap());
+    assert_eq!(
+        stream.read_int::<u128>(77).unwrap(),
+        0x1_2345_6789_ABCD_EF01_u128
+    );
+    assert_eq!(stream.read_bytes(3).unwrap(), vec![1, 2, 3]);
+
+    let (mut raw, _) = stream.into_inner();
+    assert_eq!(raw.read_int::<u8>(8).unwrap(), 0xAB);
+}
+
+#[test]
+fn test_scramble_roundtrip() {
+    test_roundtrip(LittleEndian);
+    test_roundtrip(BigEndian);
+}
+
+#[test]
+fn test_descramble_failed_read_keeps_key() {
+    let data = [0xFF];
+    let stream = BitReadStream::new(BitReadBuffer::new(&data, BigEndian));
+    let mut stream = DescrambleStream::new(stream, RepeatingKey::new(vec![0x0F], BigEndian));
+    assert!(stream.read_int::<u16>(9).is_err());
+    assert!(stream.read_int::<u8>(9).is_err());
+    assert_eq!(stream.read_int::<u8>(8).unwrap(), 0xF0);
+}
+
+#[test]
+fn test_scramble_reserve_length() {
+    let mut data = Vec::new();
+    let mut stream = BitWriteStream::new(&mut data, LittleEndian);
+    stream.attach_scrambler(RepeatingKey::new(vec![0xA5], LittleEndian));
+    stream
+        .reserve_length(8, |stream| stream.write_bytes(b"abc"))
+        .unwrap();
+
+    assert_eq!(data, vec![24 ^ 0xA5, b'a' ^ 0xA5, b'b' ^ 0xA5, b'c' ^ 0xA5]);
+}
@@ -0,0 +1,255 @@
+#![cfg(feature = "serde")]
+
+use bitbuffer::serde::{
+    deserialize, serialize, Config, Deserializer, IntEncoding, Serializer, StringEncoding,
+};
+use bitbuffer::{
+    BigEndian, BitError, BitReadBuffer, BitReadStream, BitWriteStream, LittleEndian, ReadLimit,
+    ReadLimits,
+};
+use maplit::hashmap;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt::Debug;
+
+fn roundtrip<T: Serialize + DeserializeOwned + Debug + PartialEq>(val: T) {
+    {
+        let mut data = Vec::new();
+        let mut stream = BitWriteStream::new(&mut data, LittleEndian);
+        serialize(&val, &mut stream).unwrap();
+        let size = stream.bit_len();
+        let mut read = BitReadStream::new(BitReadBuffer::new_owned(data, LittleEndian));
+        assert_eq!(val, deserialize::<T, _>(&mut read).unwrap());
+        assert_eq!(size, read.pos());
+    }
+    {
+        let mut data = Vec::new();
+        let mut stream = BitWriteStream::new(&mut data, BigEndian);
+        serialize(&val, &mut stream).unwrap();
+        let size = stream.bit_len();
+        let mut read = BitReadStream::new(BitReadBuffer::new_owned(data, BigEndian));
+        assert_eq!(val, deserialize::<T, _>(&mut read).unwrap());
+        assert_eq!(size, read.pos());
+    }
+}
+
+#[test]
+fn test_serde_primitives() {
+    roundtrip(12u8);
+    roundtrip(-12i64);
+    roundtrip(1.5f32);
+    roundtrip(true);
+    roundtrip('ü');
+    roundtrip(String::from("foobar"));
+    roundtrip(Some(12u16));
+    roundtrip(None::<u16>);
+    roundtrip((1u8, false, String::from("asd")));
+}
+
+#[test]
+fn test_serde_struct() {
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct Inner(u8, bool);
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct Foo {
+        int: u32,
+        float: f64,
+        string: String,
+        list: Vec<Inner>,
+        map: HashMap<u8, String>,
+        optional: Option<i16>,
+    }
+
+    roundtrip(Foo {
+        int: 1234,
+        float: 10.2,
+        string: "foobar".to_string(),
+        list: vec![Inner(1, true), Inner(2, false)],
+        map: hashmap! {1 => "one".to_string(), 2 => "two".to_string()},
+        optional: Some(-3),
+    });
+}
+
+#[test]
+fn test_serde_enum() {
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    enum Enum {
+        A,
+        B(String),
+        C(u8, u8),
+        D { x: f32, y: f32 },
+    }
+
+    roundtrip(Enum::A);
+    roundtrip(Enum::B("foobar".to_string()));
+    roundtrip(Enum::C(1, 2));
+    roundtrip(Enum::D { x: 1.0, y: -1.0 });
+}
+
+#[test]
+fn test_serde_bit_layout() {
+    let mut data = Vec::new();
+    let mut stream = BitWriteStream::new(&mut data, LittleEndian);
+    serialize(&(true, Some(false), 3u8), &mut stream).unwrap();
+    assert_eq!(stream.bit_len(), 11);
+    assert_eq!(data, vec![0b0001_1011, 0]);
+}
+
+#[test]
+fn test_serde_config() {
+    let config = Config {
+        length_bits: 8,
+        variant_bits: 2,
+        string_encoding: StringEncoding::LengthPrefixed,
+        int_encoding: IntEncoding::Fixed,
+    };
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    enum Enum {
+        A(String),
+        B(Vec<u8>),
+    }
+
+    let value = vec![Enum::A("foo".to_string()), Enum::B(vec![1, 2])];
+
+    let mut data = Vec::new();
+    let mut stream = BitWriteStream::new(&mut data, LittleEndian);
+    value
+        .serialize(&mut Serializer::new(&mut stream, config))
+        .unwrap();
+    assert_eq!(stream.bit_len(), 8 + 2 + 8 + 24 + 2 + 8 + 16);
+
+    let mut read = BitReadStream::new(BitReadBuffer::new(&data, LittleEndian));
+    let result = Vec::<Enum>::deserialize(&mut Deserializer::new(&mut read, config)).unwrap();
+    assert_eq!(value, result);
+}
+
+fn roundtrip_config<T: Serialize + DeserializeOwned + Debug + PartialEq>(
+    val: T,
+    config: Config,
+) -> usize {
+    let mut data = Vec::new();
+    let mut stream = BitWriteStream::new(&mut data, BigEndian);
+    val.serialize(&mut Serializer::new(&mut stream, config))
+        .unwrap();
+    let size = stream.bit_len();
+    let mut read = BitReadStream::new(BitReadBuffer::new(&data, BigEndian));
+    assert_eq!(
+        val,
+        T::deserialize(&mut Deserializer::new(&mut read, config)).unwrap()
+    );
+    assert_eq!(size, read.pos());
+    size
+}
+
+#[test]
+fn test_serde_varint() {
+    let config = Config {
+        int_encoding: IntEncoding::Varint,
+        ..Config::default()
+    };
+
+    assert_eq!(roundtrip_config(12u32, config), 8);
+    assert_eq!(roundtrip_config(300u16, config), 16);
+    assert_eq!(roundtrip_config(u64::MAX, config), 72);
+    assert_eq!(roundtrip_config(-1i32, config), 8);
+    assert_eq!(roundtrip_config(-64i64, config), 8);
+    assert_eq!(roundtrip_config(64i64, config), 16);
+    assert_eq!(roundtrip_config(i64::MIN, config), 72);
+    assert_eq!(roundtrip_config(i16::MAX, config), 24);
+    // 8 and 128 bit integers keep their full width
+    assert_eq!(roundtrip_config(200u8, config), 8);
+    assert_eq!(roundtrip_config(-3i128, config), 128);
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct Foo {
+        a: u32,
+        b: i64,
+        c: Vec<u16>,
+    }
+    assert_eq!(
+        roundtrip_config(
+            Foo {
+                a: 1,
+                b: -2,
+                c: vec![3, 4]
+            },
+            config
+        ),
+        8 + 8 + 32 + 8 + 8
+    );
+
+    // values that don't fit in the type being read are rejected
+    let mut data = Vec::new();
+    let mut stream = BitWriteStream::new(&mut data, BigEndian);
+    70_000u32
+        .serialize(&mut Serializer::new(&mut stream, config))
+        .unwrap();
+    let mut read = BitReadStream::new(BitReadBuffer::new(&data, BigEndian));
+    assert!(matches!(
+        u16::deserialize(&mut Deserializer::new(&mut read, config)),
+        Err(bitbuffer::serde::Error::Message(_))
+    ));
+}
+
+#[test]
+fn test_serde_length_limit() {
+    let mut data = Vec::new();
+    let mut stream = BitWriteStream::new(&mut data, LittleEndian);
+    serialize(&vec![1u8; 10], &mut stream).unwrap();
+    serialize(&hashmap! {1u8 => 2u8, 3 => 4, 5 => 6}, &mut stream).unwrap();
+
+    let mut read = BitReadStream::new(BitReadBuffer::new(&data, LittleEndian));
+    read.set_limits(ReadLimits {
+        max_elements: 2,
+        ..ReadLimits::default()
+    });
+    assert!(matches!(
+        deserialize::<Vec<u8>, _>(&mut read),
+        Err(bitbuffer::serde::Error::Bit(BitError::LimitExceeded {
+            limit: ReadLimit::Elements,
+            requested: 10,
+            max: 2
+        }))
+    ));
+
+    read.set_pos(32 + 80).unwrap();
+    assert!(matches!(
+        deserialize::<HashMap<u8, u8>, _>(&mut read),
+        Err(bitbuffer::serde::Error::Bit(BitError::LimitExceeded {
+            limit: ReadLimit::Elements,
+            requested: 3,
+            max: 2
+        }))
+    ));
+}
+
+#[test]
+fn test_serde_borrowed() {
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct Borrowed<'a> {
+        name: &'a str,
+    }
+
+    let mut data = Vec::new();
+    let mut stream = BitWriteStream::new(&mut data, LittleEndian);
+    serialize(&Borrowed { name: "foo" }, &mut stream).unwrap();
+
+    let mut read = BitReadStream::new(BitReadBuffer::new(&data, LittleEndian));
+    let result: Borrowed = deserialize(&mut read).unwrap();
+    assert_eq!(result.name, "foo");
+}
+
+#[test]
+fn test_serde_not_enough_data() {
+    let data = vec![1, 2];
+    let mut read = BitReadStream::new(BitReadBuffer::new(&data, LittleEndian));
+    assert!(matches!(
+        deserialize::<u32, _>(&mut read),
+        Err(bitbuffer::serde::Error::Bit(
+            bitbuffer::BitError::NotEnoughData { .. }
+        ))
+    ));
+}
@@ -0,0 +1,77 @@
+use bitbuffer::crc::{Crc, CRC_32};
+use bitbuffer::scramble::Prbs;
+use bitbuffer::{
+    BigEndian, BitReadBuffer, BitReadStream, BitWriteStream, LittleEndian, SharedBitReadBuffer,
+};
+use std::sync::Arc;
+use std::thread;
+
+fn assert_send_sync<T: Send + Sync>() {}
+
+fn assert_send<T: Send>(_: &T) {}
+
+#[test]
+fn test_read_types_are_send_sync() {
+    assert_send_sync::<BitReadBuffer<'static, LittleEndian>>();
+    assert_send_sync::<BitReadStream<'static, BigEndian>>();
+    assert_send_sync::<SharedBitReadBuffer<LittleEndian>>();
+}
+
+#[test]
+fn test_write_stream_is_send() {
+    let mut data = Vec::new();
+    let mut stream = BitWriteStream::new(&mut data, BigEndian);
+    stream.attach_hasher(Crc::new(&CRC_32));
+    stream.attach_scrambler(Prbs::dvb());
+    assert_send(&stream);
+
+    thread::scope(|scope| {
+        scope
+            .spawn(move || stream.write_bytes(&[0, 0, 0]))
+            .join()
+            .unwrap()
+            .unwrap();
+    });
+    assert_eq!(data, [0x03, 0xF6, 0x08]);
+}
+
+#[test]
+fn test_shared_buffer() {
+    let bytes: Arc<[u8]> = Arc::from(vec![0x12, 0x34, 0x56, 0x78]);
+    let buffer = SharedBitReadBuffer::new(Arc::clone(&bytes), BigEndian);
+    assert_eq!(buffer.bit_len(), 32);
+
+    let handles: Vec<_> = (0..4)
+        .map(|index| {
+            let buffer = buffer.clone();
+            thread::spawn(move || {
+                let mut stream = buffer.stream();
+                stream.skip_bits(index * 8)?;
+                stream.read_int::<u32>(32 - index * 8)
+            })
+        })
+        .collect();
+    let values: Vec<u32> = handles
+        .into_iter()
+        .map(|handle| handle.join().unwrap().unwrap())
+        .collect();
+    assert_eq!(values, [0x1234_5678, 0x34_5678, 0x5678, 0x78]);
+
+    // the data is shared, not copied
+    assert_eq!(buffer.stream().data().as_ptr(), bytes.as_ptr());
+    assert_eq!(
+        buffer.clone().into_inner(),
+        BitReadBuffer::new(&bytes, BigEndian)
+    );
+}
+
+#[test]
+fn test_shared_buffer_from_buffer() {
+    let bytes = vec![0x12, 0x34, 0x56];
+    let bounded = BitReadBuffer::with_bit_len(&bytes, 12, LittleEndian).unwrap();
+    let shared = SharedBitReadBuffer::from(bounded);
+    drop(bytes);
+    assert_eq!(shared.bit_len(), 12);
+    assert_eq!(shared.stream().read_int::<u16>(12).unwrap(), 0x412);
+    assert!(shared.read_int::<u16>(0, 13).is_err());
+}
@@ -0,0 +1,80 @@
+use bitbuffer::source::{
+    read_bit_coord, read_bit_coord_mp, read_bit_normal, write_bit_coord, write_bit_coord_mp,
+    write_bit_normal, BitCoordMp, CoordPrecision,
+};
+use bitbuffer::{BitReadBuffer, BitReadStream, BitWriteStream, LittleEndian};
+
+fn encode<F: FnOnce(&mut BitWriteStream<LittleEndian>)>(f: F) -> (Vec<u8>, usize) {
+    let mut data = Vec::new();
+    let mut stream = BitWriteStream::new(&mut data, LittleEndian);
+    f(&mut stream);
+    let len = stream.bit_len();
+    (data, len)
+}
+
+#[test]
+fn test_bit_coord() {
+    for value in [0.0, 1.0, -1.0, 0.03125, -0.03125, 1.5, -1234.875, 16383.0] {
+        let (data, len) = encode(|stream| write_bit_coord(stream, value).unwrap());
+        let mut stream = BitReadStream::new(BitReadBuffer::new(&data, LittleEndian));
+        assert_eq!(read_bit_coord(&mut stream).unwrap(), value);
+        assert_eq!(stream.pos(), len);
+    }
+
+    let (data, len) = encode(|stream| write_bit_coord(stream, 0.0).unwrap());
+    assert_eq!(len, 2);
+    assert_eq!(data, [0]);
+
+    // no integer part, fraction of 1, sign
+    let (data, len) = encode(|stream| write_bit_coord(stream, -0.04).unwrap());
+    assert_eq!(len, 8);
+    assert_eq!(data, [0b0000_1110]);
+
+    // values are truncated to the resolution
+    let (data, _) = encode(|stream| write_bit_coord(stream, 2.99).unwrap());
+    let mut stream = BitReadStream::new(BitReadBuffer::new(&data, LittleEndian));
+    assert_eq!(read_bit_coord(&mut stream).unwrap(), 2.96875);
+}
+
+#[test]
+fn test_bit_coord_mp() {
+    let cases = [
+        (CoordPrecision::Full, 5.25, 5.25, 19),
+        (CoordPrecision::Full, -3000.5, -3000.5, 22),
+        (CoordPrecision::Full, 0.0, 0.0, 8),
+        (CoordPrecision::Low, 5.25, 5.25, 17),
+        (CoordPrecision::Low, -5.3, -5.25, 17),
+        (CoordPrecision::Integral, 5.75, 5.0, 14),
+        (CoordPrecision::Integral, -3000.0, -3000.0, 17),
+        (CoordPrecision::Integral, 0.5, 0.0, 2),
+    ];
+    for (precision, value, expected, bits) in cases {
+        let (data, len) = encode(|stream| write_bit_coord_mp(stream, value, precision).unwrap());
+        assert_eq!(len, bits, "{:?} {}", precision, value);
+        let mut stream = BitReadStream::new(BitReadBuffer::new(&data, LittleEndian));
+        assert_eq!(read_bit_coord_mp(&mut stream, precision).unwrap(), expected);
+        assert_eq!(stream.pos(), len);
+    }
+
+    assert_eq!(
+        BitCoordMp::<true, true>::PRECISION,
+        CoordPrecision::Integral
+    );
+    assert_eq!(BitCoordMp::<false, true>::PRECISION, CoordPrecision::Low);
+    assert_eq!(BitCoordMp::<false, false>::PRECISION, CoordPrecision::Full);
+}
+
+#[test]
+fn test_bit_normal() {
+    for (value, expected) in [(1.0, 1.0), (-1.0, -1.0), (0.0, 0.0), (1.5, 1.0)] {
+        let (data, len) = encode(|stream| write_bit_normal(stream, value).unwrap());
+        assert_eq!(len, 12);
+        let mut stream = BitReadStream::new(BitReadBuffer::new(&data, LittleEndian));
+        assert_eq!(read_bit_normal(&mut stream).unwrap(), expected);
+    }
+
+    let (data, _) = encode(|stream| write_bit_normal(stream, 0.5).unwrap());
+    let mut stream = BitReadStream::new(BitReadBuffer::new(&data, LittleEndian));
+    let value = read_bit_normal(&mut stream).unwrap();
+    assert!((value - 0.5).abs() < 1.0 / 2047.0);
+}
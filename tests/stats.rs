@@ -0,0 +1,74 @@
+#![cfg(feature = "stats")]
+#![allow(dead_code)]
+
+use bitbuffer::stats::collect;
+use bitbuffer::{BitRead, BitReadBuffer, BitReadStream, LittleEndian};
+
+#[derive(BitRead)]
+struct Inner {
+    flag: bool,
+    value: u16,
+}
+
+#[derive(BitRead)]
+struct Outer {
+    #[size = 3]
+    inner: Vec<Inner>,
+    tail: u8,
+}
+
+#[test]
+fn test_collect_stats() {
+    let bytes = [0xFF; 8];
+    let (result, stats) = collect(|| {
+        let mut stream = BitReadStream::new(BitReadBuffer::new(&bytes, LittleEndian));
+        let outer: Outer = stream.read()?;
+        // failed reads are counted, without bits
+        assert!(stream.read::<Outer>().is_err());
+        Ok::<_, bitbuffer::BitError>(outer)
+    });
+    assert_eq!(result.unwrap().tail, 0xFF);
+
+    let outer = stats.get::<Outer>();
+    assert_eq!(outer.reads, 2);
+    assert_eq!(outer.bits, 3 * 17 + 8);
+    // the size of `Outer` is known up front, so the failed read doesn't read any fields
+    let inner = stats.get::<Inner>();
+    assert_eq!(inner.reads, 3);
+    assert_eq!(inner.bits, 3 * 17);
+    assert_eq!(stats.get::<u16>().reads, 3);
+    assert_eq!(stats.get::<u16>().bits, 3 * 16);
+    assert_eq!(stats.get::<bool>().bits, 3);
+    assert_eq!(stats.get::<u32>().reads, 0);
+
+    let by_type = stats.by_type();
+    assert_eq!(by_type.len(), 6);
+    // the outer type includes the time of its fields
+    assert!(by_type.iter().all(|(_, stats)| stats.time <= outer.time));
+
+    let read_site = stats
+        .sites()
+        .find(|(site, _)| site.type_name == std::any::type_name::<Outer>())
+        .unwrap()
+        .0;
+    assert_eq!(read_site.location.file(), file!());
+
+    assert!(stats.to_string().contains("Inner"));
+}
+
+#[test]
+fn test_no_collection_outside() {
+    let bytes = [0xFF; 8];
+    let mut stream = BitReadStream::new(BitReadBuffer::new(&bytes, LittleEndian));
+    let _: u8 = stream.read().unwrap();
+    let (_, stats) = collect(|| ());
+    assert_eq!(stats.sites().count(), 0);
+
+    let (_, outer) = collect(|| {
+        let (_, inner) = collect(|| stream.read::<u8>());
+        assert_eq!(inner.get::<u8>().reads, 1);
+        stream.read::<u16>()
+    });
+    assert_eq!(outer.get::<u8>().reads, 0);
+    assert_eq!(outer.get::<u16>().reads, 1);
+}
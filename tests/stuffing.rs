@@ -0,0 +1,138 @@
+use bitbuffer::stuffing::{read_hdlc_frame, skip_to_flag, write_hdlc_frame, BitStuffing};
+use bitbuffer::{
+    BigEndian, BitError, BitReadBuffer, BitReadStream, BitWriteStream, Endianness, LittleEndian,
+};
+
+fn stuffed_bits(rules: BitStuffing, input: &[u8]) -> Vec<bool> {
+    let input = BitReadStream::new(BitReadBuffer::new(input, BigEndian));
+    let mut data = Vec::new();
+    let mut stream = BitWriteStream::new(&mut data, BigEndian);
+    rules.stuff(&input, &mut stream).unwrap();
+    let len = stream.bit_len();
+    let mut read = BitReadStream::new(BitReadBuffer::new(&data, BigEndian));
+    (0..len).map(|_| read.read_bool().unwrap()).collect()
+}
+
+fn bits(s: &str) -> Vec<bool> {
+    s.chars().map(|c| c == '1').collect()
+}
+
+#[test]
+fn test_stuff_hdlc() {
+    assert_eq!(
+        stuffed_bits(BitStuffing::HDLC, &[0b0111_1110]),
+        bits("011111010")
+    );
+    assert_eq!(
+        stuffed_bits(BitStuffing::HDLC, &[0xFF, 0xFF]),
+        bits("1111101111101111101")
+    );
+    assert_eq!(stuffed_bits(BitStuffing::HDLC, &[0x00]), bits("00000000"));
+}
+
+#[test]
+fn test_stuff_can() {
+    assert_eq!(stuffed_bits(BitStuffing::CAN, &[0x00]), bits("000001000"));
+    // the stuffed bit starts a new run
+    assert_eq!(
+        stuffed_bits(BitStuffing::CAN, &[0b0000_0111, 0b1100_0000]),
+        bits("0000011111010000010")
+    );
+}
+
+fn test_unstuff_roundtrip<E: Endianness>(rules: BitStuffing, endianness: E) {
+    let input = [0xFF, 0x00, 0x7E, 0xF8, 0x1F, 0x55];
+    let mut data = Vec::new();
+    let mut stream = BitWriteStream::new(&mut data, E::endianness());
+    rules
+        .stuff(
+            &BitReadStream::new(BitReadBuffer::new(&input, E::endianness())),
+            &mut stream,
+        )
+        .unwrap();
+    stream.write_int(0b101u8, 3).unwrap();
+
+    let mut read = BitReadStream::new(BitReadBuffer::new(&data, endianness));
+    let mut unstuffed = rules.unstuff(&mut read, 48).unwrap();
+    assert_eq!(unstuffed.read_bytes(6).unwrap().as_ref(), &input);
+    assert_eq!(read.read_int::<u8>(3).unwrap(), 0b101);
+}
+
+#[test]
+fn test_unstuff() {
+    test_unstuff_roundtrip(BitStuffing::HDLC, LittleEndian);
+    test_unstuff_roundtrip(BitStuffing::HDLC, BigEndian);
+    test_unstuff_roundtrip(BitStuffing::CAN, LittleEndian);
+    test_unstuff_roundtrip(BitStuffing::CAN, BigEndian);
+}
+
+#[test]
+fn test_unstuff_invalid() {
+    let data = [0b0011_1111, 0b0000_0000];
+    let mut read = BitReadStream::new(BitReadBuffer::new(&data, BigEndian));
+    read.skip_bits(2).unwrap();
+    assert!(matches!(
+        BitStuffing::HDLC.unstuff(&mut read, 7),
+        Err(BitError::InvalidBitStuffing { pos: 7 })
+    ));
+    assert_eq!(read.pos(), 2);
+}
+
+#[test]
+fn test_unstuff_offset() {
+    let data = [0b1011_1110, 0b1000_0000];
+    let mut read = BitReadStream::new(BitReadBuffer::new(&data, BigEndian));
+    read.skip_bits(2).unwrap();
+    let mut unstuffed = BitStuffing::HDLC.unstuff(&mut read, 6).unwrap();
+    assert_eq!(unstuffed.read_int::<u8>(6).unwrap(), 0b11_1111);
+    assert_eq!(read.pos(), 9);
+}
+
+fn test_frames<E: Endianness>(endianness: E) {
+    let frames: Vec<Vec<u8>> = vec![vec![0xFF, 0x7E, 0x12], vec![], vec![0x3F, 0xFC]];
+
+    let mut data = Vec::new();
+    let mut stream = BitWriteStream::new(&mut data, E::endianness());
+    // some noise before the first frame
+    stream.write_int(0b1011u8, 4).unwrap();
+    for frame in &frames {
+        let payload = BitReadStream::new(BitReadBuffer::new(frame, E::endianness()));
+        write_hdlc_frame(&mut stream, &payload).unwrap();
+    }
+
+    let mut read = BitReadStream::new(BitReadBuffer::new(&data, endianness));
+    for frame in frames.iter().filter(|frame| !frame.is_empty()) {
+        let mut result = read_hdlc_frame(&mut read).unwrap();
+        assert_eq!(result.bit_len(), frame.len() * 8);
+        assert_eq!(result.read_bytes(frame.len()).unwrap().as_ref(), &frame[..]);
+    }
+    let pos = read.pos();
+    assert!(read_hdlc_frame(&mut read).is_err());
+    assert_eq!(read.pos(), pos);
+}
+
+#[test]
+fn test_hdlc_frames() {
+    test_frames(LittleEndian);
+    test_frames(BigEndian);
+}
+
+#[test]
+fn test_hdlc_abort() {
+    let data = [0b0111_1110, 0b1111_1110, 0b0111_1110];
+    let mut read = BitReadStream::new(BitReadBuffer::new(&data, BigEndian));
+    assert!(matches!(
+        read_hdlc_frame(&mut read),
+        Err(BitError::InvalidBitStuffing { .. })
+    ));
+}
+
+#[test]
+fn test_skip_to_flag() {
+    let data = [0b1010_0111, 0b1110_1100];
+    let mut read = BitReadStream::new(BitReadBuffer::new(&data, BigEndian));
+    skip_to_flag(&mut read).unwrap();
+    assert_eq!(read.pos(), 12);
+    assert!(skip_to_flag(&mut read).is_err());
+    assert_eq!(read.pos(), 12);
+}
@@ -0,0 +1,83 @@
+#![cfg(feature = "tokio")]
+
+use bitbuffer::tokio::BitCodec;
+use bitbuffer::{BigEndian, BitError, BitRead, BitWrite, LittleEndian};
+use bytes::BytesMut;
+use futures::{SinkExt, StreamExt};
+use tokio_util::codec::{Decoder, Encoder, FramedRead, FramedWrite};
+
+#[derive(BitRead, BitWrite, Debug, PartialEq, Clone)]
+struct Message {
+    #[size = 5]
+    kind: u8,
+    values: [u8; 7],
+}
+
+fn messages() -> Vec<Message> {
+    (0..20)
+        .map(|i| Message {
+            kind: i % 32,
+            values: std::array::from_fn(|j| i * 3 + j as u8),
+        })
+        .collect()
+}
+
+#[test]
+fn test_codec_bit_length() {
+    // a 12 bit length counting bits, frames aren't a whole number of bytes
+    let mut codec = BitCodec::<Message, _>::new(12, 1, BigEndian);
+    let mut buffer = BytesMut::new();
+    for message in messages() {
+        codec.encode(message, &mut buffer).unwrap();
+    }
+    // 12 bit length + 61 bit message, padded to 10 bytes
+    assert_eq!(buffer.len(), 20 * 10);
+    assert_eq!(&buffer[..2], [0x03, 0xD0]);
+
+    let mut decoded = Vec::new();
+    let mut input = BytesMut::new();
+    for byte in buffer.iter() {
+        input.extend_from_slice(&[*byte]);
+        while let Some(message) = codec.decode(&mut input).unwrap() {
+            decoded.push(message);
+        }
+    }
+    assert_eq!(decoded, messages());
+    assert!(input.is_empty());
+}
+
+#[test]
+fn test_codec_limits() {
+    let mut codec = BitCodec::<Message, _>::new(4, 8, LittleEndian);
+    let mut buffer = BytesMut::new();
+    assert!(matches!(
+        codec.encode(messages()[0].clone(), &mut buffer),
+        Ok(())
+    ));
+    assert_eq!(buffer.len(), 9);
+
+    let mut codec = BitCodec::<Message, _>::new(3, 8, LittleEndian);
+    assert!(matches!(
+        codec.encode(messages()[0].clone(), &mut buffer),
+        Err(BitError::Io(_))
+    ));
+
+    let mut codec = BitCodec::<Message, _>::new(8, 8, LittleEndian).with_max_frame_length(4);
+    let mut input = BytesMut::from(&[8u8, 0, 0][..]);
+    assert!(matches!(codec.decode(&mut input), Err(BitError::Io(_))));
+}
+
+#[tokio::test]
+async fn test_framed() {
+    let (client, server) = tokio::io::duplex(16);
+    let writer = tokio::spawn(async move {
+        let mut sink = FramedWrite::new(client, BitCodec::new(16, 8, LittleEndian));
+        for message in messages() {
+            sink.send(message).await.unwrap();
+        }
+    });
+    let stream = FramedRead::new(server, BitCodec::<Message, _>::new(16, 8, LittleEndian));
+    let received: Vec<Message> = stream.map(|message| message.unwrap()).collect().await;
+    writer.await.unwrap();
+    assert_eq!(received, messages());
+}
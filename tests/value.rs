@@ -0,0 +1,144 @@
+#![allow(dead_code)]
+#![allow(unreachable_patterns)]
+
+use bitbuffer::{
+    BigEndian, BitError, BitRead, BitReadBuffer, BitReadStream, BitValue, BitWriteStream,
+    LayoutDescribe, LittleEndian, ReadLimits,
+};
+
+#[derive(BitRead, LayoutDescribe, Debug, PartialEq)]
+struct Packet {
+    #[size = 4]
+    version: u8,
+    signed: i16,
+    flag: bool,
+    #[size_bits = 3]
+    count: u8,
+    #[size = "count"]
+    items: Vec<u16>,
+    #[size = 2]
+    raw: Vec<u8>,
+    name: String,
+    scale: f32,
+}
+
+#[derive(BitRead, LayoutDescribe, Debug, PartialEq)]
+#[discriminant_bits = 2]
+enum Kind {
+    Empty,
+    #[size = 5]
+    Value(u8),
+    #[discriminant = "_"]
+    Other,
+}
+
+#[derive(BitRead, LayoutDescribe)]
+struct Nested {
+    packet: Packet,
+}
+
+fn packet_data() -> Vec<u8> {
+    let mut data = Vec::new();
+    let mut stream = BitWriteStream::new(&mut data, LittleEndian);
+    stream.write_int(3u8, 4).unwrap();
+    stream.write_int(-300i16, 16).unwrap();
+    stream.write_bool(true).unwrap();
+    stream.write_int(2u8, 3).unwrap();
+    stream.write_int(2u8, 2).unwrap();
+    stream.write_int(0x1234u16, 16).unwrap();
+    stream.write_int(0xABCDu16, 16).unwrap();
+    stream.write_bytes(&[0xDE, 0xAD]).unwrap();
+    stream.write_string("packet", None).unwrap();
+    stream.write_float(0.5f32).unwrap();
+    data
+}
+
+#[test]
+fn test_read_dynamic() {
+    let data = packet_data();
+    let mut stream = BitReadStream::new(BitReadBuffer::new(&data, LittleEndian));
+    let value = stream.read_dynamic(&Packet::layout()).unwrap();
+    let dynamic_pos = stream.pos();
+    stream.set_pos(0).unwrap();
+    stream.read::<Packet>().unwrap();
+    assert_eq!(dynamic_pos, stream.pos());
+
+    assert_eq!(
+        value,
+        BitValue::Struct {
+            name: "Packet".to_string(),
+            fields: vec![
+                ("version".to_string(), BitValue::Int(3)),
+                ("signed".to_string(), BitValue::Int(-300)),
+                ("flag".to_string(), BitValue::Bool(true)),
+                ("count".to_string(), BitValue::Int(2)),
+                (
+                    "items".to_string(),
+                    BitValue::List(vec![BitValue::Int(0x1234), BitValue::Int(0xABCD)])
+                ),
+                ("raw".to_string(), BitValue::Bytes(vec![0xDE, 0xAD])),
+                ("name".to_string(), BitValue::Str("packet".to_string())),
+                ("scale".to_string(), BitValue::Float(0.5)),
+            ],
+        }
+    );
+    assert_eq!(
+        value.to_string(),
+        "Packet { version: 3, signed: -300, flag: true, count: 2, items: [4660, 43981], raw: 0xdead, \
+        name: \"packet\", scale: 0.5 }"
+    );
+}
+
+#[test]
+fn test_read_dynamic_errors() {
+    // nested types can't be resolved by name
+    let data = packet_data();
+    let mut stream = BitReadStream::new(BitReadBuffer::new(&data, LittleEndian));
+    assert!(matches!(
+        stream.read_dynamic(&Nested::layout()),
+        Err(BitError::UnsupportedType { type_name }) if type_name == "Packet"
+    ));
+
+    let mut stream = BitReadStream::new(BitReadBuffer::new(&data[..10], LittleEndian));
+    assert!(matches!(
+        stream.read_dynamic(&Packet::layout()),
+        Err(BitError::NotEnoughData { .. })
+    ));
+    assert_eq!(stream.pos(), 0);
+
+    let mut stream = BitReadStream::new(BitReadBuffer::new(&data, LittleEndian));
+    stream.set_limits(ReadLimits {
+        max_elements: 1,
+        ..ReadLimits::default()
+    });
+    assert!(matches!(
+        stream.read_dynamic(&Packet::layout()),
+        Err(BitError::LimitExceeded { .. })
+    ));
+    assert_eq!(stream.pos(), 0);
+
+    let mut stream = BitReadStream::new(BitReadBuffer::new(&[0b0110_1000], BigEndian));
+    let mut short = stream.read_bits(4).unwrap();
+    assert!(matches!(
+        short.read_dynamic(&Kind::layout()),
+        Err(BitError::NotEnoughData { .. })
+    ));
+}
+
+#[test]
+fn test_read_dynamic_enum() {
+    let mut stream = BitReadStream::new(BitReadBuffer::new(&[0b0110_1011, 0b1000_0000], BigEndian));
+    assert_eq!(
+        stream.read_dynamic(&Kind::layout()).unwrap(),
+        BitValue::Enum {
+            variant: "Value".to_string(),
+            value: Some(Box::new(BitValue::Int(21))),
+        }
+    );
+    assert_eq!(
+        stream.read_dynamic(&Kind::layout()).unwrap().to_string(),
+        "Other"
+    );
+    stream.set_pos(0).unwrap();
+    assert_eq!(stream.read::<Kind>().unwrap(), Kind::Value(21));
+}
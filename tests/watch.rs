@@ -0,0 +1,65 @@
+use bitbuffer::watch::WatchedReadStream;
+use bitbuffer::{BigEndian, BitError, BitReadBuffer, BitReadStream};
+use std::cell::RefCell;
+
+#[test]
+fn test_watch_crossing() {
+    let bytes = [0x12, 0x34, 0x56, 0x78];
+    let crossed = RefCell::new(Vec::new());
+    let mut stream =
+        WatchedReadStream::new(BitReadStream::new(BitReadBuffer::new(&bytes, BigEndian)));
+    stream.watch(16, |pos| {
+        crossed.borrow_mut().push((16, pos));
+        Ok(())
+    });
+    stream.watch(4, |pos| {
+        crossed.borrow_mut().push((4, pos));
+        Ok(())
+    });
+    stream.watch(8, |pos| {
+        crossed.borrow_mut().push((8, pos));
+        Ok(())
+    });
+
+    assert_eq!(stream.read_sized::<u8>(3).unwrap(), 0);
+    assert!(crossed.borrow().is_empty());
+    assert_eq!(stream.read_sized::<u8>(5).unwrap(), 0x12);
+    assert_eq!(*crossed.borrow(), [(4, 8), (8, 8)]);
+
+    // moving backwards doesn't fire, crossing again does
+    stream.set_pos(0).unwrap();
+    assert_eq!(crossed.borrow().len(), 2);
+    stream.skip_bits(20).unwrap();
+    assert_eq!(crossed.borrow()[2..], [(4, 20), (8, 20), (16, 20)]);
+
+    // failed reads don't fire
+    assert!(stream.read_bytes(2).is_err());
+    assert_eq!(crossed.borrow().len(), 5);
+
+    stream.clear_watchpoints();
+    stream.set_pos(0).unwrap();
+    stream.skip_bits(32).unwrap();
+    assert_eq!(crossed.borrow().len(), 5);
+}
+
+#[test]
+fn test_watch_error() {
+    let bytes = [0x12, 0x34, 0x56, 0x78];
+    let mut stream =
+        WatchedReadStream::new(BitReadStream::new(BitReadBuffer::new(&bytes, BigEndian)));
+    stream.watch(12, |pos| {
+        if pos > 12 {
+            Err(BitError::IndexOutOfBounds { pos, size: 12 })
+        } else {
+            Ok(())
+        }
+    });
+    stream.skip_bits(4).unwrap();
+    assert!(matches!(
+        stream.read::<u16>(),
+        Err(BitError::IndexOutOfBounds { pos: 20, size: 12 })
+    ));
+    assert_eq!(stream.pos(), 4);
+    assert_eq!(stream.read_sized::<u8>(8).unwrap(), 0x23);
+    assert_eq!(stream.into_inner().pos(), 12);
+}
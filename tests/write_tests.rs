@@ -1,4 +1,8 @@
-use bitbuffer::{BigEndian, BitReadBuffer, BitReadStream, BitWriteStream, LittleEndian};
+use bitbuffer::{
+    BigEndian, BitError, BitReadBuffer, BitReadStream, BitWrite, BitWriteStream, Endianness,
+    LittleEndian, PrefixedString, QuicVarInt, TruncatePolicy, Vlq,
+};
+use std::cell::Cell;
 
 #[test]
 fn test_write_bool_le() {
@@ -166,3 +170,1345 @@ fn test_write_signed() {
     assert_eq!(-17i32, read.read_int(32).unwrap());
     assert_eq!(-9i32, read.read_int(8).unwrap());
 }
+
+#[test]
+fn test_write_signed_checked() {
+    for (value, count) in [
+        (0i32, 0),
+        (-1, 1),
+        (0, 1),
+        (-8, 4),
+        (7, 4),
+        (i32::MIN, 32),
+        (i32::MAX, 32),
+    ] {
+        let mut data = Vec::new();
+        let mut stream = BitWriteStream::new(&mut data, BigEndian);
+        stream.write_signed(value, count).unwrap();
+        assert_eq!(stream.bit_len(), count);
+        let mut read = BitReadStream::from(BitReadBuffer::new(&data, BigEndian));
+        assert_eq!(value, read.read_signed(count).unwrap());
+    }
+
+    let mut data = Vec::new();
+    let mut stream = BitWriteStream::new(&mut data, LittleEndian);
+    for (value, count, required) in [
+        (1i64, 1, 2),
+        (-9, 4, 5),
+        (8, 4, 5),
+        (-1, 0, 1),
+        (i64::MIN, 63, 64),
+    ] {
+        assert!(matches!(
+            stream.write_signed(value, count),
+            Err(BitError::ValueOutOfRange { required: r, bits }) if r == required && bits == count
+        ));
+    }
+    assert!(matches!(
+        stream.write_signed(1i8, 9),
+        Err(BitError::TooManyBits {
+            requested: 9,
+            max: 8
+        })
+    ));
+    assert_eq!(stream.bit_len(), 0);
+}
+
+#[test]
+fn test_write_strict() {
+    let mut data = Vec::new();
+    let mut stream = BitWriteStream::new(&mut data, BigEndian);
+    assert!(!stream.is_strict());
+    stream.write_int(0x1FFu16, 8).unwrap();
+    stream.set_strict(true);
+    assert!(stream.is_strict());
+    stream.write_int(0xFFu16, 8).unwrap();
+    stream.write_int(-1i8, 1).unwrap();
+    stream.write_int(u128::MAX, 128).unwrap();
+    stream.write_int(i64::MIN, 64).unwrap();
+    stream.write_sized(&3u8, 2).unwrap();
+    assert_eq!(stream.bit_len(), 8 + 8 + 1 + 128 + 64 + 2);
+
+    for (result, required, bits) in [
+        (stream.write_int(0x100u16, 8), 9, 8),
+        (stream.write_int(1u8, 0), 1, 0),
+        (stream.write_int(-5i32, 3), 4, 3),
+        (stream.write_int(4i32, 3), 4, 3),
+        (stream.write_sized(&'€', 8), 14, 8),
+    ] {
+        assert!(matches!(
+            result,
+            Err(BitError::ValueOutOfRange { required: r, bits: b }) if r == required && b == bits
+        ));
+    }
+    assert_eq!(stream.bit_len(), 8 + 8 + 1 + 128 + 64 + 2);
+
+    // the length of a section is checked as well
+    let len = stream.bit_len();
+    assert!(matches!(
+        stream.reserve_length(4, |stream| stream.write_bytes(&[1, 2])),
+        Err(BitError::ValueOutOfRange {
+            required: 5,
+            bits: 4
+        })
+    ));
+    assert_eq!(stream.bit_len(), len);
+    stream.set_strict(false);
+    stream
+        .reserve_length(4, |stream| stream.write_bytes(&[1, 2]))
+        .unwrap();
+    assert_eq!(stream.bit_len(), len + 4 + 16);
+}
+
+#[test]
+fn test_write_large_int_partial() {
+    for &count in &[3, 8, 32, 33, 63] {
+        let mut data = Vec::new();
+        let mut stream = BitWriteStream::new(&mut data, LittleEndian);
+        stream.write_bool(true).unwrap();
+        stream.write_int(5u64, count).unwrap();
+        stream.write_bool(true).unwrap();
+        let mut read = BitReadStream::from(BitReadBuffer::new(&data, LittleEndian));
+        assert!(read.read_bool().unwrap());
+        assert_eq!(5u64, read.read_int(count).unwrap());
+        assert!(read.read_bool().unwrap());
+
+        let mut data = Vec::new();
+        let mut stream = BitWriteStream::new(&mut data, BigEndian);
+        stream.write_bool(true).unwrap();
+        stream.write_int(5u64, count).unwrap();
+        stream.write_bool(true).unwrap();
+        let mut read = BitReadStream::from(BitReadBuffer::new(&data, BigEndian));
+        assert!(read.read_bool().unwrap());
+        assert_eq!(5u64, read.read_int(count).unwrap());
+        assert!(read.read_bool().unwrap());
+    }
+}
+
+/// Write `count` bits using `write` and bit by bit from `value`, after `offset` padding bits
+fn write_wide<E: Endianness, F>(
+    value: u128,
+    count: usize,
+    offset: usize,
+    write: F,
+) -> (Vec<u8>, Vec<u8>)
+where
+    F: FnOnce(&mut BitWriteStream<E>),
+{
+    let mut data = Vec::new();
+    let mut stream = BitWriteStream::new(&mut data, E::endianness());
+    stream.write_int(0u8, offset).unwrap();
+    write(&mut stream);
+    stream.write_bool(true).unwrap();
+
+    let mut expected = Vec::new();
+    let mut stream = BitWriteStream::new(&mut expected, E::endianness());
+    stream.write_int(0u8, offset).unwrap();
+    for i in 0..count {
+        let bit = if E::is_le() { i } else { count - 1 - i };
+        stream.write_bool(value >> bit & 1 == 1).unwrap();
+    }
+    stream.write_bool(true).unwrap();
+    (data, expected)
+}
+
+fn test_write_wide_int_with<E: Endianness>() {
+    for offset in 1..8 {
+        for count in 31..=64 {
+            let value = 0xA5C3_96F0_0F69_3C5Au64 >> (64 - count);
+            let (data, expected) = write_wide::<E, _>(value.into(), count, offset, |stream| {
+                stream.write_int(value, count).unwrap()
+            });
+            assert_eq!(data, expected, "u64 with {} bits at {}", count, offset);
+        }
+        for count in (31..=65).chain(127..=128) {
+            let value = 0xA5C3_96F0_0F69_3C5A_F00F_1234_5678_9ABCu128 >> (128 - count);
+            let (data, expected) = write_wide::<E, _>(value, count, offset, |stream| {
+                stream.write_int(value, count).unwrap()
+            });
+            assert_eq!(data, expected, "u128 with {} bits at {}", count, offset);
+
+            let mut read = BitReadStream::new(BitReadBuffer::new(&data, E::endianness()));
+            read.skip_bits(offset).unwrap();
+            assert_eq!(value, read.read_int::<u128>(count).unwrap());
+            assert!(read.read_bool().unwrap());
+        }
+    }
+
+    let mut data = Vec::new();
+    let mut stream = BitWriteStream::new(&mut data, E::endianness());
+    assert!(matches!(
+        stream.write_int(1u64, 65),
+        Err(BitError::TooManyBits { .. })
+    ));
+    assert!(matches!(
+        stream.write_int(1u128, 129),
+        Err(BitError::TooManyBits { .. })
+    ));
+    assert_eq!(stream.bit_len(), 0);
+}
+
+#[test]
+fn test_write_wide_int() {
+    test_write_wide_int_with::<LittleEndian>();
+    test_write_wide_int_with::<BigEndian>();
+}
+
+#[test]
+fn test_write_zero_width_int() {
+    let mut data = Vec::new();
+    let mut stream = BitWriteStream::new(&mut data, LittleEndian);
+    stream.write_int(0xffu8, 0).unwrap();
+    stream.write_int(0x12u8, 8).unwrap();
+    stream.write_int(0xffu32, 0).unwrap();
+    assert_eq!(stream.bit_len(), 8);
+    assert_eq!(data, [0x12]);
+}
+
+#[test]
+fn test_write_after_reserve_length() {
+    let mut data = Vec::new();
+    let mut stream = BitWriteStream::new(&mut data, BigEndian);
+    stream.write_bool(true).unwrap();
+    stream
+        .reserve_length(7, |stream| stream.write_string("foo", None))
+        .unwrap();
+    stream.write_int(0b101u8, 3).unwrap();
+    assert_eq!(stream.bit_len(), 1 + 7 + 32 + 3);
+
+    let mut read = BitReadStream::from(BitReadBuffer::new(&data, BigEndian));
+    assert!(read.read_bool().unwrap());
+    assert_eq!(32u8, read.read_int(7).unwrap());
+    assert_eq!("foo", read.read_string(None).unwrap());
+    assert_eq!(0b101u8, read.read_int(3).unwrap());
+}
+
+/// Write a tag, a 16 bit length and a value
+fn write_tlv<E: Endianness>(
+    stream: &mut BitWriteStream<E>,
+    tag: u8,
+    value: impl Fn(&mut BitWriteStream<E>) -> bitbuffer::Result<()>,
+) -> bitbuffer::Result<()> {
+    stream.write_int(tag, 8)?;
+    stream.reserve_length(16, value)
+}
+
+fn test_nested_reserve_length_with<E: Endianness>() {
+    let mut data = Vec::new();
+    let mut stream = BitWriteStream::new(&mut data, E::endianness());
+    stream.write_bool(true).unwrap();
+    write_tlv(&mut stream, 1, |stream| {
+        write_tlv(stream, 2, |stream| stream.write_bytes(b"ab"))?;
+        write_tlv(stream, 3, |stream| {
+            write_tlv(stream, 4, |stream| stream.write_int(0b101u8, 3))?;
+            write_tlv(stream, 5, |_| Ok(()))
+        })
+    })
+    .unwrap();
+    stream.write_int(0b11u8, 2).unwrap();
+    assert_eq!(
+        stream.bit_len(),
+        1 + 24 + (24 + 16) + (24 + (24 + 3) + 24) + 2
+    );
+    drop(stream);
+
+    let mut read = BitReadStream::new(BitReadBuffer::new(&data, E::endianness()));
+    assert!(read.read_bool().unwrap());
+    let read_tlv = |read: &mut BitReadStream<E>, tag: u8| {
+        assert_eq!(read.read_int::<u8>(8).unwrap(), tag);
+        read.read_int::<u16>(16).unwrap() as usize
+    };
+    assert_eq!(read_tlv(&mut read, 1), 24 + 16 + 24 + 27 + 24);
+    assert_eq!(read_tlv(&mut read, 2), 16);
+    assert_eq!(read.read_bytes(2).unwrap().as_ref(), b"ab");
+    assert_eq!(read_tlv(&mut read, 3), 27 + 24);
+    assert_eq!(read_tlv(&mut read, 4), 3);
+    assert_eq!(read.read_int::<u8>(3).unwrap(), 0b101);
+    assert_eq!(read_tlv(&mut read, 5), 0);
+    assert_eq!(read.read_int::<u8>(2).unwrap(), 0b11);
+}
+
+#[test]
+fn test_nested_reserve_length() {
+    test_nested_reserve_length_with::<LittleEndian>();
+    test_nested_reserve_length_with::<BigEndian>();
+}
+
+#[test]
+fn test_interleaved_reserve_length() {
+    let mut data = Vec::new();
+    let mut stream = BitWriteStream::new(&mut data, BigEndian);
+    // a count that is filled in after the length of the containing section
+    let count_pos = stream.bit_len();
+    stream.write_int(0u8, 4).unwrap();
+    stream
+        .reserve_length(12, |stream| {
+            // positions inside the section are positions in the full stream
+            assert_eq!(stream.bit_len(), 16);
+            for value in [b'x', b'y', b'z'] {
+                stream.reserve_length(4, |stream| stream.write_int(value, 8))?;
+            }
+            Ok(())
+        })
+        .unwrap();
+    let count = [3 << 4];
+    let count = BitReadStream::new(BitReadBuffer::new(&count, BigEndian));
+    stream
+        .overwrite_bits(count_pos, &count.clone().read_bits(4).unwrap())
+        .unwrap();
+    drop(stream);
+    assert_eq!(data, [0x30, 0x24, 0x87, 0x88, 0x79, 0x87, 0xA0]);
+}
+
+#[test]
+fn test_reserve_length_error() {
+    let mut data = Vec::new();
+    let mut stream = BitWriteStream::new(&mut data, LittleEndian);
+    stream.write_int(0b101u8, 3).unwrap();
+    let result = stream.reserve_length(8, |stream| {
+        stream.reserve_length(8, |stream| stream.write_bytes(b"abc"))?;
+        stream.write_int(1u8, 9)
+    });
+    assert!(matches!(result, Err(BitError::TooManyBits { .. })));
+    assert_eq!(stream.bit_len(), 3);
+
+    let result = stream.reserve_length(65, |stream| stream.write_bytes(b"ab"));
+    assert!(matches!(result, Err(BitError::TooManyBits { .. })));
+    assert_eq!(stream.bit_len(), 3);
+    drop(stream);
+    assert_eq!(data, [0b101]);
+}
+
+#[test]
+fn test_offsets() {
+    let mut data = Vec::new();
+    let mut stream = BitWriteStream::new(&mut data, LittleEndian);
+    let table = stream.create_label();
+    let chunks = [stream.create_label(), stream.create_label()];
+    stream.write_int(0b1u8, 1).unwrap();
+    stream.place_label(table).unwrap();
+    stream.write_offset(chunks[1], 16).unwrap();
+    stream.write_byte_offset(chunks[0], 4).unwrap();
+    stream.write_int(0u8, 3).unwrap();
+    assert_eq!(stream.label_position(table), Some(1));
+    assert_eq!(stream.label_position(chunks[0]), None);
+
+    stream.place_label(chunks[0]).unwrap();
+    stream.write_bytes(b"foo").unwrap();
+    stream.place_label(chunks[1]).unwrap();
+    stream.write_offset(table, 3).unwrap();
+    stream.write_bool(true).unwrap();
+    let (_, bit_len) = stream.finish();
+    assert_eq!(bit_len, 24 + 24 + 4);
+
+    let mut read = BitReadStream::new(BitReadBuffer::new(&data, LittleEndian));
+    assert!(read.read_bool().unwrap());
+    assert_eq!(read.read_int::<u16>(16).unwrap(), 48);
+    assert_eq!(read.read_int::<u8>(4).unwrap(), 3);
+    read.skip_bits(3).unwrap();
+    assert_eq!(read.read_bytes(3).unwrap().as_ref(), b"foo");
+    assert_eq!(read.read_int::<u8>(3).unwrap(), 1);
+    assert!(read.read_bool().unwrap());
+}
+
+#[test]
+fn test_offset_errors() {
+    let mut data = Vec::new();
+    let mut stream = BitWriteStream::new(&mut data, BigEndian);
+    let label = stream.create_label();
+    stream.write_byte_offset(label, 4).unwrap();
+    assert!(matches!(
+        stream.resolve_offsets(),
+        Err(BitError::UnresolvedOffset { label: 0, .. })
+    ));
+    stream.write_bool(true).unwrap();
+    stream.place_label(label).unwrap();
+    assert!(matches!(
+        stream.resolve_offsets(),
+        Err(BitError::UnresolvedOffset { .. })
+    ));
+    stream.write_bytes(&[0; 16]).unwrap();
+    stream.place_label(label).unwrap();
+    assert!(matches!(
+        stream.resolve_offsets(),
+        Err(BitError::UnresolvedOffset { .. })
+    ));
+    stream.truncate(8);
+    stream.place_label(label).unwrap();
+    stream.resolve_offsets().unwrap();
+    stream.resolve_offsets().unwrap();
+    assert_eq!(stream.bit_len(), 8);
+    assert!(matches!(
+        stream.write_offset(label, 65),
+        Err(BitError::TooManyBits { .. })
+    ));
+    assert_eq!(stream.bit_len(), 8);
+    drop(stream);
+    assert_eq!(data, [0b0001_1000]);
+
+    let mut other_data = Vec::new();
+    let mut other = BitWriteStream::new(&mut other_data, BigEndian);
+    assert!(matches!(
+        other.write_offset(label, 8),
+        Err(BitError::UnresolvedOffset { .. })
+    ));
+    assert!(other.place_label(label).is_err());
+}
+
+#[test]
+fn test_offsets_after_truncate() {
+    let mut data = Vec::new();
+    let mut stream = BitWriteStream::new(&mut data, BigEndian);
+    let label = stream.create_label();
+    stream.write_int(1u8, 8).unwrap();
+    stream.place_label(label).unwrap();
+    let result = stream.reserve_length(8, |stream| {
+        stream.write_offset(label, 8)?;
+        stream.place_label(label)?;
+        stream.write_int(0u8, 9)
+    });
+    assert!(result.is_err());
+    // the offset written in the failed section is forgotten and the label moved into it is no longer placed
+    assert_eq!(stream.bit_len(), 8);
+    assert_eq!(stream.label_position(label), None);
+    stream.write_offset(label, 8).unwrap();
+    stream.place_label(label).unwrap();
+    stream.finish();
+    assert_eq!(data, [1, 16]);
+}
+
+#[test]
+#[should_panic(expected = "the label is never placed")]
+fn test_finish_unresolved_offset() {
+    let mut data = Vec::new();
+    let mut stream = BitWriteStream::new(&mut data, BigEndian);
+    let label = stream.create_label();
+    stream.write_offset(label, 8).unwrap();
+    stream.finish();
+}
+
+#[test]
+fn test_write_with_offsets() {
+    let mut data = Vec::new();
+    let mut stream = BitWriteStream::new(&mut data, LittleEndian);
+    stream.write_int(0b101u8, 3).unwrap();
+    let end = stream.create_label();
+    let passes = Cell::new(0);
+    stream
+        .write_with_offsets(|stream, positions| {
+            passes.set(passes.get() + 1);
+            let chunk = stream.create_label();
+            stream.write_int(positions.get(chunk), 16)?;
+            // offsets written as placeholder can be mixed in
+            stream.write_offset(end, 8)?;
+            stream.write_bool(positions.position(chunk).is_some())?;
+            stream.place_label(chunk)?;
+            stream.write_bytes(b"foo")
+        })
+        .unwrap();
+    stream.place_label(end).unwrap();
+    stream.finish();
+    // offsets with a fixed size only need a sizing pass
+    assert_eq!(passes.get(), 2);
+
+    let mut read = BitReadStream::new(BitReadBuffer::new(&data, LittleEndian));
+    assert_eq!(read.read_int::<u8>(3).unwrap(), 0b101);
+    assert_eq!(read.read_int::<u16>(16).unwrap(), 28);
+    assert_eq!(read.read_int::<u8>(8).unwrap(), 52);
+    assert!(read.read_bool().unwrap());
+    assert_eq!(read.read_bytes(3).unwrap().as_ref(), b"foo");
+}
+
+#[test]
+fn test_write_with_offsets_variable_size() {
+    let mut data = Vec::new();
+    let mut stream = BitWriteStream::new(&mut data, BigEndian);
+    stream
+        .write_with_offsets(|stream, positions| {
+            let labels: Vec<_> = (0..20).map(|_| stream.create_label()).collect();
+            for label in &labels {
+                stream.write(&Vlq(positions.get(*label) as u64))?;
+            }
+            for label in &labels {
+                stream.place_label(*label)?;
+                stream.write_bytes(&[0; 2])?;
+            }
+            Ok(())
+        })
+        .unwrap();
+    drop(stream);
+
+    let mut read = BitReadStream::new(BitReadBuffer::new(&data, BigEndian));
+    let offsets: Vec<Vlq> = read.read_sized(20).unwrap();
+    let index_len = read.pos();
+    for (i, offset) in offsets.iter().enumerate() {
+        assert_eq!(offset.0 as usize, index_len + i * 16);
+    }
+}
+
+#[test]
+fn test_write_with_offsets_errors() {
+    let mut data = Vec::new();
+    let mut stream = BitWriteStream::new(&mut data, BigEndian);
+    stream.write_int(1u8, 8).unwrap();
+    let label = stream.create_label();
+
+    // every pass moves the label further
+    let result = stream.write_with_offsets(|stream, positions| {
+        for _ in 0..positions.get(label) {
+            stream.write_bool(true)?;
+        }
+        stream.place_label(label)
+    });
+    assert!(matches!(
+        result,
+        Err(BitError::UnresolvedOffset { label: 0, .. })
+    ));
+    assert_eq!(stream.bit_len(), 8);
+
+    let passes = Cell::new(0);
+    let result = stream.write_with_offsets(|stream, _| {
+        passes.set(passes.get() + 1);
+        stream.create_label();
+        stream.write_int(1u8, 8)?;
+        if passes.get() > 1 {
+            stream.write_int(1u8, 9)?;
+        }
+        Ok(())
+    });
+    assert!(matches!(result, Err(BitError::TooManyBits { .. })));
+    assert_eq!(stream.bit_len(), 8);
+    assert_eq!(stream.label_position(label), None);
+    assert_eq!(stream.create_label().index(), 1);
+}
+
+#[test]
+fn test_overwrite_bits() {
+    for (pos, count) in [(0, 1), (3, 5), (5, 14), (9, 40), (0, 80)] {
+        for endianness in [true, false] {
+            let mut data = Vec::new();
+            let mut expected = Vec::new();
+            if endianness {
+                overwrite_bits_with(&mut data, &mut expected, LittleEndian, pos, count);
+            } else {
+                overwrite_bits_with(&mut data, &mut expected, BigEndian, pos, count);
+            }
+            assert_eq!(data, expected, "{} bits at {}", count, pos);
+        }
+    }
+
+    let mut data = Vec::new();
+    let mut stream = BitWriteStream::new(&mut data, LittleEndian);
+    stream.write_int(0u8, 4).unwrap();
+    let bits = [0xFF];
+    let bits = BitReadStream::new(BitReadBuffer::new(&bits, LittleEndian));
+    assert!(matches!(
+        stream.overwrite_bits(0, &bits),
+        Err(BitError::IndexOutOfBounds { pos: 8, size: 4 })
+    ));
+    stream
+        .overwrite_bits(4, &bits.clone().read_bits(0).unwrap())
+        .unwrap();
+}
+
+/// Overwrite bits in a stream of ones, and write the same bits in one go to compare
+fn overwrite_bits_with<E: Endianness>(
+    data: &mut Vec<u8>,
+    expected: &mut Vec<u8>,
+    endianness: E,
+    pos: usize,
+    count: usize,
+) {
+    let source: Vec<u8> = (0..16u8).map(|i| i.wrapping_mul(151) ^ 0x5A).collect();
+    let source = BitReadStream::new(BitReadBuffer::new(&source, E::endianness()));
+    let bits = source.clone().read_bits(count).unwrap();
+    let len = pos + count + 7;
+
+    let mut stream = BitWriteStream::new(data, endianness);
+    for _ in 0..len {
+        stream.write_bool(true).unwrap();
+    }
+    stream.overwrite_bits(pos, &bits).unwrap();
+
+    let mut stream = BitWriteStream::new(expected, E::endianness());
+    for _ in 0..pos {
+        stream.write_bool(true).unwrap();
+    }
+    stream.write_bits(&bits).unwrap();
+    for _ in 0..7 {
+        stream.write_bool(true).unwrap();
+    }
+}
+
+#[test]
+fn test_write_offset_binary() {
+    for (value, count, bias) in [
+        (-2048i64, 12, 2048),
+        (2047, 12, 2048),
+        (0, 12, 2048),
+        (-127, 8, 127),
+        (i64::MIN, 64, 1 << 63),
+        (i64::MAX, 64, 1 << 63),
+    ] {
+        let mut data = Vec::new();
+        let mut stream = BitWriteStream::new(&mut data, LittleEndian);
+        stream.write_bool(true).unwrap();
+        stream.write_int_offset_binary(value, count, bias).unwrap();
+        let mut read = BitReadStream::from(BitReadBuffer::new(&data, LittleEndian));
+        assert!(read.read_bool().unwrap());
+        let raw: u64 = read.clone().read_int(count).unwrap();
+        assert_eq!(raw as i128, value as i128 + bias);
+        assert_eq!(
+            value,
+            read.read_int_offset_binary::<i64>(count, bias).unwrap()
+        );
+    }
+}
+
+#[test]
+fn test_write_sign_magnitude() {
+    for (value, count) in [
+        (-3i32, 4),
+        (7, 4),
+        (-7, 4),
+        (0, 1),
+        (i32::MIN + 1, 32),
+        (-1, 32),
+    ] {
+        for endianness in [true, false] {
+            let mut data = Vec::new();
+            if endianness {
+                let mut stream = BitWriteStream::new(&mut data, LittleEndian);
+                stream.write_int_sign_magnitude(value, count).unwrap();
+                let mut read = BitReadStream::from(BitReadBuffer::new(&data, LittleEndian));
+                assert_eq!(value, read.read_int_sign_magnitude(count).unwrap());
+            } else {
+                let mut stream = BitWriteStream::new(&mut data, BigEndian);
+                stream.write_int_sign_magnitude(value, count).unwrap();
+                let mut read = BitReadStream::from(BitReadBuffer::new(&data, BigEndian));
+                assert_eq!(value, read.read_int_sign_magnitude(count).unwrap());
+            }
+        }
+    }
+
+    let mut data = Vec::new();
+    let mut stream = BitWriteStream::new(&mut data, BigEndian);
+    stream.write_int_sign_magnitude(-5i8, 8).unwrap();
+    assert_eq!(data, [0b1000_0101]);
+}
+
+#[test]
+fn test_write_sign_magnitude_strict() {
+    let mut data = Vec::new();
+    let mut stream = BitWriteStream::new(&mut data, BigEndian);
+    stream.set_strict(true);
+    stream.write_int_sign_magnitude(-7i32, 4).unwrap();
+    stream.write_int_sign_magnitude(7i32, 4).unwrap();
+
+    for (result, required, bits) in [
+        (stream.write_int_sign_magnitude(100i32, 4), 8, 4),
+        (stream.write_int_sign_magnitude(-8i32, 4), 5, 4),
+        (stream.write_int_sign_magnitude(1i8, 1), 2, 1),
+    ] {
+        assert!(matches!(
+            result,
+            Err(BitError::ValueOutOfRange { required: r, bits: b }) if r == required && b == bits
+        ));
+    }
+    assert_eq!(stream.bit_len(), 8);
+    drop(stream);
+    assert_eq!(data, [0b1111_0111]);
+}
+
+#[test]
+fn test_write_deltas() {
+    let values = [1000i32, 1003, 990, 990, 1017, -5];
+    let mut data = Vec::new();
+    let mut stream = BitWriteStream::new(&mut data, LittleEndian);
+    stream.write_deltas(&values, 11, 1000).unwrap();
+    assert_eq!(stream.bit_len(), 66);
+    let mut read = BitReadStream::from(BitReadBuffer::new(&data, LittleEndian));
+    assert_eq!(read.read_deltas::<i32>(6, 11, 1000).unwrap(), values);
+
+    // unsigned values wrap around
+    let values = [250u8, 3, 7];
+    let mut data = Vec::new();
+    let mut stream = BitWriteStream::new(&mut data, BigEndian);
+    stream.write_deltas(&values, 8, 0).unwrap();
+    assert_eq!(data, [250, 9, 4]);
+    let mut read = BitReadStream::from(BitReadBuffer::new(&data, BigEndian));
+    assert_eq!(read.read_deltas::<u8>(3, 8, 0).unwrap(), values);
+}
+
+#[test]
+fn test_copy_within() {
+    let source: Vec<u8> = (0..32u8).map(|i| i.wrapping_mul(73)).collect();
+    for (start, end, prefix) in [(0, 13, 1), (5, 200, 3), (100, 101, 7), (1, 255, 8)] {
+        let mut data = Vec::new();
+        let mut stream = BitWriteStream::new(&mut data, LittleEndian);
+        stream.write_int(0u8, prefix).unwrap();
+        stream.write_bytes(&source).unwrap();
+        let written = stream.bit_len();
+        stream.copy_within(start..end).unwrap();
+        assert_eq!(stream.bit_len(), written + end - start);
+
+        let read = BitReadBuffer::new(&data, LittleEndian);
+        let distance = written - start;
+        for i in 0..end - start {
+            assert_eq!(
+                read.read_bool(written + i).unwrap(),
+                read.read_bool(start + i % distance).unwrap()
+            );
+        }
+    }
+
+    let mut data = Vec::new();
+    let mut stream = BitWriteStream::new(&mut data, BigEndian);
+    stream.write_int(0b101u8, 3).unwrap();
+    assert!(stream.copy_within(3..5).is_err());
+    stream.copy_within(2..2).unwrap();
+    assert_eq!(stream.bit_len(), 3);
+}
+
+#[test]
+fn test_copy_within_reserved() {
+    let mut data = Vec::new();
+    let mut stream = BitWriteStream::new(&mut data, BigEndian);
+    stream.write_int(0b1100u8, 4).unwrap();
+    stream
+        .reserve_length(4, |stream| {
+            stream.write_int(0b01u8, 2)?;
+            stream.copy_within(0..4)
+        })
+        .unwrap();
+    assert_eq!(data, [0b1100_0110, 0b0111_0000]);
+}
+
+#[test]
+fn test_copy_back() {
+    let mut data = Vec::new();
+    let mut stream = BitWriteStream::new(&mut data, LittleEndian);
+    stream.write_bytes(b"abcd").unwrap();
+    stream.copy_back(4, 2).unwrap();
+    stream.copy_back(1, 3).unwrap();
+    stream.copy_back(1, 0).unwrap();
+    assert!(matches!(
+        stream.copy_back(0, 1),
+        Err(BitError::IndexOutOfBounds { .. })
+    ));
+    assert!(matches!(
+        stream.copy_back(10, 1),
+        Err(BitError::IndexOutOfBounds { .. })
+    ));
+    assert!(stream.copy_back(1, usize::MAX).is_err());
+    assert_eq!(data, b"abcdabbbb");
+
+    // unaligned streams copy the bytes ending at the current position
+    let mut data = Vec::new();
+    let mut stream = BitWriteStream::new(&mut data, BigEndian);
+    stream.write_int(0b101u8, 3).unwrap();
+    stream.write_bytes(&[0xF0]).unwrap();
+    stream.copy_back(1, 1).unwrap();
+    assert_eq!(data, [0b1011_1110, 0b0001_1110, 0b0000_0000]);
+}
+
+fn bits_of<E: bitbuffer::Endianness>(data: &[u8], len: usize) -> Vec<bool> {
+    let buffer = BitReadBuffer::new(data, E::endianness());
+    (0..len).map(|i| buffer.read_bool(i).unwrap()).collect()
+}
+
+fn test_replace_bits_with<E: bitbuffer::Endianness>() {
+    let source: Vec<u8> = (0..16u8).map(|i| i.wrapping_mul(151) ^ 0x5A).collect();
+    let insert: Vec<u8> = (0..8u8).map(|i| i.wrapping_mul(29)).collect();
+    for (len, range, insert_len) in [
+        (20, 3..3, 5),
+        (20, 0..0, 64),
+        (128, 7..100, 3),
+        (128, 0..128, 0),
+        (77, 77..77, 17),
+        (100, 10..11, 40),
+    ] {
+        let mut data = Vec::new();
+        let mut stream = BitWriteStream::new(&mut data, E::endianness());
+        stream
+            .write_bits(
+                &BitReadStream::new(BitReadBuffer::new(&source, E::endianness()))
+                    .read_bits(len)
+                    .unwrap(),
+            )
+            .unwrap();
+        let inserted = BitReadStream::new(BitReadBuffer::new(&insert, E::endianness()))
+            .read_bits(insert_len)
+            .unwrap();
+        stream.replace_bits(range.clone(), &inserted).unwrap();
+        // writing continues after the moved bits
+        stream.write_bool(true).unwrap();
+        let new_len = stream.bit_len();
+
+        let mut expected = bits_of::<E>(&source, len);
+        expected.splice(range, bits_of::<E>(&insert, insert_len));
+        expected.push(true);
+        assert_eq!(new_len, expected.len());
+        assert_eq!(data.len(), new_len.div_ceil(8));
+        assert_eq!(bits_of::<E>(&data, new_len), expected);
+        // no stray bits in the last byte
+        let padding = bits_of::<E>(&data, data.len() * 8);
+        assert!(padding[new_len..].iter().all(|bit| !bit));
+    }
+}
+
+#[test]
+fn test_replace_bits() {
+    test_replace_bits_with::<LittleEndian>();
+    test_replace_bits_with::<BigEndian>();
+
+    let mut data = Vec::new();
+    let mut stream = BitWriteStream::new(&mut data, LittleEndian);
+    stream.write_int(0u8, 4).unwrap();
+    let empty = BitReadStream::new(BitReadBuffer::new(&[], LittleEndian));
+    assert!(stream.insert_bits(5, &empty).is_err());
+    assert!(stream.replace_bits(2..5, &empty).is_err());
+}
+
+#[test]
+fn test_truncate() {
+    for len in 0..24 {
+        let mut data = Vec::new();
+        let mut stream = BitWriteStream::new(&mut data, LittleEndian);
+        stream.write_int(0xFFFFFFu32, 24).unwrap();
+        stream.truncate(len);
+        assert_eq!(stream.bit_len(), len);
+        stream.write_int(0u8, 2).unwrap();
+        assert_eq!(data.len(), (len + 2).div_ceil(8));
+        let read = BitReadBuffer::new(&data, LittleEndian);
+        for i in 0..data.len() * 8 {
+            assert_eq!(read.read_bool(i).unwrap(), i < len);
+        }
+    }
+
+    let mut data = Vec::new();
+    let mut stream = BitWriteStream::new(&mut data, LittleEndian);
+    stream.write_int(0b101u8, 3).unwrap();
+    stream.truncate(10);
+    assert_eq!(stream.bit_len(), 3);
+}
+
+#[test]
+fn test_existing_data() {
+    let mut data = vec![0xAA, 0xBB];
+    let mut stream = BitWriteStream::new(&mut data, BigEndian);
+    stream.write_int(0b1111u8, 4).unwrap();
+    stream
+        .reserve_length(4, |stream| stream.write_int(0b11u8, 2))
+        .unwrap();
+    stream.copy_within(0..2).unwrap();
+    assert_eq!(stream.bit_len(), 12);
+    assert_eq!(data, [0xAA, 0xBB, 0b1111_0010, 0b1111_0000]);
+
+    let mut stream = BitWriteStream::new(&mut data, BigEndian);
+    stream.write_int(0xFFu8, 8).unwrap();
+    stream.clear();
+    assert_eq!(stream.bit_len(), 0);
+    stream.write_int(0b1u8, 1).unwrap();
+    assert_eq!(data, [0xAA, 0xBB, 0b1111_0010, 0b1111_0000, 0b1000_0000]);
+}
+
+#[test]
+fn test_finish() {
+    let mut data = vec![0xAA];
+    let mut stream = BitWriteStream::new(&mut data, BigEndian);
+    stream.write_int(0b1_0101u8, 5).unwrap();
+    stream.write_int(0xFFFu16, 12).unwrap();
+    let (bytes, bit_len) = stream.finish();
+    assert_eq!(bit_len, 17);
+    assert_eq!(bytes, [0b1010_1111, 0b1111_1111, 0b1000_0000]);
+
+    let mut read =
+        BitReadStream::new(BitReadBuffer::with_bit_len(bytes, bit_len, BigEndian).unwrap());
+    assert_eq!(read.read_int::<u8>(5).unwrap(), 0b1_0101);
+    assert_eq!(read.read_int::<u16>(12).unwrap(), 0xFFF);
+    assert!(read.read_bool().is_err());
+    assert_eq!(data.len(), 4);
+}
+
+#[test]
+fn test_capacity() {
+    let mut data = vec![0xAA, 0xBB];
+    let mut stream = BitWriteStream::with_capacity(&mut data, 20, BigEndian);
+    assert!(stream.capacity() >= 20);
+    stream.write_int(0b101u8, 3).unwrap();
+    stream.reserve(61);
+    assert!(stream.capacity() >= 64);
+
+    let values = [0x1234u16; 64];
+    stream.write(&values).unwrap();
+    assert!(stream.capacity() >= 3 + 64 * 16);
+    assert_eq!(stream.bit_len(), 3 + 64 * 16);
+    assert!(data.capacity() >= 2 + (3 + 64 * 16usize).div_ceil(8));
+}
+
+#[test]
+fn test_bit_size_hint() {
+    assert_eq!(<u32 as BitWrite<LittleEndian>>::bit_size_hint(), Some(32));
+    assert_eq!(
+        <[bool; 5] as BitWrite<LittleEndian>>::bit_size_hint(),
+        Some(5)
+    );
+    assert_eq!(
+        <(u8, f64, Box<i16>) as BitWrite<LittleEndian>>::bit_size_hint(),
+        Some(88)
+    );
+    assert_eq!(
+        <(u8, String) as BitWrite<LittleEndian>>::bit_size_hint(),
+        None
+    );
+}
+
+#[test]
+fn test_quantized_float() {
+    let mut data = Vec::new();
+    let mut stream = BitWriteStream::new(&mut data, BigEndian);
+    stream.write_quantized_float(0.5f32, 4, 0.0, 15.0).unwrap();
+    stream.write_quantized_float(0.49f32, 4, 0.0, 15.0).unwrap();
+    stream
+        .write_quantized_float(-20.0f32, 4, 0.0, 15.0)
+        .unwrap();
+    stream.write_quantized_float(20.0f32, 4, 0.0, 15.0).unwrap();
+    stream
+        .write_quantized_float(f32::NAN, 4, 0.0, 15.0)
+        .unwrap();
+    assert!(stream.write_quantized_float(0.0f32, 33, 0.0, 1.0).is_err());
+    assert_eq!(stream.bit_len(), 20);
+    assert_eq!(data, [0x10, 0x0F, 0x00]);
+
+    let mut data = Vec::new();
+    let mut stream = BitWriteStream::new(&mut data, LittleEndian);
+    for i in 0..=100 {
+        let value = -360.0 + i as f64 * 7.2;
+        stream
+            .write_quantized_float(value, 16, -360.0, 360.0)
+            .unwrap();
+    }
+    stream.write_quantized_float(0.1f64, 32, 0.0, 1.0).unwrap();
+    let mut read = BitReadStream::new(BitReadBuffer::new(&data, LittleEndian));
+    for i in 0..=100 {
+        let value = -360.0 + i as f64 * 7.2;
+        let read_value = read.read_quantized_float::<f64>(16, -360.0, 360.0).unwrap();
+        assert!((read_value - value).abs() <= 360.0 / 65535.0 + 1e-9);
+        if i == 0 || i == 100 {
+            assert_eq!(read_value, value);
+        }
+    }
+    let read_value = read.read_quantized_float::<f64>(32, 0.0, 1.0).unwrap();
+    assert!((read_value - 0.1).abs() <= 0.5 / u32::MAX as f64);
+}
+
+#[test]
+fn test_write_string_prefixed() {
+    let mut data = Vec::new();
+    let mut stream = BitWriteStream::new(&mut data, BigEndian);
+    stream.write_string_prefixed("ab", 4).unwrap();
+    stream.write_string_prefixed("", 4).unwrap();
+    assert_eq!(stream.bit_len(), 24);
+    assert!(matches!(
+        stream.write_string_prefixed("0123456789abcdef", 4),
+        Err(BitError::StringToLong {
+            string_length: 16,
+            requested_length: 15
+        })
+    ));
+    assert!(stream.write_string_prefixed("a", 65).is_err());
+    assert_eq!(stream.bit_len(), 24);
+    assert_eq!(data, [0x26, 0x16, 0x20]);
+
+    let value: PrefixedString<12> = "prefixed".into();
+    let mut data = Vec::new();
+    let mut stream = BitWriteStream::new(&mut data, LittleEndian);
+    stream.write(&value).unwrap();
+    assert_eq!(stream.bit_len(), 12 + 8 * 8);
+    let mut read = BitReadStream::new(BitReadBuffer::new(&data, LittleEndian));
+    assert_eq!(read.read::<PrefixedString<12>>().unwrap(), value);
+}
+
+#[test]
+fn test_varint_sqlite() {
+    let cases: [(u64, &[u8]); 6] = [
+        (0, &[0x00]),
+        (0x7F, &[0x7F]),
+        (0x3FFF, &[0xFF, 0x7F]),
+        (0x4000, &[0x81, 0x80, 0x00]),
+        (
+            (1 << 56) - 1,
+            &[0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x7F],
+        ),
+        (
+            u64::MAX,
+            &[0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF],
+        ),
+    ];
+    for (value, encoded) in cases {
+        let mut data = Vec::new();
+        let mut stream = BitWriteStream::new(&mut data, BigEndian);
+        stream.write_varint_sqlite(value).unwrap();
+        assert_eq!(data, encoded);
+
+        let mut stream = BitReadStream::new(BitReadBuffer::new(encoded, BigEndian));
+        assert_eq!(stream.read_varint_sqlite().unwrap(), value);
+        assert_eq!(stream.bits_left(), 0);
+    }
+
+    // 9 byte values use all bits of the last byte
+    let mut data = Vec::new();
+    let mut stream = BitWriteStream::new(&mut data, BigEndian);
+    stream.write_varint_sqlite(1 << 56).unwrap();
+    assert_eq!(data, [0x80, 0xC0, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x00]);
+    let mut stream = BitReadStream::new(BitReadBuffer::new(&data, BigEndian));
+    assert_eq!(stream.read_varint_sqlite().unwrap(), 1 << 56);
+
+    let mut stream = BitReadStream::new(BitReadBuffer::new(&[0x81, 0x80], BigEndian));
+    assert!(matches!(
+        stream.read_varint_sqlite(),
+        Err(BitError::NotEnoughData { .. })
+    ));
+    assert_eq!(stream.pos(), 0);
+}
+
+#[test]
+fn test_varint_git_offset() {
+    let cases: [(u64, &[u8]); 5] = [
+        (0, &[0x00]),
+        (0x7F, &[0x7F]),
+        (0x80, &[0x80, 0x00]),
+        (0x407F, &[0xFF, 0x7F]),
+        (0x4080, &[0x80, 0x80, 0x00]),
+    ];
+    for (value, encoded) in cases {
+        let mut data = Vec::new();
+        let mut stream = BitWriteStream::new(&mut data, BigEndian);
+        stream.write_varint_git_offset(value).unwrap();
+        assert_eq!(data, encoded);
+
+        let mut stream = BitReadStream::new(BitReadBuffer::new(encoded, BigEndian));
+        assert_eq!(stream.read_varint_git_offset().unwrap(), value);
+    }
+
+    // unaligned roundtrip, including the largest value
+    let mut data = Vec::new();
+    let mut stream = BitWriteStream::new(&mut data, LittleEndian);
+    stream.write_bool(true).unwrap();
+    for value in [u64::MAX, 12345678, 1] {
+        stream.write_varint_git_offset(value).unwrap();
+    }
+    let mut stream = BitReadStream::new(BitReadBuffer::new(&data, LittleEndian));
+    stream.skip_bits(1).unwrap();
+    for value in [u64::MAX, 12345678, 1] {
+        assert_eq!(stream.read_varint_git_offset().unwrap(), value);
+    }
+
+    let overflow = [0xFF; 11];
+    let mut stream = BitReadStream::new(BitReadBuffer::new(&overflow, BigEndian));
+    stream.skip_bits(8).unwrap();
+    assert!(matches!(
+        stream.read_varint_git_offset(),
+        Err(BitError::VarIntOverflow { pos: 8, max: 64 })
+    ));
+    assert_eq!(stream.pos(), 8);
+}
+
+#[test]
+fn test_vlq() {
+    let cases: [(u64, &[u8]); 7] = [
+        (0, &[0x00]),
+        (0x40, &[0x40]),
+        (0x7F, &[0x7F]),
+        (0x80, &[0x81, 0x00]),
+        (0x2000, &[0xC0, 0x00]),
+        (0x0FFF_FFFF, &[0xFF, 0xFF, 0xFF, 0x7F]),
+        (
+            u64::MAX,
+            &[0x81, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x7F],
+        ),
+    ];
+    for (value, encoded) in cases {
+        let mut data = Vec::new();
+        let mut stream = BitWriteStream::new(&mut data, BigEndian);
+        stream.write(&Vlq(value)).unwrap();
+        assert_eq!(data, encoded);
+
+        let mut stream = BitReadStream::new(BitReadBuffer::new(encoded, BigEndian));
+        assert_eq!(stream.read::<Vlq>().unwrap(), Vlq(value));
+        assert_eq!(stream.bits_left(), 0);
+    }
+
+    let overflow = [0x82, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x00];
+    let mut stream = BitReadStream::new(BitReadBuffer::new(&overflow, BigEndian));
+    assert!(matches!(
+        stream.read_vlq(),
+        Err(BitError::VarIntOverflow { pos: 0, max: 64 })
+    ));
+    let mut stream = BitReadStream::new(BitReadBuffer::new(&overflow[..4], BigEndian));
+    assert!(matches!(
+        stream.read_vlq(),
+        Err(BitError::NotEnoughData { .. })
+    ));
+    assert_eq!(stream.pos(), 0);
+}
+
+#[test]
+fn test_quic_varint() {
+    // examples from RFC 9000 appendix A.1
+    let cases: [(u64, &[u8]); 6] = [
+        (0, &[0x00]),
+        (37, &[0x25]),
+        (15_293, &[0x7B, 0xBD]),
+        (494_878_333, &[0x9D, 0x7F, 0x3E, 0x7D]),
+        (
+            151_288_809_941_952_652,
+            &[0xC2, 0x19, 0x7C, 0x5E, 0xFF, 0x14, 0xE8, 0x8C],
+        ),
+        (
+            QuicVarInt::MAX.0,
+            &[0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF],
+        ),
+    ];
+    for (value, encoded) in cases {
+        let mut data = Vec::new();
+        let mut stream = BitWriteStream::new(&mut data, BigEndian);
+        stream.write(&QuicVarInt(value)).unwrap();
+        assert_eq!(data, encoded);
+
+        let mut stream = BitReadStream::new(BitReadBuffer::new(encoded, LittleEndian));
+        assert_eq!(stream.read::<QuicVarInt>().unwrap(), QuicVarInt(value));
+        assert_eq!(stream.bits_left(), 0);
+    }
+
+    // non minimal encodings are accepted
+    let mut stream = BitReadStream::new(BitReadBuffer::new(&[0x40, 0x25], BigEndian));
+    assert_eq!(stream.read_quic_varint().unwrap(), 37);
+
+    let mut data = Vec::new();
+    let mut stream = BitWriteStream::new(&mut data, BigEndian);
+    assert!(matches!(
+        stream.write_quic_varint(1 << 62),
+        Err(BitError::TooManyBits {
+            requested: 63,
+            max: 62
+        })
+    ));
+    assert_eq!(stream.bit_len(), 0);
+
+    let truncated = [0x9D, 0x7F, 0x3E];
+    let mut stream = BitReadStream::new(BitReadBuffer::new(&truncated, BigEndian));
+    assert!(matches!(
+        stream.read_quic_varint(),
+        Err(BitError::NotEnoughData { .. })
+    ));
+    assert_eq!(stream.pos(), 0);
+}
+
+#[test]
+fn test_write_string_fixed() {
+    let write = |string: &str, length: usize, policy: TruncatePolicy| {
+        let mut data = Vec::new();
+        let mut stream = BitWriteStream::new(&mut data, BigEndian);
+        stream.write_bool(true).unwrap();
+        stream
+            .write_string_fixed(string, length, policy)
+            .map(|_| stream.bit_len())
+            .map(|len| {
+                let mut read = BitReadStream::new(BitReadBuffer::new(&data, BigEndian));
+                read.skip_bits(1).unwrap();
+                assert_eq!(len, 1 + length * 8);
+                read.read_bytes(length).unwrap().into_owned()
+            })
+    };
+
+    for policy in [
+        TruncatePolicy::Error,
+        TruncatePolicy::Truncate,
+        TruncatePolicy::TruncateLossy,
+    ] {
+        assert_eq!(write("abc", 5, policy).unwrap(), b"abc\0\0");
+        assert_eq!(write("a€", 4, policy).unwrap(), "a€".as_bytes());
+        assert_eq!(write("", 0, policy).unwrap(), b"");
+    }
+
+    assert!(matches!(
+        write("a€", 3, TruncatePolicy::Error),
+        Err(BitError::StringToLong {
+            string_length: 4,
+            requested_length: 3
+        })
+    ));
+    assert_eq!(write("a€", 3, TruncatePolicy::Truncate).unwrap(), b"a\0\0");
+    assert_eq!(write("€", 2, TruncatePolicy::Truncate).unwrap(), b"\0\0");
+    assert_eq!(
+        write("a€", 3, TruncatePolicy::TruncateLossy).unwrap(),
+        [b'a', 0xE2, 0x82]
+    );
+    assert_eq!(write("a€", 0, TruncatePolicy::TruncateLossy).unwrap(), b"");
+
+    // truncated strings can be read back as fixed length strings
+    let mut data = Vec::new();
+    let mut stream = BitWriteStream::new(&mut data, LittleEndian);
+    stream
+        .write_string_fixed("Grüße", 4, TruncatePolicy::Truncate)
+        .unwrap();
+    let mut stream = BitReadStream::new(BitReadBuffer::new(&data, LittleEndian));
+    assert_eq!(stream.read_string(Some(4)).unwrap(), "Grü");
+}
+
+fn check_write_string_bits<E: Endianness>() {
+    let mut data = Vec::new();
+    let mut stream = BitWriteStream::new(&mut data, E::endianness());
+    stream.write_bool(true).unwrap();
+    stream.write_string_bits("hello", 107).unwrap();
+    stream.write_bool(true).unwrap();
+    assert_eq!(stream.bit_len(), 109);
+
+    let mut stream = BitReadStream::new(BitReadBuffer::new(&data, E::endianness()));
+    assert!(stream.read_bool().unwrap());
+    let mut string = stream.read_bits(107).unwrap();
+    assert_eq!(string.read_string(Some(5)).unwrap(), "hello");
+    assert_eq!(string.read_int::<u128>(67).unwrap(), 0);
+    assert!(stream.read_bool().unwrap());
+}
+
+#[test]
+fn test_write_string_bits() {
+    check_write_string_bits::<LittleEndian>();
+    check_write_string_bits::<BigEndian>();
+
+    let mut data = Vec::new();
+    let mut stream = BitWriteStream::new(&mut data, LittleEndian);
+    stream.write_string_bits("", 3).unwrap();
+    assert_eq!(stream.bit_len(), 3);
+    assert!(matches!(
+        stream.write_string_bits("abc", 23),
+        Err(BitError::StringToLong {
+            string_length: 3,
+            requested_length: 2
+        })
+    ));
+    assert_eq!(stream.bit_len(), 3);
+}
+
+fn check_write_string_utf16<E: Endianness>() {
+    let mut data = Vec::new();
+    let mut stream = BitWriteStream::new(&mut data, E::endianness());
+    stream.write_bool(true).unwrap();
+    stream.write_string_utf16("a😀", None).unwrap();
+    stream.write_string_utf16("ß", Some(3)).unwrap();
+    stream.write_string_utf16("", Some(0)).unwrap();
+    assert_eq!(stream.bit_len(), 1 + 4 * 16 + 3 * 16);
+
+    let mut stream = BitReadStream::new(BitReadBuffer::new(&data, E::endianness()));
+    assert!(stream.read_bool().unwrap());
+    assert_eq!(stream.read_string_utf16(None).unwrap(), "a😀");
+    assert_eq!(stream.read_string_utf16(Some(3)).unwrap(), "ß");
+    assert_eq!(stream.bits_left(), 7);
+}
+
+#[test]
+fn test_write_string_utf16() {
+    check_write_string_utf16::<LittleEndian>();
+    check_write_string_utf16::<BigEndian>();
+
+    let mut data = Vec::new();
+    let mut stream = BitWriteStream::new(&mut data, LittleEndian);
+    stream.write_string_utf16("Hi", None).unwrap();
+    assert!(matches!(
+        stream.write_string_utf16("😀", Some(1)),
+        Err(BitError::StringToLong {
+            string_length: 2,
+            requested_length: 1
+        })
+    ));
+    assert_eq!(data, [0x48, 0x00, 0x69, 0x00, 0x00, 0x00]);
+}
+
+#[test]
+fn test_write_smart_pointers() {
+    use std::rc::Rc;
+    use std::sync::Arc;
+
+    let mut data = Vec::new();
+    let mut stream = BitWriteStream::new(&mut data, BigEndian);
+    stream.write(&Box::new(1u8)).unwrap();
+    stream.write(&Rc::new(2u8)).unwrap();
+    stream.write(&Arc::new(3u8)).unwrap();
+    stream.write_sized(&Box::new(4u8), 4).unwrap();
+    stream.write_sized(&Rc::new(5u8), 4).unwrap();
+    stream.write_sized(&Arc::new(String::from("a")), 2).unwrap();
+    let boxed: Box<[u8]> = Box::new([6, 7]);
+    stream.write(&boxed).unwrap();
+    let rc: Rc<[u16]> = Rc::from([0x0809]);
+    stream.write(&rc).unwrap();
+    let arc: Arc<[bool]> = Arc::from([true, false, true, false, true, false, true, false]);
+    stream.write(&arc).unwrap();
+    assert_eq!(data, [1, 2, 3, 0x45, b'a', 0, 6, 7, 8, 9, 0b1010_1010]);
+}
+
+fn test_bytes_unaligned_with<E: Endianness>() {
+    let bytes: Vec<u8> = (0..100u8).map(|i| i.wrapping_mul(97) ^ 0x3c).collect();
+    for shift in 1..8 {
+        for len in [0, 1, 7, 8, 9, 16, 23, 100] {
+            let mut data = Vec::new();
+            let mut stream = BitWriteStream::new(&mut data, E::endianness());
+            stream.write_int(0b101u8, shift).unwrap();
+            stream.write_bytes(&bytes[..len]).unwrap();
+            stream.write_bool(true).unwrap();
+            assert_eq!(stream.bit_len(), shift + len * 8 + 1);
+
+            let mut expected = Vec::new();
+            let mut stream = BitWriteStream::new(&mut expected, E::endianness());
+            stream.write_int(0b101u8, shift).unwrap();
+            for byte in &bytes[..len] {
+                stream.write_int(*byte, 8).unwrap();
+            }
+            stream.write_bool(true).unwrap();
+            assert_eq!(data, expected, "{} bytes at {}", len, shift);
+
+            let buffer = BitReadBuffer::new(&data, E::endianness());
+            assert_eq!(buffer.read_bytes(shift, len).unwrap(), &bytes[..len]);
+        }
+    }
+
+    let mut data = Vec::new();
+    let mut stream = BitWriteStream::new(&mut data, E::endianness());
+    stream.write_bytes(&bytes).unwrap();
+    assert_eq!(data, bytes);
+}
+
+#[test]
+fn test_write_bytes_unaligned() {
+    test_bytes_unaligned_with::<LittleEndian>();
+    test_bytes_unaligned_with::<BigEndian>();
+}
+
+fn test_write_bit_with<E: Endianness>() {
+    let pattern = [
+        true, false, true, true, false, false, true, false, true, true,
+    ];
+    let mut data = Vec::new();
+    let mut stream = BitWriteStream::new(&mut data, E::endianness());
+    for bit in pattern {
+        stream.write_bit(bit).unwrap();
+    }
+    stream.write_int(0x3fu8, 6).unwrap();
+    assert_eq!(stream.bit_len(), 16);
+
+    let mut expected = Vec::new();
+    let mut stream = BitWriteStream::new(&mut expected, E::endianness());
+    for bit in pattern {
+        stream.write_int(bit as u8, 1).unwrap();
+    }
+    stream.write_int(0x3fu8, 6).unwrap();
+    assert_eq!(data, expected);
+}
+
+#[test]
+fn test_write_bit() {
+    test_write_bit_with::<LittleEndian>();
+    test_write_bit_with::<BigEndian>();
+}